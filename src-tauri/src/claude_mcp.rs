@@ -290,12 +290,18 @@ pub fn validate_command_in_path(cmd: &str) -> Result<bool, AppError> {
 
 /// 读取 ~/.claude.json 中的 mcpServers 映射
 pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>, AppError> {
-    let path = user_config_path();
+    read_mcp_servers_map_at(&user_config_path())
+}
+
+/// 读取指定文件（如项目级 `.mcp.json`）中的 mcpServers 映射
+pub fn read_mcp_servers_map_at(
+    path: &Path,
+) -> Result<std::collections::HashMap<String, Value>, AppError> {
     if !path.exists() {
         return Ok(std::collections::HashMap::new());
     }
 
-    let root = read_json_value(&path)?;
+    let root = read_json_value(path)?;
     let servers = root
         .get("mcpServers")
         .and_then(|v| v.as_object())
@@ -310,9 +316,16 @@ pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>
 pub fn set_mcp_servers_map(
     servers: &std::collections::HashMap<String, Value>,
 ) -> Result<(), AppError> {
-    let path = user_config_path();
+    set_mcp_servers_map_at(&user_config_path(), servers)
+}
+
+/// 将 mcpServers 映射写入指定文件（如项目级 `.mcp.json`），其他字段保持不变
+pub fn set_mcp_servers_map_at(
+    path: &Path,
+    servers: &std::collections::HashMap<String, Value>,
+) -> Result<(), AppError> {
     let mut root = if path.exists() {
-        read_json_value(&path)?
+        read_json_value(path)?
     } else {
         serde_json::json!({})
     };
@@ -350,10 +363,10 @@ pub fn set_mcp_servers_map(
     {
         let obj = root
             .as_object_mut()
-            .ok_or_else(|| AppError::Config("~/.claude.json 根必须是对象".into()))?;
+            .ok_or_else(|| AppError::Config(format!("{} 根必须是对象", path.display())))?;
         obj.insert("mcpServers".into(), Value::Object(out));
     }
 
-    write_json_value(&path, &root)?;
+    write_json_value(path, &root)?;
     Ok(())
 }
@@ -0,0 +1,163 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{highlight, info, success, warning};
+use crate::error::AppError;
+use crate::services::declarative::{ApplyPlan, ChangeAction, PlannedChange};
+use crate::services::{DeclarativeConfig, DeclarativeService};
+use crate::store::AppState;
+
+#[derive(Args, Debug, Clone)]
+pub struct ApplyCommand {
+    /// Setup file to apply (.yaml/.yml or .toml)
+    #[arg(short, long, value_name = "PATH")]
+    pub file: PathBuf,
+
+    /// Only print the plan; change nothing
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Delete providers, MCP servers, prompts and skill repos that the file manages but does not list
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Skip confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub fn execute(cmd: ApplyCommand, app: Option<AppType>) -> Result<(), AppError> {
+    if app.is_some() {
+        return Err(AppError::InvalidInput(
+            "`--app` cannot be used with `apply`; apps are selected by the sections of the file."
+                .to_string(),
+        ));
+    }
+
+    let config = DeclarativeConfig::load(&cmd.file)?;
+    let state = AppState::try_new()?;
+    let plan = DeclarativeService::plan(&state, &config, cmd.prune)?;
+
+    let file_label = cmd.file.display().to_string();
+    if plan.is_empty() {
+        println!(
+            "{}",
+            success(&format!(
+                "{} {file_label}",
+                crate::t!(
+                    "✓ Nothing to apply; already matches",
+                    "✓ 无需变更，已与文件一致:"
+                )
+            ))
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        highlight(&format!(
+            "{} {file_label}",
+            crate::t!("Plan for", "变更计划:")
+        ))
+    );
+    print_plan(&plan);
+
+    if cmd.dry_run {
+        return Ok(());
+    }
+    if !cmd.yes {
+        if !(std::io::stdin().is_terminal() && std::io::stdout().is_terminal()) {
+            return Err(AppError::localized(
+                "apply.confirm_required",
+                "非交互环境下请使用 --yes 确认执行",
+                "Rerun with --yes to apply without an interactive confirmation",
+            ));
+        }
+        let confirmed = inquire::Confirm::new(crate::t!("Apply these changes?", "执行以上变更？"))
+            .with_default(false)
+            .prompt()
+            .map_err(|e| AppError::Message(format!("Prompt failed: {e}")))?;
+        if !confirmed {
+            println!("{}", info(crate::t!("Cancelled.", "已取消。")));
+            return Ok(());
+        }
+    }
+
+    DeclarativeService::apply(&state, &config, &plan)?;
+    println!(
+        "{}",
+        success(&format!(
+            "{} {file_label}",
+            crate::t!("✓ Applied", "✓ 已应用")
+        ))
+    );
+    Ok(())
+}
+
+fn print_plan(plan: &ApplyPlan) {
+    for change in &plan.changes {
+        let line = describe_change(change);
+        match change.action {
+            ChangeAction::Create => println!("  {}", success(&line)),
+            ChangeAction::Update => println!("  {}", info(&line)),
+            ChangeAction::Delete => println!("  {}", warning(&line)),
+        }
+    }
+    println!();
+    println!(
+        "{}",
+        info(&format!(
+            "{} {}, {} {}, {} {}, {} {}",
+            plan.count(ChangeAction::Create),
+            crate::t!("to create", "项新增"),
+            plan.count(ChangeAction::Update),
+            crate::t!("to update", "项修改"),
+            plan.count(ChangeAction::Delete),
+            crate::t!("to delete", "项删除"),
+            plan.unchanged,
+            crate::t!("unchanged", "项不变"),
+        ))
+    );
+}
+
+/// Field paths only, never values, so secrets stay off the terminal.
+fn describe_change(change: &PlannedChange) -> String {
+    let symbol = match change.action {
+        ChangeAction::Create => "+",
+        ChangeAction::Update => "~",
+        ChangeAction::Delete => "-",
+    };
+    let mut line = format!("{symbol} {} {}", change.section.label(), change.target());
+    if !change.fields.is_empty() {
+        line.push_str(&format!(" ({})", change.fields.join(", ")));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::declarative::PlanSection;
+
+    #[test]
+    fn describes_changes_without_values() {
+        let change = PlannedChange {
+            section: PlanSection::Provider,
+            action: ChangeAction::Update,
+            app: Some(AppType::Claude),
+            id: "team".to_string(),
+            fields: vec![
+                "settings.env.ANTHROPIC_AUTH_TOKEN".to_string(),
+                "current".to_string(),
+            ],
+        };
+
+        assert_eq!(
+            describe_change(&change),
+            "~ provider claude/team (settings.env.ANTHROPIC_AUTH_TOKEN, current)"
+        );
+    }
+}
@@ -5,6 +5,8 @@ use std::path::{Path, PathBuf};
 use crate::app_config::AppType;
 use crate::cli::commands::config_common;
 use crate::cli::commands::config_openclaw;
+use crate::cli::commands::config_sync;
+use crate::cli::commands::config_vault;
 use crate::cli::commands::config_webdav;
 use crate::cli::i18n::texts;
 use crate::cli::ui::{error, highlight, info, success, to_json};
@@ -60,6 +62,14 @@ pub enum ConfigCommand {
     /// Manage WebDAV sync settings and operations
     #[command(name = "webdav", subcommand)]
     WebDav(config_webdav::WebDavCommand),
+
+    /// Manage S3 and git sync backends and sync with the active backend
+    #[command(name = "sync", subcommand)]
+    Sync(config_sync::SyncCommand),
+
+    /// Encrypt provider API keys at rest
+    #[command(name = "vault", subcommand)]
+    Vault(config_vault::VaultCommand),
 }
 
 pub fn execute(cmd: ConfigCommand, app: Option<AppType>) -> Result<(), AppError> {
@@ -77,6 +87,8 @@ pub fn execute(cmd: ConfigCommand, app: Option<AppType>) -> Result<(), AppError>
         ConfigCommand::Common(cmd) => config_common::execute(cmd, app.unwrap_or(AppType::Claude)),
         ConfigCommand::OpenClaw(cmd) => config_openclaw::execute(cmd),
         ConfigCommand::WebDav(cmd) => config_webdav::execute(cmd),
+        ConfigCommand::Sync(cmd) => config_sync::execute(cmd),
+        ConfigCommand::Vault(cmd) => config_vault::execute(cmd),
     }
 }
 
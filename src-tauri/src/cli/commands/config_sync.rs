@@ -0,0 +1,586 @@
+use std::io::IsTerminal;
+
+use clap::{Args, Subcommand};
+use inquire::Select;
+
+use crate::cli::commands::config_webdav::{
+    blank_as_na, masked_secret, sync_live_config_after_webdav, yes_no,
+};
+use crate::cli::ui::{highlight, info, success, warning};
+use crate::error::AppError;
+use crate::services::{
+    ConflictResolutions, MergeOutcome, MergeSide, RecordConflict, SyncDecision, SyncService,
+};
+use crate::settings::{
+    effective_sync_interval_minutes, get_git_sync_settings, get_s3_sync_settings, get_sync_backend,
+    get_webdav_sync_settings, is_auto_sync_enabled, set_git_sync_settings, set_s3_sync_settings,
+    set_sync_backend, set_sync_interval_minutes, GitSyncSettings, S3SyncSettings, SyncBackend,
+    WebDavSyncStatus,
+};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SyncCommand {
+    /// Show the active sync backend and its settings
+    Show,
+
+    /// Choose the backend used by `config sync check-connection/upload/download`
+    Use {
+        #[arg(value_parser = ["webdav", "s3", "git"])]
+        backend: String,
+    },
+
+    /// Create or update S3-compatible object storage settings
+    S3(S3SyncArgs),
+
+    /// Create or update git repository settings
+    Git(GitSyncArgs),
+
+    /// Check whether the active backend can be reached
+    CheckConnection,
+
+    /// Upload the current local snapshot to the active backend
+    Upload,
+
+    /// Download the current remote snapshot from the active backend
+    Download,
+
+    /// Migrate legacy V1 remote data on the active backend to the V2 protocol
+    MigrateV1ToV2,
+
+    /// Set how often the daemon syncs when auto sync is on
+    Interval {
+        /// Minutes between background syncs (default 30)
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        minutes: u32,
+    },
+
+    /// Merge local and remote changes record by record, then update both sides
+    Merge {
+        /// Resolve every conflict with this side instead of asking
+        #[arg(long, value_parser = ["local", "remote"])]
+        prefer: Option<String>,
+    },
+}
+
+/// Options shared by every backend
+#[derive(Args, Debug, Clone, Default)]
+pub struct SyncCommonArgs {
+    #[arg(long)]
+    pub remote_root: Option<String>,
+
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Encrypt uploaded artifacts end-to-end with this sync passphrase
+    #[arg(long, conflicts_with = "no_encryption")]
+    pub encryption_passphrase: Option<String>,
+
+    /// Stop encrypting uploads (clears the sync passphrase)
+    #[arg(long)]
+    pub no_encryption: bool,
+
+    #[arg(long, conflicts_with = "disable")]
+    pub enable: bool,
+
+    #[arg(long, conflicts_with = "enable")]
+    pub disable: bool,
+
+    /// Let the daemon sync in the background (see `config sync interval`)
+    #[arg(long, conflicts_with = "no_auto_sync")]
+    pub auto_sync: bool,
+
+    #[arg(long, conflicts_with = "auto_sync")]
+    pub no_auto_sync: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct S3SyncArgs {
+    /// Service endpoint, e.g. https://s3.us-east-1.amazonaws.com or http://127.0.0.1:9000
+    #[arg(long)]
+    pub endpoint: Option<String>,
+
+    #[arg(long)]
+    pub region: Option<String>,
+
+    #[arg(long)]
+    pub bucket: Option<String>,
+
+    #[arg(long)]
+    pub access_key_id: Option<String>,
+
+    #[arg(long)]
+    pub secret_access_key: Option<String>,
+
+    /// Address objects as {endpoint}/{bucket}/{key} (MinIO and most self-hosted stores)
+    #[arg(long, conflicts_with = "no_path_style")]
+    pub path_style: bool,
+
+    #[arg(long, conflicts_with = "path_style")]
+    pub no_path_style: bool,
+
+    #[command(flatten)]
+    pub common: SyncCommonArgs,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct GitSyncArgs {
+    /// Repository URL; authentication uses your git SSH keys or credential helper
+    #[arg(long)]
+    pub repo: Option<String>,
+
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    #[command(flatten)]
+    pub common: SyncCommonArgs,
+}
+
+pub fn execute(cmd: SyncCommand) -> Result<(), AppError> {
+    match cmd {
+        SyncCommand::Show => show(),
+        SyncCommand::Use { backend } => use_backend(&backend),
+        SyncCommand::S3(args) => set_s3(args),
+        SyncCommand::Git(args) => set_git(args),
+        SyncCommand::CheckConnection => check_connection(),
+        SyncCommand::Upload => upload(),
+        SyncCommand::Download => download(),
+        SyncCommand::MigrateV1ToV2 => migrate_v1_to_v2(),
+        SyncCommand::Interval { minutes } => set_interval(minutes),
+        SyncCommand::Merge { prefer } => run_merge(prefer.as_deref(), SyncService::merge),
+    }
+}
+
+fn show() -> Result<(), AppError> {
+    let backend = get_sync_backend();
+    println!("{}", highlight(crate::t!("Sync", "同步")));
+    println!("{}", "═".repeat(60));
+    println!("Backend:      {}", backend.as_str());
+
+    match backend {
+        SyncBackend::WebDav => match get_webdav_sync_settings() {
+            Some(settings) => {
+                println!("Enabled:      {}", yes_no(settings.enabled));
+                println!("Base URL:     {}", blank_as_na(&settings.base_url));
+                print_common(
+                    &settings.remote_root,
+                    &settings.profile,
+                    &settings.encryption_passphrase,
+                    settings.auto_sync,
+                    &settings.status,
+                );
+            }
+            None => print_not_configured(),
+        },
+        SyncBackend::S3 => match get_s3_sync_settings() {
+            Some(settings) => {
+                println!("Enabled:      {}", yes_no(settings.enabled));
+                println!("Endpoint:     {}", blank_as_na(&settings.endpoint));
+                println!("Region:       {}", settings.region);
+                println!("Bucket:       {}", blank_as_na(&settings.bucket));
+                println!("Path Style:   {}", yes_no(settings.path_style));
+                println!("Access Key:   {}", blank_as_na(&settings.access_key_id));
+                println!(
+                    "Secret Key:   {}",
+                    masked_secret(&settings.secret_access_key)
+                );
+                print_common(
+                    &settings.remote_root,
+                    &settings.profile,
+                    &settings.encryption_passphrase,
+                    settings.auto_sync,
+                    &settings.status,
+                );
+            }
+            None => print_not_configured(),
+        },
+        SyncBackend::Git => match get_git_sync_settings() {
+            Some(settings) => {
+                println!("Enabled:      {}", yes_no(settings.enabled));
+                println!("Repository:   {}", blank_as_na(&settings.repo_url));
+                println!("Branch:       {}", settings.branch);
+                print_common(
+                    &settings.remote_root,
+                    &settings.profile,
+                    &settings.encryption_passphrase,
+                    settings.auto_sync,
+                    &settings.status,
+                );
+            }
+            None => print_not_configured(),
+        },
+    }
+    Ok(())
+}
+
+fn print_common(
+    remote_root: &str,
+    profile: &str,
+    encryption_passphrase: &str,
+    auto_sync: bool,
+    status: &WebDavSyncStatus,
+) {
+    println!("Remote Root:  {remote_root}");
+    println!("Profile:      {profile}");
+    println!(
+        "Encryption:   {}",
+        if encryption_passphrase.is_empty() {
+            "off"
+        } else {
+            "on (AES-256-GCM)"
+        }
+    );
+    if auto_sync {
+        println!(
+            "Auto Sync:    every {} min (runs while the daemon is up)",
+            effective_sync_interval_minutes()
+        );
+    } else {
+        println!("Auto Sync:    {}", yes_no(false));
+    }
+    println!(
+        "Last Sync:    {}",
+        status
+            .last_sync_at
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| "N/A".to_string())
+    );
+    println!(
+        "Last Error:   {}",
+        status
+            .last_error
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| "N/A".to_string())
+    );
+}
+
+fn print_not_configured() {
+    println!(
+        "{}",
+        info(crate::t!(
+            "The active sync backend is not configured.",
+            "当前同步后端尚未配置。"
+        ))
+    );
+}
+
+fn use_backend(raw: &str) -> Result<(), AppError> {
+    let backend: SyncBackend = raw.parse()?;
+    set_sync_backend(backend)?;
+    let en = format!("✓ Sync backend set to {}.", backend.as_str());
+    let zh = format!("✓ 同步后端已切换为 {}。", backend.as_str());
+    println!("{}", success(crate::t!(&en, &zh)));
+    Ok(())
+}
+
+fn apply_common(
+    args: SyncCommonArgs,
+    remote_root: &mut String,
+    profile: &mut String,
+    encryption_passphrase: &mut String,
+    enabled: &mut bool,
+    auto_sync: &mut bool,
+) {
+    if let Some(value) = args.remote_root {
+        *remote_root = value;
+    }
+    if let Some(value) = args.profile {
+        *profile = value;
+    }
+    if let Some(value) = args.encryption_passphrase {
+        *encryption_passphrase = value;
+    }
+    if args.no_encryption {
+        encryption_passphrase.clear();
+    }
+    if args.enable {
+        *enabled = true;
+    }
+    if args.disable {
+        *enabled = false;
+    }
+    if args.auto_sync {
+        *auto_sync = true;
+    }
+    if args.no_auto_sync {
+        *auto_sync = false;
+    }
+}
+
+fn merged_s3_settings(current: Option<S3SyncSettings>, args: S3SyncArgs) -> S3SyncSettings {
+    let mut settings = current.unwrap_or_default();
+    if let Some(value) = args.endpoint {
+        settings.endpoint = value;
+    }
+    if let Some(value) = args.region {
+        settings.region = value;
+    }
+    if let Some(value) = args.bucket {
+        settings.bucket = value;
+    }
+    if let Some(value) = args.access_key_id {
+        settings.access_key_id = value;
+    }
+    if let Some(value) = args.secret_access_key {
+        settings.secret_access_key = value;
+    }
+    if args.path_style {
+        settings.path_style = true;
+    }
+    if args.no_path_style {
+        settings.path_style = false;
+    }
+    apply_common(
+        args.common,
+        &mut settings.remote_root,
+        &mut settings.profile,
+        &mut settings.encryption_passphrase,
+        &mut settings.enabled,
+        &mut settings.auto_sync,
+    );
+    settings
+}
+
+fn merged_git_settings(current: Option<GitSyncSettings>, args: GitSyncArgs) -> GitSyncSettings {
+    let mut settings = current.unwrap_or_default();
+    if let Some(value) = args.repo {
+        settings.repo_url = value;
+    }
+    if let Some(value) = args.branch {
+        settings.branch = value;
+    }
+    apply_common(
+        args.common,
+        &mut settings.remote_root,
+        &mut settings.profile,
+        &mut settings.encryption_passphrase,
+        &mut settings.enabled,
+        &mut settings.auto_sync,
+    );
+    settings
+}
+
+fn set_s3(args: S3SyncArgs) -> Result<(), AppError> {
+    set_s3_sync_settings(Some(merged_s3_settings(get_s3_sync_settings(), args)))?;
+    println!(
+        "{}",
+        success(crate::t!(
+            "✓ S3 sync settings saved.",
+            "✓ S3 同步设置已保存。"
+        ))
+    );
+    Ok(())
+}
+
+fn set_git(args: GitSyncArgs) -> Result<(), AppError> {
+    set_git_sync_settings(Some(merged_git_settings(get_git_sync_settings(), args)))?;
+    println!(
+        "{}",
+        success(crate::t!(
+            "✓ Git sync settings saved.",
+            "✓ Git 同步设置已保存。"
+        ))
+    );
+    Ok(())
+}
+
+fn set_interval(minutes: u32) -> Result<(), AppError> {
+    set_sync_interval_minutes(Some(minutes))?;
+    let en = format!("✓ Background sync interval set to {minutes} min.");
+    let zh = format!("✓ 后台同步间隔已设置为 {minutes} 分钟。");
+    println!("{}", success(crate::t!(&en, &zh)));
+    if !is_auto_sync_enabled(get_sync_backend()) {
+        println!(
+            "{}",
+            info(crate::t!(
+                "Auto sync is off for the active backend; enable it with --auto-sync.",
+                "当前同步后端未开启自动同步，可使用 --auto-sync 开启。"
+            ))
+        );
+    }
+    Ok(())
+}
+
+fn check_connection() -> Result<(), AppError> {
+    SyncService::check_connection()?;
+    println!(
+        "{}",
+        success(crate::t!(
+            "✓ Sync backend connection succeeded.",
+            "✓ 同步后端连接成功。"
+        ))
+    );
+    Ok(())
+}
+
+fn upload() -> Result<(), AppError> {
+    let summary = SyncService::upload()?;
+    println!("{}", success(&summary.message));
+    Ok(())
+}
+
+fn download() -> Result<(), AppError> {
+    let summary = SyncService::download()?;
+    sync_live_config_after_webdav();
+    println!("{}", success(&summary.message));
+    Ok(())
+}
+
+fn migrate_v1_to_v2() -> Result<(), AppError> {
+    let summary = SyncService::migrate_v1_to_v2()?;
+    sync_live_config_after_webdav();
+    println!("{}", success(&summary.message));
+    Ok(())
+}
+
+/// Run a record-level merge; conflicts are resolved by `--prefer` or interactively.
+pub(crate) fn run_merge(
+    prefer: Option<&str>,
+    merge: impl Fn(&ConflictResolutions) -> Result<MergeOutcome, AppError>,
+) -> Result<(), AppError> {
+    let mut resolutions = ConflictResolutions {
+        fallback: prefer.map(str::parse::<MergeSide>).transpose()?,
+        ..ConflictResolutions::default()
+    };
+
+    loop {
+        let conflicts = match merge(&resolutions)? {
+            MergeOutcome::Synced(summary)
+                if summary.decision == SyncDecision::V1MigrationNeeded =>
+            {
+                println!(
+                    "{}",
+                    warning(crate::t!(
+                        "The remote only has legacy V1 data; run `cc-switch config webdav migrate-v1-to-v2` first.",
+                        "远端只有旧版 V1 数据，请先执行 `cc-switch config webdav migrate-v1-to-v2`。"
+                    ))
+                );
+                return Ok(());
+            }
+            MergeOutcome::Synced(summary) => {
+                sync_live_config_after_webdav();
+                println!("{}", success(&summary.message));
+                return Ok(());
+            }
+            MergeOutcome::Conflicts(conflicts) => conflicts,
+        };
+
+        let en = format!("{} record(s) were changed on both sides:", conflicts.len());
+        let zh = format!("{} 条记录在两侧都被修改：", conflicts.len());
+        println!("{}", warning(crate::t!(&en, &zh)));
+        for conflict in &conflicts {
+            println!("  - {}", describe_conflict(conflict));
+        }
+
+        if !(std::io::stdin().is_terminal() && std::io::stdout().is_terminal()) {
+            return Err(AppError::localized(
+                "sync.merge.conflicts",
+                "存在同步冲突，请使用 --prefer local 或 --prefer remote 重新执行",
+                "Sync has conflicts; rerun with --prefer local or --prefer remote",
+            ));
+        }
+
+        for conflict in conflicts {
+            match prompt_conflict_side(&conflict)? {
+                Some(side) => {
+                    resolutions.per_record.insert(conflict.key, side);
+                }
+                None => {
+                    println!(
+                        "{}",
+                        info(crate::t!(
+                            "Merge cancelled; nothing was changed.",
+                            "已取消合并，未做任何修改。"
+                        ))
+                    );
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn describe_conflict(conflict: &RecordConflict) -> String {
+    let detail = if conflict.deleted_locally {
+        crate::t!("deleted here, edited remotely", "本机已删除，远端有修改").to_string()
+    } else if conflict.deleted_remotely {
+        crate::t!("edited here, deleted remotely", "本机有修改，远端已删除").to_string()
+    } else {
+        conflict.fields.join(", ")
+    };
+    format!("{} ({detail})", conflict.label)
+}
+
+fn prompt_conflict_side(conflict: &RecordConflict) -> Result<Option<MergeSide>, AppError> {
+    let keep_local = crate::t!("Keep this device's version", "保留本机版本").to_string();
+    let take_remote = crate::t!("Take the remote version", "采用远端版本").to_string();
+    match Select::new(
+        &describe_conflict(conflict),
+        vec![keep_local.clone(), take_remote],
+    )
+    .prompt()
+    {
+        Ok(choice) if choice == keep_local => Ok(Some(MergeSide::Local)),
+        Ok(_) => Ok(Some(MergeSide::Remote)),
+        Err(inquire::error::InquireError::OperationCanceled)
+        | Err(inquire::error::InquireError::OperationInterrupted) => Ok(None),
+        Err(e) => Err(AppError::Message(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_s3_settings_updates_selected_fields_only() {
+        let current = S3SyncSettings {
+            enabled: true,
+            endpoint: "http://127.0.0.1:9000".to_string(),
+            bucket: "team".to_string(),
+            access_key_id: "key".to_string(),
+            secret_access_key: "secret".to_string(),
+            path_style: true,
+            ..S3SyncSettings::default()
+        };
+        let merged = merged_s3_settings(
+            Some(current),
+            S3SyncArgs {
+                bucket: Some("next".to_string()),
+                common: SyncCommonArgs {
+                    encryption_passphrase: Some("team passphrase".to_string()),
+                    ..SyncCommonArgs::default()
+                },
+                ..S3SyncArgs::default()
+            },
+        );
+
+        assert!(merged.enabled);
+        assert!(merged.path_style);
+        assert_eq!(merged.endpoint, "http://127.0.0.1:9000");
+        assert_eq!(merged.bucket, "next");
+        assert_eq!(merged.secret_access_key, "secret");
+        assert_eq!(merged.encryption_passphrase, "team passphrase");
+    }
+
+    #[test]
+    fn merged_git_settings_keeps_default_branch() {
+        let merged = merged_git_settings(
+            None,
+            GitSyncArgs {
+                repo: Some("git@example.com:team/sync.git".to_string()),
+                common: SyncCommonArgs {
+                    enable: true,
+                    auto_sync: true,
+                    ..SyncCommonArgs::default()
+                },
+                ..GitSyncArgs::default()
+            },
+        );
+
+        assert!(merged.enabled);
+        assert!(merged.auto_sync);
+        assert_eq!(merged.repo_url, "git@example.com:team/sync.git");
+        assert_eq!(merged.branch, "main");
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+
+use crate::cli::ui::{highlight, info, success, warning};
+use crate::error::AppError;
+use crate::secret_vault::{self, SecretVaultConfig, VaultKey, VaultKeySource};
+use crate::store::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum VaultCommand {
+    /// Show whether the secret vault is enabled and how many keys it holds
+    Status,
+
+    /// Create the secret vault and encrypt existing provider keys
+    Init {
+        /// Use a key file instead of a passphrase (created if missing)
+        #[arg(long, value_name = "PATH")]
+        key_file: Option<PathBuf>,
+
+        /// Only create the vault; leave existing keys in plaintext
+        #[arg(long)]
+        no_migrate: bool,
+    },
+
+    /// Encrypt provider keys that are still stored in plaintext
+    Migrate,
+
+    /// Decrypt all provider keys back to plaintext and remove the vault
+    Disable {
+        /// Skip confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+pub fn execute(cmd: VaultCommand) -> Result<(), AppError> {
+    match cmd {
+        VaultCommand::Status => status(),
+        VaultCommand::Init {
+            key_file,
+            no_migrate,
+        } => init(key_file, no_migrate),
+        VaultCommand::Migrate => migrate(),
+        VaultCommand::Disable { yes } => disable(yes),
+    }
+}
+
+fn get_state() -> Result<AppState, AppError> {
+    AppState::try_new()
+}
+
+fn require_vault(state: &AppState) -> Result<SecretVaultConfig, AppError> {
+    state.db.get_secret_vault_config()?.ok_or_else(|| {
+        AppError::localized(
+            "vault.not_initialized",
+            "密钥保险库尚未初始化，请先运行 `cc-switch config vault init`",
+            "Secret vault is not initialized; run `cc-switch config vault init` first",
+        )
+    })
+}
+
+fn env_passphrase() -> Option<String> {
+    std::env::var(secret_vault::PASSPHRASE_ENV)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+fn prompt_passphrase(confirm: bool) -> Result<String, AppError> {
+    let prompt = inquire::Password::new(crate::t!("Vault passphrase:", "保险库口令:"));
+    let prompt = if confirm {
+        prompt.with_custom_confirmation_message(crate::t!("Confirm passphrase:", "确认口令:"))
+    } else {
+        prompt.without_confirmation()
+    };
+    prompt
+        .prompt()
+        .map_err(|e| AppError::Message(format!("Prompt failed: {e}")))
+}
+
+fn unlock(config: &SecretVaultConfig) -> Result<VaultKey, AppError> {
+    match config.key_source {
+        VaultKeySource::Passphrase => {
+            let passphrase = match env_passphrase() {
+                Some(value) => value,
+                None => prompt_passphrase(false)?,
+            };
+            secret_vault::unlock(config, Some(&passphrase))
+        }
+        VaultKeySource::KeyFile => secret_vault::unlock(config, None),
+    }
+}
+
+fn status() -> Result<(), AppError> {
+    let state = get_state()?;
+    let Some(config) = state.db.get_secret_vault_config()? else {
+        println!(
+            "{}",
+            info(crate::t!(
+                "Secret vault is not enabled; provider keys are stored in plaintext.",
+                "密钥保险库未启用，供应商密钥以明文存储。"
+            ))
+        );
+        return Ok(());
+    };
+
+    let secrets = state.db.list_provider_secrets()?;
+    let mut plaintext = 0;
+    for app_type in crate::app_config::AppType::all() {
+        for provider in state.db.get_all_providers(app_type.as_str())?.values() {
+            plaintext += secret_vault::collect_plaintext_secrets(&provider.settings_config).len();
+        }
+    }
+
+    println!("{}", highlight(crate::t!("Secret Vault", "密钥保险库")));
+    println!("{}", "═".repeat(60));
+    match config.key_source {
+        VaultKeySource::Passphrase => println!(
+            "Key Source:   passphrase ({})",
+            secret_vault::PASSPHRASE_ENV
+        ),
+        VaultKeySource::KeyFile => println!(
+            "Key Source:   key file ({})",
+            secret_vault::key_file_path(&config)
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "N/A".to_string())
+        ),
+    }
+    println!("Encrypted:    {}", secrets.len());
+    println!("Plaintext:    {plaintext}");
+    println!("Created At:   {}", config.created_at);
+    if plaintext > 0 {
+        println!(
+            "{}",
+            warning(crate::t!(
+                "Some keys are still in plaintext; run `cc-switch config vault migrate`.",
+                "仍有密钥以明文存储，请运行 `cc-switch config vault migrate`。"
+            ))
+        );
+    }
+    Ok(())
+}
+
+fn init(key_file: Option<PathBuf>, no_migrate: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    if state.db.get_secret_vault_config()?.is_some() {
+        return Err(AppError::localized(
+            "vault.already_initialized",
+            "密钥保险库已初始化",
+            "Secret vault is already initialized",
+        ));
+    }
+
+    let (config, key) = match key_file {
+        Some(path) => {
+            let path = if path.is_absolute() {
+                path
+            } else {
+                std::env::current_dir()
+                    .map_err(|e| AppError::Message(e.to_string()))?
+                    .join(path)
+            };
+            secret_vault::create_vault(VaultKeySource::KeyFile, None, Some(&path))?
+        }
+        None => {
+            let passphrase = match env_passphrase() {
+                Some(value) => value,
+                None => prompt_passphrase(true)?,
+            };
+            secret_vault::create_vault(VaultKeySource::Passphrase, Some(&passphrase), None)?
+        }
+    };
+    state.db.set_secret_vault_config(&config)?;
+    println!(
+        "{}",
+        success(crate::t!("✓ Secret vault created.", "✓ 密钥保险库已创建。"))
+    );
+
+    if !no_migrate {
+        let sealed = secret_vault::seal_all_providers(&state.db, &key)?;
+        print_sealed(sealed);
+    }
+    Ok(())
+}
+
+fn migrate() -> Result<(), AppError> {
+    let state = get_state()?;
+    let config = require_vault(&state)?;
+    let key = unlock(&config)?;
+    let sealed = secret_vault::seal_all_providers(&state.db, &key)?;
+    print_sealed(sealed);
+    Ok(())
+}
+
+fn print_sealed(sealed: usize) {
+    if sealed == 0 {
+        println!(
+            "{}",
+            info(crate::t!(
+                "No plaintext provider keys found.",
+                "没有需要加密的明文密钥。"
+            ))
+        );
+    } else {
+        println!(
+            "{}",
+            success(&format!(
+                "{} {sealed}",
+                crate::t!("✓ Encrypted provider keys:", "✓ 已加密供应商密钥:")
+            ))
+        );
+    }
+}
+
+fn disable(yes: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    let config = require_vault(&state)?;
+    if !yes {
+        let confirmed = inquire::Confirm::new(crate::t!(
+            "Decrypt all provider keys back to plaintext and remove the vault?",
+            "将所有供应商密钥解密为明文并删除保险库？"
+        ))
+        .with_default(false)
+        .prompt()
+        .map_err(|e| AppError::Message(format!("Prompt failed: {e}")))?;
+        if !confirmed {
+            println!("{}", info(crate::t!("Cancelled.", "已取消。")));
+            return Ok(());
+        }
+    }
+
+    unlock(&config)?;
+    let revealed = secret_vault::reveal_all_providers(&state.db)?;
+    state.db.disable_secret_vault(&revealed)?;
+    println!(
+        "{}",
+        success(crate::t!(
+            "✓ Secret vault removed; provider keys are stored in plaintext again.",
+            "✓ 密钥保险库已删除，供应商密钥已恢复为明文存储。"
+        ))
+    );
+    Ok(())
+}
@@ -29,6 +29,14 @@ pub enum WebDavCommand {
         #[arg(long)]
         password: Option<String>,
 
+        /// Encrypt uploaded artifacts end-to-end with this sync passphrase
+        #[arg(long, conflicts_with = "no_encryption")]
+        encryption_passphrase: Option<String>,
+
+        /// Stop encrypting uploads (clears the sync passphrase)
+        #[arg(long)]
+        no_encryption: bool,
+
         #[arg(long, conflicts_with = "disable")]
         enable: bool,
 
@@ -77,6 +85,13 @@ pub enum WebDavCommand {
 
     /// Migrate legacy V1 remote data to V2 protocol
     MigrateV1ToV2,
+
+    /// Merge local and remote changes record by record, then update both sides
+    Merge {
+        /// Resolve every conflict with this side instead of asking
+        #[arg(long, value_parser = ["local", "remote"])]
+        prefer: Option<String>,
+    },
 }
 
 pub fn execute(cmd: WebDavCommand) -> Result<(), AppError> {
@@ -88,6 +103,8 @@ pub fn execute(cmd: WebDavCommand) -> Result<(), AppError> {
             profile,
             username,
             password,
+            encryption_passphrase,
+            no_encryption,
             enable,
             disable,
             auto_sync,
@@ -98,6 +115,8 @@ pub fn execute(cmd: WebDavCommand) -> Result<(), AppError> {
             profile,
             username,
             password,
+            encryption_passphrase,
+            no_encryption,
             enable,
             disable,
             auto_sync,
@@ -123,6 +142,10 @@ pub fn execute(cmd: WebDavCommand) -> Result<(), AppError> {
         WebDavCommand::Upload => upload(),
         WebDavCommand::Download => download(),
         WebDavCommand::MigrateV1ToV2 => migrate_v1_to_v2(),
+        WebDavCommand::Merge { prefer } => crate::cli::commands::config_sync::run_merge(
+            prefer.as_deref(),
+            WebDavSyncService::merge,
+        ),
     }
 }
 
@@ -146,6 +169,14 @@ fn show() -> Result<(), AppError> {
     println!("Profile:      {}", settings.profile);
     println!("Username:     {}", blank_as_na(&settings.username));
     println!("Password:     {}", masked_secret(&settings.password));
+    println!(
+        "Encryption:   {}",
+        if settings.encryption_passphrase.is_empty() {
+            "off"
+        } else {
+            "on (AES-256-GCM)"
+        }
+    );
     println!("Auto Sync:    {}", yes_no(settings.auto_sync));
     println!(
         "Last Sync:    {}",
@@ -175,6 +206,8 @@ fn set(
     profile: Option<String>,
     username: Option<String>,
     password: Option<String>,
+    encryption_passphrase: Option<String>,
+    no_encryption: bool,
     enable: bool,
     disable: bool,
     auto_sync: bool,
@@ -187,6 +220,8 @@ fn set(
         profile,
         username,
         password,
+        encryption_passphrase,
+        no_encryption,
         enable,
         disable,
         auto_sync,
@@ -282,7 +317,7 @@ fn migrate_v1_to_v2() -> Result<(), AppError> {
     Ok(())
 }
 
-fn sync_live_config_after_webdav() {
+pub(crate) fn sync_live_config_after_webdav() {
     let Ok(state) = crate::AppState::try_new() else {
         return;
     };
@@ -302,6 +337,8 @@ fn merged_settings(
     profile: Option<String>,
     username: Option<String>,
     password: Option<String>,
+    encryption_passphrase: Option<String>,
+    no_encryption: bool,
     enable: bool,
     disable: bool,
     auto_sync: bool,
@@ -324,6 +361,12 @@ fn merged_settings(
     if let Some(password) = password {
         settings.password = password;
     }
+    if let Some(encryption_passphrase) = encryption_passphrase {
+        settings.encryption_passphrase = encryption_passphrase;
+    }
+    if no_encryption {
+        settings.encryption_passphrase.clear();
+    }
     if enable {
         settings.enabled = true;
     }
@@ -340,7 +383,7 @@ fn merged_settings(
     settings
 }
 
-fn yes_no(value: bool) -> &'static str {
+pub(crate) fn yes_no(value: bool) -> &'static str {
     if value {
         "yes"
     } else {
@@ -348,7 +391,7 @@ fn yes_no(value: bool) -> &'static str {
     }
 }
 
-fn blank_as_na(value: &str) -> &str {
+pub(crate) fn blank_as_na(value: &str) -> &str {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         "N/A"
@@ -357,7 +400,7 @@ fn blank_as_na(value: &str) -> &str {
     }
 }
 
-fn masked_secret(value: &str) -> String {
+pub(crate) fn masked_secret(value: &str) -> String {
     if value.trim().is_empty() {
         return "N/A".to_string();
     }
@@ -378,6 +421,7 @@ mod tests {
             profile: "default".to_string(),
             username: "demo".to_string(),
             password: "secret".to_string(),
+            encryption_passphrase: "team passphrase".to_string(),
             auto_sync: false,
             status: WebDavSyncStatus {
                 last_error: Some("boom".to_string()),
@@ -392,6 +436,8 @@ mod tests {
             None,
             None,
             None,
+            None,
+            false,
             false,
             false,
             true,
@@ -404,6 +450,7 @@ mod tests {
         assert_eq!(merged.profile, "default");
         assert_eq!(merged.username, "demo");
         assert_eq!(merged.password, "secret");
+        assert_eq!(merged.encryption_passphrase, "team passphrase");
         assert!(merged.auto_sync);
         assert_eq!(merged.status.last_error.as_deref(), Some("boom"));
     }
@@ -5,7 +5,7 @@ use clap::Subcommand;
 use crate::cli::ui::{highlight, info, success, warning};
 use crate::daemon;
 use crate::daemon::ipc::client;
-use crate::daemon::ipc::protocol::{Request, Response};
+use crate::daemon::ipc::protocol::{AutoSyncState, DaemonEvent, Request, Response};
 use crate::error::AppError;
 
 #[derive(Subcommand, Debug, Clone)]
@@ -23,6 +23,19 @@ pub enum DaemonCommand {
     Status,
     /// Show the path to the daemon log file.
     Logs,
+    /// Ask the running daemon and its workers to re-read settings and proxy
+    /// configuration.
+    Reload,
+    /// Stream live daemon events (worker lifecycle, provider switches,
+    /// failover, circuit changes, proxied requests) until interrupted.
+    Events {
+        /// Only show events for this app (claude, codex, gemini).
+        #[arg(long)]
+        app: Option<String>,
+        /// Print each event as a JSON line instead of a summary.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 pub fn execute(cmd: DaemonCommand) -> Result<(), AppError> {
@@ -31,6 +44,8 @@ pub fn execute(cmd: DaemonCommand) -> Result<(), AppError> {
         DaemonCommand::Stop => stop_daemon(),
         DaemonCommand::Status => status_daemon(),
         DaemonCommand::Logs => show_log_path(),
+        DaemonCommand::Reload => reload_daemon(),
+        DaemonCommand::Events { app, json } => stream_events(app, json),
     }
 }
 
@@ -65,6 +80,27 @@ fn stop_daemon() -> Result<(), AppError> {
     }
 }
 
+fn reload_daemon() -> Result<(), AppError> {
+    match daemon::send_control(&Request::ReloadConfig).map_err(AppError::Message)? {
+        Some(Response::Reloaded { workers }) => {
+            println!("{}", success("daemon configuration reloaded"));
+            if workers.is_empty() {
+                println!("{}", info("no running workers to reload"));
+            } else {
+                println!("{}", info(&format!("workers: {}", workers.join(", "))));
+            }
+            Ok(())
+        }
+        Some(other) => Err(AppError::Message(format!(
+            "unexpected response from daemon: {other:?}"
+        ))),
+        None => {
+            println!("{}", warning("daemon is not running"));
+            Ok(())
+        }
+    }
+}
+
 fn status_daemon() -> Result<(), AppError> {
     let socket = daemon::paths::socket_path();
     let response = match client::round_trip(&socket, &Request::Status) {
@@ -84,6 +120,7 @@ fn status_daemon() -> Result<(), AppError> {
             restart_count,
             last_restart_at,
             workers,
+            auto_sync,
         } => {
             println!("{}", highlight("cc-switch daemon"));
             println!(
@@ -114,6 +151,9 @@ fn status_daemon() -> Result<(), AppError> {
             if let Some(at) = last_restart_at {
                 println!("  last restart:  {at}");
             }
+            if let Some(sync) = auto_sync {
+                print_auto_sync(&sync);
+            }
             Ok(())
         }
         Response::Error { message } => Err(AppError::Message(message)),
@@ -123,6 +163,135 @@ fn status_daemon() -> Result<(), AppError> {
     }
 }
 
+fn print_auto_sync(sync: &AutoSyncState) {
+    if !sync.enabled {
+        println!("  auto sync:     off");
+        return;
+    }
+    let state = if sync.running { ", syncing now" } else { "" };
+    println!(
+        "  auto sync:     {} every {} min{state}",
+        sync.backend, sync.interval_minutes
+    );
+    if let Some(at) = &sync.last_success_at {
+        let message = sync.last_message.as_deref().unwrap_or("");
+        println!("  last sync:     {at} {message}");
+    }
+    if let Some(error) = &sync.last_error {
+        let failures = match sync.consecutive_failures {
+            0 => String::new(),
+            count => format!(" (failed {count} time(s) in a row)"),
+        };
+        println!(
+            "{}",
+            warning(&format!("  sync error:    {error}{failures}"))
+        );
+    }
+    if let Some(at) = &sync.next_retry_at {
+        println!("  next retry:    {at}");
+    }
+}
+
+fn stream_events(app: Option<String>, json: bool) -> Result<(), AppError> {
+    let socket = daemon::paths::socket_path();
+    client::subscribe(&socket, app, |at, event| {
+        if json {
+            match serde_json::to_string(&Response::Event { at, event }) {
+                Ok(line) => println!("{line}"),
+                Err(err) => eprintln!("encode event failed: {err}"),
+            }
+        } else {
+            println!("{at}  {}", describe_event(&event));
+        }
+        true
+    })
+    .map_err(|err| AppError::Message(format!("subscribe to daemon events: {err}")))
+}
+
+fn describe_event(event: &DaemonEvent) -> String {
+    match event {
+        DaemonEvent::WorkerStarted {
+            app_type,
+            pid,
+            address,
+            port,
+        } => format!("[{app_type}] worker started at {address}:{port} (pid {pid})"),
+        DaemonEvent::WorkerStopped {
+            app_type,
+            pid,
+            crashed,
+            message,
+        } => {
+            let verb = if *crashed { "crashed" } else { "stopped" };
+            match message {
+                Some(message) => format!("[{app_type}] worker {verb} (pid {pid}): {message}"),
+                None => format!("[{app_type}] worker {verb} (pid {pid})"),
+            }
+        }
+        DaemonEvent::WorkerRestarting {
+            app_type,
+            attempt,
+            delay_ms,
+        } => format!("[{app_type}] worker restarting in {delay_ms}ms (attempt {attempt})"),
+        DaemonEvent::ProviderSwitched {
+            app_type,
+            from_provider_id,
+            provider_id,
+            provider_name,
+        } => format!(
+            "[{app_type}] provider {} -> {provider_name} ({provider_id})",
+            from_provider_id.as_deref().unwrap_or("-")
+        ),
+        DaemonEvent::FailoverHop {
+            app_type,
+            from_provider_id,
+            provider_id,
+            provider_name,
+        } => format!("[{app_type}] failover {from_provider_id} -> {provider_name} ({provider_id})"),
+        DaemonEvent::CircuitChanged {
+            app_type,
+            provider_id,
+            from,
+            to,
+        } => format!("[{app_type}] circuit {provider_id}: {from} -> {to}"),
+        DaemonEvent::SpendLimit {
+            app_type,
+            provider_id,
+            period,
+            usage_usd,
+            limit_usd,
+            exceeded,
+        } => {
+            let state = if *exceeded {
+                "limit reached"
+            } else {
+                "nearing limit"
+            };
+            format!(
+                "[{app_type}] {provider_id} {period} spend {state}: ${usage_usd} of ${limit_usd}"
+            )
+        }
+        DaemonEvent::Request(summary) => format!(
+            "[{}] {} {} via {} {}ms in={} out={}{}",
+            summary.app_type,
+            summary.status_code,
+            summary.model,
+            summary.provider_id,
+            summary.latency_ms,
+            summary.input_tokens,
+            summary.output_tokens,
+            summary
+                .total_cost_usd
+                .as_deref()
+                .map(|cost| format!(" ${cost}"))
+                .unwrap_or_default()
+        ),
+        DaemonEvent::Lagged { skipped } => {
+            format!("(fell behind; {skipped} event(s) dropped)")
+        }
+    }
+}
+
 fn show_log_path() -> Result<(), AppError> {
     let path = daemon::paths::log_path();
     println!("{}", info(&path.display().to_string()));
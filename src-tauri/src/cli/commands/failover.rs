@@ -3,9 +3,12 @@ use clap::{Subcommand, ValueEnum};
 use crate::app_config::AppType;
 use crate::cli::failover_policy::{ensure_auto_failover_queue_ready, inspect_auto_failover_gate};
 use crate::cli::ui::{create_table, highlight, info, success};
+#[cfg(unix)]
+use crate::daemon::ipc::protocol::{Request, Response};
 use crate::database::FailoverQueueItem;
 use crate::error::AppError;
-use crate::proxy::types::ProxyTakeoverStatus;
+use crate::provider::ProviderMeta;
+use crate::proxy::types::{ProxyTakeoverStatus, RoutingStrategy};
 use crate::services::provider::ProviderSortUpdate;
 use crate::services::ProviderService;
 use crate::AppState;
@@ -40,12 +43,28 @@ pub enum FailoverCommand {
         direction: FailoverMoveDirection,
     },
 
+    /// Close a provider's circuit breaker and clear its health record
+    Reset { id: String },
+
     /// Clear the failover queue
     Clear {
         /// Confirm clearing the queue
         #[arg(long)]
         yes: bool,
     },
+
+    /// Set how traffic is spread across queued providers
+    Strategy {
+        #[arg(value_enum)]
+        strategy: RoutingStrategy,
+    },
+
+    /// Set a provider's weight for the weighted routing strategy
+    Weight {
+        id: String,
+        /// Relative weight; 0 keeps the provider as a fallback only
+        weight: u32,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,7 +84,10 @@ pub fn execute(cmd: FailoverCommand, app: Option<AppType>) -> Result<(), AppErro
         FailoverCommand::Add { id } => add_provider(app_type, &id),
         FailoverCommand::Remove { id } => remove_provider(app_type, &id),
         FailoverCommand::Move { id, direction } => move_provider(app_type, &id, direction),
+        FailoverCommand::Reset { id } => reset_provider(app_type, &id),
         FailoverCommand::Clear { yes } => clear_queue(app_type, yes),
+        FailoverCommand::Strategy { strategy } => set_routing_strategy(app_type, strategy),
+        FailoverCommand::Weight { id, weight } => set_routing_weight(app_type, &id, weight),
     }
 }
 
@@ -112,6 +134,7 @@ fn show_failover(app_type: AppType) -> Result<(), AppError> {
             "disabled"
         }
     );
+    println!("Routing strategy: {}", config.routing_strategy);
     println!(
         "Proxy running: {}",
         if status.running { "yes" } else { "no" }
@@ -125,7 +148,7 @@ fn show_failover(app_type: AppType) -> Result<(), AppError> {
         }
     );
     println!();
-    print_queue(&queue);
+    print_queue(&state, app_type.as_str(), &queue)?;
     Ok(())
 }
 
@@ -202,7 +225,7 @@ fn list_queue(app_type: AppType) -> Result<(), AppError> {
     ensure_failover_supported(&app_type)?;
     let state = get_state()?;
     let queue = state.db.get_failover_queue(app_type.as_str())?;
-    print_queue(&queue);
+    print_queue(&state, app_type.as_str(), &queue)?;
     Ok(())
 }
 
@@ -243,7 +266,9 @@ fn add_provider(app_type: AppType, id: &str) -> Result<(), AppError> {
         return Ok(());
     }
 
-    state.db.add_to_failover_queue(app_type.as_str(), id)?;
+    if !edit_queue_via_daemon(&app_type, id, QueueEdit::Add)? {
+        state.db.add_to_failover_queue(app_type.as_str(), id)?;
+    }
     println!("{}", success("Provider added to the failover queue."));
     print_hot_update_note_if_running(&state)?;
     Ok(())
@@ -263,12 +288,37 @@ fn remove_provider(app_type: AppType, id: &str) -> Result<(), AppError> {
         return Err(active_proxy_failover_queue_guard_error());
     }
 
-    state.db.remove_from_failover_queue(app_type.as_str(), id)?;
+    if !edit_queue_via_daemon(&app_type, id, QueueEdit::Remove)? {
+        state.db.remove_from_failover_queue(app_type.as_str(), id)?;
+    }
     println!("{}", success("Provider removed from the failover queue."));
     print_hot_update_note_if_running(&state)?;
     Ok(())
 }
 
+fn reset_provider(app_type: AppType, id: &str) -> Result<(), AppError> {
+    ensure_failover_supported(&app_type)?;
+    let state = get_state()?;
+    ensure_provider_exists(&state, &app_type, id)?;
+
+    match reset_breaker_via_daemon(&app_type, id)? {
+        Some(true) => {
+            println!(
+                "{}",
+                success("Circuit breaker reset in the running proxy and health cleared.")
+            );
+        }
+        Some(false) => println!("{}", success("Provider health cleared.")),
+        None => {
+            let runtime = create_runtime()?;
+            runtime.block_on(state.db.reset_provider_health(id, app_type.as_str()))?;
+            println!("{}", success("Provider health cleared."));
+            print_hot_update_note_if_running(&state)?;
+        }
+    }
+    Ok(())
+}
+
 fn clear_queue(app_type: AppType, yes: bool) -> Result<(), AppError> {
     ensure_failover_supported(&app_type)?;
     let state = get_state()?;
@@ -295,6 +345,52 @@ fn clear_queue(app_type: AppType, yes: bool) -> Result<(), AppError> {
     Ok(())
 }
 
+fn set_routing_strategy(app_type: AppType, strategy: RoutingStrategy) -> Result<(), AppError> {
+    ensure_failover_supported(&app_type)?;
+    let state = get_state()?;
+    let runtime = create_runtime()?;
+    let mut config = runtime.block_on(state.db.get_proxy_config_for_app(app_type.as_str()))?;
+    config.routing_strategy = strategy;
+    runtime.block_on(state.db.update_proxy_config_for_app(config))?;
+
+    println!(
+        "{}",
+        success(&format!(
+            "Routing strategy for {} set to {strategy}.",
+            app_type.as_str()
+        ))
+    );
+    if strategy == RoutingStrategy::Weighted {
+        println!(
+            "{}",
+            info("Providers without an explicit weight use weight 1.")
+        );
+    }
+    print_hot_update_note_if_running(&state)?;
+    Ok(())
+}
+
+fn set_routing_weight(app_type: AppType, id: &str, weight: u32) -> Result<(), AppError> {
+    ensure_failover_supported(&app_type)?;
+    let state = get_state()?;
+    let mut provider = state
+        .db
+        .get_provider_by_id(id, app_type.as_str())?
+        .ok_or_else(|| AppError::InvalidInput(format!("Provider not found: {id}")))?;
+    provider
+        .meta
+        .get_or_insert_with(ProviderMeta::default)
+        .routing_weight = Some(weight);
+    ProviderService::update(&state, app_type, provider)?;
+
+    println!(
+        "{}",
+        success(&format!("Routing weight for {id} set to {weight}."))
+    );
+    print_hot_update_note_if_running(&state)?;
+    Ok(())
+}
+
 fn move_provider(
     app_type: AppType,
     id: &str,
@@ -303,7 +399,17 @@ fn move_provider(
     ensure_failover_supported(&app_type)?;
     let state = get_state()?;
     ensure_provider_exists(&state, &app_type, id)?;
-    let outcome = move_provider_in_state(&state, app_type, id, direction)?;
+    let queue = state.db.get_failover_queue(app_type.as_str())?;
+    let outcome = match moved_queue_position(&queue, id, direction) {
+        Ok((_, position)) => {
+            if edit_queue_via_daemon(&app_type, id, QueueEdit::Move { position })? {
+                MoveOutcome::Updated
+            } else {
+                move_provider_in_state(&state, app_type, id, direction)?
+            }
+        }
+        Err(outcome) => outcome,
+    };
     match outcome {
         MoveOutcome::Updated => {
             println!("{}", success("Failover queue order updated."));
@@ -331,30 +437,119 @@ fn move_provider_in_state(
     id: &str,
     direction: FailoverMoveDirection,
 ) -> Result<MoveOutcome, AppError> {
-    let mut queue = state.db.get_failover_queue(app_type.as_str())?;
-    let Some(index) = queue.iter().position(|item| item.provider_id == id) else {
-        return Ok(MoveOutcome::NotQueued);
-    };
-
-    let target = match direction {
-        FailoverMoveDirection::Up if index > 0 => index - 1,
-        FailoverMoveDirection::Down if index + 1 < queue.len() => index + 1,
-        _ => return Ok(MoveOutcome::AtEdge),
+    let queue = state.db.get_failover_queue(app_type.as_str())?;
+    let order = match moved_queue_order(&queue, id, direction) {
+        Ok(order) => order,
+        Err(outcome) => return Ok(outcome),
     };
 
-    queue.swap(index, target);
-    let updates = queue
-        .iter()
+    let updates = order
+        .into_iter()
         .enumerate()
-        .map(|(sort_index, item)| ProviderSortUpdate {
-            id: item.provider_id.clone(),
-            sort_index,
-        })
+        .map(|(sort_index, id)| ProviderSortUpdate { id, sort_index })
         .collect::<Vec<_>>();
     ProviderService::update_sort_order(state, app_type, updates)?;
     Ok(MoveOutcome::Updated)
 }
 
+/// Current and target queue index of the move, or the no-op outcome when
+/// nothing moves.
+fn moved_queue_position(
+    queue: &[FailoverQueueItem],
+    id: &str,
+    direction: FailoverMoveDirection,
+) -> Result<(usize, usize), MoveOutcome> {
+    let Some(index) = queue.iter().position(|item| item.provider_id == id) else {
+        return Err(MoveOutcome::NotQueued);
+    };
+
+    match direction {
+        FailoverMoveDirection::Up if index > 0 => Ok((index, index - 1)),
+        FailoverMoveDirection::Down if index + 1 < queue.len() => Ok((index, index + 1)),
+        _ => Err(MoveOutcome::AtEdge),
+    }
+}
+
+/// Queue order after the move, or the no-op outcome when nothing moves.
+fn moved_queue_order(
+    queue: &[FailoverQueueItem],
+    id: &str,
+    direction: FailoverMoveDirection,
+) -> Result<Vec<String>, MoveOutcome> {
+    let (index, target) = moved_queue_position(queue, id, direction)?;
+    let mut order = queue
+        .iter()
+        .map(|item| item.provider_id.clone())
+        .collect::<Vec<_>>();
+    order.swap(index, target);
+    Ok(order)
+}
+
+/// With a daemon running, queue edits go through it: it applies each edit
+/// to the queue as persisted under its control lock, so edits from several
+/// terminals compose. Returns `false` when no daemon is live and the caller
+/// should write the queue itself.
+#[cfg(unix)]
+fn edit_queue_via_daemon(app_type: &AppType, id: &str, edit: QueueEdit) -> Result<bool, AppError> {
+    let app_type = app_type.as_str().to_string();
+    let provider_id = id.to_string();
+    let request = match edit {
+        QueueEdit::Add => Request::AddToFailoverQueue {
+            app_type,
+            provider_id,
+        },
+        QueueEdit::Remove => Request::RemoveFromFailoverQueue {
+            app_type,
+            provider_id,
+        },
+        QueueEdit::Move { position } => Request::MoveInFailoverQueue {
+            app_type,
+            provider_id,
+            position,
+        },
+    };
+    let response = crate::daemon::send_control(&request).map_err(AppError::Message)?;
+    Ok(response.is_some())
+}
+
+#[cfg(not(unix))]
+fn edit_queue_via_daemon(
+    _app_type: &AppType,
+    _id: &str,
+    _edit: QueueEdit,
+) -> Result<bool, AppError> {
+    Ok(false)
+}
+
+/// `Some(worker_reset)` when the daemon handled the reset, `None` when no
+/// daemon is live.
+#[cfg(unix)]
+fn reset_breaker_via_daemon(app_type: &AppType, id: &str) -> Result<Option<bool>, AppError> {
+    let response = crate::daemon::send_control(&Request::ResetCircuitBreaker {
+        app_type: app_type.as_str().to_string(),
+        provider_id: id.to_string(),
+    })
+    .map_err(AppError::Message)?;
+    Ok(response.map(|response| match response {
+        Response::CircuitReset { worker_reset, .. } => worker_reset,
+        _ => false,
+    }))
+}
+
+#[cfg(not(unix))]
+fn reset_breaker_via_daemon(_app_type: &AppType, _id: &str) -> Result<Option<bool>, AppError> {
+    Ok(None)
+}
+
+/// One failover queue edit, sent to the daemon as its own request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(unix), allow(dead_code))]
+enum QueueEdit {
+    Add,
+    Remove,
+    Move { position: usize },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MoveOutcome {
     Updated,
@@ -413,15 +608,25 @@ fn takeover_enabled_for(takeovers: &ProxyTakeoverStatus, app_type: &AppType) ->
     }
 }
 
-fn print_queue(queue: &[FailoverQueueItem]) {
+fn print_queue(
+    state: &AppState,
+    app_type: &str,
+    queue: &[FailoverQueueItem],
+) -> Result<(), AppError> {
     if queue.is_empty() {
         println!("{}", info("Failover queue is empty."));
-        return;
+        return Ok(());
     }
 
+    let providers = state.db.get_all_providers(app_type)?;
     let mut table = create_table();
-    table.set_header(vec!["#", "Provider ID", "Name", "Sort"]);
+    table.set_header(vec!["#", "Provider ID", "Name", "Sort", "Weight"]);
     for (index, item) in queue.iter().enumerate() {
+        let weight = providers
+            .get(&item.provider_id)
+            .and_then(|provider| provider.meta.as_ref())
+            .map(|meta| meta.routing_weight())
+            .unwrap_or(1);
         table.add_row(vec![
             (index + 1).to_string(),
             item.provider_id.clone(),
@@ -429,9 +634,11 @@ fn print_queue(queue: &[FailoverQueueItem]) {
             item.sort_index
                 .map(|sort_index| sort_index.to_string())
                 .unwrap_or_else(|| "-".to_string()),
+            weight.to_string(),
         ]);
     }
     println!("{}", table);
+    Ok(())
 }
 
 fn print_hot_update_note() {
@@ -4,9 +4,10 @@ use crate::app_config::{AppType, McpApps, McpServer};
 use crate::cli::commands::app_targets::{
     app_target_names, app_targets_or_default, parse_app_targets,
 };
-use crate::cli::ui::{create_table, error, highlight, info, success};
+use crate::cli::ui::{create_table, error, highlight, info, success, warning};
 use crate::error::AppError;
-use crate::services::McpService;
+use crate::services::mcp_probe::McpProbeResult;
+use crate::services::{McpProbeService, McpService};
 use crate::store::AppState;
 
 #[derive(Subcommand)]
@@ -60,6 +61,14 @@ pub enum McpCommand {
         /// Command to validate
         command: String,
     },
+    /// Start (or connect to) MCP servers and run the MCP initialize handshake
+    Check {
+        /// Server ID to check (default: all servers)
+        id: Option<String>,
+        /// Seconds to wait for each server
+        #[arg(long, default_value_t = 20)]
+        timeout: u64,
+    },
     /// Sync MCP configuration to live files
     Sync,
     /// Import MCP servers from live configuration
@@ -78,6 +87,7 @@ pub fn execute(cmd: McpCommand, app: Option<AppType>) -> Result<(), AppError> {
         McpCommand::Disable { id, apps } => set_server_enabled(app_type, &id, &apps, false),
         McpCommand::SetApps { id, apps } => set_server_apps(&id, &apps),
         McpCommand::Validate { command } => validate_command(&command),
+        McpCommand::Check { id, timeout } => check_servers(id.as_deref(), timeout),
         McpCommand::Sync => sync_servers(),
         McpCommand::Import => import_servers(),
     }
@@ -440,3 +450,108 @@ fn validate_command(command: &str) -> Result<(), AppError> {
 
     Ok(())
 }
+
+fn check_servers(id: Option<&str>, timeout: u64) -> Result<(), AppError> {
+    let state = get_state()?;
+    let servers = McpService::get_all_servers(&state)?;
+    let mut targets: Vec<McpServer> = match id {
+        Some(id) => vec![servers
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::Message(format!("MCP server '{id}' not found")))?],
+        None => servers.into_values().collect(),
+    };
+    if targets.is_empty() {
+        println!("{}", info("No MCP servers found."));
+        return Ok(());
+    }
+    targets.sort_by(|a, b| a.id.cmp(&b.id));
+
+    println!(
+        "{}",
+        info(&format!("Checking {} MCP server(s)...", targets.len()))
+    );
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| AppError::Message(format!("Failed to create async runtime: {e}")))?;
+    // 并行探测，各服务器互不阻塞
+    let results = runtime.block_on(futures::future::join_all(
+        targets
+            .iter()
+            .map(|server| McpProbeService::probe(&server.server, Some(timeout))),
+    ));
+
+    println!();
+    for (server, result) in targets.iter().zip(&results) {
+        print_probe_result(server, result);
+    }
+
+    let failed = results.iter().filter(|result| !result.success).count();
+    if failed > 0 {
+        return Err(AppError::Message(format!(
+            "{failed} of {} MCP server(s) failed the check",
+            results.len()
+        )));
+    }
+    println!("{}", success("✓ All MCP servers responded"));
+    Ok(())
+}
+
+fn print_probe_result(server: &McpServer, result: &McpProbeResult) {
+    let timing = match result.handshake_ms {
+        Some(handshake) => format!("handshake {handshake} ms, total {} ms", result.total_ms),
+        None => format!("{} ms", result.total_ms),
+    };
+    if result.success {
+        println!(
+            "{}",
+            success(&format!("✓ {} ({}, {timing})", server.id, result.transport))
+        );
+    } else {
+        println!(
+            "{}",
+            error(&format!("✗ {} ({}, {timing})", server.id, result.transport))
+        );
+    }
+
+    if let Some(name) = &result.server_name {
+        let version = result.server_version.as_deref().unwrap_or("?");
+        let protocol = result.protocol_version.as_deref().unwrap_or("?");
+        println!("    server: {name} {version} (protocol {protocol})");
+    }
+    if result.handshake_ms.is_some() {
+        for (label, names) in [
+            ("tools", &result.tools),
+            ("resources", &result.resources),
+            ("prompts", &result.prompts),
+        ] {
+            println!("    {label} ({}): {}", names.len(), summarize_names(names));
+        }
+    }
+    if let Some(err) = &result.error {
+        println!("    {}", error(&format!("error: {err}")));
+    }
+    if !result.success && !result.stderr.is_empty() {
+        println!("    {}", warning("stderr:"));
+        for line in &result.stderr {
+            println!("      | {line}");
+        }
+    }
+    println!();
+}
+
+fn summarize_names(names: &[String]) -> String {
+    const MAX_LISTED: usize = 8;
+    if names.is_empty() {
+        return "-".to_string();
+    }
+    let mut listed = names
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > MAX_LISTED {
+        listed.push_str(&format!(", … (+{} more)", names.len() - MAX_LISTED));
+    }
+    listed
+}
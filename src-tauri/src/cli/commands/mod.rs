@@ -1,9 +1,12 @@
 pub(crate) mod app_targets;
+pub mod apply;
 pub mod auth;
 pub mod completions;
 pub mod config;
 mod config_common;
 pub(crate) mod config_openclaw;
+pub mod config_sync;
+pub mod config_vault;
 pub mod config_webdav;
 #[cfg(unix)]
 pub mod daemon;
@@ -13,14 +16,20 @@ pub mod failover;
 pub mod hermes;
 pub mod internal;
 pub mod mcp;
+pub mod project;
 pub mod prompts;
 pub mod provider;
 pub mod provider_input;
 mod provider_inspect;
 pub mod provider_usage_query;
 pub mod proxy;
+mod proxy_capture;
+pub(crate) mod proxy_clients;
+pub(crate) mod proxy_limits;
+pub(crate) mod proxy_rules;
 pub mod sessions;
 pub mod settings;
 pub mod skills;
 pub mod start;
 pub mod update;
+pub mod usage;
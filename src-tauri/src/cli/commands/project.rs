@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{highlight, info, success, warning};
+use crate::error::AppError;
+use crate::services::project::{ProjectChange, ProjectTarget, PROJECT_PROFILE_FILE};
+use crate::services::{ProjectProfile, ProjectService};
+use crate::store::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProjectCommand {
+    /// Apply the project's .cc-switch.toml to project-scoped config files
+    Apply {
+        /// Project directory (default: search upward from the current directory)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+
+        /// Only list the files that would be written
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Overwrite prompt files whose content was not written by cc-switch, and write the
+        /// provider key to .claude/settings.local.json even when git does not ignore it
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub fn execute(cmd: ProjectCommand, app: Option<AppType>) -> Result<(), AppError> {
+    match cmd {
+        ProjectCommand::Apply {
+            dir,
+            dry_run,
+            force,
+        } => apply_profile(dir, app, dry_run, force),
+    }
+}
+
+fn apply_profile(
+    dir: Option<PathBuf>,
+    app: Option<AppType>,
+    dry_run: bool,
+    force: bool,
+) -> Result<(), AppError> {
+    let profile_path = match dir {
+        Some(dir) => dir.join(PROJECT_PROFILE_FILE),
+        None => {
+            let cwd = std::env::current_dir().map_err(|e| AppError::io(".", e))?;
+            ProjectProfile::find(&cwd).ok_or_else(|| {
+                AppError::localized(
+                    "project.profile_not_found",
+                    format!("当前目录及其上级目录中没有 {PROJECT_PROFILE_FILE}"),
+                    format!(
+                        "No {PROJECT_PROFILE_FILE} found in the current directory or its parents"
+                    ),
+                )
+            })?
+        }
+    };
+
+    let mut profile = ProjectProfile::load(&profile_path)?;
+    if let Some(app) = &app {
+        profile = profile.only(app)?;
+    }
+
+    let state = AppState::try_new()?;
+    let changes = ProjectService::plan(&state, &profile, force)?;
+    if changes.is_empty() {
+        println!(
+            "{}",
+            info(&format!(
+                "{} {}",
+                crate::t!("Nothing to apply in", "没有需要应用的内容:"),
+                profile_path.display()
+            ))
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        highlight(&format!(
+            "{} {}",
+            crate::t!("Project profile", "项目配置:"),
+            profile_path.display()
+        ))
+    );
+    for change in &changes {
+        println!("  {}", describe_change(change, &profile.root));
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    ProjectService::apply(&changes)?;
+    if changes
+        .iter()
+        .any(|change| change.target == ProjectTarget::Provider)
+    {
+        println!(
+            "{}",
+            warning(crate::t!(
+                "Note: .claude/settings.local.json holds the provider's API key; keep it out of version control.",
+                "注意: .claude/settings.local.json 包含供应商 API Key，请勿提交到版本库。"
+            ))
+        );
+    }
+    println!(
+        "{}",
+        success(crate::t!("✓ Project profile applied", "✓ 已应用项目配置"))
+    );
+    Ok(())
+}
+
+fn describe_change(change: &ProjectChange, root: &Path) -> String {
+    let path = change.path.strip_prefix(root).unwrap_or(&change.path);
+    let apps = change
+        .apps
+        .iter()
+        .map(|app| app.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let items = if change.items.is_empty() {
+        "-".to_string()
+    } else {
+        change.items.join(", ")
+    };
+    format!(
+        "{} [{apps}] {} → {items}",
+        path.display(),
+        change.target.label()
+    )
+}
@@ -1,9 +1,14 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
 use clap::{ArgAction, Subcommand};
 
 use crate::app_config::AppType;
-use crate::cli::ui::{create_table, highlight, info, success};
+use crate::cli::ui::{create_table, highlight, info, success, warning};
 use crate::error::AppError;
-use crate::services::PromptService;
+use crate::prompt_block::{merge3, MergeOutcome};
+use crate::services::prompt::PromptBlockStatus;
+use crate::services::{PromptService, SharedPromptService};
 use crate::store::AppState;
 
 #[derive(Subcommand)]
@@ -68,6 +73,116 @@ pub enum PromptsCommand {
         /// Prompt preset ID
         id: String,
     },
+    /// Set the fragments prepended to a prompt preset, in order
+    Compose {
+        /// Prompt preset ID
+        id: String,
+        /// Fragment prompt IDs, comma-separated (empty to clear)
+        #[arg(value_delimiter = ',')]
+        fragments: Vec<String>,
+    },
+    /// Print the rendered prompt that would be written to the live file
+    Preview {
+        /// Prompt preset ID (default: the active prompt)
+        id: Option<String>,
+    },
+    /// Manage prompts shared across apps
+    #[command(subcommand)]
+    Shared(SharedPromptsCommand),
+    /// Show or set how prompts are written to the live file
+    Mode {
+        /// `file` replaces the whole file; `block` only rewrites the cc-switch block
+        #[arg(value_enum)]
+        mode: Option<PromptWriteMode>,
+    },
+    /// Check whether the managed block was edited by hand
+    Drift,
+    /// Merge hand edits in the managed block with the active prompt
+    Merge {
+        /// Save the live block as the active prompt's content
+        #[arg(long, conflicts_with = "use_db")]
+        keep_live: bool,
+        /// Discard the live edits and write the active prompt
+        #[arg(long)]
+        use_db: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum PromptWriteMode {
+    /// cc-switch owns the whole prompt file
+    File,
+    /// cc-switch owns a delimited block and leaves the rest of the file alone
+    Block,
+}
+
+#[derive(Subcommand)]
+pub enum SharedPromptsCommand {
+    /// List shared prompts and the apps they are enabled for
+    List,
+    /// Show a shared prompt with its per-app overrides
+    Show {
+        /// Shared prompt ID
+        id: String,
+    },
+    /// Create a shared prompt and activate it for the given apps
+    Create {
+        /// Shared prompt ID (default: derived from the name)
+        #[arg(long)]
+        id: Option<String>,
+        /// Shared prompt name
+        #[arg(long)]
+        name: String,
+        /// Shared prompt description
+        #[arg(long)]
+        description: Option<String>,
+        /// Read the content from a file instead of opening the editor
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Target apps (default: --app). Accepts repeated values or comma-separated ids.
+        #[arg(long, value_name = "APP[,APP]", value_delimiter = ',', num_args = 1)]
+        apps: Vec<String>,
+    },
+    /// Edit the shared content, or one app's override with --for
+    Edit {
+        /// Shared prompt ID
+        id: String,
+        /// Edit the override used by this app only
+        #[arg(long = "for", value_name = "APP")]
+        for_app: Option<String>,
+        /// Read the content from a file instead of opening the editor
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+    },
+    /// Drop an app's override so it uses the shared content again
+    Reset {
+        /// Shared prompt ID
+        id: String,
+        /// App whose override is removed
+        #[arg(long = "for", value_name = "APP")]
+        for_app: String,
+    },
+    /// Enable a shared prompt for app(s) and activate it there
+    Enable {
+        /// Shared prompt ID
+        id: String,
+        /// Target apps (default: --app). Accepts repeated values or comma-separated ids.
+        #[arg(long, value_name = "APP[,APP]", value_delimiter = ',', num_args = 1)]
+        apps: Vec<String>,
+    },
+    /// Disable a shared prompt for app(s) and remove its copy there
+    Disable {
+        /// Shared prompt ID
+        id: String,
+        /// Target apps (default: --app). Accepts repeated values or comma-separated ids.
+        #[arg(long, value_name = "APP[,APP]", value_delimiter = ',', num_args = 1)]
+        apps: Vec<String>,
+    },
+    /// Delete a shared prompt and its copies in every app
+    Delete {
+        /// Shared prompt ID
+        id: String,
+    },
 }
 
 pub fn execute(cmd: PromptsCommand, app: Option<AppType>) -> Result<(), AppError> {
@@ -96,6 +211,12 @@ pub fn execute(cmd: PromptsCommand, app: Option<AppType>) -> Result<(), AppError
         } => rename_prompt(app_type, &id, new_id, named.or(name), description),
         PromptsCommand::Delete { id } => delete_prompt(app_type, &id),
         PromptsCommand::Show { id } => show_prompt(app_type, &id),
+        PromptsCommand::Compose { id, fragments } => compose_prompt(app_type, &id, fragments),
+        PromptsCommand::Preview { id } => preview_prompt(app_type, id.as_deref()),
+        PromptsCommand::Shared(cmd) => execute_shared(cmd, app_type),
+        PromptsCommand::Mode { mode } => set_write_mode(mode),
+        PromptsCommand::Drift => show_drift(app_type),
+        PromptsCommand::Merge { keep_live, use_db } => merge_block(app_type, keep_live, use_db),
     }
 }
 
@@ -386,6 +507,9 @@ fn show_prompt(app_type: AppType, id: &str) -> Result<(), AppError> {
         }
     );
     println!("Updated:     {}", updated);
+    if !prompt.fragments.is_empty() {
+        println!("Fragments:   {}", prompt.fragments.join(", "));
+    }
     println!();
     println!("{}", highlight("Content:"));
     println!("{}", "-".repeat(50));
@@ -397,6 +521,44 @@ fn show_prompt(app_type: AppType, id: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+fn compose_prompt(app_type: AppType, id: &str, fragments: Vec<String>) -> Result<(), AppError> {
+    let state = get_state()?;
+    let fragments: Vec<String> = fragments
+        .into_iter()
+        .map(|fragment| fragment.trim().to_string())
+        .filter(|fragment| !fragment.is_empty())
+        .collect();
+    let prompt = PromptService::set_fragments(&state, app_type, id, fragments)?;
+
+    if prompt.fragments.is_empty() {
+        println!(
+            "{}",
+            success(&format!("✓ Cleared fragments of prompt '{}'", id))
+        );
+    } else {
+        println!(
+            "{}",
+            success(&format!(
+                "✓ Prompt '{}' now starts with: {}",
+                id,
+                prompt.fragments.join(", ")
+            ))
+        );
+    }
+    if prompt.enabled {
+        println!("{}", info("The live prompt file has been re-rendered."));
+    }
+
+    Ok(())
+}
+
+fn preview_prompt(app_type: AppType, id: Option<&str>) -> Result<(), AppError> {
+    let state = get_state()?;
+    let rendered = PromptService::preview(&state, app_type, id)?;
+    println!("{}", rendered);
+    Ok(())
+}
+
 fn create_prompt(
     app_type: AppType,
     id: Option<String>,
@@ -571,3 +733,403 @@ fn rename_prompt(
     println!("{}", info(&format!("  Application: {}", app_type.as_str())));
     Ok(())
 }
+
+fn set_write_mode(mode: Option<PromptWriteMode>) -> Result<(), AppError> {
+    let Some(mode) = mode else {
+        let current = if crate::settings::prompt_managed_block() {
+            "block"
+        } else {
+            "file"
+        };
+        println!("Prompt write mode: {}", highlight(current));
+        return Ok(());
+    };
+
+    let state = get_state()?;
+    let enabled = matches!(mode, PromptWriteMode::Block);
+    PromptService::set_managed_block(&state, enabled)?;
+    if enabled {
+        println!(
+            "{}",
+            success("✓ Prompts are now written to a managed block; text outside it is kept.")
+        );
+        println!(
+            "{}",
+            info("Move personal notes outside the cc-switch:begin/end markers to keep them.")
+        );
+    } else {
+        println!(
+            "{}",
+            success("✓ Prompts now replace the whole live prompt file.")
+        );
+    }
+    Ok(())
+}
+
+fn show_drift(app_type: AppType) -> Result<(), AppError> {
+    if !crate::settings::prompt_managed_block() {
+        println!(
+            "{}",
+            info("Managed block mode is off. Enable it with 'cc-switch prompts mode block'.")
+        );
+        return Ok(());
+    }
+
+    let state = get_state()?;
+    match PromptService::block_status(&state, app_type.clone())? {
+        PromptBlockStatus::Missing => println!(
+            "{}",
+            info(&format!(
+                "No managed block in the {} prompt file yet.",
+                app_type.as_str()
+            ))
+        ),
+        PromptBlockStatus::InSync => println!(
+            "{}",
+            success(&format!(
+                "✓ The {} managed block matches what cc-switch last wrote.",
+                app_type.as_str()
+            ))
+        ),
+        PromptBlockStatus::Drifted {
+            base,
+            live,
+            current,
+        } => {
+            println!(
+                "{}",
+                warning(&format!(
+                    "The {} managed block was edited by hand.",
+                    app_type.as_str()
+                ))
+            );
+            match merge3(&base, &live, &current) {
+                MergeOutcome::Clean(_) => println!(
+                    "{}",
+                    info("The edits merge cleanly; run 'cc-switch prompts merge' to apply them.")
+                ),
+                MergeOutcome::Conflict { conflicts, .. } => println!(
+                    "{}",
+                    info(&format!(
+                        "{} conflict(s) with the active prompt; run 'cc-switch prompts merge' to resolve them.",
+                        conflicts
+                    ))
+                ),
+            }
+            println!();
+            println!("{}", highlight("Live block:"));
+            println!("{}", live);
+            println!("{}", highlight("cc-switch version:"));
+            println!("{}", current);
+        }
+    }
+    Ok(())
+}
+
+fn merge_block(app_type: AppType, keep_live: bool, use_db: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    let PromptBlockStatus::Drifted { current, .. } =
+        PromptService::block_status(&state, app_type.clone())?
+    else {
+        println!(
+            "{}",
+            info("Nothing to merge: the managed block has no hand edits.")
+        );
+        return Ok(());
+    };
+
+    if keep_live {
+        PromptService::keep_live_block(&state, app_type.clone())?;
+        println!(
+            "{}",
+            success("✓ Saved the live block as the active prompt's content.")
+        );
+        return Ok(());
+    }
+
+    let resolved = if use_db {
+        current
+    } else {
+        match PromptService::merge_block(&state, app_type.clone())? {
+            Some(MergeOutcome::Clean(merged)) => merged,
+            Some(MergeOutcome::Conflict { text, conflicts }) => {
+                println!(
+                    "{}",
+                    warning(&format!(
+                        "{} conflict(s). Resolve the <<<<<<< live / >>>>>>> cc-switch sections in the editor.",
+                        conflicts
+                    ))
+                );
+                crate::cli::editor::open_external_editor(&text)?
+            }
+            None => return Ok(()),
+        }
+    };
+
+    PromptService::resolve_block(&state, app_type.clone(), &resolved)?;
+    println!(
+        "{}",
+        success(&format!(
+            "✓ Updated the {} managed block",
+            app_type.as_str()
+        ))
+    );
+    Ok(())
+}
+
+fn execute_shared(cmd: SharedPromptsCommand, app_type: AppType) -> Result<(), AppError> {
+    match cmd {
+        SharedPromptsCommand::List => list_shared_prompts(),
+        SharedPromptsCommand::Show { id } => show_shared_prompt(&id),
+        SharedPromptsCommand::Create {
+            id,
+            name,
+            description,
+            file,
+            apps,
+        } => create_shared_prompt(
+            id.as_deref(),
+            &name,
+            description.as_deref(),
+            file.as_deref(),
+            &apps,
+            app_type,
+        ),
+        SharedPromptsCommand::Edit { id, for_app, file } => {
+            edit_shared_prompt(&id, for_app.as_deref(), file.as_deref())
+        }
+        SharedPromptsCommand::Reset { id, for_app } => reset_shared_override(&id, &for_app),
+        SharedPromptsCommand::Enable { id, apps } => {
+            set_shared_prompt_enabled(&id, &apps, app_type, true)
+        }
+        SharedPromptsCommand::Disable { id, apps } => {
+            set_shared_prompt_enabled(&id, &apps, app_type, false)
+        }
+        SharedPromptsCommand::Delete { id } => delete_shared_prompt(&id),
+    }
+}
+
+/// Prompt files exist for every app, so unlike MCP targets OpenClaw is accepted here.
+fn parse_prompt_apps(raw_apps: &[String], fallback: AppType) -> Result<Vec<AppType>, AppError> {
+    let mut apps = Vec::new();
+    for value in raw_apps.iter().flat_map(|raw| raw.split(',')) {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let app = AppType::from_str(&value.replace('-', ""))?;
+        if !apps.contains(&app) {
+            apps.push(app);
+        }
+    }
+    if apps.is_empty() {
+        apps.push(fallback);
+    }
+    Ok(apps)
+}
+
+fn app_names(apps: &[AppType]) -> String {
+    if apps.is_empty() {
+        return "-".to_string();
+    }
+    apps.iter()
+        .map(AppType::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn read_content(file: Option<&Path>, initial: &str) -> Result<String, AppError> {
+    match file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| AppError::io(path, e)),
+        None => {
+            println!("{}", info("Opening external editor..."));
+            crate::cli::editor::open_external_editor(initial)
+        }
+    }
+}
+
+fn list_shared_prompts() -> Result<(), AppError> {
+    let state = get_state()?;
+    let prompts = SharedPromptService::list(&state)?;
+
+    if prompts.is_empty() {
+        println!("{}", info("No shared prompts found."));
+        println!(
+            "Use 'cc-switch prompts shared create --name <NAME> --apps <APPS>' to create one."
+        );
+        return Ok(());
+    }
+
+    let mut table = create_table();
+    table.set_header(vec!["ID", "Name", "Apps", "Overrides"]);
+    for prompt in prompts.values() {
+        let overrides = if prompt.overrides.is_empty() {
+            "-".to_string()
+        } else {
+            prompt
+                .overrides
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        table.add_row(vec![
+            prompt.id.clone(),
+            prompt.name.clone(),
+            app_names(&prompt.apps.enabled_apps()),
+            overrides,
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+fn show_shared_prompt(id: &str) -> Result<(), AppError> {
+    let state = get_state()?;
+    let prompt = SharedPromptService::get(&state, id)?;
+
+    println!("{}", highlight(&format!("Shared Prompt: {}", prompt.name)));
+    println!("{}", "=".repeat(50));
+    println!("ID:          {}", prompt.id);
+    if let Some(desc) = &prompt.description {
+        println!("Description: {}", desc);
+    }
+    println!("Apps:        {}", app_names(&prompt.apps.enabled_apps()));
+    println!();
+    println!("{}", highlight("Content:"));
+    println!("{}", "-".repeat(50));
+    println!("{}", prompt.content);
+    for (app, content) in &prompt.overrides {
+        println!("{}", "-".repeat(50));
+        println!("{}", highlight(&format!("Override for {}:", app)));
+        println!("{}", content);
+    }
+    println!("{}", "-".repeat(50));
+    Ok(())
+}
+
+fn create_shared_prompt(
+    id: Option<&str>,
+    name: &str,
+    description: Option<&str>,
+    file: Option<&Path>,
+    raw_apps: &[String],
+    app_type: AppType,
+) -> Result<(), AppError> {
+    let state = get_state()?;
+    let apps = parse_prompt_apps(raw_apps, app_type)?;
+    let content = read_content(file, "# Write your shared prompt here\n")?;
+    let prompt = SharedPromptService::create(&state, id, name, description, &content, &apps)?;
+
+    println!(
+        "{}",
+        success(&format!(
+            "✓ Created shared prompt '{}' and activated it for {}",
+            prompt.id,
+            app_names(&apps)
+        ))
+    );
+    Ok(())
+}
+
+fn edit_shared_prompt(
+    id: &str,
+    for_app: Option<&str>,
+    file: Option<&Path>,
+) -> Result<(), AppError> {
+    let state = get_state()?;
+    let prompt = SharedPromptService::get(&state, id)?;
+
+    let (app, current) = match for_app {
+        Some(raw) => {
+            let app = AppType::from_str(&raw.replace('-', ""))?;
+            let current = prompt.content_for(&app).to_string();
+            (Some(app), current)
+        }
+        None => (None, prompt.content.clone()),
+    };
+
+    let edited = read_content(file, &current)?;
+    if edited.trim_end() == current.trim_end() {
+        println!("{}", info("No changes detected."));
+        return Ok(());
+    }
+
+    let prompt = match &app {
+        Some(app) => SharedPromptService::set_override(&state, id, app, Some(&edited))?,
+        None => SharedPromptService::update_content(&state, id, &edited)?,
+    };
+
+    println!(
+        "{}",
+        success(&match &app {
+            Some(app) => format!(
+                "✓ Updated the {} override of shared prompt '{}'",
+                app.as_str(),
+                id
+            ),
+            None => format!("✓ Updated shared prompt '{}'", id),
+        })
+    );
+    println!(
+        "{}",
+        info(&format!(
+            "  Synced to: {}",
+            app_names(&prompt.apps.enabled_apps())
+        ))
+    );
+    Ok(())
+}
+
+fn reset_shared_override(id: &str, for_app: &str) -> Result<(), AppError> {
+    let state = get_state()?;
+    let app = AppType::from_str(&for_app.replace('-', ""))?;
+    SharedPromptService::set_override(&state, id, &app, None)?;
+
+    println!(
+        "{}",
+        success(&format!(
+            "✓ {} now uses the shared content of '{}'",
+            app.as_str(),
+            id
+        ))
+    );
+    Ok(())
+}
+
+fn set_shared_prompt_enabled(
+    id: &str,
+    raw_apps: &[String],
+    app_type: AppType,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let state = get_state()?;
+    let apps = parse_prompt_apps(raw_apps, app_type)?;
+    SharedPromptService::set_apps_enabled(&state, id, &apps, enabled)?;
+
+    println!(
+        "{}",
+        success(&format!(
+            "✓ {} shared prompt '{}' for {}",
+            if enabled { "Enabled" } else { "Disabled" },
+            id,
+            app_names(&apps)
+        ))
+    );
+    println!(
+        "{}",
+        info(if enabled {
+            "Note: The prompt has been activated in those apps."
+        } else {
+            "Note: The prompt copies have been removed from those apps."
+        })
+    );
+    Ok(())
+}
+
+fn delete_shared_prompt(id: &str) -> Result<(), AppError> {
+    let state = get_state()?;
+    SharedPromptService::delete(&state, id)?;
+    println!("{}", success(&format!("✓ Deleted shared prompt '{}'", id)));
+    Ok(())
+}
@@ -722,6 +722,23 @@ fn resolve_provider_for_switch(
     }
 }
 
+/// 守护进程在线时通过 IPC 切换；返回 false 表示没有守护进程，需要本地切换
+#[cfg(unix)]
+fn switch_via_daemon(app_type: &AppType, id: &str) -> Result<bool, AppError> {
+    let response =
+        crate::daemon::send_control(&crate::daemon::ipc::protocol::Request::SwitchProvider {
+            app_type: app_type.as_str().to_string(),
+            provider_id: id.to_string(),
+        })
+        .map_err(AppError::Message)?;
+    Ok(response.is_some())
+}
+
+#[cfg(not(unix))]
+fn switch_via_daemon(_app_type: &AppType, _id: &str) -> Result<bool, AppError> {
+    Ok(false)
+}
+
 fn switch_provider(app_type: AppType, id: &str) -> Result<(), AppError> {
     let state = get_state()?;
     let app_str = app_type.as_str().to_string();
@@ -732,15 +749,18 @@ fn switch_provider(app_type: AppType, id: &str) -> Result<(), AppError> {
     let (resolved_id, provider) = resolve_provider_for_switch(&providers, id)?;
     let id = resolved_id.as_str();
 
-    // 执行切换（upstream parity：干净写入，无冲突提示）
-    ProviderService::switch(&state, app_type.clone(), id)?;
-    if let Err(err) =
-        crate::claude_plugin::sync_claude_plugin_on_provider_switch(&app_type, &provider)
-    {
-        println!(
-            "{}",
-            warning(&texts::claude_plugin_sync_failed_warning(&err.to_string()))
-        );
+    // 守护进程运行时由它执行切换，与其托管的 worker 保持一致
+    if !switch_via_daemon(&app_type, id)? {
+        // 执行切换（upstream parity：干净写入，无冲突提示）
+        ProviderService::switch(&state, app_type.clone(), id)?;
+        if let Err(err) =
+            crate::claude_plugin::sync_claude_plugin_on_provider_switch(&app_type, &provider)
+        {
+            println!(
+                "{}",
+                warning(&texts::claude_plugin_sync_failed_warning(&err.to_string()))
+            );
+        }
     }
 
     if app_type.is_additive_mode() {
@@ -1364,6 +1384,7 @@ fn export_provider(app_type: AppType, id: &str, output: Option<PathBuf>) -> Resu
         }
     }
 
+    let provider = crate::secret_vault::reveal_provider(&state.db, &provider)?;
     let settings_content = ProviderService::build_live_backup_snapshot(
         &app_type,
         &provider,
@@ -240,7 +240,7 @@ pub(crate) fn stream_check_provider(app_type: AppType, id: &str) -> Result<(), A
         .map_err(|e| AppError::Message(format!("Failed to create async runtime: {}", e)))?;
 
     let result = runtime.block_on(async {
-        StreamCheckService::check_with_retry(&app_type, &provider, &config).await
+        StreamCheckService::check_provider(&state.db, &app_type, &provider, &config).await
     })?;
 
     let _ = state
@@ -268,6 +268,7 @@ pub(crate) fn fetch_models_provider(app_type: AppType, id: &str) -> Result<(), A
     let provider = providers
         .get(id)
         .ok_or_else(|| AppError::Message(format!("Provider '{}' not found", id)))?;
+    let provider = &crate::secret_vault::reveal_provider(&state.db, provider)?;
     let source = model_fetch_source(provider, &app_type)?;
 
     println!(
@@ -1,6 +1,7 @@
 use clap::Subcommand;
 
 use crate::app_config::AppType;
+use crate::cli::commands::{proxy_capture, proxy_clients, proxy_limits, proxy_rules};
 use crate::cli::proxy_settings::{validate_proxy_listen_address, validate_proxy_listen_port};
 use crate::cli::ui::{highlight, info, success};
 use crate::error::AppError;
@@ -9,7 +10,9 @@ use crate::{AppState, ProxyConfig};
 #[cfg(unix)]
 use crate::daemon::ipc::client as daemon_client;
 #[cfg(unix)]
-use crate::daemon::ipc::protocol::{Request as DaemonRequest, Response as DaemonResponse};
+use crate::daemon::ipc::protocol::{
+    DaemonEvent, Request as DaemonRequest, Response as DaemonResponse,
+};
 #[cfg(unix)]
 use crate::daemon::supervisor::{DAEMON_SOCKET_ENV, SESSION_TOKEN_ENV};
 
@@ -49,6 +52,36 @@ pub enum ProxyCommand {
         #[arg(long = "takeover", value_enum)]
         takeovers: Vec<AppType>,
     },
+
+    /// Manage model routing rules (model rewrite and provider pinning)
+    #[command(subcommand)]
+    Rules(proxy_rules::ProxyRulesCommand),
+
+    /// Manage per-provider daily and monthly spending limits
+    #[command(subcommand)]
+    Limits(proxy_limits::ProxyLimitsCommand),
+
+    /// Manage client tokens required to use the proxy listener
+    #[command(subcommand)]
+    Clients(proxy_clients::ProxyClientsCommand),
+
+    /// Record request/response bodies for debugging format conversion
+    #[command(subcommand)]
+    Capture(proxy_capture::ProxyCaptureCommand),
+
+    /// Re-send a captured request through the proxy's conversion path
+    Replay {
+        /// Request ID from `proxy capture list`
+        request_id: String,
+
+        /// Send to this provider instead of the one that served the capture
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Print the replayed capture as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 pub fn execute(cmd: ProxyCommand, app: Option<AppType>) -> Result<(), AppError> {
@@ -66,6 +99,15 @@ pub fn execute(cmd: ProxyCommand, app: Option<AppType>) -> Result<(), AppError>
             listen_port,
             takeovers,
         } => serve_proxy(listen_address, listen_port, takeovers),
+        ProxyCommand::Rules(cmd) => proxy_rules::execute(cmd, app_type),
+        ProxyCommand::Limits(cmd) => proxy_limits::execute(cmd, app_type),
+        ProxyCommand::Clients(cmd) => proxy_clients::execute(cmd),
+        ProxyCommand::Capture(cmd) => proxy_capture::execute(cmd),
+        ProxyCommand::Replay {
+            request_id,
+            provider,
+            json,
+        } => proxy_capture::replay(&request_id, provider, json),
     }
 }
 
@@ -261,6 +303,21 @@ fn serve_proxy(
                     return Err(AppError::Message(err));
                 }
             }
+            let event_forward_task = {
+                #[cfg(unix)]
+                {
+                    match service.subscribe_events().await {
+                        Some(events) if announced_to_daemon => {
+                            Some(tokio::spawn(forward_events_to_daemon(events)))
+                        }
+                        _ => None,
+                    }
+                }
+                #[cfg(not(unix))]
+                {
+                    None::<tokio::task::JoinHandle<()>>
+                }
+            };
             crate::services::state_coordination::clear_restore_mutation_guard_bypass_env();
             let session_sync_task =
                 crate::services::session_usage::spawn_periodic_session_usage_sync(
@@ -319,6 +376,9 @@ fn serve_proxy(
                 .map_err(|e| AppError::Message(format!("failed to listen for Ctrl-C: {e}")))?;
             session_sync_task.abort();
             usage_maintenance_task.abort();
+            if let Some(task) = event_forward_task {
+                task.abort();
+            }
 
             service
                 .stop_with_restore()
@@ -366,6 +426,71 @@ fn announce_to_daemon_if_managed(
     }
 }
 
+/// Relay this worker's proxy events to the daemon so `daemon events`
+/// subscribers see them. Best effort: once the stream breaks, events are
+/// dropped until the worker is restarted.
+#[cfg(unix)]
+async fn forward_events_to_daemon(
+    mut events: tokio::sync::broadcast::Receiver<crate::proxy::events::ProxyEvent>,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::broadcast::error::RecvError;
+
+    let (Some(socket_path), Ok(session_token)) = (
+        std::env::var_os(DAEMON_SOCKET_ENV),
+        std::env::var(SESSION_TOKEN_ENV),
+    ) else {
+        return;
+    };
+    let stream = match tokio::net::UnixStream::connect(&socket_path).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            log::warn!("open daemon event stream failed: {err}");
+            return;
+        }
+    };
+    let (read_half, mut write_half) = stream.into_split();
+
+    let request = DaemonRequest::WorkerEvents { session_token };
+    let Ok(payload) = serde_json::to_string(&request) else {
+        return;
+    };
+    if let Err(err) = write_half
+        .write_all(format!("{payload}\n").as_bytes())
+        .await
+    {
+        log::warn!("open daemon event stream failed: {err}");
+        return;
+    }
+    let mut ack = String::new();
+    if let Err(err) = BufReader::new(read_half).read_line(&mut ack).await {
+        log::warn!("open daemon event stream failed: {err}");
+        return;
+    }
+    match serde_json::from_str::<DaemonResponse>(ack.trim()) {
+        Ok(DaemonResponse::Ok) => {}
+        other => {
+            log::warn!("daemon rejected worker event stream: {other:?}");
+            return;
+        }
+    }
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => DaemonEvent::from(event),
+            Err(RecvError::Lagged(skipped)) => DaemonEvent::Lagged { skipped },
+            Err(RecvError::Closed) => return,
+        };
+        let Ok(line) = serde_json::to_string(&event) else {
+            continue;
+        };
+        if let Err(err) = write_half.write_all(format!("{line}\n").as_bytes()).await {
+            log::warn!("daemon event stream closed: {err}");
+            return;
+        }
+    }
+}
+
 async fn apply_takeovers(
     service: &crate::ProxyService,
     takeovers: &[AppType],
@@ -0,0 +1,313 @@
+use chrono::{Local, TimeZone};
+use clap::Subcommand;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{create_table, highlight, info, success, to_json};
+use crate::error::AppError;
+use crate::proxy::capture::{replay_capture, ProxyCapture};
+use crate::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyCaptureCommand {
+    /// Start recording request and response bodies for proxied requests
+    Enable {
+        /// Maximum size of each captured body in KiB
+        #[arg(long = "max-body-kb")]
+        max_body_kb: Option<usize>,
+
+        /// Number of captures to keep
+        #[arg(long)]
+        retain: Option<usize>,
+    },
+
+    /// Stop recording request and response bodies
+    Disable,
+
+    /// Show capture settings and how many captures are stored
+    Status,
+
+    /// List recent captures
+    List {
+        /// Maximum number of captures to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show a captured request and response
+    Show {
+        request_id: String,
+
+        /// Print the capture as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete all captures
+    Clear,
+}
+
+pub fn execute(cmd: ProxyCaptureCommand) -> Result<(), AppError> {
+    match cmd {
+        ProxyCaptureCommand::Enable {
+            max_body_kb,
+            retain,
+        } => enable_capture(max_body_kb, retain),
+        ProxyCaptureCommand::Disable => disable_capture(),
+        ProxyCaptureCommand::Status => show_status(),
+        ProxyCaptureCommand::List { limit } => list_captures(limit),
+        ProxyCaptureCommand::Show { request_id, json } => show_capture(&request_id, json),
+        ProxyCaptureCommand::Clear => clear_captures(),
+    }
+}
+
+fn get_state() -> Result<AppState, AppError> {
+    AppState::try_new()
+}
+
+fn create_runtime() -> Result<tokio::runtime::Runtime, AppError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::Message(format!("failed to create async runtime: {e}")))
+}
+
+fn enable_capture(max_body_kb: Option<usize>, retain: Option<usize>) -> Result<(), AppError> {
+    let state = get_state()?;
+    let mut config = state.db.get_capture_config()?;
+    if let Some(max_body_kb) = max_body_kb {
+        if max_body_kb == 0 {
+            return Err(AppError::InvalidInput(
+                "--max-body-kb must be greater than 0".to_string(),
+            ));
+        }
+        config.max_body_bytes = max_body_kb.saturating_mul(1024);
+    }
+    if let Some(retain) = retain {
+        if retain == 0 {
+            return Err(AppError::InvalidInput(
+                "--retain must be greater than 0".to_string(),
+            ));
+        }
+        config.retain = retain;
+    }
+    config.enabled = true;
+    state.db.set_capture_config(&config)?;
+
+    println!(
+        "{}",
+        success(crate::t!("Request capture enabled.", "已开启请求抓包。"))
+    );
+    println!(
+        "{}",
+        info(crate::t!(
+            "Captures include request and response bodies; credentials in headers are redacted. Running proxies pick this up on the next request.",
+            "抓包会保存请求与响应内容，请求头中的凭据已脱敏。运行中的代理会在下一个请求生效。"
+        ))
+    );
+    Ok(())
+}
+
+fn disable_capture() -> Result<(), AppError> {
+    let state = get_state()?;
+    let mut config = state.db.get_capture_config()?;
+    config.enabled = false;
+    state.db.set_capture_config(&config)?;
+    println!(
+        "{}",
+        success(crate::t!("Request capture disabled.", "已关闭请求抓包。"))
+    );
+    Ok(())
+}
+
+fn show_status() -> Result<(), AppError> {
+    let state = get_state()?;
+    let config = state.db.get_capture_config()?;
+    let stored = state.db.list_proxy_captures(config.retain.max(1))?.len();
+
+    println!("{}", highlight(crate::t!("Request Capture", "请求抓包")));
+    println!(
+        "{}: {}",
+        crate::t!("Enabled", "已开启"),
+        if config.enabled { "yes" } else { "no" }
+    );
+    println!(
+        "{}: {} KiB",
+        crate::t!("Max body size", "单个内容上限"),
+        config.max_body_bytes / 1024
+    );
+    println!("{}: {}", crate::t!("Retain", "保留条数"), config.retain);
+    println!("{}: {stored}", crate::t!("Stored", "已保存"));
+    Ok(())
+}
+
+fn list_captures(limit: usize) -> Result<(), AppError> {
+    let state = get_state()?;
+    let captures = state.db.list_proxy_captures(limit)?;
+    if captures.is_empty() {
+        println!(
+            "{}",
+            info(crate::t!("No captured requests.", "暂无抓包记录。"))
+        );
+        return Ok(());
+    }
+
+    let mut table = create_table();
+    table.set_header(vec![
+        "Request ID",
+        "Time",
+        "App",
+        "Endpoint",
+        "Provider",
+        "Status",
+    ]);
+    for capture in captures {
+        table.add_row(vec![
+            capture.request_id,
+            format_capture_time(capture.created_at),
+            capture.app_type,
+            capture.endpoint,
+            capture.provider_id.unwrap_or_else(|| "-".to_string()),
+            format_status(capture.status_code, capture.truncated),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+fn show_capture(request_id: &str, json: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    let capture = load_capture(&state, request_id)?;
+    print_capture(&capture, json)
+}
+
+fn clear_captures() -> Result<(), AppError> {
+    let state = get_state()?;
+    let removed = state.db.clear_proxy_captures()?;
+    println!(
+        "{}",
+        success(&format!(
+            "{} {removed}",
+            crate::t!("Deleted captures:", "已删除抓包记录：")
+        ))
+    );
+    Ok(())
+}
+
+/// Re-send a captured client request through the proxy's conversion and forwarding path.
+pub fn replay(request_id: &str, provider_id: Option<String>, json: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    let capture = load_capture(&state, request_id)?;
+    let app_type: AppType = capture.app_type.parse().map_err(|_| {
+        AppError::InvalidInput(format!(
+            "Capture has unsupported app type: {}",
+            capture.app_type
+        ))
+    })?;
+    let provider_id = provider_id
+        .or_else(|| capture.provider_id.clone())
+        .ok_or_else(|| {
+            AppError::InvalidInput(
+                "Capture has no provider; pass --provider to choose one".to_string(),
+            )
+        })?;
+    let provider = state
+        .db
+        .get_provider_by_id(&provider_id, app_type.as_str())?
+        .ok_or_else(|| AppError::InvalidInput(format!("Provider not found: {provider_id}")))?;
+    let config = state.db.get_capture_config()?;
+
+    let runtime = create_runtime()?;
+    let replayed = runtime
+        .block_on(replay_capture(
+            state.db.clone(),
+            &capture,
+            provider,
+            &config,
+        ))
+        .map_err(|e| AppError::Message(format!("Replay failed: {e}")))?;
+    print_capture(&replayed, json)
+}
+
+fn load_capture(state: &AppState, request_id: &str) -> Result<ProxyCapture, AppError> {
+    state
+        .db
+        .get_proxy_capture(request_id)?
+        .ok_or_else(|| AppError::InvalidInput(format!("Capture not found: {request_id}")))
+}
+
+fn print_capture(capture: &ProxyCapture, json: bool) -> Result<(), AppError> {
+    if json {
+        println!(
+            "{}",
+            to_json(capture).map_err(|source| AppError::JsonSerialize { source })?
+        );
+        return Ok(());
+    }
+
+    println!("{}", highlight(&capture.request_id));
+    println!(
+        "{} {} {} -> {}",
+        format_capture_time(capture.created_at),
+        capture.app_type,
+        capture.endpoint,
+        format_status(capture.status_code, capture.truncated)
+    );
+
+    println!();
+    println!("{}", highlight(crate::t!("Client request", "客户端请求")));
+    print_headers(&capture.client_headers);
+    println!("{}", capture.client_body);
+
+    println!();
+    println!("{}", highlight(crate::t!("Upstream request", "上游请求")));
+    match (&capture.upstream_method, &capture.upstream_url) {
+        (Some(method), Some(url)) => {
+            println!(
+                "{method} {url} ({})",
+                capture.provider_id.as_deref().unwrap_or("-")
+            );
+            print_headers(&capture.upstream_headers);
+            println!("{}", capture.upstream_body.as_deref().unwrap_or(""));
+        }
+        _ => println!(
+            "{}",
+            info(crate::t!(
+                "No upstream request was sent.",
+                "未发送上游请求。"
+            ))
+        ),
+    }
+
+    println!();
+    println!("{}", highlight(crate::t!("Upstream response", "上游响应")));
+    print_headers(&capture.response_headers);
+    println!("{}", capture.response_body);
+    Ok(())
+}
+
+fn print_headers(headers: &serde_json::Value) {
+    if let Some(headers) = headers.as_object() {
+        for (name, value) in headers {
+            println!("{name}: {}", value.as_str().unwrap_or_default());
+        }
+    }
+}
+
+fn format_status(status_code: Option<u16>, truncated: bool) -> String {
+    let status = status_code
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    if truncated {
+        format!("{status} (truncated)")
+    } else {
+        status
+    }
+}
+
+fn format_capture_time(timestamp_secs: i64) -> String {
+    Local
+        .timestamp_opt(timestamp_secs, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
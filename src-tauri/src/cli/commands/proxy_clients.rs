@@ -0,0 +1,231 @@
+use chrono::{Local, TimeZone};
+use clap::Subcommand;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{create_table, highlight, info, success};
+use crate::error::AppError;
+use crate::proxy::client_auth::{generate_client_token, hash_client_token, CLIENT_TOKEN_HEADER};
+use crate::proxy::types::ClientToken;
+use crate::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyClientsCommand {
+    /// List client tokens and the usage attributed to each
+    List,
+
+    /// Create a named client token; the proxy requires a token once any exist
+    Add {
+        /// Token name recorded in request logs
+        name: String,
+
+        /// Only allow these apps (claude, codex, gemini); repeatable
+        #[arg(long = "allow-app", value_enum)]
+        apps: Vec<AppType>,
+
+        /// Only allow these provider IDs; repeatable
+        #[arg(long = "allow-provider")]
+        providers: Vec<String>,
+    },
+
+    /// Revoke a client token
+    Remove {
+        /// Token name
+        name: String,
+    },
+}
+
+pub fn execute(cmd: ProxyClientsCommand) -> Result<(), AppError> {
+    match cmd {
+        ProxyClientsCommand::List => list_clients(),
+        ProxyClientsCommand::Add {
+            name,
+            apps,
+            providers,
+        } => add_client(&name, apps, providers),
+        ProxyClientsCommand::Remove { name } => remove_client(&name),
+    }
+}
+
+fn get_state() -> Result<AppState, AppError> {
+    AppState::try_new()
+}
+
+fn list_clients() -> Result<(), AppError> {
+    let state = get_state()?;
+    let config = state.db.get_client_auth_config()?;
+
+    println!(
+        "{}",
+        highlight(crate::t!("Proxy Client Tokens", "代理客户端令牌"))
+    );
+    if !config.is_enforced() {
+        println!(
+            "{}",
+            info(crate::t!(
+                "No client tokens. The proxy accepts any request on its listen address.",
+                "暂无客户端令牌，代理接受监听地址上的所有请求。"
+            ))
+        );
+        return Ok(());
+    }
+
+    let stats = state.db.get_client_token_stats(None, None)?;
+    let mut table = create_table();
+    table.set_header(vec![
+        "Name",
+        "Apps",
+        "Providers",
+        "Created",
+        "Requests",
+        "Cost",
+        "Last used",
+    ]);
+    for token in &config.tokens {
+        let usage = stats.iter().find(|stat| stat.client_token == token.name);
+        table.add_row(vec![
+            token.name.clone(),
+            join_or_any(&token.apps),
+            join_or_any(&token.providers),
+            format_timestamp(token.created_at),
+            usage
+                .map(|stat| stat.request_count.to_string())
+                .unwrap_or_else(|| "0".to_string()),
+            usage
+                .map(|stat| format_usd(&stat.total_cost))
+                .unwrap_or_else(|| "-".to_string()),
+            usage
+                .map(|stat| format_timestamp(stat.last_used_at))
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+fn add_client(name: &str, apps: Vec<AppType>, providers: Vec<String>) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "client token name cannot be empty".to_string(),
+        ));
+    }
+    if let Some(app) = apps
+        .iter()
+        .find(|app| !matches!(app, AppType::Claude | AppType::Codex | AppType::Gemini))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "the proxy does not serve {}",
+            app.as_str()
+        )));
+    }
+
+    let state = get_state()?;
+    let mut config = state.db.get_client_auth_config()?;
+    if config.tokens.iter().any(|token| token.name == name) {
+        return Err(AppError::InvalidInput(format!(
+            "client token '{name}' already exists; remove it first to rotate"
+        )));
+    }
+
+    let secret = generate_client_token();
+    let mut app_names = apps
+        .iter()
+        .map(|app| app.as_str().to_string())
+        .collect::<Vec<_>>();
+    app_names.sort();
+    app_names.dedup();
+    config.tokens.push(ClientToken {
+        name: name.to_string(),
+        token_sha256: hash_client_token(&secret),
+        apps: app_names,
+        providers,
+        created_at: chrono::Utc::now().timestamp(),
+    });
+    let first_token = config.tokens.len() == 1;
+    state.db.set_client_auth_config(&config)?;
+    super::proxy_rules::reload_daemon_workers();
+
+    println!(
+        "{}",
+        success(&format!(
+            "{} {name}",
+            crate::t!("Created client token", "已创建客户端令牌")
+        ))
+    );
+    println!("{secret}");
+    println!(
+        "{}",
+        info(&format!(
+            "{} {CLIENT_TOKEN_HEADER}",
+            crate::t!(
+                "Store it now; it cannot be shown again. Clients send it as their API key or in",
+                "请立即保存，之后无法再次查看。客户端可将其作为 API Key 发送，或放在请求头"
+            )
+        ))
+    );
+    if first_token {
+        println!(
+            "{}",
+            info(crate::t!(
+                "The proxy now rejects requests without a valid client token.",
+                "代理现在会拒绝未携带有效客户端令牌的请求。"
+            ))
+        );
+    }
+    Ok(())
+}
+
+fn remove_client(name: &str) -> Result<(), AppError> {
+    let state = get_state()?;
+    let mut config = state.db.get_client_auth_config()?;
+    let before = config.tokens.len();
+    config.tokens.retain(|token| token.name != name);
+    if config.tokens.len() == before {
+        return Err(AppError::InvalidInput(format!(
+            "client token '{name}' not found"
+        )));
+    }
+    state.db.set_client_auth_config(&config)?;
+    super::proxy_rules::reload_daemon_workers();
+
+    println!(
+        "{}",
+        success(&format!(
+            "{} {name}",
+            crate::t!("Revoked client token", "已撤销客户端令牌")
+        ))
+    );
+    if !config.is_enforced() {
+        println!(
+            "{}",
+            info(crate::t!(
+                "No client tokens remain; the proxy accepts any request again.",
+                "已无客户端令牌，代理将重新接受所有请求。"
+            ))
+        );
+    }
+    Ok(())
+}
+
+fn join_or_any(values: &[String]) -> String {
+    if values.is_empty() {
+        "any".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+fn format_usd(value: &str) -> String {
+    value
+        .parse::<f64>()
+        .map(|amount| format!("{amount:.2}"))
+        .unwrap_or_else(|_| value.to_string())
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
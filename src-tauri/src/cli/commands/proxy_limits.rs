@@ -0,0 +1,181 @@
+use clap::Subcommand;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{create_table, highlight, info, success};
+use crate::error::AppError;
+use crate::services::ProviderService;
+use crate::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyLimitsCommand {
+    /// Show spend against each provider's daily and monthly limits
+    List,
+
+    /// Set a provider's spending limits in USD
+    Set {
+        /// Provider ID
+        id: String,
+
+        /// Daily limit in USD, or "off" to remove it
+        #[arg(long)]
+        daily: Option<String>,
+
+        /// Monthly limit in USD, or "off" to remove it
+        #[arg(long)]
+        monthly: Option<String>,
+    },
+
+    /// Configure how the proxy enforces spending limits
+    Config {
+        /// Warn when spend reaches this percentage of a limit (0 disables warnings)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        warn_percent: Option<u8>,
+
+        /// Skip providers that are over their limit (false only warns)
+        #[arg(long)]
+        enforce: Option<bool>,
+    },
+}
+
+pub fn execute(cmd: ProxyLimitsCommand, app_type: AppType) -> Result<(), AppError> {
+    match cmd {
+        ProxyLimitsCommand::List => list_limits(app_type),
+        ProxyLimitsCommand::Set { id, daily, monthly } => set_limits(app_type, &id, daily, monthly),
+        ProxyLimitsCommand::Config {
+            warn_percent,
+            enforce,
+        } => configure_limits(warn_percent, enforce),
+    }
+}
+
+fn get_state() -> Result<AppState, AppError> {
+    AppState::try_new()
+}
+
+fn list_limits(app_type: AppType) -> Result<(), AppError> {
+    let state = get_state()?;
+    let config = state.db.get_spend_limit_config()?;
+    let providers = ProviderService::list(&state, app_type.clone())?;
+
+    println!("{}", highlight(crate::t!("Spending Limits", "消费限额")));
+    println!(
+        "{}: {}  {}: {}%",
+        crate::t!("Enforced", "强制执行"),
+        if config.enforce { "yes" } else { "no" },
+        crate::t!("Warn at", "预警比例"),
+        config.warn_percent
+    );
+    if providers.is_empty() {
+        println!("{}", info(crate::t!("No providers.", "暂无供应商。")));
+        return Ok(());
+    }
+
+    let mut table = create_table();
+    table.set_header(vec![
+        "ID",
+        "Name",
+        "Today",
+        "Daily limit",
+        "This month",
+        "Monthly limit",
+        "Status",
+    ]);
+    for (id, provider) in &providers {
+        let status = state.db.check_provider_limits(id, app_type.as_str())?;
+        let state_label = if status.daily_exceeded || status.monthly_exceeded {
+            "over limit"
+        } else if status.daily_limit.is_some() || status.monthly_limit.is_some() {
+            "ok"
+        } else {
+            "-"
+        };
+        table.add_row(vec![
+            id.clone(),
+            provider.name.clone(),
+            format_usd(&status.daily_usage),
+            status.daily_limit.unwrap_or_else(|| "-".to_string()),
+            format_usd(&status.monthly_usage),
+            status.monthly_limit.unwrap_or_else(|| "-".to_string()),
+            state_label.to_string(),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+fn set_limits(
+    app_type: AppType,
+    id: &str,
+    daily: Option<String>,
+    monthly: Option<String>,
+) -> Result<(), AppError> {
+    if daily.is_none() && monthly.is_none() {
+        return Err(AppError::InvalidInput(
+            "pass --daily and/or --monthly".to_string(),
+        ));
+    }
+    let daily = daily
+        .map(|value| parse_limit("--daily", &value))
+        .transpose()?;
+    let monthly = monthly
+        .map(|value| parse_limit("--monthly", &value))
+        .transpose()?;
+
+    let state = get_state()?;
+    ProviderService::set_spend_limits(&state, app_type, id, daily, monthly)?;
+    println!(
+        "{}",
+        success(&format!(
+            "{} {id}",
+            crate::t!("Spending limits updated for", "已更新消费限额：")
+        ))
+    );
+    Ok(())
+}
+
+fn configure_limits(warn_percent: Option<u8>, enforce: Option<bool>) -> Result<(), AppError> {
+    let state = get_state()?;
+    let mut config = state.db.get_spend_limit_config()?;
+    if let Some(warn_percent) = warn_percent {
+        config.warn_percent = warn_percent;
+    }
+    if let Some(enforce) = enforce {
+        config.enforce = enforce;
+    }
+    if warn_percent.is_some() || enforce.is_some() {
+        state.db.set_spend_limit_config(&config)?;
+    }
+
+    println!(
+        "{}: {}",
+        crate::t!("Enforced", "强制执行"),
+        if config.enforce { "yes" } else { "no" }
+    );
+    println!(
+        "{}: {}%",
+        crate::t!("Warn at", "预警比例"),
+        config.warn_percent
+    );
+    Ok(())
+}
+
+/// Parse a USD limit; "off" removes the limit.
+fn parse_limit(flag: &str, value: &str) -> Result<Option<String>, AppError> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    match value.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(Some(value.to_string())),
+        _ => Err(AppError::InvalidInput(format!(
+            "{flag} must be a non-negative USD amount or \"off\""
+        ))),
+    }
+}
+
+fn format_usd(value: &str) -> String {
+    value
+        .parse::<f64>()
+        .map(|amount| format!("{amount:.2}"))
+        .unwrap_or_else(|_| value.to_string())
+}
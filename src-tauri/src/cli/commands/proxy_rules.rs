@@ -0,0 +1,224 @@
+use clap::Subcommand;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{create_table, highlight, info, success};
+use crate::error::AppError;
+use crate::proxy::model_mapper::{
+    compile_model_rules, find_model_rewrite_rule, find_pinned_provider_rule,
+};
+use crate::proxy::types::{ModelMatchType, ModelRoutingRule};
+use crate::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProxyRulesCommand {
+    /// List model routing rules that apply to the selected app
+    List {
+        /// Show rules for every app
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Append a model routing rule
+    Add {
+        /// Model name pattern to match
+        pattern: String,
+
+        /// How the pattern is matched; every match type ignores case
+        #[arg(long = "match", value_enum, default_value_t = ModelMatchType::Exact)]
+        match_type: ModelMatchType,
+
+        /// Rewrite matching requests to this upstream model
+        #[arg(long = "model")]
+        target_model: Option<String>,
+
+        /// Send matching requests to this provider first
+        #[arg(long = "pin")]
+        pin_provider: Option<String>,
+
+        /// Only rewrite the model when this provider serves the request
+        #[arg(long = "provider", conflicts_with = "pin_provider")]
+        provider: Option<String>,
+
+        /// Apply the rule to every app instead of the selected one
+        #[arg(long)]
+        all_apps: bool,
+    },
+
+    /// Remove a model routing rule
+    Remove { id: i64 },
+
+    /// Move a rule to a 1-based position in the match order
+    Move { id: i64, position: usize },
+
+    /// Show which rule a model name would hit
+    Test {
+        model: String,
+
+        /// Provider serving the request; defaults to the pinned or current provider
+        #[arg(long)]
+        provider: Option<String>,
+    },
+}
+
+pub fn execute(cmd: ProxyRulesCommand, app_type: AppType) -> Result<(), AppError> {
+    match cmd {
+        ProxyRulesCommand::List { all } => list_rules(app_type, all),
+        ProxyRulesCommand::Add {
+            pattern,
+            match_type,
+            target_model,
+            pin_provider,
+            provider,
+            all_apps,
+        } => {
+            let rule_app_type = (!all_apps).then(|| app_type.as_str().to_string());
+            add_rule(
+                app_type,
+                ModelRoutingRule {
+                    id: 0,
+                    app_type: rule_app_type,
+                    provider_id: provider,
+                    match_type,
+                    pattern,
+                    target_model,
+                    pin_provider_id: pin_provider,
+                    sort_index: 0,
+                    enabled: true,
+                    created_at: 0,
+                },
+            )
+        }
+        ProxyRulesCommand::Remove { id } => remove_rule(id),
+        ProxyRulesCommand::Move { id, position } => move_rule(id, position),
+        ProxyRulesCommand::Test { model, provider } => test_rule(app_type, &model, provider),
+    }
+}
+
+fn get_state() -> Result<AppState, AppError> {
+    AppState::try_new()
+}
+
+/// 守护进程托管的代理会缓存已编译的规则与客户端令牌；变更后通知它重新加载，使修改立即生效
+#[cfg(unix)]
+pub(crate) fn reload_daemon_workers() {
+    if let Err(err) =
+        crate::daemon::send_control(&crate::daemon::ipc::protocol::Request::ReloadConfig)
+    {
+        log::warn!("Failed to reload proxy workers after a proxy configuration change: {err}");
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn reload_daemon_workers() {}
+
+fn list_rules(app_type: AppType, all: bool) -> Result<(), AppError> {
+    let state = get_state()?;
+    let rules = state
+        .db
+        .list_model_routing_rules((!all).then_some(app_type.as_str()))?;
+    if rules.is_empty() {
+        println!("{}", info("No model routing rules configured."));
+        return Ok(());
+    }
+
+    let mut table = create_table();
+    table.set_header(vec![
+        "#", "ID", "App", "Match", "Pattern", "Model", "Pin", "Provider",
+    ]);
+    let or_default = |value: &Option<String>, fallback: &str| {
+        value.clone().unwrap_or_else(|| fallback.to_string())
+    };
+    for (index, rule) in rules.iter().enumerate() {
+        table.add_row(vec![
+            (index + 1).to_string(),
+            rule.id.to_string(),
+            or_default(&rule.app_type, "*"),
+            rule.match_type.to_string(),
+            rule.pattern.clone(),
+            or_default(&rule.target_model, "-"),
+            or_default(&rule.pin_provider_id, "-"),
+            or_default(&rule.provider_id, "*"),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+fn add_rule(app_type: AppType, rule: ModelRoutingRule) -> Result<(), AppError> {
+    let state = get_state()?;
+    if let (Some(pinned), Some(_)) = (&rule.pin_provider_id, &rule.app_type) {
+        if state
+            .db
+            .get_provider_by_id(pinned, app_type.as_str())?
+            .is_none()
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Provider not found: {pinned}"
+            )));
+        }
+    }
+
+    let id = state.db.add_model_routing_rule(&rule)?;
+    reload_daemon_workers();
+    println!(
+        "{}",
+        success(&format!(
+            "Model routing rule {id} added for '{}'.",
+            rule.pattern
+        ))
+    );
+    Ok(())
+}
+
+fn remove_rule(id: i64) -> Result<(), AppError> {
+    let state = get_state()?;
+    if !state.db.delete_model_routing_rule(id)? {
+        return Err(AppError::InvalidInput(format!(
+            "Model routing rule {id} not found"
+        )));
+    }
+    reload_daemon_workers();
+    println!("{}", success(&format!("Model routing rule {id} removed.")));
+    Ok(())
+}
+
+fn move_rule(id: i64, position: usize) -> Result<(), AppError> {
+    if position == 0 {
+        return Err(AppError::InvalidInput("position starts at 1".to_string()));
+    }
+    let state = get_state()?;
+    state.db.move_model_routing_rule(id, position - 1)?;
+    reload_daemon_workers();
+    println!("{}", success("Model routing rule order updated."));
+    Ok(())
+}
+
+fn test_rule(app_type: AppType, model: &str, provider: Option<String>) -> Result<(), AppError> {
+    let state = get_state()?;
+    let rules = compile_model_rules(state.db.list_model_routing_rules(Some(app_type.as_str()))?);
+    let pinned = find_pinned_provider_rule(&rules, model);
+    let provider_id = provider
+        .or_else(|| pinned.and_then(|rule| rule.pin_provider_id.clone()))
+        .or_else(|| {
+            crate::settings::get_effective_current_provider(&state.db, &app_type)
+                .ok()
+                .flatten()
+        })
+        .unwrap_or_default();
+    let rewrite = find_model_rewrite_rule(&rules, &provider_id, model);
+
+    println!("{}", highlight(&format!("Model routing for {model}")));
+    match pinned {
+        Some(rule) => println!(
+            "Provider: {} (rule {})",
+            rule.pin_provider_id.as_deref().unwrap_or_default(),
+            rule.id
+        ),
+        None => println!("Provider: {provider_id} (no pin rule)"),
+    }
+    match rewrite.and_then(|rule| rule.target_model.as_deref().map(|target| (rule.id, target))) {
+        Some((id, target)) => println!("Upstream model: {target} (rule {id})"),
+        None => println!("Upstream model: provider model mapping (no rewrite rule)"),
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use chrono::{Local, TimeZone};
+use chrono::{Local, NaiveDate, TimeZone};
 use clap::Subcommand;
 use serde::Serialize;
+use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
 
@@ -8,7 +9,12 @@ use crate::app_config::AppType;
 use crate::cli::ui::{create_table, info, success, to_json, warning};
 use crate::database::Database;
 use crate::error::AppError;
+use crate::services::session_search::{
+    self, SessionSearchHit, SessionSearchQuery, DEFAULT_SEARCH_LIMIT,
+};
 use crate::services::session_usage::SessionSyncResult;
+use crate::session_manager::convert::{self, ConvertTarget};
+use crate::session_manager::export::{self, ExportFormat, SessionTranscript};
 use crate::session_manager::{self, SessionMessage, SessionMeta};
 
 #[derive(Subcommand, Debug, Clone)]
@@ -53,6 +59,47 @@ pub enum SessionsCommand {
         #[arg(long)]
         json: bool,
     },
+    /// Export sessions with tool calls, tool results and thinking blocks
+    Export {
+        /// Session ids or unique id prefixes. Also accepts provider/id.
+        #[arg(required = true, num_args = 1..)]
+        selectors: Vec<String>,
+        /// Resolve against a specific provider instead of --app
+        #[arg(long, value_parser = parse_session_provider)]
+        provider: Option<AppType>,
+        /// Search every supported app
+        #[arg(long)]
+        all: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value = "md")]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Convert a session into another app's format so it can be resumed there
+    Convert {
+        /// Session id or unique id prefix. Also accepts provider/id.
+        selector: String,
+        /// App to convert the session for
+        #[arg(long, value_enum)]
+        to: ConvertTarget,
+        /// Resolve against a specific provider instead of --app
+        #[arg(long, value_parser = parse_session_provider)]
+        provider: Option<AppType>,
+        /// Search every supported app
+        #[arg(long)]
+        all: bool,
+        /// Project directory for the new session (defaults to the source session's workdir)
+        #[arg(long)]
+        project: Option<PathBuf>,
+        /// Resume the converted session right away
+        #[arg(long)]
+        resume: bool,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Resume a session using its saved resume command
     Resume {
         /// Session id or unique id prefix. Also accepts provider/id.
@@ -81,6 +128,36 @@ pub enum SessionsCommand {
         #[arg(long)]
         yes: bool,
     },
+    /// Full-text search across saved session messages
+    Search {
+        /// Words to search for; every word must match (prefix match)
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+        /// Only search a specific provider
+        #[arg(long, value_parser = parse_session_provider)]
+        provider: Option<AppType>,
+        /// Search every supported app even when --app is set
+        #[arg(long)]
+        all: bool,
+        /// Only include sessions whose workdir contains this text
+        #[arg(long)]
+        project: Option<String>,
+        /// Only include sessions active on or after this date (YYYY-MM-DD)
+        #[arg(long, value_parser = parse_search_date)]
+        since: Option<NaiveDate>,
+        /// Only include sessions active on or before this date (YYYY-MM-DD)
+        #[arg(long, value_parser = parse_search_date)]
+        until: Option<NaiveDate>,
+        /// Maximum number of sessions to return
+        #[arg(long, default_value_t = DEFAULT_SEARCH_LIMIT)]
+        limit: usize,
+        /// Search the existing index without rescanning session files
+        #[arg(long)]
+        no_sync: bool,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Sync local session logs into usage statistics
     SyncUsage {
         /// Sync a specific provider instead of --app
@@ -124,6 +201,22 @@ pub fn execute(cmd: SessionsCommand, app: Option<AppType>) -> Result<(), AppErro
             all,
             json,
         } => print_messages(app, provider, all, &selector, json),
+        SessionsCommand::Export {
+            selectors,
+            provider,
+            all,
+            format,
+            output,
+        } => export_sessions(app, provider, all, &selectors, format, output),
+        SessionsCommand::Convert {
+            selector,
+            to,
+            provider,
+            all,
+            project,
+            resume,
+            json,
+        } => convert_session(app, provider, all, &selector, to, project, resume, json),
         SessionsCommand::Resume {
             selector,
             provider,
@@ -136,6 +229,28 @@ pub fn execute(cmd: SessionsCommand, app: Option<AppType>) -> Result<(), AppErro
             all,
             yes,
         } => delete_session(app, provider, all, &selector, yes),
+        SessionsCommand::Search {
+            query,
+            provider,
+            all,
+            project,
+            since,
+            until,
+            limit,
+            no_sync,
+            json,
+        } => {
+            let provider = if all { None } else { provider.or(app) };
+            let mut search = SessionSearchQuery::new(query.join(" "));
+            search.provider_id = provider.map(|app_type| app_type.as_str().to_string());
+            search.project_dir = project.filter(|value| !value.trim().is_empty());
+            search.since_ms = since.and_then(local_day_start_ms);
+            search.until_ms = until
+                .and_then(|date| date.succ_opt())
+                .and_then(local_day_start_ms);
+            search.limit = limit.max(1);
+            search_sessions(&search, !no_sync, json)
+        }
         SessionsCommand::SyncUsage {
             provider,
             all,
@@ -243,6 +358,114 @@ fn print_messages(
     Ok(())
 }
 
+fn export_sessions(
+    app: Option<AppType>,
+    provider: Option<AppType>,
+    all: bool,
+    selectors: &[String],
+    format: ExportFormat,
+    output: Option<PathBuf>,
+) -> Result<(), AppError> {
+    let mut transcripts = Vec::with_capacity(selectors.len());
+    for selector in selectors {
+        let (session, _) = resolve_scanned_session(app.clone(), provider.clone(), all, selector)?;
+        let source_path = required_source_path(&session)?;
+        let messages = session_manager::load_transcript(&session.provider_id, source_path)
+            .map_err(AppError::Message)?;
+        transcripts.push(SessionTranscript { session, messages });
+    }
+
+    let rendered = export::render(format, &transcripts).map_err(AppError::Message)?;
+    let Some(path) = output else {
+        print!("{rendered}");
+        return Ok(());
+    };
+
+    std::fs::write(&path, rendered).map_err(|source| AppError::IoContext {
+        context: format!("failed to write session export to {}", path.display()),
+        source,
+    })?;
+    println!(
+        "{}",
+        success(&format!(
+            "Exported {} session(s) as {} to {}.",
+            transcripts.len(),
+            format.extension(),
+            path.display()
+        ))
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn convert_session(
+    app: Option<AppType>,
+    provider: Option<AppType>,
+    all: bool,
+    selector: &str,
+    target: ConvertTarget,
+    project: Option<PathBuf>,
+    resume: bool,
+    json: bool,
+) -> Result<(), AppError> {
+    let (session, _) = resolve_scanned_session(app, provider, all, selector)?;
+    let source_path = required_source_path(&session)?;
+    let messages = session_manager::load_transcript(&session.provider_id, source_path)
+        .map_err(AppError::Message)?;
+
+    let project_dir = match project {
+        Some(path) => path,
+        None => match session
+            .project_dir
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+        {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir().map_err(|source| AppError::IoContext {
+                context: "failed to read the current directory".to_string(),
+                source,
+            })?,
+        },
+    };
+    let project_dir = project_dir.to_string_lossy().to_string();
+
+    let converted = convert::convert_session(
+        &SessionTranscript { session, messages },
+        target,
+        &project_dir,
+    )
+    .map_err(AppError::Message)?;
+
+    if json {
+        println!(
+            "{}",
+            to_json(&converted).map_err(|source| AppError::JsonSerialize { source })?
+        );
+    } else {
+        println!(
+            "{}",
+            success(&format!(
+                "Converted to {} session {} ({} turns): {}",
+                converted.provider_id,
+                converted.session_id,
+                converted.turns,
+                converted.source_path.display()
+            ))
+        );
+        if !resume {
+            println!(
+                "{}",
+                info(&format!("Resume with: {}", converted.resume_command))
+            );
+        }
+    }
+
+    if resume {
+        run_resume_command(&converted.resume_command, Some(&converted.project_dir))?;
+    }
+    Ok(())
+}
+
 fn resume_session(
     app: Option<AppType>,
     provider: Option<AppType>,
@@ -262,8 +485,12 @@ fn resume_session(
         return Ok(());
     }
 
+    run_resume_command(command, session.project_dir.as_deref())
+}
+
+fn run_resume_command(command: &str, project_dir: Option<&str>) -> Result<(), AppError> {
     let status = shell_command(command)
-        .current_dir_for_session(session.project_dir.as_deref())?
+        .current_dir_for_session(project_dir)?
         .status()
         .map_err(|source| AppError::IoContext {
             context: format!("failed to execute resume command `{command}`"),
@@ -322,6 +549,46 @@ fn delete_session(
     Ok(())
 }
 
+fn search_sessions(query: &SessionSearchQuery, sync: bool, json: bool) -> Result<(), AppError> {
+    let db = Database::init()?;
+    let hits = session_search::search_sessions(&db, query, sync)?;
+
+    if json {
+        println!(
+            "{}",
+            to_json(&hits).map_err(|source| AppError::JsonSerialize { source })?
+        );
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("{}", info("No matching sessions found."));
+        return Ok(());
+    }
+
+    print_search_hits(&hits);
+    Ok(())
+}
+
+fn print_search_hits(hits: &[SessionSearchHit]) {
+    let mut table = create_table();
+    table.set_header(vec![
+        "Provider", "Session", "Title", "Updated", "Matches", "Snippet",
+    ]);
+    for hit in hits {
+        let session = &hit.session;
+        table.add_row(vec![
+            session.provider_id.clone(),
+            short_session_id(&session.session_id),
+            session_title(session),
+            format_session_time(session.last_active_at.or(session.created_at)),
+            hit.matches.to_string(),
+            format!("{}: {}", hit.role, collapse_message_preview(&hit.snippet)),
+        ]);
+    }
+    println!("{table}");
+}
+
 fn sync_usage(
     app: Option<AppType>,
     provider: Option<AppType>,
@@ -496,6 +763,17 @@ fn parse_session_provider(value: &str) -> Result<AppType, String> {
     })
 }
 
+fn parse_search_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{value}'. Expected YYYY-MM-DD"))
+}
+
+fn local_day_start_ms(date: NaiveDate) -> Option<i64> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|value| Local.from_local_datetime(&value).earliest())
+        .map(|value| value.timestamp_millis())
+}
+
 fn app_type_from_provider_id(provider_id: &str) -> Option<AppType> {
     let normalized = provider_id.trim().to_lowercase().replace('-', "");
     AppType::from_str(&normalized).ok()
@@ -753,6 +1031,16 @@ mod tests {
         assert!(parse_session_provider("unknown").is_err());
     }
 
+    #[test]
+    fn parses_search_dates_as_local_day_bounds() {
+        let date = parse_search_date("2026-03-01").expect("valid date");
+        let start = local_day_start_ms(date).expect("day start");
+        let end = local_day_start_ms(date.succ_opt().unwrap()).expect("next day start");
+
+        assert!(end > start);
+        assert!(parse_search_date("03/01/2026").is_err());
+    }
+
     #[test]
     fn session_title_prefers_title_project_then_id() {
         let titled = SessionMeta {
@@ -0,0 +1,552 @@
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use clap::Subcommand;
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::cli::ui::{create_table, highlight, info, to_json};
+use crate::error::AppError;
+use crate::services::{LogFilters, UsageScope};
+use crate::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum UsageCommand {
+    /// Show totals for requests, tokens and cost
+    Summary {
+        #[command(flatten)]
+        filters: UsageFilterArgs,
+    },
+
+    /// Show usage per hour (ranges up to a day) or per day
+    Trends {
+        #[command(flatten)]
+        filters: UsageFilterArgs,
+    },
+
+    /// Break usage down by provider
+    Providers {
+        #[command(flatten)]
+        filters: UsageFilterArgs,
+    },
+
+    /// Break usage down by model
+    Models {
+        #[command(flatten)]
+        filters: UsageFilterArgs,
+    },
+
+    /// List individual proxied requests, newest first
+    Logs {
+        #[command(flatten)]
+        filters: UsageFilterArgs,
+
+        /// Only show requests with this HTTP status code
+        #[arg(long)]
+        status: Option<u16>,
+
+        /// Only show requests made with this proxy client token
+        #[arg(long)]
+        client: Option<String>,
+
+        /// Maximum number of requests to show
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+
+        /// Skip this many pages of `--limit` requests
+        #[arg(long, default_value_t = 0)]
+        page: u32,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct UsageFilterArgs {
+    /// First day to include (YYYY-MM-DD, local time)
+    #[arg(long, value_parser = parse_usage_date, conflicts_with = "month")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to include (YYYY-MM-DD, local time)
+    #[arg(long, value_parser = parse_usage_date, conflicts_with = "month")]
+    pub to: Option<NaiveDate>,
+
+    /// Calendar month to report on (YYYY-MM)
+    #[arg(long, value_parser = parse_usage_month)]
+    pub month: Option<NaiveDate>,
+
+    /// Only include requests served by this provider ID
+    #[arg(long)]
+    pub provider: Option<String>,
+
+    /// Only include requests for this model
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = UsageFormat::Table)]
+    pub format: UsageFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum UsageFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+pub fn execute(cmd: UsageCommand, app: Option<AppType>) -> Result<(), AppError> {
+    let state = AppState::try_new()?;
+    let app_type = app.as_ref().map(AppType::as_str);
+    match cmd {
+        UsageCommand::Summary { filters } => show_summary(&state, app_type, &filters),
+        UsageCommand::Trends { filters } => show_trends(&state, app_type, &filters),
+        UsageCommand::Providers { filters } => show_providers(&state, app_type, &filters),
+        UsageCommand::Models { filters } => show_models(&state, app_type, &filters),
+        UsageCommand::Logs {
+            filters,
+            status,
+            client,
+            limit,
+            page,
+        } => show_logs(&state, app_type, &filters, status, client, limit, page),
+    }
+}
+
+impl UsageFilterArgs {
+    fn scope<'a>(&'a self, app_type: Option<&'a str>) -> UsageScope<'a> {
+        UsageScope {
+            app_type,
+            provider_id: self.provider.as_deref(),
+            model: self.model.as_deref(),
+        }
+    }
+
+    /// Inclusive `(start, end)` unix-second bounds for the selected local days.
+    fn range(&self) -> Result<(Option<i64>, Option<i64>), AppError> {
+        let (from, to) = match self.month {
+            Some(first) => (Some(first), Some(last_day_of_month(first))),
+            None => (self.from, self.to),
+        };
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(AppError::InvalidInput(
+                    "--from must not be after --to".to_string(),
+                ));
+            }
+        }
+        let start = from.and_then(local_day_start);
+        let end = to
+            .and_then(|day| day.succ_opt())
+            .and_then(local_day_start)
+            .map(|next_day| next_day - 1);
+        Ok((start, end))
+    }
+}
+
+fn show_summary(
+    state: &AppState,
+    app_type: Option<&str>,
+    filters: &UsageFilterArgs,
+) -> Result<(), AppError> {
+    let (start, end) = filters.range()?;
+    let summary = state
+        .db
+        .get_usage_summary(start, end, &filters.scope(app_type))?;
+
+    match filters.format {
+        UsageFormat::Json => print_json(&summary),
+        UsageFormat::Csv => {
+            print_csv(
+                &[
+                    "requests",
+                    "cost_usd",
+                    "input_tokens",
+                    "output_tokens",
+                    "cache_creation_tokens",
+                    "cache_read_tokens",
+                    "success_rate",
+                ],
+                vec![vec![
+                    summary.total_requests.to_string(),
+                    summary.total_cost.clone(),
+                    summary.total_input_tokens.to_string(),
+                    summary.total_output_tokens.to_string(),
+                    summary.total_cache_creation_tokens.to_string(),
+                    summary.total_cache_read_tokens.to_string(),
+                    format!("{:.2}", summary.success_rate),
+                ]],
+            );
+            Ok(())
+        }
+        UsageFormat::Table => {
+            println!("{}", highlight(crate::t!("Usage Summary", "使用量汇总")));
+            let mut table = create_table();
+            table.set_header(vec!["Metric", "Value"]);
+            table.add_row(vec![
+                "Requests".to_string(),
+                summary.total_requests.to_string(),
+            ]);
+            table.add_row(vec!["Cost".to_string(), format_usd(&summary.total_cost)]);
+            table.add_row(vec![
+                "Input tokens".to_string(),
+                summary.total_input_tokens.to_string(),
+            ]);
+            table.add_row(vec![
+                "Output tokens".to_string(),
+                summary.total_output_tokens.to_string(),
+            ]);
+            table.add_row(vec![
+                "Cache write tokens".to_string(),
+                summary.total_cache_creation_tokens.to_string(),
+            ]);
+            table.add_row(vec![
+                "Cache read tokens".to_string(),
+                summary.total_cache_read_tokens.to_string(),
+            ]);
+            table.add_row(vec![
+                "Cache hit rate".to_string(),
+                format!("{:.1}%", summary.cache_hit_rate * 100.0),
+            ]);
+            table.add_row(vec![
+                "Success rate".to_string(),
+                format!("{:.1}%", summary.success_rate),
+            ]);
+            println!("{}", table);
+            Ok(())
+        }
+    }
+}
+
+fn show_trends(
+    state: &AppState,
+    app_type: Option<&str>,
+    filters: &UsageFilterArgs,
+) -> Result<(), AppError> {
+    let (start, end) = filters.range()?;
+    let trends = state
+        .db
+        .get_daily_trends(start, end, &filters.scope(app_type))?;
+
+    let header = [
+        "period",
+        "requests",
+        "cost_usd",
+        "tokens",
+        "input_tokens",
+        "output_tokens",
+        "cache_creation_tokens",
+        "cache_read_tokens",
+    ];
+    let rows = |format_cost: fn(&str) -> String| {
+        trends
+            .iter()
+            .map(|stat| {
+                vec![
+                    stat.date.clone(),
+                    stat.request_count.to_string(),
+                    format_cost(&stat.total_cost),
+                    stat.total_tokens.to_string(),
+                    stat.total_input_tokens.to_string(),
+                    stat.total_output_tokens.to_string(),
+                    stat.total_cache_creation_tokens.to_string(),
+                    stat.total_cache_read_tokens.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    render(
+        filters.format,
+        &trends,
+        &header,
+        || rows(str::to_string),
+        || rows(format_usd),
+    )
+}
+
+fn show_providers(
+    state: &AppState,
+    app_type: Option<&str>,
+    filters: &UsageFilterArgs,
+) -> Result<(), AppError> {
+    let (start, end) = filters.range()?;
+    let stats = state
+        .db
+        .get_provider_stats(start, end, &filters.scope(app_type))?;
+
+    let header = [
+        "provider_id",
+        "provider_name",
+        "requests",
+        "tokens",
+        "cost_usd",
+        "success_rate",
+        "avg_latency_ms",
+    ];
+    let rows = |format_cost: fn(&str) -> String| {
+        stats
+            .iter()
+            .map(|stat| {
+                vec![
+                    stat.provider_id.clone(),
+                    stat.provider_name.clone(),
+                    stat.request_count.to_string(),
+                    stat.total_tokens.to_string(),
+                    format_cost(&stat.total_cost),
+                    format!("{:.1}", stat.success_rate),
+                    stat.avg_latency_ms.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    render(
+        filters.format,
+        &stats,
+        &header,
+        || rows(str::to_string),
+        || rows(format_usd),
+    )
+}
+
+fn show_models(
+    state: &AppState,
+    app_type: Option<&str>,
+    filters: &UsageFilterArgs,
+) -> Result<(), AppError> {
+    let (start, end) = filters.range()?;
+    let stats = state
+        .db
+        .get_model_stats(start, end, &filters.scope(app_type))?;
+
+    let header = [
+        "model",
+        "requests",
+        "tokens",
+        "cost_usd",
+        "avg_cost_per_request_usd",
+    ];
+    let rows = |format_cost: fn(&str) -> String| {
+        stats
+            .iter()
+            .map(|stat| {
+                vec![
+                    stat.model.clone(),
+                    stat.request_count.to_string(),
+                    stat.total_tokens.to_string(),
+                    format_cost(&stat.total_cost),
+                    stat.avg_cost_per_request.clone(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    render(
+        filters.format,
+        &stats,
+        &header,
+        || rows(str::to_string),
+        || rows(format_usd),
+    )
+}
+
+fn show_logs(
+    state: &AppState,
+    app_type: Option<&str>,
+    filters: &UsageFilterArgs,
+    status: Option<u16>,
+    client: Option<String>,
+    limit: u32,
+    page: u32,
+) -> Result<(), AppError> {
+    let (start, end) = filters.range()?;
+    let log_filters = LogFilters {
+        app_type: app_type.map(str::to_string),
+        provider_id: filters.provider.clone(),
+        client_token: client,
+        model: filters.model.clone(),
+        status_code: status,
+        start_date: start,
+        end_date: end,
+        ..LogFilters::default()
+    };
+    let logs = state
+        .db
+        .get_request_logs(&log_filters, page, limit.max(1))?;
+
+    let header = [
+        "time",
+        "request_id",
+        "app",
+        "provider_id",
+        "model",
+        "status",
+        "client",
+        "input_tokens",
+        "output_tokens",
+        "cost_usd",
+        "latency_ms",
+    ];
+    let rows = |format_cost: fn(&str) -> String| {
+        logs.data
+            .iter()
+            .map(|log| {
+                vec![
+                    format_timestamp(log.created_at),
+                    log.request_id.clone(),
+                    log.app_type.clone(),
+                    log.provider_id.clone(),
+                    log.model.clone(),
+                    log.status_code.to_string(),
+                    log.client_token.clone().unwrap_or_default(),
+                    log.input_tokens.to_string(),
+                    log.output_tokens.to_string(),
+                    format_cost(&log.total_cost_usd),
+                    log.latency_ms.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    render(
+        filters.format,
+        &logs,
+        &header,
+        || rows(str::to_string),
+        || rows(format_usd),
+    )?;
+    if filters.format == UsageFormat::Table && logs.total > (page + 1) * limit.max(1) {
+        println!(
+            "{}",
+            info(&format!(
+                "Showing {} of {} requests; use --page {} for more.",
+                logs.data.len(),
+                logs.total,
+                page + 1
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// Print `value` as JSON, CSV rows with raw numbers, or a table with rounded costs.
+fn render<T: Serialize>(
+    format: UsageFormat,
+    value: &T,
+    header: &[&str],
+    csv_rows: impl FnOnce() -> Vec<Vec<String>>,
+    table_rows: impl FnOnce() -> Vec<Vec<String>>,
+) -> Result<(), AppError> {
+    match format {
+        UsageFormat::Json => print_json(value),
+        UsageFormat::Csv => {
+            print_csv(header, csv_rows());
+            Ok(())
+        }
+        UsageFormat::Table => {
+            let rows = table_rows();
+            if rows.is_empty() {
+                println!(
+                    "{}",
+                    info(crate::t!(
+                        "No usage recorded for this range.",
+                        "该时间范围内没有使用记录。"
+                    ))
+                );
+                return Ok(());
+            }
+            let mut table = create_table();
+            table.set_header(header.to_vec());
+            for row in rows {
+                table.add_row(row);
+            }
+            println!("{}", table);
+            Ok(())
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), AppError> {
+    println!(
+        "{}",
+        to_json(value).map_err(|source| AppError::JsonSerialize { source })?
+    );
+    Ok(())
+}
+
+fn print_csv(header: &[&str], rows: Vec<Vec<String>>) {
+    println!("{}", header.join(","));
+    for row in rows {
+        let fields = row.iter().map(|field| csv_field(field)).collect::<Vec<_>>();
+        println!("{}", fields.join(","));
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_usd(value: &str) -> String {
+    value
+        .parse::<f64>()
+        .map(|amount| format!("${amount:.4}"))
+        .unwrap_or_else(|_| value.to_string())
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn local_day_start(day: NaiveDate) -> Option<i64> {
+    day.and_hms_opt(0, 0, 0)
+        .and_then(|value| Local.from_local_datetime(&value).earliest())
+        .map(|value| value.timestamp())
+}
+
+fn last_day_of_month(first: NaiveDate) -> NaiveDate {
+    let (year, month) = if first.month() == 12 {
+        (first.year() + 1, 1)
+    } else {
+        (first.year(), first.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(first)
+}
+
+fn parse_usage_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{value}'. Expected YYYY-MM-DD"))
+}
+
+fn parse_usage_month(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("invalid month '{value}'. Expected YYYY-MM"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_range_covers_whole_local_month() {
+        let filters = UsageFilterArgs {
+            month: Some(parse_usage_month("2026-02").unwrap()),
+            ..UsageFilterArgs::default()
+        };
+        let (start, end) = filters.range().unwrap();
+        let feb_first = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        let mar_first = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(start, local_day_start(feb_first));
+        assert_eq!(end, local_day_start(mar_first).map(|ts| ts - 1));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("relay-a"), "relay-a");
+        assert_eq!(csv_field("Relay, Inc"), "\"Relay, Inc\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -827,6 +827,22 @@ pub mod texts {
         }
     }
 
+    pub fn tui_label_webdav_auto_sync() -> &'static str {
+        if is_chinese() {
+            "自动同步"
+        } else {
+            "Auto sync"
+        }
+    }
+
+    pub fn tui_webdav_auto_sync_every(minutes: u32) -> String {
+        if is_chinese() {
+            format!("每 {minutes} 分钟（守护进程）")
+        } else {
+            format!("Every {minutes} min (daemon)")
+        }
+    }
+
     pub fn tui_webdav_status_not_configured() -> &'static str {
         if is_chinese() {
             "未配置"
@@ -4924,6 +4940,14 @@ pub mod texts {
         }
     }
 
+    pub fn tui_config_item_webdav_merge() -> &'static str {
+        if is_chinese() {
+            "WebDAV 双向合并同步"
+        } else {
+            "WebDAV Merge Sync (two-way)"
+        }
+    }
+
     pub fn tui_config_item_webdav_reset() -> &'static str {
         if is_chinese() {
             "重置 WebDAV 配置"
@@ -5782,6 +5806,14 @@ pub mod texts {
         }
     }
 
+    pub fn tui_webdav_loading_title_merge() -> &'static str {
+        if is_chinese() {
+            "WebDAV 合并同步"
+        } else {
+            "WebDAV Merge Sync"
+        }
+    }
+
     pub fn tui_webdav_loading_title_quick_setup() -> &'static str {
         if is_chinese() {
             "坚果云一键配置"
@@ -7426,6 +7458,47 @@ pub mod texts {
         }
     }
 
+    pub fn tui_toast_webdav_merge_ok(message: &str) -> String {
+        if is_chinese() {
+            format!("WebDAV 合并同步完成：{message}")
+        } else {
+            format!("WebDAV merge sync finished: {message}")
+        }
+    }
+
+    pub fn tui_sync_conflicts_title(count: usize) -> String {
+        if is_chinese() {
+            format!("同步冲突（{count} 条记录两侧都被修改）")
+        } else {
+            format!("Sync conflicts ({count} records changed on both sides)")
+        }
+    }
+
+    pub fn tui_sync_conflict_side(take_remote: bool) -> &'static str {
+        match (is_chinese(), take_remote) {
+            (true, false) => "本机",
+            (true, true) => "远端",
+            (false, false) => "Local",
+            (false, true) => "Remote",
+        }
+    }
+
+    pub fn tui_sync_conflict_deleted_locally() -> &'static str {
+        if is_chinese() {
+            "本机已删除，远端有修改"
+        } else {
+            "deleted here, edited remotely"
+        }
+    }
+
+    pub fn tui_sync_conflict_deleted_remotely() -> &'static str {
+        if is_chinese() {
+            "本机有修改，远端已删除"
+        } else {
+            "edited here, deleted remotely"
+        }
+    }
+
     pub fn tui_webdav_v1_migration_title() -> &'static str {
         if is_chinese() {
             "发现旧版同步数据"
@@ -8104,6 +8177,30 @@ pub mod texts {
         }
     }
 
+    pub fn tui_sessions_toast_search_finished(count: usize) -> String {
+        if is_chinese() {
+            format!("内容搜索完成：{count} 个会话匹配")
+        } else {
+            format!("Content search finished: {count} matching session(s)")
+        }
+    }
+
+    pub fn tui_sessions_toast_search_failed(err: &str) -> String {
+        if is_chinese() {
+            format!("会话内容搜索失败：{err}")
+        } else {
+            format!("Session content search failed: {err}")
+        }
+    }
+
+    pub fn tui_sessions_searching_summary() -> &'static str {
+        if is_chinese() {
+            "正在搜索会话内容…"
+        } else {
+            "Searching session contents…"
+        }
+    }
+
     pub fn tui_sessions_toast_messages_failed(err: &str) -> String {
         if is_chinese() {
             format!("消息加载失败：{err}")
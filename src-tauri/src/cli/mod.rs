@@ -74,6 +74,10 @@ pub enum Commands {
     #[command(subcommand)]
     Proxy(commands::proxy::ProxyCommand),
 
+    /// Report proxy usage and cost (summary, trends, providers, models, logs)
+    #[command(subcommand)]
+    Usage(commands::usage::UsageCommand),
+
     /// Manage persisted UI and integration settings
     #[command(subcommand)]
     Settings(commands::settings::SettingsCommand),
@@ -107,6 +111,13 @@ pub enum Commands {
     /// Import a resource (provider/mcp/prompt/skill) from a ccswitch:// deep link URL
     Deeplink(commands::deeplink::DeeplinkCommand),
 
+    /// Apply a declarative YAML/TOML setup file (providers, MCP, prompts, skills, failover, proxy)
+    Apply(commands::apply::ApplyCommand),
+
+    /// Apply the repository's .cc-switch.toml (provider, MCP servers, prompts) to project-scoped files
+    #[command(subcommand)]
+    Project(commands::project::ProjectCommand),
+
     /// Update cc-switch binary to latest release
     Update(commands::update::UpdateCommand),
 
@@ -315,6 +326,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_proxy_replay_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "proxy",
+            "replay",
+            "req-1",
+            "--provider",
+            "backup",
+        ]);
+
+        match cli.command {
+            Some(Commands::Proxy(super::commands::proxy::ProxyCommand::Replay {
+                request_id,
+                provider,
+                json,
+            })) => {
+                assert_eq!(request_id, "req-1");
+                assert_eq!(provider.as_deref(), Some("backup"));
+                assert!(!json);
+            }
+            _ => panic!("expected proxy replay command"),
+        }
+    }
+
+    #[test]
+    fn parses_proxy_limits_set_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "proxy",
+            "limits",
+            "set",
+            "relay",
+            "--monthly",
+            "200",
+            "--daily",
+            "off",
+        ]);
+
+        match cli.command {
+            Some(Commands::Proxy(super::commands::proxy::ProxyCommand::Limits(
+                super::commands::proxy_limits::ProxyLimitsCommand::Set { id, daily, monthly },
+            ))) => {
+                assert_eq!(id, "relay");
+                assert_eq!(daily.as_deref(), Some("off"));
+                assert_eq!(monthly.as_deref(), Some("200"));
+            }
+            _ => panic!("expected proxy limits set command"),
+        }
+    }
+
+    #[test]
+    fn parses_proxy_clients_add_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "proxy",
+            "clients",
+            "add",
+            "alice",
+            "--allow-app",
+            "claude",
+            "--allow-provider",
+            "relay",
+        ]);
+
+        match cli.command {
+            Some(Commands::Proxy(super::commands::proxy::ProxyCommand::Clients(
+                super::commands::proxy_clients::ProxyClientsCommand::Add {
+                    name,
+                    apps,
+                    providers,
+                },
+            ))) => {
+                assert_eq!(name, "alice");
+                assert_eq!(apps, vec![super::AppType::Claude]);
+                assert_eq!(providers, vec!["relay".to_string()]);
+            }
+            _ => panic!("expected proxy clients add command"),
+        }
+    }
+
     #[test]
     fn parses_update_check_json_flags() {
         let cli = Cli::parse_from(["cc-switch", "update", "--check", "--json"]);
@@ -517,6 +609,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_usage_providers_with_filters() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "--app",
+            "claude",
+            "usage",
+            "providers",
+            "--month",
+            "2026-03",
+            "--model",
+            "claude-sonnet-4",
+            "--format",
+            "csv",
+        ]);
+
+        assert_eq!(cli.app, Some(super::AppType::Claude));
+        match cli.command {
+            Some(Commands::Usage(super::commands::usage::UsageCommand::Providers { filters })) => {
+                assert_eq!(filters.month, chrono::NaiveDate::from_ymd_opt(2026, 3, 1));
+                assert_eq!(filters.model.as_deref(), Some("claude-sonnet-4"));
+                assert_eq!(filters.provider, None);
+                assert_eq!(filters.format, super::commands::usage::UsageFormat::Csv);
+            }
+            _ => panic!("expected usage providers command"),
+        }
+    }
+
     #[test]
     fn parses_sessions_list_subcommand() {
         let cli = Cli::parse_from(["cc-switch", "sessions", "list", "--all", "--json"]);
@@ -535,6 +655,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_sessions_search_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "sessions",
+            "search",
+            "borrow",
+            "checker",
+            "--project",
+            "alpha",
+            "--since",
+            "2026-01-02",
+            "--limit",
+            "5",
+        ]);
+
+        match cli.command {
+            Some(Commands::Sessions(super::commands::sessions::SessionsCommand::Search {
+                query,
+                project,
+                since,
+                limit,
+                no_sync,
+                ..
+            })) => {
+                assert_eq!(query, vec!["borrow".to_string(), "checker".to_string()]);
+                assert_eq!(project.as_deref(), Some("alpha"));
+                assert_eq!(
+                    since.map(|date| date.to_string()).as_deref(),
+                    Some("2026-01-02")
+                );
+                assert_eq!(limit, 5);
+                assert!(!no_sync);
+            }
+            _ => panic!("expected sessions search command"),
+        }
+    }
+
+    #[test]
+    fn parses_sessions_export_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "sessions",
+            "export",
+            "abc",
+            "codex/def",
+            "--format",
+            "html",
+            "-o",
+            "review.html",
+        ]);
+
+        match cli.command {
+            Some(Commands::Sessions(super::commands::sessions::SessionsCommand::Export {
+                selectors,
+                format,
+                output,
+                ..
+            })) => {
+                assert_eq!(selectors, vec!["abc".to_string(), "codex/def".to_string()]);
+                assert_eq!(format, crate::session_manager::export::ExportFormat::Html);
+                assert_eq!(output, Some(std::path::PathBuf::from("review.html")));
+            }
+            _ => panic!("expected sessions export command"),
+        }
+    }
+
+    #[test]
+    fn parses_sessions_convert_subcommand() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "sessions",
+            "convert",
+            "claude/abc",
+            "--to",
+            "codex",
+            "--resume",
+        ]);
+
+        match cli.command {
+            Some(Commands::Sessions(super::commands::sessions::SessionsCommand::Convert {
+                selector,
+                to,
+                project,
+                resume,
+                ..
+            })) => {
+                assert_eq!(selector, "claude/abc");
+                assert_eq!(to, crate::session_manager::convert::ConvertTarget::Codex);
+                assert_eq!(project, None);
+                assert!(resume);
+            }
+            _ => panic!("expected sessions convert command"),
+        }
+    }
+
     #[test]
     fn parses_sessions_list_with_backend_provider_id() {
         let cli = Cli::parse_from(["cc-switch", "sessions", "list", "--provider", "opencode"]);
@@ -937,6 +1153,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_prompts_compose_fragment_list() {
+        let cli = Cli::parse_from(["cc-switch", "prompts", "compose", "main", "rules,style"]);
+
+        match cli.command {
+            Some(Commands::Prompts(super::commands::prompts::PromptsCommand::Compose {
+                id,
+                fragments,
+            })) => {
+                assert_eq!(id, "main");
+                assert_eq!(fragments, vec!["rules", "style"]);
+            }
+            _ => panic!("expected prompts compose command"),
+        }
+    }
+
+    #[test]
+    fn parses_prompts_merge_keep_live() {
+        let cli = Cli::parse_from(["cc-switch", "prompts", "merge", "--keep-live"]);
+
+        match cli.command {
+            Some(Commands::Prompts(super::commands::prompts::PromptsCommand::Merge {
+                keep_live,
+                use_db,
+            })) => {
+                assert!(keep_live);
+                assert!(!use_db);
+            }
+            _ => panic!("expected prompts merge command"),
+        }
+        assert!(
+            Cli::try_parse_from(["cc-switch", "prompts", "merge", "--keep-live", "--use-db"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_prompts_shared_enable_apps() {
+        use super::commands::prompts::{PromptsCommand, SharedPromptsCommand};
+
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "prompts",
+            "shared",
+            "enable",
+            "team",
+            "--apps",
+            "claude,codex",
+            "--apps",
+            "openclaw",
+        ]);
+
+        match cli.command {
+            Some(Commands::Prompts(PromptsCommand::Shared(SharedPromptsCommand::Enable {
+                id,
+                apps,
+            }))) => {
+                assert_eq!(id, "team");
+                assert_eq!(apps, vec!["claude", "codex", "openclaw"]);
+            }
+            _ => panic!("expected prompts shared enable command"),
+        }
+    }
+
     #[test]
     fn parses_provider_stream_check_subcommand() {
         let cli = Cli::parse_from(["cc-switch", "provider", "stream-check", "demo"]);
@@ -1361,6 +1641,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_config_vault_init_with_key_file() {
+        let cli = Cli::parse_from([
+            "cc-switch",
+            "config",
+            "vault",
+            "init",
+            "--key-file",
+            "/tmp/cc-switch.key",
+        ]);
+
+        match cli.command {
+            Some(Commands::Config(super::commands::config::ConfigCommand::Vault(
+                super::commands::config_vault::VaultCommand::Init {
+                    key_file,
+                    no_migrate,
+                },
+            ))) => {
+                assert_eq!(
+                    key_file.as_deref(),
+                    Some(std::path::Path::new("/tmp/cc-switch.key"))
+                );
+                assert!(!no_migrate);
+            }
+            _ => panic!("expected config vault init command"),
+        }
+    }
+
     #[test]
     fn parses_config_openclaw_env_put_subcommand() {
         let cli = Cli::parse_from([
@@ -1619,6 +1927,38 @@ mod tests {
         assert!(help.contains("applicable"));
     }
 
+    #[test]
+    fn parses_apply_command() {
+        let cli = Cli::parse_from(["cc-switch", "apply", "-f", "team.yaml", "--dry-run"]);
+
+        match cli.command {
+            Some(Commands::Apply(cmd)) => {
+                assert_eq!(cmd.file, std::path::PathBuf::from("team.yaml"));
+                assert!(cmd.dry_run);
+                assert!(!cmd.prune);
+            }
+            _ => panic!("expected apply command"),
+        }
+    }
+
+    #[test]
+    fn parses_project_apply_command() {
+        let cli = Cli::parse_from(["cc-switch", "project", "apply", "--dir", "repo", "--force"]);
+
+        match cli.command {
+            Some(Commands::Project(super::commands::project::ProjectCommand::Apply {
+                dir,
+                dry_run,
+                force,
+            })) => {
+                assert_eq!(dir, Some(std::path::PathBuf::from("repo")));
+                assert!(!dry_run);
+                assert!(force);
+            }
+            _ => panic!("expected project apply command"),
+        }
+    }
+
     #[test]
     fn parses_env_tools_subcommand() {
         let cli = Cli::parse_from(["cc-switch", "env", "tools"]);
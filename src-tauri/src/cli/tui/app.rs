@@ -1,6 +1,6 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::Size;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use unicode_width::UnicodeWidthChar;
 
 use crate::app_config::AppType;
@@ -8,6 +8,7 @@ use crate::cli::i18n::current_language;
 use crate::cli::i18n::texts;
 use crate::cli::i18n::Language;
 use crate::services::skill::SyncMethod;
+use crate::services::{MergeSide, RecordConflict};
 
 use super::data::UiData;
 use super::form::{
@@ -44,9 +45,9 @@ pub use editor_state::{EditorKind, EditorMode, EditorState, EditorSubmit};
 pub(crate) use helpers::*;
 pub use types::{
     CommonSnippetViewSource, ConfirmAction, ConfirmOverlay, FilterScope, FilterState, Focus,
-    LoadingKind, ManagedAuthLoginState, Overlay, PricingState, SessionsPane, SessionsState,
-    SkillsDiscoverSource, TextInputState, TextSubmit, TextViewAction, TextViewState, Toast,
-    ToastKind, UsageMetric, UsagePane, UsageState,
+    LoadingKind, ManagedAuthLoginState, Overlay, PricingState, SessionContentSearch, SessionsPane,
+    SessionsState, SkillsDiscoverSource, TextInputState, TextSubmit, TextViewAction, TextViewState,
+    Toast, ToastKind, UsageMetric, UsagePane, UsageState,
 };
 
 pub(crate) fn supports_failover_controls(app_type: &AppType) -> bool {
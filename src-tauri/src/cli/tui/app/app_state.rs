@@ -11,6 +11,9 @@ pub enum Action {
     LocalEnvRefresh,
 
     SessionsRefresh,
+    SessionsContentSearch {
+        query: String,
+    },
     SessionMessagesLoad {
         key: String,
         provider_id: String,
@@ -197,6 +200,9 @@ pub enum Action {
     ConfigWebDavCheckConnection,
     ConfigWebDavUpload,
     ConfigWebDavDownload,
+    ConfigWebDavMerge {
+        resolutions: BTreeMap<String, MergeSide>,
+    },
     ConfigWebDavMigrateV1ToV2,
     ConfigWebDavReset,
     ConfigWebDavJianguoyunQuickSetup {
@@ -470,13 +476,15 @@ pub enum LocalProxySettingsItem {
     ListenAddress,
     ListenPort,
     AutoFailover,
+    ModelRoutingRules,
 }
 
 impl LocalProxySettingsItem {
-    pub const ALL: [LocalProxySettingsItem; 3] = [
+    pub const ALL: [LocalProxySettingsItem; 4] = [
         LocalProxySettingsItem::ListenAddress,
         LocalProxySettingsItem::ListenPort,
         LocalProxySettingsItem::AutoFailover,
+        LocalProxySettingsItem::ModelRoutingRules,
     ];
 }
 
@@ -492,16 +500,18 @@ pub enum WebDavConfigItem {
     CheckConnection,
     Upload,
     Download,
+    Merge,
     Reset,
     JianguoyunQuickSetup,
 }
 
 impl WebDavConfigItem {
-    pub const ALL: [WebDavConfigItem; 6] = [
+    pub const ALL: [WebDavConfigItem; 7] = [
         WebDavConfigItem::Settings,
         WebDavConfigItem::CheckConnection,
         WebDavConfigItem::Upload,
         WebDavConfigItem::Download,
+        WebDavConfigItem::Merge,
         WebDavConfigItem::Reset,
         WebDavConfigItem::JianguoyunQuickSetup,
     ];
@@ -512,6 +522,7 @@ impl WebDavConfigItem {
             WebDavConfigItem::CheckConnection => texts::tui_config_item_webdav_check_connection(),
             WebDavConfigItem::Upload => texts::tui_config_item_webdav_upload(),
             WebDavConfigItem::Download => texts::tui_config_item_webdav_download(),
+            WebDavConfigItem::Merge => texts::tui_config_item_webdav_merge(),
             WebDavConfigItem::Reset => texts::tui_config_item_webdav_reset(),
             WebDavConfigItem::JianguoyunQuickSetup => {
                 texts::tui_config_item_webdav_jianguoyun_quick_setup()
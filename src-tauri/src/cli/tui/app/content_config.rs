@@ -688,6 +688,9 @@ impl App {
                     WebDavConfigItem::CheckConnection => Action::ConfigWebDavCheckConnection,
                     WebDavConfigItem::Upload => Action::ConfigWebDavUpload,
                     WebDavConfigItem::Download => Action::ConfigWebDavDownload,
+                    WebDavConfigItem::Merge => Action::ConfigWebDavMerge {
+                        resolutions: BTreeMap::new(),
+                    },
                     WebDavConfigItem::Reset => Action::ConfigWebDavReset,
                     WebDavConfigItem::JianguoyunQuickSetup => {
                         self.webdav_quick_setup_username = None;
@@ -920,6 +923,17 @@ impl App {
                     });
                     Action::None
                 }
+                Some(LocalProxySettingsItem::ModelRoutingRules) => {
+                    let initial = serde_json::to_string_pretty(&data.proxy.model_routing_rules)
+                        .unwrap_or_else(|_| "[]".to_string());
+                    self.open_editor(
+                        crate::t!("Model Routing Rules", "模型路由规则"),
+                        EditorKind::Json,
+                        initial,
+                        EditorSubmit::ProxyModelRoutingRules,
+                    );
+                    Action::None
+                }
                 None => Action::None,
             },
             _ => Action::None,
@@ -38,6 +38,7 @@ impl App {
             self.sessions.detail_key.as_deref(),
             self.sessions.messages_loaded,
             &self.sessions.messages,
+            &self.sessions.content_search,
         );
         let Some(session) = visible.get(self.sessions.selected_idx) else {
             return Action::None;
@@ -509,6 +510,7 @@ impl App {
             self.sessions.detail_key.as_deref(),
             self.sessions.messages_loaded,
             &self.sessions.messages,
+            &self.sessions.content_search,
         );
         match key.code {
             KeyCode::Left => self.move_sessions_focus_left(),
@@ -51,6 +51,7 @@ pub enum EditorSubmit {
     ConfigOpenClawTools,
     ConfigOpenClawAgents,
     ConfigWebDavSettings,
+    ProxyModelRoutingRules,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
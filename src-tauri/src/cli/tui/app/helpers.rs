@@ -796,6 +796,7 @@ pub(crate) fn visible_sessions_for_state<'a>(
     detail_key: Option<&str>,
     messages_loaded: bool,
     messages: &[crate::session_manager::SessionMessage],
+    content_search: &SessionContentSearch,
 ) -> Vec<&'a crate::session_manager::SessionMeta> {
     let query = filter.query_lower();
     let provider_id = app_type.as_str();
@@ -809,6 +810,7 @@ pub(crate) fn visible_sessions_for_state<'a>(
             None => true,
             Some(q) => {
                 session_matches_filter(row, q)
+                    || content_search.matches(q, row)
                     || message_match_key
                         .as_deref()
                         .is_some_and(|key| session_key(row) == key)
@@ -674,12 +674,17 @@ impl App {
         let scope = self.filter.scope;
         let is_daily_memory = matches!(scope, FilterScope::Global)
             && matches!(self.route, Route::ConfigOpenClawDailyMemory);
+        let is_sessions =
+            matches!(scope, FilterScope::Global) && matches!(self.route, Route::Sessions);
         let mut filter_changed = false;
         let action = match key.code {
             KeyCode::Esc => {
                 filter_changed = !self.active_filter_input_mut().value.is_empty();
                 self.filter.active = false;
                 self.active_filter_input_mut().set("");
+                if is_sessions {
+                    self.sessions.content_search.clear();
+                }
                 if is_daily_memory {
                     self.openclaw_daily_memory_search_results.clear();
                     self.daily_memory_idx = 0;
@@ -696,6 +701,10 @@ impl App {
                     Action::OpenClawDailyMemorySearch {
                         query: self.filter.input.value.clone(),
                     }
+                } else if is_sessions && !self.filter.input.value.trim().is_empty() {
+                    Action::SessionsContentSearch {
+                        query: self.filter.input.value.clone(),
+                    }
                 } else {
                     Action::None
                 }
@@ -838,6 +847,7 @@ impl App {
             self.sessions.detail_key.as_deref(),
             self.sessions.messages_loaded,
             &self.sessions.messages,
+            &self.sessions.content_search,
         );
         let sessions_len = visible_session_rows.len();
         if sessions_len == 0 {
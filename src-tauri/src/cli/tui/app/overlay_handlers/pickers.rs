@@ -42,6 +42,9 @@ impl App {
         if let Some(action) = self.handle_mcp_type_picker_key(key) {
             return Some(action);
         }
+        if let Some(action) = self.handle_sync_conflict_picker_key(key) {
+            return Some(action);
+        }
         if let Some(action) = self.handle_mcp_apps_picker_key(key, data) {
             return Some(action);
         }
@@ -917,6 +920,66 @@ impl App {
         })
     }
 
+    fn handle_sync_conflict_picker_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let Overlay::SyncConflictPicker {
+            conflicts,
+            sides,
+            resolved,
+            selected,
+        } = &mut self.overlay
+        else {
+            return None;
+        };
+
+        Some(match key.code {
+            KeyCode::Esc => {
+                self.overlay = Overlay::None;
+                Action::None
+            }
+            KeyCode::Up => {
+                *selected = selected.saturating_sub(1);
+                Action::None
+            }
+            KeyCode::Down => {
+                *selected = (*selected + 1).min(conflicts.len().saturating_sub(1));
+                Action::None
+            }
+            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right => {
+                if let Some(side) = sides.get_mut(*selected) {
+                    *side = match side {
+                        MergeSide::Local => MergeSide::Remote,
+                        MergeSide::Remote => MergeSide::Local,
+                    };
+                }
+                Action::None
+            }
+            KeyCode::Char('l') | KeyCode::Char('L') => {
+                if let Some(side) = sides.get_mut(*selected) {
+                    *side = MergeSide::Local;
+                }
+                Action::None
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                if let Some(side) = sides.get_mut(*selected) {
+                    *side = MergeSide::Remote;
+                }
+                Action::None
+            }
+            KeyCode::Enter => {
+                let mut resolutions = std::mem::take(resolved);
+                resolutions.extend(
+                    conflicts
+                        .iter()
+                        .zip(sides.iter())
+                        .map(|(conflict, side)| (conflict.key.clone(), *side)),
+                );
+                self.overlay = Overlay::None;
+                Action::ConfigWebDavMerge { resolutions }
+            }
+            _ => Action::None,
+        })
+    }
+
     fn handle_mcp_type_picker_key(&mut self, key: KeyEvent) -> Option<Action> {
         let Overlay::McpTypePicker { selected } = &mut self.overlay else {
             return None;
@@ -3870,6 +3870,7 @@ mod tests {
                 enabled: false,
                 created_at: None,
                 updated_at: None,
+                fragments: Vec::new(),
             },
         });
 
@@ -3898,6 +3899,7 @@ mod tests {
                 enabled: true,
                 created_at: None,
                 updated_at: None,
+                fragments: Vec::new(),
             },
         });
 
@@ -4019,6 +4021,7 @@ mod tests {
                 enabled: false,
                 created_at: None,
                 updated_at: None,
+                fragments: Vec::new(),
             },
         });
 
@@ -9953,6 +9956,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn settings_proxy_submenu_model_routing_rules_opens_json_editor() {
+        let mut app = App::new(Some(AppType::Claude));
+        app.route = Route::SettingsProxy;
+        app.focus = Focus::Content;
+        app.settings_proxy_idx = LocalProxySettingsItem::ALL
+            .iter()
+            .position(|item| matches!(item, LocalProxySettingsItem::ModelRoutingRules))
+            .expect("ModelRoutingRules missing");
+
+        let mut data = UiData::default();
+        data.proxy.model_routing_rules = vec![crate::proxy::types::ModelRoutingRule {
+            id: 1,
+            app_type: Some("claude".to_string()),
+            provider_id: None,
+            match_type: crate::proxy::types::ModelMatchType::Glob,
+            pattern: "*haiku*".to_string(),
+            target_model: None,
+            pin_provider_id: Some("cheap-relay".to_string()),
+            sort_index: 0,
+            enabled: true,
+            created_at: 0,
+        }];
+
+        let action = app.on_key(key(KeyCode::Enter), &data);
+        assert!(matches!(action, Action::None));
+        let editor = app.editor.as_ref().expect("model routing editor opened");
+        assert!(matches!(
+            editor.submit,
+            EditorSubmit::ProxyModelRoutingRules
+        ));
+        assert!(editor.initial_text.contains("cheap-relay"));
+    }
+
     #[test]
     fn settings_proxy_submenu_port_opens_text_input() {
         let mut app = App::new(Some(AppType::Claude));
@@ -10273,6 +10310,16 @@ mod tests {
             Action::ConfigWebDavDownload
         ));
 
+        let merge_idx = WebDavConfigItem::ALL
+            .iter()
+            .position(|item| matches!(item, WebDavConfigItem::Merge))
+            .expect("WebDavMerge missing");
+        app.config_webdav_idx = merge_idx;
+        assert!(matches!(
+            app.on_key(key(KeyCode::Enter), &data),
+            Action::ConfigWebDavMerge { resolutions } if resolutions.is_empty()
+        ));
+
         let reset_idx = WebDavConfigItem::ALL
             .iter()
             .position(|item| matches!(item, WebDavConfigItem::Reset))
@@ -10285,11 +10332,53 @@ mod tests {
 
         assert_eq!(
             WebDavConfigItem::ALL.len(),
-            6,
+            7,
             "WebDav submenu should include Jianguoyun quick setup"
         );
     }
 
+    #[test]
+    fn sync_conflict_picker_toggles_sides_and_requeues_merge_with_resolutions() {
+        let mut app = App::new(Some(AppType::Claude));
+        let conflict = |key: &str| RecordConflict {
+            key: key.to_string(),
+            label: key.to_string(),
+            fields: vec!["settingsConfig.env.URL".to_string()],
+            deleted_locally: false,
+            deleted_remotely: false,
+        };
+        app.overlay = Overlay::SyncConflictPicker {
+            conflicts: vec![conflict("provider/claude/a"), conflict("mcp/fs")],
+            sides: vec![MergeSide::Local, MergeSide::Local],
+            resolved: BTreeMap::from([("prompt/claude/p".to_string(), MergeSide::Remote)]),
+            selected: 0,
+        };
+
+        let data = UiData::default();
+        assert!(matches!(
+            app.on_key(key(KeyCode::Down), &data),
+            Action::None
+        ));
+        assert!(matches!(
+            app.on_key(key(KeyCode::Right), &data),
+            Action::None
+        ));
+
+        let Action::ConfigWebDavMerge { resolutions } = app.on_key(key(KeyCode::Enter), &data)
+        else {
+            panic!("expected merge to be re-queued");
+        };
+        assert!(matches!(app.overlay, Overlay::None));
+        assert_eq!(
+            resolutions,
+            BTreeMap::from([
+                ("mcp/fs".to_string(), MergeSide::Remote),
+                ("prompt/claude/p".to_string(), MergeSide::Remote),
+                ("provider/claude/a".to_string(), MergeSide::Local),
+            ])
+        );
+    }
+
     #[test]
     fn config_webdav_quick_setup_requires_username_then_password() {
         let mut app = App::new(Some(AppType::Claude));
@@ -10411,6 +10500,7 @@ mod tests {
                 enabled: false,
                 created_at: None,
                 updated_at: None,
+                fragments: Vec::new(),
             },
         });
 
@@ -10526,6 +10616,7 @@ mod tests {
                 enabled: false,
                 created_at: None,
                 updated_at: None,
+                fragments: Vec::new(),
             },
         });
 
@@ -10670,6 +10761,7 @@ mod tests {
             enabled: false,
             created_at: None,
             updated_at: None,
+            fragments: Vec::new(),
         };
         let mut form = PromptMetaFormState::from_prompt(&prompt);
         form.name.set("   ");
@@ -10695,6 +10787,7 @@ mod tests {
             enabled: false,
             created_at: None,
             updated_at: None,
+            fragments: Vec::new(),
         };
         let mut form = PromptMetaFormState::from_prompt(&prompt);
         form.id.set("renamed-id");
@@ -10738,6 +10831,7 @@ mod tests {
                 enabled: false,
                 created_at: Some(1),
                 updated_at: Some(1),
+                fragments: Vec::new(),
             },
         )
         .expect("seed prompt");
@@ -10920,6 +11014,7 @@ mod tests {
                 enabled: false,
                 created_at: Some(1),
                 updated_at: Some(1),
+                fragments: Vec::new(),
             },
         )
         .expect("seed prompt");
@@ -14432,6 +14527,49 @@ mod tests {
         assert_eq!(app.sessions.selected_idx, 0);
     }
 
+    #[test]
+    fn sessions_filter_enter_runs_content_search_and_shows_matches() {
+        let mut app = app_with_session_page();
+        app.sessions.rows.push(session_meta(
+            "claude",
+            "session-2",
+            "Other",
+            "/tmp/other",
+            "/tmp/other.jsonl",
+            "claude --resume session-2",
+        ));
+        app.filter.active = true;
+        app.filter.input.set("borrow".to_string());
+
+        let action = app.on_key(key(KeyCode::Enter), &data());
+        assert!(matches!(
+            action,
+            Action::SessionsContentSearch { query } if query == "borrow"
+        ));
+
+        // The runtime starts the search before handing it to the sessions worker.
+        let request_id = app.sessions.content_search.start("borrow".to_string());
+        assert!(app
+            .sessions
+            .content_search
+            .finish(request_id, vec!["/tmp/other.jsonl".to_string()]));
+        let visible = visible_sessions_for_state(
+            &app.filter,
+            &app.app_type,
+            &app.sessions.rows,
+            None,
+            false,
+            &[],
+            &app.sessions.content_search,
+        );
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].session_id, "session-2");
+
+        app.filter.active = true;
+        app.on_key(key(KeyCode::Esc), &data());
+        assert!(app.sessions.content_search.query.is_none());
+    }
+
     #[test]
     fn session_scan_cache_restores_for_the_whole_run() {
         let mut sessions = SessionsState::default();
@@ -15058,6 +15196,7 @@ mod tests {
             role: "assistant".to_string(),
             content: "old detail".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         }];
         app.sessions.pane = SessionsPane::List;
 
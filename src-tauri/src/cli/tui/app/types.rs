@@ -166,6 +166,67 @@ pub struct CachedScan {
     pub rows: Vec<crate::session_manager::SessionMeta>,
 }
 
+/// Sessions whose message contents matched a full-text search, keyed by
+/// `source_path`. Only applied while the list filter still equals `query`, so
+/// editing the filter falls back to metadata matching until Enter is pressed.
+#[derive(Debug, Clone, Default)]
+pub struct SessionContentSearch {
+    pub query: Option<String>,
+    pub source_paths: HashSet<String>,
+    pub seq: u64,
+    pub active: Option<u64>,
+}
+
+impl SessionContentSearch {
+    pub(crate) fn start(&mut self, query: String) -> u64 {
+        self.seq = self.seq.wrapping_add(1);
+        self.active = Some(self.seq);
+        self.query = Some(query.trim().to_lowercase());
+        self.source_paths.clear();
+        self.seq
+    }
+
+    pub(crate) fn finish(&mut self, request_id: u64, source_paths: Vec<String>) -> bool {
+        if self.active != Some(request_id) {
+            return false;
+        }
+        self.active = None;
+        self.source_paths = source_paths.into_iter().collect();
+        true
+    }
+
+    pub(crate) fn fail(&mut self, request_id: u64) -> bool {
+        if self.active != Some(request_id) {
+            return false;
+        }
+        self.clear();
+        true
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.query = None;
+        self.source_paths.clear();
+        self.active = None;
+    }
+
+    pub(crate) fn is_searching(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Whether `session` matched the content search for the current filter query.
+    pub(crate) fn matches(
+        &self,
+        query: &str,
+        session: &crate::session_manager::SessionMeta,
+    ) -> bool {
+        self.query.as_deref() == Some(query)
+            && session
+                .source_path
+                .as_deref()
+                .is_some_and(|path| self.source_paths.contains(path))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionsState {
     pub provider_id: Option<String>,
@@ -191,6 +252,7 @@ pub struct SessionsState {
     pub delete_seq: u64,
     pub delete_active: HashSet<u64>,
     pub scan_cache: std::collections::HashMap<String, CachedScan>,
+    pub content_search: SessionContentSearch,
 }
 
 impl Default for SessionsState {
@@ -219,6 +281,7 @@ impl Default for SessionsState {
             delete_seq: 0,
             delete_active: HashSet::new(),
             scan_cache: std::collections::HashMap::new(),
+            content_search: SessionContentSearch::default(),
         }
     }
 }
@@ -751,6 +814,14 @@ pub enum Overlay {
         selected: usize,
     },
     McpEnvEntryEditor(McpEnvEntryEditorState),
+    SyncConflictPicker {
+        conflicts: Vec<RecordConflict>,
+        /// Side chosen for each conflict, parallel to `conflicts`.
+        sides: Vec<MergeSide>,
+        /// Resolutions already sent with the request that produced these conflicts.
+        resolved: BTreeMap<String, MergeSide>,
+        selected: usize,
+    },
     Loading {
         kind: LoadingKind,
         title: String,
@@ -816,6 +887,7 @@ impl Overlay {
                 | Overlay::SkillsSyncMethodPicker { .. }
                 | Overlay::McpEnvPicker { .. }
                 | Overlay::McpTypePicker { .. }
+                | Overlay::SyncConflictPicker { .. }
                 | Overlay::SpeedtestResult { .. }
                 | Overlay::StreamCheckResult { .. }
                 | Overlay::UpdateAvailable { .. }
@@ -853,6 +925,7 @@ impl Overlay {
             | Overlay::SkillsSyncMethodPicker { .. }
             | Overlay::McpEnvPicker { .. }
             | Overlay::McpTypePicker { .. }
+            | Overlay::SyncConflictPicker { .. }
             | Overlay::Loading { .. }
             | Overlay::SpeedtestRunning { .. }
             | Overlay::SpeedtestResult { .. }
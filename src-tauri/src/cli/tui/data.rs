@@ -22,6 +22,7 @@ use crate::openclaw_config::{
 use crate::prompt::Prompt;
 use crate::prompt_files::prompt_file_path;
 use crate::provider::Provider;
+use crate::proxy::types::ModelRoutingRule;
 use crate::services::config::BackupInfo;
 use crate::services::{ConfigService, McpService, PromptService, ProviderService, SkillService};
 use crate::store::AppState;
@@ -301,9 +302,22 @@ pub struct ProxySnapshot {
     pub last_error: Option<String>,
     #[allow(dead_code)]
     pub current_app_target: Option<ProxyTargetSnapshot>,
+    /// 全部模型路由规则（按匹配顺序）
+    pub model_routing_rules: Vec<ModelRoutingRule>,
 }
 
 impl ProxySnapshot {
+    pub fn model_routing_rule_count_for(&self, app_type: &AppType) -> usize {
+        self.model_routing_rules
+            .iter()
+            .filter(|rule| {
+                rule.app_type
+                    .as_deref()
+                    .is_none_or(|scope| scope == app_type.as_str())
+            })
+            .count()
+    }
+
     pub fn has_active_worker_for(&self, app_type: &AppType) -> bool {
         self.active_worker_apps
             .contains(&app_type.as_str().to_ascii_lowercase())
@@ -2556,6 +2570,7 @@ pub(crate) async fn load_proxy_snapshot_from_state_async(
         .ok()
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
+    let model_routing_rules = state.db.list_model_routing_rules(None)?;
 
     Ok(ProxySnapshot {
         enabled: config.proxy_enabled,
@@ -2590,6 +2605,7 @@ pub(crate) async fn load_proxy_snapshot_from_state_async(
             .filter(|value| !value.is_empty())
             .map(str::to_string),
         current_app_target,
+        model_routing_rules,
     })
 }
 
@@ -2729,6 +2745,7 @@ mod tests {
                 enabled: false,
                 created_at,
                 updated_at,
+                fragments: Vec::new(),
             },
         }
     }
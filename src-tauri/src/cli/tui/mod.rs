@@ -1186,6 +1186,7 @@ fn cache_invalidation_for_action(action: &Action) -> CacheInvalidation {
         | Action::SetAppType(_)
         | Action::LocalEnvRefresh
         | Action::SessionsRefresh
+        | Action::SessionsContentSearch { .. }
         | Action::SessionMessagesLoad { .. }
         | Action::SessionResume { .. }
         | Action::SessionDelete { .. }
@@ -1237,6 +1238,7 @@ fn cache_invalidation_for_action(action: &Action) -> CacheInvalidation {
         | Action::ConfigRestoreBackup { .. }
         | Action::ConfigReset
         | Action::ConfigWebDavDownload
+        | Action::ConfigWebDavMerge { .. }
         | Action::ConfigWebDavMigrateV1ToV2 => CacheInvalidation::AppStateRecreated,
 
         Action::ProviderSwitch { .. }
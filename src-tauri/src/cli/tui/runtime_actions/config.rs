@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::process::Command;
 
@@ -6,7 +7,7 @@ use crate::cli::i18n::texts;
 use crate::commands::workspace;
 use crate::error::AppError;
 use crate::hermes_config::MemoryKind;
-use crate::services::ConfigService;
+use crate::services::{ConfigService, MergeSide};
 use crate::settings::set_webdav_sync_settings;
 
 use super::super::app::{LoadingKind, Overlay, TextViewState, ToastKind};
@@ -177,6 +178,17 @@ pub(super) fn webdav_download(ctx: &mut RuntimeActionContext<'_>) -> Result<(),
     )
 }
 
+pub(super) fn webdav_merge(
+    ctx: &mut RuntimeActionContext<'_>,
+    resolutions: BTreeMap<String, MergeSide>,
+) -> Result<(), AppError> {
+    queue_webdav_request(
+        ctx,
+        WebDavReqKind::Merge { resolutions },
+        texts::tui_webdav_loading_title_merge().to_string(),
+    )
+}
+
 pub(super) fn webdav_migrate_v1_to_v2(ctx: &mut RuntimeActionContext<'_>) -> Result<(), AppError> {
     queue_webdav_request(
         ctx,
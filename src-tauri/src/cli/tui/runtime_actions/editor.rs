@@ -11,6 +11,7 @@ use crate::openclaw_config::{
     OpenClawEnvConfig, OpenClawToolsConfig,
 };
 use crate::provider::Provider;
+use crate::proxy::types::ModelRoutingRule;
 use crate::services::{McpService, PromptService, ProviderService};
 use crate::settings::{set_webdav_sync_settings, WebDavSyncSettings};
 
@@ -278,9 +279,41 @@ pub(super) fn submit(
         EditorSubmit::ConfigOpenClawTools => submit_openclaw_tools(ctx, content),
         EditorSubmit::ConfigOpenClawAgents => submit_openclaw_agents(ctx, content),
         EditorSubmit::ConfigWebDavSettings => submit_webdav_settings(ctx, content),
+        EditorSubmit::ProxyModelRoutingRules => submit_proxy_model_routing_rules(ctx, content),
     }
 }
 
+fn submit_proxy_model_routing_rules(
+    ctx: &mut RuntimeActionContext<'_>,
+    content: String,
+) -> Result<(), AppError> {
+    let rules: Vec<ModelRoutingRule> = match serde_json::from_str(&content) {
+        Ok(rules) => rules,
+        Err(e) => {
+            ctx.app.push_toast(
+                texts::tui_toast_invalid_json(&e.to_string()),
+                ToastKind::Error,
+            );
+            return Ok(());
+        }
+    };
+
+    let state = load_state()?;
+    if let Err(err) = state.db.replace_model_routing_rules(&rules) {
+        ctx.app.push_toast(err.to_string(), ToastKind::Warning);
+        return Ok(());
+    }
+    crate::cli::commands::proxy_rules::reload_daemon_workers();
+
+    ctx.app.editor = None;
+    ctx.app.push_toast(
+        crate::t!("Model routing rules saved.", "模型路由规则已保存。"),
+        ToastKind::Success,
+    );
+    *ctx.data = UiData::load(&ctx.app.app_type)?;
+    Ok(())
+}
+
 fn submit_hermes_memory(
     ctx: &mut RuntimeActionContext<'_>,
     kind: crate::hermes_config::MemoryKind,
@@ -239,6 +239,29 @@ pub(crate) fn handle_action(
             }
             Ok(())
         }
+        Action::SessionsContentSearch { query } => {
+            let Some(tx) = ctx.session_req_tx else {
+                ctx.app.push_toast(
+                    texts::tui_sessions_toast_worker_unavailable("sessions worker is not running"),
+                    ToastKind::Warning,
+                );
+                return Ok(());
+            };
+            let provider_id = ctx.app.app_type.as_str().to_string();
+            let request_id = ctx.app.sessions.content_search.start(query.clone());
+            if let Err(err) = tx.send(SessionReq::Search {
+                request_id,
+                provider_id,
+                query,
+            }) {
+                ctx.app.sessions.content_search.fail(request_id);
+                ctx.app.push_toast(
+                    texts::tui_sessions_toast_search_failed(&err.to_string()),
+                    ToastKind::Warning,
+                );
+            }
+            Ok(())
+        }
         Action::SessionResume { command, cwd } => {
             let preferred_terminal = crate::settings::get_preferred_terminal();
             let target = session_terminal_target(preferred_terminal.as_deref());
@@ -469,6 +492,7 @@ pub(crate) fn handle_action(
         Action::ConfigWebDavCheckConnection => config::webdav_check_connection(&mut ctx),
         Action::ConfigWebDavUpload => config::webdav_upload(&mut ctx),
         Action::ConfigWebDavDownload => config::webdav_download(&mut ctx),
+        Action::ConfigWebDavMerge { resolutions } => config::webdav_merge(&mut ctx, resolutions),
         Action::ConfigWebDavMigrateV1ToV2 => config::webdav_migrate_v1_to_v2(&mut ctx),
         Action::ConfigWebDavReset => config::webdav_reset(&mut ctx),
         Action::ConfigWebDavJianguoyunQuickSetup { username, password } => {
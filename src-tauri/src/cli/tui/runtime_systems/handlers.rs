@@ -1,6 +1,6 @@
 use crate::cli::i18n::texts;
 use crate::error::AppError;
-use crate::services::SyncDecision;
+use crate::services::{MergeSide, SyncDecision};
 use crate::settings::{
     get_webdav_sync_settings, set_webdav_sync_settings, webdav_jianguoyun_preset,
     WebDavSyncSettings,
@@ -118,6 +118,7 @@ pub(crate) fn handle_session_msg(app: &mut App, msg: SessionMsg) {
                         app.sessions.detail_key.as_deref(),
                         app.sessions.messages_loaded,
                         &app.sessions.messages,
+                        &app.sessions.content_search,
                     )
                     .len();
                     if visible_len == 0 {
@@ -168,6 +169,7 @@ pub(crate) fn handle_session_msg(app: &mut App, msg: SessionMsg) {
                         app.sessions.detail_key.as_deref(),
                         app.sessions.messages_loaded,
                         &app.sessions.messages,
+                        &app.sessions.content_search,
                     )
                     .len();
                     if visible_len == 0 {
@@ -189,6 +191,35 @@ pub(crate) fn handle_session_msg(app: &mut App, msg: SessionMsg) {
                 );
             }
         },
+        SessionMsg::SearchFinished { request_id, result } => match result {
+            Ok(source_paths) => {
+                if app.sessions.content_search.finish(request_id, source_paths) {
+                    let visible_len = crate::cli::tui::app::visible_sessions_for_state(
+                        &app.filter,
+                        &app.app_type,
+                        &app.sessions.rows,
+                        app.sessions.detail_key.as_deref(),
+                        app.sessions.messages_loaded,
+                        &app.sessions.messages,
+                        &app.sessions.content_search,
+                    )
+                    .len();
+                    app.sessions.selected_idx = 0;
+                    app.push_toast(
+                        texts::tui_sessions_toast_search_finished(visible_len),
+                        ToastKind::Info,
+                    );
+                }
+            }
+            Err(error) => {
+                if app.sessions.content_search.fail(request_id) {
+                    app.push_toast(
+                        texts::tui_sessions_toast_search_failed(&error),
+                        ToastKind::Warning,
+                    );
+                }
+            }
+        },
     }
 }
 
@@ -562,6 +593,7 @@ pub(crate) fn handle_webdav_msg(
 
                 let done_invalidation = match &done {
                     WebDavDone::Downloaded { decision, .. }
+                    | WebDavDone::Merged { decision, .. }
                         if !matches!(decision, SyncDecision::V1MigrationNeeded) =>
                     {
                         CacheInvalidation::AppStateRecreated
@@ -609,6 +641,41 @@ pub(crate) fn handle_webdav_msg(
                             }
                         }
                     }
+                    WebDavDone::Merged { decision, message } => match decision {
+                        SyncDecision::V1MigrationNeeded => {
+                            app.overlay = Overlay::Confirm(ConfirmOverlay {
+                                title: texts::tui_webdav_v1_migration_title().to_string(),
+                                message: texts::tui_webdav_v1_migration_message().to_string(),
+                                action: ConfirmAction::WebDavMigrateV1ToV2,
+                            });
+                        }
+                        _ => {
+                            if let Ok(state) = load_state() {
+                                if let Err(e) =
+                                    crate::services::provider::ProviderService::sync_current_to_live(
+                                        &state,
+                                    )
+                                {
+                                    log::warn!("WebDAV 合并同步后同步 live 配置失败: {e}");
+                                }
+                            }
+                            app.push_toast(
+                                texts::tui_toast_webdav_merge_ok(&message),
+                                ToastKind::Success,
+                            );
+                        }
+                    },
+                    WebDavDone::MergeConflicts {
+                        conflicts,
+                        resolutions,
+                    } => {
+                        app.overlay = Overlay::SyncConflictPicker {
+                            sides: vec![MergeSide::Local; conflicts.len()],
+                            conflicts,
+                            resolved: resolutions,
+                            selected: 0,
+                        };
+                    }
                     WebDavDone::V1Migrated { message: _ } => {
                         if let Ok(state) = load_state() {
                             if let Err(e) =
@@ -682,6 +749,17 @@ pub(crate) fn handle_webdav_msg(
                             &detail,
                         )
                     }
+                    WebDavReqKind::Merge { .. } => {
+                        let detail = match err {
+                            WebDavErr::Generic(e)
+                            | WebDavErr::QuickSetupSave(e)
+                            | WebDavErr::QuickSetupCheck(e) => e,
+                        };
+                        texts::tui_toast_webdav_action_failed(
+                            texts::tui_webdav_loading_title_merge(),
+                            &detail,
+                        )
+                    }
                     WebDavReqKind::MigrateV1ToV2 => {
                         let detail = match err {
                             WebDavErr::Generic(e)
@@ -778,7 +856,7 @@ pub(crate) fn handle_proxy_msg(
 
             match result {
                 Ok(proxy) => {
-                    data.proxy = proxy;
+                    data.proxy = *proxy;
                     app.observe_proxy_token_activity(
                         data.proxy.estimated_input_tokens_total,
                         data.proxy.estimated_output_tokens_total,
@@ -1065,6 +1143,7 @@ mod tests {
             role: "assistant".to_string(),
             content: "stale detail".to_string(),
             ts: None,
+            parts: Vec::new(),
         }];
         let request_id = app.sessions.start_scan("claude".to_string());
 
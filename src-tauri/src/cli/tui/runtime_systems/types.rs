@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::time::Duration;
@@ -10,7 +10,9 @@ use crate::cli::i18n::texts;
 use crate::cli::tui::data::ProxySnapshot;
 use crate::cli::tui::data::QuotaTarget;
 use crate::provider::Provider;
-use crate::services::{EndpointLatency, HealthStatus, StreamCheckResult, SyncDecision};
+use crate::services::{
+    EndpointLatency, HealthStatus, MergeSide, RecordConflict, StreamCheckResult, SyncDecision,
+};
 
 use super::super::form::ProviderAddField;
 
@@ -82,6 +84,11 @@ pub(crate) enum SessionReq {
         session_id: String,
         source_path: String,
     },
+    Search {
+        request_id: u64,
+        provider_id: String,
+        query: String,
+    },
 }
 
 pub(crate) enum SessionMsg {
@@ -99,6 +106,11 @@ pub(crate) enum SessionMsg {
         key: String,
         result: Result<(), String>,
     },
+    /// Source paths of the sessions whose messages matched the query.
+    SearchFinished {
+        request_id: u64,
+        result: Result<Vec<String>, String>,
+    },
 }
 
 pub(crate) enum QuotaReq {
@@ -227,8 +239,14 @@ pub(crate) enum WebDavReqKind {
     CheckConnection,
     Upload,
     Download,
+    Merge {
+        resolutions: BTreeMap<String, MergeSide>,
+    },
     MigrateV1ToV2,
-    JianguoyunQuickSetup { username: String, password: String },
+    JianguoyunQuickSetup {
+        username: String,
+        password: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -248,6 +266,14 @@ pub(crate) enum WebDavDone {
         decision: SyncDecision,
         message: String,
     },
+    Merged {
+        decision: SyncDecision,
+        message: String,
+    },
+    MergeConflicts {
+        conflicts: Vec<RecordConflict>,
+        resolutions: BTreeMap<String, MergeSide>,
+    },
     #[allow(dead_code)]
     V1Migrated {
         message: String,
@@ -391,7 +417,7 @@ pub(crate) enum ProxyMsg {
     SnapshotRefreshed {
         request_id: u64,
         app_type: AppType,
-        result: Result<ProxySnapshot, String>,
+        result: Result<Box<ProxySnapshot>, String>,
     },
 }
 
@@ -8,7 +8,9 @@ use std::sync::{
 use crate::app_config::AppType;
 use crate::cli::i18n::texts;
 use crate::error::AppError;
-use crate::services::{SkillService, StreamCheckService, WebDavSyncService};
+use crate::services::{
+    ConflictResolutions, MergeOutcome, SkillService, StreamCheckService, WebDavSyncService,
+};
 use crate::settings::{set_webdav_sync_settings, webdav_jianguoyun_preset};
 
 use super::super::data::{
@@ -111,6 +113,7 @@ fn proxy_worker_loop(rx: mpsc::Receiver<ProxyReq>, tx: mpsc::Sender<ProxyMsg>) {
             } => {
                 let result = load_state().map_err(|e| e.to_string()).and_then(|state| {
                     rt.block_on(load_proxy_snapshot_from_state_async(&state, &app_type))
+                        .map(Box::new)
                         .map_err(|e| e.to_string())
                 });
 
@@ -254,6 +257,23 @@ fn webdav_worker_loop(rx: mpsc::Receiver<WebDavReq>, tx: mpsc::Sender<WebDavMsg>
                     message: summary.message,
                 })
                 .map_err(|e| WebDavErr::Generic(e.to_string())),
+            WebDavReqKind::Merge { resolutions } => {
+                WebDavSyncService::merge(&ConflictResolutions {
+                    per_record: resolutions.clone(),
+                    fallback: None,
+                })
+                .map(|outcome| match outcome {
+                    MergeOutcome::Synced(summary) => WebDavDone::Merged {
+                        decision: summary.decision,
+                        message: summary.message,
+                    },
+                    MergeOutcome::Conflicts(conflicts) => WebDavDone::MergeConflicts {
+                        conflicts,
+                        resolutions,
+                    },
+                })
+                .map_err(|e| WebDavErr::Generic(e.to_string()))
+            }
             WebDavReqKind::MigrateV1ToV2 => WebDavSyncService::migrate_v1_to_v2()
                 .map(|summary| WebDavDone::V1Migrated {
                     message: summary.message,
@@ -345,7 +365,7 @@ fn stream_check_worker_loop(rx: mpsc::Receiver<StreamCheckReq>, tx: mpsc::Sender
 
         let result = rt
             .block_on(async {
-                StreamCheckService::check_with_retry(&req.app_type, &req.provider, &config).await
+                StreamCheckService::check_provider(&db, &req.app_type, &req.provider, &config).await
             })
             .map_err(|err| err.to_string());
 
@@ -477,10 +497,14 @@ fn model_fetch_worker_loop(rx: mpsc::Receiver<ModelFetchReq>, tx: mpsc::Sender<M
             })
         } else {
             let strategy = model_fetch_strategy_for_field(field);
-            rt.block_on(async {
-                fetch_provider_models_for_tui(&base_url, api_key.as_deref(), strategy).await
-            })
-            .map_err(|e| e.to_string())
+            match reveal_model_fetch_key(api_key) {
+                Ok(api_key) => rt
+                    .block_on(async {
+                        fetch_provider_models_for_tui(&base_url, api_key.as_deref(), strategy).await
+                    })
+                    .map_err(|e| e.to_string()),
+                Err(err) => Err(err.to_string()),
+            }
         };
 
         let _ = tx.send(ModelFetchMsg::Finished {
@@ -492,6 +516,17 @@ fn model_fetch_worker_loop(rx: mpsc::Receiver<ModelFetchReq>, tx: mpsc::Sender<M
     }
 }
 
+/// 编辑已加密的供应商时表单中是保险库引用，请求上游前先解密
+fn reveal_model_fetch_key(api_key: Option<String>) -> Result<Option<String>, AppError> {
+    match api_key {
+        Some(key) if crate::secret_vault::vault_ref_id(&key).is_some() => {
+            let db = crate::Database::init()?;
+            crate::secret_vault::reveal_value(&db, &key).map(Some)
+        }
+        other => Ok(other),
+    }
+}
+
 pub(crate) fn start_managed_auth_system() -> Result<ManagedAuthSystem, AppError> {
     let (result_tx, result_rx) = mpsc::channel::<ManagedAuthMsg>();
     let (req_tx, req_rx) = mpsc::channel::<ManagedAuthReq>();
@@ -668,6 +703,7 @@ fn session_worker_loop(rx: mpsc::Receiver<SessionReq>, tx: mpsc::Sender<SessionM
             match (&req, &next) {
                 (SessionReq::Refresh { .. }, SessionReq::Refresh { .. }) => req = next,
                 (SessionReq::LoadMessages { .. }, SessionReq::LoadMessages { .. }) => req = next,
+                (SessionReq::Search { .. }, SessionReq::Search { .. }) => req = next,
                 _ => {
                     let _ = handle_session_req(req, &tx);
                     req = next;
@@ -729,9 +765,32 @@ fn handle_session_req(req: SessionReq, tx: &mpsc::Sender<SessionMsg>) -> Result<
             })
             .map_err(|_| ())
         }
+        SessionReq::Search {
+            request_id,
+            provider_id,
+            query,
+        } => {
+            let result = search_session_contents(&provider_id, &query);
+            tx.send(SessionMsg::SearchFinished { request_id, result })
+                .map_err(|_| ())
+        }
     }
 }
 
+fn search_session_contents(provider_id: &str, query: &str) -> Result<Vec<String>, String> {
+    use crate::services::session_search::{self, SessionSearchQuery};
+
+    let db = crate::database::Database::init().map_err(|e| e.to_string())?;
+    let mut search = SessionSearchQuery::new(query);
+    search.provider_id = Some(provider_id.to_string());
+    search.limit = usize::MAX;
+    let hits = session_search::search_sessions(&db, &search, true).map_err(|e| e.to_string())?;
+    Ok(hits
+        .into_iter()
+        .filter_map(|hit| hit.session.source_path)
+        .collect())
+}
+
 #[cfg(test)]
 pub(crate) fn drain_session_reqs_for_test(
     mut req: SessionReq,
@@ -741,7 +800,8 @@ pub(crate) fn drain_session_reqs_for_test(
     for next in rx.try_iter() {
         match (&req, &next) {
             (SessionReq::Refresh { .. }, SessionReq::Refresh { .. })
-            | (SessionReq::LoadMessages { .. }, SessionReq::LoadMessages { .. }) => {
+            | (SessionReq::LoadMessages { .. }, SessionReq::LoadMessages { .. })
+            | (SessionReq::Search { .. }, SessionReq::Search { .. }) => {
                 req = next;
             }
             _ => {
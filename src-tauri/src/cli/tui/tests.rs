@@ -2032,7 +2032,7 @@ fn proxy_snapshot_result_updates_proxy_without_cache_invalidation() {
         ProxyMsg::SnapshotRefreshed {
             request_id: 1,
             app_type: AppType::Claude,
-            result: Ok(proxy),
+            result: Ok(Box::new(proxy)),
         },
     )
     .expect("snapshot result should be handled");
@@ -2064,10 +2064,10 @@ fn proxy_snapshot_result_ignores_stale_or_wrong_app() {
         ProxyMsg::SnapshotRefreshed {
             request_id: 1,
             app_type: AppType::Claude,
-            result: Ok(data::ProxySnapshot {
+            result: Ok(Box::new(data::ProxySnapshot {
                 running: true,
                 ..data::ProxySnapshot::default()
-            }),
+            })),
         },
     )
     .expect("stale snapshot result should be ignored");
@@ -2082,10 +2082,10 @@ fn proxy_snapshot_result_ignores_stale_or_wrong_app() {
         ProxyMsg::SnapshotRefreshed {
             request_id: 2,
             app_type: AppType::Codex,
-            result: Ok(data::ProxySnapshot {
+            result: Ok(Box::new(data::ProxySnapshot {
                 running: true,
                 ..data::ProxySnapshot::default()
-            }),
+            })),
         },
     )
     .expect("wrong-app snapshot result should be ignored");
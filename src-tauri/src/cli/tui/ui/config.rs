@@ -24,6 +24,9 @@ pub(super) fn local_proxy_settings_item_label(item: &LocalProxySettingsItem) ->
         LocalProxySettingsItem::ListenAddress => texts::tui_settings_proxy_listen_address_label(),
         LocalProxySettingsItem::ListenPort => texts::tui_settings_proxy_listen_port_label(),
         LocalProxySettingsItem::AutoFailover => crate::t!("Automatic failover", "自动故障转移"),
+        LocalProxySettingsItem::ModelRoutingRules => {
+            crate::t!("Model routing rules", "模型路由规则")
+        }
     }
 }
 
@@ -3143,6 +3146,12 @@ pub(super) fn render_settings_proxy(
                     texts::disabled().to_string()
                 },
             ),
+            LocalProxySettingsItem::ModelRoutingRules => (
+                local_proxy_settings_item_label(item).to_string(),
+                data.proxy
+                    .model_routing_rule_count_for(&app.app_type)
+                    .to_string(),
+            ),
         })
         .collect::<Vec<_>>();
 
@@ -229,7 +229,7 @@ pub(super) fn render_main(
         Style::default().fg(theme.surface)
     };
 
-    let webdav_lines = vec![
+    let mut webdav_lines = vec![
         kv_line(
             theme,
             texts::tui_label_webdav_status(),
@@ -249,6 +249,19 @@ pub(super) fn render_main(
             )],
         ),
     ];
+    if webdav_enabled && webdav.is_some_and(|cfg| cfg.auto_sync) {
+        webdav_lines.push(kv_line(
+            theme,
+            texts::tui_label_webdav_auto_sync(),
+            label_width,
+            vec![Span::styled(
+                texts::tui_webdav_auto_sync_every(
+                    crate::settings::effective_sync_interval_minutes(),
+                ),
+                value_style,
+            )],
+        ));
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -271,7 +284,7 @@ pub(super) fn render_main(
         .constraints([
             Constraint::Length(1),
             Constraint::Length(connection_card_height),
-            Constraint::Length(4),
+            Constraint::Length(webdav_lines.len() as u16 + 2),
             Constraint::Length(8),
             Constraint::Min(0),
         ])
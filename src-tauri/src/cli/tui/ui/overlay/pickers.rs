@@ -1170,6 +1170,73 @@ pub(super) fn render_mcp_apps_picker_overlay(
     );
 }
 
+pub(super) fn render_sync_conflict_picker_overlay(
+    frame: &mut Frame<'_>,
+    content_area: Rect,
+    theme: &theme::Theme,
+    conflicts: &[crate::services::RecordConflict],
+    sides: &[crate::services::MergeSide],
+    selected: usize,
+) {
+    let area = centered_rect(OVERLAY_MD.0, OVERLAY_MD.1, content_area);
+    frame.render_widget(Clear, area);
+
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Plain)
+        .border_style(overlay_border_style(theme, false))
+        .title(texts::tui_sync_conflicts_title(conflicts.len()));
+    frame.render_widget(outer.clone(), area);
+    let inner = outer.inner(area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    render_key_bar_center(
+        frame,
+        chunks[0],
+        theme,
+        &[
+            ("↑↓", texts::tui_key_select()),
+            ("←→", texts::tui_key_toggle()),
+            ("Enter", texts::tui_key_apply()),
+            ("Esc", texts::tui_key_cancel()),
+        ],
+    );
+
+    let body_area = inset_top(chunks[1], 1);
+    let items = conflicts.iter().zip(sides.iter()).map(|(conflict, side)| {
+        let take_remote = matches!(side, crate::services::MergeSide::Remote);
+        let detail = if conflict.deleted_locally {
+            texts::tui_sync_conflict_deleted_locally().to_string()
+        } else if conflict.deleted_remotely {
+            texts::tui_sync_conflict_deleted_remotely().to_string()
+        } else {
+            conflict.fields.join(", ")
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(
+                format!("[{}] ", texts::tui_sync_conflict_side(take_remote)),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(conflict.label.clone()),
+            Span::styled(format!("  {detail}"), Style::default().fg(theme.dim)),
+        ]))
+    });
+
+    let list = List::new(items)
+        .highlight_style(selection_style(theme))
+        .highlight_symbol(highlight_symbol(theme));
+
+    let mut state = ListState::default();
+    state.select(Some(selected.min(conflicts.len().saturating_sub(1))));
+    frame.render_stateful_widget(list, body_area, &mut state);
+}
+
 pub(super) fn render_mcp_type_picker_overlay(
     frame: &mut Frame<'_>,
     content_area: Rect,
@@ -173,6 +173,19 @@ pub(crate) fn render_overlay(
             *selected,
             apps,
         ),
+        Overlay::SyncConflictPicker {
+            conflicts,
+            sides,
+            selected,
+            ..
+        } => super::pickers::render_sync_conflict_picker_overlay(
+            frame,
+            content_area,
+            theme,
+            conflicts,
+            sides,
+            *selected,
+        ),
         Overlay::McpTypePicker { selected } => {
             super::pickers::render_mcp_type_picker_overlay(frame, content_area, theme, *selected)
         }
@@ -16,6 +16,7 @@ pub(super) fn render_sessions(
         app.sessions.detail_key.as_deref(),
         app.sessions.messages_loaded,
         &app.sessions.messages,
+        &app.sessions.content_search,
     );
 
     let outer = Block::default()
@@ -53,6 +54,8 @@ pub(super) fn render_sessions(
 
     let summary = if app.sessions.loading && !app.sessions.loaded_once {
         texts::tui_sessions_loading_summary().to_string()
+    } else if app.sessions.content_search.is_searching() {
+        texts::tui_sessions_searching_summary().to_string()
     } else {
         texts::tui_sessions_summary(app.sessions.rows.len(), visible.len())
     };
@@ -661,6 +661,7 @@ fn tui_sessions_renders_split_detail_and_message_preview() {
             role: "user".to_string(),
             content: "Please review this module".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         });
 
     let all = all_text(&render_with_size(
@@ -804,16 +805,19 @@ fn tui_sessions_slash_search_filters_user_role_messages() {
             role: "user".to_string(),
             content: "How do I deploy this service?".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         },
         crate::session_manager::SessionMessage {
             role: "assistant".to_string(),
             content: "The user should use the release workflow.".to_string(),
             ts: Some(1_735_689_901_000),
+            parts: Vec::new(),
         },
         crate::session_manager::SessionMessage {
             role: "tool".to_string(),
             content: "cargo test".to_string(),
             ts: Some(1_735_689_902_000),
+            parts: Vec::new(),
         },
     ];
 
@@ -864,11 +868,13 @@ fn tui_sessions_search_filters_loaded_message_content() {
             role: "user".to_string(),
             content: "How do I deploy this service?".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         },
         crate::session_manager::SessionMessage {
             role: "assistant".to_string(),
             content: "Use the release workflow.".to_string(),
             ts: Some(1_735_689_901_000),
+            parts: Vec::new(),
         },
     ];
     app.sessions.message_filter.set("deploy");
@@ -910,11 +916,13 @@ fn tui_sessions_search_matches_localized_user_role_label() {
             role: "user".to_string(),
             content: "How do I deploy this service?".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         },
         crate::session_manager::SessionMessage {
             role: "assistant".to_string(),
             content: "提醒用户使用发布流程。".to_string(),
             ts: Some(1_735_689_901_000),
+            parts: Vec::new(),
         },
     ];
     app.sessions.message_filter.set("用户");
@@ -968,11 +976,13 @@ fn tui_sessions_session_and_message_filters_are_independent() {
             role: "user".to_string(),
             content: "How do I inspect the deployment?".to_string(),
             ts: Some(1_735_689_900_000),
+            parts: Vec::new(),
         },
         crate::session_manager::SessionMessage {
             role: "assistant".to_string(),
             content: "AI response for the user.".to_string(),
             ts: Some(1_735_689_901_000),
+            parts: Vec::new(),
         },
     ];
     app.filter.input.set("AI");
@@ -4372,6 +4382,7 @@ fn prompts_page_uses_space_toggle_and_add_key() {
             enabled: true,
             created_at: None,
             updated_at: None,
+            fragments: Vec::new(),
         },
     });
 
@@ -0,0 +1,258 @@
+//! Schedule for the daemon's background sync.
+//!
+//! When the active sync backend has `autoSync` enabled, the daemon runs a
+//! record-level merge against it:
+//!  - once shortly after startup (or after auto sync is switched on),
+//!  - every `syncIntervalMinutes` (30 minutes by default),
+//!  - after local changes, once the local fingerprint has stayed the same for
+//!    `CHANGE_SETTLE` so a burst of edits produces a single sync.
+//!
+//! Failed runs back off exponentially (1 min, 2 min, 4 min, ...) capped at
+//! the interval, instead of hammering an unreachable server. Runs that need
+//! the user (conflicting edits, v1 remote data) are not retried until the
+//! next scheduled run or local change.
+//!
+//! Like the restart policy, the state machine is pure: it takes a "now"
+//! instant from the caller so tests never sleep.
+
+use std::time::{Duration, Instant};
+
+/// How often the daemon re-reads settings and checks for local changes.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const STARTUP_DELAY: Duration = Duration::from_secs(10);
+const CHANGE_SETTLE: Duration = Duration::from_secs(30);
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncReason {
+    Startup,
+    Scheduled,
+    LocalChange,
+    Retry,
+}
+
+impl SyncReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncReason::Startup => "startup",
+            SyncReason::Scheduled => "scheduled",
+            SyncReason::LocalChange => "local change",
+            SyncReason::Retry => "retry",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AutoSyncSchedule {
+    started_at: Instant,
+    last_attempt: Option<Instant>,
+    /// Local fingerprint right after the last run; a different value means
+    /// there are local edits the remote has not seen.
+    synced_fingerprint: Option<String>,
+    pending_change: Option<(String, Instant)>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl AutoSyncSchedule {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started_at: now,
+            last_attempt: None,
+            synced_fingerprint: None,
+            pending_change: None,
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    /// Called on every poll with the current local fingerprint (`None` when
+    /// it could not be computed). Returns why a sync should run now, if it
+    /// should.
+    pub fn due(
+        &mut self,
+        now: Instant,
+        interval: Duration,
+        fingerprint: Option<&str>,
+    ) -> Option<SyncReason> {
+        if let Some(retry_at) = self.retry_at {
+            return (now >= retry_at).then_some(SyncReason::Retry);
+        }
+
+        let Some(last_attempt) = self.last_attempt else {
+            return (now.saturating_duration_since(self.started_at) >= STARTUP_DELAY)
+                .then_some(SyncReason::Startup);
+        };
+
+        if let Some(fingerprint) = fingerprint {
+            if self.synced_fingerprint.as_deref() == Some(fingerprint) {
+                self.pending_change = None;
+            } else {
+                match &self.pending_change {
+                    Some((pending, seen_at)) if pending == fingerprint => {
+                        if now.saturating_duration_since(*seen_at) >= CHANGE_SETTLE {
+                            return Some(SyncReason::LocalChange);
+                        }
+                    }
+                    _ => self.pending_change = Some((fingerprint.to_string(), now)),
+                }
+            }
+        }
+
+        (now.saturating_duration_since(last_attempt) >= interval).then_some(SyncReason::Scheduled)
+    }
+
+    /// `fingerprint` is the local fingerprint after the run, so changes
+    /// pulled from the remote do not count as local edits.
+    pub fn on_success(&mut self, now: Instant, fingerprint: Option<String>) {
+        self.record_attempt(now, fingerprint);
+        self.failures = 0;
+        self.retry_at = None;
+    }
+
+    /// Records a failed run and returns the delay before the retry.
+    pub fn on_failure(
+        &mut self,
+        now: Instant,
+        interval: Duration,
+        fingerprint: Option<String>,
+    ) -> Duration {
+        self.record_attempt(now, fingerprint);
+        let delay = retry_delay(self.failures, interval);
+        self.failures = self.failures.saturating_add(1);
+        self.retry_at = Some(now + delay);
+        delay
+    }
+
+    /// Records a run that cannot finish without the user.
+    pub fn on_blocked(&mut self, now: Instant, fingerprint: Option<String>) {
+        self.on_success(now, fingerprint);
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.failures
+    }
+
+    fn record_attempt(&mut self, now: Instant, fingerprint: Option<String>) {
+        self.last_attempt = Some(now);
+        self.synced_fingerprint = fingerprint;
+        self.pending_change = None;
+    }
+}
+
+fn retry_delay(failures: u32, interval: Duration) -> Duration {
+    let factor = 1u32.checked_shl(failures).unwrap_or(u32::MAX);
+    RETRY_BASE_DELAY
+        .saturating_mul(factor)
+        .min(interval.max(RETRY_BASE_DELAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+    fn at(base: Instant, secs: u64) -> Instant {
+        base + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn first_sync_waits_for_startup_delay() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+        assert_eq!(schedule.due(at(base, 1), INTERVAL, Some("a")), None);
+        assert_eq!(
+            schedule.due(at(base, 10), INTERVAL, Some("a")),
+            Some(SyncReason::Startup)
+        );
+    }
+
+    #[test]
+    fn runs_again_after_interval() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+        schedule.on_success(at(base, 10), Some("a".to_string()));
+
+        assert_eq!(schedule.due(at(base, 600), INTERVAL, Some("a")), None);
+        assert_eq!(
+            schedule.due(at(base, 10 + 1800), INTERVAL, Some("a")),
+            Some(SyncReason::Scheduled)
+        );
+    }
+
+    #[test]
+    fn local_change_syncs_once_it_settles() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+        schedule.on_success(at(base, 10), Some("a".to_string()));
+
+        assert_eq!(schedule.due(at(base, 20), INTERVAL, Some("b")), None);
+        // Still editing: the fingerprint moved again, so the clock restarts.
+        assert_eq!(schedule.due(at(base, 40), INTERVAL, Some("c")), None);
+        assert_eq!(schedule.due(at(base, 60), INTERVAL, Some("c")), None);
+        assert_eq!(
+            schedule.due(at(base, 70), INTERVAL, Some("c")),
+            Some(SyncReason::LocalChange)
+        );
+
+        schedule.on_success(at(base, 71), Some("c".to_string()));
+        assert_eq!(schedule.due(at(base, 200), INTERVAL, Some("c")), None);
+    }
+
+    #[test]
+    fn reverted_change_does_not_sync() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+        schedule.on_success(at(base, 10), Some("a".to_string()));
+
+        assert_eq!(schedule.due(at(base, 20), INTERVAL, Some("b")), None);
+        assert_eq!(schedule.due(at(base, 35), INTERVAL, Some("a")), None);
+        assert_eq!(schedule.due(at(base, 100), INTERVAL, Some("a")), None);
+    }
+
+    #[test]
+    fn failures_back_off_exponentially_up_to_the_interval() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+
+        let mut now = at(base, 10);
+        let mut delays = Vec::new();
+        for _ in 0..7 {
+            let delay = schedule.on_failure(now, INTERVAL, Some("a".to_string()));
+            assert_eq!(
+                schedule.due(now + delay - Duration::from_secs(1), INTERVAL, Some("b")),
+                None
+            );
+            assert_eq!(
+                schedule.due(now + delay, INTERVAL, Some("a")),
+                Some(SyncReason::Retry)
+            );
+            delays.push(delay.as_secs() / 60);
+            now += delay;
+        }
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(schedule.consecutive_failures(), 7);
+
+        schedule.on_success(now, Some("a".to_string()));
+        assert_eq!(schedule.consecutive_failures(), 0);
+        assert_eq!(
+            schedule.due(now + Duration::from_secs(60), INTERVAL, Some("a")),
+            None
+        );
+    }
+
+    #[test]
+    fn blocked_run_waits_for_next_change_or_interval() {
+        let base = Instant::now();
+        let mut schedule = AutoSyncSchedule::new(base);
+        schedule.on_blocked(at(base, 10), Some("a".to_string()));
+
+        assert_eq!(schedule.due(at(base, 100), INTERVAL, Some("a")), None);
+        assert_eq!(schedule.due(at(base, 110), INTERVAL, Some("b")), None);
+        assert_eq!(
+            schedule.due(at(base, 140), INTERVAL, Some("b")),
+            Some(SyncReason::LocalChange)
+        );
+    }
+}
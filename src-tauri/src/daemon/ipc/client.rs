@@ -1,6 +1,7 @@
 //! Synchronous client used by the foreground TUI/CLI to talk to the daemon.
 //!
-//! - One TCP-style request/response per connection.
+//! - One TCP-style request/response per connection, except `subscribe`,
+//!   which keeps reading event lines until the caller stops.
 //! - Auto-spawns the daemon (`cc-switch daemon start --detach`) on
 //!   `ECONNREFUSED` / missing socket; subsequent retries wait for the socket
 //!   to appear.
@@ -11,7 +12,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
-use super::protocol::{encode_request, Request, Response};
+use super::protocol::{encode_request, DaemonEvent, Request, Response};
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 const READ_TIMEOUT: Duration = Duration::from_secs(15);
@@ -120,6 +121,60 @@ pub fn round_trip(socket_path: &Path, request: &Request) -> Result<Response, Cli
     exchange(&mut stream, request)
 }
 
+/// Subscribe to the daemon event stream and call `on_event(at, event)` for
+/// each event. Returns when the daemon closes the stream or `on_event`
+/// returns `false`. There is no read timeout — events can be minutes apart.
+pub fn subscribe<F>(
+    socket_path: &Path,
+    app_type: Option<String>,
+    mut on_event: F,
+) -> Result<(), ClientError>
+where
+    F: FnMut(String, DaemonEvent) -> bool,
+{
+    let mut stream = connect(socket_path)?;
+    let payload = encode_request(&Request::Subscribe { app_type })
+        .map_err(|err| ClientError::Protocol(format!("encode request: {err}")))?;
+    stream
+        .write_all(payload.as_bytes())
+        .map_err(ClientError::Io)?;
+    stream.write_all(b"\n").map_err(ClientError::Io)?;
+    stream.flush().map_err(ClientError::Io)?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut acknowledged = false;
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line).map_err(ClientError::Io)?;
+        if n == 0 {
+            return if acknowledged {
+                Ok(())
+            } else {
+                Err(ClientError::Protocol(
+                    "daemon closed connection without response".into(),
+                ))
+            };
+        }
+        let response: Response = serde_json::from_str(line.trim())
+            .map_err(|err| ClientError::Protocol(format!("decode response: {err}")))?;
+        match response {
+            Response::Ok if !acknowledged => acknowledged = true,
+            Response::Event { at, event } if acknowledged => {
+                if !on_event(at, event) {
+                    return Ok(());
+                }
+            }
+            Response::Error { message } => return Err(ClientError::Protocol(message)),
+            other => {
+                return Err(ClientError::Protocol(format!(
+                    "unexpected response on event stream: {other:?}"
+                )));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +219,7 @@ mod tests {
             restart_count: 0,
             last_restart_at: None,
             workers: vec![],
+            auto_sync: None,
         };
         let server = spawn_test_server(sock.clone(), stub.clone());
 
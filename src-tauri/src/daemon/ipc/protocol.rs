@@ -1,11 +1,16 @@
 //! Wire protocol for the daemon control socket.
 //!
-//! Framing: one JSON object per line (newline-delimited). Each connection is
+//! Framing: one JSON object per line (newline-delimited). Most connections are
 //! request/response style — the client writes one Request line, the server
-//! writes one Response line, and either side may close.
+//! writes one Response line, and either side may close. Two requests turn the
+//! connection into a stream after the `Ok` reply: `Subscribe` (server keeps
+//! writing `Event` lines) and `WorkerEvents` (worker keeps writing
+//! `DaemonEvent` lines).
 
 use serde::{Deserialize, Serialize};
 
+use crate::proxy::events::{ProxyEvent, RequestSummary};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Request {
@@ -36,6 +41,51 @@ pub enum Request {
     /// daemon writes the desired switch only; app routes start through
     /// `EnsureWorker`.
     SetGlobalEnabled { enabled: bool },
+    /// Foreground asks for a live event feed. The daemon replies `Ok`, then
+    /// writes one `Event` line per event until the client disconnects. With
+    /// `app_type` set, proxy events for other apps are filtered out.
+    Subscribe {
+        #[serde(default)]
+        app_type: Option<String>,
+    },
+    /// Worker → daemon, opened after a successful `WorkerHello`. The daemon
+    /// replies `Ok`, then reads one `DaemonEvent` line per proxy event and
+    /// fans them out to subscribers.
+    WorkerEvents { session_token: String },
+    /// Foreground asks the daemon to make `provider_id` the current provider
+    /// for the app, including the live-config rewrite.
+    SwitchProvider {
+        app_type: String,
+        provider_id: String,
+    },
+    /// Foreground appends a provider to the app's failover queue. The daemon
+    /// applies queue edits one at a time against the persisted queue, so
+    /// concurrent edits from several terminals all take effect.
+    AddToFailoverQueue {
+        app_type: String,
+        provider_id: String,
+    },
+    /// Foreground takes a provider out of the app's failover queue.
+    RemoveFromFailoverQueue {
+        app_type: String,
+        provider_id: String,
+    },
+    /// Foreground moves a queued provider to `position` (0-based, clamped to
+    /// the end of the queue).
+    MoveInFailoverQueue {
+        app_type: String,
+        provider_id: String,
+        position: usize,
+    },
+    /// Foreground asks the daemon to close a provider's circuit breaker and
+    /// clear its persisted health.
+    ResetCircuitBreaker {
+        app_type: String,
+        provider_id: String,
+    },
+    /// Foreground asks the daemon and its workers to re-read settings and
+    /// proxy configuration from disk.
+    ReloadConfig,
     /// Force the daemon to stop the worker (if any) and exit.
     Shutdown,
 }
@@ -62,10 +112,176 @@ pub enum Response {
         last_restart_at: Option<String>,
         #[serde(default)]
         workers: Vec<WorkerState>,
+        #[serde(default)]
+        auto_sync: Option<AutoSyncState>,
     },
     Error {
         message: String,
     },
+    ProviderSwitched {
+        app_type: String,
+        provider_id: String,
+        #[serde(default)]
+        previous_provider_id: Option<String>,
+    },
+    FailoverQueue {
+        app_type: String,
+        provider_ids: Vec<String>,
+    },
+    CircuitReset {
+        app_type: String,
+        provider_id: String,
+        /// Whether a running worker for the app applied the reset.
+        worker_reset: bool,
+    },
+    Reloaded {
+        /// Apps whose running worker picked up the new configuration.
+        workers: Vec<String>,
+    },
+    /// One streamed event on a `Subscribe` connection.
+    Event {
+        at: String,
+        event: DaemonEvent,
+    },
+}
+
+impl Response {
+    /// Wrap an event for the subscriber stream, stamped with the current time.
+    pub fn event(event: DaemonEvent) -> Self {
+        Self::Event {
+            at: chrono::Utc::now().to_rfc3339(),
+            event,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonEvent {
+    WorkerStarted {
+        app_type: String,
+        pid: u32,
+        address: String,
+        port: u16,
+    },
+    WorkerStopped {
+        app_type: String,
+        pid: u32,
+        /// False when the daemon asked the worker to stop.
+        crashed: bool,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    WorkerRestarting {
+        app_type: String,
+        attempt: u32,
+        delay_ms: u64,
+    },
+    ProviderSwitched {
+        app_type: String,
+        #[serde(default)]
+        from_provider_id: Option<String>,
+        provider_id: String,
+        provider_name: String,
+    },
+    FailoverHop {
+        app_type: String,
+        from_provider_id: String,
+        provider_id: String,
+        provider_name: String,
+    },
+    CircuitChanged {
+        app_type: String,
+        provider_id: String,
+        from: String,
+        to: String,
+    },
+    /// A provider crossed its spend warning threshold or hit its limit.
+    SpendLimit {
+        app_type: String,
+        provider_id: String,
+        period: String,
+        usage_usd: String,
+        limit_usd: String,
+        exceeded: bool,
+    },
+    Request(RequestSummary),
+    /// The subscriber fell behind and this many events were dropped.
+    Lagged {
+        skipped: u64,
+    },
+}
+
+impl DaemonEvent {
+    pub fn app_type(&self) -> Option<&str> {
+        match self {
+            Self::WorkerStarted { app_type, .. }
+            | Self::WorkerStopped { app_type, .. }
+            | Self::WorkerRestarting { app_type, .. }
+            | Self::ProviderSwitched { app_type, .. }
+            | Self::FailoverHop { app_type, .. }
+            | Self::CircuitChanged { app_type, .. }
+            | Self::SpendLimit { app_type, .. } => Some(app_type),
+            Self::Request(summary) => Some(&summary.app_type),
+            Self::Lagged { .. } => None,
+        }
+    }
+}
+
+impl From<ProxyEvent> for DaemonEvent {
+    fn from(event: ProxyEvent) -> Self {
+        match event {
+            ProxyEvent::ProviderSwitched {
+                app_type,
+                from_provider_id,
+                provider_id,
+                provider_name,
+            } => Self::ProviderSwitched {
+                app_type,
+                from_provider_id,
+                provider_id,
+                provider_name,
+            },
+            ProxyEvent::FailoverHop {
+                app_type,
+                from_provider_id,
+                provider_id,
+                provider_name,
+            } => Self::FailoverHop {
+                app_type,
+                from_provider_id,
+                provider_id,
+                provider_name,
+            },
+            ProxyEvent::CircuitChanged {
+                app_type,
+                provider_id,
+                from,
+                to,
+            } => Self::CircuitChanged {
+                app_type,
+                provider_id,
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            ProxyEvent::SpendLimit {
+                app_type,
+                provider_id,
+                period,
+                usage_usd,
+                limit_usd,
+                exceeded,
+            } => Self::SpendLimit {
+                app_type,
+                provider_id,
+                period,
+                usage_usd,
+                limit_usd,
+                exceeded,
+            },
+            ProxyEvent::Request(summary) => Self::Request(summary),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -75,6 +291,29 @@ pub struct TakeoverFlags {
     pub gemini: bool,
 }
 
+/// Background sync as seen by the daemon. `None` in `Status` when the daemon
+/// predates auto sync.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoSyncState {
+    pub enabled: bool,
+    pub backend: String,
+    pub interval_minutes: u32,
+    pub running: bool,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub last_success_at: Option<String>,
+    /// Summary of the last successful run, e.g. "2 pulled, 0 pushed".
+    #[serde(default)]
+    pub last_message: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub next_retry_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct WorkerState {
     pub app_type: String,
@@ -196,6 +435,113 @@ mod tests {
         roundtrip_request(Request::SetGlobalEnabled { enabled: false });
     }
 
+    #[test]
+    fn subscribe_roundtrips_and_decodes_without_filter() {
+        roundtrip_request(Request::Subscribe {
+            app_type: Some("codex".to_string()),
+        });
+        let decoded: Request =
+            serde_json::from_str(r#"{"kind":"subscribe"}"#).expect("decode bare subscribe");
+        assert_eq!(decoded, Request::Subscribe { app_type: None });
+    }
+
+    #[test]
+    fn worker_events_roundtrips() {
+        roundtrip_request(Request::WorkerEvents {
+            session_token: "tok".to_string(),
+        });
+    }
+
+    #[test]
+    fn event_response_roundtrips() {
+        roundtrip_response(Response::Event {
+            at: "2026-05-15T12:34:56Z".to_string(),
+            event: DaemonEvent::WorkerStopped {
+                app_type: "claude".to_string(),
+                pid: 9999,
+                crashed: true,
+                message: Some("exit status: 1".to_string()),
+            },
+        });
+        roundtrip_response(Response::event(DaemonEvent::Request(RequestSummary {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+            status_code: 200,
+            latency_ms: 840,
+            total_cost_usd: Some("0.0123".to_string()),
+            ..RequestSummary::default()
+        })));
+    }
+
+    #[test]
+    fn proxy_circuit_event_converts_to_wire_state_names() {
+        use crate::proxy::circuit_breaker::CircuitState;
+
+        let event = DaemonEvent::from(ProxyEvent::CircuitChanged {
+            app_type: "gemini".to_string(),
+            provider_id: "p".to_string(),
+            from: CircuitState::HalfOpen,
+            to: CircuitState::Closed,
+        });
+        assert_eq!(
+            event,
+            DaemonEvent::CircuitChanged {
+                app_type: "gemini".to_string(),
+                provider_id: "p".to_string(),
+                from: "half_open".to_string(),
+                to: "closed".to_string(),
+            }
+        );
+        assert_eq!(event.app_type(), Some("gemini"));
+    }
+
+    #[test]
+    fn control_requests_roundtrip() {
+        roundtrip_request(Request::SwitchProvider {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+        });
+        roundtrip_request(Request::AddToFailoverQueue {
+            app_type: "codex".to_string(),
+            provider_id: "a".to_string(),
+        });
+        roundtrip_request(Request::RemoveFromFailoverQueue {
+            app_type: "codex".to_string(),
+            provider_id: "a".to_string(),
+        });
+        roundtrip_request(Request::MoveInFailoverQueue {
+            app_type: "codex".to_string(),
+            provider_id: "b".to_string(),
+            position: 0,
+        });
+        roundtrip_request(Request::ResetCircuitBreaker {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+        });
+        roundtrip_request(Request::ReloadConfig);
+    }
+
+    #[test]
+    fn control_responses_roundtrip() {
+        roundtrip_response(Response::ProviderSwitched {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+            previous_provider_id: Some("official".to_string()),
+        });
+        roundtrip_response(Response::FailoverQueue {
+            app_type: "codex".to_string(),
+            provider_ids: vec!["b".to_string(), "a".to_string()],
+        });
+        roundtrip_response(Response::CircuitReset {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+            worker_reset: true,
+        });
+        roundtrip_response(Response::Reloaded {
+            workers: vec!["claude".to_string()],
+        });
+    }
+
     #[test]
     fn ok_response_roundtrips() {
         roundtrip_response(Response::Ok);
@@ -253,6 +599,18 @@ mod tests {
                     }],
                 }),
             }],
+            auto_sync: Some(AutoSyncState {
+                enabled: true,
+                backend: "webdav".to_string(),
+                interval_minutes: 30,
+                running: false,
+                last_run_at: Some("2026-05-15T12:30:00Z".to_string()),
+                last_success_at: None,
+                last_message: None,
+                last_error: Some("connection refused".to_string()),
+                consecutive_failures: 2,
+                next_retry_at: Some("2026-05-15T12:34:00Z".to_string()),
+            }),
         });
     }
 
@@ -3,16 +3,20 @@
 //! Each accepted connection reads a single request line, dispatches it via the
 //! provided `Handler`, and writes back a single response line. Connections are
 //! ephemeral — the foreground client connects, exchanges one request/response,
-//! and disconnects.
+//! and disconnects. The exceptions are `Subscribe` and `WorkerEvents`, which
+//! stay open as event streams until the peer goes away or the daemon shuts
+//! down.
 
 use std::path::Path;
 use std::time::Duration;
 
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, watch};
 use tokio::task::JoinSet;
 
-use super::protocol::{encode_response, Request, Response};
+use super::protocol::{encode_response, DaemonEvent, Request, Response};
 
 /// Maximum time we wait for in-flight connections to drain after shutdown
 /// is signalled. The handlers that trigger self-shutdown (drop_takeover with
@@ -25,6 +29,15 @@ const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
 /// into actions on its internal state (worker child, takeover ops, DB writes).
 pub trait Handler: Send + Sync + 'static {
     fn handle(&self, request: Request) -> impl std::future::Future<Output = Response> + Send;
+
+    /// Event feed for `Subscribe` connections. Items are `Response::Event`
+    /// lines. Handlers without a feed reject subscriptions.
+    fn subscribe(&self) -> Option<broadcast::Receiver<Response>> {
+        None
+    }
+
+    /// Fan out one event read from an accepted `WorkerEvents` stream.
+    fn publish(&self, _event: DaemonEvent) {}
 }
 
 /// Bind a Unix domain socket at `path`, removing any stale entry first.
@@ -57,10 +70,14 @@ where
 {
     tokio::pin!(shutdown);
     let mut tasks: JoinSet<()> = JoinSet::new();
+    // Streaming connections never finish on their own; this tells them to
+    // close so the drain below isn't held up by idle subscribers.
+    let (stop_tx, stop_rx) = watch::channel(false);
     loop {
         tokio::select! {
             _ = &mut shutdown => {
                 log::debug!("daemon ipc: shutdown signalled, draining in-flight connections");
+                let _ = stop_tx.send(true);
                 drain_in_flight(&mut tasks).await;
                 return;
             }
@@ -68,8 +85,9 @@ where
                 match accept {
                     Ok((stream, _)) => {
                         let handler = handler.clone();
+                        let stop = stop_rx.clone();
                         tasks.spawn(async move {
-                            if let Err(err) = serve_connection(stream, handler).await {
+                            if let Err(err) = serve_connection(stream, handler, stop).await {
                                 log::warn!("daemon ipc: connection failed: {err}");
                             }
                         });
@@ -101,7 +119,11 @@ async fn drain_in_flight(tasks: &mut JoinSet<()>) {
     }
 }
 
-async fn serve_connection<H>(stream: UnixStream, handler: std::sync::Arc<H>) -> std::io::Result<()>
+async fn serve_connection<H>(
+    stream: UnixStream,
+    handler: std::sync::Arc<H>,
+    stop: watch::Receiver<bool>,
+) -> std::io::Result<()>
 where
     H: Handler,
 {
@@ -124,12 +146,105 @@ where
         }
     };
 
+    if let Request::Subscribe { app_type } = request {
+        return stream_events(write_half, handler.as_ref(), app_type, stop).await;
+    }
+
+    let accepts_worker_events = matches!(request, Request::WorkerEvents { .. });
     let response = handler.handle(request).await;
-    write_response(&mut write_half, &response).await
+    write_response(&mut write_half, &response).await?;
+    if accepts_worker_events && response == Response::Ok {
+        read_worker_events(reader, handler.as_ref(), stop).await?;
+    }
+    Ok(())
+}
+
+/// Write events to a subscriber until it disconnects or the daemon stops.
+async fn stream_events<H>(
+    mut write_half: OwnedWriteHalf,
+    handler: &H,
+    app_type: Option<String>,
+    mut stop: watch::Receiver<bool>,
+) -> std::io::Result<()>
+where
+    H: Handler,
+{
+    let Some(mut events) = handler.subscribe() else {
+        let resp = Response::Error {
+            message: "event subscription is not supported".to_string(),
+        };
+        return write_response(&mut write_half, &resp).await;
+    };
+    write_response(&mut write_half, &Response::Ok).await?;
+
+    loop {
+        let received = tokio::select! {
+            _ = stop.changed() => return Ok(()),
+            received = events.recv() => received,
+        };
+        let response = match received {
+            Ok(response) => response,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                Response::event(DaemonEvent::Lagged { skipped })
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+        if !event_matches_app(&response, app_type.as_deref()) {
+            continue;
+        }
+        match write_response(&mut write_half, &response).await {
+            Ok(()) => {}
+            // The subscriber went away; that's how streams normally end.
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset
+                ) =>
+            {
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn event_matches_app(response: &Response, app_type: Option<&str>) -> bool {
+    match (response, app_type) {
+        (Response::Event { event, .. }, Some(app_type)) => event
+            .app_type()
+            .is_none_or(|event_app| event_app == app_type),
+        _ => true,
+    }
+}
+
+/// Read `DaemonEvent` lines from a worker and hand them to the handler.
+async fn read_worker_events<H>(
+    mut reader: BufReader<OwnedReadHalf>,
+    handler: &H,
+    mut stop: watch::Receiver<bool>,
+) -> std::io::Result<()>
+where
+    H: Handler,
+{
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = tokio::select! {
+            _ = stop.changed() => return Ok(()),
+            read = reader.read_line(&mut line) => read?,
+        };
+        if n == 0 {
+            return Ok(());
+        }
+        match serde_json::from_str::<DaemonEvent>(line.trim()) {
+            Ok(event) => handler.publish(event),
+            Err(err) => log::warn!("daemon ipc: dropping malformed worker event: {err}"),
+        }
+    }
 }
 
 async fn write_response(
-    write_half: &mut tokio::net::unix::OwnedWriteHalf,
+    write_half: &mut OwnedWriteHalf,
     response: &Response,
 ) -> std::io::Result<()> {
     let payload = encode_response(response).map_err(std::io::Error::other)?;
@@ -206,6 +321,147 @@ mod tests {
         server.await.expect("server task join");
     }
 
+    /// Handler with a live event feed: worker streams are accepted and every
+    /// published event is fanned out to subscribers.
+    struct Feed {
+        events: broadcast::Sender<Response>,
+    }
+
+    impl Handler for Feed {
+        async fn handle(&self, request: Request) -> Response {
+            match request {
+                Request::WorkerEvents { .. } => Response::Ok,
+                _ => Response::Error {
+                    message: "unsupported in feed".into(),
+                },
+            }
+        }
+
+        fn subscribe(&self) -> Option<broadcast::Receiver<Response>> {
+            Some(self.events.subscribe())
+        }
+
+        fn publish(&self, event: DaemonEvent) {
+            let _ = self.events.send(Response::event(event));
+        }
+    }
+
+    async fn send_line(stream: &mut UnixStream, request: &Request) {
+        let line = serde_json::to_string(request).unwrap();
+        stream
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .unwrap();
+    }
+
+    async fn read_response<R>(reader: &mut BufReader<R>) -> Response
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let mut buf = String::new();
+        reader.read_line(&mut buf).await.expect("read response");
+        serde_json::from_str(buf.trim()).expect("parse")
+    }
+
+    #[tokio::test]
+    async fn subscribe_streams_worker_events_filtered_by_app() {
+        let tmp = tempfile::tempdir().expect("tmp");
+        let sock = tmp.path().join("daemon.sock");
+        let listener = bind(&sock).expect("bind");
+
+        let (events, _) = broadcast::channel(16);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server = tokio::spawn(async move {
+            run(listener, Arc::new(Feed { events }), async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        });
+
+        let mut subscriber = UnixStream::connect(&sock).await.expect("connect");
+        send_line(
+            &mut subscriber,
+            &Request::Subscribe {
+                app_type: Some("claude".into()),
+            },
+        )
+        .await;
+        let mut subscriber = BufReader::new(subscriber);
+        assert_eq!(read_response(&mut subscriber).await, Response::Ok);
+
+        let mut worker = UnixStream::connect(&sock).await.expect("connect");
+        send_line(
+            &mut worker,
+            &Request::WorkerEvents {
+                session_token: "tok".into(),
+            },
+        )
+        .await;
+        let mut worker = BufReader::new(worker);
+        assert_eq!(read_response(&mut worker).await, Response::Ok);
+
+        let hop = DaemonEvent::FailoverHop {
+            app_type: "claude".into(),
+            from_provider_id: "a".into(),
+            provider_id: "b".into(),
+            provider_name: "B".into(),
+        };
+        for event in [
+            DaemonEvent::CircuitChanged {
+                app_type: "codex".into(),
+                provider_id: "x".into(),
+                from: "closed".into(),
+                to: "open".into(),
+            },
+            hop.clone(),
+        ] {
+            let line = serde_json::to_string(&event).unwrap();
+            worker
+                .get_mut()
+                .write_all(format!("{line}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+
+        match read_response(&mut subscriber).await {
+            Response::Event { event, .. } => assert_eq!(event, hop),
+            other => panic!("unexpected: {other:?}"),
+        }
+
+        // Open streams must not hold the daemon past shutdown.
+        let _ = shutdown_tx.send(());
+        tokio::time::timeout(Duration::from_secs(1), server)
+            .await
+            .expect("server stops promptly")
+            .expect("server task join");
+    }
+
+    #[tokio::test]
+    async fn subscribe_is_rejected_without_event_feed() {
+        let tmp = tempfile::tempdir().expect("tmp");
+        let sock = tmp.path().join("daemon.sock");
+        let listener = bind(&sock).expect("bind");
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server = tokio::spawn(async move {
+            run(listener, Arc::new(Echo), async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        });
+
+        let mut stream = UnixStream::connect(&sock).await.expect("connect");
+        send_line(&mut stream, &Request::Subscribe { app_type: None }).await;
+        let mut reader = BufReader::new(stream);
+        assert!(matches!(
+            read_response(&mut reader).await,
+            Response::Error { .. }
+        ));
+
+        let _ = shutdown_tx.send(());
+        server.await.expect("server task join");
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn bind_creates_private_runtime_dir_and_socket() {
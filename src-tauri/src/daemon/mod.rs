@@ -2,9 +2,11 @@
 //!
 //! The daemon owns the worker process: it spawns it, watches it, restarts it
 //! under a backoff policy, and keeps the SQLite `proxy_runtime_session` row
-//! aligned with the actual worker state. Foreground TUI/CLI processes talk to
-//! the daemon via a Unix domain socket.
+//! aligned with the actual worker state. While it runs it also syncs with the
+//! configured sync backend in the background when `autoSync` is on.
+//! Foreground TUI/CLI processes talk to the daemon via a Unix domain socket.
 
+pub mod auto_sync;
 pub mod ipc;
 pub mod logging;
 pub mod paths;
@@ -55,6 +57,32 @@ pub fn notify_global_switch(enabled: bool) -> Result<(), String> {
     }
 }
 
+/// Send a control request (provider switch, failover queue, breaker reset,
+/// reload) to the daemon so it applies the change atomically alongside its
+/// running workers.
+///
+/// Returns `Ok(None)` when there is no live daemon, using the same stale
+/// socket handling as [`notify_global_switch`]; callers then apply the change
+/// in-process. A daemon-side refusal comes back as `Err(message)`.
+pub fn send_control(request: &Request) -> Result<Option<Response>, String> {
+    use std::io::ErrorKind;
+    let socket = paths::socket_path();
+    if !socket.exists() {
+        return Ok(None);
+    }
+    match client::round_trip(&socket, request) {
+        Ok(Response::Error { message }) => Err(message),
+        Ok(response) => Ok(Some(response)),
+        Err(client::ClientError::Io(e))
+            if matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::NotFound) =>
+        {
+            let _ = std::fs::remove_file(&socket);
+            Ok(None)
+        }
+        Err(err) => Err(err.to_string()),
+    }
+}
+
 /// Run the daemon to completion. Acquires the pidfile, installs the file
 /// logger, runs startup recovery, binds the IPC socket, and dispatches
 /// requests until shutdown is signalled.
@@ -98,6 +126,7 @@ pub async fn run(binary_path: PathBuf) -> Result<(), String> {
     if let Err(err) = supervisor.recover_on_startup().await {
         log::warn!("[daemon] startup recovery: {err}");
     }
+    supervisor.spawn_auto_sync();
 
     let listener = ipc::server::bind(&socket_path)
         .map_err(|err| format!("bind socket {}: {err}", socket_path.display()))?;
@@ -5,6 +5,7 @@
 //! preservation) lives in `ProxyService`. The supervisor's job is to keep one
 //! worker per active app route, keep the `proxy_runtime_session` row aligned
 //! with the actual workers, and survive worker crashes via the restart policy.
+//! It also runs background sync when the active sync backend asks for it.
 
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -15,14 +16,22 @@ use std::time::{Duration, Instant};
 use serde_json::json;
 use tokio::io::AsyncReadExt;
 use tokio::process::{Child, Command};
-use tokio::sync::{oneshot, Mutex, Notify};
+use tokio::sync::{broadcast, oneshot, Mutex, Notify};
 
 use crate::app_config::AppType;
 use crate::database::Database;
-use crate::services::ProxyService;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::handlers::CONTROL_SESSION_TOKEN_HEADER;
+use crate::services::{
+    ConflictResolutions, MergeOutcome, ProviderService, ProxyService, SyncDecision, SyncService,
+};
+use crate::store::AppState;
 
+use super::auto_sync::{self, AutoSyncSchedule};
 use super::ipc::protocol::{
-    Request, Response, TakeoverFlags, WorkerRuntimeStatus, WorkerState, WorkerTargetState,
+    AutoSyncState, DaemonEvent, Request, Response, TakeoverFlags, WorkerRuntimeStatus, WorkerState,
+    WorkerTargetState,
 };
 use super::ipc::server::Handler;
 use super::restart::{Decision, RestartPolicy};
@@ -38,6 +47,8 @@ pub const RUNTIME_KIND_ENV: &str = "CC_SWITCH_PROXY_RUNTIME_KIND";
 pub const RUNTIME_KIND_MANAGED_EXTERNAL: &str = "managed_external";
 
 const WORKER_HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events buffered per subscriber before it starts missing them.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
 
 #[derive(Debug, Clone)]
 struct WorkerInfo {
@@ -99,9 +110,17 @@ pub struct Supervisor {
     /// "Resource temporarily unavailable (os error 35)" once the client's 15 s
     /// IPC read timeout expired.
     spawn_lock: Arc<Mutex<()>>,
+    /// Serializes control requests (switch, failover queue, breaker reset,
+    /// reload) so two foreground clients cannot interleave their writes.
+    control_lock: Arc<Mutex<()>>,
     socket_path: PathBuf,
     binary_path: PathBuf,
     shutdown_notify: Arc<Notify>,
+    /// Fan-out for `Subscribe` connections: worker lifecycle events from the
+    /// supervisor plus proxy events relayed from each worker.
+    events: broadcast::Sender<Response>,
+    /// Background sync state reported by `Status`.
+    auto_sync: Arc<Mutex<AutoSyncState>>,
 }
 
 impl Supervisor {
@@ -112,9 +131,12 @@ impl Supervisor {
             proxy,
             inner: Arc::new(Mutex::new(SupervisorInner::default())),
             spawn_lock: Arc::new(Mutex::new(())),
+            control_lock: Arc::new(Mutex::new(())),
             socket_path,
             binary_path,
             shutdown_notify: Arc::new(Notify::new()),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            auto_sync: Arc::new(Mutex::new(AutoSyncState::default())),
         }
     }
 
@@ -301,6 +323,12 @@ impl Supervisor {
             inner.pending_worker_pids.remove(&app);
             inner.shutdown_requested = false;
         }
+        self.publish_event(DaemonEvent::WorkerStarted {
+            app_type: app_key,
+            pid: info.pid,
+            address: info.address.clone(),
+            port: info.port,
+        });
         self.persist_runtime_session().await?;
         Ok(info)
     }
@@ -704,6 +732,34 @@ impl Supervisor {
         Response::Ok
     }
 
+    /// Accept a worker's event stream only from a worker this daemon spawned.
+    /// The token may still be pending: the worker opens the stream right after
+    /// its hello is acknowledged, possibly before `ensure_worker_locked` has
+    /// moved it into `workers`.
+    async fn handle_worker_events(&self, session_token: &str) -> Response {
+        let inner = self.inner.lock().await;
+        let known = inner
+            .workers
+            .values()
+            .any(|info| info.session_token == session_token)
+            || inner
+                .pending_tokens
+                .values()
+                .any(|token| token == session_token);
+        if !known {
+            log::warn!("[daemon] worker event stream with unknown session token");
+            return Response::Error {
+                message: "session token mismatch".to_string(),
+            };
+        }
+        Response::Ok
+    }
+
+    fn publish_event(&self, event: DaemonEvent) {
+        // No subscribers is the common case; send only fails then.
+        let _ = self.events.send(Response::event(event));
+    }
+
     async fn handle_set_global_enabled(&self, enabled: bool) -> Response {
         if enabled {
             match self.proxy.set_global_enabled(true).await {
@@ -772,6 +828,192 @@ impl Supervisor {
         Response::Ok
     }
 
+    /// Switch the app's current provider through the same service path as
+    /// `cc-switch provider switch`. The switch blocks on SQLite and live
+    /// config file IO, so it runs on the blocking pool.
+    async fn handle_switch_provider(&self, app_type: &str, provider_id: &str) -> Response {
+        let app = match app_type.parse::<AppType>() {
+            Ok(app) => app,
+            Err(err) => {
+                return Response::Error {
+                    message: err.to_string(),
+                };
+            }
+        };
+        let _control_guard = self.control_lock.lock().await;
+        let db = self.db.clone();
+        let target = provider_id.to_string();
+        let result =
+            tokio::task::spawn_blocking(move || switch_provider_blocking(db, app, &target)).await;
+        let (previous_provider_id, provider) = match result {
+            Ok(Ok(switched)) => switched,
+            Ok(Err(err)) => {
+                return Response::Error {
+                    message: err.to_string(),
+                };
+            }
+            Err(err) => {
+                return Response::Error {
+                    message: format!("switch provider task failed: {err}"),
+                };
+            }
+        };
+
+        if previous_provider_id.as_deref() != Some(provider.id.as_str()) {
+            self.publish_event(DaemonEvent::ProviderSwitched {
+                app_type: app_type.to_string(),
+                from_provider_id: previous_provider_id.clone(),
+                provider_id: provider.id.clone(),
+                provider_name: provider.name.clone(),
+            });
+        }
+        Response::ProviderSwitched {
+            app_type: app_type.to_string(),
+            provider_id: provider.id,
+            previous_provider_id,
+        }
+    }
+
+    /// Apply one queue edit to the queue as persisted and answer with the
+    /// result. Edits run one at a time under the control lock and each
+    /// re-reads the queue, so concurrent edits compose instead of the last
+    /// full order winning. Workers read the queue per request, so no worker
+    /// round-trip is needed.
+    async fn handle_edit_failover_queue(&self, app_type: &str, edit: QueueEdit<'_>) -> Response {
+        if let Err(err) = app_type.parse::<AppType>() {
+            return Response::Error {
+                message: err.to_string(),
+            };
+        }
+        let _control_guard = self.control_lock.lock().await;
+        let queue = self
+            .db
+            .get_failover_queue(app_type)
+            .and_then(|queue| {
+                let mut provider_ids = queue
+                    .into_iter()
+                    .map(|item| item.provider_id)
+                    .collect::<Vec<_>>();
+                edit.apply(&mut provider_ids)?;
+                self.db.replace_failover_queue(app_type, &provider_ids)
+            })
+            .and_then(|()| self.db.get_failover_queue(app_type));
+        match queue {
+            Ok(queue) => Response::FailoverQueue {
+                app_type: app_type.to_string(),
+                provider_ids: queue.into_iter().map(|item| item.provider_id).collect(),
+            },
+            Err(err) => Response::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Clear the provider's persisted health and, when a worker is serving
+    /// the app, close its in-memory circuit breaker too.
+    async fn handle_reset_circuit_breaker(&self, app_type: &str, provider_id: &str) -> Response {
+        let app = match app_type.parse::<AppType>() {
+            Ok(app) => app,
+            Err(err) => {
+                return Response::Error {
+                    message: err.to_string(),
+                };
+            }
+        };
+        let _control_guard = self.control_lock.lock().await;
+        if let Err(err) = self.db.reset_provider_health(provider_id, app_type).await {
+            return Response::Error {
+                message: err.to_string(),
+            };
+        }
+
+        let worker = self.inner.lock().await.workers.get(&app).cloned();
+        let worker_reset = match worker {
+            Some(info) => {
+                self.post_worker_control(
+                    &info,
+                    "/control/circuit-breaker/reset",
+                    json!({ "app_type": app_type, "provider_id": provider_id }),
+                )
+                .await
+            }
+            None => false,
+        };
+        Response::CircuitReset {
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+            worker_reset,
+        }
+    }
+
+    /// Re-read settings in the daemon and ask every running worker to reload
+    /// its proxy configuration.
+    async fn handle_reload_config(&self) -> Response {
+        let _control_guard = self.control_lock.lock().await;
+        if let Err(err) = crate::settings::reload_settings() {
+            return Response::Error {
+                message: err.to_string(),
+            };
+        }
+
+        let mut worker_infos = {
+            let inner = self.inner.lock().await;
+            inner.workers.values().cloned().collect::<Vec<_>>()
+        };
+        worker_infos.sort_by(|left, right| left.app_type.as_str().cmp(right.app_type.as_str()));
+        let mut workers = Vec::with_capacity(worker_infos.len());
+        for info in worker_infos {
+            if self
+                .post_worker_control(&info, "/control/reload", json!({}))
+                .await
+            {
+                workers.push(info.app_type.as_str().to_string());
+            }
+        }
+        Response::Reloaded { workers }
+    }
+
+    /// POST to a worker control endpoint, authenticated with the session
+    /// token the daemon handed the worker at spawn. Returns whether the
+    /// worker accepted the request.
+    async fn post_worker_control(
+        &self,
+        info: &WorkerInfo,
+        path: &str,
+        body: serde_json::Value,
+    ) -> bool {
+        let Ok(client) = reqwest::Client::builder()
+            .timeout(Duration::from_secs(2))
+            .build()
+        else {
+            return false;
+        };
+        let result = client
+            .post(worker_url(&info.address, info.port, path))
+            .header(CONTROL_SESSION_TOKEN_HEADER, &info.session_token)
+            .json(&body)
+            .send()
+            .await;
+        match result {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                log::warn!(
+                    "[daemon] worker {} rejected {path}: {}",
+                    info.app_type.as_str(),
+                    response.status()
+                );
+                false
+            }
+            Err(err) => {
+                log::warn!(
+                    "[daemon] worker {} control {path} failed: {err}",
+                    info.app_type.as_str()
+                );
+                false
+            }
+        }
+    }
+
     async fn handle_status(&self) -> Response {
         let (worker_infos, restart_count, last_restart_at) = {
             let inner = self.inner.lock().await;
@@ -795,6 +1037,7 @@ impl Supervisor {
             });
         }
         workers.sort_by(|left, right| left.app_type.cmp(&right.app_type));
+        let auto_sync = self.auto_sync.lock().await.clone();
         let primary = workers.first();
         Response::Status {
             running: !workers.is_empty(),
@@ -805,6 +1048,7 @@ impl Supervisor {
             restart_count,
             last_restart_at: last_restart_at.map(|d| d.to_rfc3339()),
             workers,
+            auto_sync: Some(auto_sync),
         }
     }
 
@@ -814,7 +1058,8 @@ impl Supervisor {
             .build()
             .ok()?;
         let response = client
-            .get(worker_status_url(&info.address, info.port))
+            .get(worker_url(&info.address, info.port, "/status"))
+            .header(CONTROL_SESSION_TOKEN_HEADER, &info.session_token)
             .send()
             .await
             .ok()?;
@@ -855,6 +1100,171 @@ impl Supervisor {
         })
     }
 
+    /// Run background sync for as long as the daemon lives. The schedule is
+    /// described in [`super::auto_sync`].
+    pub fn spawn_auto_sync(&self) -> tokio::task::JoinHandle<()> {
+        let supervisor = self.clone();
+        tokio::spawn(async move {
+            let mut schedule = AutoSyncSchedule::new(Instant::now());
+            let mut ticker = tokio::time::interval(auto_sync::POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                supervisor.auto_sync_tick(&mut schedule).await;
+            }
+        })
+    }
+
+    async fn auto_sync_tick(&self, schedule: &mut AutoSyncSchedule) {
+        // Settings may have been edited by a foreground process since the
+        // last tick (auto sync toggled, credentials changed).
+        if let Err(err) = crate::settings::reload_settings() {
+            log::warn!("[daemon] auto sync: reload settings failed: {err}");
+            return;
+        }
+        let backend = crate::settings::get_sync_backend();
+        let enabled = crate::settings::is_auto_sync_enabled(backend);
+        let interval_minutes = crate::settings::effective_sync_interval_minutes();
+        {
+            let mut state = self.auto_sync.lock().await;
+            if !enabled || state.backend != backend.as_str() {
+                *state = AutoSyncState::default();
+            }
+            state.enabled = enabled;
+            state.backend = backend.as_str().to_string();
+            state.interval_minutes = interval_minutes;
+        }
+        if !enabled {
+            *schedule = AutoSyncSchedule::new(Instant::now());
+            return;
+        }
+
+        let interval = Duration::from_secs(u64::from(interval_minutes) * 60);
+        let before = self.local_sync_fingerprint().await;
+        let Some(reason) = schedule.due(Instant::now(), interval, before.as_deref()) else {
+            return;
+        };
+
+        log::info!(
+            "[daemon] auto sync ({}) with {}",
+            reason.as_str(),
+            backend.as_str()
+        );
+        self.auto_sync.lock().await.running = true;
+        let result =
+            tokio::task::spawn_blocking(|| SyncService::merge(&ConflictResolutions::default()))
+                .await
+                .unwrap_or_else(|err| {
+                    Err(AppError::Message(format!("auto sync task failed: {err}")))
+                });
+        let after = self.local_sync_fingerprint().await;
+        let finished_at = chrono::Utc::now();
+
+        let mut pulled = false;
+        let mut state = self.auto_sync.lock().await;
+        state.running = false;
+        state.last_run_at = Some(finished_at.to_rfc3339());
+        let blocked = match result {
+            Ok(MergeOutcome::Synced(summary))
+                if !matches!(summary.decision, SyncDecision::V1MigrationNeeded) =>
+            {
+                log::info!("[daemon] auto sync: {}", summary.message);
+                pulled = before.is_some() && after != before;
+                schedule.on_success(Instant::now(), after.clone());
+                state.last_success_at = Some(finished_at.to_rfc3339());
+                state.last_message = Some(summary.message);
+                state.last_error = None;
+                state.next_retry_at = None;
+                None
+            }
+            Ok(MergeOutcome::Synced(_)) => Some(
+                "remote holds v1 sync data; run `cc-switch config sync migrate-v1-to-v2`"
+                    .to_string(),
+            ),
+            Ok(MergeOutcome::Conflicts(conflicts)) => Some(format!(
+                "{} record(s) changed on both sides; run `cc-switch config sync merge` to resolve",
+                conflicts.len()
+            )),
+            Err(err) => {
+                let delay = schedule.on_failure(Instant::now(), interval, after.clone());
+                let message = err.to_string();
+                log::warn!(
+                    "[daemon] auto sync failed, retrying in {}s: {message}",
+                    delay.as_secs()
+                );
+                state.last_error = Some(message.clone());
+                state.next_retry_at = chrono::Duration::from_std(delay)
+                    .ok()
+                    .map(|delay| (finished_at + delay).to_rfc3339());
+                record_auto_sync_error(backend, message);
+                None
+            }
+        };
+        if let Some(message) = blocked {
+            log::warn!("[daemon] auto sync needs attention: {message}");
+            schedule.on_blocked(Instant::now(), after);
+            state.last_error = Some(message.clone());
+            state.next_retry_at = None;
+            record_auto_sync_error(backend, message);
+        }
+        state.consecutive_failures = schedule.consecutive_failures();
+        drop(state);
+
+        if pulled {
+            self.apply_pulled_sync_changes().await;
+        }
+    }
+
+    async fn local_sync_fingerprint(&self) -> Option<String> {
+        let db = self.db.clone();
+        match tokio::task::spawn_blocking(move || {
+            crate::services::webdav_sync::local_change_fingerprint(&db)
+        })
+        .await
+        {
+            Ok(Ok(fingerprint)) => Some(fingerprint),
+            Ok(Err(err)) => {
+                log::warn!("[daemon] auto sync: read local records failed: {err}");
+                None
+            }
+            Err(err) => {
+                log::warn!("[daemon] auto sync: fingerprint task failed: {err}");
+                None
+            }
+        }
+    }
+
+    /// Write pulled changes to the apps' live configs and let the workers
+    /// pick up changed providers. Apps under proxy takeover keep their live
+    /// config pointed at the proxy; only prompts and skills are refreshed.
+    async fn apply_pulled_sync_changes(&self) {
+        let takeovers = self.read_takeover_flags().await;
+        let any_takeover = takeovers.claude || takeovers.codex || takeovers.gemini;
+        let db = self.db.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<(), AppError> {
+            let state = AppState::from_database(db)?;
+            if !any_takeover {
+                return ProviderService::sync_current_to_live(&state);
+            }
+            crate::services::prompt::PromptService::sync_all_active_to_live_best_effort(&state)?;
+            crate::services::skill::SkillService::sync_all_enabled_best_effort()?;
+            Ok(())
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => log::warn!("[daemon] auto sync: live config sync failed: {err}"),
+            Err(err) => log::warn!("[daemon] auto sync: live config task failed: {err}"),
+        }
+        if let Response::Reloaded { workers } = self.handle_reload_config().await {
+            if !workers.is_empty() {
+                log::info!(
+                    "[daemon] auto sync: reloaded workers {}",
+                    workers.join(", ")
+                );
+            }
+        }
+    }
+
     pub async fn shutdown(&self) {
         let _spawn_guard = self.spawn_lock.lock().await;
         let stop_plan = self.plan_stop_all_workers(true).await;
@@ -915,8 +1325,15 @@ impl Supervisor {
             log::warn!("[daemon] {app_key} worker pid={pid} stderr: {message}");
         }
 
+        let exit_message = startup_failure.clone();
         let (intentional, has_remaining_workers, teardown_in_progress) =
             self.record_worker_exit(&app, pid, startup_failure).await;
+        self.publish_event(DaemonEvent::WorkerStopped {
+            app_type: app_key.clone(),
+            pid,
+            crashed: !intentional,
+            message: exit_message,
+        });
 
         let _ = self.persist_runtime_session().await;
 
@@ -945,6 +1362,11 @@ impl Supervisor {
                     delay,
                     attempt + 1
                 );
+                self.publish_event(DaemonEvent::WorkerRestarting {
+                    app_type: app_key.clone(),
+                    attempt: attempt + 1,
+                    delay_ms: delay.as_millis() as u64,
+                });
                 tokio::time::sleep(delay).await;
                 if !self.should_restart_after_crash(&app).await {
                     log::info!(
@@ -1120,9 +1542,125 @@ impl Handler for Supervisor {
                     .await
             }
             Request::SetGlobalEnabled { enabled } => self.handle_set_global_enabled(enabled).await,
+            Request::WorkerEvents { session_token } => {
+                self.handle_worker_events(&session_token).await
+            }
+            // The IPC server serves subscriptions itself via `subscribe`.
+            Request::Subscribe { .. } => Response::Error {
+                message: "subscribe must be sent on its own connection".to_string(),
+            },
+            Request::SwitchProvider {
+                app_type,
+                provider_id,
+            } => self.handle_switch_provider(&app_type, &provider_id).await,
+            Request::AddToFailoverQueue {
+                app_type,
+                provider_id,
+            } => {
+                self.handle_edit_failover_queue(&app_type, QueueEdit::Add(&provider_id))
+                    .await
+            }
+            Request::RemoveFromFailoverQueue {
+                app_type,
+                provider_id,
+            } => {
+                self.handle_edit_failover_queue(&app_type, QueueEdit::Remove(&provider_id))
+                    .await
+            }
+            Request::MoveInFailoverQueue {
+                app_type,
+                provider_id,
+                position,
+            } => {
+                self.handle_edit_failover_queue(&app_type, QueueEdit::Move(&provider_id, position))
+                    .await
+            }
+            Request::ResetCircuitBreaker {
+                app_type,
+                provider_id,
+            } => {
+                self.handle_reset_circuit_breaker(&app_type, &provider_id)
+                    .await
+            }
+            Request::ReloadConfig => self.handle_reload_config().await,
             Request::Shutdown => self.handle_shutdown().await,
         }
     }
+
+    fn subscribe(&self) -> Option<broadcast::Receiver<Response>> {
+        Some(self.events.subscribe())
+    }
+
+    fn publish(&self, event: DaemonEvent) {
+        self.publish_event(event);
+    }
+}
+
+/// A single failover queue edit from a control request.
+#[derive(Debug, Clone, Copy)]
+enum QueueEdit<'a> {
+    Add(&'a str),
+    Remove(&'a str),
+    /// Move the provider to this 0-based position, clamped to the queue end.
+    Move(&'a str, usize),
+}
+
+impl QueueEdit<'_> {
+    fn apply(self, provider_ids: &mut Vec<String>) -> Result<(), AppError> {
+        match self {
+            Self::Add(id) => {
+                if !provider_ids.iter().any(|queued| queued == id) {
+                    provider_ids.push(id.to_string());
+                }
+            }
+            Self::Remove(id) => provider_ids.retain(|queued| queued != id),
+            Self::Move(id, position) => {
+                let index = provider_ids
+                    .iter()
+                    .position(|queued| queued == id)
+                    .ok_or_else(|| {
+                        AppError::InvalidInput(format!(
+                            "Provider is not in the failover queue: {id}"
+                        ))
+                    })?;
+                let id = provider_ids.remove(index);
+                provider_ids.insert(position.min(provider_ids.len()), id);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the effective provider before the switch and the provider now
+/// current.
+fn switch_provider_blocking(
+    db: Arc<Database>,
+    app: AppType,
+    provider_id: &str,
+) -> Result<(Option<String>, Provider), AppError> {
+    crate::settings::reload_settings()?;
+    let previous = if app.is_additive_mode() {
+        None
+    } else {
+        crate::settings::get_effective_current_provider(&db, &app)?
+    };
+    let provider = db
+        .get_provider_by_id(provider_id, app.as_str())?
+        .ok_or_else(|| AppError::InvalidInput(format!("Provider not found: {provider_id}")))?;
+
+    let state = AppState::from_database(db)?;
+    ProviderService::switch(&state, app.clone(), provider_id)?;
+    if let Err(err) = crate::claude_plugin::sync_claude_plugin_on_provider_switch(&app, &provider) {
+        log::warn!("[daemon] claude plugin sync after provider switch failed: {err}");
+    }
+    Ok((previous, provider))
+}
+
+/// Surface an auto sync problem where `config sync show` and the TUI look.
+fn record_auto_sync_error(backend: crate::settings::SyncBackend, message: String) {
+    if let Err(err) = crate::settings::set_sync_last_error(backend, Some(message), Some("auto")) {
+        log::warn!("[daemon] auto sync: record status failed: {err}");
+    }
 }
 
 fn parse_app_type(s: &str) -> Option<AppType> {
@@ -1197,7 +1735,7 @@ fn is_process_alive_for_signal(pid: u32) -> bool {
     }
 }
 
-fn worker_status_url(address: &str, port: u16) -> String {
+fn worker_url(address: &str, port: u16, path: &str) -> String {
     let connect_host = match address {
         "0.0.0.0" => "127.0.0.1".to_string(),
         "::" => "::1".to_string(),
@@ -1208,7 +1746,7 @@ fn worker_status_url(address: &str, port: u16) -> String {
     } else {
         connect_host
     };
-    format!("http://{connect_host}:{port}/status")
+    format!("http://{connect_host}:{port}{path}")
 }
 
 #[cfg(test)]
@@ -1409,6 +1947,150 @@ mod tests {
         assert!(inner.stopping_workers.is_empty());
     }
 
+    #[tokio::test]
+    async fn worker_event_stream_requires_known_token_and_relays_to_subscribers() {
+        let db = Arc::new(Database::memory().expect("create database"));
+        let supervisor = supervisor_for_test(db, Path::new("/tmp"));
+        {
+            let mut inner = supervisor.inner.lock().await;
+            inner
+                .workers
+                .insert(AppType::Claude, worker_info_for_test(AppType::Claude, 1001));
+        }
+
+        assert_eq!(
+            supervisor
+                .handle(Request::WorkerEvents {
+                    session_token: "token".to_string(),
+                })
+                .await,
+            Response::Ok
+        );
+        assert!(matches!(
+            supervisor
+                .handle(Request::WorkerEvents {
+                    session_token: "forged".to_string(),
+                })
+                .await,
+            Response::Error { .. }
+        ));
+
+        let mut events = supervisor
+            .subscribe()
+            .expect("supervisor has an event feed");
+        let event = DaemonEvent::ProviderSwitched {
+            app_type: "claude".to_string(),
+            from_provider_id: Some("a".to_string()),
+            provider_id: "b".to_string(),
+            provider_name: "B".to_string(),
+        };
+        supervisor.publish(event.clone());
+        match events.try_recv().expect("relayed event") {
+            Response::Event { event: relayed, .. } => assert_eq!(relayed, event),
+            other => panic!("expected event, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn control_requests_update_failover_queue_and_reset_health() {
+        let db = Arc::new(Database::memory().expect("create database"));
+        for id in ["a", "b"] {
+            db.save_provider(
+                "claude",
+                &Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None),
+            )
+            .expect("save provider");
+        }
+        let supervisor = supervisor_for_test(db.clone(), Path::new("/tmp"));
+
+        for id in ["a", "b"] {
+            supervisor
+                .handle(Request::AddToFailoverQueue {
+                    app_type: "claude".to_string(),
+                    provider_id: id.to_string(),
+                })
+                .await;
+        }
+        let response = supervisor
+            .handle(Request::MoveInFailoverQueue {
+                app_type: "claude".to_string(),
+                provider_id: "b".to_string(),
+                position: 0,
+            })
+            .await;
+        assert_eq!(
+            response,
+            Response::FailoverQueue {
+                app_type: "claude".to_string(),
+                provider_ids: vec!["b".to_string(), "a".to_string()],
+            }
+        );
+        assert!(matches!(
+            supervisor
+                .handle(Request::AddToFailoverQueue {
+                    app_type: "claude".to_string(),
+                    provider_id: "missing".to_string(),
+                })
+                .await,
+            Response::Error { .. }
+        ));
+        assert!(matches!(
+            supervisor
+                .handle(Request::MoveInFailoverQueue {
+                    app_type: "claude".to_string(),
+                    provider_id: "missing".to_string(),
+                    position: 0,
+                })
+                .await,
+            Response::Error { .. }
+        ));
+        let response = supervisor
+            .handle(Request::RemoveFromFailoverQueue {
+                app_type: "claude".to_string(),
+                provider_id: "b".to_string(),
+            })
+            .await;
+        assert_eq!(
+            response,
+            Response::FailoverQueue {
+                app_type: "claude".to_string(),
+                provider_ids: vec!["a".to_string()],
+            }
+        );
+        assert!(matches!(
+            supervisor
+                .handle(Request::ResetCircuitBreaker {
+                    app_type: "not-an-app".to_string(),
+                    provider_id: "a".to_string(),
+                })
+                .await,
+            Response::Error { .. }
+        ));
+
+        db.update_provider_health("a", "claude", false, Some("boom".to_string()))
+            .await
+            .expect("record failure");
+        let response = supervisor
+            .handle(Request::ResetCircuitBreaker {
+                app_type: "claude".to_string(),
+                provider_id: "a".to_string(),
+            })
+            .await;
+        assert_eq!(
+            response,
+            Response::CircuitReset {
+                app_type: "claude".to_string(),
+                provider_id: "a".to_string(),
+                worker_reset: false,
+            }
+        );
+        let health = db
+            .get_provider_health("a", "claude")
+            .await
+            .expect("read health");
+        assert_eq!(health.consecutive_failures, 0);
+    }
+
     #[tokio::test]
     async fn status_and_runtime_session_preserve_worker_started_at() {
         let db = Arc::new(Database::memory().expect("create database"));
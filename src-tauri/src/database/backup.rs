@@ -27,7 +27,23 @@ const SYNC_IMPORT_RESTORE_TABLES: &[&str] = &[
 
 const SYNC_EXPORT_RESETTABLE_TABLES: &[&str] = &["provider_health"];
 
-const SYNC_LOCAL_SETTINGS_KEYS: &[&str] = &["proxy_runtime_session"];
+/// 仅属于本机的 settings 键；`prompt_block_base_<app>` 记录本机提示词文件中托管区块上次写入的版本，
+/// 被远端覆盖后三方合并会把本机内容误判为手动修改
+const SYNC_LOCAL_SETTINGS_KEYS: &[&str] = &[
+    "proxy_runtime_session",
+    "capture_config",
+    "prompt_block_base_claude",
+    "prompt_block_base_codex",
+    "prompt_block_base_gemini",
+    "prompt_block_base_opencode",
+    "prompt_block_base_hermes",
+    "prompt_block_base_openclaw",
+];
+
+/// 会话全文索引（含 FTS5 影子表）可由本地会话文件重建，不进入 SQL 导出
+const LOCAL_INDEX_TABLE_PREFIX: &str = "session_search_";
+/// 仅用于本机调试的表（请求抓包含完整对话内容），不进入 SQL 导出
+const LOCAL_ONLY_TABLES: &[&str] = &["proxy_request_captures"];
 const PROXY_CONFIG_LOCAL_COLUMNS: &[&str] =
     &["proxy_enabled", "listen_address", "listen_port", "enabled"];
 
@@ -705,10 +721,14 @@ impl Database {
         while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
             let obj_type: String = row.get(0).map_err(|e| AppError::Database(e.to_string()))?;
             let name: String = row.get(1).map_err(|e| AppError::Database(e.to_string()))?;
+            let tbl_name: String = row.get(2).map_err(|e| AppError::Database(e.to_string()))?;
             let sql: String = row.get(3).map_err(|e| AppError::Database(e.to_string()))?;
 
-            // 跳过 SQLite 内部对象（如 sqlite_sequence）
-            if name.starts_with("sqlite_") {
+            // 跳过 SQLite 内部对象（如 sqlite_sequence）、本地搜索索引与本机调试表
+            if name.starts_with("sqlite_")
+                || name.starts_with(LOCAL_INDEX_TABLE_PREFIX)
+                || LOCAL_ONLY_TABLES.contains(&tbl_name.as_str())
+            {
                 continue;
             }
 
@@ -1029,6 +1049,73 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sql_export_skips_session_search_index() -> Result<(), AppError> {
+        let source_db = Database::memory()?;
+        {
+            let conn = crate::database::lock_conn!(source_db.conn);
+            seed_provider(&conn, "p1")?;
+            conn.execute(
+                "INSERT INTO session_search_sessions (source_path, provider_id, session_id)
+                 VALUES ('/tmp/claude/s1.jsonl', 'claude', 's1')",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO session_search_fts (content, role, source_path, msg_index)
+                 VALUES ('indexed message', 'user', '/tmp/claude/s1.jsonl', 0)",
+                [],
+            )?;
+        }
+
+        let sql = source_db.export_sql_string()?;
+        assert!(
+            !sql.contains("session_search_"),
+            "search index tables should not be exported"
+        );
+
+        let target_db = Database::memory()?;
+        target_db.import_sql_string(&sql)?;
+        let indexed: i64 = {
+            let conn = crate::database::lock_conn!(target_db.conn);
+            conn.query_row("SELECT COUNT(*) FROM session_search_fts", [], |row| {
+                row.get(0)
+            })?
+        };
+        assert_eq!(indexed, 0, "imported database starts with an empty index");
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_export_skips_proxy_request_captures() -> Result<(), AppError> {
+        let source_db = Database::memory()?;
+        {
+            let conn = crate::database::lock_conn!(source_db.conn);
+            seed_provider(&conn, "p1")?;
+            conn.execute(
+                "INSERT INTO proxy_request_captures (request_id, app_type, endpoint, client_body, created_at)
+                 VALUES ('req-1', 'claude', '/v1/messages', '{\"model\":\"x\"}', 1)",
+                [],
+            )?;
+        }
+
+        let sql = source_db.export_sql_string()?;
+        assert!(
+            !sql.contains("proxy_request_captures"),
+            "request captures stay on this machine"
+        );
+
+        let target_db = Database::memory()?;
+        target_db.import_sql_string(&sql)?;
+        let conn = crate::database::lock_conn!(target_db.conn);
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM proxy_request_captures", [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(count, 0);
+        Ok(())
+    }
+
     #[test]
     fn memory_import_does_not_create_global_database_backup() -> Result<(), AppError> {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -1094,6 +1181,11 @@ mod tests {
                 [],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO settings (key, value) VALUES ('prompt_block_base_claude', 'remote block')",
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
         }
         let remote_sql = remote_db.export_sql_string()?;
 
@@ -1101,6 +1193,9 @@ mod tests {
         local_db
             .set_setting("proxy_runtime_session", "{\"pid\":123}")
             .expect("persist local runtime session");
+        local_db
+            .set_setting("prompt_block_base_claude", "local block")
+            .expect("persist local prompt block base");
         {
             let conn = crate::database::lock_conn!(local_db.conn);
             seed_provider(&conn, "local-provider")?;
@@ -1115,6 +1210,13 @@ mod tests {
                 .as_deref(),
             Some("{\"pid\":123}")
         );
+        assert_eq!(
+            local_db
+                .get_setting("prompt_block_base_claude")
+                .expect("read local prompt block base after import")
+                .as_deref(),
+            Some("local block")
+        );
 
         Ok(())
     }
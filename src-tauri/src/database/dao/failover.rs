@@ -163,6 +163,80 @@ impl Database {
         Ok(())
     }
 
+    /// 用给定的供应商列表整体替换故障转移队列（成员与顺序），在单个事务中完成
+    ///
+    /// 移出队列的供应商会一并清除健康状态；队列顺序写入 sort_index。
+    pub fn replace_failover_queue(
+        &self,
+        app_type: &str,
+        provider_ids: &[String],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut seen = std::collections::HashSet::new();
+        for provider_id in provider_ids {
+            if !seen.insert(provider_id.as_str()) {
+                return Err(AppError::InvalidInput(format!(
+                    "Provider listed twice in failover queue: {provider_id}"
+                )));
+            }
+            let exists: bool = tx
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM providers WHERE id = ?1 AND app_type = ?2",
+                    rusqlite::params![provider_id, app_type],
+                    |row| row.get(0),
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            if !exists {
+                return Err(AppError::InvalidInput(format!(
+                    "Provider not found: {provider_id}"
+                )));
+            }
+        }
+        if provider_ids.is_empty() {
+            Self::reject_emptying_active_failover_queue(&tx, app_type, None)?;
+        }
+
+        let mut stmt = tx
+            .prepare("SELECT id FROM providers WHERE app_type = ?1 AND in_failover_queue = 1")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let previously_queued = stmt
+            .query_map([app_type], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        drop(stmt);
+        for provider_id in previously_queued
+            .iter()
+            .filter(|id| !seen.contains(id.as_str()))
+        {
+            tx.execute(
+                "UPDATE providers SET in_failover_queue = 0 WHERE id = ?1 AND app_type = ?2",
+                rusqlite::params![provider_id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            tx.execute(
+                "DELETE FROM provider_health WHERE provider_id = ?1 AND app_type = ?2",
+                rusqlite::params![provider_id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        for (sort_index, provider_id) in provider_ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE providers SET in_failover_queue = 1, sort_index = ?3
+                 WHERE id = ?1 AND app_type = ?2",
+                rusqlite::params![provider_id, app_type, sort_index as i64],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 检查供应商是否在故障转移队列中
     pub fn is_in_failover_queue(
         &self,
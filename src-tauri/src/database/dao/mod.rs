@@ -5,11 +5,16 @@
 pub mod failover;
 pub mod mcp;
 pub mod model_pricing;
+pub mod model_routing;
 pub mod prompts;
 pub mod providers;
 pub mod providers_seed;
 pub mod proxy;
+pub mod proxy_capture;
+pub mod secrets;
+pub mod session_search;
 pub mod settings;
+pub mod shared_prompts;
 pub mod skills;
 pub mod stream_check;
 pub mod usage_rollup;
@@ -0,0 +1,176 @@
+//! 模型路由规则数据访问对象
+//!
+//! 规则按 sort_index 升序匹配，首条命中的规则生效。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::proxy::model_mapper::validate_model_routing_rule;
+use crate::proxy::types::ModelRoutingRule;
+use rusqlite::{params, Connection, Row};
+
+const SELECT_COLUMNS: &str = "id, app_type, provider_id, match_type, pattern, target_model,
+    pin_provider_id, sort_index, enabled, created_at";
+
+fn row_to_rule(row: &Row<'_>) -> rusqlite::Result<ModelRoutingRule> {
+    let match_type: String = row.get(3)?;
+    Ok(ModelRoutingRule {
+        id: row.get(0)?,
+        app_type: row.get(1)?,
+        provider_id: row.get(2)?,
+        match_type: match_type.parse().unwrap_or_default(),
+        pattern: row.get(4)?,
+        target_model: row.get(5)?,
+        pin_provider_id: row.get(6)?,
+        sort_index: row.get(7)?,
+        enabled: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn normalize_optional(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+fn insert_rule_on_conn(
+    conn: &Connection,
+    rule: &ModelRoutingRule,
+    sort_index: i64,
+    created_at: i64,
+) -> Result<i64, AppError> {
+    validate_model_routing_rule(rule).map_err(AppError::InvalidInput)?;
+    conn.execute(
+        "INSERT INTO model_routing_rules
+            (app_type, provider_id, match_type, pattern, target_model,
+             pin_provider_id, sort_index, enabled, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            normalize_optional(&rule.app_type),
+            normalize_optional(&rule.provider_id),
+            rule.match_type.as_str(),
+            rule.pattern.trim(),
+            normalize_optional(&rule.target_model),
+            normalize_optional(&rule.pin_provider_id),
+            sort_index,
+            rule.enabled,
+            created_at,
+        ],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(conn.last_insert_rowid())
+}
+
+impl Database {
+    /// 获取模型路由规则
+    ///
+    /// 传入 `app_type` 时只返回对该应用生效的规则（含不限应用的规则）。
+    pub fn list_model_routing_rules(
+        &self,
+        app_type: Option<&str>,
+    ) -> Result<Vec<ModelRoutingRule>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let sql = format!(
+            "SELECT {SELECT_COLUMNS} FROM model_routing_rules
+             WHERE ?1 IS NULL OR app_type IS NULL OR app_type = ?1
+             ORDER BY sort_index ASC, id ASC"
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rules = stmt
+            .query_map(params![app_type], row_to_rule)
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rules)
+    }
+
+    /// 追加一条规则到末尾，返回新规则 ID
+    pub fn add_model_routing_rule(&self, rule: &ModelRoutingRule) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        let next_index: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(sort_index), -1) + 1 FROM model_routing_rules",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        insert_rule_on_conn(&conn, rule, next_index, chrono::Utc::now().timestamp())
+    }
+
+    /// 删除规则，返回是否存在
+    pub fn delete_model_routing_rule(&self, id: i64) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute("DELETE FROM model_routing_rules WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(deleted > 0)
+    }
+
+    /// 移动规则到指定位置（从 0 开始），并重新编号 sort_index
+    pub fn move_model_routing_rule(&self, id: i64, position: usize) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut stmt = tx
+            .prepare("SELECT id FROM model_routing_rules ORDER BY sort_index ASC, id ASC")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        drop(stmt);
+
+        let Some(current) = ids.iter().position(|existing| *existing == id) else {
+            return Err(AppError::InvalidInput(format!(
+                "Model routing rule {id} not found"
+            )));
+        };
+        let moved = ids.remove(current);
+        ids.insert(position.min(ids.len()), moved);
+
+        for (index, rule_id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE model_routing_rules SET sort_index = ?1 WHERE id = ?2",
+                params![index as i64, rule_id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 整体替换规则表，规则顺序即匹配顺序
+    pub fn replace_model_routing_rules(&self, rules: &[ModelRoutingRule]) -> Result<(), AppError> {
+        for rule in rules {
+            validate_model_routing_rule(rule).map_err(AppError::InvalidInput)?;
+        }
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute("DELETE FROM model_routing_rules", [])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let now = chrono::Utc::now().timestamp();
+        for (index, rule) in rules.iter().enumerate() {
+            let created_at = if rule.created_at > 0 {
+                rule.created_at
+            } else {
+                now
+            };
+            insert_rule_on_conn(&tx, rule, index as i64, created_at)?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -291,6 +291,12 @@ impl Database {
         let is_update = existing.is_some();
         let (is_current, in_failover_queue) =
             existing.unwrap_or((false, provider.in_failover_queue));
+        // 保险库开启时，live 回填的明文密钥恢复为引用，新增或变更的密钥加密后落库
+        let restored_settings =
+            super::secrets::seal_for_save(&tx, app_type, &provider.id, &provider.settings_config)?;
+        let settings_config = restored_settings
+            .as_ref()
+            .unwrap_or(&provider.settings_config);
 
         if is_update {
             // 更新模式：使用 UPDATE 避免触发 ON DELETE CASCADE
@@ -311,7 +317,7 @@ impl Database {
                 WHERE id = ?13 AND app_type = ?14",
                 params![
                     provider.name,
-                    serde_json::to_string(&provider.settings_config).map_err(|e| {
+                    serde_json::to_string(settings_config).map_err(|e| {
                         AppError::Database(format!("Failed to serialize settings_config: {e}"))
                     })?,
                     provider.website_url,
@@ -342,7 +348,7 @@ impl Database {
                     provider.id,
                     app_type,
                     provider.name,
-                    serde_json::to_string(&provider.settings_config)
+                    serde_json::to_string(settings_config)
                         .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))?,
                     provider.website_url,
                     provider.category,
@@ -412,6 +418,11 @@ impl Database {
             params![id, app_type],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM provider_secrets WHERE provider_id = ?1 AND app_type = ?2",
+            params![id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
@@ -448,6 +459,9 @@ impl Database {
         settings_config: &serde_json::Value,
     ) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        let restored_settings =
+            super::secrets::seal_for_save(&conn, app_type, provider_id, settings_config)?;
+        let settings_config = restored_settings.as_ref().unwrap_or(settings_config);
         conn.execute(
             "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
             params![
@@ -43,6 +43,7 @@ fn default_app_proxy_config(app_type: impl Into<String>) -> AppProxyConfig {
         circuit_timeout_seconds: 60,
         circuit_error_rate_threshold: 0.6,
         circuit_min_requests: 10,
+        routing_strategy: RoutingStrategy::Ordered,
     }
 }
 
@@ -334,7 +335,7 @@ impl Database {
                 "SELECT app_type, enabled, auto_failover_enabled,
                         max_retries, streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
                         circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
-                        circuit_error_rate_threshold, circuit_min_requests
+                        circuit_error_rate_threshold, circuit_min_requests, routing_strategy
                  FROM proxy_config WHERE app_type = ?1",
                 [app_type],
                 |row| {
@@ -351,6 +352,10 @@ impl Database {
                         circuit_timeout_seconds: row.get::<_, i32>(9)? as u32,
                         circuit_error_rate_threshold: row.get(10)?,
                         circuit_min_requests: row.get::<_, i32>(11)? as u32,
+                        routing_strategy: row
+                            .get::<_, String>(12)?
+                            .parse()
+                            .unwrap_or_default(),
                     })
                 },
             )
@@ -380,7 +385,7 @@ impl Database {
                 "SELECT app_type, enabled, auto_failover_enabled,
                         max_retries, streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
                         circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
-                        circuit_error_rate_threshold, circuit_min_requests
+                        circuit_error_rate_threshold, circuit_min_requests, routing_strategy
                  FROM proxy_config WHERE app_type = ?1",
                 [app_type],
                 |row| {
@@ -397,6 +402,10 @@ impl Database {
                         circuit_timeout_seconds: row.get::<_, i32>(9)? as u32,
                         circuit_error_rate_threshold: row.get(10)?,
                         circuit_min_requests: row.get::<_, i32>(11)? as u32,
+                        routing_strategy: row
+                            .get::<_, String>(12)?
+                            .parse()
+                            .unwrap_or_default(),
                     })
                 },
             )
@@ -437,6 +446,7 @@ impl Database {
                 circuit_timeout_seconds = ?10,
                 circuit_error_rate_threshold = ?11,
                 circuit_min_requests = ?12,
+                routing_strategy = ?13,
                 updated_at = datetime('now')
              WHERE app_type = ?1",
             rusqlite::params![
@@ -452,6 +462,7 @@ impl Database {
                 config.circuit_timeout_seconds as i32,
                 config.circuit_error_rate_threshold,
                 config.circuit_min_requests as i32,
+                config.routing_strategy.as_str(),
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -878,6 +889,36 @@ impl Database {
         Ok(())
     }
 
+    /// 获取指定应用各 Provider 近期成功请求的平均延迟（毫秒）
+    ///
+    /// 仅统计 `since`（Unix 秒）之后的 2xx 代理请求，供最低延迟路由策略使用。
+    pub fn get_recent_provider_latencies(
+        &self,
+        app_type: &str,
+        since: i64,
+    ) -> Result<std::collections::HashMap<String, f64>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider_id, AVG(latency_ms)
+                 FROM proxy_request_logs
+                 WHERE app_type = ?1 AND created_at >= ?2
+                   AND status_code >= 200 AND status_code < 300
+                   AND data_source = 'proxy'
+                 GROUP BY provider_id",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![app_type, since], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
     // ==================== Circuit Breaker Config (Legacy Compatibility) ====================
 
     /// 获取熔断器配置（兼容旧接口，从 claude 行读取）
@@ -0,0 +1,134 @@
+//! 代理请求抓包数据访问对象
+//!
+//! 抓包记录按 request_id 与 proxy_request_logs 关联，只保留最近的若干条。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::proxy::capture::{ProxyCapture, ProxyCaptureSummary};
+use rusqlite::{params, OptionalExtension, Row};
+
+const SELECT_COLUMNS: &str = "request_id, app_type, endpoint, provider_id, status_code,
+    client_headers, client_body, upstream_method, upstream_url, upstream_headers, upstream_body,
+    response_headers, response_body, truncated, created_at";
+
+fn json_column(row: &Row<'_>, index: usize) -> rusqlite::Result<serde_json::Value> {
+    let text: String = row.get(index)?;
+    Ok(serde_json::from_str(&text).unwrap_or_default())
+}
+
+fn row_to_capture(row: &Row<'_>) -> rusqlite::Result<ProxyCapture> {
+    Ok(ProxyCapture {
+        request_id: row.get(0)?,
+        app_type: row.get(1)?,
+        endpoint: row.get(2)?,
+        provider_id: row.get(3)?,
+        status_code: row.get(4)?,
+        client_headers: json_column(row, 5)?,
+        client_body: row.get(6)?,
+        upstream_method: row.get(7)?,
+        upstream_url: row.get(8)?,
+        upstream_headers: json_column(row, 9)?,
+        upstream_body: row.get(10)?,
+        response_headers: json_column(row, 11)?,
+        response_body: row.get(12)?,
+        truncated: row.get(13)?,
+        created_at: row.get(14)?,
+    })
+}
+
+impl Database {
+    /// 保存抓包记录，并删除超出 `retain` 条数的旧记录
+    pub fn insert_proxy_capture(
+        &self,
+        capture: &ProxyCapture,
+        retain: usize,
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO proxy_request_captures ({SELECT_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)"
+            ),
+            params![
+                capture.request_id,
+                capture.app_type,
+                capture.endpoint,
+                capture.provider_id,
+                capture.status_code,
+                capture.client_headers.to_string(),
+                capture.client_body,
+                capture.upstream_method,
+                capture.upstream_url,
+                capture.upstream_headers.to_string(),
+                capture.upstream_body,
+                capture.response_headers.to_string(),
+                capture.response_body,
+                capture.truncated,
+                capture.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM proxy_request_captures WHERE rowid NOT IN (
+                SELECT rowid FROM proxy_request_captures
+                ORDER BY created_at DESC, rowid DESC
+                LIMIT ?1
+            )",
+            params![retain.max(1) as i64],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 按 request_id 获取完整抓包记录
+    pub fn get_proxy_capture(&self, request_id: &str) -> Result<Option<ProxyCapture>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            &format!("SELECT {SELECT_COLUMNS} FROM proxy_request_captures WHERE request_id = ?1"),
+            params![request_id],
+            row_to_capture,
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 最近的抓包记录摘要（不含请求/响应体），按时间倒序
+    pub fn list_proxy_captures(&self, limit: usize) -> Result<Vec<ProxyCaptureSummary>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT request_id, app_type, endpoint, provider_id, status_code, truncated, created_at
+                 FROM proxy_request_captures
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT ?1",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let captures = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(ProxyCaptureSummary {
+                    request_id: row.get(0)?,
+                    app_type: row.get(1)?,
+                    endpoint: row.get(2)?,
+                    provider_id: row.get(3)?,
+                    status_code: row.get(4)?,
+                    truncated: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(captures)
+    }
+
+    /// 清空所有抓包记录，返回删除条数
+    pub fn clear_proxy_captures(&self) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM proxy_request_captures", [])
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
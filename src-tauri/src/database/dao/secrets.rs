@@ -0,0 +1,261 @@
+//! 供应商密钥保险库数据访问对象
+//!
+//! provider_secrets 表只保存密文与指纹；settings_config 中对应字段为 `ccs-vault:<id>` 引用。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::secret_vault::{self, ProviderSecret, SecretVaultConfig};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde_json::Value;
+
+const SECRET_VAULT_SETTING_KEY: &str = "secret_vault";
+
+fn row_to_secret(row: &Row<'_>) -> rusqlite::Result<ProviderSecret> {
+    Ok(ProviderSecret {
+        id: row.get(0)?,
+        provider_id: row.get(1)?,
+        app_type: row.get(2)?,
+        json_path: row.get(3)?,
+        ciphertext: row.get(4)?,
+        fingerprint: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn read_vault_config(conn: &Connection) -> Result<Option<SecretVaultConfig>, AppError> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![SECRET_VAULT_SETTING_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    value
+        .map(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析密钥保险库配置失败: {e}")))
+        })
+        .transpose()
+}
+
+/// 保存供应商前，把从 live 配置回填的明文密钥恢复为保险库引用（按指纹匹配）。
+/// 指纹以保险库密钥计算，保险库无法解锁时不恢复
+pub(crate) fn restore_vault_refs(
+    conn: &Connection,
+    app_type: &str,
+    provider_id: &str,
+    settings: &Value,
+) -> Result<Option<Value>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, provider_id, app_type, json_path, ciphertext, fingerprint, created_at
+             FROM provider_secrets WHERE app_type = ?1 AND provider_id = ?2",
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let secrets = stmt
+        .query_map(params![app_type, provider_id], row_to_secret)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if secrets.is_empty() {
+        return Ok(None);
+    }
+    let has_plaintext = secrets.iter().any(|secret| {
+        settings
+            .pointer(&secret.json_path)
+            .and_then(Value::as_str)
+            .is_some_and(|value| !value.starts_with(secret_vault::VAULT_REF_PREFIX))
+    });
+    if !has_plaintext {
+        return Ok(None);
+    }
+    let Some(config) = read_vault_config(conn)? else {
+        return Ok(None);
+    };
+    let Ok(key) = secret_vault::unlock(&config, None) else {
+        return Ok(None);
+    };
+
+    let mut restored = settings.clone();
+    let mut changed = false;
+    for secret in secrets {
+        let Some(slot) = restored.pointer_mut(&secret.json_path) else {
+            continue;
+        };
+        let matches = slot.as_str().is_some_and(|value| {
+            !value.starts_with(secret_vault::VAULT_REF_PREFIX)
+                && key.fingerprint(value) == secret.fingerprint
+        });
+        if matches {
+            *slot = Value::String(secret_vault::vault_ref(&secret.id));
+            changed = true;
+        }
+    }
+    Ok(changed.then_some(restored))
+}
+
+/// 保险库开启时准备要落库的 settings_config：先按指纹恢复已有引用，
+/// 其余新增或变更的明文密钥在同一连接（事务）内加密写入。
+/// 需要加密但保险库无法解锁时返回错误，拒绝以明文保存。
+pub(crate) fn seal_for_save(
+    conn: &Connection,
+    app_type: &str,
+    provider_id: &str,
+    settings: &Value,
+) -> Result<Option<Value>, AppError> {
+    let Some(config) = read_vault_config(conn)? else {
+        return Ok(None);
+    };
+    let restored = restore_vault_refs(conn, app_type, provider_id, settings)?;
+    let current = restored.as_ref().unwrap_or(settings);
+    if secret_vault::collect_plaintext_secrets(current).is_empty() {
+        return Ok(restored);
+    }
+
+    let key = secret_vault::unlock(&config, None)?;
+    let (sealed, secrets) = secret_vault::seal_settings(&key, app_type, provider_id, current)?;
+    for secret in &secrets {
+        insert_secret(conn, secret)?;
+    }
+    Ok(Some(sealed))
+}
+
+fn insert_secret(conn: &Connection, secret: &ProviderSecret) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO provider_secrets
+            (id, provider_id, app_type, json_path, ciphertext, fingerprint, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            secret.id,
+            secret.provider_id,
+            secret.app_type,
+            secret.json_path,
+            secret.ciphertext,
+            secret.fingerprint,
+            secret.created_at,
+        ],
+    )
+    .map_err(|e| AppError::Database(format!("写入加密密钥失败: {e}")))?;
+    Ok(())
+}
+
+impl Database {
+    /// 获取密钥保险库配置；未初始化时返回 None
+    pub fn get_secret_vault_config(&self) -> Result<Option<SecretVaultConfig>, AppError> {
+        let conn = lock_conn!(self.conn);
+        read_vault_config(&conn)
+    }
+
+    /// 保存密钥保险库配置
+    pub fn set_secret_vault_config(&self, config: &SecretVaultConfig) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化密钥保险库配置失败: {e}")))?;
+        self.set_setting(SECRET_VAULT_SETTING_KEY, &json)
+    }
+
+    /// 按 ID 获取加密密钥
+    pub fn get_provider_secret(&self, id: &str) -> Result<Option<ProviderSecret>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT id, provider_id, app_type, json_path, ciphertext, fingerprint, created_at
+             FROM provider_secrets WHERE id = ?1",
+            params![id],
+            row_to_secret,
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 把明文密钥按指纹替换为已有的保险库引用，便于与数据库中保存的配置比较
+    pub fn settings_with_vault_refs(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        settings: &Value,
+    ) -> Result<Value, AppError> {
+        let conn = lock_conn!(self.conn);
+        Ok(restore_vault_refs(&conn, app_type, provider_id, settings)?
+            .unwrap_or_else(|| settings.clone()))
+    }
+
+    /// 列出全部加密密钥
+    pub fn list_provider_secrets(&self) -> Result<Vec<ProviderSecret>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider_id, app_type, json_path, ciphertext, fingerprint, created_at
+                 FROM provider_secrets ORDER BY app_type, provider_id, json_path",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let secrets = stmt
+            .query_map([], row_to_secret)
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(secrets)
+    }
+
+    /// 在同一事务中写入密文并把供应商配置替换为引用后的版本
+    pub fn store_sealed_provider_settings(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        settings_config: &Value,
+        secrets: &[ProviderSecret],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for secret in secrets {
+            insert_secret(&tx, secret)?;
+        }
+        tx.execute(
+            "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+            params![
+                serde_json::to_string(settings_config).map_err(|e| AppError::Database(format!(
+                    "Failed to serialize settings_config: {e}"
+                )))?,
+                provider_id,
+                app_type,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 关闭保险库：写回明文配置，删除全部密文与保险库配置
+    pub fn disable_secret_vault(
+        &self,
+        revealed: &[(String, String, Value)],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (app_type, provider_id, settings_config) in revealed {
+            tx.execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![
+                    serde_json::to_string(settings_config).map_err(|e| AppError::Database(
+                        format!("Failed to serialize settings_config: {e}")
+                    ))?,
+                    provider_id,
+                    app_type,
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.execute("DELETE FROM provider_secrets", [])
+            .map_err(|e| AppError::Database(format!("清空加密密钥失败: {e}")))?;
+        tx.execute(
+            "DELETE FROM settings WHERE key = ?1",
+            params![SECRET_VAULT_SETTING_KEY],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
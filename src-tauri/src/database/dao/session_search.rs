@@ -0,0 +1,252 @@
+//! 会话全文搜索索引 DAO
+//!
+//! 索引按 source_path 关联会话元数据（session_search_sessions）与消息内容
+//! （session_search_fts），增量进度记录在 session_log_sync 中。
+
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::session_search::{SessionSearchHit, SessionSearchQuery};
+use crate::session_manager::{SessionMessage, SessionMeta};
+
+impl Database {
+    /// 已建立索引的会话 source_path 集合
+    pub fn indexed_session_search_paths(&self) -> Result<HashSet<String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT source_path FROM session_search_sessions")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(paths)
+    }
+
+    /// 写入单个会话的索引：更新元数据，追加 `start` 之后的消息，并记录同步进度
+    ///
+    /// `reset` 为 true 时先清空该会话已有的消息索引（会话被截断或重写时使用）。
+    pub fn index_session_messages(
+        &self,
+        session: &SessionMeta,
+        sync_key: &str,
+        marker: i64,
+        start: usize,
+        messages: &[SessionMessage],
+        reset: bool,
+    ) -> Result<usize, AppError> {
+        let Some(source_path) = session.source_path.as_deref() else {
+            return Err(AppError::InvalidInput(
+                "会话缺少 source_path，无法建立索引".to_string(),
+            ));
+        };
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO session_search_sessions
+             (source_path, provider_id, session_id, title, project_dir, created_at, last_active_at, resume_command)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                source_path,
+                session.provider_id,
+                session.session_id,
+                session.title,
+                session.project_dir,
+                session.created_at,
+                session.last_active_at,
+                session.resume_command,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if reset {
+            tx.execute(
+                "DELETE FROM session_search_fts WHERE source_path = ?1",
+                rusqlite::params![source_path],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        let mut inserted = 0usize;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO session_search_fts (content, role, source_path, msg_index, ts)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            for (index, message) in messages.iter().enumerate().skip(start) {
+                if message.content.trim().is_empty() {
+                    continue;
+                }
+                stmt.execute(rusqlite::params![
+                    message.content,
+                    message.role,
+                    source_path,
+                    index as i64,
+                    message.ts,
+                ])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+                inserted += 1;
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        tx.execute(
+            "INSERT OR REPLACE INTO session_log_sync (file_path, last_modified, last_line_offset, last_synced_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![sync_key, marker, messages.len() as i64, now],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(inserted)
+    }
+
+    /// 移除已不存在的会话索引；`provider_id` 为 None 时检查所有应用
+    ///
+    /// 返回被移除的会话数。
+    pub fn prune_session_search_index(
+        &self,
+        provider_id: Option<&str>,
+        live: &HashSet<String>,
+        sync_key_prefix: &str,
+    ) -> Result<usize, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let stale = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT source_path FROM session_search_sessions
+                     WHERE ?1 IS NULL OR provider_id = ?1",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let paths = stmt
+                .query_map(rusqlite::params![provider_id], |row| {
+                    row.get::<_, String>(0)
+                })
+                .map_err(|e| AppError::Database(e.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            paths
+                .into_iter()
+                .filter(|path| !live.contains(path))
+                .collect::<Vec<_>>()
+        };
+
+        for path in &stale {
+            for sql in [
+                "DELETE FROM session_search_fts WHERE source_path = ?1",
+                "DELETE FROM session_search_sessions WHERE source_path = ?1",
+            ] {
+                tx.execute(sql, rusqlite::params![path])
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            tx.execute(
+                "DELETE FROM session_log_sync WHERE file_path = ?1",
+                rusqlite::params![format!("{sync_key_prefix}{path}")],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(stale.len())
+    }
+
+    /// 全文搜索会话消息，按会话聚合，每个会话保留相关度最高的片段
+    pub fn search_session_messages(
+        &self,
+        match_expr: &str,
+        query: &SessionSearchQuery,
+    ) -> Result<Vec<SessionSearchHit>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let project_pattern = query
+            .project_dir
+            .as_deref()
+            .map(|dir| format!("%{}%", escape_like(dir)));
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.source_path, s.provider_id, s.session_id, s.title, s.project_dir,
+                        s.created_at, s.last_active_at, s.resume_command,
+                        session_search_fts.role, session_search_fts.msg_index,
+                        snippet(session_search_fts, 0, '«', '»', '…', 12)
+                 FROM session_search_fts
+                 JOIN session_search_sessions s ON s.source_path = session_search_fts.source_path
+                 WHERE session_search_fts MATCH ?1
+                   AND (?2 IS NULL OR s.provider_id = ?2)
+                   AND (?3 IS NULL OR s.project_dir LIKE ?3 ESCAPE '\\')
+                   AND (?4 IS NULL OR COALESCE(s.last_active_at, s.created_at) >= ?4)
+                   AND (?5 IS NULL OR COALESCE(s.last_active_at, s.created_at) < ?5)
+                 ORDER BY rank",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut rows = stmt
+            .query(rusqlite::params![
+                match_expr,
+                query.provider_id,
+                project_pattern,
+                query.since_ms,
+                query.until_ms,
+            ])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut hits: Vec<SessionSearchHit> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
+            let source_path: String = row.get(0).map_err(|e| AppError::Database(e.to_string()))?;
+            if let Some(&position) = positions.get(&source_path) {
+                hits[position].matches += 1;
+                continue;
+            }
+
+            let session = SessionMeta {
+                provider_id: row.get(1).map_err(|e| AppError::Database(e.to_string()))?,
+                session_id: row.get(2).map_err(|e| AppError::Database(e.to_string()))?,
+                title: row.get(3).map_err(|e| AppError::Database(e.to_string()))?,
+                summary: None,
+                project_dir: row.get(4).map_err(|e| AppError::Database(e.to_string()))?,
+                created_at: row.get(5).map_err(|e| AppError::Database(e.to_string()))?,
+                last_active_at: row.get(6).map_err(|e| AppError::Database(e.to_string()))?,
+                source_path: Some(source_path.clone()),
+                resume_command: row.get(7).map_err(|e| AppError::Database(e.to_string()))?,
+            };
+            let role: String = row.get(8).map_err(|e| AppError::Database(e.to_string()))?;
+            let message_index: i64 = row.get(9).map_err(|e| AppError::Database(e.to_string()))?;
+            let snippet: String = row.get(10).map_err(|e| AppError::Database(e.to_string()))?;
+
+            positions.insert(source_path, hits.len());
+            hits.push(SessionSearchHit {
+                session,
+                matches: 1,
+                role,
+                message_index: message_index.max(0) as usize,
+                snippet,
+            });
+        }
+
+        hits.truncate(query.limit);
+        Ok(hits)
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -272,6 +272,73 @@ impl Database {
         self.set_setting("copilot_optimizer_config", &json)
     }
 
+    // --- 请求抓包配置 ---
+
+    /// 获取请求抓包配置，不存在时返回默认值（关闭）
+    pub fn get_capture_config(&self) -> Result<crate::proxy::types::CaptureConfig, AppError> {
+        match self.get_setting("capture_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析抓包配置失败: {e}"))),
+            None => Ok(crate::proxy::types::CaptureConfig::default()),
+        }
+    }
+
+    /// 更新请求抓包配置
+    pub fn set_capture_config(
+        &self,
+        config: &crate::proxy::types::CaptureConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化抓包配置失败: {e}")))?;
+        self.set_setting("capture_config", &json)
+    }
+
+    // --- 消费限额配置 ---
+
+    /// 获取消费限额配置，不存在时返回默认值
+    pub fn get_spend_limit_config(
+        &self,
+    ) -> Result<crate::proxy::types::SpendLimitConfig, AppError> {
+        match self.get_setting("spend_limit_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析消费限额配置失败: {e}"))),
+            None => Ok(crate::proxy::types::SpendLimitConfig::default()),
+        }
+    }
+
+    /// 更新消费限额配置
+    pub fn set_spend_limit_config(
+        &self,
+        config: &crate::proxy::types::SpendLimitConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化消费限额配置失败: {e}")))?;
+        self.set_setting("spend_limit_config", &json)
+    }
+
+    // --- 代理客户端令牌 ---
+
+    /// 获取代理客户端令牌配置，不存在时返回默认值（不校验令牌）
+    pub fn get_client_auth_config(
+        &self,
+    ) -> Result<crate::proxy::types::ClientAuthConfig, AppError> {
+        match self.get_setting("client_auth_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析客户端令牌配置失败: {e}"))),
+            None => Ok(crate::proxy::types::ClientAuthConfig::default()),
+        }
+    }
+
+    /// 更新代理客户端令牌配置
+    pub fn set_client_auth_config(
+        &self,
+        config: &crate::proxy::types::ClientAuthConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化客户端令牌配置失败: {e}")))?;
+        self.set_setting("client_auth_config", &json)
+    }
+
     // --- 日志配置 ---
 
     /// 获取日志配置
@@ -0,0 +1,98 @@
+//! 共享提示词数据访问对象
+//!
+//! 提供跨应用共享提示词（SharedPrompt）的 CRUD 操作。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::prompt::{PromptApps, SharedPrompt};
+use indexmap::IndexMap;
+use rusqlite::params;
+
+impl Database {
+    /// 获取所有共享提示词
+    pub fn get_shared_prompts(&self) -> Result<IndexMap<String, SharedPrompt>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, content, description, enabled_claude, enabled_codex,
+                    enabled_gemini, enabled_opencode, enabled_hermes, enabled_openclaw,
+                    overrides, created_at, updated_at
+             FROM shared_prompts
+             ORDER BY created_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let prompt_iter = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let overrides: String = row.get(10)?;
+
+                Ok((
+                    id.clone(),
+                    SharedPrompt {
+                        id,
+                        name: row.get(1)?,
+                        content: row.get(2)?,
+                        description: row.get(3)?,
+                        apps: PromptApps {
+                            claude: row.get(4)?,
+                            codex: row.get(5)?,
+                            gemini: row.get(6)?,
+                            opencode: row.get(7)?,
+                            hermes: row.get(8)?,
+                            openclaw: row.get(9)?,
+                        },
+                        overrides: serde_json::from_str(&overrides).unwrap_or_default(),
+                        created_at: row.get(11)?,
+                        updated_at: row.get(12)?,
+                    },
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut prompts = IndexMap::new();
+        for prompt_res in prompt_iter {
+            let (id, prompt) = prompt_res.map_err(|e| AppError::Database(e.to_string()))?;
+            prompts.insert(id, prompt);
+        }
+        Ok(prompts)
+    }
+
+    /// 保存共享提示词
+    pub fn save_shared_prompt(&self, prompt: &SharedPrompt) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO shared_prompts (
+                id, name, content, description, enabled_claude, enabled_codex, enabled_gemini,
+                enabled_opencode, enabled_hermes, enabled_openclaw, overrides, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                prompt.id,
+                prompt.name,
+                prompt.content,
+                prompt.description,
+                prompt.apps.claude,
+                prompt.apps.codex,
+                prompt.apps.gemini,
+                prompt.apps.opencode,
+                prompt.apps.hermes,
+                prompt.apps.openclaw,
+                serde_json::to_string(&prompt.overrides).map_err(|e| AppError::Database(
+                    format!("Failed to serialize overrides: {e}")
+                ))?,
+                prompt.created_at,
+                prompt.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除共享提示词
+    pub fn delete_shared_prompt(&self, id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM shared_prompts WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
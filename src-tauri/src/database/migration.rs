@@ -164,8 +164,8 @@ impl Database {
             for (id, prompt) in prompts_map {
                 tx.execute(
                         "INSERT OR REPLACE INTO prompts (
-                            id, app_type, name, content, description, enabled, created_at, updated_at
-                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                            id, app_type, name, content, description, enabled, created_at, updated_at, fragments
+                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                         params![
                             id,
                             app_type,
@@ -175,6 +175,9 @@ impl Database {
                             prompt.enabled,
                             prompt.created_at,
                             prompt.updated_at,
+                            serde_json::to_string(&prompt.fragments).map_err(|e| {
+                                AppError::Database(format!("Failed to serialize fragments: {e}"))
+                            })?,
                         ],
                     )
                     .map_err(|e| AppError::Database(format!("Migrate prompt failed: {e}")))?;
@@ -69,6 +69,7 @@ impl Database {
         conn.execute("CREATE TABLE IF NOT EXISTS prompts (
             id TEXT NOT NULL, app_type TEXT NOT NULL, name TEXT NOT NULL, content TEXT NOT NULL,
             description TEXT, enabled BOOLEAN NOT NULL DEFAULT 1, created_at INTEGER, updated_at INTEGER,
+            fragments TEXT NOT NULL DEFAULT '[]',
             PRIMARY KEY (id, app_type)
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -126,6 +127,7 @@ impl Database {
             circuit_min_requests INTEGER NOT NULL DEFAULT 10,
             default_cost_multiplier TEXT NOT NULL DEFAULT '1',
             pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            routing_strategy TEXT NOT NULL DEFAULT 'ordered',
             created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -188,7 +190,7 @@ impl Database {
             duration_ms INTEGER, status_code INTEGER NOT NULL, error_message TEXT, session_id TEXT,
             provider_type TEXT, is_streaming INTEGER NOT NULL DEFAULT 0,
             cost_multiplier TEXT NOT NULL DEFAULT '1.0', created_at INTEGER NOT NULL,
-            data_source TEXT NOT NULL DEFAULT 'proxy'
+            data_source TEXT NOT NULL DEFAULT 'proxy', client_token TEXT
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
         Self::create_request_logs_indexes_if_supported(conn)?;
@@ -278,6 +280,29 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 模型路由规则表（按 sort_index 顺序匹配）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS model_routing_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT,
+                provider_id TEXT,
+                match_type TEXT NOT NULL DEFAULT 'exact',
+                pattern TEXT NOT NULL,
+                target_model TEXT,
+                pin_provider_id TEXT,
+                sort_index INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Self::create_session_search_tables(conn)?;
+        Self::create_proxy_request_captures_table(conn)?;
+        Self::create_provider_secrets_table(conn)?;
+        Self::create_shared_prompts_table(conn)?;
+
         // 尝试添加 live_takeover_active 列到 proxy_config 表
         let _ = conn.execute(
             "ALTER TABLE proxy_config ADD COLUMN live_takeover_active INTEGER NOT NULL DEFAULT 0",
@@ -427,6 +452,46 @@ impl Database {
                         Self::migrate_v10_to_v11(conn)?;
                         Self::set_user_version(conn, 11)?;
                     }
+                    11 => {
+                        log::info!("迁移数据库从 v11 到 v12（故障转移队列路由策略）");
+                        Self::migrate_v11_to_v12(conn)?;
+                        Self::set_user_version(conn, 12)?;
+                    }
+                    12 => {
+                        log::info!("迁移数据库从 v12 到 v13（模型路由规则）");
+                        Self::migrate_v12_to_v13(conn)?;
+                        Self::set_user_version(conn, 13)?;
+                    }
+                    13 => {
+                        log::info!("迁移数据库从 v13 到 v14（会话全文搜索索引）");
+                        Self::migrate_v13_to_v14(conn)?;
+                        Self::set_user_version(conn, 14)?;
+                    }
+                    14 => {
+                        log::info!("迁移数据库从 v14 到 v15（代理请求抓包）");
+                        Self::migrate_v14_to_v15(conn)?;
+                        Self::set_user_version(conn, 15)?;
+                    }
+                    15 => {
+                        log::info!("迁移数据库从 v15 到 v16（代理客户端令牌用量归属）");
+                        Self::migrate_v15_to_v16(conn)?;
+                        Self::set_user_version(conn, 16)?;
+                    }
+                    16 => {
+                        log::info!("迁移数据库从 v16 到 v17（供应商密钥加密存储）");
+                        Self::migrate_v16_to_v17(conn)?;
+                        Self::set_user_version(conn, 17)?;
+                    }
+                    17 => {
+                        log::info!("迁移数据库从 v17 到 v18（提示词片段）");
+                        Self::migrate_v17_to_v18(conn)?;
+                        Self::set_user_version(conn, 18)?;
+                    }
+                    18 => {
+                        log::info!("迁移数据库从 v18 到 v19（跨应用共享提示词）");
+                        Self::migrate_v18_to_v19(conn)?;
+                        Self::set_user_version(conn, 19)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -759,6 +824,7 @@ impl Database {
             circuit_min_requests INTEGER NOT NULL DEFAULT 10,
             default_cost_multiplier TEXT NOT NULL DEFAULT '1',
             pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            routing_strategy TEXT NOT NULL DEFAULT 'ordered',
             created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )", [])?;
 
@@ -1347,6 +1413,201 @@ impl Database {
         Ok(())
     }
 
+    /// v11 -> v12 迁移：proxy_config 增加路由策略列
+    fn migrate_v11_to_v12(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "routing_strategy",
+                "TEXT NOT NULL DEFAULT 'ordered'",
+            )?;
+        }
+
+        log::info!("v11 -> v12 迁移完成：已添加 proxy_config.routing_strategy");
+        Ok(())
+    }
+
+    /// v12 -> v13 迁移：新增模型路由规则表
+    fn migrate_v12_to_v13(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS model_routing_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT,
+                provider_id TEXT,
+                match_type TEXT NOT NULL DEFAULT 'exact',
+                pattern TEXT NOT NULL,
+                target_model TEXT,
+                pin_provider_id TEXT,
+                sort_index INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 model_routing_rules 表失败: {e}")))?;
+
+        log::info!("v12 -> v13 迁移完成：已添加 model_routing_rules 表");
+        Ok(())
+    }
+
+    /// v13 -> v14 迁移：新增会话全文搜索索引
+    fn migrate_v13_to_v14(conn: &Connection) -> Result<(), AppError> {
+        Self::create_session_search_tables(conn)?;
+        log::info!("v13 -> v14 迁移完成：已添加 session_search_sessions / session_search_fts 表");
+        Ok(())
+    }
+
+    /// 会话搜索索引：会话元数据表 + 消息内容 FTS5 表（按 source_path 关联）
+    fn create_session_search_tables(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_search_sessions (
+                source_path TEXT PRIMARY KEY,
+                provider_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                title TEXT,
+                project_dir TEXT,
+                created_at INTEGER,
+                last_active_at INTEGER,
+                resume_command TEXT
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 session_search_sessions 表失败: {e}")))?;
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS session_search_fts USING fts5(
+                content,
+                role UNINDEXED,
+                source_path UNINDEXED,
+                msg_index UNINDEXED,
+                ts UNINDEXED,
+                tokenize = 'unicode61 remove_diacritics 2'
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 session_search_fts 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// v14 -> v15 迁移：新增代理请求抓包表
+    fn migrate_v14_to_v15(conn: &Connection) -> Result<(), AppError> {
+        Self::create_proxy_request_captures_table(conn)?;
+        log::info!("v14 -> v15 迁移完成：已添加 proxy_request_captures 表");
+        Ok(())
+    }
+
+    /// v15 -> v16 迁移：proxy_request_logs 记录发起请求的客户端令牌名称
+    fn migrate_v15_to_v16(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_request_logs")? {
+            Self::add_column_if_missing(conn, "proxy_request_logs", "client_token", "TEXT")?;
+        }
+        log::info!("v15 -> v16 迁移完成：proxy_request_logs 已添加 client_token 列");
+        Ok(())
+    }
+
+    /// v16 -> v17 迁移：新增供应商加密密钥表
+    fn migrate_v16_to_v17(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_secrets_table(conn)?;
+        log::info!("v16 -> v17 迁移完成：已添加 provider_secrets 表");
+        Ok(())
+    }
+
+    /// v17 -> v18 迁移：提示词记录按顺序引用的片段
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "prompts")? {
+            Self::add_column_if_missing(
+                conn,
+                "prompts",
+                "fragments",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )?;
+        }
+        log::info!("v17 -> v18 迁移完成：prompts 已添加 fragments 列");
+        Ok(())
+    }
+
+    /// v18 -> v19 迁移：新增跨应用共享提示词表
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<(), AppError> {
+        Self::create_shared_prompts_table(conn)?;
+        log::info!("v18 -> v19 迁移完成：已添加 shared_prompts 表");
+        Ok(())
+    }
+
+    /// 跨应用共享提示词：按应用的启用开关与正文覆盖
+    fn create_shared_prompts_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS shared_prompts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                description TEXT,
+                enabled_claude BOOLEAN NOT NULL DEFAULT 0,
+                enabled_codex BOOLEAN NOT NULL DEFAULT 0,
+                enabled_gemini BOOLEAN NOT NULL DEFAULT 0,
+                enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
+                enabled_hermes BOOLEAN NOT NULL DEFAULT 0,
+                enabled_openclaw BOOLEAN NOT NULL DEFAULT 0,
+                overrides TEXT NOT NULL DEFAULT '{}',
+                created_at INTEGER,
+                updated_at INTEGER
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 shared_prompts 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 供应商密钥保险库：settings_config 中只保留引用，密文与指纹单独存放
+    fn create_provider_secrets_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_secrets (
+                id TEXT PRIMARY KEY,
+                provider_id TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                json_path TEXT NOT NULL,
+                ciphertext TEXT NOT NULL,
+                fingerprint TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE (app_type, provider_id, json_path)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 provider_secrets 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 代理请求抓包：客户端请求、转换后的上游请求与上游响应（请求头已脱敏）
+    fn create_proxy_request_captures_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS proxy_request_captures (
+                request_id TEXT PRIMARY KEY,
+                app_type TEXT NOT NULL,
+                endpoint TEXT NOT NULL,
+                provider_id TEXT,
+                status_code INTEGER,
+                client_headers TEXT NOT NULL DEFAULT '{}',
+                client_body TEXT NOT NULL DEFAULT '',
+                upstream_method TEXT,
+                upstream_url TEXT,
+                upstream_headers TEXT NOT NULL DEFAULT '{}',
+                upstream_body TEXT,
+                response_headers TEXT NOT NULL DEFAULT '{}',
+                response_body TEXT NOT NULL DEFAULT '',
+                truncated INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 proxy_request_captures 表失败: {e}")))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_proxy_request_captures_created_at
+             ON proxy_request_captures(created_at)",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 proxy_request_captures 索引失败: {e}")))?;
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
@@ -2271,6 +2532,7 @@ impl Database {
             ("cost_multiplier", "TEXT NOT NULL DEFAULT '1.0'"),
             ("created_at", "INTEGER NOT NULL DEFAULT 0"),
             ("data_source", "TEXT NOT NULL DEFAULT 'proxy'"),
+            ("client_token", "TEXT"),
         ] {
             Self::add_column_if_missing(conn, "proxy_request_logs", column, definition)?;
         }
@@ -1363,6 +1363,190 @@ fn schema_migration_v10_to_v11_preserves_rollup_rows_with_empty_new_dimensions()
     );
 }
 
+#[test]
+fn schema_migration_v11_to_v12_adds_ordered_routing_strategy() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute_batch(
+        r#"
+        CREATE TABLE proxy_config (
+            app_type TEXT PRIMARY KEY,
+            enabled INTEGER NOT NULL DEFAULT 0,
+            auto_failover_enabled INTEGER NOT NULL DEFAULT 0
+        );
+        INSERT INTO proxy_config (app_type) VALUES ('claude');
+        "#,
+    )
+    .expect("seed v11 schema");
+
+    Database::set_user_version(&conn, 11).expect("set user_version=11");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v12 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    let strategy: String = conn
+        .query_row(
+            "SELECT routing_strategy FROM proxy_config WHERE app_type = 'claude'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("read routing strategy");
+    assert_eq!(strategy, "ordered");
+}
+
+#[test]
+fn schema_migration_v12_to_v13_creates_model_routing_rules() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 12).expect("set user_version=12");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v13 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(Database::table_exists(&conn, "model_routing_rules").expect("check table"));
+}
+
+#[test]
+fn schema_migration_v13_to_v14_creates_session_search_index() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 13).expect("set user_version=13");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v14 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(Database::table_exists(&conn, "session_search_sessions").expect("check table"));
+    assert!(Database::table_exists(&conn, "session_search_fts").expect("check fts table"));
+}
+
+#[test]
+fn schema_migration_v14_to_v15_creates_proxy_request_captures() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 14).expect("set user_version=14");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v15 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(Database::table_exists(&conn, "proxy_request_captures").expect("check table"));
+}
+
+#[test]
+fn schema_migration_v15_to_v16_adds_client_token_column() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute(
+        "CREATE TABLE proxy_request_logs (
+            request_id TEXT PRIMARY KEY, provider_id TEXT NOT NULL, app_type TEXT NOT NULL,
+            model TEXT NOT NULL, latency_ms INTEGER NOT NULL, status_code INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .expect("create legacy request logs");
+    Database::set_user_version(&conn, 15).expect("set user_version=15");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v16 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(
+        Database::has_column(&conn, "proxy_request_logs", "client_token").expect("check column")
+    );
+}
+
+#[test]
+fn schema_migration_v16_to_v17_creates_provider_secrets() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 16).expect("set user_version=16");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v17 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(Database::table_exists(&conn, "provider_secrets").expect("check table"));
+}
+
+#[test]
+fn schema_migration_v18_to_v19_creates_shared_prompts() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 18).expect("set user_version=18");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply v19 migration");
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+    assert!(Database::table_exists(&conn, "shared_prompts").expect("check table"));
+}
+
+fn routing_rule(pattern: &str, app_type: Option<&str>) -> crate::proxy::types::ModelRoutingRule {
+    crate::proxy::types::ModelRoutingRule {
+        id: 0,
+        app_type: app_type.map(String::from),
+        provider_id: None,
+        match_type: crate::proxy::types::ModelMatchType::Glob,
+        pattern: pattern.to_string(),
+        target_model: Some(format!("{pattern}-target")),
+        pin_provider_id: None,
+        sort_index: 0,
+        enabled: true,
+        created_at: 0,
+    }
+}
+
+#[test]
+fn model_routing_rules_keep_order_and_app_scope() {
+    let db = Database::memory().expect("create memory database");
+    let first = db
+        .add_model_routing_rule(&routing_rule("*haiku*", None))
+        .expect("add global rule");
+    let second = db
+        .add_model_routing_rule(&routing_rule("gpt-*", Some("codex")))
+        .expect("add codex rule");
+    let third = db
+        .add_model_routing_rule(&routing_rule("*opus*", Some("claude")))
+        .expect("add claude rule");
+
+    let claude = db
+        .list_model_routing_rules(Some("claude"))
+        .expect("list claude rules");
+    assert_eq!(
+        claude.iter().map(|rule| rule.id).collect::<Vec<_>>(),
+        vec![first, third]
+    );
+
+    db.move_model_routing_rule(third, 0)
+        .expect("move rule to top");
+    let all = db.list_model_routing_rules(None).expect("list all rules");
+    assert_eq!(
+        all.iter().map(|rule| rule.id).collect::<Vec<_>>(),
+        vec![third, first, second]
+    );
+
+    assert!(db.delete_model_routing_rule(first).expect("delete rule"));
+    assert!(!db.delete_model_routing_rule(first).expect("delete twice"));
+    assert_eq!(db.list_model_routing_rules(None).expect("list").len(), 2);
+}
+
+#[test]
+fn model_routing_rules_reject_invalid_regex() {
+    let db = Database::memory().expect("create memory database");
+    let mut rule = routing_rule("(unclosed", None);
+    rule.match_type = crate::proxy::types::ModelMatchType::Regex;
+
+    let err = db
+        .add_model_routing_rule(&rule)
+        .expect_err("invalid regex should be rejected");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    assert!(db.replace_model_routing_rules(&[rule]).is_err());
+}
+
 #[test]
 fn schema_migration_v9_adds_hermes_columns() {
     let conn = Connection::open_in_memory().expect("open memory db");
@@ -1852,6 +2036,7 @@ fn json_migration_imports_opencode_and_openclaw_prompts() {
             enabled: true,
             created_at: Some(1),
             updated_at: Some(2),
+            fragments: Vec::new(),
         },
     );
     config.prompts.openclaw.prompts.insert(
@@ -1864,6 +2049,7 @@ fn json_migration_imports_opencode_and_openclaw_prompts() {
             enabled: false,
             created_at: Some(3),
             updated_at: Some(4),
+            fragments: Vec::new(),
         },
     );
 
@@ -2391,3 +2577,83 @@ fn should_auto_extract_config_snippet_respects_snippet_and_cleared_flag() {
         .should_auto_extract_config_snippet("claude")
         .expect("gate after unset"));
 }
+
+#[test]
+fn replace_failover_queue_sets_members_and_order_atomically() {
+    let db = Database::memory().expect("create memory db");
+    for id in ["a", "b", "c"] {
+        db.save_provider(
+            "claude",
+            &Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None),
+        )
+        .expect("save provider");
+    }
+    db.add_to_failover_queue("claude", "a").expect("queue a");
+    db.add_to_failover_queue("claude", "b").expect("queue b");
+
+    db.replace_failover_queue("claude", &["c".to_string(), "a".to_string()])
+        .expect("replace queue");
+    let queue = db.get_failover_queue("claude").expect("load queue");
+    let ids = queue
+        .iter()
+        .map(|item| item.provider_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["c", "a"]);
+
+    let err = db
+        .replace_failover_queue("claude", &["a".to_string(), "missing".to_string()])
+        .expect_err("unknown provider should be rejected");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    let ids_after_error = db
+        .get_failover_queue("claude")
+        .expect("load queue")
+        .into_iter()
+        .map(|item| item.provider_id)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids_after_error,
+        vec!["c", "a"],
+        "failed replace leaves queue untouched"
+    );
+}
+
+#[test]
+fn proxy_captures_keep_only_newest_entries() {
+    use crate::proxy::capture::ProxyCapture;
+
+    let db = Database::memory().expect("create memory database");
+    for (index, request_id) in ["req-1", "req-2", "req-3"].iter().enumerate() {
+        let capture = ProxyCapture {
+            request_id: request_id.to_string(),
+            app_type: "claude".to_string(),
+            endpoint: "/v1/messages".to_string(),
+            status_code: Some(200),
+            client_headers: json!({"content-type": "application/json"}),
+            client_body: "{}".to_string(),
+            created_at: 1_700_000_000 + index as i64,
+            ..Default::default()
+        };
+        db.insert_proxy_capture(&capture, 2)
+            .expect("insert capture");
+    }
+
+    let listed = db.list_proxy_captures(10).expect("list captures");
+    let ids: Vec<_> = listed.iter().map(|c| c.request_id.as_str()).collect();
+    assert_eq!(ids, vec!["req-3", "req-2"]);
+
+    let stored = db
+        .get_proxy_capture("req-2")
+        .expect("get capture")
+        .expect("capture exists");
+    assert_eq!(stored.client_headers["content-type"], "application/json");
+    assert!(db
+        .get_proxy_capture("req-1")
+        .expect("get capture")
+        .is_none());
+
+    assert_eq!(db.clear_proxy_captures().expect("clear captures"), 2);
+    assert!(db
+        .list_proxy_captures(10)
+        .expect("list captures")
+        .is_empty());
+}
@@ -72,6 +72,7 @@ pub fn import_prompt_from_deeplink(
         enabled: false, // Always start as disabled, will be enabled later if needed
         created_at: Some(timestamp),
         updated_at: Some(timestamp),
+        fragments: Vec::new(),
     };
 
     // Save using PromptService
@@ -52,12 +52,18 @@ pub fn read_mcp_json() -> Result<Option<String>, AppError> {
 
 /// 读取 Gemini settings.json 中的 mcpServers 映射
 pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>, AppError> {
-    let path = user_config_path();
+    read_mcp_servers_map_at(&user_config_path())
+}
+
+/// 读取指定 settings.json（如项目级 `.gemini/settings.json`）中的 mcpServers 映射
+pub fn read_mcp_servers_map_at(
+    path: &Path,
+) -> Result<std::collections::HashMap<String, Value>, AppError> {
     if !path.exists() {
         return Ok(std::collections::HashMap::new());
     }
 
-    let root = read_json_value(&path)?;
+    let root = read_json_value(path)?;
     let mut servers: std::collections::HashMap<String, Value> = std::collections::HashMap::new();
     let Some(obj) = root.get("mcpServers").and_then(|v| v.as_object()) else {
         return Ok(servers);
@@ -96,9 +102,16 @@ pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>
 pub fn set_mcp_servers_map(
     servers: &std::collections::HashMap<String, Value>,
 ) -> Result<(), AppError> {
-    let path = user_config_path();
+    set_mcp_servers_map_at(&user_config_path(), servers)
+}
+
+/// 将 mcpServers 映射写入指定 settings.json（如项目级 `.gemini/settings.json`），其他字段保持不变
+pub fn set_mcp_servers_map_at(
+    path: &Path,
+    servers: &std::collections::HashMap<String, Value>,
+) -> Result<(), AppError> {
     let mut root = if path.exists() {
-        read_json_value(&path)?
+        read_json_value(path)?
     } else {
         serde_json::json!({})
     };
@@ -188,10 +201,10 @@ pub fn set_mcp_servers_map(
     {
         let obj = root
             .as_object_mut()
-            .ok_or_else(|| AppError::Config("~/.gemini/settings.json 根必须是对象".into()))?;
+            .ok_or_else(|| AppError::Config(format!("{} 根必须是对象", path.display())))?;
         obj.insert("mcpServers".into(), Value::Object(out));
     }
 
-    write_json_value(&path, &root)?;
+    write_json_value(path, &root)?;
     Ok(())
 }
@@ -21,10 +21,12 @@ mod mcp;
 mod openclaw_config;
 mod opencode_config;
 mod prompt;
+mod prompt_block;
 mod prompt_files;
 mod provider;
 mod provider_defaults;
 mod proxy;
+mod secret_vault;
 mod services;
 mod session_manager;
 mod settings;
@@ -65,11 +67,12 @@ pub use mcp::{
 pub use provider::{Provider, ProviderMeta, UsageScript};
 pub use proxy::{ProxyConfig, ProxyServerInfo, ProxyStatus};
 pub use services::{
-    AuthService, ConfigService, CredentialStatus, EndpointLatency, ExtraUsage, HealthStatus,
-    ImportSkillSelection, ManagedAuthAccount, ManagedAuthDeviceCodeResponse, ManagedAuthStatus,
-    McpService, PromptService, ProviderService, ProxyService, QuotaTier, SkillService,
-    SpeedtestService, StreamCheckConfig, StreamCheckResult, StreamCheckService, SubscriptionQuota,
-    SyncDecision, WebDavSyncService, WebDavSyncSummary,
+    AuthService, ConfigService, CredentialStatus, DeclarativeConfig, DeclarativeService,
+    EndpointLatency, ExtraUsage, HealthStatus, ImportSkillSelection, ManagedAuthAccount,
+    ManagedAuthDeviceCodeResponse, ManagedAuthStatus, McpService, ProjectProfile, ProjectService,
+    PromptService, ProviderService, ProxyService, QuotaTier, SkillService, SpeedtestService,
+    StreamCheckConfig, StreamCheckResult, StreamCheckService, SubscriptionQuota, SyncDecision,
+    WebDavSyncService, WebDavSyncSummary,
 };
 pub use settings::{
     get_enable_claude_plugin_integration, get_skip_claude_onboarding, get_webdav_sync_settings,
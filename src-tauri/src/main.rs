@@ -68,6 +68,7 @@ fn run(cli: Cli) -> Result<(), AppError> {
         Some(Commands::Skills(cmd)) => cc_switch_lib::cli::commands::skills::execute(cmd, cli.app),
         Some(Commands::Config(cmd)) => cc_switch_lib::cli::commands::config::execute(cmd, cli.app),
         Some(Commands::Proxy(cmd)) => cc_switch_lib::cli::commands::proxy::execute(cmd, cli.app),
+        Some(Commands::Usage(cmd)) => cc_switch_lib::cli::commands::usage::execute(cmd, cli.app),
         Some(Commands::Settings(cmd)) => cc_switch_lib::cli::commands::settings::execute(cmd),
         Some(Commands::Failover(cmd)) => {
             cc_switch_lib::cli::commands::failover::execute(cmd, cli.app)
@@ -84,6 +85,10 @@ fn run(cli: Cli) -> Result<(), AppError> {
         Some(Commands::Deeplink(cmd)) => {
             cc_switch_lib::cli::commands::deeplink::execute(cmd, cli.app)
         }
+        Some(Commands::Apply(cmd)) => cc_switch_lib::cli::commands::apply::execute(cmd, cli.app),
+        Some(Commands::Project(cmd)) => {
+            cc_switch_lib::cli::commands::project::execute(cmd, cli.app)
+        }
         Some(Commands::Update(cmd)) => cc_switch_lib::cli::commands::update::execute(cmd),
         Some(Commands::Completions(cmd)) => cc_switch_lib::cli::commands::completions::execute(cmd),
         Some(Commands::Internal(cmd)) => cc_switch_lib::cli::commands::internal::execute(cmd),
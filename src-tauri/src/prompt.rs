@@ -1,5 +1,8 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::app_config::AppType;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Prompt {
     pub id: String,
@@ -13,4 +16,86 @@ pub struct Prompt {
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<i64>,
+    /// 按顺序拼接在正文之前的片段（同一应用下其他提示词的 ID）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<String>,
+}
+
+/// 共享提示词启用的应用，与 MCP 服务器的 per-app 开关对应
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptApps {
+    #[serde(default)]
+    pub claude: bool,
+    #[serde(default)]
+    pub codex: bool,
+    #[serde(default)]
+    pub gemini: bool,
+    #[serde(default)]
+    pub opencode: bool,
+    #[serde(default)]
+    pub hermes: bool,
+    #[serde(default)]
+    pub openclaw: bool,
+}
+
+impl PromptApps {
+    /// 检查指定应用是否启用
+    pub fn is_enabled_for(&self, app: &AppType) -> bool {
+        match app {
+            AppType::Claude => self.claude,
+            AppType::Codex => self.codex,
+            AppType::Gemini => self.gemini,
+            AppType::OpenCode => self.opencode,
+            AppType::Hermes => self.hermes,
+            AppType::OpenClaw => self.openclaw,
+        }
+    }
+
+    /// 设置指定应用的启用状态
+    pub fn set_enabled_for(&mut self, app: &AppType, enabled: bool) {
+        match app {
+            AppType::Claude => self.claude = enabled,
+            AppType::Codex => self.codex = enabled,
+            AppType::Gemini => self.gemini = enabled,
+            AppType::OpenCode => self.opencode = enabled,
+            AppType::Hermes => self.hermes = enabled,
+            AppType::OpenClaw => self.openclaw = enabled,
+        }
+    }
+
+    /// 获取所有启用的应用列表
+    pub fn enabled_apps(&self) -> Vec<AppType> {
+        AppType::all()
+            .filter(|app| self.is_enabled_for(app))
+            .collect()
+    }
+}
+
+/// 跨应用共享的提示词：每个启用的应用下维护一份同 ID 的提示词副本，编辑时一并同步
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedPrompt {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub apps: PromptApps,
+    /// 按应用覆盖的正文（键为应用 ID），未覆盖的应用使用共享正文
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub overrides: IndexMap<String, String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl SharedPrompt {
+    /// 指定应用使用的正文：有覆盖时取覆盖内容
+    pub fn content_for(&self, app: &AppType) -> &str {
+        self.overrides
+            .get(app.as_str())
+            .map(String::as_str)
+            .unwrap_or(&self.content)
+    }
 }
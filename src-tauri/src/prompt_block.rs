@@ -0,0 +1,329 @@
+//! 提示词托管区块
+//!
+//! 托管区块模式下，cc-switch 只改写提示词文件中 [`BLOCK_BEGIN`] 与 [`BLOCK_END`]
+//! 之间的内容，区块外由用户编写的内容保持不变。区块内容被手动修改时，
+//! 以上次写入的版本为基准，与 cc-switch 的新版本做按行的三方合并。
+
+use crate::error::AppError;
+
+pub const BLOCK_BEGIN: &str = "<!-- cc-switch:begin -->";
+pub const BLOCK_END: &str = "<!-- cc-switch:end -->";
+
+const CONFLICT_LIVE: &str = "<<<<<<< live";
+const CONFLICT_SEPARATOR: &str = "=======";
+const CONFLICT_DB: &str = ">>>>>>> cc-switch";
+
+/// 提示词文件按托管区块拆分后的位置（字节偏移）
+struct BlockSpan {
+    /// 开始标记行的起始位置
+    start: usize,
+    /// 区块内容的起止位置
+    inner: (usize, usize),
+    /// 结束标记行（含换行）之后的位置
+    end: usize,
+}
+
+fn line_spans(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    text.split_inclusive('\n').map(move |line| {
+        let start = offset;
+        offset += line.len();
+        (start, line)
+    })
+}
+
+fn find_block(file: &str) -> Result<Option<BlockSpan>, AppError> {
+    let mut begin: Option<(usize, usize)> = None;
+    let mut span = None;
+
+    for (start, line) in line_spans(file) {
+        let marker = line.trim();
+        if marker == BLOCK_BEGIN {
+            if begin.is_some() || span.is_some() {
+                return Err(invalid_markers("托管区块开始标记重复出现"));
+            }
+            begin = Some((start, start + line.len()));
+        } else if marker == BLOCK_END {
+            let Some((block_start, inner_start)) = begin.take() else {
+                return Err(invalid_markers("托管区块结束标记缺少对应的开始标记"));
+            };
+            span = Some(BlockSpan {
+                start: block_start,
+                inner: (inner_start, start),
+                end: start + line.len(),
+            });
+        }
+    }
+
+    if begin.is_some() {
+        return Err(invalid_markers("托管区块缺少结束标记"));
+    }
+    Ok(span)
+}
+
+fn invalid_markers(reason: &str) -> AppError {
+    AppError::InvalidInput(format!(
+        "{reason}（{BLOCK_BEGIN} … {BLOCK_END}），请手动修正提示词文件"
+    ))
+}
+
+/// 读取托管区块内容；文件中没有区块时返回 None
+pub fn extract_block(file: &str) -> Result<Option<String>, AppError> {
+    Ok(find_block(file)?.map(|span| {
+        file[span.inner.0..span.inner.1]
+            .trim_end_matches('\n')
+            .to_string()
+    }))
+}
+
+/// 用 `content` 替换托管区块；文件中没有区块时追加到末尾，`content` 为空时移除区块
+pub fn replace_block(file: &str, content: &str) -> Result<String, AppError> {
+    let block = if content.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "{BLOCK_BEGIN}\n{}\n{BLOCK_END}\n",
+            content.trim_end_matches('\n')
+        )
+    };
+
+    let Some(span) = find_block(file)? else {
+        if block.is_empty() {
+            return Ok(file.to_string());
+        }
+        let user = file.trim_end();
+        return Ok(if user.is_empty() {
+            block
+        } else {
+            format!("{user}\n\n{block}")
+        });
+    };
+
+    let before = &file[..span.start];
+    let after = &file[span.end..];
+    if block.is_empty() {
+        // 去掉区块与前文之间的空行，避免每次启停后空行累积
+        let before = before.trim_end();
+        let after = after.trim_start_matches('\n');
+        return Ok(match (before.is_empty(), after.is_empty()) {
+            (true, _) => after.to_string(),
+            (false, true) => format!("{before}\n"),
+            (false, false) => format!("{before}\n\n{after}"),
+        });
+    }
+    Ok(format!("{before}{block}{after}"))
+}
+
+/// 去掉托管区块的开始与结束标记，区块内容与区块外的文本原样保留
+pub fn unwrap_block(file: &str) -> Result<String, AppError> {
+    let Some(span) = find_block(file)? else {
+        return Ok(file.to_string());
+    };
+    Ok(format!(
+        "{}{}{}",
+        &file[..span.start],
+        &file[span.inner.0..span.inner.1],
+        &file[span.end..]
+    ))
+}
+
+/// 把文件中已有的 `content`（须占据整行）原地包进托管区块，其余文本留在区块外；
+/// 文件中找不到 `content` 时返回 None
+pub fn wrap_block(file: &str, content: &str) -> Result<Option<String>, AppError> {
+    let content = content.trim_end_matches('\n');
+    if content.is_empty() || find_block(file)?.is_some() {
+        return Ok(None);
+    }
+    let found = file.match_indices(content).find(|(start, _)| {
+        let end = start + content.len();
+        (*start == 0 || file[..*start].ends_with('\n'))
+            && (end == file.len() || file[end..].starts_with('\n'))
+    });
+    Ok(found.map(|(start, _)| {
+        let end = start + content.len();
+        let after = file[end..].strip_prefix('\n').unwrap_or(&file[end..]);
+        format!(
+            "{}{BLOCK_BEGIN}\n{content}\n{BLOCK_END}\n{after}",
+            &file[..start]
+        )
+    }))
+}
+
+/// 三方合并结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Clean(String),
+    /// 含冲突标记（`<<<<<<< live` / `=======` / `>>>>>>> cc-switch`）的合并文本与冲突数
+    Conflict {
+        text: String,
+        conflicts: usize,
+    },
+}
+
+/// 以 `base` 为基准，按行合并 `live`（用户修改）与 `db`（cc-switch 的新版本）
+pub fn merge3(base: &str, live: &str, db: &str) -> MergeOutcome {
+    let base = base.lines().collect::<Vec<_>>();
+    let live = live.lines().collect::<Vec<_>>();
+    let db = db.lines().collect::<Vec<_>>();
+    let live_map = lcs_map(&base, &live);
+    let db_map = lcs_map(&base, &db);
+
+    let mut output: Vec<&str> = Vec::new();
+    let mut conflicts = 0;
+    let (mut i, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // 下一个在三方中都未改动的基准行
+        let anchor = (i..base.len()).find_map(|j| match (live_map[j], db_map[j]) {
+            (Some(x), Some(y)) if x >= a && y >= b => Some((j, x, y)),
+            _ => None,
+        });
+        let (j, x, y) = anchor.unwrap_or((base.len(), live.len(), db.len()));
+
+        let base_chunk = &base[i..j];
+        let live_chunk = &live[a..x];
+        let db_chunk = &db[b..y];
+        if live_chunk == base_chunk || live_chunk == db_chunk {
+            output.extend_from_slice(db_chunk);
+        } else if db_chunk == base_chunk {
+            output.extend_from_slice(live_chunk);
+        } else {
+            conflicts += 1;
+            output.push(CONFLICT_LIVE);
+            output.extend_from_slice(live_chunk);
+            output.push(CONFLICT_SEPARATOR);
+            output.extend_from_slice(db_chunk);
+            output.push(CONFLICT_DB);
+        }
+
+        if anchor.is_none() {
+            break;
+        }
+        output.push(base[j]);
+        (i, a, b) = (j + 1, x + 1, y + 1);
+    }
+
+    let text = output.join("\n");
+    if conflicts == 0 {
+        MergeOutcome::Clean(text)
+    } else {
+        MergeOutcome::Conflict { text, conflicts }
+    }
+}
+
+/// 文本中是否仍有未解决的冲突标记
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|line| {
+        line.starts_with(CONFLICT_LIVE)
+            || line.starts_with(CONFLICT_DB)
+            || line == CONFLICT_SEPARATOR
+    })
+}
+
+/// 最长公共子序列：`base` 每一行在 `other` 中对应的行号
+fn lcs_map(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (base.len(), other.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if base[i] == other[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut map = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            map[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_block_keeps_user_content_around_the_block() {
+        let file = "# My notes\n\nKeep this.\n";
+        let written = replace_block(file, "Rules v1.").expect("append block");
+        assert_eq!(
+            written,
+            format!("# My notes\n\nKeep this.\n\n{BLOCK_BEGIN}\nRules v1.\n{BLOCK_END}\n")
+        );
+
+        let edited = format!("{written}\nMore notes.\n");
+        let replaced = replace_block(&edited, "Rules v2.").expect("replace block");
+        assert_eq!(
+            extract_block(&replaced).expect("extract"),
+            Some("Rules v2.".to_string())
+        );
+        assert!(replaced.starts_with("# My notes\n\nKeep this.\n"));
+        assert!(replaced.ends_with("\nMore notes.\n"));
+
+        let removed = replace_block(&replaced, "").expect("remove block");
+        assert_eq!(removed, "# My notes\n\nKeep this.\n\nMore notes.\n");
+        assert!(replace_block(&format!("{BLOCK_BEGIN}\nopen"), "x").is_err());
+    }
+
+    #[test]
+    fn wrap_and_unwrap_block_only_move_the_markers() {
+        let file = "# My notes\n\nRules v1.\nMore rules.\n\nKeep this.\n";
+        let wrapped = wrap_block(file, "Rules v1.\nMore rules.")
+            .expect("wrap")
+            .expect("content found");
+        assert_eq!(
+            wrapped,
+            format!(
+                "# My notes\n\n{BLOCK_BEGIN}\nRules v1.\nMore rules.\n{BLOCK_END}\n\nKeep this.\n"
+            )
+        );
+        assert_eq!(unwrap_block(&wrapped).expect("unwrap"), file);
+
+        assert_eq!(wrap_block(file, "Rules v2.").expect("wrap"), None);
+        assert_eq!(wrap_block(file, "Rules").expect("wrap"), None);
+        assert_eq!(wrap_block(&wrapped, "Keep this.").expect("wrap"), None);
+        assert_eq!(unwrap_block(file).expect("unwrap"), file);
+    }
+
+    #[test]
+    fn merge3_combines_edits_to_different_lines() {
+        let base = "a\nb\nc\nd";
+        let live = "a\nb (live)\nc\nd";
+        let db = "a\nb\nc\nd (db)\ne";
+        assert_eq!(
+            merge3(base, live, db),
+            MergeOutcome::Clean("a\nb (live)\nc\nd (db)\ne".to_string())
+        );
+        assert_eq!(merge3(base, base, db), MergeOutcome::Clean(db.to_string()));
+        assert_eq!(
+            merge3(base, live, base),
+            MergeOutcome::Clean(live.to_string())
+        );
+    }
+
+    #[test]
+    fn merge3_marks_overlapping_edits_as_conflicts() {
+        let outcome = merge3("a\nb\nc", "a\nlive\nc", "a\ndb\nc");
+        let MergeOutcome::Conflict { text, conflicts } = outcome else {
+            panic!("expected conflict");
+        };
+        assert_eq!(conflicts, 1);
+        assert_eq!(
+            text,
+            "a\n<<<<<<< live\nlive\n=======\ndb\n>>>>>>> cc-switch\nc"
+        );
+        assert!(has_conflict_markers(&text));
+    }
+}
@@ -386,6 +386,9 @@ pub struct ProviderMeta {
     /// 每月消费限额（USD）
     #[serde(rename = "limitMonthlyUsd", skip_serializing_if = "Option::is_none")]
     pub limit_monthly_usd: Option<String>,
+    /// 加权路由策略下的权重（缺省为 1，0 表示仅作为后备）
+    #[serde(rename = "routingWeight", skip_serializing_if = "Option::is_none")]
+    pub routing_weight: Option<u32>,
     /// 供应商单独的模型测试配置
     #[serde(rename = "testConfig", skip_serializing_if = "Option::is_none")]
     pub test_config: Option<ProviderTestConfig>,
@@ -435,6 +438,10 @@ impl ProviderMeta {
         self.codex_fast_mode.unwrap_or(false)
     }
 
+    pub fn routing_weight(&self) -> u32 {
+        self.routing_weight.unwrap_or(1)
+    }
+
     pub fn managed_account_id_for(&self, auth_provider: &str) -> Option<String> {
         if let Some(binding) = self.auth_binding.as_ref() {
             if binding.source == AuthBindingSource::ManagedAccount
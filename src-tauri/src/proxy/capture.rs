@@ -0,0 +1,461 @@
+//! 请求抓包
+//!
+//! 开启后为每个代理请求记录客户端请求、格式转换后的上游请求以及上游原始响应
+//! （流式响应按原样保存 SSE 文本），写入 proxy_request_captures 表，供
+//! `proxy replay` 重放以复现格式转换问题。请求头中的凭据与 URL 中的 key 参数
+//! 在记录前脱敏。
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::provider::Provider;
+
+use super::error::ProxyError;
+use super::forwarder::{ForwardOptions, RequestForwarder};
+use super::model_mapper::compile_model_rules;
+use super::provider_router::ProviderRouter;
+use super::types::{CaptureConfig, RectifierConfig};
+
+/// 脱敏后的占位值
+pub const REDACTED: &str = "[REDACTED]";
+
+/// 重放请求的总超时
+const REPLAY_TIMEOUT_SECS: u64 = 600;
+
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+    "x-goog-api-key",
+    "cookie",
+    "set-cookie",
+    "chatgpt-account-id",
+];
+
+const SENSITIVE_QUERY_KEYS: &[&str] = &["key", "api_key", "apikey", "access_token", "token"];
+
+/// 一条抓包记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCapture {
+    pub request_id: String,
+    pub app_type: String,
+    /// 客户端请求的端点（含查询参数，敏感参数已移除）
+    pub endpoint: String,
+    pub provider_id: Option<String>,
+    pub status_code: Option<u16>,
+    pub client_headers: Value,
+    pub client_body: String,
+    pub upstream_method: Option<String>,
+    pub upstream_url: Option<String>,
+    pub upstream_headers: Value,
+    pub upstream_body: Option<String>,
+    pub response_headers: Value,
+    pub response_body: String,
+    /// 任一请求体或响应体因超过大小上限被截断
+    pub truncated: bool,
+    pub created_at: i64,
+}
+
+/// 抓包列表中的摘要信息（不含请求/响应体）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCaptureSummary {
+    pub request_id: String,
+    pub app_type: String,
+    pub endpoint: String,
+    pub provider_id: Option<String>,
+    pub status_code: Option<u16>,
+    pub truncated: bool,
+    pub created_at: i64,
+}
+
+struct CaptureState {
+    capture: ProxyCapture,
+    response_body: Vec<u8>,
+}
+
+/// 单个代理请求的抓包记录器，在处理链路各阶段之间共享
+#[derive(Clone)]
+pub struct RequestCapture {
+    state: Arc<Mutex<CaptureState>>,
+    max_body_bytes: usize,
+    retain: usize,
+}
+
+impl RequestCapture {
+    /// 记录客户端请求，开始一次抓包
+    pub fn start(
+        app_type: &AppType,
+        endpoint: &str,
+        headers: &HeaderMap,
+        body: &Value,
+        config: &CaptureConfig,
+    ) -> Self {
+        let mut truncated = false;
+        let client_body = serde_json::to_vec(body).unwrap_or_default();
+        let client_body = capped_text(&client_body, config.max_body_bytes, &mut truncated);
+        Self {
+            state: Arc::new(Mutex::new(CaptureState {
+                capture: ProxyCapture {
+                    app_type: app_type.as_str().to_string(),
+                    endpoint: strip_sensitive_query(endpoint),
+                    client_headers: redact_headers(headers),
+                    client_body,
+                    upstream_headers: Value::Object(Map::new()),
+                    response_headers: Value::Object(Map::new()),
+                    truncated,
+                    ..ProxyCapture::default()
+                },
+                response_body: Vec::new(),
+            })),
+            max_body_bytes: config.max_body_bytes,
+            retain: config.retain,
+        }
+    }
+
+    pub fn retain(&self) -> usize {
+        self.retain
+    }
+
+    /// 记录发往上游的请求；故障转移或整流重试时覆盖上一次尝试
+    pub fn record_upstream_request(&self, provider_id: &str, request: &reqwest::RequestBuilder) {
+        let Some(request) = request.try_clone().and_then(|builder| builder.build().ok()) else {
+            return;
+        };
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let mut truncated = state.capture.truncated;
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(|bytes| capped_text(bytes, self.max_body_bytes, &mut truncated));
+
+        let capture = &mut state.capture;
+        capture.provider_id = Some(provider_id.to_string());
+        capture.upstream_method = Some(request.method().to_string());
+        capture.upstream_url = Some(redact_url(request.url()));
+        capture.upstream_headers = redact_headers(request.headers());
+        capture.upstream_body = body;
+        capture.truncated = truncated;
+        capture.status_code = None;
+        capture.response_headers = Value::Object(Map::new());
+        state.response_body.clear();
+    }
+
+    /// 记录上游响应状态与响应头，并清空之前尝试的响应体
+    pub fn record_response(&self, status: reqwest::StatusCode, headers: &HeaderMap) {
+        if let Ok(mut state) = self.state.lock() {
+            state.capture.status_code = Some(status.as_u16());
+            state.capture.response_headers = redact_headers(headers);
+            state.response_body.clear();
+        }
+    }
+
+    /// 追加上游响应体；超过上限的部分丢弃并标记截断
+    pub fn append_response_body(&self, chunk: &[u8]) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let remaining = self
+            .max_body_bytes
+            .saturating_sub(state.response_body.len());
+        if chunk.len() > remaining {
+            state.capture.truncated = true;
+        }
+        let take = chunk.len().min(remaining);
+        state.response_body.extend_from_slice(&chunk[..take]);
+    }
+
+    /// 包装上游响应：数据块原样转发给下游，同时写入抓包记录
+    pub fn tee_response(&self, response: reqwest::Response) -> reqwest::Response {
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        self.record_response(status, &headers);
+
+        let capture = self.clone();
+        let stream = response.bytes_stream().map(move |chunk| {
+            if let Ok(bytes) = &chunk {
+                capture.append_response_body(bytes);
+            }
+            chunk
+        });
+
+        let mut teed = axum::http::Response::new(reqwest::Body::wrap_stream(stream));
+        *teed.status_mut() = status;
+        *teed.version_mut() = version;
+        *teed.headers_mut() = headers;
+        reqwest::Response::from(teed)
+    }
+
+    /// 生成最终记录；`status_code` 用于补全未收到上游响应的情况
+    pub fn finish(&self, request_id: &str, status_code: u16) -> ProxyCapture {
+        let (mut capture, response_body) = match self.state.lock() {
+            Ok(state) => (state.capture.clone(), state.response_body.clone()),
+            Err(_) => (ProxyCapture::default(), Vec::new()),
+        };
+        capture.request_id = request_id.to_string();
+        capture.status_code.get_or_insert(status_code);
+        capture.response_body = String::from_utf8_lossy(&response_body).into_owned();
+        capture.created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+        capture
+    }
+}
+
+/// 以抓包中的客户端请求重新执行转换与转发，发送到指定供应商
+///
+/// 不经过熔断器与故障转移，也不写入请求日志；返回本次重放的抓包记录。
+pub async fn replay_capture(
+    db: Arc<Database>,
+    capture: &ProxyCapture,
+    provider: Provider,
+    config: &CaptureConfig,
+) -> Result<ProxyCapture, ProxyError> {
+    let app_type = AppType::from_str(&capture.app_type).map_err(|_| {
+        ProxyError::ConfigError(format!("unsupported app type: {}", capture.app_type))
+    })?;
+    let body: Value = serde_json::from_str(&capture.client_body).map_err(|error| {
+        ProxyError::ConfigError(format!(
+            "captured client request is not valid JSON (truncated: {}): {error}",
+            capture.truncated
+        ))
+    })?;
+    let headers = header_map_from_capture(&capture.client_headers);
+    let model_rules = db
+        .list_model_routing_rules(Some(app_type.as_str()))
+        .unwrap_or_default();
+    let rectifier_config = match app_type {
+        AppType::Claude => db.get_rectifier_config().unwrap_or_default(),
+        _ => RectifierConfig::default(),
+    };
+
+    // 重放总是记录，不受抓包开关影响
+    let recorder = RequestCapture::start(&app_type, &capture.endpoint, &headers, &body, config);
+    let forwarder = RequestForwarder::new(Arc::new(ProviderRouter::new(db)))?
+        .with_model_rules(Arc::new(compile_model_rules(model_rules)))
+        .with_capture(Some(recorder.clone()));
+    let options = ForwardOptions {
+        max_retries: 0,
+        request_timeout: Some(Duration::from_secs(REPLAY_TIMEOUT_SECS)),
+        bypass_circuit_breaker: true,
+    };
+
+    let forwarded = forwarder
+        .forward_buffered_response_detailed(
+            &app_type,
+            &capture.endpoint,
+            body,
+            &headers,
+            vec![provider],
+            options,
+            rectifier_config,
+        )
+        .await
+        .map_err(|failure| failure.error)?;
+
+    Ok(recorder.finish(
+        &format!("replay:{}", capture.request_id),
+        forwarded.response.status.as_u16(),
+    ))
+}
+
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_HEADERS.contains(&name.as_str()) || name.contains("token") || name.contains("secret")
+}
+
+fn is_sensitive_query_key(key: &str) -> bool {
+    SENSITIVE_QUERY_KEYS.contains(&key.to_ascii_lowercase().as_str())
+}
+
+/// 请求头转为 JSON 对象，同名多值以 ", " 拼接，敏感值替换为占位符
+pub fn redact_headers(headers: &HeaderMap) -> Value {
+    let mut map = Map::new();
+    for (name, value) in headers {
+        let value = if is_sensitive_header(name.as_str()) {
+            REDACTED.to_string()
+        } else {
+            String::from_utf8_lossy(value.as_bytes()).into_owned()
+        };
+        match map.get_mut(name.as_str()) {
+            Some(Value::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            _ => {
+                map.insert(name.as_str().to_string(), Value::String(value));
+            }
+        }
+    }
+    Value::Object(map)
+}
+
+/// 隐藏 URL 中的密码与敏感查询参数
+pub fn redact_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some(REDACTED));
+    }
+    if url.query().is_some() {
+        let pairs = url
+            .query_pairs()
+            .map(|(key, value)| {
+                let value = if is_sensitive_query_key(&key) {
+                    REDACTED.to_string()
+                } else {
+                    value.into_owned()
+                };
+                (key.into_owned(), value)
+            })
+            .collect::<Vec<_>>();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+/// 移除端点中的敏感查询参数，保留其余参数以便重放
+fn strip_sensitive_query(endpoint: &str) -> String {
+    let Some((path, query)) = endpoint.split_once('?') else {
+        return endpoint.to_string();
+    };
+    let kept = query
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or_default();
+            !pair.is_empty() && !is_sensitive_query_key(key)
+        })
+        .collect::<Vec<_>>();
+    if kept.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", kept.join("&"))
+    }
+}
+
+/// 由抓包中的请求头还原 HeaderMap，跳过已脱敏的值
+fn header_map_from_capture(headers: &Value) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    let Some(object) = headers.as_object() else {
+        return map;
+    };
+    for (name, value) in object {
+        let Some(value) = value.as_str().filter(|value| *value != REDACTED) else {
+            continue;
+        };
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            map.insert(name, value);
+        }
+    }
+    map
+}
+
+fn capped_text(bytes: &[u8], max_bytes: usize, truncated: &mut bool) -> String {
+    if bytes.len() > max_bytes {
+        *truncated = true;
+    }
+    String::from_utf8_lossy(&bytes[..bytes.len().min(max_bytes)]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(max_body_bytes: usize) -> CaptureConfig {
+        CaptureConfig {
+            enabled: true,
+            max_body_bytes,
+            retain: 10,
+        }
+    }
+
+    #[test]
+    fn redacts_credentials_in_headers_and_urls() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer sk-live"));
+        headers.insert("x-goog-api-key", HeaderValue::from_static("AIza-secret"));
+        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["authorization"], REDACTED);
+        assert_eq!(redacted["x-goog-api-key"], REDACTED);
+        assert_eq!(redacted["anthropic-version"], "2023-06-01");
+
+        let url = reqwest::Url::parse(
+            "https://user:pw@example.com/v1beta/models/gemini:generateContent?key=AIza&alt=sse",
+        )
+        .unwrap();
+        let redacted = redact_url(&url);
+        assert!(!redacted.contains("AIza"));
+        assert!(!redacted.contains("pw@"));
+        assert!(redacted.contains("alt=sse"));
+
+        assert_eq!(
+            strip_sensitive_query("/v1beta/models/x:streamGenerateContent?alt=sse&key=AIza"),
+            "/v1beta/models/x:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(strip_sensitive_query("/v1/messages?key=1"), "/v1/messages");
+    }
+
+    #[test]
+    fn capture_records_latest_attempt_and_caps_response_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("client-key"));
+        let capture = RequestCapture::start(
+            &AppType::Claude,
+            "/v1/messages",
+            &headers,
+            &json!({ "model": "claude-sonnet", "stream": true }),
+            &config(8),
+        );
+
+        let client = reqwest::Client::new();
+        let first = client
+            .post("https://a.example.com/v1/messages")
+            .header("x-api-key", "upstream-a")
+            .body("{\"a\":1}");
+        capture.record_upstream_request("provider-a", &first);
+        capture.append_response_body(b"stale");
+
+        let second = client
+            .post("https://b.example.com/v1/chat/completions")
+            .bearer_auth("upstream-b")
+            .body("{\"b\":2}");
+        capture.record_upstream_request("provider-b", &second);
+        capture.record_response(reqwest::StatusCode::OK, &HeaderMap::new());
+        capture.append_response_body(b"data: 1\n\n");
+        capture.append_response_body(b"data: 2\n\n");
+
+        let record = capture.finish("req-1", 500);
+        assert_eq!(record.request_id, "req-1");
+        assert_eq!(record.provider_id.as_deref(), Some("provider-b"));
+        assert_eq!(record.status_code, Some(200));
+        assert_eq!(
+            record.upstream_url.as_deref(),
+            Some("https://b.example.com/v1/chat/completions")
+        );
+        assert_eq!(record.upstream_headers["authorization"], REDACTED);
+        assert_eq!(record.client_headers["x-api-key"], REDACTED);
+        assert_eq!(record.response_body, "data: 1\n");
+        assert!(record.truncated);
+
+        let restored = header_map_from_capture(&record.client_headers);
+        assert!(restored.get("x-api-key").is_none());
+    }
+}
@@ -0,0 +1,276 @@
+//! 代理客户端令牌校验
+//!
+//! 代理监听在非回环地址上时，用具名令牌区分团队成员：每个令牌可限定可用的应用与供应商，
+//! 并在 proxy_request_logs 中记录令牌名称用于用量归属。客户端可以把令牌作为 API Key
+//! （x-api-key / Authorization: Bearer / x-goog-api-key）发送，也可以使用专用请求头。
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{database::Database, error::AppError};
+
+use super::{
+    error::ProxyError,
+    handlers::session_token_header,
+    server::ProxyServerState,
+    types::{ClientAuthConfig, ClientToken},
+};
+
+/// 专用客户端令牌请求头（不会转发到上游）
+pub const CLIENT_TOKEN_HEADER: &str = "x-cc-switch-client-token";
+
+const CLIENT_TOKEN_PREFIX: &str = "ccs_";
+
+/// 不需要客户端令牌的路由
+const EXEMPT_PATHS: &[&str] = &["/health"];
+
+/// 守护进程与 CLI 访问的内部路由：携带本 worker 的托管会话令牌时可代替客户端令牌。
+/// 该令牌不会经由任何未认证的路由返回
+const SESSION_PATH_PREFIXES: &[&str] = &["/status", "/control/"];
+
+/// 令牌配置的缓存有效期；创建或撤销令牌、`/control/reload` 时立即失效，
+/// 有效期只兜底未经守护进程托管、收不到重载通知的前台代理
+const CLIENT_TOKENS_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// 客户端令牌配置缓存，避免每个请求都查询数据库
+#[derive(Default)]
+pub struct ClientTokenCache {
+    config: Mutex<Option<(Instant, Arc<ClientAuthConfig>)>>,
+}
+
+impl ClientTokenCache {
+    pub fn get(&self, db: &Database) -> Result<Arc<ClientAuthConfig>, AppError> {
+        let mut cache = self
+            .config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((loaded_at, config)) = cache.as_ref() {
+            if loaded_at.elapsed() < CLIENT_TOKENS_CACHE_TTL {
+                return Ok(config.clone());
+            }
+        }
+
+        let config = Arc::new(db.get_client_auth_config()?);
+        *cache = Some((Instant::now(), config.clone()));
+        Ok(config)
+    }
+
+    pub fn invalidate(&self) {
+        *self
+            .config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+/// 通过校验的客户端身份，由中间件写入请求扩展
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    pub name: String,
+    pub apps: Vec<String>,
+    pub providers: Vec<String>,
+}
+
+impl ClientIdentity {
+    fn from_token(token: &ClientToken) -> Self {
+        Self {
+            name: token.name.clone(),
+            apps: token.apps.clone(),
+            providers: token.providers.clone(),
+        }
+    }
+
+    pub fn allows_app(&self, app_type: &str) -> bool {
+        self.apps.is_empty() || self.apps.iter().any(|app| app == app_type)
+    }
+
+    pub fn allows_provider(&self, provider_id: &str) -> bool {
+        self.providers.is_empty() || self.providers.iter().any(|id| id == provider_id)
+    }
+}
+
+/// 生成新的客户端令牌明文（仅在创建时展示一次）
+pub fn generate_client_token() -> String {
+    format!(
+        "{CLIENT_TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+pub fn hash_client_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+/// 校验请求携带的令牌；未配置任何令牌时不校验，返回 `Ok(None)`
+pub fn authenticate(
+    config: &ClientAuthConfig,
+    headers: &HeaderMap,
+) -> Result<Option<ClientIdentity>, ProxyError> {
+    if !config.is_enforced() {
+        return Ok(None);
+    }
+
+    let presented = presented_tokens(headers);
+    if presented.is_empty() {
+        return Err(ProxyError::AuthError("missing client token".to_string()));
+    }
+    for candidate in presented {
+        let digest = hash_client_token(&candidate);
+        if let Some(token) = config
+            .tokens
+            .iter()
+            .find(|token| token.token_sha256 == digest)
+        {
+            return Ok(Some(ClientIdentity::from_token(token)));
+        }
+    }
+    Err(ProxyError::AuthError("invalid client token".to_string()))
+}
+
+/// 代理路由中间件：拒绝未携带有效令牌的请求，并把客户端身份写入请求扩展
+pub async fn require_client_token(
+    State(state): State<ProxyServerState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if EXEMPT_PATHS.contains(&path) {
+        return next.run(request).await;
+    }
+    if SESSION_PATH_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        && state
+            .is_managed_session_token(session_token_header(request.headers()))
+            .await
+    {
+        return next.run(request).await;
+    }
+
+    let config = match state.client_tokens.get(&state.db) {
+        Ok(config) => config,
+        Err(error) => {
+            log::error!("load proxy client tokens failed: {error}");
+            return ProxyError::DatabaseError(error.to_string()).into_response();
+        }
+    };
+
+    match authenticate(&config, request.headers()) {
+        Ok(Some(identity)) => {
+            request.extensions_mut().insert(identity);
+            next.run(request).await
+        }
+        Ok(None) => next.run(request).await,
+        Err(error) => {
+            log::warn!(
+                "rejected proxy request {} {}: {error}",
+                request.method(),
+                request.uri().path()
+            );
+            error.into_response()
+        }
+    }
+}
+
+fn presented_tokens(headers: &HeaderMap) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for name in [CLIENT_TOKEN_HEADER, "x-api-key", "x-goog-api-key"] {
+        if let Some(value) = headers.get(name).and_then(|value| value.to_str().ok()) {
+            tokens.push(value.trim().to_string());
+        }
+    }
+    if let Some(value) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        tokens.push(value.trim().to_string());
+    }
+    tokens.retain(|token| !token.is_empty());
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn config_with(token: &str, apps: &[&str]) -> ClientAuthConfig {
+        ClientAuthConfig {
+            tokens: vec![ClientToken {
+                name: "alice".to_string(),
+                token_sha256: hash_client_token(token),
+                apps: apps.iter().map(|app| app.to_string()).collect(),
+                providers: Vec::new(),
+                created_at: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn authenticate_skips_when_no_tokens_configured() {
+        let result = authenticate(&ClientAuthConfig::default(), &HeaderMap::new());
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[test]
+    fn authenticate_accepts_token_from_auth_headers() {
+        let token = generate_client_token();
+        let config = config_with(&token, &["claude"]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        let identity = authenticate(&config, &headers).unwrap().unwrap();
+        assert_eq!(identity.name, "alice");
+        assert!(identity.allows_app("claude"));
+        assert!(!identity.allows_app("codex"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_TOKEN_HEADER, HeaderValue::from_str(&token).unwrap());
+        headers.insert("x-api-key", HeaderValue::from_static("PROXY_MANAGED"));
+        assert!(authenticate(&config, &headers).unwrap().is_some());
+    }
+
+    #[test]
+    fn token_cache_keeps_config_until_invalidated() {
+        let db = Database::memory().unwrap();
+        let cache = ClientTokenCache::default();
+        assert!(!cache.get(&db).unwrap().is_enforced());
+
+        db.set_client_auth_config(&config_with(&generate_client_token(), &[]))
+            .unwrap();
+        assert!(!cache.get(&db).unwrap().is_enforced());
+        cache.invalidate();
+        assert!(cache.get(&db).unwrap().is_enforced());
+    }
+
+    #[test]
+    fn authenticate_rejects_missing_and_unknown_tokens() {
+        let config = config_with(&generate_client_token(), &[]);
+        assert!(matches!(
+            authenticate(&config, &HeaderMap::new()),
+            Err(ProxyError::AuthError(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("PROXY_MANAGED"));
+        assert!(matches!(
+            authenticate(&config, &headers),
+            Err(ProxyError::AuthError(_))
+        ));
+    }
+}
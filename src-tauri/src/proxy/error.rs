@@ -36,6 +36,9 @@ pub enum ProxyError {
     #[error("no providers configured")]
     NoProvidersConfigured,
 
+    #[error("spending limit reached: {0}")]
+    SpendLimitExceeded(String),
+
     #[error("provider unhealthy: {0}")]
     ProviderUnhealthy(String),
 
@@ -124,6 +127,7 @@ fn proxy_error_status(error: &ProxyError) -> StatusCode {
         | ProxyError::MaxRetriesExceeded => StatusCode::SERVICE_UNAVAILABLE,
         ProxyError::ConfigError(_) | ProxyError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
         ProxyError::AuthError(_) => StatusCode::UNAUTHORIZED,
+        ProxyError::SpendLimitExceeded(_) => StatusCode::PAYMENT_REQUIRED,
         ProxyError::TransformError(_) => StatusCode::UNPROCESSABLE_ENTITY,
         ProxyError::Timeout(_) | ProxyError::StreamIdleTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
         ProxyError::UpstreamError { status, .. } => {
@@ -0,0 +1,158 @@
+//! 代理运行时事件
+//!
+//! 供应商切换、故障转移、熔断状态变化以及每个请求的摘要通过广播通道发布。
+//! 由守护进程托管的 worker 会把这些事件转发到 IPC 订阅者；没有订阅者时
+//! 发布只是一次无操作的 `send`。
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use super::circuit_breaker::CircuitState;
+
+/// 广播通道容量，慢订阅者落后超过该数量时会收到 `Lagged`
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProxyEvent {
+    /// 某应用实际使用的供应商发生变化（手动切换或首次请求）
+    ProviderSwitched {
+        app_type: String,
+        from_provider_id: Option<String>,
+        provider_id: String,
+        provider_name: String,
+    },
+    /// 请求在当前供应商失败后由队列中的其他供应商完成
+    FailoverHop {
+        app_type: String,
+        from_provider_id: String,
+        provider_id: String,
+        provider_name: String,
+    },
+    /// 熔断器状态变化
+    CircuitChanged {
+        app_type: String,
+        provider_id: String,
+        from: CircuitState,
+        to: CircuitState,
+    },
+    /// 供应商消费达到预警比例或限额（每个周期只发布一次）
+    SpendLimit {
+        app_type: String,
+        provider_id: String,
+        /// "daily" 或 "monthly"
+        period: String,
+        usage_usd: String,
+        limit_usd: String,
+        exceeded: bool,
+    },
+    /// 单个代理请求的摘要
+    Request(RequestSummary),
+}
+
+impl ProxyEvent {
+    pub fn app_type(&self) -> &str {
+        match self {
+            Self::ProviderSwitched { app_type, .. }
+            | Self::FailoverHop { app_type, .. }
+            | Self::CircuitChanged { app_type, .. }
+            | Self::SpendLimit { app_type, .. } => app_type,
+            Self::Request(summary) => &summary.app_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestSummary {
+    pub app_type: String,
+    pub provider_id: String,
+    pub model: String,
+    pub request_model: String,
+    pub status_code: u16,
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub first_token_ms: Option<u64>,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_read_tokens: u32,
+    #[serde(default)]
+    pub cache_creation_tokens: u32,
+    /// 十进制字符串，与 proxy_request_logs.total_cost_usd 一致；无定价时为空
+    #[serde(default)]
+    pub total_cost_usd: Option<String>,
+    pub session_id: String,
+    pub is_streaming: bool,
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// 代理事件广播总线，克隆后共享同一通道
+#[derive(Debug, Clone)]
+pub struct ProxyEventBus {
+    sender: broadcast::Sender<ProxyEvent>,
+}
+
+impl Default for ProxyEventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl ProxyEventBus {
+    pub fn publish(&self, event: ProxyEvent) {
+        // 没有订阅者时 send 返回错误，这是正常情况
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ProxyEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn subscribers_receive_events_published_after_subscribing() {
+        let bus = ProxyEventBus::default();
+        bus.publish(ProxyEvent::FailoverHop {
+            app_type: "claude".to_string(),
+            from_provider_id: "a".to_string(),
+            provider_id: "b".to_string(),
+            provider_name: "B".to_string(),
+        });
+
+        let mut rx = bus.clone().subscribe();
+        let event = ProxyEvent::CircuitChanged {
+            app_type: "codex".to_string(),
+            provider_id: "relay".to_string(),
+            from: CircuitState::Closed,
+            to: CircuitState::Open,
+        };
+        bus.publish(event.clone());
+
+        assert_eq!(rx.recv().await.expect("event"), event);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn request_summary_serializes_with_type_tag() {
+        let event = ProxyEvent::Request(RequestSummary {
+            app_type: "claude".to_string(),
+            provider_id: "relay".to_string(),
+            model: "claude-sonnet-4-6".to_string(),
+            status_code: 200,
+            ..RequestSummary::default()
+        });
+        let value = serde_json::to_value(&event).expect("serialize");
+        assert_eq!(value["type"], "request");
+        assert_eq!(value["status_code"], 200);
+
+        let decoded: ProxyEvent = serde_json::from_value(value).expect("deserialize");
+        assert_eq!(decoded, event);
+        assert_eq!(decoded.app_type(), "claude");
+    }
+}
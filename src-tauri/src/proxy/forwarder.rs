@@ -1,5 +1,6 @@
 use axum::http::HeaderMap;
 use bytes::Bytes;
+use futures::StreamExt;
 use serde_json::Value;
 use std::{
     sync::Arc,
@@ -9,8 +10,10 @@ use std::{
 use crate::{app_config::AppType, provider::Provider};
 
 use super::{
+    capture::RequestCapture,
     error::ProxyError,
-    provider_router::ProviderRouter,
+    model_mapper::CompiledModelRule,
+    provider_router::{InflightGuard, ProviderRouter},
     providers::codex_chat_history::CodexChatHistoryStore,
     providers::gemini_shadow::GeminiShadowStore,
     providers::get_adapter,
@@ -32,6 +35,8 @@ pub struct RequestForwarder {
     session_client_provided: bool,
     codex_chat_history: Option<Arc<CodexChatHistoryStore>>,
     gemini_shadow: Option<Arc<GeminiShadowStore>>,
+    model_rules: Arc<Vec<CompiledModelRule>>,
+    capture: Option<RequestCapture>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -79,6 +84,27 @@ impl StreamingResponse {
             Self::Buffered(response) => response.status,
         }
     }
+
+    /// 进行中计数守卫随响应体一起交给下游，响应体读完或被丢弃时才释放
+    fn hold_inflight(self, guard: InflightGuard) -> Self {
+        let Self::Live(response) = self else {
+            return self;
+        };
+
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let stream = response.bytes_stream().map(move |chunk| {
+            let _ = &guard;
+            chunk
+        });
+
+        let mut held = axum::http::Response::new(reqwest::Body::wrap_stream(stream));
+        *held.status_mut() = status;
+        *held.version_mut() = version;
+        *held.headers_mut() = headers;
+        Self::Live(reqwest::Response::from(held))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -118,6 +144,8 @@ impl RequestForwarder {
             session_client_provided: false,
             codex_chat_history: None,
             gemini_shadow: None,
+            model_rules: Arc::new(Vec::new()),
+            capture: None,
         })
     }
 
@@ -150,6 +178,17 @@ impl RequestForwarder {
         self
     }
 
+    pub fn with_model_rules(mut self, rules: Arc<Vec<CompiledModelRule>>) -> Self {
+        self.model_rules = rules;
+        self
+    }
+
+    /// 开启抓包时记录每次尝试的上游请求与响应
+    pub fn with_capture(mut self, capture: Option<RequestCapture>) -> Self {
+        self.capture = capture;
+        self
+    }
+
     #[cfg(test)]
     #[expect(
         clippy::too_many_arguments,
@@ -218,6 +257,7 @@ impl RequestForwarder {
                 continue;
             }
 
+            let inflight = self.router.track_inflight(&provider.id, app_type.as_str());
             attempted_provider = true;
             pending_upstream_response = None;
             let provider_needs_transform = matches!(app_type, AppType::Claude)
@@ -236,7 +276,7 @@ impl RequestForwarder {
                 .await
             {
                 Ok(outcome) => {
-                    let response = outcome.response;
+                    let response = outcome.response.hold_inflight(inflight);
                     if response.status().is_success() {
                         if !bypass_circuit_breaker {
                             let _ = self
@@ -464,6 +504,7 @@ impl RequestForwarder {
                 continue;
             }
 
+            let _inflight = self.router.track_inflight(&provider.id, app_type.as_str());
             attempted_provider = true;
             pending_upstream_response = None;
             let provider_needs_transform = matches!(app_type, AppType::Claude)
@@ -673,6 +714,9 @@ impl RequestForwarder {
                 )
                 .await
                 .map_err(StreamingRequestError::BeforeResponse)?;
+            if let Some(capture) = &self.capture {
+                capture.record_upstream_request(&provider.id, &base_request);
+            }
             let mut attempt = 0u32;
 
             loop {
@@ -710,6 +754,10 @@ impl RequestForwarder {
                     None => Ok(request.send().await),
                 } {
                     Ok(Ok(response)) => {
+                        let response = match &self.capture {
+                            Some(capture) => capture.tee_response(response),
+                            None => response,
+                        };
                         if response.status().is_success() {
                             return Ok(StreamingAttemptOutcome {
                                 response: StreamingResponse::Live(response),
@@ -825,6 +873,9 @@ impl RequestForwarder {
                 )
                 .await
                 .map_err(BufferedRequestError::BeforeResponse)?;
+            if let Some(capture) = &self.capture {
+                capture.record_upstream_request(&provider.id, &base_request);
+            }
             let mut attempt = 0u32;
 
             loop {
@@ -895,6 +946,10 @@ impl RequestForwarder {
                         };
                         let response_body =
                             decode_buffered_response_body(&mut response_headers, response_body);
+                        if let Some(capture) = &self.capture {
+                            capture.record_response(status, &response_headers);
+                            capture.append_response_body(&response_body);
+                        }
 
                         let buffered_response = BufferedResponse {
                             status,
@@ -975,6 +1030,7 @@ fn classify_attempt_error(error: &ProxyError) -> AttemptDecision {
         | ProxyError::NoAvailableProvider
         | ProxyError::AllProvidersCircuitOpen
         | ProxyError::NoProvidersConfigured
+        | ProxyError::SpendLimitExceeded(_)
         | ProxyError::DatabaseError(_)
         | ProxyError::InvalidRequest(_)
         | ProxyError::Internal(_) => AttemptDecision::FatalStop,
@@ -10,7 +10,7 @@ use super::super::{
     error::ProxyError,
     http_client,
     json_canonical::canonicalize_value,
-    model_mapper::{apply_model_mapping, strip_one_m_suffix_for_upstream_from_body},
+    model_mapper::{apply_model_routing, strip_one_m_suffix_for_upstream_from_body},
     providers::{
         apply_codex_chat_upstream_model, claude_api_format_needs_transform, copilot_auth,
         get_adapter, normalize_anthropic_tool_thinking_history_for_provider,
@@ -27,6 +27,7 @@ const HEADER_BLACKLIST: &[&str] = &[
     "authorization",
     "x-api-key",
     "x-goog-api-key",
+    "x-cc-switch-client-token",
     "host",
     "content-length",
     "transfer-encoding",
@@ -105,7 +106,8 @@ impl RequestForwarder {
             .unwrap_or(false);
         let is_copilot = is_claude_request
             && (provider.is_github_copilot() || base_url.contains("githubcopilot.com"));
-        let (mut mapped_body, _, _) = apply_model_mapping(body.clone(), provider);
+        let (mut mapped_body, _, _) =
+            apply_model_routing(body.clone(), provider, &self.model_rules);
         let codex_responses_to_chat = should_convert_codex_responses_to_chat(provider, endpoint)
             && matches!(app_type, AppType::Codex);
 
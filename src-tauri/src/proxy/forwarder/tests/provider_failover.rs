@@ -4,9 +4,9 @@ use axum::http::{HeaderMap, StatusCode};
 use serde_json::{json, Value};
 
 use super::{
-    claude_provider, claude_request_body, spawn_delayed_scripted_streaming_upstream,
-    spawn_mock_upstream, spawn_scripted_streaming_upstream, spawn_scripted_upstream, test_router,
-    ScriptedStreamingBody,
+    claude_provider, claude_request_body, spawn_delayed_body_upstream,
+    spawn_delayed_scripted_streaming_upstream, spawn_mock_upstream,
+    spawn_scripted_streaming_upstream, spawn_scripted_upstream, test_router, ScriptedStreamingBody,
 };
 use crate::{
     app_config::AppType,
@@ -1216,3 +1216,42 @@ async fn claude_streaming_openai_chat_budget_rectifier_retries_same_provider() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn streaming_response_counts_as_inflight_until_body_finishes() {
+    let (base_url, _hits, server) = spawn_delayed_body_upstream().await;
+    let provider = claude_provider("p1", &base_url, None);
+    let (db, router) = test_router().await;
+    let forwarder = RequestForwarder::new(router.clone()).expect("create forwarder");
+
+    db.save_provider("claude", &provider)
+        .expect("save provider for health tracking");
+
+    let forwarded = forwarder
+        .forward_response(
+            &AppType::Claude,
+            "/v1/messages",
+            claude_request_body(),
+            &HeaderMap::new(),
+            vec![provider],
+            ForwardOptions {
+                max_retries: 0,
+                request_timeout: None,
+                bypass_circuit_breaker: true,
+            },
+            RectifierConfig::default(),
+        )
+        .await
+        .expect("streaming request should get response headers");
+
+    assert_eq!(router.inflight_count("p1", "claude"), 1);
+
+    let StreamingResponse::Live(response) = forwarded.response else {
+        panic!("expected live streaming response");
+    };
+    let body = response.bytes().await.expect("read streamed body");
+    assert_eq!(body.as_ref(), br#"{"ok":true}"#);
+    assert_eq!(router.inflight_count("p1", "claude"), 0);
+
+    server.abort();
+}
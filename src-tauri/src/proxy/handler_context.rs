@@ -7,11 +7,16 @@ use crate::app_config::AppType;
 use crate::provider::Provider;
 
 use super::{
+    capture::RequestCapture,
+    client_auth::ClientIdentity,
     error::ProxyError,
+    model_mapper::{find_pinned_provider_rule, CompiledModelRule},
     provider_router::ProviderRouter,
     server::ProxyServerState,
     session::extract_session_id,
-    types::{AppProxyConfig, CopilotOptimizerConfig, OptimizerConfig, RectifierConfig},
+    types::{
+        AppProxyConfig, CaptureConfig, CopilotOptimizerConfig, OptimizerConfig, RectifierConfig,
+    },
 };
 
 pub struct HandlerContext {
@@ -24,10 +29,15 @@ pub struct HandlerContext {
     pub rectifier_config: RectifierConfig,
     pub optimizer_config: OptimizerConfig,
     pub copilot_optimizer_config: CopilotOptimizerConfig,
+    pub model_rules: Arc<Vec<CompiledModelRule>>,
     pub request_model: String,
     pub session_id: String,
     pub session_client_provided: bool,
     pub current_provider_id_at_start: String,
+    capture_config: CaptureConfig,
+    pub capture: Option<RequestCapture>,
+    /// 发起请求的客户端令牌名称（未启用客户端令牌时为空）
+    pub client_name: Option<String>,
 }
 
 impl HandlerContext {
@@ -47,7 +57,7 @@ impl HandlerContext {
         let start_time = Instant::now();
 
         let provider_router = state.provider_router.clone();
-        let providers = provider_router.select_providers(app_type.as_str()).await?;
+        let mut providers = provider_router.select_providers(app_type.as_str()).await?;
 
         let app_proxy = state
             .db
@@ -62,11 +72,24 @@ impl HandlerContext {
         let rectifier_config = state.db.get_rectifier_config().unwrap_or_default();
         let optimizer_config = state.db.get_optimizer_config().unwrap_or_default();
         let copilot_optimizer_config = state.db.get_copilot_optimizer_config().unwrap_or_default();
+        let capture_config = state.db.get_capture_config().unwrap_or_default();
         let request_model = body
             .get("model")
             .and_then(|value| value.as_str())
             .unwrap_or("unknown")
             .to_string();
+        let model_rules = provider_router.model_rules(app_type.as_str());
+        if let Some(pinned_id) = find_pinned_provider_rule(&model_rules, &request_model)
+            .and_then(|rule| rule.pin_provider_id.as_deref())
+        {
+            pin_provider(
+                state,
+                &app_type,
+                pinned_id,
+                app_proxy.auto_failover_enabled,
+                &mut providers,
+            );
+        }
         let session_result = extract_session_id(headers, body, app_type.as_str());
 
         Ok(Self {
@@ -79,13 +102,59 @@ impl HandlerContext {
             rectifier_config,
             optimizer_config,
             copilot_optimizer_config,
+            model_rules,
             request_model,
             session_id: session_result.session_id,
             session_client_provided: session_result.client_provided,
             current_provider_id_at_start,
+            capture_config,
+            capture: None,
+            client_name: None,
         })
     }
 
+    /// 按客户端令牌限定可用的应用与供应商，并记录令牌名称用于用量归属
+    pub fn apply_client(&mut self, client: Option<ClientIdentity>) -> Result<(), ProxyError> {
+        let Some(client) = client else {
+            return Ok(());
+        };
+        let app = self.app_type.as_str();
+        if !client.allows_app(app) {
+            log::warn!("client token '{}' rejected for {app}", client.name);
+            return Err(ProxyError::AuthError(format!(
+                "client token '{}' is not allowed to use {app}",
+                client.name
+            )));
+        }
+        self.providers
+            .retain(|provider| client.allows_provider(&provider.id));
+        if self.providers.is_empty() {
+            log::warn!(
+                "client token '{}' has no permitted provider available for {app}",
+                client.name
+            );
+            return Err(ProxyError::AuthError(format!(
+                "client token '{}' has no permitted provider available for {app}",
+                client.name
+            )));
+        }
+        self.client_name = Some(client.name);
+        Ok(())
+    }
+
+    /// 抓包开启时记录客户端请求，后续转发与日志阶段共用同一记录器
+    pub fn start_capture(&mut self, endpoint: &str, headers: &HeaderMap, body: &Value) {
+        if self.capture_config.enabled {
+            self.capture = Some(RequestCapture::start(
+                &self.app_type,
+                endpoint,
+                headers,
+                body,
+                &self.capture_config,
+            ));
+        }
+    }
+
     pub fn providers(&self) -> &[Provider] {
         &self.providers
     }
@@ -127,6 +196,63 @@ impl HandlerContext {
     }
 }
 
+/// 将命中模型路由规则的供应商放到首位
+///
+/// 开启自动故障转移时其余供应商保留为后备，否则只使用固定的供应商。
+/// 固定的供应商不存在或已超出消费限额时忽略该规则。
+fn pin_provider(
+    state: &ProxyServerState,
+    app_type: &AppType,
+    pinned_id: &str,
+    keep_fallbacks: bool,
+    providers: &mut Vec<Provider>,
+) {
+    let pinned = match providers
+        .iter()
+        .position(|provider| provider.id == pinned_id)
+    {
+        Some(index) => providers.remove(index),
+        None => match state.db.get_provider_by_id(pinned_id, app_type.as_str()) {
+            Ok(Some(provider))
+                if !state
+                    .provider_router
+                    .within_spend_limit(app_type.as_str(), &provider) =>
+            {
+                log::warn!(
+                    "Model routing rule pins provider {pinned_id} for {}, but it is over its spend limit",
+                    app_type.as_str()
+                );
+                return;
+            }
+            Ok(Some(provider)) => {
+                match crate::secret_vault::reveal_provider(&state.db, &provider) {
+                    Ok(provider) => provider,
+                    Err(error) => {
+                        log::warn!("Failed to unlock pinned provider {pinned_id}: {error}");
+                        return;
+                    }
+                }
+            }
+            Ok(None) => {
+                log::warn!(
+                    "Model routing rule pins missing provider {pinned_id} for {}",
+                    app_type.as_str()
+                );
+                return;
+            }
+            Err(error) => {
+                log::warn!("Failed to load pinned provider {pinned_id}: {error}");
+                return;
+            }
+        },
+    };
+
+    if !keep_fallbacks {
+        providers.clear();
+    }
+    providers.insert(0, pinned);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,7 +264,10 @@ mod tests {
     use tokio::sync::RwLock;
 
     use crate::proxy::providers::gemini_shadow::GeminiShadowStore;
-    use crate::{database::Database, proxy::types::ProxyConfig};
+    use crate::{
+        database::Database,
+        proxy::types::{ModelRoutingRule, ProxyConfig},
+    };
 
     struct TempHome {
         #[allow(dead_code)]
@@ -217,6 +346,9 @@ mod tests {
             provider_router: Arc::new(ProviderRouter::new(db)),
             codex_chat_history: Arc::new(Default::default()),
             gemini_shadow: Arc::new(GeminiShadowStore::default()),
+            metrics: Arc::new(Default::default()),
+            events: Default::default(),
+            client_tokens: Arc::new(Default::default()),
         }
     }
 
@@ -349,4 +481,61 @@ mod tests {
         assert_eq!(context.providers()[0].id, "claude-failover");
         assert_eq!(context.current_provider_id_at_start, "claude-current");
     }
+
+    #[tokio::test]
+    #[serial(home_settings)]
+    async fn load_pins_provider_from_model_routing_rule() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().expect("create memory database"));
+        let current = test_provider("claude-current", 0);
+        let cheap = test_provider("claude-cheap", 1);
+
+        db.save_provider("claude", &current)
+            .expect("save current provider");
+        db.save_provider("claude", &cheap)
+            .expect("save cheap provider");
+        db.set_current_provider("claude", &current.id)
+            .expect("set current provider");
+        db.add_model_routing_rule(&ModelRoutingRule {
+            id: 0,
+            app_type: Some("claude".to_string()),
+            provider_id: None,
+            match_type: crate::proxy::types::ModelMatchType::Glob,
+            pattern: "*haiku*".to_string(),
+            target_model: None,
+            pin_provider_id: Some(cheap.id.clone()),
+            sort_index: 0,
+            enabled: true,
+            created_at: 0,
+        })
+        .expect("add routing rule");
+
+        let state = test_state(db);
+        let haiku = HandlerContext::load(
+            &state,
+            AppType::Claude,
+            &HeaderMap::new(),
+            &json!({"model": "claude-haiku-4-5"}),
+        )
+        .await
+        .expect("load handler context");
+        let sonnet = HandlerContext::load(
+            &state,
+            AppType::Claude,
+            &HeaderMap::new(),
+            &json!({"model": "claude-sonnet-4-6"}),
+        )
+        .await
+        .expect("load handler context");
+
+        let ids = |context: &HandlerContext| {
+            context
+                .providers()
+                .iter()
+                .map(|provider| provider.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&haiku), vec!["claude-cheap"]);
+        assert_eq!(ids(&sonnet), vec!["claude-current"]);
+    }
 }
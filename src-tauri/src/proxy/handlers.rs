@@ -1,20 +1,24 @@
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode, Uri},
+    extract::{Extension, State},
+    http::{header, HeaderMap, StatusCode, Uri},
     response::{IntoResponse, Response},
     Json,
 };
 use bytes::Bytes;
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::time::{Duration, Instant};
 
 use crate::{app_config::AppType, provider::Provider};
 
 use super::{
+    client_auth::ClientIdentity,
     error::ProxyError,
     forwarder::{ForwardOptions, RequestForwarder},
     handler_context::HandlerContext,
-    metrics::estimate_tokens_from_value,
+    metrics::{estimate_anthropic_input_tokens, estimate_tokens_from_value},
+    models::{self, ModelListFormat},
+    prometheus,
     providers::{ClaudeAdapter, ProviderAdapter},
     response::{
         build_anthropic_stream_response, build_buffered_codex_chat_response,
@@ -34,26 +38,222 @@ pub async fn health_check() -> impl IntoResponse {
     (StatusCode::OK, Json(json!({ "ok": true })))
 }
 
-pub async fn get_status(State(state): State<ProxyServerState>) -> impl IntoResponse {
-    Json(state.snapshot_status().await)
+/// 托管会话令牌只返回给已经持有它的调用方（守护进程与 CLI 用它确认 worker 身份），
+/// 令牌同时是控制接口的凭据，不能经由 `/status` 泄露
+pub async fn get_status(
+    State(state): State<ProxyServerState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let mut status = state.snapshot_status().await;
+    if !state
+        .is_managed_session_token(session_token_header(&headers))
+        .await
+    {
+        status.managed_session_token = None;
+    }
+    Json(status)
+}
+
+pub async fn get_metrics(State(state): State<ProxyServerState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        prometheus::render(&state).await,
+    )
+}
+
+/// 守护进程与 CLI 调用 worker 的 `/status` 与控制接口时携带的会话令牌请求头
+pub const CONTROL_SESSION_TOKEN_HEADER: &str = "x-cc-switch-session-token";
+
+pub(crate) fn session_token_header(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(CONTROL_SESSION_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+const COUNT_TOKENS_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+pub struct CircuitResetRequest {
+    pub app_type: String,
+    pub provider_id: String,
+}
+
+async fn authorize_control(state: &ProxyServerState, headers: &HeaderMap) -> Option<Response> {
+    if state
+        .is_managed_session_token(session_token_header(headers))
+        .await
+    {
+        return None;
+    }
+    Some(
+        (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "session token mismatch" })),
+        )
+            .into_response(),
+    )
+}
+
+/// 关闭指定供应商的熔断器（健康记录由守护进程在数据库中清除）
+pub async fn control_reset_circuit_breaker(
+    State(state): State<ProxyServerState>,
+    headers: HeaderMap,
+    Json(body): Json<CircuitResetRequest>,
+) -> Response {
+    if let Some(rejected) = authorize_control(&state, &headers).await {
+        return rejected;
+    }
+    state
+        .provider_router
+        .reset_provider_breaker(&body.provider_id, &body.app_type)
+        .await;
+    (StatusCode::OK, Json(json!({ "ok": true }))).into_response()
+}
+
+/// 重新读取设置与代理配置
+pub async fn control_reload(State(state): State<ProxyServerState>, headers: HeaderMap) -> Response {
+    if let Some(rejected) = authorize_control(&state, &headers).await {
+        return rejected;
+    }
+    match state.reload_config().await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "ok": true }))).into_response(),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": error.to_string() })),
+        )
+            .into_response(),
+    }
 }
 
 pub async fn handle_messages(
     State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    handle_claude_request(state, client.map(|Extension(client)| client), headers, body).await
+}
+
+/// `POST /v1/messages/count_tokens`：当前供应商为 Anthropic 原生格式时透传到上游；
+/// 需要格式转换的供应商没有该接口，由代理在本地估算输入 token
+pub async fn handle_count_tokens(
+    State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
     headers: HeaderMap,
     Json(body): Json<Value>,
 ) -> Response {
-    handle_claude_request(state, headers, body).await
+    let client = client.map(|Extension(client)| client);
+    if let Some(client) = &client {
+        if !client.allows_app(AppType::Claude.as_str()) {
+            return proxy_error_response(ProxyError::AuthError(
+                "client token is not allowed to use claude".to_string(),
+            ));
+        }
+    }
+
+    let providers = match state
+        .provider_router
+        .select_providers(AppType::Claude.as_str())
+        .await
+    {
+        Ok(providers) => providers
+            .into_iter()
+            .filter(|provider| match &client {
+                Some(client) => client.allows_provider(&provider.id),
+                None => true,
+            })
+            .collect::<Vec<_>>(),
+        Err(error) => return proxy_error_response(error),
+    };
+
+    let adapter = ClaudeAdapter::new();
+    let primary_is_native = providers
+        .first()
+        .is_some_and(|provider| !adapter.needs_transform(provider));
+    if primary_is_native {
+        let native = providers
+            .into_iter()
+            .filter(|provider| !adapter.needs_transform(provider))
+            .collect::<Vec<_>>();
+        if let Some(response) = forward_count_tokens(&state, &headers, body.clone(), native).await {
+            return response;
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({ "input_tokens": estimate_anthropic_input_tokens(&body) })),
+    )
+        .into_response()
+}
+
+/// 透传 count_tokens；上游失败或不支持时返回 `None`，由调用方改用本地估算。
+/// 不计入熔断器，避免不支持该接口的中转站影响正常的消息请求
+async fn forward_count_tokens(
+    state: &ProxyServerState,
+    headers: &HeaderMap,
+    body: Value,
+    providers: Vec<Provider>,
+) -> Option<Response> {
+    let forwarder = RequestForwarder::new(state.provider_router.clone())
+        .ok()?
+        .with_model_rules(state.provider_router.model_rules(AppType::Claude.as_str()));
+    let options = ForwardOptions {
+        max_retries: 0,
+        request_timeout: Some(COUNT_TOKENS_TIMEOUT),
+        bypass_circuit_breaker: true,
+    };
+
+    match forwarder
+        .forward_buffered_response_detailed(
+            &AppType::Claude,
+            "/v1/messages/count_tokens",
+            body,
+            headers,
+            providers,
+            options,
+            state.db.get_rectifier_config().unwrap_or_default(),
+        )
+        .await
+    {
+        Ok(forwarded) if forwarded.response.status.is_success() => {
+            let response = forwarded.response;
+            build_buffered_passthrough_response(response.status, &response.headers, response.body)
+                .map(|prepared| prepared.response)
+                .ok()
+        }
+        Ok(forwarded) => {
+            log::debug!(
+                "count_tokens not supported by {} (status {}), estimating locally",
+                forwarded.provider.id,
+                forwarded.response.status
+            );
+            None
+        }
+        Err(failure) => {
+            log::debug!(
+                "count_tokens passthrough failed, estimating locally: {}",
+                failure.error
+            );
+            None
+        }
+    }
 }
 
 pub async fn handle_chat_completions(
     State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
     uri: Uri,
     headers: HeaderMap,
     Json(body): Json<Value>,
 ) -> Response {
     handle_passthrough_request(
         state,
+        client.map(|Extension(client)| client),
         headers,
         body,
         AppType::Codex,
@@ -64,12 +264,14 @@ pub async fn handle_chat_completions(
 
 pub async fn handle_responses(
     State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
     uri: Uri,
     headers: HeaderMap,
     Json(body): Json<Value>,
 ) -> Response {
     handle_passthrough_request(
         state,
+        client.map(|Extension(client)| client),
         headers,
         body,
         AppType::Codex,
@@ -80,12 +282,14 @@ pub async fn handle_responses(
 
 pub async fn handle_responses_compact(
     State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
     uri: Uri,
     headers: HeaderMap,
     Json(body): Json<Value>,
 ) -> Response {
     handle_passthrough_request(
         state,
+        client.map(|Extension(client)| client),
         headers,
         body,
         AppType::Codex,
@@ -103,6 +307,7 @@ fn endpoint_with_query(uri: &Uri, endpoint: &str) -> String {
 
 pub async fn handle_gemini(
     State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
     uri: Uri,
     headers: HeaderMap,
     Json(body): Json<Value>,
@@ -115,31 +320,123 @@ pub async fn handle_gemini(
         .strip_prefix("/gemini")
         .unwrap_or(endpoint.as_str())
         .to_string();
-    handle_passthrough_request(state, headers, body, AppType::Gemini, endpoint).await
+    handle_passthrough_request(
+        state,
+        client.map(|Extension(client)| client),
+        headers,
+        body,
+        AppType::Gemini,
+        endpoint,
+    )
+    .await
+}
+
+/// `GET /v1/models`：携带 `anthropic-version` 的 Claude 客户端返回 Anthropic 格式，
+/// 其余按 OpenAI 格式返回 Codex 可用模型
+pub async fn handle_models(
+    State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
+    headers: HeaderMap,
+) -> Response {
+    let client = client.map(|Extension(client)| client);
+    if headers.contains_key("anthropic-version") {
+        respond_with_models(state, client, AppType::Claude, ModelListFormat::Anthropic).await
+    } else {
+        respond_with_models(state, client, AppType::Codex, ModelListFormat::OpenAi).await
+    }
+}
+
+pub async fn handle_claude_models(
+    State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
+) -> Response {
+    let client = client.map(|Extension(client)| client);
+    respond_with_models(state, client, AppType::Claude, ModelListFormat::Anthropic).await
+}
+
+pub async fn handle_codex_models(
+    State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
+) -> Response {
+    let client = client.map(|Extension(client)| client);
+    respond_with_models(state, client, AppType::Codex, ModelListFormat::OpenAi).await
+}
+
+/// Gemini 的 GET 请求与 POST 共用 `/v1beta/*path` 路由，这里只响应模型列表
+pub async fn handle_gemini_get(
+    State(state): State<ProxyServerState>,
+    client: Option<Extension<ClientIdentity>>,
+    uri: Uri,
+) -> Response {
+    let path = uri.path();
+    let path = path.strip_prefix("/gemini").unwrap_or(path);
+    if path.trim_end_matches('/') != "/v1beta/models" {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let client = client.map(|Extension(client)| client);
+    respond_with_models(state, client, AppType::Gemini, ModelListFormat::Gemini).await
+}
+
+async fn respond_with_models(
+    state: ProxyServerState,
+    client: Option<ClientIdentity>,
+    app_type: AppType,
+    format: ModelListFormat,
+) -> Response {
+    if let Some(client) = &client {
+        if !client.allows_app(app_type.as_str()) {
+            log::warn!(
+                "client token '{}' is not allowed to list {} models",
+                client.name,
+                app_type.as_str()
+            );
+            return proxy_error_response(ProxyError::AuthError(format!(
+                "client token is not allowed to use {}",
+                app_type.as_str()
+            )));
+        }
+    }
+
+    match models::list_models(&state, &app_type, client.as_ref()).await {
+        Ok(listed) => (
+            StatusCode::OK,
+            Json(models::render_model_list(format, &listed)),
+        )
+            .into_response(),
+        Err(error) => proxy_error_response(error),
+    }
 }
 
 async fn handle_claude_request(
     state: ProxyServerState,
+    client: Option<ClientIdentity>,
     headers: HeaderMap,
     body: Value,
 ) -> Response {
     state
         .record_estimated_input_tokens(estimate_tokens_from_value(&body))
         .await;
-    let context = match HandlerContext::load(&state, AppType::Claude, &headers, &body).await {
+    let mut context = match HandlerContext::load(&state, AppType::Claude, &headers, &body).await {
         Ok(context) => context,
         Err(error) => {
             state.record_request_error(&error).await;
             return proxy_error_response(error);
         }
     };
+    if let Err(error) = context.apply_client(client) {
+        state.record_request_error(&error).await;
+        return proxy_error_response(error);
+    }
+    context.start_capture("/v1/messages", &headers, &body);
 
     let forwarder = match RequestForwarder::new(context.provider_router.clone()) {
         Ok(forwarder) => forwarder
             .with_optimizer_config(context.optimizer_config.clone())
             .with_copilot_optimizer_config(context.copilot_optimizer_config.clone())
             .with_session(context.session_id.clone(), context.session_client_provided)
-            .with_gemini_shadow(context.state.gemini_shadow.clone()),
+            .with_model_rules(context.model_rules.clone())
+            .with_gemini_shadow(context.state.gemini_shadow.clone())
+            .with_capture(context.capture.clone()),
         Err(error) => {
             context.state.record_request_error(&error).await;
             return proxy_error_response(error);
@@ -460,6 +757,7 @@ fn should_use_claude_transform_streaming(
 
 async fn handle_passthrough_request(
     state: ProxyServerState,
+    client: Option<ClientIdentity>,
     headers: HeaderMap,
     body: Value,
     app_type: AppType,
@@ -468,20 +766,27 @@ async fn handle_passthrough_request(
     state
         .record_estimated_input_tokens(estimate_tokens_from_value(&body))
         .await;
-    let context = match HandlerContext::load(&state, app_type, &headers, &body).await {
+    let mut context = match HandlerContext::load(&state, app_type, &headers, &body).await {
         Ok(context) => context,
         Err(error) => {
             state.record_request_error(&error).await;
             return proxy_error_response(error);
         }
     };
+    if let Err(error) = context.apply_client(client) {
+        state.record_request_error(&error).await;
+        return proxy_error_response(error);
+    }
+    context.start_capture(&endpoint, &headers, &body);
 
     let forwarder = match RequestForwarder::new(context.provider_router.clone()) {
         Ok(forwarder) => forwarder
             .with_optimizer_config(context.optimizer_config.clone())
             .with_copilot_optimizer_config(context.copilot_optimizer_config.clone())
             .with_session(context.session_id.clone(), context.session_client_provided)
-            .with_codex_chat_history(context.state.codex_chat_history.clone()),
+            .with_model_rules(context.model_rules.clone())
+            .with_codex_chat_history(context.state.codex_chat_history.clone())
+            .with_capture(context.capture.clone()),
         Err(error) => {
             context.state.record_request_error(&error).await;
             return proxy_error_response(error);
@@ -889,6 +1194,7 @@ fn codex_proxy_error_code(error: &ProxyError) -> &'static str {
         ProxyError::NoAvailableProvider => "cc_switch_no_available_provider",
         ProxyError::AllProvidersCircuitOpen => "cc_switch_all_providers_circuit_open",
         ProxyError::NoProvidersConfigured => "cc_switch_no_providers_configured",
+        ProxyError::SpendLimitExceeded(_) => "cc_switch_spend_limit_exceeded",
         ProxyError::MaxRetriesExceeded => "cc_switch_max_retries_exceeded",
         ProxyError::ProviderUnhealthy(_) => "cc_switch_provider_unhealthy",
         ProxyError::ConfigError(_) => "cc_switch_config_error",
@@ -1130,9 +1436,10 @@ fn remaining_timeout(timeout: Option<Duration>, started_at: Instant) -> Option<D
 #[cfg(test)]
 mod tests {
     use super::{
-        build_buffered_claude_transform_response, endpoint_with_query, handle_responses,
-        handle_responses_compact, responses_sse_to_response_value,
-        should_use_claude_transform_streaming,
+        build_buffered_claude_transform_response, control_reload, control_reset_circuit_breaker,
+        endpoint_with_query, get_status, handle_responses, handle_responses_compact,
+        responses_sse_to_response_value, should_use_claude_transform_streaming,
+        CircuitResetRequest, CONTROL_SESSION_TOKEN_HEADER,
     };
     use crate::{
         app_config::AppType,
@@ -1151,7 +1458,7 @@ mod tests {
         body::{to_bytes, Body},
         extract::State,
         http::{HeaderMap, StatusCode, Uri},
-        response::Response,
+        response::{IntoResponse, Response},
         routing::any,
         Json, Router,
     };
@@ -1222,6 +1529,9 @@ mod tests {
             provider_router: Arc::new(ProviderRouter::new(db)),
             codex_chat_history: Arc::new(CodexChatHistoryStore::default()),
             gemini_shadow: Arc::new(GeminiShadowStore::default()),
+            metrics: Arc::new(Default::default()),
+            events: Default::default(),
+            client_tokens: Arc::new(Default::default()),
         }
     }
 
@@ -1348,6 +1658,7 @@ mod tests {
             Duration::from_millis(500),
             handle_responses(
                 State(state),
+                None,
                 Uri::from_static("/v1/responses"),
                 HeaderMap::new(),
                 Json(json!({
@@ -1415,6 +1726,7 @@ mod tests {
 
         let response = handle_responses(
             State(state),
+            None,
             Uri::from_static("/v1/responses"),
             HeaderMap::new(),
             Json(json!({
@@ -1464,6 +1776,7 @@ mod tests {
 
         let response = handle_responses(
             State(state),
+            None,
             Uri::from_static("/v1/responses?beta=true"),
             HeaderMap::new(),
             Json(json!({
@@ -1516,6 +1829,7 @@ mod tests {
 
         let response = handle_responses_compact(
             State(state),
+            None,
             Uri::from_static("/v1/responses/compact?beta=true"),
             HeaderMap::new(),
             Json(json!({
@@ -1691,4 +2005,57 @@ data: {\"type\":\"response.output_item.done\",\"item\":{\"type\":\"message\"}}\n
 
         assert!(responses_sse_to_response_value(sse).is_err());
     }
+
+    #[tokio::test]
+    async fn control_endpoints_require_managed_session_token() {
+        let db = Arc::new(Database::memory().expect("create memory db"));
+        let state = codex_test_state(db);
+        state.status.write().await.managed_session_token = Some("token-1".to_string());
+        let body = || {
+            Json(CircuitResetRequest {
+                app_type: "codex".to_string(),
+                provider_id: "relay".to_string(),
+            })
+        };
+
+        let response =
+            control_reset_circuit_breaker(State(state.clone()), HeaderMap::new(), body()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let mut wrong = HeaderMap::new();
+        wrong.insert(CONTROL_SESSION_TOKEN_HEADER, "token-2".parse().unwrap());
+        let response = control_reload(State(state.clone()), wrong).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTROL_SESSION_TOKEN_HEADER, "token-1".parse().unwrap());
+        let response = control_reset_circuit_breaker(State(state), headers, body()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn status_only_returns_session_token_to_callers_that_present_it() {
+        let db = Arc::new(Database::memory().expect("create memory db"));
+        let state = codex_test_state(db);
+        state.status.write().await.managed_session_token = Some("token-1".to_string());
+
+        let body = get_status(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response()
+            .into_body();
+        let body = to_bytes(body, usize::MAX).await.expect("read status body");
+        let body = String::from_utf8(body.to_vec()).expect("status body is utf-8");
+        assert!(!body.contains("token-1"), "{body}");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTROL_SESSION_TOKEN_HEADER, "token-1".parse().unwrap());
+        let body = get_status(State(state), headers)
+            .await
+            .into_response()
+            .into_body();
+        let status: ProxyStatus =
+            serde_json::from_slice(&to_bytes(body, usize::MAX).await.unwrap())
+                .expect("parse status");
+        assert_eq!(status.managed_session_token.as_deref(), Some("token-1"));
+    }
 }
@@ -1,6 +1,12 @@
 use serde_json::Value;
 
 const APPROX_CHARS_PER_TOKEN: u64 = 4;
+/// 每条消息的角色与分隔符开销
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+/// 图片 / 文档块无法在本地解码，按 Anthropic 常见尺寸图片的近似值计
+const ATTACHMENT_TOKENS: u64 = 1_600;
+/// 声明工具时上游注入的工具使用说明
+const TOOLS_PREAMBLE_TOKENS: u64 = 300;
 
 pub(crate) fn estimate_tokens_from_value(value: &Value) -> u64 {
     serde_json::to_string(value)
@@ -25,6 +31,72 @@ pub(crate) fn estimate_tokens_from_char_count(char_count: u64) -> u64 {
     }
 }
 
+/// 按 Anthropic Messages 请求结构估算输入 token，用于本地应答 count_tokens
+pub(crate) fn estimate_anthropic_input_tokens(body: &Value) -> u64 {
+    let mut tokens = body.get("system").map(estimate_content_tokens).unwrap_or(0);
+
+    if let Some(messages) = body.get("messages").and_then(Value::as_array) {
+        for message in messages {
+            tokens += MESSAGE_OVERHEAD_TOKENS;
+            tokens += message
+                .get("content")
+                .map(estimate_content_tokens)
+                .unwrap_or(0);
+        }
+    }
+
+    if let Some(tools) = body.get("tools").and_then(Value::as_array) {
+        if !tools.is_empty() {
+            tokens += TOOLS_PREAMBLE_TOKENS;
+            tokens += tools.iter().map(estimate_tokens_from_value).sum::<u64>();
+        }
+    }
+
+    tokens
+}
+
+fn estimate_content_tokens(content: &Value) -> u64 {
+    match content {
+        Value::String(text) => estimate_tokens_from_text(text),
+        Value::Array(blocks) => blocks.iter().map(estimate_block_tokens).sum(),
+        Value::Null => 0,
+        other => estimate_tokens_from_value(other),
+    }
+}
+
+fn estimate_block_tokens(block: &Value) -> u64 {
+    match block.get("type").and_then(Value::as_str) {
+        Some("text") => block
+            .get("text")
+            .and_then(Value::as_str)
+            .map(estimate_tokens_from_text)
+            .unwrap_or(0),
+        Some("thinking") => block
+            .get("thinking")
+            .and_then(Value::as_str)
+            .map(estimate_tokens_from_text)
+            .unwrap_or(0),
+        Some("redacted_thinking") => 0,
+        Some("image") | Some("document") => ATTACHMENT_TOKENS,
+        Some("tool_use") => {
+            let name = block
+                .get("name")
+                .and_then(Value::as_str)
+                .map(estimate_tokens_from_text)
+                .unwrap_or(0);
+            name + block
+                .get("input")
+                .map(estimate_tokens_from_value)
+                .unwrap_or(0)
+        }
+        Some("tool_result") => block
+            .get("content")
+            .map(estimate_content_tokens)
+            .unwrap_or(0),
+        _ => estimate_tokens_from_value(block),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +116,33 @@ mod tests {
 
         assert!(estimate_tokens_from_value(&value) > 0);
     }
+
+    #[test]
+    fn estimate_anthropic_input_tokens_counts_system_messages_and_tools() {
+        let text_only = json!({
+            "model": "claude-sonnet-4-5",
+            "system": [{ "type": "text", "text": "a".repeat(40) }],
+            "messages": [{ "role": "user", "content": "b".repeat(400) }],
+        });
+        assert_eq!(
+            estimate_anthropic_input_tokens(&text_only),
+            10 + MESSAGE_OVERHEAD_TOKENS + 100
+        );
+
+        let with_tools = json!({
+            "messages": [{
+                "role": "user",
+                "content": [
+                    { "type": "image", "source": { "type": "base64", "data": "x".repeat(10_000) } },
+                    { "type": "tool_result", "tool_use_id": "t1", "content": "c".repeat(80) },
+                ],
+            }],
+            "tools": [{ "name": "read_file", "input_schema": { "type": "object" } }],
+        });
+        let estimated = estimate_anthropic_input_tokens(&with_tools);
+        assert!(
+            estimated > MESSAGE_OVERHEAD_TOKENS + ATTACHMENT_TOKENS + 20 + TOOLS_PREAMBLE_TOKENS
+        );
+        assert!(estimated < 2_500);
+    }
 }
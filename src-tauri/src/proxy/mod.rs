@@ -1,8 +1,11 @@
 pub mod body_filter;
 pub mod cache_injector;
+pub mod capture;
 pub mod circuit_breaker;
+pub mod client_auth;
 pub mod copilot_optimizer;
 pub mod error;
+pub mod events;
 pub mod forwarder;
 pub mod gemini_url;
 pub mod handler_context;
@@ -11,6 +14,8 @@ pub mod http_client;
 pub(crate) mod json_canonical;
 pub mod metrics;
 pub mod model_mapper;
+pub mod models;
+pub mod prometheus;
 pub mod provider_router;
 pub mod providers;
 pub mod response;
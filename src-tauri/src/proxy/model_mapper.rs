@@ -1,4 +1,6 @@
 use crate::provider::Provider;
+use crate::proxy::types::{ModelMatchType, ModelRoutingRule};
+use regex::Regex;
 use serde_json::Value;
 
 const ONE_M_CONTEXT_MARKER: &str = "[1m]";
@@ -97,6 +99,157 @@ pub fn apply_model_mapping(
     (body, original_model, None)
 }
 
+/// 将通配符模式转换为忽略大小写的正则
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("(?i)^");
+    for ch in pattern.chars() {
+        match ch {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            other => regex.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// 编译规则模式；三种匹配方式都忽略大小写
+fn compile_rule_pattern(match_type: ModelMatchType, pattern: &str) -> Result<Regex, String> {
+    let source = match match_type {
+        ModelMatchType::Exact => format!("(?i)^{}$", regex::escape(pattern)),
+        ModelMatchType::Glob => glob_to_regex(pattern),
+        ModelMatchType::Regex => format!("(?i){pattern}"),
+    };
+    Regex::new(&source).map_err(|e| format!("Invalid pattern '{pattern}': {e}"))
+}
+
+/// 校验规则：模式非空且可编译，且至少改写模型或固定供应商之一
+pub fn validate_model_routing_rule(rule: &ModelRoutingRule) -> Result<(), String> {
+    if rule.pattern.trim().is_empty() {
+        return Err("Model routing rule pattern cannot be empty".to_string());
+    }
+    compile_rule_pattern(rule.match_type, &rule.pattern)?;
+
+    let has_target = rule
+        .target_model
+        .as_deref()
+        .is_some_and(|model| !model.trim().is_empty());
+    let has_pin = rule
+        .pin_provider_id
+        .as_deref()
+        .is_some_and(|id| !id.trim().is_empty());
+    if !has_target && !has_pin {
+        return Err(format!(
+            "Model routing rule '{}' must set a target model or a pinned provider",
+            rule.pattern
+        ));
+    }
+    Ok(())
+}
+
+/// 已编译的模型路由规则，匹配时不再重复编译正则
+#[derive(Debug, Clone)]
+pub struct CompiledModelRule {
+    pub rule: ModelRoutingRule,
+    /// 精确匹配与无效模式为 None；无效模式视为不匹配
+    matcher: Option<Regex>,
+}
+
+impl CompiledModelRule {
+    pub fn new(rule: ModelRoutingRule) -> Self {
+        let matcher = match rule.match_type {
+            ModelMatchType::Exact => None,
+            _ => compile_rule_pattern(rule.match_type, &rule.pattern).ok(),
+        };
+        Self { rule, matcher }
+    }
+
+    /// 判断规则是否匹配模型名（忽略大小写）
+    pub fn matches(&self, model: &str) -> bool {
+        match self.rule.match_type {
+            ModelMatchType::Exact => self.rule.pattern.eq_ignore_ascii_case(model),
+            _ => self
+                .matcher
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(model)),
+        }
+    }
+}
+
+/// 按匹配顺序编译规则
+pub fn compile_model_rules(rules: Vec<ModelRoutingRule>) -> Vec<CompiledModelRule> {
+    rules.into_iter().map(CompiledModelRule::new).collect()
+}
+
+/// 查找决定供应商的规则：首条命中且设置了 `pin_provider_id` 的全局（不限供应商）规则
+pub fn find_pinned_provider_rule<'a>(
+    rules: &'a [CompiledModelRule],
+    model: &str,
+) -> Option<&'a ModelRoutingRule> {
+    rules
+        .iter()
+        .find(|compiled| {
+            let rule = &compiled.rule;
+            rule.enabled
+                && rule.provider_id.is_none()
+                && rule.pin_provider_id.is_some()
+                && compiled.matches(model)
+        })
+        .map(|compiled| &compiled.rule)
+}
+
+/// 查找对指定供应商生效的改写规则
+///
+/// 固定到其他供应商的规则不会改写当前供应商的请求，
+/// 避免故障转移到后备供应商时带上不属于它的模型名。
+pub fn find_model_rewrite_rule<'a>(
+    rules: &'a [CompiledModelRule],
+    provider_id: &str,
+    model: &str,
+) -> Option<&'a ModelRoutingRule> {
+    rules
+        .iter()
+        .find(|compiled| {
+            let rule = &compiled.rule;
+            rule.enabled
+                && rule.target_model.is_some()
+                && rule
+                    .provider_id
+                    .as_deref()
+                    .is_none_or(|scope| scope == provider_id)
+                && rule
+                    .pin_provider_id
+                    .as_deref()
+                    .is_none_or(|pinned| pinned == provider_id)
+                && compiled.matches(model)
+        })
+        .map(|compiled| &compiled.rule)
+}
+
+/// 先按路由规则改写模型，未命中时回退到供应商自身的模型映射
+pub fn apply_model_routing(
+    mut body: Value,
+    provider: &Provider,
+    rules: &[CompiledModelRule],
+) -> (Value, Option<String>, Option<String>) {
+    let original_model = body.get("model").and_then(Value::as_str).map(String::from);
+
+    if let Some(original) = &original_model {
+        if let Some(target) = find_model_rewrite_rule(rules, &provider.id, original)
+            .and_then(|rule| rule.target_model.as_deref())
+            .filter(|target| !target.is_empty())
+        {
+            if target != original {
+                body["model"] = serde_json::json!(target);
+                return (body, Some(original.clone()), Some(target.to_string()));
+            }
+            return (body, original_model, None);
+        }
+    }
+
+    apply_model_mapping(body, provider)
+}
+
 pub fn strip_one_m_suffix_for_upstream(model: &str) -> &str {
     let trimmed = model.trim_end();
     let marker = ONE_M_CONTEXT_MARKER.as_bytes();
@@ -162,6 +315,105 @@ mod tests {
         assert_eq!(mapped, Some("sonnet-mapped".to_string()));
     }
 
+    fn rule(match_type: ModelMatchType, pattern: &str) -> ModelRoutingRule {
+        ModelRoutingRule {
+            id: 0,
+            app_type: None,
+            provider_id: None,
+            match_type,
+            pattern: pattern.to_string(),
+            target_model: None,
+            pin_provider_id: None,
+            sort_index: 0,
+            enabled: true,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn routing_rules_match_exact_glob_and_regex_ignoring_case() {
+        let exact = CompiledModelRule::new(rule(ModelMatchType::Exact, "gpt-5-mini"));
+        assert!(exact.matches("GPT-5-mini"));
+        assert!(!exact.matches("gpt-5-mini-high"));
+
+        let glob = CompiledModelRule::new(rule(ModelMatchType::Glob, "claude-*-haiku-?"));
+        assert!(glob.matches("Claude-3-5-haiku-1"));
+        assert!(!glob.matches("claude-3-5-haiku-12"));
+
+        let regex = CompiledModelRule::new(rule(ModelMatchType::Regex, r"^o[34](-mini)?$"));
+        assert!(regex.matches("O4-mini"));
+        assert!(!regex.matches("o1"));
+    }
+
+    #[test]
+    fn routing_rule_rewrite_takes_precedence_over_provider_mapping() {
+        let provider = provider_with_mapping("sonnet-mapped");
+        let mut first = rule(ModelMatchType::Glob, "*sonnet*");
+        first.target_model = Some("relay-sonnet".to_string());
+        let mut second = rule(ModelMatchType::Glob, "*");
+        second.target_model = Some("catch-all".to_string());
+        let rules = compile_model_rules(vec![first, second]);
+
+        let (result, original, mapped) =
+            apply_model_routing(json!({"model": "claude-sonnet-4-6"}), &provider, &rules);
+
+        assert_eq!(result["model"], "relay-sonnet");
+        assert_eq!(original.as_deref(), Some("claude-sonnet-4-6"));
+        assert_eq!(mapped.as_deref(), Some("relay-sonnet"));
+    }
+
+    #[test]
+    fn routing_rule_scoped_to_other_provider_falls_back_to_mapping() {
+        let provider = provider_with_mapping("sonnet-mapped");
+        let mut scoped = rule(ModelMatchType::Glob, "*sonnet*");
+        scoped.provider_id = Some("other".to_string());
+        scoped.target_model = Some("relay-sonnet".to_string());
+        let mut pinned = rule(ModelMatchType::Glob, "*sonnet*");
+        pinned.pin_provider_id = Some("other".to_string());
+        pinned.target_model = Some("pinned-sonnet".to_string());
+
+        let (result, _, _) = apply_model_routing(
+            json!({"model": "claude-sonnet-4-6"}),
+            &provider,
+            &compile_model_rules(vec![scoped, pinned]),
+        );
+
+        assert_eq!(result["model"], "sonnet-mapped");
+    }
+
+    #[test]
+    fn pinned_provider_rule_skips_disabled_and_provider_scoped_rules() {
+        let mut disabled = rule(ModelMatchType::Glob, "*haiku*");
+        disabled.pin_provider_id = Some("disabled".to_string());
+        disabled.enabled = false;
+        let mut scoped = rule(ModelMatchType::Glob, "*haiku*");
+        scoped.provider_id = Some("scope".to_string());
+        scoped.pin_provider_id = Some("scoped".to_string());
+        let mut active = rule(ModelMatchType::Glob, "*haiku*");
+        active.pin_provider_id = Some("cheap-relay".to_string());
+        let rules = compile_model_rules(vec![disabled, scoped, active]);
+
+        let pinned = find_pinned_provider_rule(&rules, "claude-haiku-4-5")
+            .and_then(|rule| rule.pin_provider_id.as_deref());
+
+        assert_eq!(pinned, Some("cheap-relay"));
+        assert!(find_pinned_provider_rule(&rules, "claude-opus-4-8").is_none());
+    }
+
+    #[test]
+    fn routing_rule_validation_rejects_bad_patterns_and_no_op_rules() {
+        let mut invalid = rule(ModelMatchType::Regex, "(unclosed");
+        invalid.target_model = Some("x".to_string());
+        assert!(validate_model_routing_rule(&invalid).is_err());
+
+        let no_op = rule(ModelMatchType::Exact, "gpt-5");
+        assert!(validate_model_routing_rule(&no_op).is_err());
+
+        let mut pinned = rule(ModelMatchType::Exact, "gpt-5");
+        pinned.pin_provider_id = Some("relay".to_string());
+        assert!(validate_model_routing_rule(&pinned).is_ok());
+    }
+
     #[test]
     fn strips_one_m_suffix_before_upstream() {
         let body = json!({"model": "deepseek-v4-pro[1M]"});
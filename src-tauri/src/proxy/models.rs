@@ -0,0 +1,489 @@
+//! 代理模型列表
+//!
+//! 汇总当前供应商（开启故障转移时为整个队列）可用的模型，连同模型映射与精确匹配的
+//! 路由规则别名，按 OpenAI / Anthropic / Gemini 的模型列表格式返回给客户端。
+//! 上游 `/models` 结果按供应商缓存，各供应商并发拉取；拉取失败时回退到供应商配置中的模型，
+//! 失败结果也会短暂缓存，避免不支持 `/models` 的上游拖慢每次请求。
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+use super::{
+    client_auth::ClientIdentity,
+    error::ProxyError,
+    http_client,
+    model_mapper::ModelMapping,
+    providers::{get_adapter, AuthStrategy},
+    server::ProxyServerState,
+    types::ModelMatchType,
+};
+
+const MODEL_CACHE_TTL: Duration = Duration::from_secs(300);
+/// 拉取失败（或上游返回空列表）的缓存有效期
+const MODEL_FETCH_FAILURE_TTL: Duration = Duration::from_secs(60);
+const MODEL_FETCH_TIMEOUT: Duration = Duration::from_secs(8);
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// 模型列表响应格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelListFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+}
+
+/// 代理可提供的单个模型；`alias_of` 不为空时表示该 ID 会被改写为目标模型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedModel {
+    pub id: String,
+    pub provider_id: String,
+    pub alias_of: Option<String>,
+}
+
+type ModelCache = Mutex<HashMap<String, (Instant, Vec<String>)>>;
+
+fn model_cache() -> &'static ModelCache {
+    static CACHE: OnceLock<ModelCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 收集指定应用可用的模型（含别名），客户端令牌限定的供应商之外的不计入
+pub async fn list_models(
+    state: &ProxyServerState,
+    app_type: &AppType,
+    client: Option<&ClientIdentity>,
+) -> Result<Vec<ListedModel>, ProxyError> {
+    let providers = state
+        .provider_router
+        .select_providers(app_type.as_str())
+        .await?
+        .into_iter()
+        .filter(|provider| match client {
+            Some(client) => client.allows_provider(&provider.id),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    let fetched = join_all(
+        providers
+            .iter()
+            .map(|provider| provider_models(app_type, provider)),
+    )
+    .await;
+    let mut models = Vec::new();
+    for (provider, mut ids) in providers.iter().zip(fetched) {
+        if ids.is_empty() {
+            ids = configured_models(app_type, provider);
+        }
+        models.extend(ids.into_iter().map(|id| ListedModel {
+            id,
+            provider_id: provider.id.clone(),
+            alias_of: None,
+        }));
+    }
+
+    let rules = state
+        .db
+        .list_model_routing_rules(Some(app_type.as_str()))
+        .map_err(|error| ProxyError::DatabaseError(error.to_string()))?;
+    let exact_rules = rules
+        .iter()
+        .filter(|rule| rule.enabled && rule.match_type == ModelMatchType::Exact)
+        .filter_map(|rule| {
+            let target = rule.target_model.as_deref()?.trim();
+            let provider_id = rule
+                .provider_id
+                .as_deref()
+                .or(rule.pin_provider_id.as_deref());
+            if let Some(provider_id) = provider_id {
+                if !providers.iter().any(|provider| provider.id == provider_id) {
+                    return None;
+                }
+            }
+            (!target.is_empty()).then(|| {
+                (
+                    rule.pattern.trim().to_string(),
+                    target.to_string(),
+                    provider_id.unwrap_or_default().to_string(),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    for (pattern, target, provider_id) in exact_rules {
+        let provider_id = if provider_id.is_empty() {
+            providers
+                .first()
+                .map(|provider| provider.id.clone())
+                .unwrap_or_default()
+        } else {
+            provider_id
+        };
+        models.push(ListedModel {
+            id: pattern,
+            provider_id,
+            alias_of: Some(target),
+        });
+    }
+
+    if matches!(app_type, AppType::Claude) {
+        if let Some(provider) = providers.first() {
+            models.extend(claude_mapping_aliases(provider));
+        }
+    }
+
+    Ok(dedupe_models(models))
+}
+
+/// Claude 供应商配置了模型映射时，把 sonnet / opus / haiku 别名指向映射后的模型
+fn claude_mapping_aliases(provider: &Provider) -> Vec<ListedModel> {
+    let mapping = ModelMapping::from_provider(provider);
+    [
+        (
+            "sonnet",
+            mapping.sonnet_model.or(mapping.default_model.clone()),
+        ),
+        ("opus", mapping.opus_model.or(mapping.default_model.clone())),
+        ("haiku", mapping.haiku_model.or(mapping.default_model)),
+    ]
+    .into_iter()
+    .filter_map(|(alias, target)| {
+        target.map(|target| ListedModel {
+            id: alias.to_string(),
+            provider_id: provider.id.clone(),
+            alias_of: Some(target),
+        })
+    })
+    .collect()
+}
+
+/// 供应商配置中显式写明的模型，上游不支持模型列表时作为回退
+fn configured_models(app_type: &AppType, provider: &Provider) -> Vec<String> {
+    let mut models = match app_type {
+        AppType::Claude => {
+            let mapping = ModelMapping::from_provider(provider);
+            [
+                mapping.default_model,
+                mapping.sonnet_model,
+                mapping.opus_model,
+                mapping.haiku_model,
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+        AppType::Gemini => provider
+            .settings_config
+            .get("env")
+            .and_then(|env| env.get("GEMINI_MODEL"))
+            .and_then(Value::as_str)
+            .map(|model| vec![model.to_string()])
+            .unwrap_or_default(),
+        _ => provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .and_then(|config| config.parse::<toml::Table>().ok())
+            .and_then(|table| table.get("model")?.as_str().map(str::to_string))
+            .map(|model| vec![model])
+            .unwrap_or_default(),
+    };
+    models.retain(|model| !model.trim().is_empty());
+    models.dedup();
+    models
+}
+
+async fn provider_models(app_type: &AppType, provider: &Provider) -> Vec<String> {
+    let key = format!("{}:{}", app_type.as_str(), provider.id);
+    if let Ok(cache) = model_cache().lock() {
+        if let Some((fetched_at, models)) = cache.get(&key) {
+            let ttl = if models.is_empty() {
+                MODEL_FETCH_FAILURE_TTL
+            } else {
+                MODEL_CACHE_TTL
+            };
+            if fetched_at.elapsed() < ttl {
+                return models.clone();
+            }
+        }
+    }
+
+    let models = fetch_upstream_models(app_type, provider)
+        .await
+        .unwrap_or_else(|error| {
+            log::debug!(
+                "[Models] fetch model list for {} failed: {error}",
+                provider.id
+            );
+            Vec::new()
+        });
+    if let Ok(mut cache) = model_cache().lock() {
+        cache.insert(key, (Instant::now(), models.clone()));
+    }
+    models
+}
+
+async fn fetch_upstream_models(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<Vec<String>, String> {
+    let is_full_url = provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.is_full_url)
+        .unwrap_or(false);
+    if is_full_url || provider.is_github_copilot() || provider.is_codex_oauth() {
+        return Err("provider does not expose a model list endpoint".to_string());
+    }
+
+    let adapter = get_adapter(app_type);
+    let base_url = adapter
+        .extract_base_url(provider)
+        .map_err(|error| error.to_string())?;
+    let auth = adapter
+        .extract_auth(provider)
+        .ok_or_else(|| "provider has no credentials".to_string())?;
+    if matches!(
+        auth.strategy,
+        AuthStrategy::GitHubCopilot | AuthStrategy::CodexOAuth
+    ) {
+        return Err("managed account providers are not queried".to_string());
+    }
+
+    let endpoint = match app_type {
+        AppType::Claude => "/v1/models",
+        AppType::Gemini => "/v1beta/models?pageSize=1000",
+        _ => "/models",
+    };
+    let url = adapter.build_url(&base_url, endpoint);
+    let client = http_client::get_for_provider(
+        provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.proxy_config.as_ref()),
+    );
+    let mut request = client.get(&url).timeout(MODEL_FETCH_TIMEOUT);
+    if matches!(app_type, AppType::Claude) {
+        request = request.header("anthropic-version", ANTHROPIC_API_VERSION);
+    }
+    let response = adapter
+        .add_auth_headers(request, &auth)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("upstream returned {}", response.status()));
+    }
+    let body = response
+        .json::<Value>()
+        .await
+        .map_err(|error| error.to_string())?;
+    Ok(parse_model_ids(&body))
+}
+
+/// 解析上游模型列表：兼容 OpenAI / Anthropic 的 `data[].id` 与 Gemini 的 `models[].name`
+fn parse_model_ids(body: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    if let Some(data) = body.get("data").and_then(Value::as_array) {
+        ids.extend(
+            data.iter()
+                .filter_map(|model| model.get("id").and_then(Value::as_str))
+                .map(str::to_string),
+        );
+    }
+    if let Some(models) = body.get("models").and_then(Value::as_array) {
+        ids.extend(
+            models
+                .iter()
+                .filter_map(|model| model.get("name").and_then(Value::as_str))
+                .map(|name| name.strip_prefix("models/").unwrap_or(name).to_string()),
+        );
+    }
+    ids
+}
+
+fn dedupe_models(models: Vec<ListedModel>) -> Vec<ListedModel> {
+    let mut seen = std::collections::HashSet::new();
+    models
+        .into_iter()
+        .filter(|model| seen.insert(model.id.to_ascii_lowercase()))
+        .collect()
+}
+
+/// 按客户端期望的格式渲染模型列表
+pub fn render_model_list(format: ModelListFormat, models: &[ListedModel]) -> Value {
+    match format {
+        ModelListFormat::OpenAi => json!({
+            "object": "list",
+            "data": models
+                .iter()
+                .map(|model| {
+                    let mut entry = json!({
+                        "id": model.id,
+                        "object": "model",
+                        "created": 0,
+                        "owned_by": model.provider_id,
+                    });
+                    if let Some(target) = &model.alias_of {
+                        entry["alias_of"] = json!(target);
+                    }
+                    entry
+                })
+                .collect::<Vec<_>>(),
+        }),
+        ModelListFormat::Anthropic => json!({
+            "data": models
+                .iter()
+                .map(|model| json!({
+                    "type": "model",
+                    "id": model.id,
+                    "display_name": display_name(model),
+                    "created_at": "1970-01-01T00:00:00Z",
+                }))
+                .collect::<Vec<_>>(),
+            "has_more": false,
+            "first_id": models.first().map(|model| model.id.as_str()),
+            "last_id": models.last().map(|model| model.id.as_str()),
+        }),
+        ModelListFormat::Gemini => json!({
+            "models": models
+                .iter()
+                .map(|model| json!({
+                    "name": format!("models/{}", model.id),
+                    "baseModelId": model.id,
+                    "displayName": display_name(model),
+                    "supportedGenerationMethods": [
+                        "generateContent",
+                        "streamGenerateContent",
+                        "countTokens",
+                    ],
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn display_name(model: &ListedModel) -> String {
+    match &model.alias_of {
+        Some(target) => format!("{} → {target}", model.id),
+        None => model.id.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(id: &str, alias_of: Option<&str>) -> ListedModel {
+        ListedModel {
+            id: id.to_string(),
+            provider_id: "p1".to_string(),
+            alias_of: alias_of.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parse_model_ids_reads_openai_and_gemini_shapes() {
+        let openai = json!({ "data": [{ "id": "gpt-5" }, { "id": "gpt-5-mini" }] });
+        assert_eq!(parse_model_ids(&openai), vec!["gpt-5", "gpt-5-mini"]);
+
+        let gemini = json!({ "models": [{ "name": "models/gemini-2.5-pro" }] });
+        assert_eq!(parse_model_ids(&gemini), vec!["gemini-2.5-pro"]);
+    }
+
+    #[test]
+    fn claude_mapping_aliases_fall_back_to_default_model() {
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "Mapped".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_MODEL": "glm-4.6",
+                "ANTHROPIC_DEFAULT_HAIKU_MODEL": "glm-4.5-air",
+            }}),
+            None,
+        );
+        let aliases = claude_mapping_aliases(&provider);
+        assert_eq!(aliases.len(), 3);
+        assert_eq!(aliases[0], listed("sonnet", Some("glm-4.6")));
+        assert_eq!(aliases[2], listed("haiku", Some("glm-4.5-air")));
+        assert_eq!(
+            configured_models(&AppType::Claude, &provider),
+            vec!["glm-4.6", "glm-4.5-air"]
+        );
+    }
+
+    #[test]
+    fn render_model_list_matches_client_shapes() {
+        let models = vec![listed("gpt-5", None), listed("fast", Some("gpt-5-mini"))];
+
+        let openai = render_model_list(ModelListFormat::OpenAi, &models);
+        assert_eq!(openai["object"], "list");
+        assert_eq!(openai["data"][1]["object"], "model");
+        assert_eq!(openai["data"][1]["alias_of"], "gpt-5-mini");
+
+        let anthropic = render_model_list(ModelListFormat::Anthropic, &models);
+        assert_eq!(anthropic["data"][0]["type"], "model");
+        assert_eq!(anthropic["first_id"], "gpt-5");
+        assert_eq!(anthropic["last_id"], "fast");
+        assert_eq!(anthropic["has_more"], false);
+
+        let gemini = render_model_list(ModelListFormat::Gemini, &models);
+        assert_eq!(gemini["models"][0]["name"], "models/gpt-5");
+    }
+
+    #[test]
+    fn dedupe_models_keeps_first_occurrence() {
+        let models = dedupe_models(vec![
+            listed("GPT-5", None),
+            listed("gpt-5", Some("other")),
+            listed("o3", None),
+        ]);
+        assert_eq!(models, vec![listed("GPT-5", None), listed("o3", None)]);
+    }
+
+    #[tokio::test]
+    async fn failed_model_fetches_are_cached_briefly() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/v1/models",
+            axum::routing::get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { axum::http::StatusCode::NOT_FOUND }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let provider = Provider::with_id(
+            "no-models-endpoint".to_string(),
+            "Relay".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_BASE_URL": format!("http://{address}"),
+                "ANTHROPIC_AUTH_TOKEN": "sk-test",
+            }}),
+            None,
+        );
+        assert!(provider_models(&AppType::Claude, &provider)
+            .await
+            .is_empty());
+        assert!(provider_models(&AppType::Claude, &provider)
+            .await
+            .is_empty());
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,380 @@
+//! Prometheus 文本格式指标
+//!
+//! 按 (app, provider, model) 维度在内存中累计请求数、延迟直方图、token 与费用，
+//! 由 `/metrics` 端点连同熔断器状态一起导出。计数随代理进程重启归零，
+//! 这符合 Prometheus counter 语义（由 `rate()` / `increase()` 处理重置）。
+
+use std::{collections::BTreeMap, fmt::Write as _, sync::Mutex, time::Duration};
+
+use super::{circuit_breaker::CircuitState, server::ProxyServerState, usage::parser::TokenUsage};
+
+/// 请求耗时直方图桶上界（秒）
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct SeriesKey {
+    app_type: String,
+    provider_id: String,
+    model: String,
+}
+
+#[derive(Debug, Default)]
+struct SeriesStats {
+    requests_by_status: BTreeMap<u16, u64>,
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum_seconds: f64,
+    latency_count: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_read_tokens: u64,
+    cache_creation_tokens: u64,
+    cost_usd: f64,
+}
+
+/// 单次请求的指标样本
+pub struct RequestSample<'a> {
+    pub app_type: &'a str,
+    pub provider_id: &'a str,
+    pub model: &'a str,
+    pub status_code: u16,
+    pub latency: Duration,
+    pub usage: &'a TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// 代理请求指标注册表
+#[derive(Debug, Default)]
+pub struct ProxyMetrics {
+    series: Mutex<BTreeMap<SeriesKey, SeriesStats>>,
+}
+
+impl ProxyMetrics {
+    pub fn record(&self, sample: RequestSample<'_>) {
+        let key = SeriesKey {
+            app_type: sample.app_type.to_string(),
+            provider_id: sample.provider_id.to_string(),
+            model: sample.model.to_string(),
+        };
+        let mut series = self
+            .series
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let stats = series.entry(key).or_default();
+
+        *stats
+            .requests_by_status
+            .entry(sample.status_code)
+            .or_insert(0) += 1;
+
+        let seconds = sample.latency.as_secs_f64();
+        for (bucket, upper) in stats.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= upper {
+                *bucket += 1;
+            }
+        }
+        stats.latency_sum_seconds += seconds;
+        stats.latency_count += 1;
+
+        stats.input_tokens += u64::from(sample.usage.input_tokens);
+        stats.output_tokens += u64::from(sample.usage.output_tokens);
+        stats.cache_read_tokens += u64::from(sample.usage.cache_read_tokens);
+        stats.cache_creation_tokens += u64::from(sample.usage.cache_creation_tokens);
+        if sample.cost_usd.is_finite() {
+            stats.cost_usd += sample.cost_usd;
+        }
+    }
+
+    fn render_series(&self, out: &mut String) {
+        let series = self
+            .series
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        write_header(
+            out,
+            "ccswitch_proxy_requests_total",
+            "counter",
+            "Proxied requests by app, provider, model and HTTP status.",
+        );
+        for (key, stats) in series.iter() {
+            for (status, count) in &stats.requests_by_status {
+                let status = status.to_string();
+                let labels = key.labels(&[("status", status.as_str())]);
+                let _ = writeln!(out, "ccswitch_proxy_requests_total{labels} {count}");
+            }
+        }
+
+        write_header(
+            out,
+            "ccswitch_proxy_request_duration_seconds",
+            "histogram",
+            "End-to-end proxied request latency.",
+        );
+        for (key, stats) in series.iter() {
+            for (upper, count) in LATENCY_BUCKETS.iter().zip(stats.latency_buckets) {
+                let upper = format_float(*upper);
+                let labels = key.labels(&[("le", upper.as_str())]);
+                let _ = writeln!(
+                    out,
+                    "ccswitch_proxy_request_duration_seconds_bucket{labels} {count}"
+                );
+            }
+            let labels = key.labels(&[("le", "+Inf")]);
+            let _ = writeln!(
+                out,
+                "ccswitch_proxy_request_duration_seconds_bucket{labels} {}",
+                stats.latency_count
+            );
+            let labels = key.labels(&[]);
+            let _ = writeln!(
+                out,
+                "ccswitch_proxy_request_duration_seconds_sum{labels} {}",
+                format_float(stats.latency_sum_seconds)
+            );
+            let _ = writeln!(
+                out,
+                "ccswitch_proxy_request_duration_seconds_count{labels} {}",
+                stats.latency_count
+            );
+        }
+
+        write_header(
+            out,
+            "ccswitch_proxy_tokens_total",
+            "counter",
+            "Tokens reported by upstream responses.",
+        );
+        for (key, stats) in series.iter() {
+            for (kind, value) in [
+                ("input", stats.input_tokens),
+                ("output", stats.output_tokens),
+                ("cache_read", stats.cache_read_tokens),
+                ("cache_creation", stats.cache_creation_tokens),
+            ] {
+                let labels = key.labels(&[("type", kind)]);
+                let _ = writeln!(out, "ccswitch_proxy_tokens_total{labels} {value}");
+            }
+        }
+
+        write_header(
+            out,
+            "ccswitch_proxy_cost_usd_total",
+            "counter",
+            "Estimated request cost in USD from the model pricing table.",
+        );
+        for (key, stats) in series.iter() {
+            let labels = key.labels(&[]);
+            let _ = writeln!(
+                out,
+                "ccswitch_proxy_cost_usd_total{labels} {}",
+                format_float(stats.cost_usd)
+            );
+        }
+    }
+}
+
+impl SeriesKey {
+    fn labels(&self, extra: &[(&str, &str)]) -> String {
+        let mut pairs = vec![
+            ("app", self.app_type.as_str()),
+            ("provider", self.provider_id.as_str()),
+            ("model", self.model.as_str()),
+        ];
+        pairs.extend_from_slice(extra);
+        format_labels(&pairs)
+    }
+}
+
+/// 生成完整的 `/metrics` 响应体
+pub async fn render(state: &ProxyServerState) -> String {
+    let mut out = String::new();
+    let status = state.snapshot_status().await;
+
+    write_header(
+        &mut out,
+        "ccswitch_proxy_uptime_seconds",
+        "gauge",
+        "Seconds since the proxy server started.",
+    );
+    let _ = writeln!(
+        out,
+        "ccswitch_proxy_uptime_seconds {}",
+        status.uptime_seconds
+    );
+    write_header(
+        &mut out,
+        "ccswitch_proxy_active_connections",
+        "gauge",
+        "Requests currently being proxied.",
+    );
+    let _ = writeln!(
+        out,
+        "ccswitch_proxy_active_connections {}",
+        status.active_connections
+    );
+
+    state.metrics.render_series(&mut out);
+
+    let breakers = state.provider_router.all_circuit_breaker_stats().await;
+    write_header(
+        &mut out,
+        "ccswitch_circuit_breaker_state",
+        "gauge",
+        "Circuit breaker state per provider (0 = closed, 1 = half-open, 2 = open).",
+    );
+    for (app_type, provider_id, stats) in &breakers {
+        let labels = format_labels(&[
+            ("app", app_type.as_str()),
+            ("provider", provider_id.as_str()),
+        ]);
+        let value = match stats.state {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        };
+        let _ = writeln!(out, "ccswitch_circuit_breaker_state{labels} {value}");
+    }
+    write_header(
+        &mut out,
+        "ccswitch_circuit_breaker_consecutive_failures",
+        "gauge",
+        "Consecutive failures recorded by the circuit breaker.",
+    );
+    for (app_type, provider_id, stats) in &breakers {
+        let labels = format_labels(&[
+            ("app", app_type.as_str()),
+            ("provider", provider_id.as_str()),
+        ]);
+        let _ = writeln!(
+            out,
+            "ccswitch_circuit_breaker_consecutive_failures{labels} {}",
+            stats.consecutive_failures
+        );
+    }
+    write_header(
+        &mut out,
+        "ccswitch_circuit_breaker_failed_requests",
+        "gauge",
+        "Failed requests counted in the circuit breaker's current window.",
+    );
+    for (app_type, provider_id, stats) in &breakers {
+        let labels = format_labels(&[
+            ("app", app_type.as_str()),
+            ("provider", provider_id.as_str()),
+        ]);
+        let _ = writeln!(
+            out,
+            "ccswitch_circuit_breaker_failed_requests{labels} {}",
+            stats.failed_requests
+        );
+    }
+
+    out
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn format_labels(pairs: &[(&str, &str)]) -> String {
+    let body = pairs
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{body}}}")
+}
+
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+fn format_float(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.1}")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u32, output: u32) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            output_tokens: output,
+            ..TokenUsage::default()
+        }
+    }
+
+    #[test]
+    fn records_requests_latency_tokens_and_cost_per_series() {
+        let metrics = ProxyMetrics::default();
+        let first = usage(100, 20);
+        let second = usage(50, 5);
+        metrics.record(RequestSample {
+            app_type: "claude",
+            provider_id: "relay-a",
+            model: "claude-sonnet-4-6",
+            status_code: 200,
+            latency: Duration::from_millis(300),
+            usage: &first,
+            cost_usd: 0.25,
+        });
+        metrics.record(RequestSample {
+            app_type: "claude",
+            provider_id: "relay-a",
+            model: "claude-sonnet-4-6",
+            status_code: 529,
+            latency: Duration::from_secs(4),
+            usage: &second,
+            cost_usd: 0.0,
+        });
+
+        let mut out = String::new();
+        metrics.render_series(&mut out);
+        let base = r#"app="claude",provider="relay-a",model="claude-sonnet-4-6""#;
+
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_requests_total{{{base},status=\"200\"}} 1"
+        )));
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_requests_total{{{base},status=\"529\"}} 1"
+        )));
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_request_duration_seconds_bucket{{{base},le=\"0.5\"}} 1"
+        )));
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_request_duration_seconds_bucket{{{base},le=\"5.0\"}} 2"
+        )));
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_request_duration_seconds_count{{{base}}} 2"
+        )));
+        assert!(out.contains(&format!(
+            "ccswitch_proxy_tokens_total{{{base},type=\"input\"}} 150"
+        )));
+        assert!(out.contains(&format!("ccswitch_proxy_cost_usd_total{{{base}}} 0.25")));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(
+            format_labels(&[("model", "a\"b\\c\nd")]),
+            r#"{model="a\"b\\c\nd"}"#
+        );
+    }
+}
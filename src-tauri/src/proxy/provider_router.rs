@@ -1,19 +1,37 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
 
 use tokio::sync::RwLock;
 
 use crate::{app_config::AppType, database::Database, provider::Provider};
 
+mod load_balancer;
+mod model_rules;
+mod spend_limit;
 mod upstream_endpoint;
 
+pub use load_balancer::InflightGuard;
+
 use super::{
-    circuit_breaker::{AllowResult, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats},
+    circuit_breaker::{
+        AllowResult, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats, CircuitState,
+    },
     error::ProxyError,
+    events::{ProxyEvent, ProxyEventBus},
+    model_mapper::CompiledModelRule,
+    types::SpendLimitConfig,
 };
 
 pub struct ProviderRouter {
     db: Arc<Database>,
     circuit_breakers: Arc<RwLock<HashMap<String, Arc<CircuitBreaker>>>>,
+    load_balancer: Arc<load_balancer::LoadBalancer>,
+    model_rules: Arc<model_rules::ModelRuleCache>,
+    spend_limiter: Arc<spend_limit::SpendLimiter>,
+    events: ProxyEventBus,
 }
 
 impl ProviderRouter {
@@ -21,20 +39,32 @@ impl ProviderRouter {
         Self {
             db,
             circuit_breakers: Arc::new(RwLock::new(HashMap::new())),
+            load_balancer: Arc::new(load_balancer::LoadBalancer::default()),
+            model_rules: Arc::new(model_rules::ModelRuleCache::default()),
+            spend_limiter: Arc::new(spend_limit::SpendLimiter::default()),
+            events: ProxyEventBus::default(),
         }
     }
 
+    /// 熔断器状态变化与消费预警发布到指定事件总线
+    pub fn with_events(mut self, events: ProxyEventBus) -> Self {
+        self.events = events;
+        self
+    }
+
     pub async fn select_providers(&self, app_type: &str) -> Result<Vec<Provider>, ProxyError> {
         let mut result = Vec::new();
         let mut total_providers = 0usize;
         let mut circuit_open_count = 0usize;
+        let mut over_budget = Vec::new();
+        let spend_config = self.spend_limiter.config(&self.db);
 
-        let auto_failover_enabled = self
+        let (auto_failover_enabled, routing_strategy) = self
             .db
             .get_proxy_config_for_app(app_type)
             .await
-            .map(|config| config.auto_failover_enabled)
-            .unwrap_or(false);
+            .map(|config| (config.auto_failover_enabled, config.routing_strategy))
+            .unwrap_or_default();
 
         if auto_failover_enabled {
             let all_providers = self
@@ -50,36 +80,111 @@ impl ProviderRouter {
                 .collect::<Vec<_>>();
 
             total_providers = ordered_ids.len();
+            let mut half_open = HashSet::new();
 
             for provider_id in ordered_ids {
                 let Some(provider) = all_providers.get(&provider_id).cloned() else {
                     continue;
                 };
+                if let spend_limit::Budget::Exceeded(reason) =
+                    self.check_budget(app_type, &provider, &spend_config)
+                {
+                    over_budget.push(reason);
+                    continue;
+                }
 
                 let breaker = self
                     .get_or_create_circuit_breaker(&format!("{app_type}:{}", provider.id))
                     .await;
 
                 if breaker.is_available().await {
+                    if breaker.get_stats().await.state == CircuitState::HalfOpen {
+                        half_open.insert(provider.id.clone());
+                    }
                     result.push(provider);
                 } else {
                     circuit_open_count += 1;
                 }
             }
+
+            result =
+                self.load_balancer
+                    .order(&self.db, app_type, routing_strategy, result, &half_open);
         } else if let Some(current) = self.current_provider(app_type)? {
             total_providers = 1;
-            result.push(current);
+            match self.check_budget(app_type, &current, &spend_config) {
+                spend_limit::Budget::Available => result.push(current),
+                spend_limit::Budget::Exceeded(reason) => over_budget.push(reason),
+            }
         }
 
         if result.is_empty() {
             return if total_providers > 0 && circuit_open_count == total_providers {
                 Err(ProxyError::AllProvidersCircuitOpen)
+            } else if !over_budget.is_empty() {
+                if circuit_open_count > 0 {
+                    over_budget.push(format!(
+                        "{circuit_open_count} other provider(s) are circuit open"
+                    ));
+                }
+                Err(ProxyError::SpendLimitExceeded(over_budget.join("; ")))
             } else {
                 Err(ProxyError::NoProvidersConfigured)
             };
         }
 
-        Ok(result)
+        // 保险库中的密钥只在转发前解密
+        result
+            .iter()
+            .map(|provider| crate::secret_vault::reveal_provider(&self.db, provider))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| ProxyError::ConfigError(error.to_string()))
+    }
+
+    /// 供应商未超出消费限额时返回 true；限额检查关闭时总是返回 true
+    pub fn within_spend_limit(&self, app_type: &str, provider: &Provider) -> bool {
+        let config = self.spend_limiter.config(&self.db);
+        matches!(
+            self.check_budget(app_type, provider, &config),
+            spend_limit::Budget::Available
+        )
+    }
+
+    fn check_budget(
+        &self,
+        app_type: &str,
+        provider: &Provider,
+        config: &SpendLimitConfig,
+    ) -> spend_limit::Budget {
+        self.spend_limiter
+            .check(&self.db, app_type, provider, config, &self.events)
+    }
+
+    /// 标记一个发往该供应商的进行中请求，供最少进行中请求策略使用
+    pub fn track_inflight(&self, provider_id: &str, app_type: &str) -> InflightGuard {
+        self.load_balancer
+            .track_inflight(&format!("{app_type}:{provider_id}"))
+    }
+
+    /// 该应用生效的已编译模型路由规则（按匹配顺序）
+    pub fn model_rules(&self, app_type: &str) -> Arc<Vec<CompiledModelRule>> {
+        self.model_rules.get(&self.db, app_type)
+    }
+
+    /// 规则变更后丢弃缓存，下个请求重新读取
+    pub fn invalidate_model_rules(&self) {
+        self.model_rules.invalidate();
+    }
+
+    /// 丢弃缓存的消费限额配置与消费额，下个请求重新读取
+    pub fn invalidate_spend_limits(&self) {
+        self.spend_limiter.invalidate();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn inflight_count(&self, provider_id: &str, app_type: &str) -> usize {
+        self.load_balancer
+            .inflight_count(&format!("{app_type}:{provider_id}"))
     }
 
     pub async fn allow_provider_request(&self, provider_id: &str, app_type: &str) -> AllowResult {
@@ -108,11 +213,21 @@ impl ProviderRouter {
             .get_or_create_circuit_breaker(&format!("{app_type}:{provider_id}"))
             .await;
 
+        let state_before = breaker.get_state().await;
         if success {
             breaker.record_success(used_half_open_permit).await;
         } else {
             breaker.record_failure(used_half_open_permit).await;
         }
+        let state_after = breaker.get_state().await;
+        if state_after != state_before {
+            self.events.publish(ProxyEvent::CircuitChanged {
+                app_type: app_type.to_string(),
+                provider_id: provider_id.to_string(),
+                from: state_before,
+                to: state_after,
+            });
+        }
 
         self.db
             .update_provider_health_with_threshold(
@@ -161,6 +276,23 @@ impl ProviderRouter {
         }
     }
 
+    /// 按各应用当前的代理配置重新设置已创建熔断器的阈值
+    pub async fn reload_circuit_breaker_configs(&self) {
+        let breakers = self
+            .circuit_breakers
+            .read()
+            .await
+            .iter()
+            .map(|(key, breaker)| (key.clone(), breaker.clone()))
+            .collect::<Vec<_>>();
+        for (key, breaker) in breakers {
+            let app_type = key.split(':').next().unwrap_or("claude");
+            breaker
+                .update_config(self.circuit_config_for_app(app_type).await)
+                .await;
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn get_circuit_breaker_stats(
         &self,
@@ -176,6 +308,29 @@ impl ProviderRouter {
         }
     }
 
+    /// 所有已创建熔断器的状态，返回 (app_type, provider_id, stats)
+    pub async fn all_circuit_breaker_stats(&self) -> Vec<(String, String, CircuitBreakerStats)> {
+        let keys = self
+            .circuit_breakers
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut stats = Vec::with_capacity(keys.len());
+        for key in keys {
+            let Some((app_type, provider_id)) = key.split_once(':') else {
+                continue;
+            };
+            if let Some(breaker_stats) = self.get_circuit_breaker_stats(provider_id, app_type).await
+            {
+                stats.push((app_type.to_string(), provider_id.to_string(), breaker_stats));
+            }
+        }
+        stats.sort_by(|left, right| (&left.0, &left.1).cmp(&(&right.0, &right.1)));
+        stats
+    }
+
     pub(super) fn upstream_endpoint(
         &self,
         app_type: &AppType,
@@ -207,6 +362,20 @@ impl ProviderRouter {
         }
     }
 
+    async fn circuit_config_for_app(&self, app_type: &str) -> CircuitBreakerConfig {
+        self.db
+            .get_proxy_config_for_app(app_type)
+            .await
+            .map(|app_config| CircuitBreakerConfig {
+                failure_threshold: app_config.circuit_failure_threshold,
+                success_threshold: app_config.circuit_success_threshold,
+                timeout_seconds: app_config.circuit_timeout_seconds as u64,
+                error_rate_threshold: app_config.circuit_error_rate_threshold,
+                min_requests: app_config.circuit_min_requests,
+            })
+            .unwrap_or_default()
+    }
+
     async fn get_or_create_circuit_breaker(&self, key: &str) -> Arc<CircuitBreaker> {
         {
             let breakers = self.circuit_breakers.read().await;
@@ -221,18 +390,7 @@ impl ProviderRouter {
         }
 
         let app_type = key.split(':').next().unwrap_or("claude");
-        let config = self
-            .db
-            .get_proxy_config_for_app(app_type)
-            .await
-            .map(|app_config| CircuitBreakerConfig {
-                failure_threshold: app_config.circuit_failure_threshold,
-                success_threshold: app_config.circuit_success_threshold,
-                timeout_seconds: app_config.circuit_timeout_seconds as u64,
-                error_rate_threshold: app_config.circuit_error_rate_threshold,
-                min_requests: app_config.circuit_min_requests,
-            })
-            .unwrap_or_default();
+        let config = self.circuit_config_for_app(app_type).await;
 
         let breaker = Arc::new(CircuitBreaker::new(config));
         breakers.insert(key.to_string(), breaker.clone());
@@ -0,0 +1,180 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{database::Database, provider::Provider, proxy::types::RoutingStrategy};
+
+/// 最低延迟策略统计的时间窗口
+const LATENCY_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// 延迟统计缓存有效期，避免每个请求都查询 proxy_request_logs
+const LATENCY_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// 按应用缓存的各供应商平均延迟（毫秒）及读取时间
+type LatencySnapshot = (Instant, HashMap<String, f64>);
+
+/// 故障转移队列内的负载均衡状态
+///
+/// 只负责给可用供应商重新排序：策略选中的供应商放在首位，
+/// 其余供应商保持队列顺序作为后备，因此失败重试语义不变。
+#[derive(Default)]
+pub(super) struct LoadBalancer {
+    weighted_state: Mutex<HashMap<String, i64>>,
+    inflight: Mutex<HashMap<String, Arc<AtomicUsize>>>,
+    latency_cache: Mutex<HashMap<String, LatencySnapshot>>,
+}
+
+/// 进行中请求计数守卫，drop 时自动减一
+pub struct InflightGuard {
+    counter: Arc<AtomicUsize>,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl LoadBalancer {
+    pub(super) fn track_inflight(&self, circuit_key: &str) -> InflightGuard {
+        let counter = {
+            let mut inflight = self
+                .inflight
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            inflight
+                .entry(circuit_key.to_string())
+                .or_insert_with(|| Arc::new(AtomicUsize::new(0)))
+                .clone()
+        };
+        counter.fetch_add(1, Ordering::AcqRel);
+        InflightGuard { counter }
+    }
+
+    pub(super) fn inflight_count(&self, circuit_key: &str) -> usize {
+        self.inflight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(circuit_key)
+            .map(|counter| counter.load(Ordering::Acquire))
+            .unwrap_or(0)
+    }
+
+    /// 按策略重排可用供应商
+    ///
+    /// `providers` 按队列顺序传入；`half_open` 为熔断器正在探测恢复的供应商，
+    /// 非顺序策略下它们排在最后，只在其余供应商失败时尝试。
+    pub(super) fn order(
+        &self,
+        db: &Database,
+        app_type: &str,
+        strategy: RoutingStrategy,
+        providers: Vec<Provider>,
+        half_open: &HashSet<String>,
+    ) -> Vec<Provider> {
+        if strategy == RoutingStrategy::Ordered {
+            return providers;
+        }
+
+        let (mut healthy, half_open): (Vec<_>, Vec<_>) = providers
+            .into_iter()
+            .partition(|provider| !half_open.contains(&provider.id));
+
+        if healthy.len() > 1 {
+            match strategy {
+                RoutingStrategy::Ordered => {}
+                RoutingStrategy::Weighted => {
+                    if let Some(index) = self.pick_weighted(app_type, &healthy) {
+                        let chosen = healthy.remove(index);
+                        healthy.insert(0, chosen);
+                    }
+                }
+                RoutingStrategy::LeastLatency => {
+                    let latencies = self.recent_latencies(db, app_type);
+                    // 尚无样本的供应商优先，以便尽快获得延迟数据
+                    healthy.sort_by(|left, right| {
+                        let left = latencies.get(&left.id).copied().unwrap_or(0.0);
+                        let right = latencies.get(&right.id).copied().unwrap_or(0.0);
+                        left.total_cmp(&right)
+                    });
+                }
+                RoutingStrategy::LeastInflight => {
+                    healthy.sort_by_key(|provider| {
+                        self.inflight_count(&format!("{app_type}:{}", provider.id))
+                    });
+                }
+            }
+        }
+
+        healthy.extend(half_open);
+        healthy
+    }
+
+    /// 平滑加权轮询（nginx 算法）：返回本次选中的下标
+    fn pick_weighted(&self, app_type: &str, providers: &[Provider]) -> Option<usize> {
+        let weights = providers
+            .iter()
+            .map(|provider| {
+                provider
+                    .meta
+                    .as_ref()
+                    .map(|meta| meta.routing_weight())
+                    .unwrap_or(1) as i64
+            })
+            .collect::<Vec<_>>();
+        let total: i64 = weights.iter().sum();
+        if total <= 0 {
+            return None;
+        }
+
+        let mut state = self
+            .weighted_state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut best: Option<(usize, i64)> = None;
+        for (index, provider) in providers.iter().enumerate() {
+            if weights[index] <= 0 {
+                continue;
+            }
+            let current = state
+                .entry(format!("{app_type}:{}", provider.id))
+                .or_insert(0);
+            *current += weights[index];
+            if best.is_none_or(|(_, best_weight)| *current > best_weight) {
+                best = Some((index, *current));
+            }
+        }
+
+        let (index, _) = best?;
+        if let Some(current) = state.get_mut(&format!("{app_type}:{}", providers[index].id)) {
+            *current -= total;
+        }
+        Some(index)
+    }
+
+    fn recent_latencies(&self, db: &Database, app_type: &str) -> HashMap<String, f64> {
+        let mut cache = self
+            .latency_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((fetched_at, latencies)) = cache.get(app_type) {
+            if fetched_at.elapsed() < LATENCY_CACHE_TTL {
+                return latencies.clone();
+            }
+        }
+
+        let since = chrono::Utc::now().timestamp() - LATENCY_WINDOW.as_secs() as i64;
+        let latencies = db
+            .get_recent_provider_latencies(app_type, since)
+            .unwrap_or_else(|error| {
+                log::warn!("Failed to load provider latencies for {app_type}: {error}");
+                HashMap::new()
+            });
+        cache.insert(app_type.to_string(), (Instant::now(), latencies.clone()));
+        latencies
+    }
+}
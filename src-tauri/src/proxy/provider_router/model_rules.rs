@@ -0,0 +1,56 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    database::Database,
+    proxy::model_mapper::{compile_model_rules, CompiledModelRule},
+};
+
+/// 编译后规则的缓存有效期；规则变更或 `/control/reload` 时立即失效，
+/// 有效期只兜底未经守护进程托管、收不到重载通知的前台代理
+const MODEL_RULES_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// 按应用缓存的已编译规则及读取时间
+type CachedRules = (Instant, Arc<Vec<CompiledModelRule>>);
+
+/// 模型路由规则缓存，避免每个请求都查询数据库并重新编译正则
+#[derive(Default)]
+pub(super) struct ModelRuleCache {
+    rules: Mutex<HashMap<String, CachedRules>>,
+}
+
+impl ModelRuleCache {
+    pub(super) fn get(&self, db: &Database, app_type: &str) -> Arc<Vec<CompiledModelRule>> {
+        let mut cache = self
+            .rules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((loaded_at, rules)) = cache.get(app_type) {
+            if loaded_at.elapsed() < MODEL_RULES_CACHE_TTL {
+                return rules.clone();
+            }
+        }
+
+        match db.list_model_routing_rules(Some(app_type)) {
+            Ok(rules) => {
+                let rules = Arc::new(compile_model_rules(rules));
+                cache.insert(app_type.to_string(), (Instant::now(), rules.clone()));
+                rules
+            }
+            Err(error) => {
+                log::warn!("Failed to load model routing rules for {app_type}: {error}");
+                Arc::new(Vec::new())
+            }
+        }
+    }
+
+    pub(super) fn invalidate(&self) {
+        self.rules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+
+use crate::{
+    database::Database,
+    provider::Provider,
+    proxy::{
+        events::{ProxyEvent, ProxyEventBus},
+        types::SpendLimitConfig,
+    },
+    services::usage_stats::ProviderLimitStatus,
+};
+
+/// 限额配置与消费额缓存有效期，避免每个请求都持有数据库锁执行聚合查询；
+/// 消费额因此最多滞后几秒，`/control/reload` 时立即失效
+const SPEND_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// 单个供应商的限额检查结果
+pub(super) enum Budget {
+    Available,
+    /// 已超出限额，附带可直接返回给客户端的说明
+    Exceeded(String),
+}
+
+/// 供应商消费限额检查
+///
+/// 限额来自供应商 meta，消费额取自 proxy_request_logs 与 usage_daily_rollups。
+/// 预警与超限事件在每个自然日/自然月内只发布一次。
+#[derive(Default)]
+pub(super) struct SpendLimiter {
+    config: Mutex<Option<(Instant, SpendLimitConfig)>>,
+    /// 按 `app_type:provider_id` 缓存的消费额及读取时间
+    usage: Mutex<HashMap<String, (Instant, ProviderLimitStatus)>>,
+    /// 已发布的预警，按 (周期窗口, 预警键) 记录；进入新周期时清理旧窗口
+    notified: Mutex<HashSet<(String, String)>>,
+}
+
+impl SpendLimiter {
+    pub(super) fn config(&self, db: &Database) -> SpendLimitConfig {
+        let mut cached = self
+            .config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((loaded_at, config)) = cached.as_ref() {
+            if loaded_at.elapsed() < SPEND_CACHE_TTL {
+                return config.clone();
+            }
+        }
+
+        let config = db.get_spend_limit_config().unwrap_or_default();
+        *cached = Some((Instant::now(), config.clone()));
+        config
+    }
+
+    pub(super) fn invalidate(&self) {
+        *self
+            .config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        self.usage
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    pub(super) fn check(
+        &self,
+        db: &Database,
+        app_type: &str,
+        provider: &Provider,
+        config: &SpendLimitConfig,
+        events: &ProxyEventBus,
+    ) -> Budget {
+        let has_limit = provider
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.limit_daily_usd.is_some() || meta.limit_monthly_usd.is_some());
+        if !has_limit {
+            return Budget::Available;
+        }
+
+        let Some(status) = self.usage(db, app_type, &provider.id) else {
+            return Budget::Available;
+        };
+
+        let now = Local::now();
+        let today = now.format("%Y-%m-%d").to_string();
+        let this_month = now.format("%Y-%m").to_string();
+        self.prune_notices(&today, &this_month);
+        let periods = [
+            (
+                "daily",
+                today,
+                &status.daily_usage,
+                &status.daily_limit,
+                status.daily_exceeded,
+            ),
+            (
+                "monthly",
+                this_month,
+                &status.monthly_usage,
+                &status.monthly_limit,
+                status.monthly_exceeded,
+            ),
+        ];
+
+        let mut exceeded_reason = None;
+        for (period, window, usage, limit, exceeded) in periods {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let usage = usage.parse::<f64>().unwrap_or(0.0);
+            let limit_value = limit.parse::<f64>().unwrap_or(0.0);
+            let warn = config.warn_percent > 0
+                && limit_value > 0.0
+                && usage >= limit_value * f64::from(config.warn_percent) / 100.0;
+
+            let key = format!("{app_type}:{}:{period}:{exceeded}", provider.id);
+            if (exceeded || warn) && self.first_notice(window, key) {
+                let state = if exceeded { "reached" } else { "is nearing" };
+                log::warn!(
+                    "Provider {} ({app_type}) {state} its {period} spend limit: ${usage:.2} of ${limit}",
+                    provider.id
+                );
+                events.publish(ProxyEvent::SpendLimit {
+                    app_type: app_type.to_string(),
+                    provider_id: provider.id.clone(),
+                    period: period.to_string(),
+                    usage_usd: format!("{usage:.2}"),
+                    limit_usd: limit.clone(),
+                    exceeded,
+                });
+            }
+            if exceeded && exceeded_reason.is_none() {
+                exceeded_reason = Some(format!(
+                    "provider '{}' reached its {period} limit (${usage:.2} of ${limit})",
+                    provider.name
+                ));
+            }
+        }
+
+        match exceeded_reason {
+            Some(reason) if config.enforce => Budget::Exceeded(reason),
+            _ => Budget::Available,
+        }
+    }
+
+    fn usage(
+        &self,
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Option<ProviderLimitStatus> {
+        let key = format!("{app_type}:{provider_id}");
+        if let Some((loaded_at, status)) = self
+            .usage
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+        {
+            if loaded_at.elapsed() < SPEND_CACHE_TTL {
+                return Some(status.clone());
+            }
+        }
+
+        // 查询期间不持有缓存锁，避免其他供应商的检查排队等待数据库
+        match db.check_provider_limits(provider_id, app_type) {
+            Ok(status) => {
+                self.usage
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .insert(key, (Instant::now(), status.clone()));
+                Some(status)
+            }
+            Err(error) => {
+                log::warn!("Failed to check spend limits for {key}: {error}");
+                None
+            }
+        }
+    }
+
+    /// 同一预警在当前周期内是否第一次出现
+    fn first_notice(&self, window: String, key: String) -> bool {
+        self.notified
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert((window, key))
+    }
+
+    /// 丢弃已经过去的自然日/自然月的预警记录
+    fn prune_notices(&self, today: &str, this_month: &str) {
+        self.notified
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|(window, _)| window == today || window == this_month);
+    }
+}
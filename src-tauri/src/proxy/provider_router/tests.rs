@@ -1,5 +1,8 @@
 use super::*;
-use crate::{database::Database, proxy::circuit_breaker::CircuitBreakerConfig};
+use crate::{
+    database::Database,
+    proxy::{circuit_breaker::CircuitBreakerConfig, types::RoutingStrategy},
+};
 use serde_json::json;
 use serial_test::serial;
 use std::{env, sync::Arc};
@@ -369,3 +372,293 @@ async fn test_record_result_uses_app_failure_threshold_for_health_updates() {
     assert_eq!(second_health.consecutive_failures, 2);
     assert_eq!(second_health.last_error.as_deref(), Some("fail-2"));
 }
+
+async fn enable_failover_with_strategy(db: &Database, strategy: RoutingStrategy) {
+    let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+    config.enabled = true;
+    config.auto_failover_enabled = true;
+    config.routing_strategy = strategy;
+    db.update_proxy_config_for_app(config).await.unwrap();
+}
+
+fn weighted_provider(id: &str, sort_index: usize, weight: Option<u32>) -> Provider {
+    let mut provider = Provider::with_id(id.to_string(), id.to_string(), json!({}), None);
+    provider.sort_index = Some(sort_index);
+    provider.meta = Some(crate::provider::ProviderMeta {
+        routing_weight: weight,
+        ..Default::default()
+    });
+    provider
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_weighted_strategy_spreads_first_choice_by_weight() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.save_provider("claude", &weighted_provider("a", 0, Some(3)))
+        .unwrap();
+    db.save_provider("claude", &weighted_provider("b", 1, None))
+        .unwrap();
+    db.add_to_failover_queue("claude", "a").unwrap();
+    db.add_to_failover_queue("claude", "b").unwrap();
+    enable_failover_with_strategy(&db, RoutingStrategy::Weighted).await;
+
+    let router = ProviderRouter::new(db.clone());
+    let mut first_choices = Vec::new();
+    for _ in 0..8 {
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(
+            providers.len(),
+            2,
+            "fallback providers must stay in the list"
+        );
+        first_choices.push(providers[0].id.clone());
+    }
+
+    assert_eq!(first_choices.iter().filter(|id| *id == "a").count(), 6);
+    assert_eq!(first_choices.iter().filter(|id| *id == "b").count(), 2);
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_least_inflight_strategy_prefers_idle_provider() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.save_provider("claude", &weighted_provider("a", 0, None))
+        .unwrap();
+    db.save_provider("claude", &weighted_provider("b", 1, None))
+        .unwrap();
+    db.add_to_failover_queue("claude", "a").unwrap();
+    db.add_to_failover_queue("claude", "b").unwrap();
+    enable_failover_with_strategy(&db, RoutingStrategy::LeastInflight).await;
+
+    let router = ProviderRouter::new(db.clone());
+    let guard = router.track_inflight("a", "claude");
+
+    let busy = router.select_providers("claude").await.unwrap();
+    assert_eq!(busy[0].id, "b");
+    assert_eq!(busy[1].id, "a");
+
+    drop(guard);
+    let idle = router.select_providers("claude").await.unwrap();
+    assert_eq!(idle[0].id, "a", "ties keep queue order");
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_least_latency_strategy_prefers_fastest_recent_provider() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.save_provider("claude", &weighted_provider("slow", 0, None))
+        .unwrap();
+    db.save_provider("claude", &weighted_provider("fast", 1, None))
+        .unwrap();
+    db.add_to_failover_queue("claude", "slow").unwrap();
+    db.add_to_failover_queue("claude", "fast").unwrap();
+    enable_failover_with_strategy(&db, RoutingStrategy::LeastLatency).await;
+
+    let now = chrono::Utc::now().timestamp();
+    {
+        let conn = db.conn.lock().expect("lock db");
+        for (request_id, provider_id, latency_ms) in [
+            ("r1", "slow", 2400),
+            ("r2", "fast", 300),
+            ("r3", "fast", 500),
+        ] {
+            conn.execute(
+                "INSERT INTO proxy_request_logs
+                 (request_id, provider_id, app_type, model, latency_ms, status_code, created_at)
+                 VALUES (?1, ?2, 'claude', 'claude-sonnet-4', ?3, 200, ?4)",
+                rusqlite::params![request_id, provider_id, latency_ms, now],
+            )
+            .expect("seed request log");
+        }
+    }
+
+    let router = ProviderRouter::new(db.clone());
+    let providers = router.select_providers("claude").await.unwrap();
+
+    assert_eq!(providers[0].id, "fast");
+    assert_eq!(providers[1].id, "slow");
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_record_result_publishes_circuit_state_changes() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.update_circuit_breaker_config(&CircuitBreakerConfig {
+        failure_threshold: 1,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let provider_a = Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
+    db.save_provider("claude", &provider_a).unwrap();
+
+    let events = crate::proxy::events::ProxyEventBus::default();
+    let mut rx = events.subscribe();
+    let router = ProviderRouter::new(db.clone()).with_events(events);
+
+    router
+        .record_result("a", "claude", false, false, Some("fail".to_string()))
+        .await
+        .unwrap();
+    router
+        .record_result("a", "claude", false, false, Some("fail".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        rx.try_recv().expect("circuit opened event"),
+        ProxyEvent::CircuitChanged {
+            app_type: "claude".to_string(),
+            provider_id: "a".to_string(),
+            from: CircuitState::Closed,
+            to: CircuitState::Open,
+        }
+    );
+    assert!(
+        rx.try_recv().is_err(),
+        "no event while the circuit stays open"
+    );
+}
+
+fn limited_provider(id: &str, sort_index: usize, monthly_limit: &str) -> Provider {
+    let mut provider = weighted_provider(id, sort_index, None);
+    if let Some(meta) = provider.meta.as_mut() {
+        meta.limit_monthly_usd = Some(monthly_limit.to_string());
+    }
+    provider
+}
+
+fn seed_cost(db: &Database, request_id: &str, provider_id: &str, cost: &str) {
+    let conn = db.conn.lock().expect("lock db");
+    conn.execute(
+        "INSERT INTO proxy_request_logs
+         (request_id, provider_id, app_type, model, total_cost_usd, latency_ms, status_code, created_at)
+         VALUES (?1, ?2, 'claude', 'claude-sonnet-4', ?3, 100, 200, ?4)",
+        rusqlite::params![request_id, provider_id, cost, chrono::Utc::now().timestamp()],
+    )
+    .expect("seed request log");
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_over_budget_providers_are_skipped_in_failover_order() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.save_provider("claude", &limited_provider("a", 0, "10"))
+        .unwrap();
+    db.save_provider("claude", &limited_provider("b", 1, "10"))
+        .unwrap();
+    db.add_to_failover_queue("claude", "a").unwrap();
+    db.add_to_failover_queue("claude", "b").unwrap();
+    enable_failover_with_strategy(&db, RoutingStrategy::default()).await;
+    seed_cost(&db, "r1", "a", "12");
+    seed_cost(&db, "r2", "b", "8.5");
+
+    let events = crate::proxy::events::ProxyEventBus::default();
+    let mut rx = events.subscribe();
+    let router = ProviderRouter::new(db.clone()).with_events(events);
+
+    let providers = router.select_providers("claude").await.unwrap();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].id, "b");
+
+    let mut notices = Vec::new();
+    while let Ok(ProxyEvent::SpendLimit {
+        provider_id,
+        exceeded,
+        ..
+    }) = rx.try_recv()
+    {
+        notices.push((provider_id, exceeded));
+    }
+    assert_eq!(
+        notices,
+        vec![("a".to_string(), true), ("b".to_string(), false)]
+    );
+
+    router.select_providers("claude").await.unwrap();
+    assert!(rx.try_recv().is_err(), "notices are sent once per period");
+
+    seed_cost(&db, "r3", "b", "2");
+    let providers = router.select_providers("claude").await.unwrap();
+    assert_eq!(providers[0].id, "b", "spend is cached between requests");
+
+    router.invalidate_spend_limits();
+    let error = router.select_providers("claude").await.unwrap_err();
+    assert!(matches!(error, ProxyError::SpendLimitExceeded(_)));
+    assert_eq!(
+        error.status_code(),
+        axum::http::StatusCode::PAYMENT_REQUIRED
+    );
+}
+
+#[tokio::test]
+#[serial(home_settings)]
+async fn test_spend_limits_are_advisory_when_enforcement_is_off() {
+    let _home = TempHome::new();
+    let db = Arc::new(Database::memory().unwrap());
+
+    db.save_provider("claude", &limited_provider("a", 0, "1"))
+        .unwrap();
+    db.set_current_provider("claude", "a").unwrap();
+    seed_cost(&db, "r1", "a", "3");
+
+    let router = ProviderRouter::new(db.clone());
+    let error = router.select_providers("claude").await.unwrap_err();
+    assert!(matches!(error, ProxyError::SpendLimitExceeded(_)));
+
+    db.set_spend_limit_config(&crate::proxy::types::SpendLimitConfig {
+        enforce: false,
+        ..Default::default()
+    })
+    .unwrap();
+    router.invalidate_spend_limits();
+    let providers = router.select_providers("claude").await.unwrap();
+    assert_eq!(providers[0].id, "a");
+}
+
+#[test]
+fn test_model_rules_are_cached_until_invalidated() {
+    use crate::proxy::types::{ModelMatchType, ModelRoutingRule};
+
+    let db = Arc::new(Database::memory().unwrap());
+    let rule = |pattern: &str| ModelRoutingRule {
+        id: 0,
+        app_type: Some("claude".to_string()),
+        provider_id: None,
+        match_type: ModelMatchType::Glob,
+        pattern: pattern.to_string(),
+        target_model: Some("relay-model".to_string()),
+        pin_provider_id: None,
+        sort_index: 0,
+        enabled: true,
+        created_at: 0,
+    };
+    db.add_model_routing_rule(&rule("*haiku*")).unwrap();
+
+    let router = ProviderRouter::new(db.clone());
+    let first = router.model_rules("claude");
+    assert_eq!(first.len(), 1);
+
+    db.add_model_routing_rule(&rule("*opus*")).unwrap();
+    assert!(
+        Arc::ptr_eq(&first, &router.model_rules("claude")),
+        "rules are compiled once and reused"
+    );
+
+    router.invalidate_model_rules();
+    let reloaded = router.model_rules("claude");
+    assert_eq!(reloaded.len(), 2);
+    assert!(reloaded[1].matches("claude-OPUS-4"));
+}
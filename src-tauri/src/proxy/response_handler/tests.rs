@@ -55,6 +55,9 @@ fn test_state_with_db(db: Arc<Database>) -> ProxyServerState {
         provider_router: Arc::new(ProviderRouter::new(db)),
         codex_chat_history: Arc::new(Default::default()),
         gemini_shadow: Arc::new(GeminiShadowStore::default()),
+        metrics: Arc::new(Default::default()),
+        events: Default::default(),
+        client_tokens: Arc::new(Default::default()),
     }
 }
 
@@ -2,6 +2,7 @@ use std::{collections::HashMap, io::ErrorKind, net::SocketAddr, sync::Arc, time:
 
 use axum::{
     extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -12,13 +13,17 @@ use tokio::{
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::{
-    app_config::AppType, database::Database, provider::Provider, services::proxy::ProxyService,
+    app_config::AppType, database::Database, error::AppError, provider::Provider,
+    services::proxy::ProxyService,
 };
 
 use super::{
     circuit_breaker::CircuitBreakerConfig,
+    client_auth,
     error::ProxyError,
+    events::{ProxyEvent, ProxyEventBus},
     handlers,
+    prometheus::ProxyMetrics,
     provider_router::ProviderRouter,
     providers::codex_chat_history::CodexChatHistoryStore,
     providers::gemini_shadow::GeminiShadowStore,
@@ -37,6 +42,9 @@ pub struct ProxyServerState {
     pub provider_router: Arc<ProviderRouter>,
     pub codex_chat_history: Arc<CodexChatHistoryStore>,
     pub gemini_shadow: Arc<GeminiShadowStore>,
+    pub metrics: Arc<ProxyMetrics>,
+    pub events: ProxyEventBus,
+    pub client_tokens: Arc<client_auth::ClientTokenCache>,
 }
 
 impl ProxyServerState {
@@ -64,6 +72,34 @@ impl ProxyServerState {
         status
     }
 
+    /// 重新读取设置与代理配置，刷新熔断器阈值并丢弃模型路由规则、消费限额与客户端令牌缓存；
+    /// 监听地址和端口在运行期间保持不变
+    pub async fn reload_config(&self) -> Result<(), AppError> {
+        crate::settings::reload_settings()?;
+        let mut fresh = self.db.get_proxy_config().await?;
+        {
+            let mut config = self.config.write().await;
+            fresh.listen_address = config.listen_address.clone();
+            fresh.listen_port = config.listen_port;
+            fresh.live_takeover_active = config.live_takeover_active;
+            *config = fresh;
+        }
+        self.provider_router.reload_circuit_breaker_configs().await;
+        self.provider_router.invalidate_model_rules();
+        self.provider_router.invalidate_spend_limits();
+        self.client_tokens.invalidate();
+        Ok(())
+    }
+
+    /// 守护进程控制请求只接受携带本进程托管会话令牌的调用方
+    pub async fn is_managed_session_token(&self, token: Option<&str>) -> bool {
+        let status = self.status.read().await;
+        match (status.managed_session_token.as_deref(), token) {
+            (Some(expected), Some(token)) => expected == token,
+            _ => false,
+        }
+    }
+
     pub async fn record_request_start(&self) {
         let mut status = self.status.write().await;
         status.total_requests += 1;
@@ -91,8 +127,13 @@ impl ProxyServerState {
             status.estimated_output_tokens_total.saturating_add(tokens);
     }
 
-    pub async fn record_active_target(&self, app_type: &AppType, provider: &Provider) {
-        self.current_providers.write().await.insert(
+    /// 记录应用当前实际使用的供应商，返回之前的供应商 ID
+    pub async fn record_active_target(
+        &self,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Option<String> {
+        let previous = self.current_providers.write().await.insert(
             app_type.as_str().to_string(),
             (provider.id.clone(), provider.name.clone()),
         );
@@ -100,6 +141,7 @@ impl ProxyServerState {
         let mut status = self.status.write().await;
         status.current_provider = Some(provider.name.clone());
         status.current_provider_id = Some(provider.id.clone());
+        previous.map(|(provider_id, _)| provider_id)
     }
 
     pub async fn sync_successful_provider_selection(
@@ -108,9 +150,17 @@ impl ProxyServerState {
         provider: &Provider,
         current_provider_id_at_start: &str,
     ) {
-        self.record_active_target(app_type, provider).await;
+        let previous_provider_id = self.record_active_target(app_type, provider).await;
 
         if provider.id == current_provider_id_at_start {
+            if previous_provider_id.as_deref() != Some(provider.id.as_str()) {
+                self.events.publish(ProxyEvent::ProviderSwitched {
+                    app_type: app_type.as_str().to_string(),
+                    from_provider_id: previous_provider_id,
+                    provider_id: provider.id.clone(),
+                    provider_name: provider.name.clone(),
+                });
+            }
             return;
         }
 
@@ -133,6 +183,13 @@ impl ProxyServerState {
                 .ok();
         }
 
+        self.events.publish(ProxyEvent::FailoverHop {
+            app_type: app_type.as_str().to_string(),
+            from_provider_id: current_provider_id_at_start.to_string(),
+            provider_id: provider.id.clone(),
+            provider_name: provider.name.clone(),
+        });
+
         let mut status = self.status.write().await;
         status.failover_count = status.failover_count.saturating_add(1);
     }
@@ -191,7 +248,8 @@ pub struct ProxyServer {
 
 impl ProxyServer {
     pub fn new(config: ProxyConfig, db: Arc<Database>) -> Self {
-        let provider_router = Arc::new(ProviderRouter::new(db.clone()));
+        let events = ProxyEventBus::default();
+        let provider_router = Arc::new(ProviderRouter::new(db.clone()).with_events(events.clone()));
         let managed_session_token = std::env::var(PROXY_RUNTIME_SESSION_TOKEN_ENV_KEY)
             .ok()
             .filter(|value| !value.trim().is_empty());
@@ -210,6 +268,9 @@ impl ProxyServer {
                 provider_router,
                 codex_chat_history: Arc::new(CodexChatHistoryStore::default()),
                 gemini_shadow: Arc::new(GeminiShadowStore::default()),
+                metrics: Arc::new(ProxyMetrics::default()),
+                events,
+                client_tokens: Arc::new(client_auth::ClientTokenCache::default()),
             },
             shutdown_tx: Arc::new(RwLock::new(None)),
             server_handle: Arc::new(RwLock::new(None)),
@@ -290,11 +351,23 @@ impl ProxyServer {
     }
 
     pub async fn set_active_target(&self, app_type: &str, provider_id: &str, provider_name: &str) {
-        let mut current_providers = self.state.current_providers.write().await;
-        current_providers.insert(
+        let previous = self.state.current_providers.write().await.insert(
             app_type.to_string(),
             (provider_id.to_string(), provider_name.to_string()),
         );
+        let from_provider_id = previous.map(|(id, _)| id);
+        if from_provider_id.as_deref() != Some(provider_id) {
+            self.state.events.publish(ProxyEvent::ProviderSwitched {
+                app_type: app_type.to_string(),
+                from_provider_id,
+                provider_id: provider_id.to_string(),
+                provider_name: provider_name.to_string(),
+            });
+        }
+    }
+
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<ProxyEvent> {
+        self.state.events.subscribe()
     }
 
     pub async fn stop(&self) -> Result<(), String> {
@@ -341,8 +414,22 @@ impl ProxyServer {
         Router::new()
             .route("/health", get(handlers::health_check))
             .route("/status", get(handlers::get_status))
+            .route("/metrics", get(handlers::get_metrics))
+            .route(
+                "/control/circuit-breaker/reset",
+                post(handlers::control_reset_circuit_breaker),
+            )
+            .route("/control/reload", post(handlers::control_reload))
             .route("/v1/messages", post(handlers::handle_messages))
             .route("/claude/v1/messages", post(handlers::handle_messages))
+            .route(
+                "/v1/messages/count_tokens",
+                post(handlers::handle_count_tokens),
+            )
+            .route(
+                "/claude/v1/messages/count_tokens",
+                post(handlers::handle_count_tokens),
+            )
             .route("/chat/completions", post(handlers::handle_chat_completions))
             .route(
                 "/v1/chat/completions",
@@ -376,9 +463,23 @@ impl ProxyServer {
                 "/codex/v1/responses/compact",
                 post(handlers::handle_responses_compact),
             )
-            .route("/v1beta/*path", post(handlers::handle_gemini))
-            .route("/gemini/v1beta/*path", post(handlers::handle_gemini))
+            .route("/v1/models", get(handlers::handle_models))
+            .route("/models", get(handlers::handle_codex_models))
+            .route("/claude/v1/models", get(handlers::handle_claude_models))
+            .route("/codex/v1/models", get(handlers::handle_codex_models))
+            .route(
+                "/v1beta/*path",
+                post(handlers::handle_gemini).get(handlers::handle_gemini_get),
+            )
+            .route(
+                "/gemini/v1beta/*path",
+                post(handlers::handle_gemini).get(handlers::handle_gemini_get),
+            )
             .layer(DefaultBodyLimit::max(200 * 1024 * 1024))
+            .layer(middleware::from_fn_with_state(
+                self.state.clone(),
+                client_auth::require_client_token,
+            ))
             .layer(cors)
             .with_state(self.state.clone())
     }
@@ -491,6 +592,9 @@ mod tests {
             provider_router: Arc::new(ProviderRouter::new(db)),
             codex_chat_history: Arc::new(CodexChatHistoryStore::default()),
             gemini_shadow: Arc::new(GeminiShadowStore::default()),
+            metrics: Arc::new(Default::default()),
+            events: Default::default(),
+            client_tokens: Arc::new(Default::default()),
         }
     }
 
@@ -695,4 +799,87 @@ mod tests {
         assert_eq!(status.active_targets.len(), 1);
         assert_eq!(status.active_targets[0].provider_id, "claude-failover");
     }
+
+    #[tokio::test]
+    async fn metrics_render_includes_request_series_and_circuit_state() {
+        let db = Arc::new(Database::memory().expect("create memory database"));
+        let state = test_state(db);
+        let _ = state
+            .provider_router
+            .allow_provider_request("relay", "claude")
+            .await;
+        let usage = crate::proxy::usage::parser::TokenUsage {
+            input_tokens: 12,
+            output_tokens: 3,
+            ..Default::default()
+        };
+        state
+            .metrics
+            .record(crate::proxy::prometheus::RequestSample {
+                app_type: "claude",
+                provider_id: "relay",
+                model: "claude-haiku-4-5",
+                status_code: 200,
+                latency: std::time::Duration::from_millis(80),
+                usage: &usage,
+                cost_usd: 0.0,
+            });
+
+        let body = crate::proxy::prometheus::render(&state).await;
+
+        assert!(body.contains("# TYPE ccswitch_proxy_requests_total counter"));
+        assert!(body.contains(
+            "ccswitch_proxy_requests_total{app=\"claude\",provider=\"relay\",model=\"claude-haiku-4-5\",status=\"200\"} 1"
+        ));
+        assert!(
+            body.contains("ccswitch_circuit_breaker_state{app=\"claude\",provider=\"relay\"} 0")
+        );
+    }
+
+    #[tokio::test]
+    async fn client_tokens_guard_status_and_control_unless_session_token_matches() {
+        use axum::{
+            body::Body,
+            http::{Request, StatusCode},
+        };
+        use tower::ServiceExt;
+
+        let db = Arc::new(Database::memory().expect("create memory database"));
+        db.set_client_auth_config(&crate::proxy::types::ClientAuthConfig {
+            tokens: vec![crate::proxy::types::ClientToken {
+                name: "alice".to_string(),
+                token_sha256: client_auth::hash_client_token("ccs_alice"),
+                apps: Vec::new(),
+                providers: Vec::new(),
+                created_at: 0,
+            }],
+        })
+        .expect("save client tokens");
+        let server = ProxyServer::new(ProxyConfig::default(), db);
+        server.state.status.write().await.managed_session_token = Some("token-1".to_string());
+        let router = server.build_router();
+
+        let call = |method: &str, uri: &str, session_token: Option<&str>| {
+            let mut request = Request::builder().method(method).uri(uri);
+            if let Some(token) = session_token {
+                request = request.header(handlers::CONTROL_SESSION_TOKEN_HEADER, token);
+            }
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let health = call("GET", "/health", None).await.unwrap();
+        assert_eq!(health.status(), StatusCode::OK);
+        let status = call("GET", "/status", None).await.unwrap();
+        assert_eq!(status.status(), StatusCode::UNAUTHORIZED);
+        let status = call("GET", "/status", Some("token-2")).await.unwrap();
+        assert_eq!(status.status(), StatusCode::UNAUTHORIZED);
+        let status = call("GET", "/status", Some("token-1")).await.unwrap();
+        assert_eq!(status.status(), StatusCode::OK);
+        let reload = call("POST", "/control/reload", None).await.unwrap();
+        assert_eq!(reload.status(), StatusCode::UNAUTHORIZED);
+        let reload = call("POST", "/control/reload", Some("token-1"))
+            .await
+            .unwrap();
+        assert_eq!(reload.status(), StatusCode::OK);
+    }
 }
@@ -94,7 +94,7 @@ pub struct ProxyStatus {
     pub last_error: Option<String>,
     /// Provider故障转移次数
     pub failover_count: u64,
-    /// managed external session 身份令牌
+    /// managed external session 身份令牌（`/status` 只向携带该令牌的调用方返回）
     #[serde(default)]
     pub managed_session_token: Option<String>,
     /// 当前活跃的代理目标列表
@@ -224,6 +224,138 @@ pub struct AppProxyConfig {
     pub circuit_error_rate_threshold: f64,
     /// 计算错误率的最小请求数
     pub circuit_min_requests: u32,
+    /// 故障转移队列内的路由策略
+    #[serde(default)]
+    pub routing_strategy: RoutingStrategy,
+}
+
+/// 故障转移队列的路由策略
+///
+/// 仅在自动故障转移开启时生效；策略决定每个请求的首选供应商，
+/// 其余可用供应商仍按队列顺序作为后备。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    /// 按队列顺序，首个健康供应商承接全部流量
+    #[default]
+    Ordered,
+    /// 按供应商权重平滑加权轮询
+    Weighted,
+    /// 优先最近平均延迟最低的供应商
+    LeastLatency,
+    /// 优先当前进行中请求最少的供应商
+    LeastInflight,
+}
+
+impl RoutingStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoutingStrategy::Ordered => "ordered",
+            RoutingStrategy::Weighted => "weighted",
+            RoutingStrategy::LeastLatency => "least_latency",
+            RoutingStrategy::LeastInflight => "least_inflight",
+        }
+    }
+}
+
+impl std::fmt::Display for RoutingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for RoutingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ordered" | "" => Ok(RoutingStrategy::Ordered),
+            "weighted" | "weighted_round_robin" | "weighted-round-robin" => {
+                Ok(RoutingStrategy::Weighted)
+            }
+            "least_latency" | "least-latency" => Ok(RoutingStrategy::LeastLatency),
+            "least_inflight" | "least-inflight" => Ok(RoutingStrategy::LeastInflight),
+            _ => Err(format!("Invalid routing strategy: {s}")),
+        }
+    }
+}
+
+/// 模型路由规则的匹配方式
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelMatchType {
+    /// 完全匹配（忽略大小写）
+    #[default]
+    Exact,
+    /// 通配符匹配：`*` 匹配任意字符，`?` 匹配单个字符（忽略大小写）
+    Glob,
+    /// 正则表达式匹配（同样忽略大小写）
+    Regex,
+}
+
+impl ModelMatchType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelMatchType::Exact => "exact",
+            ModelMatchType::Glob => "glob",
+            ModelMatchType::Regex => "regex",
+        }
+    }
+}
+
+impl std::fmt::Display for ModelMatchType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for ModelMatchType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "exact" => Ok(ModelMatchType::Exact),
+            "glob" | "wildcard" => Ok(ModelMatchType::Glob),
+            "regex" | "regexp" => Ok(ModelMatchType::Regex),
+            _ => Err(format!("Invalid model match type: {s}")),
+        }
+    }
+}
+
+/// 模型路由规则
+///
+/// 按 `sort_index` 升序匹配请求中的模型名，首条命中的规则生效：
+/// 可改写上游模型名，也可将请求固定到指定供应商。
+/// `app_type` / `provider_id` 为空表示对所有应用 / 供应商生效。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRoutingRule {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    /// 仅当当前供应商为该 ID 时改写模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub match_type: ModelMatchType,
+    pub pattern: String,
+    /// 改写后的模型名；为空时保持原模型（仍按供应商自身映射处理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_model: Option<String>,
+    /// 命中后固定使用的供应商
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin_provider_id: Option<String>,
+    #[serde(default)]
+    pub sort_index: i64,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -353,6 +485,101 @@ impl Default for CopilotOptimizerConfig {
     }
 }
 
+/// 请求抓包配置
+///
+/// 存储在 settings 表中，key = "capture_config"，默认关闭
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureConfig {
+    /// 是否记录请求/响应内容
+    #[serde(default)]
+    pub enabled: bool,
+    /// 单个请求体或响应体最多保留的字节数，超出部分截断
+    #[serde(default = "default_capture_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// 最多保留的抓包条数，超出后删除最旧的记录
+    #[serde(default = "default_capture_retain")]
+    pub retain: usize,
+}
+
+fn default_capture_max_body_bytes() -> usize {
+    8 * 1024 * 1024
+}
+
+fn default_capture_retain() -> usize {
+    100
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_body_bytes: default_capture_max_body_bytes(),
+            retain: default_capture_retain(),
+        }
+    }
+}
+
+/// 消费限额配置
+///
+/// 存储在 settings 表中，key = "spend_limit_config"。限额本身保存在
+/// 供应商 meta 的 limitDailyUsd / limitMonthlyUsd 中。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendLimitConfig {
+    /// 是否在转发前跳过已超出限额的供应商
+    #[serde(default = "default_true")]
+    pub enforce: bool,
+    /// 消费达到限额的该百分比时发出预警（0 表示不预警）
+    #[serde(default = "default_spend_warn_percent")]
+    pub warn_percent: u8,
+}
+
+fn default_spend_warn_percent() -> u8 {
+    80
+}
+
+impl Default for SpendLimitConfig {
+    fn default() -> Self {
+        Self {
+            enforce: true,
+            warn_percent: default_spend_warn_percent(),
+        }
+    }
+}
+
+/// 代理客户端令牌配置
+///
+/// 存储在 settings 表中，key = "client_auth_config"。存在任一令牌时，
+/// 代理监听地址上的所有路由（/health、/status 与守护进程控制接口除外）都要求客户端携带有效令牌。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientAuthConfig {
+    #[serde(default)]
+    pub tokens: Vec<ClientToken>,
+}
+
+impl ClientAuthConfig {
+    pub fn is_enforced(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+}
+
+/// 具名客户端令牌；只保存令牌的 SHA-256 摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientToken {
+    pub name: String,
+    pub token_sha256: String,
+    /// 允许访问的应用（空表示不限）
+    #[serde(default)]
+    pub apps: Vec<String>,
+    /// 允许使用的供应商 ID（空表示不限）
+    #[serde(default)]
+    pub providers: Vec<String>,
+    pub created_at: i64,
+}
+
 /// 日志配置
 ///
 /// 存储在 settings 表的 log_config 字段中（JSON 格式）
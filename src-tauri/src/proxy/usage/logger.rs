@@ -1,9 +1,18 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use rust_decimal::prelude::ToPrimitive;
+
 use crate::{
     app_config::AppType,
     provider::Provider,
-    proxy::{error::ProxyError, handler_context::HandlerContext, server::ProxyServerState},
+    proxy::{
+        capture::RequestCapture,
+        error::ProxyError,
+        events::{ProxyEvent, RequestSummary},
+        handler_context::HandlerContext,
+        prometheus::RequestSample,
+        server::ProxyServerState,
+    },
 };
 
 use super::{
@@ -39,6 +48,8 @@ pub struct RequestLogContext {
     pub started_at: std::time::Instant,
     pub is_streaming: bool,
     pub policy: UsageLogPolicy,
+    pub capture: Option<RequestCapture>,
+    pub client_name: Option<String>,
 }
 
 impl RequestLogContext {
@@ -56,6 +67,8 @@ impl RequestLogContext {
             started_at: context.start_time,
             is_streaming,
             policy,
+            capture: context.capture.clone(),
+            client_name: context.client_name.clone(),
         }
     }
 
@@ -70,13 +83,9 @@ pub async fn log_buffered_response(
     status_code: u16,
     body: &[u8],
 ) {
-    if !logging_enabled(state).await {
-        return;
-    }
-
     if let Some(parsed) = parse_response_usage(&context.app_type, body) {
         let model = non_empty_model(&parsed, &context.request_model);
-        insert_request_log(
+        record_request(
             state,
             context,
             &model,
@@ -94,7 +103,7 @@ pub async fn log_buffered_response(
     }
 
     let model = fallback_model_from_response_bytes(body, &context.request_model);
-    insert_request_log(
+    record_request(
         state,
         context,
         &model,
@@ -112,13 +121,9 @@ pub async fn log_stream_response(
     status_code: u16,
     collector: &StreamLogCollector,
 ) {
-    if !logging_enabled(state).await {
-        return;
-    }
-
     if let Some(parsed) = collector.parsed_usage_for_app(&context.app_type) {
         let model = non_empty_model(&parsed, &context.request_model);
-        insert_request_log(
+        record_request(
             state,
             context,
             &model,
@@ -136,7 +141,7 @@ pub async fn log_stream_response(
     }
 
     let model = collector.fallback_model(&context.request_model);
-    insert_request_log(
+    record_request(
         state,
         context,
         &model,
@@ -153,11 +158,7 @@ pub async fn log_error_request(
     context: &RequestLogContext,
     error: &ProxyError,
 ) {
-    if !logging_enabled(state).await {
-        return;
-    }
-
-    insert_request_log(
+    record_request(
         state,
         context,
         &context.request_model,
@@ -173,7 +174,8 @@ async fn logging_enabled(state: &ProxyServerState) -> bool {
     state.config.read().await.enable_logging
 }
 
-async fn insert_request_log(
+/// 记录请求指标并发布请求摘要事件；开启抓包时保存抓包记录，开启日志时再写入 proxy_request_logs
+async fn record_request(
     state: &ProxyServerState,
     context: &RequestLogContext,
     model: &str,
@@ -195,7 +197,49 @@ async fn insert_request_log(
         lookup_model_pricing(state.db.as_ref(), pricing_model).as_ref(),
         pricing_config.cost_multiplier,
     );
+
+    state.metrics.record(RequestSample {
+        app_type: context.app_type.as_str(),
+        provider_id: &context.provider.id,
+        model,
+        status_code,
+        latency: context.started_at.elapsed(),
+        usage: &usage,
+        cost_usd: cost
+            .as_ref()
+            .and_then(|value| value.total_cost.to_f64())
+            .unwrap_or(0.0),
+    });
+    state.events.publish(ProxyEvent::Request(RequestSummary {
+        app_type: context.app_type.as_str().to_string(),
+        provider_id: context.provider.id.clone(),
+        model: model.to_string(),
+        request_model: context.request_model.clone(),
+        status_code,
+        latency_ms: context.latency_ms(),
+        first_token_ms,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cache_read_tokens: usage.cache_read_tokens,
+        cache_creation_tokens: usage.cache_creation_tokens,
+        total_cost_usd: cost.as_ref().map(|value| format_decimal(value.total_cost)),
+        session_id: context.session_id.clone(),
+        is_streaming: context.is_streaming,
+        error_message: error_message.clone(),
+    }));
+
     let request_id = usage.dedup_request_id();
+    if let Some(capture) = &context.capture {
+        let record = capture.finish(&request_id, status_code);
+        if let Err(error) = state.db.insert_proxy_capture(&record, capture.retain()) {
+            log::warn!("record proxy request capture failed: {error}");
+        }
+    }
+
+    if !logging_enabled(state).await {
+        return;
+    }
+
     let created_at = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|duration| duration.as_secs() as i64)
@@ -215,8 +259,8 @@ async fn insert_request_log(
             input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens,
             input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd, total_cost_usd,
             latency_ms, first_token_ms, status_code, error_message, session_id,
-            provider_type, is_streaming, cost_multiplier, created_at, data_source
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
+            provider_type, is_streaming, cost_multiplier, created_at, data_source, client_token
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26)",
         rusqlite::params![
             request_id,
             &context.provider.id,
@@ -243,6 +287,7 @@ async fn insert_request_log(
             format_decimal(pricing_config.cost_multiplier),
             created_at,
             "proxy",
+            &context.client_name,
         ],
     ) {
         Ok(inserted) if inserted > 0 && (200..300).contains(&status_code) => {
@@ -0,0 +1,697 @@
+//! 供应商密钥保险库
+//!
+//! 开启后，供应商 settings_config 中的 API Key 等敏感字段被替换为 `ccs-vault:<id>` 引用，
+//! 密文（AES-256-GCM）与指纹单独存放在 provider_secrets 表中，SQL 备份与 WebDAV 同步只包含密文。
+//! 仅在写入 live 配置和代理转发时解密。密钥由口令派生（PBKDF2-HMAC-SHA256）或读取密钥文件。
+
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use base64::prelude::*;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+
+/// settings_config 中密钥引用的前缀
+pub const VAULT_REF_PREFIX: &str = "ccs-vault:";
+/// 口令模式下提供口令的环境变量（守护进程 / 代理等非交互场景）
+pub const PASSPHRASE_ENV: &str = "CC_SWITCH_VAULT_PASSPHRASE";
+/// 覆盖保险库元数据中记录的密钥文件路径（多台机器路径不同时使用）
+pub const KEY_FILE_ENV: &str = "CC_SWITCH_VAULT_KEY_FILE";
+
+#[cfg(not(test))]
+pub(crate) const PBKDF2_ITERATIONS: u32 = 600_000;
+#[cfg(test)]
+pub(crate) const PBKDF2_ITERATIONS: u32 = 1_000;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const KEY_CHECK_AAD: &str = "cc-switch-vault:key-check";
+const KEY_CHECK_PLAINTEXT: &[u8] = b"cc-switch-vault";
+/// 由保险库密钥派生指纹子密钥时使用的用途标签
+const FINGERPRINT_KEY_LABEL: &[u8] = b"cc-switch-vault:fingerprint";
+/// 代理接管时写入 live 配置的占位令牌，不是真实密钥
+const PROXY_TOKEN_PLACEHOLDER: &str = "PROXY_MANAGED";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultKeySource {
+    Passphrase,
+    KeyFile,
+}
+
+/// 保险库元数据（settings 键 `secret_vault`），随同步上传，不含任何密钥材料
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVaultConfig {
+    pub key_source: VaultKeySource,
+    /// base64 编码的随机盐，同时用于口令派生与密钥指纹
+    pub salt: String,
+    pub iterations: u32,
+    /// 用于校验口令 / 密钥文件是否正确的密文
+    pub key_check: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<String>,
+    pub created_at: i64,
+}
+
+/// provider_secrets 表中的一条加密密钥
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSecret {
+    pub id: String,
+    pub provider_id: String,
+    pub app_type: String,
+    /// 密钥在 settings_config 中的位置（JSON Pointer）
+    pub json_path: String,
+    pub ciphertext: String,
+    pub fingerprint: String,
+    pub created_at: i64,
+}
+
+/// 解锁后的保险库密钥
+pub struct VaultKey {
+    bytes: [u8; KEY_LEN],
+}
+
+impl VaultKey {
+    fn aead_key(&self) -> Result<LessSafeKey, AppError> {
+        UnboundKey::new(&AES_256_GCM, &self.bytes)
+            .map(LessSafeKey::new)
+            .map_err(|_| AppError::Message("初始化保险库密钥失败".to_string()))
+    }
+
+    /// 加密并返回 nonce || 密文 || tag；`aad` 绑定密文的用途，防止密文被挪用
+    pub fn seal_bytes(&self, aad: &str, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        let nonce = random_bytes::<NONCE_LEN>()?;
+        let mut in_out = plaintext.to_vec();
+        self.aead_key()?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| AppError::Message("加密密钥失败".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    pub fn open_bytes(&self, aad: &str, sealed: &[u8]) -> Result<Vec<u8>, AppError> {
+        if sealed.len() < NONCE_LEN {
+            return Err(AppError::Message("密文格式无效: 长度不足".to_string()));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| AppError::Message("密文格式无效: nonce 错误".to_string()))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .aead_key()?
+            .open_in_place(nonce, Aad::from(aad.as_bytes()), &mut in_out)
+            .map_err(|_| AppError::Message("解密失败：密钥不正确或密文已损坏".to_string()))?;
+        Ok(plaintext.to_vec())
+    }
+
+    /// 加密并返回 base64(nonce || 密文 || tag)
+    pub fn encrypt(&self, aad: &str, plaintext: &[u8]) -> Result<String, AppError> {
+        Ok(BASE64_STANDARD.encode(self.seal_bytes(aad, plaintext)?))
+    }
+
+    pub fn decrypt(&self, aad: &str, sealed: &str) -> Result<Vec<u8>, AppError> {
+        let raw = BASE64_STANDARD
+            .decode(sealed.trim())
+            .map_err(|e| AppError::Message(format!("密文格式无效: {e}")))?;
+        self.open_bytes(aad, &raw)
+    }
+
+    /// 明文密钥的指纹：以保险库密钥派生的子密钥计算 HMAC-SHA256，
+    /// 没有密钥时无法据指纹离线猜测明文
+    pub fn fingerprint(&self, plaintext: &str) -> String {
+        let subkey = hmac::sign(
+            &hmac::Key::new(hmac::HMAC_SHA256, &self.bytes),
+            FINGERPRINT_KEY_LABEL,
+        );
+        hmac::sign(
+            &hmac::Key::new(hmac::HMAC_SHA256, subkey.as_ref()),
+            plaintext.as_bytes(),
+        )
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+    }
+}
+
+/// 已解锁的密钥：（对应保险库的 key_check, 派生密钥）
+type UnlockedKey = (String, [u8; KEY_LEN]);
+
+fn unlocked_keys() -> &'static Mutex<Option<UnlockedKey>> {
+    static UNLOCKED: OnceLock<Mutex<Option<UnlockedKey>>> = OnceLock::new();
+    UNLOCKED.get_or_init(|| Mutex::new(None))
+}
+
+pub(crate) fn random_bytes<const N: usize>() -> Result<[u8; N], AppError> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Message("生成随机数失败".to_string()))?;
+    Ok(bytes)
+}
+
+fn decode_salt(config: &SecretVaultConfig) -> Result<Vec<u8>, AppError> {
+    BASE64_STANDARD
+        .decode(&config.salt)
+        .map_err(|e| AppError::Message(format!("保险库盐值格式无效: {e}")))
+}
+
+pub(crate) fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: u32,
+) -> Result<VaultKey, AppError> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| AppError::Message("保险库迭代次数无效".to_string()))?;
+    let mut bytes = [0u8; KEY_LEN];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut bytes,
+    );
+    Ok(VaultKey { bytes })
+}
+
+fn read_key_file(path: &Path) -> Result<VaultKey, AppError> {
+    let text = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let raw = BASE64_STANDARD
+        .decode(text.trim())
+        .map_err(|e| AppError::Message(format!("密钥文件格式无效 {}: {e}", path.display())))?;
+    let bytes: [u8; KEY_LEN] = raw.try_into().map_err(|_| {
+        AppError::Message(format!(
+            "密钥文件格式无效 {}: 需要 {KEY_LEN} 字节密钥",
+            path.display()
+        ))
+    })?;
+    Ok(VaultKey { bytes })
+}
+
+fn write_new_key_file(path: &Path) -> Result<VaultKey, AppError> {
+    let bytes = random_bytes::<KEY_LEN>()?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(|e| AppError::io(path, e))?;
+    std::io::Write::write_all(
+        &mut file,
+        format!("{}\n", BASE64_STANDARD.encode(bytes)).as_bytes(),
+    )
+    .map_err(|e| AppError::io(path, e))?;
+    Ok(VaultKey { bytes })
+}
+
+/// 创建新的保险库；密钥文件模式下文件不存在时自动生成
+pub fn create_vault(
+    key_source: VaultKeySource,
+    passphrase: Option<&str>,
+    key_file: Option<&Path>,
+) -> Result<(SecretVaultConfig, VaultKey), AppError> {
+    let salt = random_bytes::<SALT_LEN>()?;
+    let key = match key_source {
+        VaultKeySource::Passphrase => {
+            let passphrase = passphrase
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| AppError::InvalidInput("保险库口令不能为空".to_string()))?;
+            derive_passphrase_key(passphrase, &salt, PBKDF2_ITERATIONS)?
+        }
+        VaultKeySource::KeyFile => {
+            let path =
+                key_file.ok_or_else(|| AppError::InvalidInput("缺少密钥文件路径".to_string()))?;
+            if path.exists() {
+                read_key_file(path)?
+            } else {
+                write_new_key_file(path)?
+            }
+        }
+    };
+
+    let config = SecretVaultConfig {
+        key_source,
+        salt: BASE64_STANDARD.encode(salt),
+        iterations: PBKDF2_ITERATIONS,
+        key_check: key.encrypt(KEY_CHECK_AAD, KEY_CHECK_PLAINTEXT)?,
+        key_file: key_file.map(|path| path.to_string_lossy().to_string()),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    remember_key(&config, &key);
+    Ok((config, key))
+}
+
+fn remember_key(config: &SecretVaultConfig, key: &VaultKey) {
+    if let Ok(mut unlocked) = unlocked_keys().lock() {
+        *unlocked = Some((config.key_check.clone(), key.bytes));
+    }
+}
+
+/// 密钥文件模式下实际使用的路径：环境变量优先于元数据中记录的路径
+pub fn key_file_path(config: &SecretVaultConfig) -> Option<PathBuf> {
+    std::env::var_os(KEY_FILE_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| config.key_file.as_ref().map(PathBuf::from))
+}
+
+/// 解锁保险库；口令优先取参数，其次取环境变量。派生出的密钥在进程内缓存，
+/// 但显式传入的口令总是重新派生并校验，不会因为缓存而放过错误的口令
+pub fn unlock(config: &SecretVaultConfig, passphrase: Option<&str>) -> Result<VaultKey, AppError> {
+    if passphrase.is_none() {
+        if let Ok(unlocked) = unlocked_keys().lock() {
+            if let Some((key_check, bytes)) = unlocked.as_ref() {
+                if *key_check == config.key_check {
+                    return Ok(VaultKey { bytes: *bytes });
+                }
+            }
+        }
+    }
+
+    let key = match config.key_source {
+        VaultKeySource::Passphrase => {
+            let passphrase = match passphrase {
+                Some(value) => value.to_string(),
+                None => std::env::var(PASSPHRASE_ENV).map_err(|_| {
+                    AppError::localized(
+                        "vault.locked",
+                        format!("密钥保险库已锁定：请设置环境变量 {PASSPHRASE_ENV}"),
+                        format!("Secret vault is locked: set {PASSPHRASE_ENV} to unlock it"),
+                    )
+                })?,
+            };
+            derive_passphrase_key(&passphrase, &decode_salt(config)?, config.iterations)?
+        }
+        VaultKeySource::KeyFile => {
+            let path = key_file_path(config).ok_or_else(|| {
+                AppError::localized(
+                    "vault.key_file_missing",
+                    format!("密钥保险库未配置密钥文件：请设置环境变量 {KEY_FILE_ENV}"),
+                    format!("Secret vault has no key file: set {KEY_FILE_ENV}"),
+                )
+            })?;
+            read_key_file(&path)?
+        }
+    };
+
+    key.decrypt(KEY_CHECK_AAD, &config.key_check).map_err(|_| {
+        AppError::localized(
+            "vault.wrong_key",
+            "密钥保险库口令或密钥文件不正确",
+            "Wrong secret vault passphrase or key file",
+        )
+    })?;
+    remember_key(config, &key);
+    Ok(key)
+}
+
+pub fn vault_ref(id: &str) -> String {
+    format!("{VAULT_REF_PREFIX}{id}")
+}
+
+pub fn vault_ref_id(value: &str) -> Option<&str> {
+    value.strip_prefix(VAULT_REF_PREFIX)
+}
+
+/// 判断字段名是否为需要加密的密钥字段（忽略大小写与 `_` / `-`）
+pub fn is_secret_field(name: &str) -> bool {
+    let normalized = name
+        .chars()
+        .filter(|ch| *ch != '_' && *ch != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    normalized == "token"
+        || [
+            "apikey",
+            "authtoken",
+            "accesstoken",
+            "bearertoken",
+            "secret",
+            "secretkey",
+            "password",
+        ]
+        .iter()
+        .any(|suffix| normalized.ends_with(suffix))
+}
+
+fn is_sealable_value(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && value != PROXY_TOKEN_PLACEHOLDER
+        && !value.starts_with(VAULT_REF_PREFIX)
+        && !value.starts_with("{env:")
+        && !value.starts_with("${")
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn walk_strings(value: &Value, pointer: &str, visit: &mut dyn FnMut(&str, Option<&str>, &str)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                match child {
+                    Value::String(text) => visit(&child_pointer, Some(key), text),
+                    _ => walk_strings(child, &child_pointer, visit),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                let child_pointer = format!("{pointer}/{index}");
+                match child {
+                    Value::String(text) => visit(&child_pointer, None, text),
+                    _ => walk_strings(child, &child_pointer, visit),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 收集 settings_config 中尚未加密的密钥：`(JSON Pointer, 明文)`
+pub fn collect_plaintext_secrets(settings: &Value) -> Vec<(String, String)> {
+    let mut secrets = Vec::new();
+    walk_strings(settings, "", &mut |pointer, key, text| {
+        if key.is_some_and(is_secret_field) && is_sealable_value(text) {
+            secrets.push((pointer.to_string(), text.to_string()));
+        }
+    });
+    secrets
+}
+
+/// 收集 settings_config 中的密钥引用：`(JSON Pointer, 密钥 ID)`
+pub fn collect_vault_refs(settings: &Value) -> Vec<(String, String)> {
+    let mut refs = Vec::new();
+    walk_strings(settings, "", &mut |pointer, _, text| {
+        if let Some(id) = vault_ref_id(text) {
+            refs.push((pointer.to_string(), id.to_string()));
+        }
+    });
+    refs
+}
+
+/// 加密 settings_config 中的明文密钥，返回替换为引用后的配置与待写入的密文
+pub fn seal_settings(
+    key: &VaultKey,
+    app_type: &str,
+    provider_id: &str,
+    settings: &Value,
+) -> Result<(Value, Vec<ProviderSecret>), AppError> {
+    let mut sealed = settings.clone();
+    let mut secrets = Vec::new();
+    let now = chrono::Utc::now().timestamp();
+    for (pointer, plaintext) in collect_plaintext_secrets(settings) {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        secrets.push(ProviderSecret {
+            ciphertext: key.encrypt(&id, plaintext.as_bytes())?,
+            fingerprint: key.fingerprint(&plaintext),
+            id: id.clone(),
+            provider_id: provider_id.to_string(),
+            app_type: app_type.to_string(),
+            json_path: pointer.clone(),
+            created_at: now,
+        });
+        if let Some(slot) = sealed.pointer_mut(&pointer) {
+            *slot = Value::String(vault_ref(&id));
+        }
+    }
+    Ok((sealed, secrets))
+}
+
+/// 把 settings_config 中的引用替换为明文；没有引用时无需解锁
+pub fn reveal_settings(db: &Database, settings: &Value) -> Result<Value, AppError> {
+    let refs = collect_vault_refs(settings);
+    if refs.is_empty() {
+        return Ok(settings.clone());
+    }
+
+    let config = db.get_secret_vault_config()?.ok_or_else(|| {
+        AppError::localized(
+            "vault.not_initialized",
+            "供应商配置引用了密钥保险库，但保险库未初始化",
+            "Provider settings reference the secret vault, but no vault is configured",
+        )
+    })?;
+    let key = unlock(&config, None)?;
+    let mut revealed = settings.clone();
+    for (pointer, id) in refs {
+        let secret = db
+            .get_provider_secret(&id)?
+            .ok_or_else(|| AppError::Message(format!("密钥保险库中缺少密钥 {id}（{pointer}）")))?;
+        let plaintext = String::from_utf8(key.decrypt(&id, &secret.ciphertext)?)
+            .map_err(|_| AppError::Message(format!("密钥 {id} 不是有效的 UTF-8 文本")))?;
+        if let Some(slot) = revealed.pointer_mut(&pointer) {
+            *slot = Value::String(plaintext);
+        }
+    }
+    Ok(revealed)
+}
+
+/// 解密单个可能是引用的值（如编辑表单中的 API Key）；不是引用时原样返回
+pub fn reveal_value(db: &Database, value: &str) -> Result<String, AppError> {
+    if vault_ref_id(value).is_none() {
+        return Ok(value.to_string());
+    }
+    let revealed = reveal_settings(db, &Value::Array(vec![Value::String(value.to_string())]))?;
+    Ok(revealed[0].as_str().unwrap_or_default().to_string())
+}
+
+/// 返回密钥已解密的供应商副本，供写入 live 配置与代理转发使用
+pub fn reveal_provider(db: &Database, provider: &Provider) -> Result<Provider, AppError> {
+    let mut revealed = provider.clone();
+    revealed.settings_config = reveal_settings(db, &provider.settings_config)?;
+    Ok(revealed)
+}
+
+/// 加密所有供应商中尚未加密的密钥，返回本次加密的数量（可重复执行）
+pub fn seal_all_providers(db: &Database, key: &VaultKey) -> Result<usize, AppError> {
+    let mut sealed_count = 0;
+    for app_type in AppType::all() {
+        let app = app_type.as_str();
+        for (provider_id, provider) in db.get_all_providers(app)? {
+            let (settings, secrets) =
+                seal_settings(key, app, &provider_id, &provider.settings_config)?;
+            if secrets.is_empty() {
+                continue;
+            }
+            db.store_sealed_provider_settings(app, &provider_id, &settings, &secrets)?;
+            sealed_count += secrets.len();
+        }
+    }
+    Ok(sealed_count)
+}
+
+/// 解密所有供应商中的引用，返回 `(应用, 供应商 ID, 明文配置)`，供关闭保险库时写回
+pub fn reveal_all_providers(db: &Database) -> Result<Vec<(String, String, Value)>, AppError> {
+    let mut revealed = Vec::new();
+    for app_type in AppType::all() {
+        let app = app_type.as_str();
+        for (provider_id, provider) in db.get_all_providers(app)? {
+            if collect_vault_refs(&provider.settings_config).is_empty() {
+                continue;
+            }
+            revealed.push((
+                app.to_string(),
+                provider_id,
+                reveal_settings(db, &provider.settings_config)?,
+            ));
+        }
+    }
+    Ok(revealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use serial_test::serial;
+
+    /// 进程内的解锁缓存是全局的：用到它的测试串行执行，并在开始时清空
+    fn reset_unlocked_keys() {
+        if let Ok(mut unlocked) = unlocked_keys().lock() {
+            *unlocked = None;
+        }
+    }
+
+    fn test_key() -> VaultKey {
+        VaultKey {
+            bytes: [7u8; KEY_LEN],
+        }
+    }
+
+    #[test]
+    fn encrypt_round_trips_and_binds_aad() {
+        let key = test_key();
+        let sealed = key.encrypt("secret-1", b"sk-live-123").unwrap();
+        assert_eq!(key.decrypt("secret-1", &sealed).unwrap(), b"sk-live-123");
+        assert!(key.decrypt("secret-2", &sealed).is_err());
+
+        let other = VaultKey {
+            bytes: [8u8; KEY_LEN],
+        };
+        assert!(other.decrypt("secret-1", &sealed).is_err());
+    }
+
+    #[test]
+    fn fingerprint_is_keyed_by_the_vault_key() {
+        let key = test_key();
+        let other = VaultKey {
+            bytes: [8u8; KEY_LEN],
+        };
+        assert_eq!(
+            key.fingerprint("sk-live-123"),
+            key.fingerprint("sk-live-123")
+        );
+        assert_ne!(
+            key.fingerprint("sk-live-123"),
+            key.fingerprint("sk-live-456")
+        );
+        assert_ne!(
+            key.fingerprint("sk-live-123"),
+            other.fingerprint("sk-live-123")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn passphrase_vault_rejects_wrong_passphrase() {
+        reset_unlocked_keys();
+        let (config, _) = create_vault(VaultKeySource::Passphrase, Some("correct"), None).unwrap();
+        // 缓存中已有该保险库的密钥，显式传入的错误口令仍然被拒绝
+        assert!(unlock(&config, Some("wrong")).is_err());
+        assert!(unlock(&config, Some("correct")).is_ok());
+
+        reset_unlocked_keys();
+        assert!(unlock(&config, Some("wrong")).is_err());
+        assert!(unlock(&config, Some("correct")).is_ok());
+        assert!(unlock(&config, None).is_ok());
+    }
+
+    #[test]
+    fn secret_fields_cover_provider_key_names() {
+        for name in [
+            "ANTHROPIC_AUTH_TOKEN",
+            "ANTHROPIC_API_KEY",
+            "OPENAI_API_KEY",
+            "GEMINI_API_KEY",
+            "apiKey",
+            "client_secret",
+        ] {
+            assert!(is_secret_field(name), "{name}");
+        }
+        for name in ["ANTHROPIC_BASE_URL", "max_tokens", "apiKeyEnv", "model"] {
+            assert!(!is_secret_field(name), "{name}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn seal_settings_replaces_secrets_with_refs() {
+        reset_unlocked_keys();
+        let (_, key) = create_vault(VaultKeySource::Passphrase, Some("vault-test"), None).unwrap();
+        let settings = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-ant-1",
+                "ANTHROPIC_BASE_URL": "https://api.example.com",
+            },
+            "auth": { "OPENAI_API_KEY": "PROXY_MANAGED" },
+        });
+
+        let (sealed, secrets) = seal_settings(&key, "claude", "p1", &settings).unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].json_path, "/env/ANTHROPIC_AUTH_TOKEN");
+        assert_eq!(secrets[0].fingerprint, key.fingerprint("sk-ant-1"));
+        assert_eq!(
+            sealed["env"]["ANTHROPIC_AUTH_TOKEN"],
+            json!(vault_ref(&secrets[0].id))
+        );
+        assert_eq!(sealed["auth"]["OPENAI_API_KEY"], "PROXY_MANAGED");
+        assert_eq!(
+            collect_vault_refs(&sealed),
+            vec![(
+                "/env/ANTHROPIC_AUTH_TOKEN".to_string(),
+                secrets[0].id.clone()
+            )]
+        );
+        assert!(collect_plaintext_secrets(&sealed).is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn save_provider_seals_changed_secrets_and_refuses_when_locked() {
+        reset_unlocked_keys();
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::memory().unwrap();
+        let (config, _) = create_vault(
+            VaultKeySource::KeyFile,
+            None,
+            Some(&dir.path().join("vault.key")),
+        )
+        .unwrap();
+        db.set_secret_vault_config(&config).unwrap();
+
+        let provider = |key: &str| {
+            Provider::with_id(
+                "p1".to_string(),
+                "Relay".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": key } }),
+                None,
+            )
+        };
+        let stored_token = |db: &Database| {
+            db.get_all_providers("claude").unwrap()["p1"].settings_config["env"]
+                ["ANTHROPIC_AUTH_TOKEN"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        db.save_provider("claude", &provider("sk-first")).unwrap();
+        let first_ref = stored_token(&db);
+        assert!(vault_ref_id(&first_ref).is_some(), "{first_ref}");
+        assert_eq!(reveal_value(&db, &first_ref).unwrap(), "sk-first");
+
+        // live 回填的同一明文恢复为原引用，不重复加密
+        db.save_provider("claude", &provider("sk-first")).unwrap();
+        assert_eq!(stored_token(&db), first_ref);
+
+        db.save_provider("claude", &provider("sk-second")).unwrap();
+        let second_ref = stored_token(&db);
+        assert_ne!(second_ref, first_ref);
+        assert_eq!(reveal_value(&db, &second_ref).unwrap(), "sk-second");
+        assert_eq!(db.list_provider_secrets().unwrap().len(), 1);
+
+        let (locked, _) = create_vault(VaultKeySource::Passphrase, Some("locked"), None).unwrap();
+        db.set_secret_vault_config(&locked).unwrap();
+        reset_unlocked_keys();
+        assert!(db.save_provider("claude", &provider("sk-third")).is_err());
+        assert_eq!(stored_token(&db), second_ref);
+    }
+}
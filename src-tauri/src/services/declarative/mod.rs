@@ -0,0 +1,406 @@
+//! 声明式配置：从 YAML/TOML 文件整体描述并同步 cc-switch 配置
+//!
+//! 先用 `plan` 把声明与数据库当前状态对比得到变更计划，再由 `apply` 按计划执行。
+//! 只有文件中出现的应用与分区受管理；删除未声明条目需要显式开启 prune。
+
+mod plan;
+mod spec;
+
+use std::collections::HashMap;
+
+use crate::app_config::{AppType, McpApps, McpServer};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::skill::SkillRepo;
+use crate::services::{McpService, PromptService, ProviderService, SkillService};
+use crate::store::AppState;
+
+use self::plan::{build_plan, merge_declared, AppProxySnapshot, Snapshot};
+
+pub use self::plan::{ApplyPlan, ChangeAction, PlanSection, PlannedChange};
+pub use self::spec::DeclarativeConfig;
+
+pub struct DeclarativeService;
+
+impl DeclarativeService {
+    /// 对比声明与数据库，生成变更计划（不做任何修改）
+    pub fn plan(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        prune: bool,
+    ) -> Result<ApplyPlan, AppError> {
+        let snapshot = Self::capture(state, config)?;
+        let config = Self::with_vault_refs(state, config)?;
+        build_plan(&snapshot, &config, prune)
+    }
+
+    /// 按计划执行变更；计划应由同一份声明通过 `plan` 生成
+    pub fn apply(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        plan: &ApplyPlan,
+    ) -> Result<(), AppError> {
+        let runtime = create_runtime()?;
+        for change in &plan.changes {
+            match change.section {
+                PlanSection::Provider => Self::apply_provider(state, config, change)?,
+                PlanSection::McpServer => Self::apply_mcp_server(state, config, change)?,
+                PlanSection::Prompt => Self::apply_prompt(state, config, change)?,
+                PlanSection::SkillRepo => Self::apply_skill_repo(config, change)?,
+                PlanSection::Failover => {
+                    let app = change_app(change)?;
+                    let queue = config.failover.get(&app).cloned().unwrap_or_default();
+                    state.db.replace_failover_queue(app.as_str(), &queue)?;
+                }
+                PlanSection::Proxy => Self::apply_proxy(state, config, change, &runtime)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 读取声明涉及部分的当前状态
+    fn capture(state: &AppState, config: &DeclarativeConfig) -> Result<Snapshot, AppError> {
+        let runtime = create_runtime()?;
+        let mut snapshot = Snapshot::default();
+
+        let provider_apps = config.providers.keys().chain(config.failover.keys());
+        for app in provider_apps {
+            if snapshot.providers.contains_key(app) {
+                continue;
+            }
+            snapshot
+                .providers
+                .insert(app.clone(), ProviderService::list(state, app.clone())?);
+            if !app.is_additive_mode() {
+                snapshot
+                    .current
+                    .insert(app.clone(), ProviderService::current(state, app.clone())?);
+            }
+        }
+
+        if config.mcp_servers.is_some() {
+            snapshot.mcp_servers = McpService::get_all_servers(state)?;
+        }
+        for app in config.prompts.keys() {
+            snapshot
+                .prompts
+                .insert(app.clone(), PromptService::get_prompts(state, app.clone())?);
+        }
+        if config.skill_repos.is_some() {
+            snapshot.skill_repos = SkillService::list_repos()?;
+        }
+        for app in config.failover.keys() {
+            let queue = state
+                .db
+                .get_failover_queue(app.as_str())?
+                .into_iter()
+                .map(|item| item.provider_id)
+                .collect();
+            snapshot.failover.insert(app.clone(), queue);
+        }
+
+        if let Some(proxy) = &config.proxy {
+            if proxy.listen_address.is_some() {
+                snapshot.listen_address = runtime
+                    .block_on(state.proxy_service.get_config())?
+                    .listen_address;
+            }
+            let mut app_proxy = HashMap::new();
+            for app in proxy.apps.keys() {
+                let app_config =
+                    runtime.block_on(state.db.get_proxy_config_for_app(app.as_str()))?;
+                app_proxy.insert(
+                    app.clone(),
+                    AppProxySnapshot {
+                        listen_port: state.db.get_app_proxy_preferred_port(app.as_str())?,
+                        routing_strategy: app_config.routing_strategy,
+                        max_retries: app_config.max_retries,
+                    },
+                );
+            }
+            snapshot.app_proxy = app_proxy;
+        }
+
+        Ok(snapshot)
+    }
+
+    /// 把声明中的明文密钥替换为已有的保险库引用，避免保险库启用时误判为变更
+    fn with_vault_refs(
+        state: &AppState,
+        config: &DeclarativeConfig,
+    ) -> Result<DeclarativeConfig, AppError> {
+        let mut config = config.clone();
+        for (app, specs) in config.providers.iter_mut() {
+            for spec in specs.iter_mut() {
+                spec.settings =
+                    state
+                        .db
+                        .settings_with_vault_refs(app.as_str(), &spec.id, &spec.settings)?;
+            }
+        }
+        Ok(config)
+    }
+
+    fn apply_provider(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        change: &PlannedChange,
+    ) -> Result<(), AppError> {
+        let app = change_app(change)?;
+        if change.action == ChangeAction::Delete {
+            return ProviderService::delete(state, app, &change.id);
+        }
+
+        let spec = config
+            .providers
+            .get(&app)
+            .and_then(|specs| specs.iter().find(|spec| spec.id == change.id))
+            .ok_or_else(|| missing_spec(change))?;
+        let only_current = change.fields.iter().all(|field| field == "current");
+
+        if change.action == ChangeAction::Create {
+            let mut provider = Provider::with_id(
+                spec.id.clone(),
+                spec.display_name().to_string(),
+                spec.settings.clone(),
+                spec.website_url.clone(),
+            );
+            provider.notes = spec.notes.clone();
+            provider.category = spec.category.clone();
+            ProviderService::add(state, app.clone(), provider)?;
+        } else if !only_current {
+            let mut provider = ProviderService::list(state, app.clone())?
+                .shift_remove(&spec.id)
+                .ok_or_else(|| missing_spec(change))?;
+            provider.name = spec.display_name().to_string();
+            merge_declared(&mut provider.settings_config, &spec.settings);
+            if spec.website_url.is_some() {
+                provider.website_url = spec.website_url.clone();
+            }
+            if spec.notes.is_some() {
+                provider.notes = spec.notes.clone();
+            }
+            if spec.category.is_some() {
+                provider.category = spec.category.clone();
+            }
+            ProviderService::update(state, app.clone(), provider)?;
+        }
+
+        if change.fields.iter().any(|field| field == "current") {
+            ProviderService::switch(state, app, &spec.id)?;
+        }
+        Ok(())
+    }
+
+    fn apply_mcp_server(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        change: &PlannedChange,
+    ) -> Result<(), AppError> {
+        if change.action == ChangeAction::Delete {
+            McpService::delete_server(state, &change.id)?;
+            return Ok(());
+        }
+
+        let spec = config
+            .mcp_servers
+            .iter()
+            .flatten()
+            .find(|spec| spec.id == change.id)
+            .ok_or_else(|| missing_spec(change))?;
+        let mut server = McpService::get_all_servers(state)?
+            .remove(&spec.id)
+            .unwrap_or_else(|| McpServer {
+                id: spec.id.clone(),
+                name: String::new(),
+                server: spec.server.clone(),
+                apps: McpApps::default(),
+                description: None,
+                homepage: None,
+                docs: None,
+                tags: Vec::new(),
+            });
+        server.name = spec.display_name().to_string();
+        server.server = spec.server.clone();
+        server.apps = McpApps::default();
+        for app in &spec.apps {
+            server.apps.set_enabled_for(app, true);
+        }
+        if spec.description.is_some() {
+            server.description = spec.description.clone();
+        }
+        if spec.homepage.is_some() {
+            server.homepage = spec.homepage.clone();
+        }
+        if spec.docs.is_some() {
+            server.docs = spec.docs.clone();
+        }
+        if !spec.tags.is_empty() {
+            server.tags = spec.tags.clone();
+        }
+        McpService::upsert_server(state, server)
+    }
+
+    fn apply_prompt(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        change: &PlannedChange,
+    ) -> Result<(), AppError> {
+        let app = change_app(change)?;
+        let existing = PromptService::get_prompts(state, app.clone())?.shift_remove(&change.id);
+
+        if change.action == ChangeAction::Delete {
+            if existing.as_ref().is_some_and(|prompt| prompt.enabled) {
+                PromptService::disable_prompt(state, app.clone(), &change.id)?;
+            }
+            return PromptService::delete_prompt(state, app, &change.id);
+        }
+
+        let spec = config
+            .prompts
+            .get(&app)
+            .and_then(|specs| specs.iter().find(|spec| spec.id == change.id))
+            .ok_or_else(|| missing_spec(change))?;
+
+        let Some(existing) = existing else {
+            PromptService::create_prompt_with_id(
+                state,
+                app.clone(),
+                Some(&spec.id),
+                spec.display_name(),
+                spec.description.as_deref(),
+                spec.resolved_content(),
+            )?;
+            if spec.enabled {
+                PromptService::enable_prompt(state, app, &spec.id)?;
+            }
+            return Ok(());
+        };
+
+        if existing.enabled && !spec.enabled {
+            PromptService::disable_prompt(state, app.clone(), &spec.id)?;
+        }
+        let updated = PromptService::update_prompt(
+            state,
+            app.clone(),
+            &spec.id,
+            &spec.id,
+            spec.display_name(),
+            spec.description.clone().or(existing.description),
+            Some(spec.resolved_content().to_string()),
+        )?;
+        if spec.enabled && existing.enabled {
+            // 已启用的提示词内容变化后需要重写 live 文件
+            PromptService::upsert_prompt(state, app, &spec.id, updated)?;
+        } else if spec.enabled {
+            PromptService::enable_prompt(state, app, &spec.id)?;
+        }
+        Ok(())
+    }
+
+    fn apply_skill_repo(
+        config: &DeclarativeConfig,
+        change: &PlannedChange,
+    ) -> Result<(), AppError> {
+        let (owner, name) = change
+            .id
+            .split_once('/')
+            .ok_or_else(|| missing_spec(change))?;
+        if change.action == ChangeAction::Delete {
+            return SkillService::remove_repo(owner, name);
+        }
+
+        let spec = config
+            .skill_repos
+            .iter()
+            .flatten()
+            .find(|spec| spec.owner == owner && spec.name == name)
+            .ok_or_else(|| missing_spec(change))?;
+        SkillService::upsert_repo(SkillRepo {
+            owner: spec.owner.clone(),
+            name: spec.name.clone(),
+            branch: spec.branch.clone(),
+            enabled: spec.enabled,
+        })
+    }
+
+    fn apply_proxy(
+        state: &AppState,
+        config: &DeclarativeConfig,
+        change: &PlannedChange,
+        runtime: &tokio::runtime::Runtime,
+    ) -> Result<(), AppError> {
+        let Some(proxy) = &config.proxy else {
+            return Err(missing_spec(change));
+        };
+
+        let Some(app) = change.app.clone() else {
+            let Some(address) = &proxy.listen_address else {
+                return Err(missing_spec(change));
+            };
+            if runtime.block_on(state.proxy_service.get_status()).running {
+                return Err(AppError::localized(
+                    "declarative.proxy_running",
+                    "代理运行中，无法修改监听地址；请先停止代理",
+                    "Stop the proxy before changing its listen address",
+                ));
+            }
+            let mut proxy_config = runtime.block_on(state.proxy_service.get_config())?;
+            proxy_config.listen_address = address.clone();
+            return runtime.block_on(state.proxy_service.update_config(&proxy_config));
+        };
+
+        let spec = proxy.apps.get(&app).ok_or_else(|| missing_spec(change))?;
+        if let Some(port) = spec.listen_port {
+            if change.fields.iter().any(|field| field == "listen_port") {
+                let status = runtime.block_on(state.proxy_service.get_status());
+                if status
+                    .active_workers
+                    .iter()
+                    .any(|worker| worker.app_type == app.as_str())
+                {
+                    return Err(AppError::localized(
+                        "declarative.proxy_route_running",
+                        format!("{} 的代理路由运行中，无法修改端口；请先停止", app.as_str()),
+                        format!(
+                            "Stop the {} proxy route before changing its port",
+                            app.as_str()
+                        ),
+                    ));
+                }
+                state.db.set_app_proxy_preferred_port(app.as_str(), port)?;
+            }
+        }
+        if spec.routing_strategy.is_some() || spec.max_retries.is_some() {
+            let mut app_config =
+                runtime.block_on(state.db.get_proxy_config_for_app(app.as_str()))?;
+            if let Some(strategy) = spec.routing_strategy {
+                app_config.routing_strategy = strategy;
+            }
+            if let Some(retries) = spec.max_retries {
+                app_config.max_retries = retries;
+            }
+            runtime.block_on(state.db.update_proxy_config_for_app(app_config))?;
+        }
+        Ok(())
+    }
+}
+
+fn change_app(change: &PlannedChange) -> Result<AppType, AppError> {
+    change.app.clone().ok_or_else(|| missing_spec(change))
+}
+
+fn missing_spec(change: &PlannedChange) -> AppError {
+    AppError::Message(format!(
+        "计划与声明不一致: {} {}",
+        change.section.label(),
+        change.target()
+    ))
+}
+
+fn create_runtime() -> Result<tokio::runtime::Runtime, AppError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::Message(format!("failed to create async runtime: {e}")))
+}
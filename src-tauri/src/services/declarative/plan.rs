@@ -0,0 +1,705 @@
+//! 计算声明式配置与数据库当前状态之间的差异
+//!
+//! 差异只记录字段路径，不包含字段值，因此打印计划不会泄露密钥。
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::app_config::{AppType, McpServer};
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::provider::Provider;
+use crate::proxy::types::RoutingStrategy;
+use crate::services::skill::SkillRepo;
+
+use super::spec::{DeclarativeConfig, McpServerSpec, PromptSpec, ProviderSpec};
+
+/// 计划中的变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// 变更所属的配置分区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanSection {
+    Provider,
+    McpServer,
+    Prompt,
+    SkillRepo,
+    Failover,
+    Proxy,
+}
+
+impl PlanSection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlanSection::Provider => "provider",
+            PlanSection::McpServer => "mcp",
+            PlanSection::Prompt => "prompt",
+            PlanSection::SkillRepo => "skill repo",
+            PlanSection::Failover => "failover",
+            PlanSection::Proxy => "proxy",
+        }
+    }
+}
+
+/// 单条计划变更；`fields` 为发生变化的字段路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    pub section: PlanSection,
+    pub action: ChangeAction,
+    pub app: Option<AppType>,
+    pub id: String,
+    pub fields: Vec<String>,
+}
+
+impl PlannedChange {
+    fn new(
+        section: PlanSection,
+        action: ChangeAction,
+        app: Option<AppType>,
+        id: impl Into<String>,
+        fields: Vec<String>,
+    ) -> Self {
+        Self {
+            section,
+            action,
+            app,
+            id: id.into(),
+            fields,
+        }
+    }
+
+    /// 用于展示的目标名称，如 `claude/team`
+    pub fn target(&self) -> String {
+        match (&self.section, &self.app) {
+            (PlanSection::Provider | PlanSection::Prompt, Some(app)) => {
+                format!("{}/{}", app.as_str(), self.id)
+            }
+            (PlanSection::Failover | PlanSection::Proxy, Some(app)) => app.as_str().to_string(),
+            _ => self.id.clone(),
+        }
+    }
+}
+
+/// 完整的执行计划，变更按执行顺序排列（删除排在最后）
+#[derive(Debug, Clone, Default)]
+pub struct ApplyPlan {
+    pub changes: Vec<PlannedChange>,
+    /// 已与声明一致、无需改动的条目数
+    pub unchanged: usize,
+}
+
+impl ApplyPlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn count(&self, action: ChangeAction) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.action == action)
+            .count()
+    }
+}
+
+/// 单个应用的代理设置快照
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct AppProxySnapshot {
+    pub listen_port: u16,
+    pub routing_strategy: RoutingStrategy,
+    pub max_retries: u32,
+}
+
+/// 计划所需的数据库状态快照；只包含声明文件涉及的部分
+#[derive(Debug, Clone, Default)]
+pub(super) struct Snapshot {
+    pub providers: HashMap<AppType, IndexMap<String, Provider>>,
+    pub current: HashMap<AppType, String>,
+    pub mcp_servers: HashMap<String, McpServer>,
+    pub prompts: HashMap<AppType, IndexMap<String, Prompt>>,
+    pub skill_repos: Vec<SkillRepo>,
+    pub failover: HashMap<AppType, Vec<String>>,
+    pub listen_address: String,
+    pub app_proxy: HashMap<AppType, AppProxySnapshot>,
+}
+
+/// 对比声明与快照生成计划；`prune` 为真时删除受管分区中未声明的条目
+pub(super) fn build_plan(
+    snapshot: &Snapshot,
+    config: &DeclarativeConfig,
+    prune: bool,
+) -> Result<ApplyPlan, AppError> {
+    let mut plan = ApplyPlan::default();
+    let mut deletions = Vec::new();
+
+    for (app, specs) in &config.providers {
+        let existing = snapshot.providers.get(app);
+        let current = snapshot.current.get(app).map(String::as_str);
+        for spec in specs {
+            match existing.and_then(|providers| providers.get(&spec.id)) {
+                Some(provider) => {
+                    let mut fields = provider_diff(spec, provider);
+                    if spec.current && current != Some(spec.id.as_str()) {
+                        fields.push("current".to_string());
+                    }
+                    push_update(
+                        &mut plan,
+                        PlanSection::Provider,
+                        Some(app),
+                        &spec.id,
+                        fields,
+                    );
+                }
+                None => {
+                    let fields = if spec.current {
+                        vec!["current".to_string()]
+                    } else {
+                        Vec::new()
+                    };
+                    plan.changes.push(PlannedChange::new(
+                        PlanSection::Provider,
+                        ChangeAction::Create,
+                        Some(app.clone()),
+                        &spec.id,
+                        fields,
+                    ));
+                }
+            }
+        }
+
+        if prune {
+            let switches_current = specs.iter().any(|spec| spec.current);
+            for id in existing.into_iter().flat_map(|providers| providers.keys()) {
+                if specs.iter().any(|spec| &spec.id == id) {
+                    continue;
+                }
+                if current == Some(id.as_str()) && !switches_current {
+                    return Err(AppError::localized(
+                        "declarative.prune_current",
+                        format!(
+                            "无法删除当前供应商 {}/{id}：请在配置中把其他供应商标记为 current",
+                            app.as_str()
+                        ),
+                        format!(
+                            "Cannot prune the current provider {}/{id}: mark another provider as `current`",
+                            app.as_str()
+                        ),
+                    ));
+                }
+                deletions.push(PlannedChange::new(
+                    PlanSection::Provider,
+                    ChangeAction::Delete,
+                    Some(app.clone()),
+                    id,
+                    Vec::new(),
+                ));
+            }
+        }
+    }
+
+    if let Some(specs) = &config.mcp_servers {
+        for spec in specs {
+            match snapshot.mcp_servers.get(&spec.id) {
+                Some(server) => {
+                    let fields = mcp_diff(spec, server);
+                    push_update(&mut plan, PlanSection::McpServer, None, &spec.id, fields);
+                }
+                None => plan.changes.push(PlannedChange::new(
+                    PlanSection::McpServer,
+                    ChangeAction::Create,
+                    None,
+                    &spec.id,
+                    Vec::new(),
+                )),
+            }
+        }
+        if prune {
+            let mut stale = snapshot
+                .mcp_servers
+                .keys()
+                .filter(|id| !specs.iter().any(|spec| &spec.id == *id))
+                .collect::<Vec<_>>();
+            stale.sort();
+            for id in stale {
+                deletions.push(PlannedChange::new(
+                    PlanSection::McpServer,
+                    ChangeAction::Delete,
+                    None,
+                    id,
+                    Vec::new(),
+                ));
+            }
+        }
+    }
+
+    for (app, specs) in &config.prompts {
+        let existing = snapshot.prompts.get(app);
+        for spec in specs {
+            match existing.and_then(|prompts| prompts.get(&spec.id)) {
+                Some(prompt) => {
+                    let fields = prompt_diff(spec, prompt);
+                    push_update(&mut plan, PlanSection::Prompt, Some(app), &spec.id, fields);
+                }
+                None => {
+                    let fields = if spec.enabled {
+                        vec!["enabled".to_string()]
+                    } else {
+                        Vec::new()
+                    };
+                    plan.changes.push(PlannedChange::new(
+                        PlanSection::Prompt,
+                        ChangeAction::Create,
+                        Some(app.clone()),
+                        &spec.id,
+                        fields,
+                    ));
+                }
+            }
+        }
+        if prune {
+            for id in existing.into_iter().flat_map(|prompts| prompts.keys()) {
+                if !specs.iter().any(|spec| &spec.id == id) {
+                    deletions.push(PlannedChange::new(
+                        PlanSection::Prompt,
+                        ChangeAction::Delete,
+                        Some(app.clone()),
+                        id,
+                        Vec::new(),
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(specs) = &config.skill_repos {
+        for spec in specs {
+            let id = format!("{}/{}", spec.owner, spec.name);
+            let existing = snapshot
+                .skill_repos
+                .iter()
+                .find(|repo| repo.owner == spec.owner && repo.name == spec.name);
+            match existing {
+                Some(repo) => {
+                    let mut fields = Vec::new();
+                    if repo.branch != spec.branch {
+                        fields.push("branch".to_string());
+                    }
+                    if repo.enabled != spec.enabled {
+                        fields.push("enabled".to_string());
+                    }
+                    push_update(&mut plan, PlanSection::SkillRepo, None, &id, fields);
+                }
+                None => plan.changes.push(PlannedChange::new(
+                    PlanSection::SkillRepo,
+                    ChangeAction::Create,
+                    None,
+                    id,
+                    Vec::new(),
+                )),
+            }
+        }
+        if prune {
+            for repo in &snapshot.skill_repos {
+                let declared = specs
+                    .iter()
+                    .any(|spec| repo.owner == spec.owner && repo.name == spec.name);
+                if !declared {
+                    deletions.push(PlannedChange::new(
+                        PlanSection::SkillRepo,
+                        ChangeAction::Delete,
+                        None,
+                        format!("{}/{}", repo.owner, repo.name),
+                        Vec::new(),
+                    ));
+                }
+            }
+        }
+    }
+
+    for (app, queue) in &config.failover {
+        for id in queue {
+            let declared = config
+                .providers
+                .get(app)
+                .is_some_and(|specs| specs.iter().any(|spec| &spec.id == id));
+            let pruned = deletions.iter().any(|change| {
+                change.section == PlanSection::Provider
+                    && change.app.as_ref() == Some(app)
+                    && &change.id == id
+            });
+            let stored = snapshot
+                .providers
+                .get(app)
+                .is_some_and(|providers| providers.contains_key(id));
+            if !declared && (!stored || pruned) {
+                return Err(AppError::localized(
+                    "declarative.failover_unknown_provider",
+                    format!("故障转移队列 {} 引用了不存在的供应商 {id}", app.as_str()),
+                    format!(
+                        "Failover queue {} references unknown provider {id}",
+                        app.as_str()
+                    ),
+                ));
+            }
+        }
+        let current = snapshot.failover.get(app).cloned().unwrap_or_default();
+        let fields = if &current == queue {
+            Vec::new()
+        } else {
+            vec!["queue".to_string()]
+        };
+        push_update(
+            &mut plan,
+            PlanSection::Failover,
+            Some(app),
+            app.as_str(),
+            fields,
+        );
+    }
+
+    if let Some(proxy) = &config.proxy {
+        if let Some(address) = &proxy.listen_address {
+            let fields = if address != &snapshot.listen_address {
+                vec!["listen_address".to_string()]
+            } else {
+                Vec::new()
+            };
+            push_update(&mut plan, PlanSection::Proxy, None, "global", fields);
+        }
+        for (app, spec) in &proxy.apps {
+            let current = snapshot.app_proxy.get(app);
+            let mut fields = Vec::new();
+            if spec
+                .listen_port
+                .is_some_and(|port| current.map(|c| c.listen_port) != Some(port))
+            {
+                fields.push("listen_port".to_string());
+            }
+            if spec
+                .routing_strategy
+                .is_some_and(|strategy| current.map(|c| c.routing_strategy) != Some(strategy))
+            {
+                fields.push("routing_strategy".to_string());
+            }
+            if spec
+                .max_retries
+                .is_some_and(|retries| current.map(|c| c.max_retries) != Some(retries))
+            {
+                fields.push("max_retries".to_string());
+            }
+            push_update(
+                &mut plan,
+                PlanSection::Proxy,
+                Some(app),
+                app.as_str(),
+                fields,
+            );
+        }
+    }
+
+    // 先删提示词与 MCP，再删仓库与供应商，保证被引用的条目最后移除
+    deletions.sort_by_key(|change| match change.section {
+        PlanSection::Prompt => 0,
+        PlanSection::McpServer => 1,
+        PlanSection::SkillRepo => 2,
+        _ => 3,
+    });
+    plan.changes.extend(deletions);
+    Ok(plan)
+}
+
+fn push_update(
+    plan: &mut ApplyPlan,
+    section: PlanSection,
+    app: Option<&AppType>,
+    id: &str,
+    fields: Vec<String>,
+) {
+    if fields.is_empty() {
+        plan.unchanged += 1;
+        return;
+    }
+    plan.changes.push(PlannedChange::new(
+        section,
+        ChangeAction::Update,
+        app.cloned(),
+        id,
+        fields,
+    ));
+}
+
+fn provider_diff(spec: &ProviderSpec, provider: &Provider) -> Vec<String> {
+    let mut fields = Vec::new();
+    if provider.name != spec.display_name() {
+        fields.push("name".to_string());
+    }
+    subset_diff(
+        &spec.settings,
+        &provider.settings_config,
+        "settings",
+        &mut fields,
+    );
+    push_if_declared_differs(
+        &mut fields,
+        "website_url",
+        &spec.website_url,
+        &provider.website_url,
+    );
+    push_if_declared_differs(&mut fields, "notes", &spec.notes, &provider.notes);
+    push_if_declared_differs(&mut fields, "category", &spec.category, &provider.category);
+    fields
+}
+
+fn mcp_diff(spec: &McpServerSpec, server: &McpServer) -> Vec<String> {
+    let mut fields = Vec::new();
+    if server.name != spec.display_name() {
+        fields.push("name".to_string());
+    }
+    if server.server != spec.server {
+        fields.push("server".to_string());
+    }
+    let mut apps = server.apps.enabled_apps();
+    let mut declared = spec.apps.clone();
+    apps.sort_by_key(|app| app.as_str());
+    declared.sort_by_key(|app| app.as_str());
+    declared.dedup();
+    if apps != declared {
+        fields.push("apps".to_string());
+    }
+    push_if_declared_differs(
+        &mut fields,
+        "description",
+        &spec.description,
+        &server.description,
+    );
+    push_if_declared_differs(&mut fields, "homepage", &spec.homepage, &server.homepage);
+    push_if_declared_differs(&mut fields, "docs", &spec.docs, &server.docs);
+    if !spec.tags.is_empty() && spec.tags != server.tags {
+        fields.push("tags".to_string());
+    }
+    fields
+}
+
+fn prompt_diff(spec: &PromptSpec, prompt: &Prompt) -> Vec<String> {
+    let mut fields = Vec::new();
+    if prompt.name != spec.display_name() {
+        fields.push("name".to_string());
+    }
+    if prompt.content != spec.resolved_content() {
+        fields.push("content".to_string());
+    }
+    push_if_declared_differs(
+        &mut fields,
+        "description",
+        &spec.description,
+        &prompt.description,
+    );
+    if prompt.enabled != spec.enabled {
+        fields.push("enabled".to_string());
+    }
+    fields
+}
+
+fn push_if_declared_differs(
+    fields: &mut Vec<String>,
+    name: &str,
+    declared: &Option<String>,
+    current: &Option<String>,
+) {
+    if declared.is_some() && declared != current {
+        fields.push(name.to_string());
+    }
+}
+
+/// 只比较声明中出现的键：对象逐键递归，其余类型（含数组）整体比较
+fn subset_diff(desired: &Value, current: &Value, path: &str, fields: &mut Vec<String>) {
+    match desired {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let child = current.get(key).unwrap_or(&Value::Null);
+                subset_diff(value, child, &format!("{path}.{key}"), fields);
+            }
+            if map.is_empty() && !current.is_object() {
+                fields.push(path.to_string());
+            }
+        }
+        _ if desired != current => fields.push(path.to_string()),
+        _ => {}
+    }
+}
+
+/// 把声明的值深度合并进已有配置，未声明的键保持不变
+pub(super) fn merge_declared(target: &mut Value, desired: &Value) {
+    match (target, desired) {
+        (Value::Object(target), Value::Object(desired)) => {
+            for (key, value) in desired {
+                match target.get_mut(key) {
+                    Some(slot) if slot.is_object() && value.is_object() => {
+                        merge_declared(slot, value)
+                    }
+                    _ => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, desired) => *target = desired.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::McpApps;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn config(raw: &str) -> DeclarativeConfig {
+        DeclarativeConfig::parse(raw, false, Path::new("."), |_| None).expect("parse config")
+    }
+
+    fn provider(id: &str, settings: Value) -> Provider {
+        Provider::with_id(id.to_string(), id.to_string(), settings, None)
+    }
+
+    fn snapshot_with_claude(providers: Vec<Provider>, current: &str) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot.providers.insert(
+            AppType::Claude,
+            providers
+                .into_iter()
+                .map(|provider| (provider.id.clone(), provider))
+                .collect(),
+        );
+        snapshot
+            .current
+            .insert(AppType::Claude, current.to_string());
+        snapshot
+    }
+
+    #[test]
+    fn undeclared_settings_keys_are_not_drift() {
+        let snapshot = snapshot_with_claude(
+            vec![provider(
+                "team",
+                json!({"env": {"ANTHROPIC_BASE_URL": "https://a", "EXTRA": "1"}}),
+            )],
+            "team",
+        );
+        let config = config(
+            "providers:\n  claude:\n    - { id: team, current: true, settings: { env: { ANTHROPIC_BASE_URL: 'https://a' } } }\n",
+        );
+
+        let plan = build_plan(&snapshot, &config, false).expect("plan");
+        assert!(plan.is_empty());
+        assert_eq!(plan.unchanged, 1);
+    }
+
+    #[test]
+    fn reports_changed_setting_paths_and_current_switch() {
+        let snapshot = snapshot_with_claude(
+            vec![
+                provider("old", json!({})),
+                provider("team", json!({"env": {"ANTHROPIC_AUTH_TOKEN": "sk-old"}})),
+            ],
+            "old",
+        );
+        let config = config(
+            "providers:\n  claude:\n    - { id: team, current: true, settings: { env: { ANTHROPIC_AUTH_TOKEN: sk-new } } }\n",
+        );
+
+        let plan = build_plan(&snapshot, &config, false).expect("plan");
+        assert_eq!(
+            plan.changes,
+            vec![PlannedChange::new(
+                PlanSection::Provider,
+                ChangeAction::Update,
+                Some(AppType::Claude),
+                "team",
+                vec![
+                    "settings.env.ANTHROPIC_AUTH_TOKEN".to_string(),
+                    "current".to_string()
+                ],
+            )]
+        );
+    }
+
+    #[test]
+    fn prune_deletes_undeclared_entries_last() {
+        let mut snapshot = snapshot_with_claude(
+            vec![provider("old", json!({})), provider("team", json!({}))],
+            "old",
+        );
+        snapshot.mcp_servers.insert(
+            "stale".to_string(),
+            McpServer {
+                id: "stale".to_string(),
+                name: "stale".to_string(),
+                server: json!({"command": "x"}),
+                apps: McpApps::default(),
+                description: None,
+                homepage: None,
+                docs: None,
+                tags: Vec::new(),
+            },
+        );
+        let config = config(
+            "providers:\n  claude:\n    - { id: team, current: true, settings: {} }\nmcp_servers:\n  - { id: fs, server: { command: npx }, apps: [claude] }\n",
+        );
+
+        let without_prune = build_plan(&snapshot, &config, false).expect("plan");
+        assert_eq!(without_prune.count(ChangeAction::Delete), 0);
+
+        let plan = build_plan(&snapshot, &config, true).expect("plan");
+        let summary = plan
+            .changes
+            .iter()
+            .map(|change| (change.action, change.target()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeAction::Update, "claude/team".to_string()),
+                (ChangeAction::Create, "fs".to_string()),
+                (ChangeAction::Delete, "stale".to_string()),
+                (ChangeAction::Delete, "claude/old".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn pruning_the_current_provider_requires_a_replacement() {
+        let snapshot = snapshot_with_claude(
+            vec![provider("old", json!({})), provider("team", json!({}))],
+            "old",
+        );
+        let config = config("providers:\n  claude:\n    - { id: team, settings: {} }\n");
+
+        assert!(build_plan(&snapshot, &config, true).is_err());
+    }
+
+    #[test]
+    fn failover_queue_must_reference_known_providers() {
+        let snapshot = snapshot_with_claude(vec![provider("a", json!({}))], "a");
+
+        let known = config("failover:\n  claude: [a]\n");
+        let plan = build_plan(&snapshot, &known, false).expect("plan");
+        assert_eq!(plan.changes[0].fields, vec!["queue".to_string()]);
+
+        let unknown = config("failover:\n  claude: [a, missing]\n");
+        assert!(build_plan(&snapshot, &unknown, false).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_undeclared_keys() {
+        let mut target = json!({"env": {"A": "1", "B": "2"}, "model": "x"});
+        merge_declared(&mut target, &json!({"env": {"B": "3"}}));
+        assert_eq!(target, json!({"env": {"A": "1", "B": "3"}, "model": "x"}));
+    }
+}
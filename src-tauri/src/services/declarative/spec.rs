@@ -0,0 +1,584 @@
+//! 声明式配置文件的结构定义、解析与校验
+//!
+//! 文件可以是 YAML（`.yaml` / `.yml`，默认）或 TOML（`.toml`）。字符串中的
+//! `${env:NAME}` 会在反序列化前替换为环境变量的值，便于把密钥留在仓库之外。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::proxy::types::RoutingStrategy;
+use crate::services::PromptService;
+
+/// 当前支持的声明式配置版本
+pub const DECLARATIVE_CONFIG_VERSION: u32 = 1;
+
+const ENV_REF_PREFIX: &str = "${env:";
+
+/// 声明式配置根结构
+///
+/// 只有出现在文件中的部分才受管理：未列出的应用、未声明的分区不会被改动。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeclarativeConfig {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// 按应用声明的供应商
+    #[serde(default)]
+    pub providers: IndexMap<AppType, Vec<ProviderSpec>>,
+    /// MCP 服务器及其启用的应用；省略时不管理 MCP
+    #[serde(default)]
+    pub mcp_servers: Option<Vec<McpServerSpec>>,
+    /// 按应用声明的提示词
+    #[serde(default)]
+    pub prompts: IndexMap<AppType, Vec<PromptSpec>>,
+    /// Skill 仓库；省略时不管理仓库列表
+    #[serde(default)]
+    pub skill_repos: Option<Vec<SkillRepoSpec>>,
+    /// 按应用声明的故障转移队列（供应商 ID，按顺序）
+    #[serde(default)]
+    pub failover: IndexMap<AppType, Vec<String>>,
+    /// 代理设置
+    #[serde(default)]
+    pub proxy: Option<ProxySpec>,
+}
+
+/// 供应商声明
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderSpec {
+    pub id: String,
+    /// 显示名称，省略时使用 ID
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 写入 settings_config 的内容；只比较声明出的字段
+    #[serde(alias = "settings_config")]
+    pub settings: Value,
+    #[serde(default)]
+    pub website_url: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// 是否设为当前供应商（仅非累加模式的应用）
+    #[serde(default)]
+    pub current: bool,
+}
+
+impl ProviderSpec {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// MCP 服务器声明
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct McpServerSpec {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 服务器定义（command/args/env 或 url 等）
+    pub server: Value,
+    /// 启用该服务器的应用
+    #[serde(default)]
+    pub apps: Vec<AppType>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl McpServerSpec {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// 提示词声明；`content` 与 `file` 二选一，`file` 相对于配置文件所在目录
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptSpec {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl PromptSpec {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    /// 解析后的正文（与数据库一致去掉末尾空白）
+    pub fn resolved_content(&self) -> &str {
+        self.content.as_deref().unwrap_or_default().trim_end()
+    }
+}
+
+/// Skill 仓库声明
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillRepoSpec {
+    pub owner: String,
+    pub name: String,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 代理设置声明
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxySpec {
+    /// 全局监听地址
+    #[serde(default)]
+    pub listen_address: Option<String>,
+    /// 按应用的代理设置
+    #[serde(default)]
+    pub apps: IndexMap<AppType, AppProxySpec>,
+}
+
+/// 单个应用的代理设置声明
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppProxySpec {
+    #[serde(default)]
+    pub listen_port: Option<u16>,
+    #[serde(default)]
+    pub routing_strategy: Option<RoutingStrategy>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+fn default_version() -> u32 {
+    DECLARATIVE_CONFIG_VERSION
+}
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl DeclarativeConfig {
+    /// 读取并校验配置文件，展开环境变量引用与提示词文件
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let is_toml = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse(&raw, is_toml, base_dir, |name| std::env::var(name).ok())
+    }
+
+    /// 解析配置文本；`lookup_env` 用于查询环境变量（便于测试注入）
+    pub fn parse(
+        raw: &str,
+        is_toml: bool,
+        base_dir: &Path,
+        lookup_env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, AppError> {
+        let mut value: Value = if is_toml {
+            toml::from_str(raw).map_err(|e| parse_error(e.to_string()))?
+        } else {
+            serde_yaml::from_str(raw).map_err(|e| parse_error(e.to_string()))?
+        };
+        if value.is_null() {
+            value = Value::Object(Default::default());
+        }
+
+        let mut missing = Vec::new();
+        expand_env_refs(&mut value, &lookup_env, &mut missing);
+        if !missing.is_empty() {
+            missing.sort();
+            missing.dedup();
+            let names = missing.join(", ");
+            return Err(AppError::localized(
+                "declarative.env_missing",
+                format!("声明式配置引用的环境变量未设置: {names}"),
+                format!("Environment variables referenced by the config are not set: {names}"),
+            ));
+        }
+
+        let mut config: Self =
+            serde_json::from_value(value).map_err(|e| parse_error(e.to_string()))?;
+        config.resolve_prompt_files(base_dir)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn resolve_prompt_files(&mut self, base_dir: &Path) -> Result<(), AppError> {
+        for (app, prompts) in self.prompts.iter_mut() {
+            for prompt in prompts.iter_mut() {
+                match (&prompt.content, &prompt.file) {
+                    (Some(_), Some(_)) | (None, None) => {
+                        return Err(invalid(
+                            format!(
+                                "提示词 {}/{} 需要且只能设置 content 或 file 其中之一",
+                                app.as_str(),
+                                prompt.id
+                            ),
+                            format!(
+                                "Prompt {}/{} needs exactly one of `content` or `file`",
+                                app.as_str(),
+                                prompt.id
+                            ),
+                        ));
+                    }
+                    (None, Some(file)) => {
+                        let path = base_dir.join(file);
+                        let content =
+                            std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+                        prompt.content = Some(content);
+                    }
+                    (Some(_), None) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        if self.version != DECLARATIVE_CONFIG_VERSION {
+            return Err(invalid(
+                format!(
+                    "不支持的声明式配置版本: {}（当前支持 {DECLARATIVE_CONFIG_VERSION}）",
+                    self.version
+                ),
+                format!(
+                    "Unsupported config version: {} (supported: {DECLARATIVE_CONFIG_VERSION})",
+                    self.version
+                ),
+            ));
+        }
+
+        for (app, providers) in &self.providers {
+            let section = format!("providers.{}", app.as_str());
+            ensure_unique_ids(&section, providers.iter().map(|p| p.id.as_str()))?;
+            for provider in providers {
+                if !provider.settings.is_object() {
+                    return Err(invalid(
+                        format!("{section}.{}: settings 必须是对象", provider.id),
+                        format!("{section}.{}: `settings` must be a mapping", provider.id),
+                    ));
+                }
+            }
+            let current = providers.iter().filter(|p| p.current).count();
+            if current > 0 && app.is_additive_mode() {
+                return Err(invalid(
+                    format!("{section}: {} 为累加模式，不支持 current", app.as_str()),
+                    format!(
+                        "{section}: {} uses additive mode and has no current provider",
+                        app.as_str()
+                    ),
+                ));
+            }
+            if current > 1 {
+                return Err(invalid(
+                    format!("{section}: 最多只能有一个 current 供应商"),
+                    format!("{section}: at most one provider can be `current`"),
+                ));
+            }
+        }
+
+        if let Some(servers) = &self.mcp_servers {
+            ensure_unique_ids("mcp_servers", servers.iter().map(|s| s.id.as_str()))?;
+            for server in servers {
+                if let Some(app) = server.apps.iter().find(|app| **app == AppType::OpenClaw) {
+                    return Err(invalid(
+                        format!("mcp_servers.{}: {} 不支持 MCP", server.id, app.as_str()),
+                        format!(
+                            "mcp_servers.{}: {} does not support MCP servers",
+                            server.id,
+                            app.as_str()
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for (app, prompts) in &self.prompts {
+            let section = format!("prompts.{}", app.as_str());
+            ensure_unique_ids(&section, prompts.iter().map(|p| p.id.as_str()))?;
+            for prompt in prompts {
+                PromptService::validate_prompt_id(&prompt.id)?;
+            }
+            if prompts.iter().filter(|p| p.enabled).count() > 1 {
+                return Err(invalid(
+                    format!("{section}: 最多只能启用一个提示词"),
+                    format!("{section}: at most one prompt can be enabled"),
+                ));
+            }
+        }
+
+        if let Some(repos) = &self.skill_repos {
+            let mut seen = HashSet::new();
+            for repo in repos {
+                let key = format!("{}/{}", repo.owner, repo.name);
+                if !seen.insert(key.clone()) {
+                    return Err(invalid(
+                        format!("skill_repos: 仓库 {key} 重复"),
+                        format!("skill_repos: repository {key} is listed twice"),
+                    ));
+                }
+            }
+        }
+
+        for (app, queue) in &self.failover {
+            let section = format!("failover.{}", app.as_str());
+            ensure_failover_app(&section, app)?;
+            ensure_unique_ids(&section, queue.iter().map(String::as_str))?;
+        }
+
+        if let Some(proxy) = &self.proxy {
+            if let Some(address) = &proxy.listen_address {
+                crate::cli::proxy_settings::validate_proxy_listen_address(address)?;
+            }
+            for (app, settings) in &proxy.apps {
+                ensure_failover_app(&format!("proxy.apps.{}", app.as_str()), app)?;
+                if let Some(port) = settings.listen_port {
+                    crate::cli::proxy_settings::validate_proxy_listen_port(port)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 递归替换字符串中的 `${env:NAME}`，收集缺失的变量名
+fn expand_env_refs(
+    value: &mut Value,
+    lookup_env: &impl Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) {
+    match value {
+        Value::String(text) if text.contains(ENV_REF_PREFIX) => {
+            let mut expanded = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find(ENV_REF_PREFIX) {
+                expanded.push_str(&rest[..start]);
+                let after = &rest[start + ENV_REF_PREFIX.len()..];
+                let Some(end) = after.find('}') else {
+                    expanded.push_str(&rest[start..]);
+                    rest = "";
+                    break;
+                };
+                let name = after[..end].trim();
+                match lookup_env(name) {
+                    Some(resolved) => expanded.push_str(&resolved),
+                    None => missing.push(name.to_string()),
+                }
+                rest = &after[end + 1..];
+            }
+            expanded.push_str(rest);
+            *text = expanded;
+        }
+        Value::Array(items) => {
+            for item in items {
+                expand_env_refs(item, lookup_env, missing);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                expand_env_refs(item, lookup_env, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn ensure_unique_ids<'a>(
+    section: &str,
+    ids: impl Iterator<Item = &'a str>,
+) -> Result<(), AppError> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if id.trim().is_empty() {
+            return Err(invalid(
+                format!("{section}: ID 不能为空"),
+                format!("{section}: ids must not be empty"),
+            ));
+        }
+        if !seen.insert(id) {
+            return Err(invalid(
+                format!("{section}: ID {id} 重复"),
+                format!("{section}: id {id} is listed twice"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn ensure_failover_app(section: &str, app: &AppType) -> Result<(), AppError> {
+    if app.supports_failover() {
+        return Ok(());
+    }
+    Err(invalid(
+        format!("{section}: {} 不支持代理与故障转移", app.as_str()),
+        format!(
+            "{section}: {} does not support proxy or failover",
+            app.as_str()
+        ),
+    ))
+}
+
+fn parse_error(detail: String) -> AppError {
+    AppError::localized(
+        "declarative.parse_failed",
+        format!("解析声明式配置失败: {detail}"),
+        format!("Failed to parse the config file: {detail}"),
+    )
+}
+
+fn invalid(zh: String, en: String) -> AppError {
+    AppError::localized("declarative.invalid", zh, en)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_yaml(raw: &str) -> Result<DeclarativeConfig, AppError> {
+        DeclarativeConfig::parse(raw, false, Path::new("."), |name| {
+            (name == "TEAM_KEY").then(|| "sk-team".to_string())
+        })
+    }
+
+    #[test]
+    fn expands_env_refs_inside_strings() {
+        let config = parse_yaml(
+            r#"
+providers:
+  claude:
+    - id: team
+      current: true
+      settings:
+        env:
+          ANTHROPIC_AUTH_TOKEN: "${env:TEAM_KEY}"
+          ANTHROPIC_BASE_URL: "https://relay.example.com"
+"#,
+        )
+        .expect("parse config");
+
+        let provider = &config.providers[&AppType::Claude][0];
+        assert_eq!(
+            provider.settings["env"]["ANTHROPIC_AUTH_TOKEN"],
+            Value::String("sk-team".to_string())
+        );
+        assert_eq!(provider.display_name(), "team");
+    }
+
+    #[test]
+    fn reports_every_missing_env_var() {
+        let err = parse_yaml(
+            r#"
+providers:
+  claude:
+    - id: a
+      settings: { env: { ANTHROPIC_AUTH_TOKEN: "${env:A_KEY}" } }
+    - id: b
+      settings: { env: { ANTHROPIC_AUTH_TOKEN: "Bearer ${env:B_KEY}" } }
+"#,
+        )
+        .expect_err("missing env vars should fail");
+
+        let message = err.to_string();
+        assert!(message.contains("A_KEY"), "{message}");
+        assert!(message.contains("B_KEY"), "{message}");
+    }
+
+    #[test]
+    fn parses_toml_files() {
+        let config = DeclarativeConfig::parse(
+            r#"
+[[skill_repos]]
+owner = "team"
+name = "skills"
+
+[proxy]
+listen_address = "127.0.0.1"
+
+[proxy.apps.claude]
+listen_port = 15721
+routing_strategy = "least_latency"
+"#,
+            true,
+            Path::new("."),
+            |_| None,
+        )
+        .expect("parse toml");
+
+        let repos = config.skill_repos.expect("skill repos");
+        assert_eq!(repos[0].branch, "main");
+        assert!(repos[0].enabled);
+        let proxy = config.proxy.expect("proxy");
+        assert_eq!(
+            proxy.apps[&AppType::Claude].routing_strategy,
+            Some(RoutingStrategy::LeastLatency)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_invalid_sections() {
+        assert!(parse_yaml("providerz: {}").is_err());
+        assert!(parse_yaml("failover: { opencode: [a] }").is_err());
+        assert!(parse_yaml(
+            r#"
+providers:
+  claude:
+    - { id: a, current: true, settings: {} }
+    - { id: b, current: true, settings: {} }
+"#
+        )
+        .is_err());
+        assert!(parse_yaml(
+            r#"
+prompts:
+  claude:
+    - { id: a, content: x, file: x.md }
+"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reads_prompt_files_relative_to_config() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("team.md"), "Be concise.\n").expect("write prompt");
+
+        let config = DeclarativeConfig::parse(
+            "prompts:\n  claude:\n    - { id: team, file: team.md, enabled: true }\n",
+            false,
+            dir.path(),
+            |_| None,
+        )
+        .expect("parse config");
+
+        assert_eq!(
+            config.prompts[&AppType::Claude][0].resolved_content(),
+            "Be concise."
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! git 仓库传输层
+//!
+//! 通过本机 `git` 命令把同步产物存放在普通 git 仓库的一个分支中。
+//! 本地工作副本位于 `~/.cc-switch/sync/git/<hash>/`，每次同步前先与远端分支对齐；
+//! 认证沿用 git 自身的 SSH key / credential helper 配置，且禁止交互式提示。
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::settings::GitSyncSettings;
+
+const FALLBACK_AUTHOR_NAME: &str = "cc-switch";
+const FALLBACK_AUTHOR_EMAIL: &str = "cc-switch@localhost";
+
+/// 文件内容及读取时的提交（用作 etag）
+type FileWithEtag = (Vec<u8>, Option<String>);
+
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    repo_url: String,
+    branch: String,
+    work_dir: PathBuf,
+}
+
+impl GitRepo {
+    pub fn new(settings: &GitSyncSettings) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(settings.repo_url.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(settings.branch.as_bytes());
+        let id = format!("{:x}", hasher.finalize());
+        let work_dir = crate::config::get_app_config_dir()
+            .join("sync")
+            .join("git")
+            .join(&id[..16]);
+        Self::with_work_dir(settings, work_dir)
+    }
+
+    pub fn with_work_dir(settings: &GitSyncSettings, work_dir: PathBuf) -> Self {
+        Self {
+            repo_url: settings.repo_url.clone(),
+            branch: settings.branch.clone(),
+            work_dir,
+        }
+    }
+
+    /// 用于区分同步目标的位置描述（仓库 + 分支）
+    pub fn location(&self) -> String {
+        format!("{}#{}", self.repo_url, self.branch)
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, AppError> {
+        run_git(Some(&self.work_dir), args)
+    }
+
+    /// 检查远端仓库可访问
+    pub fn test_connection(&self) -> Result<(), AppError> {
+        run_git(None, &["ls-remote", "--heads", "--", &self.repo_url]).map(|_| ())
+    }
+
+    fn remote_has_branch(&self) -> Result<bool, AppError> {
+        Ok(self.remote_head()?.is_some())
+    }
+
+    /// 远端分支当前的最新提交；分支不存在时返回 None
+    pub fn remote_head(&self) -> Result<Option<String>, AppError> {
+        let refspec = format!("refs/heads/{}", self.branch);
+        let output = self.git(&["ls-remote", "--heads", "origin", &refspec])?;
+        Ok(output.split_whitespace().next().map(str::to_string))
+    }
+
+    /// 准备工作副本并与远端分支对齐（丢弃本地未推送的改动）
+    pub fn checkout(&self) -> Result<(), AppError> {
+        if !self.work_dir.join(".git").exists() {
+            std::fs::create_dir_all(&self.work_dir).map_err(|e| AppError::io(&self.work_dir, e))?;
+            self.git(&["init", "--quiet"])?;
+            self.git(&["remote", "add", "origin", "--", &self.repo_url])?;
+        } else {
+            self.git(&["remote", "set-url", "origin", "--", &self.repo_url])?;
+        }
+
+        if self.remote_has_branch()? {
+            self.git(&["fetch", "--quiet", "--depth", "1", "origin", &self.branch])?;
+            self.git(&[
+                "checkout",
+                "--quiet",
+                "-f",
+                "-B",
+                &self.branch,
+                "FETCH_HEAD",
+            ])?;
+            self.git(&["clean", "-fdq"])?;
+        } else {
+            // 空仓库或新分支：在未诞生的分支上开始
+            let head = format!("refs/heads/{}", self.branch);
+            self.git(&["symbolic-ref", "HEAD", &head])?;
+        }
+        Ok(())
+    }
+
+    fn file_path(&self, segments: &[String]) -> Result<PathBuf, AppError> {
+        let mut path = self.work_dir.clone();
+        for segment in segments {
+            if segment.is_empty() || segment == "." || segment == ".." || segment == ".git" {
+                return Err(AppError::InvalidInput(format!(
+                    "git 同步路径包含非法分段: {segment}"
+                )));
+            }
+            path.push(segment);
+        }
+        Ok(path)
+    }
+
+    pub fn write_file(&self, segments: &[String], bytes: &[u8]) -> Result<(), AppError> {
+        let path = self.file_path(segments)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        std::fs::write(&path, bytes).map_err(|e| AppError::io(&path, e))
+    }
+
+    /// 读取文件，附带当前提交作为 etag；文件不存在时返回 None
+    pub fn read_file(
+        &self,
+        segments: &[String],
+        max_bytes: Option<u64>,
+    ) -> Result<Option<FileWithEtag>, AppError> {
+        let path = self.file_path(segments)?;
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AppError::io(&path, e)),
+        };
+        if let Some(limit) = max_bytes {
+            if metadata.len() > limit {
+                return Err(AppError::Message(format!(
+                    "git 同步文件超过大小限制 ({limit} bytes): {}",
+                    path.display()
+                )));
+            }
+        }
+        let bytes = std::fs::read(&path).map_err(|e| AppError::io(&path, e))?;
+        Ok(Some((bytes, self.head_commit()?)))
+    }
+
+    pub fn file_etag(&self, segments: &[String]) -> Result<Option<String>, AppError> {
+        if !self.file_path(segments)?.exists() {
+            return Ok(None);
+        }
+        self.head_commit()
+    }
+
+    pub fn remove_dir(&self, segments: &[String]) -> Result<bool, AppError> {
+        let path = self.file_path(segments)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_dir_all(&path).map_err(|e| AppError::io(&path, e))?;
+        Ok(true)
+    }
+
+    /// 工作副本当前的提交；未诞生的分支返回 None
+    pub fn head_commit(&self) -> Result<Option<String>, AppError> {
+        match self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]) {
+            Ok(commit) => Ok(Some(commit.trim().to_string())),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// 提交全部改动并推送到远端分支；没有改动时不提交
+    pub fn commit_and_push(&self, message: &str) -> Result<(), AppError> {
+        self.git(&["add", "-A"])?;
+        if self.git(&["status", "--porcelain"])?.trim().is_empty() {
+            return Ok(());
+        }
+
+        let mut args = Vec::new();
+        let has_identity = self
+            .git(&["config", "user.email"])
+            .map(|email| !email.trim().is_empty())
+            .unwrap_or(false);
+        let name_override = format!("user.name={FALLBACK_AUTHOR_NAME}");
+        let email_override = format!("user.email={FALLBACK_AUTHOR_EMAIL}");
+        if !has_identity {
+            args.extend(["-c", name_override.as_str(), "-c", email_override.as_str()]);
+        }
+        args.extend(["commit", "--quiet", "-m", message]);
+        self.git(&args)?;
+
+        let refspec = format!("HEAD:refs/heads/{}", self.branch);
+        self.git(&["push", "--quiet", "origin", &refspec])
+            .map_err(|e| {
+                AppError::localized(
+                    "git.sync.push_failed",
+                    format!("推送到 git 仓库失败（远端可能已被其他设备更新，请先下载）: {e}"),
+                    format!(
+                        "Failed to push to the git repository (another device may have pushed; download first): {e}"
+                    ),
+                )
+            })?;
+        Ok(())
+    }
+}
+
+fn run_git(cwd: Option<&Path>, args: &[&str]) -> Result<String, AppError> {
+    let mut command = Command::new("git");
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| {
+            AppError::localized(
+                "git.sync.git_missing",
+                format!("无法执行 git 命令，请确认已安装 git: {e}"),
+                format!("Failed to run git; make sure git is installed: {e}"),
+            )
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let subcommand = args
+            .iter()
+            .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+            .copied()
+            .unwrap_or("git");
+        return Err(AppError::Message(format!(
+            "git {subcommand} 失败: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_available() -> bool {
+        run_git(None, &["--version"]).is_ok()
+    }
+
+    fn segments(path: &str) -> Vec<String> {
+        path.split('/').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn files_round_trip_between_two_work_copies() {
+        if !git_available() {
+            eprintln!("git not installed; skipping");
+            return;
+        }
+        let temp = tempfile::tempdir().expect("tempdir");
+        let remote = temp.path().join("remote.git");
+        run_git(
+            None,
+            &[
+                "init",
+                "--quiet",
+                "--bare",
+                remote.to_str().expect("utf-8 path"),
+            ],
+        )
+        .expect("init bare repo");
+
+        let settings = GitSyncSettings {
+            enabled: true,
+            repo_url: remote.display().to_string(),
+            ..GitSyncSettings::default()
+        };
+        let first = GitRepo::with_work_dir(&settings, temp.path().join("first"));
+        let second = GitRepo::with_work_dir(&settings, temp.path().join("second"));
+
+        first.checkout().expect("checkout empty remote");
+        assert!(first
+            .read_file(&segments("root/manifest.json"), None)
+            .expect("read missing")
+            .is_none());
+        first
+            .write_file(&segments("root/manifest.json"), b"{\"v\":1}")
+            .expect("write");
+        first.commit_and_push("sync: first").expect("push");
+
+        second.checkout().expect("checkout pushed branch");
+        let (bytes, etag) = second
+            .read_file(&segments("root/manifest.json"), Some(1024))
+            .expect("read")
+            .expect("file exists");
+        assert_eq!(bytes, b"{\"v\":1}");
+        assert!(etag.is_some());
+
+        second
+            .write_file(&segments("root/manifest.json"), b"{\"v\":2}")
+            .expect("write update");
+        second.commit_and_push("sync: second").expect("push update");
+
+        first.checkout().expect("re-align first copy");
+        let (bytes, _) = first
+            .read_file(&segments("root/manifest.json"), None)
+            .expect("read update")
+            .expect("file exists");
+        assert_eq!(bytes, b"{\"v\":2}");
+    }
+
+    #[test]
+    fn file_path_rejects_traversal() {
+        let settings = GitSyncSettings::default();
+        let repo = GitRepo::with_work_dir(&settings, PathBuf::from("/tmp/cc-switch-git"));
+        assert!(repo.file_path(&segments("root/../escape")).is_err());
+        assert!(repo.file_path(&segments(".git/config")).is_err());
+    }
+
+    #[test]
+    fn repo_url_is_never_parsed_as_a_git_option() {
+        if !git_available() {
+            eprintln!("git not installed; skipping");
+            return;
+        }
+        let temp = tempfile::tempdir().expect("tempdir");
+        let marker = temp.path().join("injected");
+        let settings = GitSyncSettings {
+            enabled: true,
+            repo_url: format!("--upload-pack=touch {}", marker.display()),
+            ..GitSyncSettings::default()
+        };
+        let repo = GitRepo::with_work_dir(&settings, temp.path().join("work"));
+
+        assert!(repo.test_connection().is_err());
+        assert!(repo.checkout().is_err());
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,803 @@
+//! MCP 服务器健康探测
+//!
+//! 按存储的连接定义真正启动 stdio 服务器（或连接 http/sse 服务器），完成
+//! `initialize` 握手并列出 tools/resources/prompts，记录耗时与 stderr 输出。
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::{Client, Url};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 20;
+const MAX_TIMEOUT_SECS: u64 = 120;
+const MIN_TIMEOUT_SECS: u64 = 2;
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const MAX_LIST_PAGES: usize = 20;
+const STDERR_TAIL_LINES: usize = 20;
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+const EXIT_STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 单个 MCP 服务器的探测结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpProbeResult {
+    /// stdio / http / sse
+    pub transport: String,
+    pub success: bool,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub tools: Vec<String>,
+    pub resources: Vec<String>,
+    pub prompts: Vec<String>,
+    /// 从启动到 initialize 响应的耗时（毫秒）
+    pub handshake_ms: Option<u128>,
+    pub total_ms: u128,
+    pub error: Option<String>,
+    /// stdio 服务器 stderr 的最后若干行
+    pub stderr: Vec<String>,
+}
+
+/// MCP 服务器探测相关业务
+pub struct McpProbeService;
+
+impl McpProbeService {
+    /// 按连接定义探测一个 MCP 服务器；失败信息写入结果而不是返回错误
+    pub async fn probe(spec: &Value, timeout_secs: Option<u64>) -> McpProbeResult {
+        let timeout = Self::sanitize_timeout(timeout_secs);
+        let started = Instant::now();
+        let stderr = Arc::new(Mutex::new(VecDeque::new()));
+        let mut stderr_reader = None;
+        let mut result = McpProbeResult {
+            transport: transport_kind(spec).to_string(),
+            ..Default::default()
+        };
+
+        let outcome = tokio::time::timeout(
+            timeout,
+            run_probe(spec, started, &mut result, &stderr, &mut stderr_reader),
+        )
+        .await;
+        result.error = match outcome {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(err),
+            Err(_) => Some(format!("timed out after {}s", timeout.as_secs())),
+        };
+        result.success = result.error.is_none();
+        result.total_ms = started.elapsed().as_millis();
+        // 子进程已随传输层结束，稍等 stderr 读取任务收尾，避免丢掉最后几行
+        if let Some(reader) = stderr_reader {
+            let _ = tokio::time::timeout(STDERR_DRAIN_TIMEOUT, reader).await;
+        }
+        result.stderr = stderr
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default();
+        result
+    }
+
+    fn sanitize_timeout(timeout_secs: Option<u64>) -> Duration {
+        let secs = timeout_secs
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+        Duration::from_secs(secs)
+    }
+}
+
+fn transport_kind(spec: &Value) -> &str {
+    spec.get("type").and_then(Value::as_str).unwrap_or("stdio")
+}
+
+async fn run_probe(
+    spec: &Value,
+    started: Instant,
+    result: &mut McpProbeResult,
+    stderr: &Arc<Mutex<VecDeque<String>>>,
+    stderr_reader: &mut Option<JoinHandle<()>>,
+) -> Result<(), String> {
+    let transport = match transport_kind(spec) {
+        "stdio" => Transport::Stdio(StdioTransport::spawn(spec, stderr.clone(), stderr_reader)?),
+        "http" => Transport::Http(HttpTransport::new(spec)?),
+        "sse" => Transport::Sse(SseTransport::connect(spec).await?),
+        other => return Err(format!("unsupported transport type: {other}")),
+    };
+    let mut session = Session {
+        transport,
+        next_id: 1,
+    };
+
+    let init_params = json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "cc-switch", "version": env!("CARGO_PKG_VERSION") },
+    });
+    let init = match session.request("initialize", init_params).await {
+        Ok(init) => init,
+        Err(err) => {
+            return Err(session
+                .with_exit_status(format!("initialize failed: {err}"))
+                .await)
+        }
+    };
+    result.handshake_ms = Some(started.elapsed().as_millis());
+    result.protocol_version = init
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    result.server_name = init
+        .pointer("/serverInfo/name")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    result.server_version = init
+        .pointer("/serverInfo/version")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    session.notify("notifications/initialized").await?;
+
+    let capabilities = init.get("capabilities").cloned().unwrap_or(Value::Null);
+    if capabilities.get("tools").is_some() {
+        result.tools = session.list("tools/list", "tools", &["name"]).await?;
+    }
+    if capabilities.get("resources").is_some() {
+        result.resources = session
+            .list("resources/list", "resources", &["name", "uri"])
+            .await?;
+    }
+    if capabilities.get("prompts").is_some() {
+        result.prompts = session.list("prompts/list", "prompts", &["name"]).await?;
+    }
+    Ok(())
+}
+
+/// 一次探测会话：负责分配请求 ID 并处理分页
+struct Session {
+    transport: Transport,
+    next_id: u64,
+}
+
+impl Session {
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let response = match &mut self.transport {
+            Transport::Stdio(transport) => transport.request(id, &message).await?,
+            Transport::Http(transport) => transport.request(id, &message).await?,
+            Transport::Sse(transport) => transport.request(id, &message).await?,
+        };
+        if let Some(error) = response.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            return Err(format!("{method}: {message}"));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        match &mut self.transport {
+            Transport::Stdio(transport) => transport.send(&message).await,
+            Transport::Http(transport) => transport.notify(&message).await,
+            Transport::Sse(transport) => transport.post(&message).await,
+        }
+    }
+
+    /// 调用 `*/list` 并跟随 `nextCursor` 分页，返回条目名称
+    async fn list(
+        &mut self,
+        method: &str,
+        key: &str,
+        name_fields: &[&str],
+    ) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request(method, params).await?;
+            for item in page
+                .get(key)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(name) = name_fields
+                    .iter()
+                    .find_map(|field| item.get(*field).and_then(Value::as_str))
+                {
+                    names.push(name.to_string());
+                }
+            }
+            cursor = page
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    /// stdio 服务器提前退出时在错误信息后附上退出状态
+    async fn with_exit_status(&mut self, message: String) -> String {
+        let Transport::Stdio(transport) = &mut self.transport else {
+            return message;
+        };
+        match tokio::time::timeout(EXIT_STATUS_TIMEOUT, transport.child.wait()).await {
+            Ok(Ok(status)) => format!("{message} (process exited with {status})"),
+            _ => message,
+        }
+    }
+}
+
+enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+    Sse(SseTransport),
+}
+
+/// 逐行收发 JSON-RPC 消息的子进程
+struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl StdioTransport {
+    fn spawn(
+        spec: &Value,
+        stderr_tail: Arc<Mutex<VecDeque<String>>>,
+        stderr_reader: &mut Option<JoinHandle<()>>,
+    ) -> Result<Self, String> {
+        let command = spec
+            .get("command")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+            .ok_or_else(|| "missing `command`".to_string())?;
+        // 先在 PATH 中解析，Windows 上才能找到 npx.cmd 这类脚本
+        let program = which::which(command).unwrap_or_else(|_| PathBuf::from(command));
+
+        let mut cmd = tokio::process::Command::new(program);
+        for arg in spec
+            .get("args")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            match arg {
+                Value::String(arg) => cmd.arg(arg),
+                other => cmd.arg(other.to_string()),
+            };
+        }
+        for (key, value) in spec
+            .get("env")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            match value {
+                Value::String(value) => cmd.env(key, value),
+                other => cmd.env(key, other.to_string()),
+            };
+        }
+        if let Some(cwd) = spec.get("cwd").and_then(Value::as_str) {
+            cmd.current_dir(cwd);
+        }
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|err| format!("failed to start `{command}`: {err}"))?;
+        let stdin = child.stdin.take().ok_or("failed to open stdin")?;
+        let stdout = child.stdout.take().ok_or("failed to open stdout")?;
+        if let Some(stderr) = child.stderr.take() {
+            *stderr_reader = Some(tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Ok(mut tail) = stderr_tail.lock() {
+                        if tail.len() == STDERR_TAIL_LINES {
+                            tail.pop_front();
+                        }
+                        tail.push_back(line);
+                    }
+                }
+            }));
+        }
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    async fn send(&mut self, message: &Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|err| format!("failed to write to server: {err}"))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|err| format!("failed to write to server: {err}"))
+    }
+
+    async fn request(&mut self, id: u64, message: &Value) -> Result<Value, String> {
+        self.send(message).await?;
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await
+                .map_err(|err| format!("failed to read from server: {err}"))?
+                .ok_or("server closed stdout before responding")?;
+            // 非 JSON 行（误写到 stdout 的日志）直接跳过
+            let Ok(incoming) = serde_json::from_str::<Value>(line.trim()) else {
+                continue;
+            };
+            if let Some(response) = self.handle_incoming(id, incoming).await? {
+                return Ok(response);
+            }
+        }
+    }
+
+    /// 匹配响应；对服务器发来的请求回复 method not found，通知直接忽略
+    async fn handle_incoming(&mut self, id: u64, incoming: Value) -> Result<Option<Value>, String> {
+        if is_response_to(&incoming, id) {
+            return Ok(Some(incoming));
+        }
+        if let (Some(request_id), Some(_)) = (incoming.get("id"), incoming.get("method")) {
+            let reply = json!({
+                "jsonrpc": "2.0",
+                "id": request_id,
+                "error": { "code": -32601, "message": "Method not found" },
+            });
+            self.send(&reply).await?;
+        }
+        Ok(None)
+    }
+}
+
+/// Streamable HTTP 传输：每个请求一次 POST，响应可能是 JSON 或 SSE
+struct HttpTransport {
+    client: Client,
+    url: Url,
+    headers: HeaderMap,
+    session_id: Option<String>,
+}
+
+impl HttpTransport {
+    fn new(spec: &Value) -> Result<Self, String> {
+        Ok(Self {
+            client: build_client()?,
+            url: spec_url(spec)?,
+            headers: spec_headers(spec)?,
+            session_id: None,
+        })
+    }
+
+    async fn post(&mut self, message: &Value) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .headers(self.headers.clone())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        let response = request.send().await.map_err(describe_reqwest_error)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {status}: {}", body.trim()));
+        }
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            self.session_id = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+
+    async fn request(&mut self, id: u64, message: &Value) -> Result<Value, String> {
+        let response = self.post(message).await?;
+        let is_event_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+
+        if !is_event_stream {
+            let body: Value = response
+                .json()
+                .await
+                .map_err(|err| format!("invalid JSON response: {err}"))?;
+            let messages = match body {
+                Value::Array(items) => items,
+                other => vec![other],
+            };
+            return messages
+                .into_iter()
+                .find(|message| is_response_to(message, id))
+                .ok_or_else(|| "response did not answer the request".to_string());
+        }
+
+        let mut parser = SseParser::default();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(describe_reqwest_error)?;
+            for event in parser.push(&String::from_utf8_lossy(&chunk)) {
+                if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                    if is_response_to(&message, id) {
+                        return Ok(message);
+                    }
+                }
+            }
+        }
+        Err("event stream closed before responding".to_string())
+    }
+
+    async fn notify(&mut self, message: &Value) -> Result<(), String> {
+        self.post(message).await.map(|_| ())
+    }
+}
+
+/// 旧版 HTTP+SSE 传输：GET 建立事件流，从 `endpoint` 事件得到 POST 地址
+struct SseTransport {
+    client: Client,
+    headers: HeaderMap,
+    endpoint: Url,
+    events: mpsc::UnboundedReceiver<SseEvent>,
+    reader: JoinHandle<()>,
+}
+
+impl SseTransport {
+    async fn connect(spec: &Value) -> Result<Self, String> {
+        let client = build_client()?;
+        let url = spec_url(spec)?;
+        let headers = spec_headers(spec)?;
+        let response = client
+            .get(url.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(describe_reqwest_error)?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let reader = tokio::spawn(async move {
+            let mut parser = SseParser::default();
+            let mut stream = response.bytes_stream();
+            while let Some(Ok(chunk)) = stream.next().await {
+                for event in parser.push(&String::from_utf8_lossy(&chunk)) {
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let endpoint = loop {
+            let event = events
+                .recv()
+                .await
+                .ok_or("event stream closed before sending the endpoint")?;
+            if event.event == "endpoint" {
+                break url
+                    .join(event.data.trim())
+                    .map_err(|err| format!("invalid endpoint `{}`: {err}", event.data))?;
+            }
+        };
+
+        Ok(Self {
+            client,
+            headers,
+            endpoint,
+            events,
+            reader,
+        })
+    }
+
+    async fn post(&mut self, message: &Value) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .headers(self.headers.clone())
+            .json(message)
+            .send()
+            .await
+            .map_err(describe_reqwest_error)?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn request(&mut self, id: u64, message: &Value) -> Result<Value, String> {
+        self.post(message).await?;
+        while let Some(event) = self.events.recv().await {
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                if is_response_to(&message, id) {
+                    return Ok(message);
+                }
+            }
+        }
+        Err("event stream closed before responding".to_string())
+    }
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// 增量解析 `text/event-stream`，按空行切分事件
+#[derive(Default)]
+struct SseParser {
+    buffer: String,
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &str) -> Vec<SseEvent> {
+        self.buffer.push_str(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.find('\n') {
+            let line = self.buffer[..pos].trim_end_matches('\r').to_string();
+            self.buffer.drain(..=pos);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    let event = if self.event.is_empty() {
+                        "message".to_string()
+                    } else {
+                        std::mem::take(&mut self.event)
+                    };
+                    events.push(SseEvent {
+                        event,
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                self.event.clear();
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("event:") {
+                self.event = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+        events
+    }
+}
+
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message.get("id").and_then(Value::as_u64) == Some(id)
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+fn build_client() -> Result<Client, String> {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|err| format!("failed to create HTTP client: {err}"))
+}
+
+fn spec_url(spec: &Value) -> Result<Url, String> {
+    let raw = spec
+        .get("url")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| "missing `url`".to_string())?;
+    Url::parse(raw).map_err(|err| format!("invalid url `{raw}`: {err}"))
+}
+
+fn spec_headers(spec: &Value) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    for (key, value) in spec
+        .get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        let name = HeaderName::from_bytes(key.as_bytes())
+            .map_err(|err| format!("invalid header `{key}`: {err}"))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|err| format!("invalid value for header `{key}`: {err}"))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+fn describe_reqwest_error(err: reqwest::Error) -> String {
+    if err.is_connect() {
+        format!("connection failed: {err}")
+    } else {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_parser_handles_split_chunks_and_named_events() {
+        let mut parser = SseParser::default();
+        assert!(parser.push("event: endpoint\r\ndata: /messages").is_empty());
+        let events = parser.push("?session=1\r\n\r\ndata: {\"id\":1}\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".to_string(),
+                    data: "/messages?session=1".to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "{\"id\":1}".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_command_fails_without_spawning() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let result = runtime.block_on(McpProbeService::probe(&json!({"type": "stdio"}), None));
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("missing `command`"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_performs_handshake_and_lists_tools() {
+        let script = r#"
+echo "fake server starting" >&2
+echo "not json"
+while IFS= read -r line; do
+  case "$line" in
+    *'"initialize"'*) echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"1.2.3"}}}' ;;
+    *'"tools/list"'*) echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo"},{"name":"sum"}]}}' ;;
+  esac
+done
+"#;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let result = runtime.block_on(McpProbeService::probe(
+            &json!({"command": "sh", "args": ["-c", script]}),
+            Some(10),
+        ));
+
+        assert!(result.success, "probe failed: {:?}", result.error);
+        assert_eq!(result.server_name.as_deref(), Some("fake"));
+        assert_eq!(result.server_version.as_deref(), Some("1.2.3"));
+        assert_eq!(result.tools, vec!["echo", "sum"]);
+        assert!(result.resources.is_empty());
+        assert!(result.handshake_ms.is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_reports_exit_status_and_stderr() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let result = runtime.block_on(McpProbeService::probe(
+            &json!({"command": "sh", "args": ["-c", "echo 'Cannot find module' >&2; exit 3"]}),
+            Some(10),
+        ));
+
+        assert!(!result.success);
+        let error = result.error.expect("error");
+        assert!(error.starts_with("initialize failed"), "{error}");
+        assert!(error.contains("exit status: 3"), "{error}");
+        assert_eq!(result.stderr, vec!["Cannot find module"]);
+    }
+
+    #[test]
+    fn http_probe_speaks_streamable_http() {
+        use axum::{http::HeaderMap as AxumHeaders, routing::post, Json, Router};
+
+        async fn handle(headers: AxumHeaders, Json(body): Json<Value>) -> axum::response::Response {
+            use axum::response::IntoResponse;
+            let method = body.get("method").and_then(Value::as_str).unwrap_or("");
+            let id = body.get("id").cloned();
+            match method {
+                "initialize" => (
+                    [(SESSION_ID_HEADER, "s-1")],
+                    Json(json!({"jsonrpc": "2.0", "id": id, "result": {
+                        "protocolVersion": PROTOCOL_VERSION,
+                        "capabilities": {"prompts": {}},
+                        "serverInfo": {"name": "remote", "version": "0.1.0"}
+                    }})),
+                )
+                    .into_response(),
+                "prompts/list" => {
+                    assert_eq!(
+                        headers
+                            .get(SESSION_ID_HEADER)
+                            .and_then(|value| value.to_str().ok()),
+                        Some("s-1")
+                    );
+                    Json(json!({"jsonrpc": "2.0", "id": id, "result": {
+                        "prompts": [{"name": "review"}]
+                    }}))
+                    .into_response()
+                }
+                _ => axum::http::StatusCode::ACCEPTED.into_response(),
+            }
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let result = runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("bind");
+            let addr = listener.local_addr().expect("addr");
+            tokio::spawn(async move {
+                let app = Router::new().route("/mcp", post(handle));
+                let _ = axum::serve(listener, app).await;
+            });
+            McpProbeService::probe(
+                &json!({"type": "http", "url": format!("http://{addr}/mcp")}),
+                Some(10),
+            )
+            .await
+        });
+
+        assert!(result.success, "probe failed: {:?}", result.error);
+        assert_eq!(result.transport, "http");
+        assert_eq!(result.server_name.as_deref(), Some("remote"));
+        assert_eq!(result.prompts, vec!["review"]);
+    }
+}
@@ -6,19 +6,26 @@ pub mod codex_oauth_models;
 pub mod coding_plan;
 pub mod config;
 pub mod copilot_auth;
+pub mod declarative;
 pub mod env_checker;
 #[allow(dead_code)]
 pub mod env_manager;
+pub mod git_repo;
 pub mod local_env_check;
 pub mod mcp;
+pub mod mcp_probe;
 pub mod model_fetch;
+pub mod project;
 pub mod prompt;
 pub mod provider;
 pub mod proxy;
+pub mod s3;
+pub mod session_search;
 pub mod session_usage;
 pub mod session_usage_codex;
 pub mod session_usage_gemini;
 pub mod session_usage_opencode;
+pub mod shared_prompt;
 pub mod skill;
 pub mod speedtest;
 pub mod sql_helpers;
@@ -34,18 +41,25 @@ pub use auth::{AuthService, ManagedAuthAccount, ManagedAuthDeviceCodeResponse, M
 pub use codex_oauth::CodexOAuthService;
 pub use config::ConfigService;
 pub use copilot_auth::CopilotAuthService;
+pub use declarative::{DeclarativeConfig, DeclarativeService};
 pub use mcp::McpService;
+pub use mcp_probe::McpProbeService;
 pub use model_fetch::FetchedModel;
+pub use project::{ProjectProfile, ProjectService};
 pub use prompt::PromptService;
 pub use provider::ProviderService;
 pub use proxy::ProxyService;
+pub use shared_prompt::SharedPromptService;
 pub use skill::{ImportSkillSelection, SkillService};
 pub use speedtest::{EndpointLatency, SpeedtestService};
 pub use stream_check::{HealthStatus, StreamCheckConfig, StreamCheckResult, StreamCheckService};
 pub use subscription::{CredentialStatus, ExtraUsage, QuotaTier, SubscriptionQuota};
 #[allow(unused_imports)]
 pub use usage_stats::{
-    DailyStats, LogFilters, ModelStats, PaginatedLogs, ProviderLimitStatus, ProviderStats,
-    RequestLogDetail, UsageSummary, UsageSummaryByApp,
+    ClientTokenStats, DailyStats, LogFilters, ModelStats, PaginatedLogs, ProviderLimitStatus,
+    ProviderStats, RequestLogDetail, UsageScope, UsageSummary, UsageSummaryByApp,
+};
+pub use webdav_sync::{
+    ConflictResolutions, MergeOutcome, MergeSide, RecordConflict, SyncDecision, SyncService,
+    WebDavSyncService, WebDavSyncSummary,
 };
-pub use webdav_sync::{SyncDecision, WebDavSyncService, WebDavSyncSummary};
@@ -0,0 +1,571 @@
+//! 项目级配置（`.cc-switch.toml`）
+//!
+//! 项目配置为某个仓库选择供应商、启用 MCP 服务器子集并激活提示词，写入各应用
+//! 支持的项目级位置，不改动全局 live 配置。cc-switch 只管理数据库中已知的条目：
+//! 用户自行加入 `.mcp.json` 的服务器、settings.local.json 中的其他字段都会保留。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::config::{write_json_file, write_text_file};
+use crate::error::AppError;
+use crate::prompt_block::{extract_block, replace_block};
+use crate::services::prompt::{project_name_for, PromptContext};
+use crate::services::{McpService, PromptService, ProviderService};
+use crate::store::AppState;
+
+/// 项目配置文件名
+pub const PROJECT_PROFILE_FILE: &str = ".cc-switch.toml";
+
+/// 单个应用的项目配置；省略的字段不会改动对应文件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppProfile {
+    /// 供应商 ID（仅 Claude 支持项目级供应商）
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// 启用的 MCP 服务器 ID；空列表表示移除 cc-switch 管理的全部服务器
+    #[serde(default)]
+    pub mcp_servers: Option<Vec<String>>,
+    /// 激活的提示词 ID
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// 项目配置：按应用分节，例如 `[claude]`、`[codex]`
+#[derive(Debug, Clone)]
+pub struct ProjectProfile {
+    /// 项目根目录（配置文件所在目录）
+    pub root: PathBuf,
+    pub apps: IndexMap<AppType, AppProfile>,
+}
+
+/// 项目级写入目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectTarget {
+    Provider,
+    McpServers,
+    Prompt,
+}
+
+impl ProjectTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Provider => "provider",
+            Self::McpServers => "mcp",
+            Self::Prompt => "prompt",
+        }
+    }
+}
+
+/// 一次待写入的项目文件
+#[derive(Debug, Clone)]
+pub struct ProjectChange {
+    pub target: ProjectTarget,
+    pub apps: Vec<AppType>,
+    pub path: PathBuf,
+    /// 写入内容摘要：供应商 ID、MCP 服务器 ID 或提示词 ID
+    pub items: Vec<String>,
+    payload: Payload,
+}
+
+#[derive(Debug, Clone)]
+enum Payload {
+    Json(Value),
+    McpServers(HashMap<String, Value>),
+    Text(String),
+}
+
+fn settings_path(app: &AppType) -> Option<&'static str> {
+    match app {
+        AppType::Claude => Some(".claude/settings.local.json"),
+        _ => None,
+    }
+}
+
+fn mcp_path(app: &AppType) -> Option<&'static str> {
+    match app {
+        AppType::Claude => Some(".mcp.json"),
+        AppType::Gemini => Some(".gemini/settings.json"),
+        _ => None,
+    }
+}
+
+fn prompt_path(app: &AppType) -> Option<&'static str> {
+    match app {
+        AppType::Claude => Some("CLAUDE.md"),
+        AppType::Gemini => Some("GEMINI.md"),
+        AppType::Codex | AppType::OpenCode | AppType::Hermes => Some("AGENTS.md"),
+        AppType::OpenClaw => None,
+    }
+}
+
+impl ProjectProfile {
+    /// 从 `start` 开始逐级向上查找 `.cc-switch.toml`
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_PROFILE_FILE))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// 读取并校验项目配置
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let root = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        Self::parse(&raw, root)
+    }
+
+    pub fn parse(raw: &str, root: &Path) -> Result<Self, AppError> {
+        let apps: IndexMap<AppType, AppProfile> = toml::from_str(raw).map_err(|e| {
+            AppError::localized(
+                "project.parse_failed",
+                format!("解析项目配置失败: {e}"),
+                format!("Failed to parse the project profile: {e}"),
+            )
+        })?;
+        let profile = Self {
+            root: root.to_path_buf(),
+            apps,
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        for (app, section) in &self.apps {
+            let unsupported = if section.provider.is_some() && settings_path(app).is_none() {
+                Some("provider")
+            } else if section.mcp_servers.is_some() && mcp_path(app).is_none() {
+                Some("mcp_servers")
+            } else if section.prompt.is_some() && prompt_path(app).is_none() {
+                Some("prompt")
+            } else {
+                None
+            };
+            if let Some(field) = unsupported {
+                return Err(AppError::localized(
+                    "project.unsupported",
+                    format!("{} 不支持项目级 {field}", app.as_str()),
+                    format!("{} does not support a project-scoped {field}", app.as_str()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 只保留指定应用的配置
+    pub fn only(mut self, app: &AppType) -> Result<Self, AppError> {
+        let Some(section) = self.apps.shift_remove(app) else {
+            return Err(AppError::localized(
+                "project.app_missing",
+                format!("项目配置中没有 [{}] 节", app.as_str()),
+                format!("The project profile has no [{}] section", app.as_str()),
+            ));
+        };
+        self.apps = IndexMap::from([(app.clone(), section)]);
+        Ok(self)
+    }
+}
+
+pub struct ProjectService;
+
+impl ProjectService {
+    /// 计算项目配置需要写入的文件；`force` 允许覆盖非 cc-switch 写入的提示词文件，
+    /// 以及把供应商密钥写入未被 git 忽略的 settings.local.json
+    pub fn plan(
+        state: &AppState,
+        profile: &ProjectProfile,
+        force: bool,
+    ) -> Result<Vec<ProjectChange>, AppError> {
+        let mut changes = Vec::new();
+
+        for (app, section) in &profile.apps {
+            if let (Some(provider_id), Some(rel)) =
+                (section.provider.as_deref(), settings_path(app))
+            {
+                let path = profile.root.join(rel);
+                if !force && exposed_to_git(&profile.root, &path) {
+                    return Err(AppError::localized(
+                        "project.settings_not_ignored",
+                        format!(
+                            "{} 未被 git 忽略，写入的 API Key 可能被提交：请将其加入 .gitignore，或使用 --force 写入",
+                            path.display()
+                        ),
+                        format!(
+                            "{} is not ignored by git, so the provider API key could be committed; add it to .gitignore or rerun with --force",
+                            path.display()
+                        ),
+                    ));
+                }
+                changes.push(Self::plan_claude_provider(state, provider_id, path)?);
+            }
+        }
+
+        for (app, section) in &profile.apps {
+            if let (Some(ids), Some(rel)) = (&section.mcp_servers, mcp_path(app)) {
+                changes.push(Self::plan_mcp(state, app, ids, profile.root.join(rel))?);
+            }
+        }
+
+        changes.extend(Self::plan_prompts(state, profile, force)?);
+        Ok(changes)
+    }
+
+    /// 按计划写入文件
+    pub fn apply(changes: &[ProjectChange]) -> Result<(), AppError> {
+        for change in changes {
+            match &change.payload {
+                Payload::Json(value) => write_json_file(&change.path, value)?,
+                Payload::Text(content) => write_text_file(&change.path, content)?,
+                Payload::McpServers(servers) => match change.apps.first() {
+                    Some(AppType::Gemini) => {
+                        crate::gemini_mcp::set_mcp_servers_map_at(&change.path, servers)?
+                    }
+                    _ => crate::claude_mcp::set_mcp_servers_map_at(&change.path, servers)?,
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// 供应商写入 `.claude/settings.local.json`：先移除其他供应商带来的 env 变量，
+    /// 再合并所选供应商的配置，其余字段保持不变
+    fn plan_claude_provider(
+        state: &AppState,
+        provider_id: &str,
+        path: PathBuf,
+    ) -> Result<ProjectChange, AppError> {
+        let provider = ProviderService::get_provider(state, AppType::Claude, provider_id)?;
+        let settings = ProviderService::claude_project_settings(state, &provider)?;
+
+        let mut root = read_json_object(&path)?;
+        let provider_env_keys = ProviderService::list(state, AppType::Claude)?
+            .values()
+            .filter_map(|p| p.settings_config.get("env").and_then(Value::as_object))
+            .flat_map(|env| env.keys().cloned())
+            .collect::<HashSet<_>>();
+        if let Some(env) = root.get_mut("env").and_then(Value::as_object_mut) {
+            env.retain(|key, _| !provider_env_keys.contains(key));
+        }
+
+        if let Some(incoming) = settings.as_object() {
+            for (key, value) in incoming {
+                match (key.as_str(), value.as_object()) {
+                    ("env", Some(incoming_env)) => {
+                        let env = root
+                            .entry("env")
+                            .or_insert_with(|| Value::Object(Map::new()));
+                        if let Some(env) = env.as_object_mut() {
+                            env.extend(incoming_env.clone());
+                        } else {
+                            *env = value.clone();
+                        }
+                    }
+                    _ => {
+                        root.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        if root
+            .get("env")
+            .and_then(Value::as_object)
+            .is_some_and(Map::is_empty)
+        {
+            root.remove("env");
+        }
+
+        Ok(ProjectChange {
+            target: ProjectTarget::Provider,
+            apps: vec![AppType::Claude],
+            path,
+            items: vec![provider_id.to_string()],
+            payload: Payload::Json(Value::Object(root)),
+        })
+    }
+
+    /// MCP：移除数据库中已知但未选中的服务器，写入选中的服务器，保留用户自行添加的条目
+    fn plan_mcp(
+        state: &AppState,
+        app: &AppType,
+        ids: &[String],
+        path: PathBuf,
+    ) -> Result<ProjectChange, AppError> {
+        let known = McpService::get_all_servers(state)?;
+        let missing = ids
+            .iter()
+            .filter(|id| !known.contains_key(id.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let names = missing.join(", ");
+            return Err(AppError::localized(
+                "project.mcp_not_found",
+                format!("MCP 服务器不存在: {names}"),
+                format!("MCP servers not found: {names}"),
+            ));
+        }
+
+        let mut servers = match app {
+            AppType::Gemini => crate::gemini_mcp::read_mcp_servers_map_at(&path)?,
+            _ => crate::claude_mcp::read_mcp_servers_map_at(&path)?,
+        };
+        servers.retain(|id, _| !known.contains_key(id));
+        for id in ids {
+            servers.insert(id.clone(), known[id].server.clone());
+        }
+
+        Ok(ProjectChange {
+            target: ProjectTarget::McpServers,
+            apps: vec![app.clone()],
+            path,
+            items: ids.to_vec(),
+            payload: Payload::McpServers(servers),
+        })
+    }
+
+    /// 提示词写入项目根目录；多个应用共用同一文件（AGENTS.md）时内容必须一致
+    fn plan_prompts(
+        state: &AppState,
+        profile: &ProjectProfile,
+        force: bool,
+    ) -> Result<Vec<ProjectChange>, AppError> {
+        let mut changes: Vec<ProjectChange> = Vec::new();
+        let mut known_contents: Option<HashSet<String>> = None;
+
+        for (app, section) in &profile.apps {
+            let (Some(prompt_id), Some(rel)) = (section.prompt.as_deref(), prompt_path(app)) else {
+                continue;
+            };
+            if !PromptService::get_prompts(state, app.clone())?.contains_key(prompt_id) {
+                return Err(AppError::localized(
+                    "project.prompt_not_found",
+                    format!("提示词不存在: {}/{prompt_id}", app.as_str()),
+                    format!("Prompt not found: {}/{prompt_id}", app.as_str()),
+                ));
+            }
+            let context = Self::prompt_context(state, profile, app);
+            let content = PromptService::render(state, app.clone(), prompt_id, &context)?;
+            let path = profile.root.join(rel);
+            let file = if path.exists() {
+                Some(std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?)
+            } else {
+                None
+            };
+            // 托管区块模式下只比较并替换区块，区块外由用户编写的内容保持不变
+            let managed_block = crate::settings::prompt_managed_block();
+            let text = if managed_block {
+                replace_block(file.as_deref().unwrap_or_default(), &content)?
+            } else {
+                content.clone()
+            };
+
+            if let Some(existing) = changes.iter_mut().find(|change| change.path == path) {
+                if matches!(&existing.payload, Payload::Text(other) if *other != text) {
+                    return Err(AppError::localized(
+                        "project.prompt_shared_file",
+                        format!("{rel} 被多个应用共用，但渲染出的提示词不同"),
+                        format!(
+                            "{rel} is shared by several apps but their prompts render differently"
+                        ),
+                    ));
+                }
+                existing.apps.push(app.clone());
+                if !existing.items.iter().any(|item| item == prompt_id) {
+                    existing.items.push(prompt_id.to_string());
+                }
+                continue;
+            }
+
+            let owned = match (file, managed_block) {
+                (Some(file), true) => extract_block(&file)?,
+                (file, _) => file,
+            };
+            if let (false, Some(current)) = (force, owned) {
+                if current != content {
+                    let known = match &known_contents {
+                        Some(known) => known,
+                        None => known_contents.insert(Self::known_prompt_contents(state, profile)?),
+                    };
+                    if !known.contains(&current) {
+                        return Err(AppError::localized(
+                            "project.prompt_conflict",
+                            format!(
+                                "{} 包含非 cc-switch 写入的内容，使用 --force 覆盖",
+                                path.display()
+                            ),
+                            format!(
+                                "{} has content cc-switch did not write; rerun with --force to overwrite it",
+                                path.display()
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            changes.push(ProjectChange {
+                target: ProjectTarget::Prompt,
+                apps: vec![app.clone()],
+                path,
+                items: vec![prompt_id.to_string()],
+                payload: Payload::Text(text),
+            });
+        }
+
+        Ok(changes)
+    }
+
+    /// 项目中渲染提示词的上下文：项目目录名，以及项目配置选择的供应商（未选择时为当前供应商）
+    fn prompt_context(state: &AppState, profile: &ProjectProfile, app: &AppType) -> PromptContext {
+        let mut context = PromptContext::current(state, app);
+        context.project_name = project_name_for(&profile.root);
+        if let Some(provider_id) = profile
+            .apps
+            .get(app)
+            .and_then(|section| section.provider.as_deref())
+        {
+            let name = ProviderService::list(state, app.clone())
+                .ok()
+                .and_then(|providers| providers.get(provider_id).map(|p| p.name.clone()));
+            context.provider = Some(name.unwrap_or_else(|| provider_id.to_string()));
+        }
+        context
+    }
+
+    /// cc-switch 可能写入过的提示词内容：原文与在本项目中的渲染结果
+    fn known_prompt_contents(
+        state: &AppState,
+        profile: &ProjectProfile,
+    ) -> Result<HashSet<String>, AppError> {
+        let mut contents = HashSet::new();
+        for app in AppType::all() {
+            let context = Self::prompt_context(state, profile, &app);
+            for prompt in PromptService::get_prompts(state, app.clone())?.into_values() {
+                if let Ok(rendered) =
+                    PromptService::render(state, app.clone(), &prompt.id, &context)
+                {
+                    contents.insert(rendered);
+                }
+                contents.insert(prompt.content);
+            }
+        }
+        Ok(contents)
+    }
+}
+
+/// 文件位于 git 工作区内且未被忽略时返回 true；不是 git 仓库或未安装 git 时返回 false
+fn exposed_to_git(root: &Path, path: &Path) -> bool {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["check-ignore", "-q", "--"])
+        .arg(path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.code() == Some(1))
+}
+
+fn read_json_object(path: &Path) -> Result<Map<String, Value>, AppError> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    match serde_json::from_str::<Value>(&raw).map_err(|e| AppError::json(path, e))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(AppError::Config(format!("{} 根必须是对象", path.display()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_app_sections() {
+        let profile = ProjectProfile::parse(
+            r#"
+[claude]
+provider = "team-relay"
+mcp_servers = ["filesystem"]
+prompt = "team"
+
+[codex]
+prompt = "team"
+"#,
+            Path::new("/repo"),
+        )
+        .expect("parse profile");
+
+        let claude = &profile.apps[&AppType::Claude];
+        assert_eq!(claude.provider.as_deref(), Some("team-relay"));
+        assert_eq!(
+            claude.mcp_servers.as_deref(),
+            Some(&["filesystem".to_string()][..])
+        );
+        assert_eq!(
+            profile.apps[&AppType::Codex].prompt.as_deref(),
+            Some("team")
+        );
+        assert_eq!(profile.root, PathBuf::from("/repo"));
+    }
+
+    #[test]
+    fn rejects_settings_an_app_cannot_scope_to_a_project() {
+        let err = ProjectProfile::parse("[codex]\nprovider = \"openai\"\n", Path::new("."))
+            .expect_err("codex has no project-scoped provider");
+        assert!(err.to_string().contains("codex"), "{err}");
+
+        let err = ProjectProfile::parse("[claude]\nmodel = \"x\"\n", Path::new("."))
+            .expect_err("unknown field");
+        assert!(err.to_string().contains("model"), "{err}");
+    }
+
+    #[test]
+    fn finds_profile_in_ancestor_directories() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let nested = dir.path().join("src/bin");
+        std::fs::create_dir_all(&nested).expect("create nested dir");
+        std::fs::write(dir.path().join(PROJECT_PROFILE_FILE), "").expect("write profile");
+
+        assert_eq!(
+            ProjectProfile::find(&nested),
+            Some(dir.path().join(PROJECT_PROFILE_FILE))
+        );
+    }
+
+    #[test]
+    fn detects_settings_files_git_would_commit() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let settings = dir.path().join(".claude/settings.local.json");
+        assert!(!exposed_to_git(dir.path(), &settings), "not a git repo");
+
+        let initialized = std::process::Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .arg(dir.path())
+            .status()
+            .is_ok_and(|status| status.success());
+        if !initialized {
+            eprintln!("git not installed; skipping");
+            return;
+        }
+        assert!(exposed_to_git(dir.path(), &settings));
+
+        std::fs::write(
+            dir.path().join(".gitignore"),
+            ".claude/settings.local.json\n",
+        )
+        .expect("write .gitignore");
+        assert!(!exposed_to_git(dir.path(), &settings));
+    }
+}
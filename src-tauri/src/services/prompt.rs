@@ -1,19 +1,227 @@
+use std::path::Path;
+
 use indexmap::IndexMap;
 
 use crate::app_config::AppType;
 use crate::config::write_text_file;
 use crate::error::AppError;
 use crate::prompt::Prompt;
+use crate::prompt_block::{
+    extract_block, has_conflict_markers, merge3, replace_block, unwrap_block, wrap_block,
+    MergeOutcome,
+};
 use crate::prompt_files::prompt_file_path;
+use crate::services::ProviderService;
 use crate::store::AppState;
 
-fn get_unix_timestamp() -> Result<i64, AppError> {
+/// 模板中引用环境变量的前缀：`{{env:NAME}}`
+const ENV_VAR_PREFIX: &str = "env:";
+/// 变量名（大写）包含这些片段的环境变量可能是凭据，不允许渲染进提示词文件
+const SENSITIVE_ENV_VAR_PARTS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSPHRASE"];
+
+/// 提示词渲染上下文：`{{project_name}}`、`{{provider}}` 的取值
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    pub project_name: Option<String>,
+    pub provider: Option<String>,
+}
+
+impl PromptContext {
+    /// 全局激活时的上下文：应用当前供应商的名称。全局提示词文件不属于任何项目，
+    /// `{{project_name}}` 留空，只有项目配置写入的提示词才会填入项目名
+    pub fn current(state: &AppState, app: &AppType) -> Self {
+        Self {
+            project_name: None,
+            provider: current_provider_name(state, app),
+        }
+    }
+}
+
+/// 项目名：优先取包含 `.git` 的上级目录名，否则取目录本身的名称
+pub fn project_name_for(dir: &Path) -> Option<String> {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let root = dir
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .unwrap_or(&dir);
+    root.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+fn current_provider_name(state: &AppState, app: &AppType) -> Option<String> {
+    let id = ProviderService::current(state, app.clone()).ok()?;
+    if id.is_empty() {
+        return None;
+    }
+    let name = ProviderService::list(state, app.clone())
+        .ok()
+        .and_then(|providers| providers.get(&id).map(|provider| provider.name.clone()));
+    Some(name.unwrap_or(id))
+}
+
+/// 按片段顺序拼接提示词，片段在前、正文在后，各部分之间空一行
+fn compose(
+    prompts: &IndexMap<String, Prompt>,
+    id: &str,
+    stack: &mut Vec<String>,
+    parts: &mut Vec<String>,
+) -> Result<(), AppError> {
+    if stack.iter().any(|entry| entry == id) {
+        stack.push(id.to_string());
+        return Err(AppError::InvalidInput(format!(
+            "提示词片段存在循环引用: {}",
+            stack.join(" → ")
+        )));
+    }
+    let Some(prompt) = prompts.get(id) else {
+        return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+    };
+
+    stack.push(id.to_string());
+    for fragment in &prompt.fragments {
+        compose(prompts, fragment, stack, parts)?;
+    }
+    stack.pop();
+
+    if !prompt.content.trim().is_empty() {
+        parts.push(prompt.content.clone());
+    }
+    Ok(())
+}
+
+/// 提示词文件可能被提交或同步，保险库口令与疑似凭据的环境变量不允许展开
+fn is_sensitive_env_var(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    name == crate::secret_vault::PASSPHRASE_ENV
+        || SENSITIVE_ENV_VAR_PARTS
+            .iter()
+            .any(|part| upper.contains(part))
+}
+
+/// 替换 `{{project_name}}`、`{{provider}}`、`{{app}}` 与 `{{env:NAME}}`；
+/// 其他 `{{...}}` 原样保留，缺失的环境变量汇总后报错，疑似凭据的环境变量拒绝展开
+fn render_template(
+    template: &str,
+    app: &AppType,
+    context: &PromptContext,
+    lookup_env: &impl Fn(&str) -> Option<String>,
+) -> Result<String, AppError> {
+    let mut output = String::with_capacity(template.len());
+    let mut missing = Vec::new();
+    let mut refused = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let name = after[..end].trim();
+        let value = match name {
+            "project_name" => Some(context.project_name.clone().unwrap_or_default()),
+            "provider" => Some(context.provider.clone().unwrap_or_default()),
+            "app" => Some(app.as_str().to_string()),
+            _ => match name.strip_prefix(ENV_VAR_PREFIX) {
+                Some(var) if is_sensitive_env_var(var.trim()) => {
+                    refused.push(var.trim().to_string());
+                    None
+                }
+                Some(var) => {
+                    let value = lookup_env(var.trim());
+                    if value.is_none() {
+                        missing.push(var.trim().to_string());
+                    }
+                    value
+                }
+                None => Some(rest[start..start + end + 4].to_string()),
+            },
+        };
+        output.push_str(&value.unwrap_or_default());
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    if !refused.is_empty() {
+        refused.sort();
+        refused.dedup();
+        return Err(AppError::localized(
+            "prompt.env_refused",
+            format!(
+                "提示词不能引用可能包含凭据的环境变量: {}",
+                refused.join(", ")
+            ),
+            format!(
+                "Prompts cannot reference environment variables that may hold credentials: {}",
+                refused.join(", ")
+            ),
+        ));
+    }
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        return Err(AppError::localized(
+            "prompt.env_missing",
+            format!("提示词引用的环境变量未设置: {}", missing.join(", ")),
+            format!(
+                "Environment variables referenced by the prompt are not set: {}",
+                missing.join(", ")
+            ),
+        ));
+    }
+    Ok(output)
+}
+
+/// 渲染提示词：拼接片段并替换模板变量，得到写入 live 文件的内容
+fn render_prompt(
+    prompts: &IndexMap<String, Prompt>,
+    id: &str,
+    app: &AppType,
+    context: &PromptContext,
+    lookup_env: impl Fn(&str) -> Option<String>,
+) -> Result<String, AppError> {
+    let mut parts = Vec::new();
+    compose(prompts, id, &mut Vec::new(), &mut parts)?;
+    let last = parts.len().saturating_sub(1);
+    let composed = parts
+        .iter()
+        .enumerate()
+        .map(|(index, part)| {
+            if index == last {
+                part.as_str()
+            } else {
+                part.trim_end()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    render_template(&composed, app, context, &lookup_env)
+}
+
+pub(crate) fn get_unix_timestamp() -> Result<i64, AppError> {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs() as i64)
         .map_err(|e| AppError::Message(format!("Failed to get system time: {e}")))
 }
 
+/// 托管区块与 cc-switch 版本的对比结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptBlockStatus {
+    /// 提示词文件中没有托管区块
+    Missing,
+    /// 区块内容与上次写入的版本一致
+    InSync,
+    /// 区块被手动修改：`base` 为上次写入的版本，`live` 为区块内容，`current` 为 cc-switch 当前版本
+    Drifted {
+        base: String,
+        live: String,
+        current: String,
+    },
+}
+
 pub struct PromptService;
 
 impl PromptService {
@@ -82,19 +290,92 @@ impl PromptService {
         _id: &str,
         prompt: Prompt,
     ) -> Result<(), AppError> {
-        let is_enabled = prompt.enabled;
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        prompts.insert(prompt.id.clone(), prompt.clone());
+
+        // 启用的提示词，或当前激活提示词引用的片段发生变化时，重新渲染 live 文件
+        let live_id = if prompt.enabled {
+            Some(prompt.id.clone())
+        } else {
+            select_active_prompt(&prompts)
+                .filter(|active| uses_fragment(&prompts, &active.id, &prompt.id))
+                .map(|active| active.id)
+        };
+        let rendered = live_id
+            .map(|id| Self::render_with(state, &app, &prompts, &id))
+            .transpose()?;
 
         state.db.save_prompt(app.as_str(), &prompt)?;
 
-        if is_enabled {
-            let target_path = prompt_file_path(&app)?;
-            write_text_file(&target_path, &prompt.content)?;
+        if let Some(content) = rendered {
+            write_live(state, &app, &content, true)?;
         }
 
         Ok(())
     }
 
+    /// 设置提示词的片段（同一应用下其他提示词的 ID，按顺序拼接在正文之前）
+    pub fn set_fragments(
+        state: &AppState,
+        app: AppType,
+        id: &str,
+        fragments: Vec<String>,
+    ) -> Result<Prompt, AppError> {
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        let Some(prompt) = prompts.get_mut(id) else {
+            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        if let Some(duplicate) = fragments.iter().find(|fragment| !seen.insert(*fragment)) {
+            return Err(AppError::InvalidInput(format!("片段 {duplicate} 重复出现")));
+        }
+        prompt.fragments = fragments;
+        prompt.updated_at = Some(get_unix_timestamp()?);
+        let prompt = prompt.clone();
+
+        // 校验片段存在且没有循环引用
+        compose(&prompts, id, &mut Vec::new(), &mut Vec::new())?;
+
+        Self::upsert_prompt(state, app, id, prompt.clone())?;
+        Ok(prompt)
+    }
+
+    /// 渲染提示词，返回写入 live 文件的最终内容；未指定 ID 时使用当前激活的提示词
+    pub fn preview(state: &AppState, app: AppType, id: Option<&str>) -> Result<String, AppError> {
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => select_active_prompt(&prompts)
+                .map(|prompt| prompt.id)
+                .ok_or_else(|| AppError::InvalidInput("没有已激活的提示词".to_string()))?,
+        };
+        Self::render_with(state, &app, &prompts, &id)
+    }
+
+    /// 用指定上下文渲染提示词，例如项目配置使用项目目录名与项目供应商
+    pub fn render(
+        state: &AppState,
+        app: AppType,
+        id: &str,
+        context: &PromptContext,
+    ) -> Result<String, AppError> {
+        let prompts = state.db.get_prompts(app.as_str())?;
+        render_prompt(&prompts, id, &app, context, |name| std::env::var(name).ok())
+    }
+
+    fn render_with(
+        state: &AppState,
+        app: &AppType,
+        prompts: &IndexMap<String, Prompt>,
+        id: &str,
+    ) -> Result<String, AppError> {
+        let context = PromptContext::current(state, app);
+        render_prompt(prompts, id, app, &context, |name| std::env::var(name).ok())
+    }
+
     pub fn delete_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
+        ensure_not_shared_copy(state, &app, id)?;
         let prompts = state.db.get_prompts(app.as_str())?;
 
         if let Some(prompt) = prompts.get(id) {
@@ -103,6 +384,18 @@ impl PromptService {
             }
         }
 
+        let users = prompts
+            .values()
+            .filter(|prompt| prompt.fragments.iter().any(|fragment| fragment == id))
+            .map(|prompt| prompt.id.as_str())
+            .collect::<Vec<_>>();
+        if !users.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "提示词 {id} 被用作片段: {}",
+                users.join(", ")
+            )));
+        }
+
         state.db.delete_prompt(app.as_str(), id)?;
         Ok(())
     }
@@ -149,6 +442,9 @@ impl PromptService {
             return Err(AppError::InvalidInput("提示词名称不能为空".to_string()));
         }
 
+        if old_id != new_id {
+            ensure_not_shared_copy(state, &app, old_id)?;
+        }
         let prompts = state.db.get_prompts(app.as_str())?;
         if old_id != new_id && prompts.contains_key(new_id) {
             return Err(AppError::InvalidInput(format!("提示词 ID {new_id} 已存在")));
@@ -173,6 +469,18 @@ impl PromptService {
         state.db.save_prompt(app.as_str(), &prompt)?;
         if old_prompt_id != prompt.id {
             state.db.delete_prompt(app.as_str(), &old_prompt_id)?;
+            for other in prompts.values() {
+                if other.id == old_prompt_id || !other.fragments.contains(&old_prompt_id) {
+                    continue;
+                }
+                let mut other = other.clone();
+                for fragment in other.fragments.iter_mut() {
+                    if *fragment == old_prompt_id {
+                        *fragment = prompt.id.clone();
+                    }
+                }
+                state.db.save_prompt(app.as_str(), &other)?;
+            }
         }
 
         Ok(prompt)
@@ -224,6 +532,7 @@ impl PromptService {
             enabled: false,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
+            fragments: Vec::new(),
         };
 
         Self::upsert_prompt(state, app, &id, prompt.clone())?;
@@ -232,44 +541,49 @@ impl PromptService {
 
     pub fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
         let app_key = app.as_str();
-        let target_path = prompt_file_path(&app)?;
-
-        if target_path.exists() {
-            if let Ok(live_content) = std::fs::read_to_string(&target_path) {
-                if !live_content.trim().is_empty() {
-                    let mut prompts = state.db.get_prompts(app_key)?;
-
-                    if let Some((enabled_id, enabled_prompt)) = prompts
-                        .iter_mut()
-                        .find(|(_, prompt)| prompt.enabled)
-                        .map(|(id, prompt)| (id.clone(), prompt))
-                    {
+        let live_content = read_live(&app)?.filter(|live| !live.trim().is_empty());
+        if let Some(live_content) = live_content {
+            let mut prompts = state.db.get_prompts(app_key)?;
+
+            // 含片段或模板变量的提示词，渲染结果与正文不同，live 内容不能回填到正文
+            let owner = select_active_prompt(&prompts).map(|enabled| {
+                let rendered = Self::render_with(state, &app, &prompts, &enabled.id).ok();
+                let templated = rendered.as_deref() != Some(enabled.content.as_str());
+                (enabled.id, rendered, templated)
+            });
+
+            match owner {
+                Some((_, Some(rendered), _)) if rendered == live_content => {}
+                Some((enabled_id, _, false)) => {
+                    if let Some(enabled_prompt) = prompts.get_mut(&enabled_id) {
                         enabled_prompt.content = live_content.clone();
                         enabled_prompt.updated_at = Some(get_unix_timestamp()?);
                         log::info!("回填 live 提示词内容到已启用项: {enabled_id}");
                         state.db.save_prompt(app_key, enabled_prompt)?;
-                    } else {
-                        let content_exists = prompts
-                            .values()
-                            .any(|prompt| prompt.content.trim() == live_content.trim());
-                        if !content_exists {
-                            let timestamp = get_unix_timestamp()?;
-                            let backup_id = format!("backup-{timestamp}");
-                            let backup_prompt = Prompt {
-                                id: backup_id.clone(),
-                                name: format!(
-                                    "原始提示词 {}",
-                                    chrono::Local::now().format("%Y-%m-%d %H:%M")
-                                ),
-                                content: live_content,
-                                description: Some("自动备份的原始提示词".to_string()),
-                                enabled: false,
-                                created_at: Some(timestamp),
-                                updated_at: Some(timestamp),
-                            };
-                            log::info!("回填 live 提示词内容，创建备份: {backup_id}");
-                            state.db.save_prompt(app_key, &backup_prompt)?;
-                        }
+                    }
+                }
+                _ => {
+                    let content_exists = prompts
+                        .values()
+                        .any(|prompt| prompt.content.trim() == live_content.trim());
+                    if !content_exists {
+                        let timestamp = get_unix_timestamp()?;
+                        let backup_id = format!("backup-{timestamp}");
+                        let backup_prompt = Prompt {
+                            id: backup_id.clone(),
+                            name: format!(
+                                "原始提示词 {}",
+                                chrono::Local::now().format("%Y-%m-%d %H:%M")
+                            ),
+                            content: live_content,
+                            description: Some("自动备份的原始提示词".to_string()),
+                            enabled: false,
+                            created_at: Some(timestamp),
+                            updated_at: Some(timestamp),
+                            fragments: Vec::new(),
+                        };
+                        log::info!("回填 live 提示词内容，创建备份: {backup_id}");
+                        state.db.save_prompt(app_key, &backup_prompt)?;
                     }
                 }
             }
@@ -284,7 +598,9 @@ impl PromptService {
             return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
         };
         prompt.enabled = true;
-        write_text_file(&target_path, &prompt.content)?;
+        let content = Self::render_with(state, &app, &prompts, id)?;
+        // 区块内的手动修改已回填或备份，直接覆盖
+        write_live(state, &app, &content, false)?;
 
         for prompt in prompts.values() {
             state.db.save_prompt(app_key, prompt)?;
@@ -308,13 +624,135 @@ impl PromptService {
         state.db.save_prompt(app_key, prompt)?;
 
         if !prompts.values().any(|prompt| prompt.enabled) {
-            let target_path = prompt_file_path(&app)?;
-            write_text_file(&target_path, "")?;
+            write_live(state, &app, "", true)?;
         }
 
         Ok(())
     }
 
+    /// 开启或关闭托管区块模式。开启时，已由当前提示词写入的文件整体转为托管区块，
+    /// 手动修改过的部分随后以漂移的形式出现，可移到区块外或合并
+    pub fn set_managed_block(state: &AppState, enabled: bool) -> Result<(), AppError> {
+        crate::settings::set_prompt_managed_block(enabled)?;
+        for app in AppType::all() {
+            let key = block_base_key(&app);
+            if !enabled {
+                // 只去掉标记，区块内容与用户写在区块外的文本都留在文件里
+                let file = read_prompt_file(&app)?;
+                if extract_block(&file)?.is_some() {
+                    write_text_file(&prompt_file_path(&app)?, &unwrap_block(&file)?)?;
+                }
+                state.db.delete_setting(&key)?;
+                continue;
+            }
+
+            let prompts = state.db.get_prompts(app.as_str())?;
+            let Some(active) = select_active_prompt(&prompts) else {
+                continue;
+            };
+            let file = read_prompt_file(&app)?;
+            if file.trim().is_empty() || extract_block(&file)?.is_some() {
+                continue;
+            }
+            let current = match Self::render_with(state, &app, &prompts, &active.id) {
+                Ok(current) => current,
+                Err(err) => {
+                    log::warn!("渲染 {app} 提示词失败，跳过托管区块转换: {err}");
+                    continue;
+                }
+            };
+            // 只把文件中与当前提示词一致的部分包进区块；找不到时保持文件不变，
+            // 下次写入时区块追加到末尾，原有内容留在区块外
+            let Some(wrapped) = wrap_block(&file, &current)? else {
+                continue;
+            };
+            write_text_file(&prompt_file_path(&app)?, &wrapped)?;
+            state.db.set_setting(&key, &current)?;
+        }
+        Ok(())
+    }
+
+    /// 对比托管区块与上次写入的版本
+    pub fn block_status(state: &AppState, app: AppType) -> Result<PromptBlockStatus, AppError> {
+        let Some(live) = extract_block(&read_prompt_file(&app)?)? else {
+            return Ok(PromptBlockStatus::Missing);
+        };
+        let base = state
+            .db
+            .get_setting(&block_base_key(&app))?
+            .unwrap_or_default();
+        if live == base {
+            return Ok(PromptBlockStatus::InSync);
+        }
+
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let current = match select_active_prompt(&prompts) {
+            Some(active) => Self::render_with(state, &app, &prompts, &active.id)?,
+            None => String::new(),
+        };
+        Ok(PromptBlockStatus::Drifted {
+            base,
+            live,
+            current,
+        })
+    }
+
+    /// 三方合并手动修改过的区块与 cc-switch 当前版本（不写入）；区块未漂移时返回 None
+    pub fn merge_block(state: &AppState, app: AppType) -> Result<Option<MergeOutcome>, AppError> {
+        Ok(match Self::block_status(state, app)? {
+            PromptBlockStatus::Drifted {
+                base,
+                live,
+                current,
+            } => Some(merge3(&base, &live, &current)),
+            _ => None,
+        })
+    }
+
+    /// 用解决后的内容写入托管区块，并以 cc-switch 当前版本作为下次合并的基准
+    pub fn resolve_block(state: &AppState, app: AppType, resolved: &str) -> Result<(), AppError> {
+        if has_conflict_markers(resolved) {
+            return Err(AppError::InvalidInput(
+                "内容中仍有未解决的冲突标记".to_string(),
+            ));
+        }
+        let current = match Self::block_status(state, app.clone())? {
+            PromptBlockStatus::Drifted { current, .. } => current,
+            _ => return Ok(()),
+        };
+
+        let file = read_prompt_file(&app)?;
+        write_text_file(
+            &prompt_file_path(&app)?,
+            &replace_block(&file, resolved.trim_end())?,
+        )?;
+        state.db.set_setting(&block_base_key(&app), &current)
+    }
+
+    /// 把手动修改过的区块内容保存为当前激活提示词的正文
+    pub fn keep_live_block(state: &AppState, app: AppType) -> Result<(), AppError> {
+        let PromptBlockStatus::Drifted { live, current, .. } =
+            Self::block_status(state, app.clone())?
+        else {
+            return Ok(());
+        };
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let Some(mut active) = select_active_prompt(&prompts) else {
+            return Err(AppError::InvalidInput("没有已激活的提示词".to_string()));
+        };
+        if current != active.content {
+            return Err(AppError::InvalidInput(format!(
+                "提示词 {} 含片段或模板变量，无法直接保存区块内容",
+                active.id
+            )));
+        }
+
+        active.content = live.clone();
+        active.updated_at = Some(get_unix_timestamp()?);
+        state.db.save_prompt(app.as_str(), &active)?;
+        state.db.set_setting(&block_base_key(&app), &live)
+    }
+
     pub fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
         let file_path = prompt_file_path(&app)?;
 
@@ -338,6 +776,7 @@ impl PromptService {
             enabled: false,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
+            fragments: Vec::new(),
         };
 
         Self::upsert_prompt(state, app, &id, prompt)?;
@@ -354,13 +793,43 @@ impl PromptService {
         Ok(Some(content))
     }
 
+    /// 切换供应商后重新渲染当前激活的提示词，使 `{{provider}}` 保持最新；
+    /// 渲染结果与正文相同的提示词不受供应商影响，不会改写 live 文件
+    pub fn refresh_active_after_provider_switch(state: &AppState, app: &AppType) {
+        if !crate::sync_policy::should_sync_live(app) {
+            return;
+        }
+        let prompts = match state.db.get_prompts(app.as_str()) {
+            Ok(prompts) => prompts,
+            Err(err) => {
+                log::warn!("读取 {app} 提示词失败: {err}");
+                return;
+            }
+        };
+        let Some(active) = select_active_prompt(&prompts) else {
+            return;
+        };
+        let result = Self::render_with(state, app, &prompts, &active.id).and_then(|content| {
+            if content == active.content {
+                return Ok(());
+            }
+            write_live(state, app, &content, true)
+        });
+        if let Err(err) = result {
+            log::warn!("切换供应商后刷新 {app} 提示词失败: {err}");
+        }
+    }
+
     pub fn sync_all_active_to_live_best_effort(state: &AppState) -> Result<(), AppError> {
         let mut active_prompts = Vec::new();
 
         for app in AppType::all() {
             let prompts = state.db.get_prompts(app.as_str())?;
             if let Some(prompt) = select_active_prompt(&prompts) {
-                active_prompts.push((app, prompt.content));
+                match Self::render_with(state, &app, &prompts, &prompt.id) {
+                    Ok(content) => active_prompts.push((app, content)),
+                    Err(err) => log::warn!("渲染 {app} 提示词失败: {err}"),
+                }
             }
         }
 
@@ -369,15 +838,7 @@ impl PromptService {
                 continue;
             }
 
-            let target_path = match prompt_file_path(&app) {
-                Ok(path) => path,
-                Err(err) => {
-                    log::warn!("同步 {app} 提示词 live 文件时解析路径失败: {err}");
-                    continue;
-                }
-            };
-
-            if let Err(err) = write_text_file(&target_path, &content) {
+            if let Err(err) = write_live(state, &app, &content, true) {
                 log::warn!("同步 {app} 提示词到 live 文件失败: {err}");
             }
         }
@@ -386,6 +847,114 @@ impl PromptService {
     }
 }
 
+/// 托管区块上次写入的 cc-switch 版本，作为三方合并的基准
+fn block_base_key(app: &AppType) -> String {
+    format!("prompt_block_base_{}", app.as_str())
+}
+
+fn read_prompt_file(app: &AppType) -> Result<String, AppError> {
+    let path = prompt_file_path(app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(AppError::io(&path, e)),
+    }
+}
+
+/// 读取 live 提示词：托管区块模式下只取区块内容（没有区块时为 None）
+fn read_live(app: &AppType) -> Result<Option<String>, AppError> {
+    let path = prompt_file_path(app)?;
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    if crate::settings::prompt_managed_block() {
+        return extract_block(&content);
+    }
+    Ok(Some(content))
+}
+
+/// 写入 live 提示词。托管区块模式下只替换区块；`merge` 为 true 且区块被手动修改过时，
+/// 与新内容三方合并，存在冲突则不写入并报错
+fn write_live(state: &AppState, app: &AppType, content: &str, merge: bool) -> Result<(), AppError> {
+    let path = prompt_file_path(app)?;
+    if !crate::settings::prompt_managed_block() {
+        return write_text_file(&path, content);
+    }
+
+    let file = read_prompt_file(app)?;
+    let key = block_base_key(app);
+    let mut block = content.to_string();
+    if merge {
+        if let Some(live) = extract_block(&file)? {
+            let base = state.db.get_setting(&key)?.unwrap_or_default();
+            if live != base {
+                match merge3(&base, &live, content) {
+                    MergeOutcome::Clean(merged) => block = merged,
+                    MergeOutcome::Conflict { conflicts, .. } => {
+                        return Err(block_conflict(app, conflicts));
+                    }
+                }
+            }
+        }
+    }
+
+    write_text_file(&path, &replace_block(&file, &block)?)?;
+    if content.is_empty() {
+        state.db.delete_setting(&key)
+    } else {
+        state.db.set_setting(&key, content)
+    }
+}
+
+fn block_conflict(app: &AppType, conflicts: usize) -> AppError {
+    AppError::localized(
+        "prompt.block_conflict",
+        format!(
+            "{} 提示词文件的托管区块已被手动修改，与 cc-switch 的新内容有 {conflicts} 处冲突，请先运行 `cc-switch --app {} prompts merge`",
+            app.as_str(),
+            app.as_str()
+        ),
+        format!(
+            "The managed block in the {} prompt file was edited by hand and has {conflicts} conflict(s) with the new content; run `cc-switch --app {} prompts merge` first",
+            app.as_str(),
+            app.as_str()
+        ),
+    )
+}
+
+/// 共享提示词在启用应用下的副本由共享提示词维护，不能单独删除或修改 ID
+fn ensure_not_shared_copy(state: &AppState, app: &AppType, id: &str) -> Result<(), AppError> {
+    let shared = state.db.get_shared_prompts()?;
+    if shared
+        .get(id)
+        .is_some_and(|shared| shared.apps.is_enabled_for(app))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "提示词 {id} 由共享提示词管理，请修改共享提示词"
+        )));
+    }
+    Ok(())
+}
+
+/// `root` 是否直接或间接引用了片段 `fragment`
+fn uses_fragment(prompts: &IndexMap<String, Prompt>, root: &str, fragment: &str) -> bool {
+    let mut pending = vec![root];
+    let mut visited = std::collections::HashSet::new();
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        let Some(prompt) = prompts.get(id) else {
+            continue;
+        };
+        if prompt.fragments.iter().any(|entry| entry == fragment) {
+            return true;
+        }
+        pending.extend(prompt.fragments.iter().map(String::as_str));
+    }
+    false
+}
+
 fn select_active_prompt(prompts: &IndexMap<String, Prompt>) -> Option<Prompt> {
     prompts
         .values()
@@ -405,6 +974,7 @@ mod tests {
     use super::*;
     use crate::app_config::MultiAppConfig;
     use crate::database::Database;
+    use crate::prompt_block::{BLOCK_BEGIN, BLOCK_END};
     use crate::services::ProxyService;
     use serial_test::serial;
     use std::ffi::OsString;
@@ -482,6 +1052,7 @@ mod tests {
             enabled,
             created_at: Some(1),
             updated_at: Some(1),
+            fragments: Vec::new(),
         }
     }
 
@@ -516,8 +1087,7 @@ mod tests {
     fn enable_prompt_backfills_live_to_previous_active_and_disable_clears_live() {
         let home = TempHome::new();
         let state = state_with_config(MultiAppConfig::default());
-        let live_path =
-            crate::prompt_files::prompt_file_path(&AppType::Claude).expect("claude prompt path");
+        let live_path = prompt_file_path(&AppType::Claude).expect("claude prompt path");
         std::fs::create_dir_all(live_path.parent().expect("live parent"))
             .expect("create live parent");
 
@@ -566,8 +1136,7 @@ mod tests {
     fn enable_prompt_creates_backup_when_live_has_no_active_owner() {
         let _home = TempHome::new();
         let state = state_with_config(MultiAppConfig::default());
-        let live_path =
-            crate::prompt_files::prompt_file_path(&AppType::Claude).expect("claude prompt path");
+        let live_path = prompt_file_path(&AppType::Claude).expect("claude prompt path");
         std::fs::create_dir_all(live_path.parent().expect("live parent"))
             .expect("create live parent");
         std::fs::write(&live_path, "manual live").expect("write live prompt");
@@ -634,6 +1203,7 @@ mod tests {
                 enabled: true,
                 created_at: Some(1),
                 updated_at: Some(1),
+                fragments: Vec::new(),
             },
         )
         .expect("seed prompt");
@@ -694,4 +1264,246 @@ mod tests {
 
         assert!(err.to_string().contains("已存在"));
     }
+
+    #[test]
+    fn render_template_substitutes_known_variables_and_keeps_others() {
+        let context = PromptContext {
+            project_name: Some("api".to_string()),
+            provider: Some("Team Relay".to_string()),
+        };
+        let rendered = render_template(
+            "{{project_name}} via {{ provider }} for {{app}} as {{env:TEAM}}; keep {{.Name}}",
+            &AppType::Codex,
+            &context,
+            &|name: &str| (name == "TEAM").then(|| "core".to_string()),
+        )
+        .expect("render template");
+
+        assert_eq!(
+            rendered,
+            "api via Team Relay for codex as core; keep {{.Name}}"
+        );
+
+        let err = render_template("{{env:MISSING}}", &AppType::Claude, &context, &|_: &str| {
+            None
+        })
+        .expect_err("missing env var");
+        assert!(err.to_string().contains("MISSING"), "{err}");
+
+        for name in [
+            crate::secret_vault::PASSPHRASE_ENV,
+            "OPENAI_API_KEY",
+            "github_token",
+            "CLIENT_SECRET",
+        ] {
+            let err = render_template(
+                &format!("{{{{env:{name}}}}}"),
+                &AppType::Claude,
+                &context,
+                &|_: &str| Some("leaked".to_string()),
+            )
+            .expect_err("credential env var");
+            assert!(err.to_string().contains(name), "{err}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn fragments_render_in_order_and_cycles_are_rejected() {
+        let _home = TempHome::new();
+        let state = state_with_config(MultiAppConfig::default());
+        for (id, content) in [
+            ("rules", "Team rules.\n"),
+            ("style", "Write in English."),
+            ("main", "Project {{app}} notes."),
+        ] {
+            PromptService::upsert_prompt(&state, AppType::Claude, id, prompt(id, content, false))
+                .expect("seed prompt");
+        }
+
+        PromptService::set_fragments(
+            &state,
+            AppType::Claude,
+            "main",
+            vec!["rules".to_string(), "style".to_string()],
+        )
+        .expect("set fragments");
+        assert_eq!(
+            PromptService::preview(&state, AppType::Claude, Some("main")).expect("preview"),
+            "Team rules.\n\nWrite in English.\n\nProject claude notes."
+        );
+
+        let err = PromptService::set_fragments(
+            &state,
+            AppType::Claude,
+            "rules",
+            vec!["main".to_string()],
+        )
+        .expect_err("cycle should be rejected");
+        assert!(err.to_string().contains("rules → main → rules"), "{err}");
+
+        let err = PromptService::delete_prompt(&state, AppType::Claude, "style")
+            .expect_err("fragment in use");
+        assert!(err.to_string().contains("main"), "{err}");
+    }
+
+    #[test]
+    #[serial]
+    fn editing_a_fragment_rerenders_the_active_prompt_without_backfilling_it() {
+        let _home = TempHome::new();
+        let state = state_with_config(MultiAppConfig::default());
+        let live_path = prompt_file_path(&AppType::Claude).expect("claude prompt path");
+
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "rules",
+            prompt("rules", "Rules v1.", false),
+        )
+        .expect("seed fragment");
+        let mut main = prompt("main", "Main.", false);
+        main.fragments = vec!["rules".to_string()];
+        PromptService::upsert_prompt(&state, AppType::Claude, "main", main).expect("seed main");
+
+        PromptService::enable_prompt(&state, AppType::Claude, "main").expect("enable main");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("read live"),
+            "Rules v1.\n\nMain."
+        );
+
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "rules",
+            prompt("rules", "Rules v2.", false),
+        )
+        .expect("edit fragment");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("read live"),
+            "Rules v2.\n\nMain."
+        );
+
+        // Re-activating must not copy the rendered file back over the template.
+        PromptService::enable_prompt(&state, AppType::Claude, "main").expect("re-enable main");
+        let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("load prompts");
+        assert_eq!(prompts["main"].content, "Main.");
+        assert!(!prompts.keys().any(|id| id.starts_with("backup-")));
+    }
+
+    #[test]
+    #[serial]
+    fn toggling_managed_block_only_adds_or_removes_markers() {
+        let _home = TempHome::new();
+        let state = state_with_config(MultiAppConfig::default());
+        let live_path = prompt_file_path(&AppType::Claude).expect("claude prompt path");
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "main",
+            prompt("main", "Main.", true),
+        )
+        .expect("seed main");
+        let file = "# My notes\n\nMain.\n\nTail.\n";
+        std::fs::write(&live_path, file).expect("add notes");
+
+        PromptService::set_managed_block(&state, true).expect("enable block mode");
+        let live = std::fs::read_to_string(&live_path).expect("read live");
+        assert_eq!(
+            live,
+            format!("# My notes\n\n{BLOCK_BEGIN}\nMain.\n{BLOCK_END}\n\nTail.\n")
+        );
+        assert_eq!(
+            PromptService::block_status(&state, AppType::Claude).expect("status"),
+            PromptBlockStatus::InSync
+        );
+
+        PromptService::set_managed_block(&state, false).expect("disable block mode");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("read live"),
+            file
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn managed_block_keeps_user_notes_and_merges_hand_edits() {
+        let _home = TempHome::new();
+        let state = state_with_config(MultiAppConfig::default());
+        let live_path = prompt_file_path(&AppType::Claude).expect("claude prompt path");
+        std::fs::create_dir_all(live_path.parent().expect("parent")).expect("create dir");
+        std::fs::write(&live_path, "# My notes\n").expect("seed notes");
+
+        PromptService::set_managed_block(&state, true).expect("enable block mode");
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "a",
+            prompt("a", "one\ntwo\nthree", false),
+        )
+        .expect("seed a");
+        PromptService::upsert_prompt(&state, AppType::Claude, "b", prompt("b", "B.", false))
+            .expect("seed b");
+        PromptService::enable_prompt(&state, AppType::Claude, "a").expect("enable a");
+        let live = std::fs::read_to_string(&live_path).expect("read live");
+        assert!(live.starts_with("# My notes\n\n"), "{live}");
+        assert_eq!(
+            PromptService::block_status(&state, AppType::Claude).expect("status"),
+            PromptBlockStatus::InSync
+        );
+
+        // A hand edit inside the block merges with an edit to a different line.
+        std::fs::write(&live_path, live.replace("one", "one (mine)")).expect("edit block");
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "a",
+            prompt("a", "one\ntwo\nthree (db)", true),
+        )
+        .expect("edit a");
+        let merged = std::fs::read_to_string(&live_path).expect("read live");
+        assert!(merged.contains("one (mine)\ntwo\nthree (db)"), "{merged}");
+
+        // Overlapping edits are a conflict and leave the file alone.
+        std::fs::write(&live_path, merged.replace("two", "two (mine)")).expect("edit block");
+        let err = PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "a",
+            prompt("a", "one\ntwo (db)\nthree (db)", true),
+        )
+        .expect_err("conflict");
+        assert!(err.to_string().contains("prompts merge"), "{err}");
+        assert!(matches!(
+            PromptService::merge_block(&state, AppType::Claude).expect("merge"),
+            Some(MergeOutcome::Conflict { conflicts: 1, .. })
+        ));
+        PromptService::resolve_block(
+            &state,
+            AppType::Claude,
+            "one (mine)\ntwo (both)\nthree (db)",
+        )
+        .expect("resolve");
+        assert_eq!(
+            PromptService::block_status(&state, AppType::Claude).expect("status"),
+            PromptBlockStatus::Drifted {
+                base: "one\ntwo (db)\nthree (db)".to_string(),
+                live: "one (mine)\ntwo (both)\nthree (db)".to_string(),
+                current: "one\ntwo (db)\nthree (db)".to_string(),
+            }
+        );
+
+        // Switching prompts backfills the block into `a` and keeps the notes.
+        PromptService::enable_prompt(&state, AppType::Claude, "b").expect("enable b");
+        let live = std::fs::read_to_string(&live_path).expect("read live");
+        assert!(live.starts_with("# My notes\n\n"), "{live}");
+        assert_eq!(extract_block(&live).expect("block"), Some("B.".to_string()));
+        let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("prompts");
+        assert_eq!(prompts["a"].content, "one (mine)\ntwo (both)\nthree (db)");
+
+        PromptService::disable_prompt(&state, AppType::Claude, "b").expect("disable b");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("read live"),
+            "# My notes\n"
+        );
+    }
 }
@@ -259,6 +259,20 @@ impl ProviderService {
         Ok(PreparedLiveWrite::Claude { settings })
     }
 
+    /// 构建写入项目级 `.claude/settings.local.json` 的供应商配置：
+    /// 解密密钥、规范化模型字段并去掉内部字段，不叠加通用配置片段
+    /// （通用配置已在用户级 settings.json 中生效）。
+    pub(crate) fn claude_project_settings(
+        state: &AppState,
+        provider: &Provider,
+    ) -> Result<Value, AppError> {
+        let mut settings =
+            crate::secret_vault::reveal_provider(&state.db, provider)?.settings_config;
+        let _ = Self::normalize_claude_models_in_value(&mut settings);
+        Self::sanitize_claude_settings_for_live(&mut settings);
+        Ok(settings)
+    }
+
     /// Mirror of upstream `sanitize_claude_settings_for_live`: remove CC-Switch
     /// internal-only fields that must not leak into Claude Code's settings.json.
     fn sanitize_claude_settings_for_live(settings: &mut Value) {
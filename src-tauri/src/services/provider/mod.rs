@@ -72,6 +72,7 @@ pub fn reapply_current_codex_official_live(state: &AppState) -> Result<bool, App
 
     let common_config_snippet = state.db.get_config_snippet(AppType::Codex.as_str())?;
     ProviderService::write_live_snapshot(
+        &state.db,
         &AppType::Codex,
         provider,
         common_config_snippet.as_deref(),
@@ -541,7 +542,13 @@ impl ProviderService {
         };
 
         for provider in &providers {
-            Self::write_live_snapshot(&AppType::OpenClaw, provider, snippet.as_deref(), true)?;
+            Self::write_live_snapshot(
+                &state.db,
+                &AppType::OpenClaw,
+                provider,
+                snippet.as_deref(),
+                true,
+            )?;
         }
 
         Ok(())
@@ -786,10 +793,16 @@ impl ProviderService {
                 .as_ref()
                 .and_then(|meta| meta.apply_common_config)
                 .unwrap_or(false);
+            let provider = crate::secret_vault::reveal_provider(&state.db, &action.provider)?;
+            let previous_provider = action
+                .previous_provider
+                .as_ref()
+                .map(|previous| crate::secret_vault::reveal_provider(&state.db, previous))
+                .transpose()?;
             PreparedPostCommitEffect::Live(Self::prepare_live_snapshot(
                 &action.app_type,
-                &action.provider,
-                action.previous_provider.as_ref(),
+                &provider,
+                previous_provider.as_ref(),
                 action.common_config_snippet.as_deref(),
                 action.previous_common_config_snippet.as_deref(),
                 apply_common_config,
@@ -2281,6 +2294,40 @@ impl ProviderService {
         }
     }
 
+    /// 更新供应商的每日/每月消费限额（USD）
+    ///
+    /// 外层 `None` 表示保持不变，`Some(None)` 表示移除该限额。
+    pub fn set_spend_limits(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        daily: Option<Option<String>>,
+        monthly: Option<Option<String>>,
+    ) -> Result<(), AppError> {
+        {
+            let mut cfg = state.config.write().map_err(AppError::from)?;
+            let manager = cfg
+                .get_manager_mut(&app_type)
+                .ok_or_else(|| Self::app_not_found(&app_type))?;
+            let provider = manager.providers.get_mut(provider_id).ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {provider_id}"),
+                    format!("Provider not found: {provider_id}"),
+                )
+            })?;
+            let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+            if let Some(daily) = daily {
+                meta.limit_daily_usd = daily;
+            }
+            if let Some(monthly) = monthly {
+                meta.limit_monthly_usd = monthly;
+            }
+        }
+
+        state.save()
+    }
+
     /// 更新供应商排序
     pub fn update_sort_order(
         state: &AppState,
@@ -2592,7 +2639,7 @@ impl ProviderService {
                 continue;
             }
 
-            Self::write_live_snapshot(app_type, provider, snippet.as_deref(), true)?;
+            Self::write_live_snapshot(&state.db, app_type, provider, snippet.as_deref(), true)?;
         }
 
         if let Err(e) =
@@ -2723,10 +2770,15 @@ impl ProviderService {
                 )
                 .map_err(|e| AppError::Message(format!("热切换失败: {e}")))?;
 
-                let mut guard = state.config.write().map_err(AppError::from)?;
-                if let Some(manager) = guard.get_manager_mut(&app_type) {
-                    manager.current = provider_id.to_string();
+                {
+                    let mut guard = state.config.write().map_err(AppError::from)?;
+                    if let Some(manager) = guard.get_manager_mut(&app_type) {
+                        manager.current = provider_id.to_string();
+                    }
                 }
+                crate::services::prompt::PromptService::refresh_active_after_provider_switch(
+                    state, &app_type,
+                );
                 return Ok(());
             }
         }
@@ -2758,19 +2810,24 @@ impl ProviderService {
         if !app_type.is_additive_mode() {
             crate::settings::set_current_provider(&app_type, Some(provider_id))?;
         }
+        crate::services::prompt::PromptService::refresh_active_after_provider_switch(
+            state, &app_type,
+        );
 
         Ok(())
     }
 
     fn write_live_snapshot(
+        db: &crate::database::Database,
         app_type: &AppType,
         provider: &Provider,
         common_config_snippet: Option<&str>,
         apply_common_config: bool,
     ) -> Result<(), AppError> {
+        let provider = crate::secret_vault::reveal_provider(db, provider)?;
         let prepared = Self::prepare_live_snapshot(
             app_type,
-            provider,
+            &provider,
             None,
             common_config_snippet,
             None,
@@ -3260,9 +3317,10 @@ impl ProviderService {
             common_config_snippet.as_deref(),
             true,
         );
+        let provider = crate::secret_vault::reveal_provider(&state.db, provider)?;
         Self::build_effective_live_snapshot(
             &app_type,
-            provider,
+            &provider,
             common_config_snippet.as_deref(),
             apply_common_config,
         )
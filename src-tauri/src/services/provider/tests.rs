@@ -1002,6 +1002,43 @@ fn switch_claude_writes_target_when_live_matches_current_provider() {
     );
 }
 
+#[test]
+#[serial]
+fn switch_rerenders_active_prompt_with_new_provider_name() {
+    let (_temp_home, _env, state) = setup_claude_switch_preview_state(json!({
+        "env": {
+            "ANTHROPIC_AUTH_TOKEN": "token1",
+            "ANTHROPIC_BASE_URL": "https://claude.one"
+        }
+    }));
+    let prompt = crate::prompt::Prompt {
+        id: "main".to_string(),
+        name: "main".to_string(),
+        content: "Using {{provider}} in {{project_name}}.".to_string(),
+        description: None,
+        enabled: false,
+        created_at: Some(1),
+        updated_at: Some(1),
+        fragments: Vec::new(),
+    };
+    crate::services::PromptService::upsert_prompt(&state, AppType::Claude, "main", prompt)
+        .expect("seed prompt");
+    crate::services::PromptService::enable_prompt(&state, AppType::Claude, "main")
+        .expect("enable prompt");
+    let live_path = crate::prompt_files::prompt_file_path(&AppType::Claude).expect("prompt path");
+    assert_eq!(
+        std::fs::read_to_string(&live_path).expect("read live prompt"),
+        "Using First in ."
+    );
+
+    ProviderService::switch(&state, AppType::Claude, "p2").expect("switch should succeed");
+
+    assert_eq!(
+        std::fs::read_to_string(&live_path).expect("read live prompt"),
+        "Using Second in ."
+    );
+}
+
 #[test]
 #[serial]
 fn switch_overwrites_claude_settings_discarding_unstored_live_edit() {
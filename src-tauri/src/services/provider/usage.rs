@@ -128,8 +128,9 @@ impl ProviderService {
                 ));
             }
 
+            let provider = crate::secret_vault::reveal_provider(&state.db, provider)?;
             let (api_key, base_url) =
-                Self::resolve_usage_script_credentials(provider, &app_type, usage_script)?;
+                Self::resolve_usage_script_credentials(&provider, &app_type, usage_script)?;
 
             (
                 usage_script.code.clone(),
@@ -180,8 +181,10 @@ impl ProviderService {
             let Some((provider, usage_script)) = provider.zip(usage_script) else {
                 return Err("Usage script is not configured".to_string());
             };
+            let provider = crate::secret_vault::reveal_provider(&state.db, provider)
+                .map_err(|e| e.to_string())?;
             let (api_key, base_url) =
-                Self::resolve_usage_script_credentials(provider, &app_type, usage_script)
+                Self::resolve_usage_script_credentials(&provider, &app_type, usage_script)
                     .map_err(|e| e.to_string())?;
 
             let quota = crate::services::coding_plan::get_coding_plan_quota(&base_url, &api_key)
@@ -227,8 +230,10 @@ impl ProviderService {
             let Some((provider, usage_script)) = provider.zip(usage_script) else {
                 return Err("Usage script is not configured".to_string());
             };
+            let provider = crate::secret_vault::reveal_provider(&state.db, provider)
+                .map_err(|e| e.to_string())?;
             let (api_key, base_url) =
-                Self::resolve_usage_script_credentials(provider, &app_type, usage_script)
+                Self::resolve_usage_script_credentials(&provider, &app_type, usage_script)
                     .map_err(|e| e.to_string())?;
 
             return crate::services::balance::get_balance(&base_url, &api_key)
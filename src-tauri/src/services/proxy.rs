@@ -21,6 +21,7 @@ use crate::{
     },
     provider::Provider,
     proxy::{
+        handlers::CONTROL_SESSION_TOKEN_HEADER,
         switch_lock::SwitchLockManager,
         types::{ActiveTarget, GlobalProxyConfig, ProxyTakeoverStatus},
         ProxyConfig, ProxyServer, ProxyServerInfo, ProxyStatus,
@@ -1498,6 +1499,18 @@ impl ProxyService {
         Ok(())
     }
 
+    /// 订阅本进程内代理服务器的运行时事件；代理未启动时返回 None
+    pub async fn subscribe_events(
+        &self,
+    ) -> Option<tokio::sync::broadcast::Receiver<crate::proxy::events::ProxyEvent>> {
+        self.runtime
+            .server
+            .read()
+            .await
+            .as_ref()
+            .map(|server| server.subscribe_events())
+    }
+
     pub async fn get_global_config(&self) -> Result<GlobalProxyConfig, AppError> {
         self.db.get_global_proxy_config().await
     }
@@ -2791,6 +2804,8 @@ impl ProxyService {
         app_type: &AppType,
         provider: &Provider,
     ) -> Result<Value, String> {
+        let provider = &crate::secret_vault::reveal_provider(&self.db, provider)
+            .map_err(|error| error.to_string())?;
         let common_config_snippet =
             self.db
                 .get_config_snippet(app_type.as_str())
@@ -3447,6 +3462,8 @@ impl ProxyService {
         app_type: &AppType,
         provider: &Provider,
     ) -> Result<Value, String> {
+        let provider = &crate::secret_vault::reveal_provider(&self.db, provider)
+            .map_err(|error| error.to_string())?;
         let common_config_snippet =
             self.db
                 .get_config_snippet(app_type.as_str())
@@ -3763,6 +3780,7 @@ impl ProxyService {
 
         let response = client
             .get(Self::build_session_status_url(session))
+            .header(CONTROL_SESSION_TOKEN_HEADER, expected_session_token)
             .send()
             .await;
         let Ok(response) = response else {
@@ -4166,6 +4184,7 @@ mod tests {
                 takeovers: crate::daemon::ipc::protocol::TakeoverFlags::default(),
                 restart_count: 0,
                 last_restart_at: None,
+                auto_sync: None,
                 workers: vec![
                     crate::daemon::ipc::protocol::WorkerState {
                         app_type: "claude".to_string(),
@@ -4223,6 +4242,7 @@ mod tests {
                 takeovers: TakeoverFlags::default(),
                 restart_count: 0,
                 last_restart_at: None,
+                auto_sync: None,
                 workers: vec![WorkerState {
                     app_type: "claude".to_string(),
                     running: true,
@@ -4328,6 +4348,7 @@ mod tests {
                 takeovers: TakeoverFlags::default(),
                 restart_count: 0,
                 last_restart_at: None,
+                auto_sync: None,
                 workers: vec![
                     worker(
                         "claude",
@@ -0,0 +1,272 @@
+//! 会话全文搜索服务
+//!
+//! 扫描各应用保存的会话并增量写入 SQLite FTS5 索引，再按关键词、应用、
+//! 项目目录和时间范围检索。索引进度复用 `session_log_sync` 表，键名加
+//! `search:` 前缀以与用量同步的条目区分。
+
+use std::collections::HashSet;
+use std::fs;
+
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::session_usage::{get_all_sync_states, metadata_modified_nanos};
+use crate::session_manager::{self, SessionMeta};
+
+/// session_log_sync 中搜索索引条目的键名前缀
+pub const SEARCH_SYNC_KEY_PREFIX: &str = "search:";
+
+/// 默认返回的会话数量
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// 搜索条件
+#[derive(Debug, Clone)]
+pub struct SessionSearchQuery {
+    pub text: String,
+    pub provider_id: Option<String>,
+    pub project_dir: Option<String>,
+    /// 最近活跃时间下限（毫秒，含）
+    pub since_ms: Option<i64>,
+    /// 最近活跃时间上限（毫秒，不含）
+    pub until_ms: Option<i64>,
+    pub limit: usize,
+}
+
+impl SessionSearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            provider_id: None,
+            project_dir: None,
+            since_ms: None,
+            until_ms: None,
+            limit: DEFAULT_SEARCH_LIMIT,
+        }
+    }
+}
+
+/// 单个会话的搜索结果，`snippet` 中命中词以 «» 标记
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchHit {
+    pub session: SessionMeta,
+    pub matches: usize,
+    pub role: String,
+    pub message_index: usize,
+    pub snippet: String,
+}
+
+/// 一次索引同步的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionIndexSyncResult {
+    pub indexed_sessions: usize,
+    pub indexed_messages: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+/// 增量同步会话搜索索引；`provider_id` 为 None 时同步所有应用
+///
+/// 文件会话以 mtime 判断变化，SQLite 会话（`sqlite:` 前缀）以最近活跃时间判断。
+/// 变化的会话只追加上次索引位置之后的消息；消息数变少时视为重写并整体重建。
+pub fn sync_session_search_index(
+    db: &Database,
+    provider_id: Option<&str>,
+) -> Result<SessionIndexSyncResult, AppError> {
+    let sessions = match provider_id {
+        Some(provider_id) => session_manager::scan_sessions_for_provider(provider_id),
+        None => session_manager::scan_sessions(),
+    };
+    let states = get_all_sync_states(db)?;
+    let indexed = db.indexed_session_search_paths()?;
+
+    let mut result = SessionIndexSyncResult::default();
+    let mut live = HashSet::new();
+
+    for session in &sessions {
+        let Some(source_path) = session.source_path.as_deref() else {
+            continue;
+        };
+        live.insert(source_path.to_string());
+
+        let sync_key = format!("{SEARCH_SYNC_KEY_PREFIX}{source_path}");
+        let marker = change_marker(session, source_path);
+        let (last_marker, last_offset) = match states.get(&sync_key) {
+            // 索引表被清空（如导入备份）后旧进度失效
+            Some(state) if indexed.contains(source_path) => *state,
+            _ => (0, 0),
+        };
+        if marker != 0 && marker == last_marker {
+            continue;
+        }
+
+        let messages = match session_manager::load_messages(&session.provider_id, source_path) {
+            Ok(messages) => messages,
+            Err(e) => {
+                log::warn!("[SESSION-SEARCH] 读取会话消息失败 {source_path}: {e}");
+                result.failed += 1;
+                continue;
+            }
+        };
+
+        let offset = last_offset.max(0) as usize;
+        let reset = offset > messages.len() || last_offset == 0;
+        let start = if reset { 0 } else { offset };
+        match db.index_session_messages(session, &sync_key, marker, start, &messages, reset) {
+            Ok(inserted) => {
+                result.indexed_sessions += 1;
+                result.indexed_messages += inserted;
+            }
+            Err(e) => {
+                log::warn!("[SESSION-SEARCH] 写入会话索引失败 {source_path}: {e}");
+                result.failed += 1;
+            }
+        }
+    }
+
+    result.removed = db.prune_session_search_index(provider_id, &live, SEARCH_SYNC_KEY_PREFIX)?;
+    Ok(result)
+}
+
+/// 搜索会话内容；`sync` 为 true 时先增量更新索引
+pub fn search_sessions(
+    db: &Database,
+    query: &SessionSearchQuery,
+    sync: bool,
+) -> Result<Vec<SessionSearchHit>, AppError> {
+    let Some(match_expr) = fts_match_expression(&query.text) else {
+        return Err(AppError::InvalidInput("搜索关键词不能为空".to_string()));
+    };
+    if sync {
+        sync_session_search_index(db, query.provider_id.as_deref())?;
+    }
+    db.search_session_messages(&match_expr, query)
+}
+
+/// 把用户输入转换为 FTS5 MATCH 表达式：每个词加引号转义并做前缀匹配，词之间为 AND
+pub fn fts_match_expression(input: &str) -> Option<String> {
+    let terms = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn change_marker(session: &SessionMeta, source_path: &str) -> i64 {
+    if source_path.starts_with("sqlite:") {
+        return session.last_active_at.or(session.created_at).unwrap_or(0);
+    }
+    fs::metadata(source_path)
+        .map(|metadata| metadata_modified_nanos(&metadata))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_manager::SessionMessage;
+
+    fn session(provider_id: &str, session_id: &str, project_dir: &str) -> SessionMeta {
+        SessionMeta {
+            provider_id: provider_id.to_string(),
+            session_id: session_id.to_string(),
+            title: Some(format!("{session_id} title")),
+            summary: None,
+            project_dir: Some(project_dir.to_string()),
+            created_at: Some(1_700_000_000_000),
+            last_active_at: Some(1_700_000_100_000),
+            source_path: Some(format!("/tmp/{provider_id}/{session_id}.jsonl")),
+            resume_command: None,
+        }
+    }
+
+    fn message(role: &str, content: &str) -> SessionMessage {
+        SessionMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            ts: None,
+            parts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn fts_match_expression_quotes_terms_for_prefix_match() {
+        assert_eq!(
+            fts_match_expression("  borrow checker "),
+            Some("\"borrow\"* \"checker\"*".to_string())
+        );
+        assert_eq!(
+            fts_match_expression("say \"hi\""),
+            Some("\"say\"* \"\"\"hi\"\"\"*".to_string())
+        );
+        assert_eq!(fts_match_expression("   "), None);
+    }
+
+    #[test]
+    fn index_appends_messages_incrementally_and_filters_results() {
+        let db = Database::memory().expect("memory db");
+        let claude = session("claude", "s1", "/work/alpha");
+        let codex = session("codex", "s2", "/work/beta");
+        let claude_key = format!("search:{}", claude.source_path.as_deref().unwrap());
+        let codex_key = format!("search:{}", codex.source_path.as_deref().unwrap());
+
+        let mut messages = vec![
+            message("user", "How do I fix the borrow checker error?"),
+            message("assistant", "Clone the value before moving it."),
+        ];
+        db.index_session_messages(&claude, &claude_key, 1, 0, &messages, true)
+            .expect("index claude");
+        db.index_session_messages(
+            &codex,
+            &codex_key,
+            1,
+            0,
+            &[message("user", "borrow a cup of sugar")],
+            true,
+        )
+        .expect("index codex");
+
+        messages.push(message("user", "Now the lifetime annotations fail"));
+        let inserted = db
+            .index_session_messages(&claude, &claude_key, 2, 2, &messages, false)
+            .expect("append claude");
+        assert_eq!(inserted, 1);
+
+        let expr = fts_match_expression("borrow").unwrap();
+        let hits = db
+            .search_session_messages(&expr, &SessionSearchQuery::new("borrow"))
+            .expect("search all");
+        assert_eq!(hits.len(), 2);
+
+        let mut query = SessionSearchQuery::new("borrow");
+        query.project_dir = Some("alpha".to_string());
+        let hits = db.search_session_messages(&expr, &query).expect("search");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session.provider_id, "claude");
+        assert!(hits[0].snippet.contains("«borrow»"));
+
+        let expr = fts_match_expression("lifetime").unwrap();
+        let mut query = SessionSearchQuery::new("lifetime");
+        query.provider_id = Some("claude".to_string());
+        let hits = db.search_session_messages(&expr, &query).expect("search");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_index, 2);
+
+        query.since_ms = Some(1_800_000_000_000);
+        let hits = db.search_session_messages(&expr, &query).expect("search");
+        assert!(hits.is_empty());
+
+        let live = HashSet::from([claude.source_path.clone().unwrap()]);
+        let removed = db
+            .prune_session_search_index(None, &live, SEARCH_SYNC_KEY_PREFIX)
+            .expect("prune");
+        assert_eq!(removed, 1);
+        let expr = fts_match_expression("sugar").unwrap();
+        let hits = db
+            .search_session_messages(&expr, &SessionSearchQuery::new("sugar"))
+            .expect("search");
+        assert!(hits.is_empty());
+    }
+}
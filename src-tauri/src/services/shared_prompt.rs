@@ -0,0 +1,443 @@
+//! 跨应用共享提示词
+//!
+//! 共享提示词在每个启用的应用下维护一份同 ID 的提示词副本：启用应用时创建副本并激活，
+//! 编辑共享正文或应用覆盖时同步副本（已激活的副本会重新渲染 live 文件），停用应用时移除副本。
+//! 正文中独占一行的 `{{#app claude,codex}}` 与 `{{/app}}` 之间的段落只保留给列出的应用。
+
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::prompt::{Prompt, PromptApps, SharedPrompt};
+use crate::services::prompt::get_unix_timestamp;
+use crate::services::PromptService;
+use crate::store::AppState;
+
+const APP_SECTION_START: &str = "{{#app";
+const APP_SECTION_END: &str = "{{/app}}";
+
+/// 只保留属于 `app` 的应用段落，并去掉段落标记行；没有标记时原样返回
+fn select_app_sections(content: &str, app: &AppType) -> Result<String, AppError> {
+    let mut output = String::with_capacity(content.len());
+    // Some(true) 表示处于当前应用的段落内，Some(false) 表示处于其他应用的段落内
+    let mut section: Option<bool> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let spec = trimmed
+            .strip_prefix(APP_SECTION_START)
+            .and_then(|rest| rest.strip_suffix("}}"))
+            .filter(|spec| spec.starts_with(char::is_whitespace));
+        if let Some(spec) = spec {
+            if section.is_some() {
+                return Err(AppError::InvalidInput("应用段落不能嵌套".to_string()));
+            }
+            let mut keep = false;
+            let mut listed = false;
+            for name in spec
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                keep |= AppType::from_str(name)? == *app;
+                listed = true;
+            }
+            if !listed {
+                return Err(AppError::InvalidInput(format!(
+                    "应用段落未列出应用: {trimmed}"
+                )));
+            }
+            section = Some(keep);
+            continue;
+        }
+        if trimmed == APP_SECTION_END {
+            if section.take().is_none() {
+                return Err(AppError::InvalidInput(format!(
+                    "{APP_SECTION_END} 没有对应的应用段落开始标记"
+                )));
+            }
+            continue;
+        }
+        if section != Some(false) {
+            output.push_str(line);
+        }
+    }
+
+    if section.is_some() {
+        return Err(AppError::InvalidInput(format!(
+            "应用段落缺少结束标记 {APP_SECTION_END}"
+        )));
+    }
+    Ok(output)
+}
+
+/// 一次保存需要对某个应用的副本执行的操作
+enum CopyChange {
+    /// 写入副本；`activate` 为新启用的应用，写入后激活
+    Write {
+        content: String,
+        activate: bool,
+    },
+    Remove,
+}
+
+pub struct SharedPromptService;
+
+impl SharedPromptService {
+    pub fn list(state: &AppState) -> Result<IndexMap<String, SharedPrompt>, AppError> {
+        state.db.get_shared_prompts()
+    }
+
+    pub fn get(state: &AppState, id: &str) -> Result<SharedPrompt, AppError> {
+        state
+            .db
+            .get_shared_prompts()?
+            .shift_remove(id)
+            .ok_or_else(|| AppError::InvalidInput(format!("共享提示词 {id} 不存在")))
+    }
+
+    /// 指定应用下副本的正文：取应用覆盖或共享正文，只保留该应用的段落
+    pub fn content_for(shared: &SharedPrompt, app: &AppType) -> Result<String, AppError> {
+        Ok(select_app_sections(shared.content_for(app), app)?
+            .trim_end()
+            .to_string())
+    }
+
+    /// 创建共享提示词，并在 `apps` 中创建副本并激活
+    pub fn create(
+        state: &AppState,
+        id: Option<&str>,
+        name: &str,
+        description: Option<&str>,
+        content: &str,
+        apps: &[AppType],
+    ) -> Result<SharedPrompt, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("提示词名称不能为空".to_string()));
+        }
+
+        let existing = state.db.get_shared_prompts()?;
+        let id = match id {
+            Some(id) => {
+                let id = id.trim();
+                PromptService::validate_prompt_id(id)?;
+                if existing.contains_key(id) {
+                    return Err(AppError::InvalidInput(format!("共享提示词 {id} 已存在")));
+                }
+                id.to_string()
+            }
+            None => {
+                let ids = existing.keys().cloned().collect::<Vec<_>>();
+                PromptService::generate_prompt_id(name, &ids)
+            }
+        };
+
+        let mut enabled = PromptApps::default();
+        for app in apps {
+            enabled.set_enabled_for(app, true);
+        }
+        let timestamp = get_unix_timestamp()?;
+        let shared = SharedPrompt {
+            id,
+            name: name.to_string(),
+            content: content.trim_end().to_string(),
+            description: description
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            apps: enabled,
+            overrides: IndexMap::new(),
+            created_at: Some(timestamp),
+            updated_at: Some(timestamp),
+        };
+
+        Self::save(state, None, &shared)?;
+        Ok(shared)
+    }
+
+    /// 修改共享正文，同步到所有启用应用的副本
+    pub fn update_content(
+        state: &AppState,
+        id: &str,
+        content: &str,
+    ) -> Result<SharedPrompt, AppError> {
+        let previous = Self::get(state, id)?;
+        let mut shared = previous.clone();
+        shared.content = content.trim_end().to_string();
+        shared.updated_at = Some(get_unix_timestamp()?);
+        Self::save(state, Some(&previous), &shared)?;
+        Ok(shared)
+    }
+
+    /// 设置或清除（`None`）某个应用的正文覆盖
+    pub fn set_override(
+        state: &AppState,
+        id: &str,
+        app: &AppType,
+        content: Option<&str>,
+    ) -> Result<SharedPrompt, AppError> {
+        let previous = Self::get(state, id)?;
+        let mut shared = previous.clone();
+        match content {
+            Some(content) => {
+                shared
+                    .overrides
+                    .insert(app.as_str().to_string(), content.trim_end().to_string());
+            }
+            None => {
+                shared.overrides.shift_remove(app.as_str());
+            }
+        }
+        shared.updated_at = Some(get_unix_timestamp()?);
+        Self::save(state, Some(&previous), &shared)?;
+        Ok(shared)
+    }
+
+    /// 在指定应用中启用（创建副本并激活）或停用（移除副本）共享提示词
+    pub fn set_apps_enabled(
+        state: &AppState,
+        id: &str,
+        apps: &[AppType],
+        enabled: bool,
+    ) -> Result<SharedPrompt, AppError> {
+        let previous = Self::get(state, id)?;
+        let mut shared = previous.clone();
+        for app in apps {
+            shared.apps.set_enabled_for(app, enabled);
+        }
+        Self::save(state, Some(&previous), &shared)?;
+        Ok(shared)
+    }
+
+    /// 删除共享提示词及其在各应用下的副本
+    pub fn delete(state: &AppState, id: &str) -> Result<(), AppError> {
+        let previous = Self::get(state, id)?;
+        let mut shared = previous.clone();
+        shared.apps = PromptApps::default();
+        Self::save(state, Some(&previous), &shared)?;
+        state.db.delete_shared_prompt(id)
+    }
+
+    /// 保存共享提示词并同步各应用下的副本；先完成全部校验，再写入
+    fn save(
+        state: &AppState,
+        previous: Option<&SharedPrompt>,
+        shared: &SharedPrompt,
+    ) -> Result<(), AppError> {
+        let mut changes = Vec::new();
+        for app in AppType::all() {
+            let was_enabled = previous.is_some_and(|prev| prev.apps.is_enabled_for(&app));
+            let prompts = state.db.get_prompts(app.as_str())?;
+
+            if shared.apps.is_enabled_for(&app) {
+                if !was_enabled && prompts.contains_key(&shared.id) {
+                    return Err(AppError::InvalidInput(format!(
+                        "{} 下已存在 ID 为 {} 的提示词",
+                        app.as_str(),
+                        shared.id
+                    )));
+                }
+                let content = Self::content_for(shared, &app)?;
+                changes.push((
+                    app,
+                    CopyChange::Write {
+                        content,
+                        activate: !was_enabled,
+                    },
+                ));
+            } else if was_enabled {
+                let users = prompts
+                    .values()
+                    .filter(|prompt| prompt.fragments.contains(&shared.id))
+                    .map(|prompt| prompt.id.as_str())
+                    .collect::<Vec<_>>();
+                if !users.is_empty() {
+                    return Err(AppError::InvalidInput(format!(
+                        "提示词 {} 在 {} 中被用作片段: {}",
+                        shared.id,
+                        app.as_str(),
+                        users.join(", ")
+                    )));
+                }
+                changes.push((app, CopyChange::Remove));
+            }
+        }
+
+        state.db.save_shared_prompt(shared)?;
+
+        for (app, change) in changes {
+            let existing = state.db.get_prompts(app.as_str())?.shift_remove(&shared.id);
+            match change {
+                CopyChange::Write { content, activate } => {
+                    let copy = Prompt {
+                        id: shared.id.clone(),
+                        name: shared.name.clone(),
+                        content,
+                        description: shared.description.clone(),
+                        enabled: existing.as_ref().is_some_and(|prompt| prompt.enabled),
+                        created_at: existing
+                            .as_ref()
+                            .and_then(|prompt| prompt.created_at)
+                            .or(shared.created_at),
+                        updated_at: shared.updated_at,
+                        fragments: existing.map(|prompt| prompt.fragments).unwrap_or_default(),
+                    };
+                    PromptService::upsert_prompt(state, app.clone(), &shared.id, copy)?;
+                    if activate {
+                        PromptService::enable_prompt(state, app, &shared.id)?;
+                    }
+                }
+                CopyChange::Remove => {
+                    let Some(existing) = existing else {
+                        continue;
+                    };
+                    if existing.enabled {
+                        PromptService::disable_prompt(state, app.clone(), &shared.id)?;
+                    }
+                    PromptService::delete_prompt(state, app, &shared.id)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::MultiAppConfig;
+    use crate::database::Database;
+    use crate::prompt_files::prompt_file_path;
+    use crate::services::ProxyService;
+    use serial_test::serial;
+    use std::sync::{Arc, RwLock};
+
+    fn state() -> AppState {
+        let db = Arc::new(Database::memory().expect("memory db"));
+        AppState {
+            proxy_service: ProxyService::new(db.clone()),
+            db,
+            config: RwLock::new(MultiAppConfig::default()),
+        }
+    }
+
+    #[test]
+    fn app_sections_keep_only_the_listed_apps() {
+        let content = "Shared rules.\n{{#app claude}}\nUse /compact.\n{{/app}}\n{{#app codex, gemini}}\nKeep diffs small.\n{{/app}}\nEnd.";
+
+        assert_eq!(
+            select_app_sections(content, &AppType::Claude).expect("claude"),
+            "Shared rules.\nUse /compact.\nEnd."
+        );
+        assert_eq!(
+            select_app_sections(content, &AppType::Gemini).expect("gemini"),
+            "Shared rules.\nKeep diffs small.\nEnd."
+        );
+        assert_eq!(
+            select_app_sections("{{#apple}}\n", &AppType::Claude).expect("not a marker"),
+            "{{#apple}}\n"
+        );
+        assert!(select_app_sections("{{#app claude}}\nopen", &AppType::Claude).is_err());
+        assert!(select_app_sections("{{#app vim}}\n{{/app}}", &AppType::Claude).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn shared_prompt_is_activated_synced_and_removed_per_app() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let _env = crate::test_support::TestEnvGuard::isolated(temp.path());
+        let state = state();
+
+        let shared = SharedPromptService::create(
+            &state,
+            Some("team"),
+            "Team",
+            None,
+            "Be brief.\n{{#app codex}}\nRun cargo fmt.\n{{/app}}",
+            &[AppType::Claude, AppType::Codex],
+        )
+        .expect("create shared prompt");
+        let claude_file = prompt_file_path(&AppType::Claude).expect("claude path");
+        let codex_file = prompt_file_path(&AppType::Codex).expect("codex path");
+        assert_eq!(
+            std::fs::read_to_string(&claude_file).expect("read"),
+            "Be brief."
+        );
+        assert_eq!(
+            std::fs::read_to_string(&codex_file).expect("read"),
+            "Be brief.\nRun cargo fmt."
+        );
+
+        SharedPromptService::set_override(
+            &state,
+            &shared.id,
+            &AppType::Claude,
+            Some("Be brief, Claude."),
+        )
+        .expect("override claude");
+        SharedPromptService::update_content(&state, &shared.id, "Be concise.")
+            .expect("update content");
+        assert_eq!(
+            std::fs::read_to_string(&claude_file).expect("read"),
+            "Be brief, Claude."
+        );
+        assert_eq!(
+            std::fs::read_to_string(&codex_file).expect("read"),
+            "Be concise."
+        );
+
+        let err = PromptService::delete_prompt(&state, AppType::Codex, "team")
+            .expect_err("copy is managed by the shared prompt");
+        assert!(err.to_string().contains("共享提示词"), "{err}");
+
+        SharedPromptService::set_apps_enabled(&state, &shared.id, &[AppType::Codex], false)
+            .expect("disable codex");
+        assert!(!state
+            .db
+            .get_prompts("codex")
+            .expect("codex prompts")
+            .contains_key("team"));
+        assert_eq!(std::fs::read_to_string(&codex_file).expect("read"), "");
+
+        SharedPromptService::delete(&state, &shared.id).expect("delete");
+        assert!(state.db.get_prompts("claude").expect("prompts").is_empty());
+        assert!(SharedPromptService::list(&state).expect("list").is_empty());
+    }
+
+    #[test]
+    fn enabling_an_app_with_a_same_id_prompt_is_rejected() {
+        let state = state();
+        state
+            .db
+            .save_prompt(
+                "gemini",
+                &Prompt {
+                    id: "team".to_string(),
+                    name: "Team".to_string(),
+                    content: "Local rules.".to_string(),
+                    description: None,
+                    enabled: false,
+                    created_at: Some(1),
+                    updated_at: Some(1),
+                    fragments: Vec::new(),
+                },
+            )
+            .expect("seed prompt");
+
+        let err = SharedPromptService::create(
+            &state,
+            Some("team"),
+            "Team",
+            None,
+            "Shared.",
+            &[AppType::Gemini],
+        )
+        .expect_err("id collision");
+        assert!(err.to_string().contains("gemini"), "{err}");
+        assert!(SharedPromptService::list(&state).expect("list").is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use crate::{app_config::AppType, error::AppError, provider::Provider};
+use crate::{app_config::AppType, database::Database, error::AppError, provider::Provider};
 
 use super::types::{HealthStatus, StreamCheckConfig, StreamCheckResult};
 
@@ -8,6 +8,17 @@ use super::types::{HealthStatus, StreamCheckConfig, StreamCheckResult};
 pub struct StreamCheckService;
 
 impl StreamCheckService {
+    /// 解密保险库中的密钥后执行流式健康检查（带重试）
+    pub async fn check_provider(
+        db: &Database,
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
+    ) -> Result<StreamCheckResult, AppError> {
+        let provider = crate::secret_vault::reveal_provider(db, provider)?;
+        Self::check_with_retry(app_type, &provider, config).await
+    }
+
     /// 执行流式健康检查（带重试）
     pub async fn check_with_retry(
         app_type: &AppType,
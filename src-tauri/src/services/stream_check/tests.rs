@@ -293,3 +293,71 @@ fn stream_check_preserves_claude_gemini_native_opaque_full_url() {
 
     assert_eq!(url, "https://relay.example/custom/generate-content?alt=sse");
 }
+
+#[tokio::test]
+async fn stream_check_reveals_sealed_provider_keys() {
+    use std::sync::{Arc, Mutex};
+
+    let seen_keys = Arc::new(Mutex::new(Vec::<String>::new()));
+    let recorder = seen_keys.clone();
+    let app = axum::Router::new().fallback(move |headers: axum::http::HeaderMap| {
+        let recorder = recorder.clone();
+        async move {
+            for name in ["x-api-key", "authorization"] {
+                if let Some(value) = headers.get(name).and_then(|value| value.to_str().ok()) {
+                    recorder.lock().unwrap().push(value.to_string());
+                }
+            }
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        }
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let db = crate::Database::memory().unwrap();
+    let (vault, _) = crate::secret_vault::create_vault(
+        crate::secret_vault::VaultKeySource::KeyFile,
+        None,
+        Some(&dir.path().join("vault.key")),
+    )
+    .unwrap();
+    db.set_secret_vault_config(&vault).unwrap();
+    db.save_provider(
+        "claude",
+        &Provider::with_id(
+            "sealed".to_string(),
+            "Sealed".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_BASE_URL": format!("http://{address}"),
+                "ANTHROPIC_AUTH_TOKEN": "sk-sealed-key",
+            }}),
+            None,
+        ),
+    )
+    .unwrap();
+    let provider = db.get_all_providers("claude").unwrap()["sealed"].clone();
+    assert!(provider.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"]
+        .as_str()
+        .unwrap()
+        .starts_with(crate::secret_vault::VAULT_REF_PREFIX));
+
+    let config = StreamCheckConfig {
+        max_retries: 0,
+        ..Default::default()
+    };
+    // 上游返回 503，检查结果本身不重要，只关心发出的凭据
+    let _ = StreamCheckService::check_provider(&db, &AppType::Claude, &provider, &config).await;
+
+    let seen_keys = seen_keys.lock().unwrap();
+    assert!(!seen_keys.is_empty(), "upstream was not called");
+    assert!(
+        seen_keys
+            .iter()
+            .all(|value| value.contains("sk-sealed-key")),
+        "{seen_keys:?}"
+    );
+}
@@ -96,11 +96,26 @@ pub struct ModelStats {
     pub avg_cost_per_request: String,
 }
 
+/// 代理客户端令牌统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientTokenStats {
+    pub client_token: String,
+    pub request_count: u64,
+    pub total_tokens: u64,
+    pub total_cost: String,
+    pub last_used_at: i64,
+}
+
 /// 请求日志过滤器
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LogFilters {
     pub app_type: Option<String>,
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub client_token: Option<String>,
     pub provider_name: Option<String>,
     pub model: Option<String>,
     pub status_code: Option<u16>,
@@ -108,6 +123,42 @@ pub struct LogFilters {
     pub end_date: Option<i64>,
 }
 
+/// 统计查询范围：按应用、供应商与模型过滤，未设置的维度不过滤
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UsageScope<'a> {
+    pub app_type: Option<&'a str>,
+    pub provider_id: Option<&'a str>,
+    pub model: Option<&'a str>,
+}
+
+impl<'a> UsageScope<'a> {
+    pub fn for_app(app_type: &'a str) -> Self {
+        Self {
+            app_type: Some(app_type),
+            ..Self::default()
+        }
+    }
+
+    /// 追加过滤条件；`prefix` 为列名前缀（如 "l."），rollup 表与明细表列名一致
+    fn push_filters(
+        &self,
+        conditions: &mut Vec<String>,
+        params: &mut Vec<Box<dyn rusqlite::ToSql>>,
+        prefix: &str,
+    ) {
+        for (column, value) in [
+            ("app_type", self.app_type),
+            ("provider_id", self.provider_id),
+            ("model", self.model),
+        ] {
+            if let Some(value) = value {
+                conditions.push(format!("{prefix}{column} = ?"));
+                params.push(Box::new(value.to_string()));
+            }
+        }
+    }
+}
+
 /// 分页请求日志响应
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -149,17 +200,20 @@ pub struct RequestLogDetail {
     pub created_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_source: Option<String>,
+    /// 发起请求的代理客户端令牌名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_token: Option<String>,
 }
 
-/// 把 24 列的查询结果映射为 `RequestLogDetail`。
+/// 把 25 列的查询结果映射为 `RequestLogDetail`。
 ///
-/// 调用方的 SELECT **必须**按以下顺序返回 24 列：
+/// 调用方的 SELECT **必须**按以下顺序返回 25 列：
 /// `request_id, provider_id, provider_name, app_type, model, request_model,
 ///  cost_multiplier, input_tokens, output_tokens, cache_read_tokens,
 ///  cache_creation_tokens, input_cost_usd, output_cost_usd, cache_read_cost_usd,
 ///  cache_creation_cost_usd, total_cost_usd, is_streaming, latency_ms,
 ///  first_token_ms, duration_ms, status_code, error_message, created_at,
-///  data_source`
+///  data_source, client_token`
 ///
 /// 不需要 provider_name 时（如 backfill）SELECT `NULL AS provider_name` 占位即可。
 fn row_to_request_log_detail(row: &rusqlite::Row<'_>) -> rusqlite::Result<RequestLogDetail> {
@@ -190,6 +244,7 @@ fn row_to_request_log_detail(row: &rusqlite::Row<'_>) -> rusqlite::Result<Reques
         error_message: row.get(21)?,
         created_at: row.get(22)?,
         data_source: row.get(23)?,
+        client_token: row.get(24)?,
     })
 }
 
@@ -437,7 +492,7 @@ impl Database {
         &self,
         start_date: Option<i64>,
         end_date: Option<i64>,
-        app_type: Option<&str>,
+        scope: &UsageScope<'_>,
     ) -> Result<UsageSummary, AppError> {
         let conn = lock_conn!(self.conn);
 
@@ -453,10 +508,7 @@ impl Database {
             conditions.push("l.created_at <= ?".to_string());
             params_vec.push(Box::new(end));
         }
-        if let Some(at) = app_type {
-            conditions.push("l.app_type = ?".to_string());
-            params_vec.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut conditions, &mut params_vec, "l.");
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -475,10 +527,7 @@ impl Database {
             "date",
             &rollup_bounds,
         );
-        if let Some(at) = app_type {
-            rollup_conditions.push("app_type = ?".to_string());
-            rollup_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut rollup_conditions, &mut rollup_params, "");
 
         let rollup_where = if rollup_conditions.is_empty() {
             String::new()
@@ -702,7 +751,7 @@ impl Database {
         &self,
         start_date: Option<i64>,
         end_date: Option<i64>,
-        app_type: Option<&str>,
+        scope: &UsageScope<'_>,
     ) -> Result<Vec<DailyStats>, AppError> {
         let conn = lock_conn!(self.conn);
 
@@ -726,11 +775,13 @@ impl Database {
                 bucket_count = 1;
             }
 
-            let app_type_filter = if app_type.is_some() {
-                "AND l.app_type = ?4"
-            } else {
-                ""
-            };
+            let mut scope_conditions = Vec::new();
+            let mut scope_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+            scope.push_filters(&mut scope_conditions, &mut scope_params, "l.");
+            let scope_filter: String = scope_conditions
+                .iter()
+                .map(|condition| format!(" AND {condition}"))
+                .collect();
 
             let effective_filter = effective_usage_log_filter("l");
             let fresh_input = fresh_input_sql("l");
@@ -746,7 +797,7 @@ impl Database {
                     COALESCE(SUM(l.cache_read_tokens), 0) as total_cache_read_tokens
                 FROM proxy_request_logs l
                 WHERE l.created_at >= ?1 AND l.created_at <= ?2
-                  AND {effective_filter} {app_type_filter}
+                  AND {effective_filter}{scope_filter}
                 GROUP BY bucket_idx
                 ORDER BY bucket_idx ASC"
             );
@@ -770,11 +821,15 @@ impl Database {
 
             let mut map: HashMap<i64, DailyStats> = HashMap::new();
 
-            let rows = if let Some(at) = app_type {
-                stmt.query_map(params![start_ts, end_ts, bucket_seconds, at], row_mapper)?
-            } else {
-                stmt.query_map(params![start_ts, end_ts, bucket_seconds], row_mapper)?
-            };
+            let mut query_params: Vec<Box<dyn rusqlite::ToSql>> = vec![
+                Box::new(start_ts),
+                Box::new(end_ts),
+                Box::new(bucket_seconds),
+            ];
+            query_params.extend(scope_params);
+            let param_refs: Vec<&dyn rusqlite::ToSql> =
+                query_params.iter().map(|param| param.as_ref()).collect();
+            let rows = stmt.query_map(param_refs.as_slice(), row_mapper)?;
             for row in rows {
                 let (mut bucket_idx, stat) = row?;
                 if bucket_idx < 0 {
@@ -816,11 +871,13 @@ impl Database {
         let end_day = local_datetime_from_timestamp(end_ts)?.date_naive();
         let bucket_count = (end_day.signed_duration_since(start_day).num_days() + 1) as usize;
 
-        let app_type_filter = if app_type.is_some() {
-            "AND l.app_type = ?3"
-        } else {
-            ""
-        };
+        let mut scope_conditions = Vec::new();
+        let mut scope_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        scope.push_filters(&mut scope_conditions, &mut scope_params, "l.");
+        let scope_filter: String = scope_conditions
+            .iter()
+            .map(|condition| format!(" AND {condition}"))
+            .collect();
 
         let effective_filter = effective_usage_log_filter("l");
         let fresh_input = fresh_input_sql("l");
@@ -836,7 +893,7 @@ impl Database {
                 COALESCE(SUM(l.cache_read_tokens), 0) as total_cache_read_tokens
             FROM proxy_request_logs l
             WHERE l.created_at >= ?1 AND l.created_at <= ?2
-              AND {effective_filter} {app_type_filter}
+              AND {effective_filter}{scope_filter}
             GROUP BY bucket_date
             ORDER BY bucket_date ASC"
         );
@@ -859,11 +916,12 @@ impl Database {
         };
 
         let mut map: HashMap<NaiveDate, DailyStats> = HashMap::new();
-        let detail_rows = if let Some(at) = app_type {
-            detail_stmt.query_map(params![start_ts, end_ts, at], detail_row_mapper)?
-        } else {
-            detail_stmt.query_map(params![start_ts, end_ts], detail_row_mapper)?
-        };
+        let mut detail_params: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(start_ts), Box::new(end_ts)];
+        detail_params.extend(scope_params);
+        let detail_param_refs: Vec<&dyn rusqlite::ToSql> =
+            detail_params.iter().map(|param| param.as_ref()).collect();
+        let detail_rows = detail_stmt.query_map(detail_param_refs.as_slice(), detail_row_mapper)?;
 
         for row in detail_rows {
             let (bucket_date, stat) = row?;
@@ -881,10 +939,7 @@ impl Database {
             "date",
             &rollup_bounds,
         );
-        if let Some(at) = app_type {
-            rollup_conditions.push("app_type = ?".to_string());
-            rollup_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut rollup_conditions, &mut rollup_params, "");
 
         let rollup_where = if rollup_conditions.is_empty() {
             String::new()
@@ -984,7 +1039,7 @@ impl Database {
         &self,
         start_date: Option<i64>,
         end_date: Option<i64>,
-        app_type: Option<&str>,
+        scope: &UsageScope<'_>,
     ) -> Result<Vec<ProviderStats>, AppError> {
         let conn = lock_conn!(self.conn);
 
@@ -998,10 +1053,7 @@ impl Database {
             detail_conditions.push("l.created_at <= ?".to_string());
             detail_params.push(Box::new(end));
         }
-        if let Some(at) = app_type {
-            detail_conditions.push("l.app_type = ?".to_string());
-            detail_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut detail_conditions, &mut detail_params, "l.");
         let detail_where = if detail_conditions.is_empty() {
             String::new()
         } else {
@@ -1017,10 +1069,7 @@ impl Database {
             "r.date",
             &rollup_bounds,
         );
-        if let Some(at) = app_type {
-            rollup_conditions.push("r.app_type = ?".to_string());
-            rollup_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut rollup_conditions, &mut rollup_params, "r.");
         let rollup_where = if rollup_conditions.is_empty() {
             String::new()
         } else {
@@ -1110,7 +1159,7 @@ impl Database {
         &self,
         start_date: Option<i64>,
         end_date: Option<i64>,
-        app_type: Option<&str>,
+        scope: &UsageScope<'_>,
     ) -> Result<Vec<ModelStats>, AppError> {
         let conn = lock_conn!(self.conn);
 
@@ -1124,10 +1173,7 @@ impl Database {
             detail_conditions.push("l.created_at <= ?".to_string());
             detail_params.push(Box::new(end));
         }
-        if let Some(at) = app_type {
-            detail_conditions.push("l.app_type = ?".to_string());
-            detail_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut detail_conditions, &mut detail_params, "l.");
         let detail_where = if detail_conditions.is_empty() {
             String::new()
         } else {
@@ -1143,10 +1189,7 @@ impl Database {
             "r.date",
             &rollup_bounds,
         );
-        if let Some(at) = app_type {
-            rollup_conditions.push("r.app_type = ?".to_string());
-            rollup_params.push(Box::new(at.to_string()));
-        }
+        scope.push_filters(&mut rollup_conditions, &mut rollup_params, "r.");
         let rollup_where = if rollup_conditions.is_empty() {
             String::new()
         } else {
@@ -1215,6 +1258,65 @@ impl Database {
         Ok(stats)
     }
 
+    /// 按代理客户端令牌汇总用量
+    ///
+    /// 令牌名称只记录在明细日志中，已归档到 usage_daily_rollups 的数据不参与统计。
+    pub fn get_client_token_stats(
+        &self,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+    ) -> Result<Vec<ClientTokenStats>, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let mut conditions = vec![
+            effective_usage_log_filter("l"),
+            "l.client_token IS NOT NULL".to_string(),
+        ];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(start) = start_date {
+            conditions.push("l.created_at >= ?".to_string());
+            params.push(Box::new(start));
+        }
+        if let Some(end) = end_date {
+            conditions.push("l.created_at <= ?".to_string());
+            params.push(Box::new(end));
+        }
+
+        let fresh_input = fresh_input_sql("l");
+        let sql = format!(
+            "SELECT l.client_token,
+                COUNT(*) as request_count,
+                COALESCE(SUM({fresh_input} + l.output_tokens), 0) as total_tokens,
+                COALESCE(SUM(CAST(l.total_cost_usd AS REAL)), 0) as total_cost,
+                MAX(l.created_at) as last_used_at
+            FROM proxy_request_logs l
+            WHERE {}
+            GROUP BY l.client_token
+            ORDER BY total_cost DESC",
+            conditions.join(" AND ")
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            let total_cost: f64 = row.get(3)?;
+            Ok(ClientTokenStats {
+                client_token: row.get(0)?,
+                request_count: row.get::<_, i64>(1)? as u64,
+                total_tokens: row.get::<_, i64>(2)? as u64,
+                total_cost: format!("{total_cost:.6}"),
+                last_used_at: row.get(4)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+
+        Ok(stats)
+    }
+
     /// 获取请求日志列表（分页）
     pub fn get_request_logs(
         &self,
@@ -1231,6 +1333,14 @@ impl Database {
             conditions.push("l.app_type = ?".to_string());
             params.push(Box::new(app_type.clone()));
         }
+        if let Some(ref provider_id) = filters.provider_id {
+            conditions.push("l.provider_id = ?".to_string());
+            params.push(Box::new(provider_id.clone()));
+        }
+        if let Some(ref client_token) = filters.client_token {
+            conditions.push("l.client_token = ?".to_string());
+            params.push(Box::new(client_token.clone()));
+        }
         if let Some(ref provider_name) = filters.provider_name {
             conditions.push("p.name LIKE ?".to_string());
             params.push(Box::new(format!("%{provider_name}%")));
@@ -1281,7 +1391,7 @@ impl Database {
                     l.input_tokens, l.output_tokens, l.cache_read_tokens, l.cache_creation_tokens,
                     l.input_cost_usd, l.output_cost_usd, l.cache_read_cost_usd, l.cache_creation_cost_usd, l.total_cost_usd,
                     l.is_streaming, l.latency_ms, l.first_token_ms, l.duration_ms,
-                    l.status_code, l.error_message, l.created_at, l.data_source
+                    l.status_code, l.error_message, l.created_at, l.data_source, l.client_token
              FROM proxy_request_logs l
              LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
              {where_clause}
@@ -1324,7 +1434,7 @@ impl Database {
                     input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens,
                     input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd, total_cost_usd,
                     is_streaming, latency_ms, first_token_ms, duration_ms,
-                    status_code, error_message, created_at, l.data_source
+                    status_code, error_message, created_at, l.data_source, l.client_token
              FROM proxy_request_logs l
              LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
              WHERE l.request_id = ?"
@@ -1480,7 +1590,7 @@ impl Database {
                         input_cost_usd, output_cost_usd, cache_read_cost_usd,
                         cache_creation_cost_usd, total_cost_usd, is_streaming, latency_ms,
                         first_token_ms, duration_ms, status_code, error_message, created_at,
-                        data_source
+                        data_source, client_token
              FROM proxy_request_logs
              WHERE CAST(total_cost_usd AS REAL) <= 0
                AND (input_tokens > 0 OR output_tokens > 0
@@ -2306,13 +2416,94 @@ mod tests {
             )?;
         }
 
-        let summary = db.get_usage_summary(None, None, None)?;
+        let summary = db.get_usage_summary(None, None, &UsageScope::default())?;
         assert_eq!(summary.total_requests, 2);
         assert_eq!(summary.success_rate, 100.0);
 
         Ok(())
     }
 
+    #[test]
+    fn test_usage_scope_filters_provider_and_model() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            insert_usage_log(
+                &conn, "a1", "claude", "relay-a", "sonnet", "proxy", 1_000, 100, 10, 0, 0, 200,
+                "0.10",
+            )?;
+            insert_usage_log(
+                &conn, "a2", "claude", "relay-a", "opus", "proxy", 2_000, 100, 10, 0, 0, 200,
+                "0.50",
+            )?;
+            insert_usage_log(
+                &conn, "b1", "claude", "relay-b", "sonnet", "proxy", 3_000, 100, 10, 0, 0, 200,
+                "0.20",
+            )?;
+        }
+
+        let relay_a = UsageScope {
+            provider_id: Some("relay-a"),
+            ..UsageScope::for_app("claude")
+        };
+        let summary = db.get_usage_summary(None, None, &relay_a)?;
+        assert_eq!(summary.total_requests, 2);
+        assert_eq!(summary.total_cost, "0.600000");
+
+        let sonnet = UsageScope {
+            model: Some("sonnet"),
+            ..UsageScope::default()
+        };
+        let trends = db.get_daily_trends(Some(0), Some(40_000), &sonnet)?;
+        assert_eq!(trends.iter().map(|stat| stat.request_count).sum::<u64>(), 2);
+
+        let providers = db.get_provider_stats(None, None, &sonnet)?;
+        assert_eq!(providers.len(), 2);
+        let models = db.get_model_stats(None, None, &relay_a)?;
+        assert_eq!(models.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_token_stats_and_log_filter() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            for (id, client, cost) in [
+                ("t1", Some("alice"), "0.10"),
+                ("t2", Some("alice"), "0.30"),
+                ("t3", Some("bob"), "0.05"),
+                ("t4", None, "1.00"),
+            ] {
+                insert_usage_log(
+                    &conn, id, "claude", "relay-a", "sonnet", "proxy", 1_000, 100, 10, 0, 0, 200,
+                    cost,
+                )?;
+                conn.execute(
+                    "UPDATE proxy_request_logs SET client_token = ?1 WHERE request_id = ?2",
+                    rusqlite::params![client, id],
+                )?;
+            }
+        }
+
+        let stats = db.get_client_token_stats(None, None)?;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].client_token, "alice");
+        assert_eq!(stats[0].request_count, 2);
+        assert_eq!(stats[0].total_cost, "0.400000");
+
+        let filters = LogFilters {
+            client_token: Some("bob".to_string()),
+            ..LogFilters::default()
+        };
+        let logs = db.get_request_logs(&filters, 0, 10)?;
+        assert_eq!(logs.total, 1);
+        assert_eq!(logs.data[0].client_token.as_deref(), Some("bob"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_usage_summary_excludes_partial_rollup_boundary_days() -> Result<(), AppError> {
         let db = Database::memory()?;
@@ -2386,7 +2577,8 @@ mod tests {
             )?;
         }
 
-        let summary = db.get_usage_summary(Some(start), Some(end), Some("claude"))?;
+        let summary =
+            db.get_usage_summary(Some(start), Some(end), &UsageScope::for_app("claude"))?;
         assert_eq!(summary.total_requests, 20);
         assert_eq!(summary.total_input_tokens, 2000);
         assert_eq!(summary.total_output_tokens, 1000);
@@ -2447,7 +2639,8 @@ mod tests {
             )?;
         }
 
-        let summary = db.get_usage_summary(Some(start), Some(end), Some("claude"))?;
+        let summary =
+            db.get_usage_summary(Some(start), Some(end), &UsageScope::for_app("claude"))?;
         assert_eq!(summary.total_requests, 30);
         assert_eq!(summary.total_input_tokens, 3000);
         assert_eq!(summary.total_output_tokens, 1500);
@@ -2568,7 +2761,7 @@ mod tests {
             )?;
         }
 
-        let summary = db.get_usage_summary(None, None, None)?;
+        let summary = db.get_usage_summary(None, None, &UsageScope::default())?;
         assert_eq!(summary.total_requests, 4);
         // codex-proxy contributes 100-10=90; gemini-proxy contributes 200-30=170
         // (both cache-inclusive providers). claude-proxy=300, codex-session-only=50.
@@ -2583,10 +2776,10 @@ mod tests {
         let expected_hit_rate = 60.0_f64 / 682.0_f64;
         assert!((summary.cache_hit_rate - expected_hit_rate).abs() < 1e-9);
 
-        let trends = db.get_daily_trends(Some(0), Some(40_000), None)?;
+        let trends = db.get_daily_trends(Some(0), Some(40_000), &UsageScope::default())?;
         assert_eq!(trends.iter().map(|stat| stat.request_count).sum::<u64>(), 4);
 
-        let provider_stats = db.get_provider_stats(None, None, None)?;
+        let provider_stats = db.get_provider_stats(None, None, &UsageScope::default())?;
         assert_eq!(
             provider_stats
                 .iter()
@@ -2604,7 +2797,7 @@ mod tests {
             .iter()
             .any(|stat| stat.provider_id == "_session"));
 
-        let model_stats = db.get_model_stats(None, None, None)?;
+        let model_stats = db.get_model_stats(None, None, &UsageScope::default())?;
         assert_eq!(
             model_stats
                 .iter()
@@ -2796,7 +2989,7 @@ mod tests {
             )?;
         }
 
-        let summary = db.get_usage_summary(None, None, None)?;
+        let summary = db.get_usage_summary(None, None, &UsageScope::default())?;
         assert_eq!(summary.total_requests, 9);
 
         let logs = db.get_request_logs(&LogFilters::default(), 0, 10)?;
@@ -2844,7 +3037,7 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_model_stats(None, None, None)?;
+        let stats = db.get_model_stats(None, None, &UsageScope::default())?;
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].model, "claude-3-sonnet");
         assert_eq!(stats[0].request_count, 1);
@@ -2876,7 +3069,8 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_provider_stats(Some(1500), Some(2500), Some("claude"))?;
+        let stats =
+            db.get_provider_stats(Some(1500), Some(2500), &UsageScope::for_app("claude"))?;
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].provider_id, "p1");
         assert_eq!(stats[0].request_count, 1);
@@ -2908,7 +3102,7 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_provider_stats(None, None, Some("opencode"))?;
+        let stats = db.get_provider_stats(None, None, &UsageScope::for_app("opencode"))?;
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].provider_id, "_opencode_session");
         assert_eq!(stats[0].provider_name, "OpenCode (Session)");
@@ -2989,7 +3183,8 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_provider_stats(Some(start), Some(end), Some("claude"))?;
+        let stats =
+            db.get_provider_stats(Some(start), Some(end), &UsageScope::for_app("claude"))?;
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].provider_id, "p-rollup");
         assert_eq!(stats[0].request_count, 8);
@@ -3025,7 +3220,8 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_daily_trends(Some(0), Some(15 * 60 * 60), Some("claude"))?;
+        let stats =
+            db.get_daily_trends(Some(0), Some(15 * 60 * 60), &UsageScope::for_app("claude"))?;
         assert_eq!(stats.len(), 15);
         assert_eq!(stats[3].request_count, 1);
 
@@ -3102,7 +3298,7 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_daily_trends(Some(start), Some(end), Some("claude"))?;
+        let stats = db.get_daily_trends(Some(start), Some(end), &UsageScope::for_app("claude"))?;
         assert_eq!(stats.len(), 3);
         assert_eq!(stats[0].request_count, 1);
         assert_eq!(stats[0].total_tokens, 150);
@@ -3187,7 +3383,7 @@ mod tests {
             )?;
         }
 
-        let stats = db.get_model_stats(Some(start), Some(end), Some("claude"))?;
+        let stats = db.get_model_stats(Some(start), Some(end), &UsageScope::for_app("claude"))?;
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].model, "claude-3-haiku");
         assert_eq!(stats[0].request_count, 9);
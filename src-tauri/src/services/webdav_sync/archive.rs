@@ -1,10 +1,11 @@
 //! Skills ZIP 打包 / 解压 + 备份回滚
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
+use sha2::{Digest, Sha256};
 use tempfile::{tempdir, TempDir};
 use zip::{write::SimpleFileOptions, DateTime};
 
@@ -221,19 +222,146 @@ pub fn zip_dir_recursive(
 // ---------------------------------------------------------------------------
 
 pub fn restore_skills_zip(raw: &[u8]) -> Result<(), AppError> {
-    let tmp = tempdir().map_err(|e| {
+    let tmp = extract_tmpdir()?;
+    let extracted = extract_skills_zip(raw, &tmp, None)?;
+
+    let ssot = SkillService::get_ssot_dir()?;
+    let bak = ssot.with_extension("bak");
+
+    // 原子替换：先 rename 到 .bak，再 copy，失败则回滚
+    if ssot.exists() {
+        if bak.exists() {
+            let _ = fs::remove_dir_all(&bak);
+        }
+        fs::rename(&ssot, &bak).map_err(|e| AppError::io(&ssot, e))?;
+    }
+
+    if let Err(e) = copy_dir_recursive(&extracted, &ssot) {
+        if bak.exists() {
+            let _ = fs::remove_dir_all(&ssot);
+            let _ = fs::rename(&bak, &ssot);
+        }
+        return Err(e);
+    }
+
+    let _ = fs::remove_dir_all(&bak);
+    Ok(())
+}
+
+/// 按目录恢复：`replace` 中的 skill 目录替换为 ZIP 中的版本，`remove` 中的目录直接删除
+pub fn restore_skill_dirs(
+    raw: &[u8],
+    replace: &[String],
+    remove: &[String],
+) -> Result<(), AppError> {
+    for dir in replace.iter().chain(remove) {
+        validate_skill_dir_name(dir)?;
+    }
+    let ssot = SkillService::get_ssot_dir()?;
+
+    if !replace.is_empty() {
+        let tmp = extract_tmpdir()?;
+        let extracted = extract_skills_zip(raw, &tmp, Some(replace))?;
+        for dir in replace {
+            let target = ssot.join(dir);
+            if target.exists() {
+                fs::remove_dir_all(&target).map_err(|e| AppError::io(&target, e))?;
+            }
+            fs::create_dir_all(&target).map_err(|e| AppError::io(&target, e))?;
+            copy_dir_recursive(&extracted.join(dir), &target)?;
+        }
+    }
+    for dir in remove {
+        let target = ssot.join(dir);
+        if target.exists() {
+            fs::remove_dir_all(&target).map_err(|e| AppError::io(&target, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 计算 ZIP 中每个顶层 skill 目录的内容指纹（相对路径 + 文件 SHA256）
+pub fn skill_dir_hashes(raw: &[u8]) -> Result<BTreeMap<String, String>, AppError> {
+    let mut archive = open_skills_zip(std::io::Cursor::new(raw))?;
+    let mut files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut total_bytes: u64 = 0;
+    for idx in 0..archive.len() {
+        let mut entry = archive.by_index(idx).map_err(zip_entry_error)?;
+        let Some(safe_name) = entry.enclosed_name() else {
+            continue;
+        };
+        let Some(top) = top_level_dir(&safe_name, entry.is_dir()) else {
+            continue;
+        };
+        let lines = files.entry(top.clone()).or_default();
+        if entry.is_dir() {
+            continue;
+        }
+        let rel = safe_name.to_string_lossy().replace('\\', "/");
+        let mut content = Vec::new();
+        copy_entry_with_total_limit(
+            &mut entry,
+            &mut content,
+            &mut total_bytes,
+            MAX_ZIP_EXTRACT_BYTES,
+            &safe_name,
+        )?;
+        lines.push(format!("{rel}:{:x}", Sha256::digest(&content)));
+    }
+
+    Ok(files
+        .into_iter()
+        .map(|(dir, mut lines)| {
+            lines.sort();
+            (
+                dir,
+                format!("{:x}", Sha256::digest(lines.join("\n").as_bytes())),
+            )
+        })
+        .collect())
+}
+
+fn validate_skill_dir_name(dir: &str) -> Result<(), AppError> {
+    if dir.is_empty() || dir == "." || dir == ".." || dir.contains(['/', '\\']) {
+        return Err(localized(
+            "webdav.sync.skill_dir_invalid",
+            format!("非法的 skill 目录名: {dir}"),
+            format!("Invalid skill directory name: {dir}"),
+        ));
+    }
+    Ok(())
+}
+
+/// 条目所属的顶层目录；根目录下的散落文件不属于任何 skill
+fn top_level_dir(path: &Path, is_dir: bool) -> Option<String> {
+    let mut components = path.components();
+    let std::path::Component::Normal(name) = components.next()? else {
+        return None;
+    };
+    (is_dir || components.next().is_some()).then(|| name.to_string_lossy().into_owned())
+}
+
+fn extract_tmpdir() -> Result<TempDir, AppError> {
+    tempdir().map_err(|e| {
         io_context_localized(
             "webdav.sync.skills_extract_tmpdir_failed",
             "创建 skills 解压临时目录失败",
             "Failed to create temporary directory for skills extraction",
             e,
         )
-    })?;
-    let zip_path = tmp.path().join("skills.zip");
-    crate::config::atomic_write(&zip_path, raw)?;
+    })
+}
 
-    let file = fs::File::open(&zip_path).map_err(|e| AppError::io(&zip_path, e))?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+fn zip_entry_error(e: zip::result::ZipError) -> AppError {
+    localized(
+        "webdav.sync.skills_zip_entry_read_failed",
+        format!("读取 ZIP 项失败: {e}"),
+        format!("Failed to read ZIP entry: {e}"),
+    )
+}
+
+fn open_skills_zip<R: Read + std::io::Seek>(reader: R) -> Result<zip::ZipArchive<R>, AppError> {
+    let archive = zip::ZipArchive::new(reader).map_err(|e| {
         localized(
             "webdav.sync.skills_zip_parse_failed",
             format!("解析 skills.zip 失败: {e}"),
@@ -254,22 +382,36 @@ pub fn restore_skills_zip(raw: &[u8]) -> Result<(), AppError> {
             ),
         ));
     }
+    Ok(archive)
+}
+
+/// 解压到临时目录；`only_dirs` 为 Some 时只解压这些顶层目录
+fn extract_skills_zip(
+    raw: &[u8],
+    tmp: &TempDir,
+    only_dirs: Option<&[String]>,
+) -> Result<PathBuf, AppError> {
+    let zip_path = tmp.path().join("skills.zip");
+    crate::config::atomic_write(&zip_path, raw)?;
+
+    let file = fs::File::open(&zip_path).map_err(|e| AppError::io(&zip_path, e))?;
+    let mut archive = open_skills_zip(file)?;
 
     let extracted = tmp.path().join("skills-extracted");
     fs::create_dir_all(&extracted).map_err(|e| AppError::io(&extracted, e))?;
 
     let mut total_bytes: u64 = 0;
     for idx in 0..archive.len() {
-        let mut entry = archive.by_index(idx).map_err(|e| {
-            localized(
-                "webdav.sync.skills_zip_entry_read_failed",
-                format!("读取 ZIP 项失败: {e}"),
-                format!("Failed to read ZIP entry: {e}"),
-            )
-        })?;
+        let mut entry = archive.by_index(idx).map_err(zip_entry_error)?;
         let Some(safe_name) = entry.enclosed_name() else {
             continue;
         };
+        if let Some(dirs) = only_dirs {
+            let top = top_level_dir(&safe_name, entry.is_dir());
+            if !top.is_some_and(|top| dirs.contains(&top)) {
+                continue;
+            }
+        }
         let out_path = extracted.join(safe_name);
         if entry.is_dir() {
             fs::create_dir_all(&out_path).map_err(|e| AppError::io(&out_path, e))?;
@@ -288,27 +430,7 @@ pub fn restore_skills_zip(raw: &[u8]) -> Result<(), AppError> {
         )?;
     }
 
-    let ssot = SkillService::get_ssot_dir()?;
-    let bak = ssot.with_extension("bak");
-
-    // 原子替换：先 rename 到 .bak，再 copy，失败则回滚
-    if ssot.exists() {
-        if bak.exists() {
-            let _ = fs::remove_dir_all(&bak);
-        }
-        fs::rename(&ssot, &bak).map_err(|e| AppError::io(&ssot, e))?;
-    }
-
-    if let Err(e) = copy_dir_recursive(&extracted, &ssot) {
-        if bak.exists() {
-            let _ = fs::remove_dir_all(&ssot);
-            let _ = fs::rename(&bak, &ssot);
-        }
-        return Err(e);
-    }
-
-    let _ = fs::remove_dir_all(&bak);
-    Ok(())
+    Ok(extracted)
 }
 
 /// 带总量限制的流式复制，在写入前检查大小是否超限。
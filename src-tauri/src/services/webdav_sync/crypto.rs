@@ -0,0 +1,143 @@
+//! WebDAV 同步产物的端到端加密
+//!
+//! 配置同步口令后，`db.sql` 与 `skills.zip` 在上传前以 AES-256-GCM 加密（PBKDF2-HMAC-SHA256 派生密钥），
+//! manifest 记录算法、派生参数与口令校验密文；manifest 本身保持明文以便判断兼容性。
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::secret_vault::{self, VaultKey};
+
+pub(super) const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+pub(super) const KDF_PBKDF2_SHA256: &str = "pbkdf2-hmac-sha256";
+
+const SALT_LEN: usize = 16;
+/// 接受的远端 PBKDF2 迭代次数：过低削弱口令保护，过高会让解锁长时间占满 CPU
+#[cfg(not(test))]
+pub(super) const ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 100_000..=10_000_000;
+#[cfg(test)]
+pub(super) const ITERATIONS_RANGE: std::ops::RangeInclusive<u32> =
+    secret_vault::PBKDF2_ITERATIONS..=10_000_000;
+const KEY_CHECK_AAD: &str = "cc-switch-webdav-sync:key-check";
+const KEY_CHECK_PLAINTEXT: &[u8] = b"cc-switch-webdav-sync";
+
+/// manifest 中记录的加密参数，不含任何密钥材料
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ManifestEncryption {
+    pub cipher: String,
+    pub kdf: String,
+    pub iterations: u32,
+    /// base64 编码的随机盐
+    pub salt: String,
+    /// 用于在下载 artifact 之前校验口令的密文
+    pub key_check: String,
+}
+
+pub(super) struct SyncCipher {
+    key: VaultKey,
+}
+
+impl SyncCipher {
+    /// 以新的随机盐创建加密器（每次上传都会重新生成）
+    pub fn create(passphrase: &str) -> Result<(Self, ManifestEncryption), AppError> {
+        let salt = secret_vault::random_bytes::<SALT_LEN>()?;
+        let iterations = secret_vault::PBKDF2_ITERATIONS;
+        let key = secret_vault::derive_passphrase_key(passphrase, &salt, iterations)?;
+        let key_check = key.encrypt(KEY_CHECK_AAD, KEY_CHECK_PLAINTEXT)?;
+        let encryption = ManifestEncryption {
+            cipher: CIPHER_AES_256_GCM.to_string(),
+            kdf: KDF_PBKDF2_SHA256.to_string(),
+            iterations,
+            salt: BASE64_STANDARD.encode(salt),
+            key_check,
+        };
+        Ok((Self { key }, encryption))
+    }
+
+    /// 按远端 manifest 的参数派生密钥并校验口令
+    pub fn unlock(
+        passphrase: &str,
+        encryption: &ManifestEncryption,
+        device_name: &str,
+    ) -> Result<Self, AppError> {
+        if encryption.cipher != CIPHER_AES_256_GCM || encryption.kdf != KDF_PBKDF2_SHA256 {
+            return Err(AppError::localized(
+                "webdav.sync.encryption_unsupported",
+                format!(
+                    "远端同步数据使用了不支持的加密方式: {} / {}",
+                    encryption.cipher, encryption.kdf
+                ),
+                format!(
+                    "Remote sync data uses an unsupported encryption scheme: {} / {}",
+                    encryption.cipher, encryption.kdf
+                ),
+            ));
+        }
+        let salt = BASE64_STANDARD
+            .decode(&encryption.salt)
+            .map_err(|e| AppError::Message(format!("远端 manifest 盐值格式无效: {e}")))?;
+        let key = secret_vault::derive_passphrase_key(passphrase, &salt, encryption.iterations)?;
+        key.decrypt(KEY_CHECK_AAD, &encryption.key_check)
+            .map_err(|_| {
+                AppError::localized(
+                    "webdav.sync.passphrase_wrong",
+                    format!("同步口令不正确：与设备“{device_name}”上传时使用的口令不一致"),
+                    format!(
+                        "Wrong sync passphrase: it does not match the one used by \"{device_name}\" for the last upload"
+                    ),
+                )
+            })?;
+        Ok(Self { key })
+    }
+
+    /// 加密 artifact；以文件名作为 AAD，防止密文被替换到其他位置
+    pub fn seal(&self, artifact_name: &str, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        self.key.seal_bytes(artifact_name, plaintext)
+    }
+
+    pub fn open(&self, artifact_name: &str, sealed: &[u8]) -> Result<Vec<u8>, AppError> {
+        self.key.open_bytes(artifact_name, sealed).map_err(|_| {
+            AppError::localized(
+                "webdav.sync.artifact_decrypt_failed",
+                format!("解密 artifact {artifact_name} 失败：数据已损坏"),
+                format!("Failed to decrypt artifact {artifact_name}: the data is corrupted"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_artifacts_round_trip_with_same_passphrase() {
+        let (cipher, encryption) = SyncCipher::create("correct horse").expect("create cipher");
+        let sealed = cipher.seal("db.sql", b"INSERT INTO x;").expect("seal");
+        assert_ne!(sealed, b"INSERT INTO x;");
+
+        let other = SyncCipher::unlock("correct horse", &encryption, "laptop").expect("unlock");
+        assert_eq!(
+            other.open("db.sql", &sealed).expect("open"),
+            b"INSERT INTO x;"
+        );
+        assert!(
+            other.open("skills.zip", &sealed).is_err(),
+            "ciphertext must be bound to its artifact name"
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected_before_decrypting_artifacts() {
+        let (_, encryption) = SyncCipher::create("correct horse").expect("create cipher");
+        let err = SyncCipher::unlock("battery staple", &encryption, "laptop")
+            .err()
+            .expect("wrong passphrase should fail");
+        assert!(
+            err.to_string().contains("laptop"),
+            "error should name the uploading device: {err}"
+        );
+    }
+}
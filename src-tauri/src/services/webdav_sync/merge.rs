@@ -0,0 +1,296 @@
+//! 记录级三方合并
+//!
+//! 以上次同步成功时的记录集合为基线（base），分别与本地、远端比较：
+//! 只有一侧改动的记录直接采用该侧；两侧都改动时逐字段合并，同一字段两侧改成不同的值才算冲突。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 以记录键（如 `provider/claude/<id>`）索引的记录集合
+pub(super) type RecordSet = BTreeMap<String, Value>;
+
+/// 冲突时保留哪一侧
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeSide {
+    Local,
+    Remote,
+}
+
+impl std::str::FromStr for MergeSide {
+    type Err = crate::error::AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "remote" => Ok(Self::Remote),
+            other => Err(crate::error::AppError::InvalidInput(format!(
+                "未知的冲突处理方式: {other}（可选 local / remote）"
+            ))),
+        }
+    }
+}
+
+/// 两侧都改动且无法自动合并的记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordConflict {
+    pub key: String,
+    /// 便于展示的名称（取记录的 name 字段）
+    pub label: String,
+    /// 冲突字段路径；为空表示整条记录冲突（一侧删除、另一侧修改）
+    pub fields: Vec<String>,
+    pub deleted_locally: bool,
+    pub deleted_remotely: bool,
+}
+
+pub(super) struct MergeResult {
+    pub merged: RecordSet,
+    pub conflicts: Vec<RecordConflict>,
+}
+
+impl MergeResult {
+    /// 合并结果与给定集合不同的记录键
+    pub fn changed_against(&self, records: &RecordSet) -> Vec<String> {
+        let keys: BTreeSet<&String> = self.merged.keys().chain(records.keys()).collect();
+        keys.into_iter()
+            .filter(|key| self.merged.get(*key) != records.get(*key))
+            .cloned()
+            .collect()
+    }
+}
+
+/// 三方合并；`resolutions` 中给出的记录在冲突字段上采用指定一侧
+pub(super) fn three_way_merge(
+    base: &RecordSet,
+    local: &RecordSet,
+    remote: &RecordSet,
+    resolutions: &BTreeMap<String, MergeSide>,
+    fallback: Option<MergeSide>,
+) -> MergeResult {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(local.keys())
+        .chain(remote.keys())
+        .collect();
+
+    let mut merged = RecordSet::new();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let (base_value, local_value, remote_value) =
+            (base.get(key), local.get(key), remote.get(key));
+        let prefer = resolutions.get(key).copied().or(fallback);
+        let mut fields = Vec::new();
+        let value = merge_value(
+            base_value,
+            local_value,
+            remote_value,
+            "",
+            prefer,
+            &mut fields,
+        );
+        if !fields.is_empty() {
+            let whole_record = fields.iter().any(String::is_empty);
+            conflicts.push(RecordConflict {
+                key: key.clone(),
+                label: record_label(key, local_value.or(remote_value)),
+                fields: if whole_record { Vec::new() } else { fields },
+                deleted_locally: local_value.is_none(),
+                deleted_remotely: remote_value.is_none(),
+            });
+        }
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+
+    MergeResult { merged, conflicts }
+}
+
+fn merge_value(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    remote: Option<&Value>,
+    path: &str,
+    prefer: Option<MergeSide>,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if local == remote {
+        return local.cloned();
+    }
+    if local == base {
+        return remote.cloned();
+    }
+    if remote == base {
+        return local.cloned();
+    }
+
+    if let (Some(Value::Object(local_map)), Some(Value::Object(remote_map))) = (local, remote) {
+        let base_map = base.and_then(Value::as_object);
+        let mut out = Map::new();
+        let fields = local_map
+            .keys()
+            .chain(remote_map.keys().filter(|k| !local_map.contains_key(*k)));
+        for field in fields {
+            let child_path = if path.is_empty() {
+                field.clone()
+            } else {
+                format!("{path}.{field}")
+            };
+            if let Some(value) = merge_value(
+                base_map.and_then(|map| map.get(field)),
+                local_map.get(field),
+                remote_map.get(field),
+                &child_path,
+                prefer,
+                conflicts,
+            ) {
+                out.insert(field.clone(), value);
+            }
+        }
+        return Some(Value::Object(out));
+    }
+
+    match prefer {
+        Some(MergeSide::Local) => local.cloned(),
+        Some(MergeSide::Remote) => remote.cloned(),
+        None => {
+            conflicts.push(path.to_string());
+            local.cloned()
+        }
+    }
+}
+
+fn record_label(key: &str, value: Option<&Value>) -> String {
+    let kind = key.split('/').next().unwrap_or(key);
+    let name = value
+        .and_then(|value| value.get("name"))
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty());
+    match name {
+        Some(name) => format!("{kind}: {name}"),
+        None => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn set(entries: &[(&str, Value)]) -> RecordSet {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn edits_to_different_records_are_both_kept() {
+        let base = set(&[
+            ("provider/claude/a", json!({"name": "A", "url": "a0"})),
+            ("provider/claude/b", json!({"name": "B", "url": "b0"})),
+        ]);
+        let local = set(&[
+            ("provider/claude/a", json!({"name": "A", "url": "a1"})),
+            ("provider/claude/b", json!({"name": "B", "url": "b0"})),
+        ]);
+        let remote = set(&[
+            ("provider/claude/a", json!({"name": "A", "url": "a0"})),
+            ("provider/claude/b", json!({"name": "B", "url": "b1"})),
+            ("provider/claude/c", json!({"name": "C", "url": "c1"})),
+        ]);
+
+        let result = three_way_merge(&base, &local, &remote, &BTreeMap::new(), None);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.merged["provider/claude/a"]["url"], "a1");
+        assert_eq!(result.merged["provider/claude/b"]["url"], "b1");
+        assert!(result.merged.contains_key("provider/claude/c"));
+        assert_eq!(
+            result.changed_against(&local),
+            vec!["provider/claude/b", "provider/claude/c"]
+        );
+        assert_eq!(result.changed_against(&remote), vec!["provider/claude/a"]);
+    }
+
+    #[test]
+    fn edits_to_different_fields_of_one_record_are_merged() {
+        let base = set(&[(
+            "provider/claude/a",
+            json!({"name": "A", "settingsConfig": {"env": {"URL": "u0", "KEY": "k0"}}}),
+        )]);
+        let local = set(&[(
+            "provider/claude/a",
+            json!({"name": "A", "settingsConfig": {"env": {"URL": "u1", "KEY": "k0"}}}),
+        )]);
+        let remote = set(&[(
+            "provider/claude/a",
+            json!({"name": "A", "settingsConfig": {"env": {"URL": "u0", "KEY": "k1"}}}),
+        )]);
+
+        let result = three_way_merge(&base, &local, &remote, &BTreeMap::new(), None);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(
+            result.merged["provider/claude/a"]["settingsConfig"]["env"],
+            json!({"URL": "u1", "KEY": "k1"})
+        );
+    }
+
+    #[test]
+    fn same_field_edited_on_both_sides_is_a_conflict_until_resolved() {
+        let base = set(&[("mcp/fs", json!({"name": "fs", "server": {"command": "a"}}))]);
+        let local = set(&[("mcp/fs", json!({"name": "fs", "server": {"command": "b"}}))]);
+        let remote = set(&[("mcp/fs", json!({"name": "fs", "server": {"command": "c"}}))]);
+
+        let result = three_way_merge(&base, &local, &remote, &BTreeMap::new(), None);
+        assert_eq!(
+            result.conflicts,
+            vec![RecordConflict {
+                key: "mcp/fs".to_string(),
+                label: "mcp: fs".to_string(),
+                fields: vec!["server.command".to_string()],
+                deleted_locally: false,
+                deleted_remotely: false,
+            }]
+        );
+
+        let resolutions = BTreeMap::from([("mcp/fs".to_string(), MergeSide::Remote)]);
+        let result = three_way_merge(&base, &local, &remote, &resolutions, None);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.merged["mcp/fs"]["server"]["command"], "c");
+    }
+
+    #[test]
+    fn delete_against_edit_is_a_whole_record_conflict() {
+        let base = set(&[("prompt/claude/p", json!({"name": "P", "content": "x"}))]);
+        let local = RecordSet::new();
+        let remote = set(&[("prompt/claude/p", json!({"name": "P", "content": "y"}))]);
+
+        let result = three_way_merge(&base, &local, &remote, &BTreeMap::new(), None);
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(result.conflicts[0].fields.is_empty());
+        assert!(result.conflicts[0].deleted_locally);
+
+        let result = three_way_merge(
+            &base,
+            &local,
+            &remote,
+            &BTreeMap::new(),
+            Some(MergeSide::Local),
+        );
+        assert!(result.conflicts.is_empty());
+        assert!(!result.merged.contains_key("prompt/claude/p"));
+    }
+
+    #[test]
+    fn unchanged_deletion_propagates() {
+        let base = set(&[("skill/demo", json!({"name": "demo"}))]);
+        let local = base.clone();
+        let remote = RecordSet::new();
+
+        let result = three_way_merge(&base, &local, &remote, &BTreeMap::new(), None);
+        assert!(result.conflicts.is_empty());
+        assert!(result.merged.is_empty());
+    }
+}
@@ -1,10 +1,18 @@
 //! WebDAV v2 sync protocol layer with DB compatibility subdirectories.
 //!
-//! Manifest-based synchronization on top of the WebDAV transport helpers.
+//! Manifest-based synchronization on top of a pluggable transport: WebDAV,
+//! an S3-compatible object store or a git repository (see `transport`).
 //! Current layout uses `{root}/v2/db-v6/{profile}/`, with legacy fallback to
-//! `{root}/v2/{profile}/`. Artifact set: `db.sql` + `skills.zip`.
+//! `{root}/v2/{profile}/`. Artifact set: `db.sql` + `skills.zip`, optionally
+//! encrypted end-to-end with the sync passphrase (see `crypto`).
+//! Besides whole-snapshot upload/download, `merge` performs a record-level
+//! three-way merge against the last synced state (see `merge` and `records`).
 
 mod archive;
+mod crypto;
+mod merge;
+mod records;
+mod transport;
 
 use std::collections::BTreeMap;
 
@@ -13,14 +21,21 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tempfile::tempdir;
 
+use crate::app_config::AppType;
 use crate::database::{Database, SCHEMA_VERSION};
 use crate::error::AppError;
 use crate::services::webdav;
-use crate::settings::{
-    get_webdav_sync_settings, update_webdav_sync_status, WebDavSyncSettings, WebDavSyncStatus,
+use crate::settings::{get_sync_backend, update_sync_status, SyncBackend, WebDavSyncStatus};
+
+use self::archive::{restore_skills_zip, skill_dir_hashes, zip_skills_ssot, SkillsBackup};
+use self::crypto::{ManifestEncryption, SyncCipher, ITERATIONS_RANGE};
+use self::merge::{three_way_merge, RecordSet};
+use self::records::{
+    apply_records, collect_records, load_base, record_versions, save_base_best_effort,
 };
+use self::transport::SyncTarget;
 
-use self::archive::{restore_skills_zip, zip_skills_ssot, SkillsBackup};
+pub use self::merge::{MergeSide, RecordConflict};
 
 // ---------------------------------------------------------------------------
 // i18n 辅助
@@ -58,6 +73,8 @@ const REMOTE_MANIFEST: &str = "manifest.json";
 
 const MAX_DEVICE_NAME_LEN: usize = 64;
 const MAX_MANIFEST_BYTES: u64 = 1024 * 1024; // 1 MB
+/// 写回前发现远端已被其他设备更新时，最多重新合并的次数
+const MAX_MERGE_ATTEMPTS: usize = 3;
 const MAX_SYNC_ARTIFACT_BYTES: u64 = 512 * 1024 * 1024; // 512 MB
 
 // ---------------------------------------------------------------------------
@@ -70,6 +87,8 @@ pub enum SyncDecision {
     Download,
     /// V2 远端为空，但检测到 V1 数据，需要用户确认迁移
     V1MigrationNeeded,
+    /// 记录级三方合并（双向）
+    Merge,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -78,6 +97,20 @@ pub struct WebDavSyncSummary {
     pub message: String,
 }
 
+/// 合并结果：完成，或存在需要用户选择的冲突（此时本地与远端均未改动）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Synced(WebDavSyncSummary),
+    Conflicts(Vec<RecordConflict>),
+}
+
+/// 冲突处理方式：逐条指定，未指定的冲突按 `fallback` 处理（为 None 时报告冲突）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictResolutions {
+    pub per_record: BTreeMap<String, MergeSide>,
+    pub fallback: Option<MergeSide>,
+}
+
 // ---------------------------------------------------------------------------
 // Manifest 类型
 // ---------------------------------------------------------------------------
@@ -93,6 +126,12 @@ struct SyncManifest {
     created_at: String,
     artifacts: BTreeMap<String, ArtifactMeta>,
     snapshot_id: String,
+    /// 存在时 artifacts 为密文，sha256/size 描述的是密文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<ManifestEncryption>,
+    /// 记录键 → 记录版本（规范化 JSON 的 SHA256）；加密时省略，避免泄露记录指纹
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    records: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,6 +145,8 @@ struct ArtifactMeta {
 // ---------------------------------------------------------------------------
 
 struct LocalSnapshot {
+    device_name: String,
+    records: RecordSet,
     db_sql: Vec<u8>,
     skills_zip: Vec<u8>,
     manifest_bytes: Vec<u8>,
@@ -133,103 +174,149 @@ pub struct WebDavSyncService;
 
 impl WebDavSyncService {
     pub fn check_connection() -> Result<(), AppError> {
-        run_http(check_connection())
+        run_http(check_connection(SyncBackend::WebDav))
     }
 
     pub fn upload() -> Result<WebDavSyncSummary, AppError> {
-        run_http(upload())
+        run_http(upload(SyncBackend::WebDav))
     }
 
     pub fn download() -> Result<WebDavSyncSummary, AppError> {
-        run_http(download())
+        run_http(download(SyncBackend::WebDav))
     }
 
     /// 用户确认后调用：下载 V1 数据 → 应用 → 上传 V2 → 删除 V1
     pub fn migrate_v1_to_v2() -> Result<WebDavSyncSummary, AppError> {
-        run_http(migrate_v1_to_v2())
+        run_http(migrate_v1_to_v2(SyncBackend::WebDav))
+    }
+
+    pub fn merge(resolutions: &ConflictResolutions) -> Result<MergeOutcome, AppError> {
+        run_http(merge(SyncBackend::WebDav, resolutions))
     }
 }
 
+/// 使用当前选择的同步后端（`settings.syncBackend`）执行同步
+pub struct SyncService;
+
+impl SyncService {
+    pub fn check_connection() -> Result<(), AppError> {
+        run_http(check_connection(get_sync_backend()))
+    }
+
+    pub fn upload() -> Result<WebDavSyncSummary, AppError> {
+        run_http(upload(get_sync_backend()))
+    }
+
+    pub fn download() -> Result<WebDavSyncSummary, AppError> {
+        run_http(download(get_sync_backend()))
+    }
+
+    pub fn migrate_v1_to_v2() -> Result<WebDavSyncSummary, AppError> {
+        run_http(migrate_v1_to_v2(get_sync_backend()))
+    }
+
+    pub fn merge(resolutions: &ConflictResolutions) -> Result<MergeOutcome, AppError> {
+        run_http(merge(get_sync_backend(), resolutions))
+    }
+}
+
+/// 本地可同步记录的整体指纹，守护进程据此发现需要同步的本地改动。
+/// 不打包 skills 目录，Skill 文件内容的修改由定时同步覆盖。
+pub fn local_change_fingerprint(db: &Database) -> Result<String, AppError> {
+    let records = collect_records(db, &BTreeMap::new())?;
+    let combined = record_versions(&records)
+        .iter()
+        .map(|(key, version)| format!("{key}:{version}"))
+        .collect::<Vec<_>>()
+        .join("|");
+    Ok(sha256_hex(combined.as_bytes()))
+}
+
 // ---------------------------------------------------------------------------
 // 异步核心
 // ---------------------------------------------------------------------------
 
-async fn check_connection() -> Result<(), AppError> {
-    let settings = load_webdav_settings()?;
-    let auth = webdav::auth_from_credentials(&settings.username, &settings.password);
-    webdav::test_connection(&settings.base_url, &auth).await?;
-    let dir_segments = remote_dir_segments(&settings, RemoteLayout::Current);
-    webdav::ensure_remote_directories(&settings.base_url, &dir_segments, &auth).await?;
+fn backend_label(backend: SyncBackend) -> &'static str {
+    match backend {
+        SyncBackend::WebDav => "WebDAV",
+        SyncBackend::S3 => "S3",
+        SyncBackend::Git => "Git",
+    }
+}
+
+async fn check_connection(backend: SyncBackend) -> Result<(), AppError> {
+    let target = SyncTarget::load(backend)?;
+    target.transport.test_connection().await?;
+    let dir_segments = remote_dir_segments(&target, RemoteLayout::Current);
+    target.transport.ensure_dirs(&dir_segments).await?;
     Ok(())
 }
 
-async fn upload() -> Result<WebDavSyncSummary, AppError> {
-    let mut settings = load_webdav_settings()?;
-    let auth = webdav::auth_from_credentials(&settings.username, &settings.password);
+async fn upload(backend: SyncBackend) -> Result<WebDavSyncSummary, AppError> {
+    let target = SyncTarget::load(backend)?;
+    target.transport.open().await?;
+
+    let snapshot = build_local_snapshot(&target)?;
+    upload_snapshot(&target, snapshot).await?;
 
-    let dir_segments = remote_dir_segments(&settings, RemoteLayout::Current);
-    webdav::ensure_remote_directories(&settings.base_url, &dir_segments, &auth).await?;
+    Ok(WebDavSyncSummary {
+        decision: SyncDecision::Upload,
+        message: format!("{} upload completed", backend_label(backend)),
+    })
+}
 
-    let snapshot = build_local_snapshot(&settings)?;
+/// 上传快照并记录同步状态与基线
+async fn upload_snapshot(target: &SyncTarget, snapshot: LocalSnapshot) -> Result<(), AppError> {
+    let transport = &target.transport;
+    let dir_segments = remote_dir_segments(target, RemoteLayout::Current);
+    transport.ensure_dirs(&dir_segments).await?;
 
     // 上传 artifacts
-    let db_url = build_artifact_url(&settings, RemoteLayout::Current, REMOTE_DB_SQL)?;
-    webdav::put_bytes(&db_url, &auth, snapshot.db_sql, "application/sql").await?;
+    let db_segments = artifact_segments(target, RemoteLayout::Current, REMOTE_DB_SQL);
+    transport
+        .put(&db_segments, snapshot.db_sql, "application/sql")
+        .await?;
 
-    let skills_url = build_artifact_url(&settings, RemoteLayout::Current, REMOTE_SKILLS_ZIP)?;
-    webdav::put_bytes(&skills_url, &auth, snapshot.skills_zip, "application/zip").await?;
+    let skills_segments = artifact_segments(target, RemoteLayout::Current, REMOTE_SKILLS_ZIP);
+    transport
+        .put(&skills_segments, snapshot.skills_zip, "application/zip")
+        .await?;
 
     // 上传 manifest（最后上传，确保 artifacts 已就绪）
-    let manifest_url = build_artifact_url(&settings, RemoteLayout::Current, REMOTE_MANIFEST)?;
-    webdav::put_bytes(
-        &manifest_url,
-        &auth,
-        snapshot.manifest_bytes,
-        "application/json",
-    )
-    .await?;
+    let manifest_segments = artifact_segments(target, RemoteLayout::Current, REMOTE_MANIFEST);
+    transport
+        .put(
+            &manifest_segments,
+            snapshot.manifest_bytes,
+            "application/json",
+        )
+        .await?;
+    transport
+        .commit(&format!("cc-switch sync: {}", snapshot.device_name))
+        .await?;
 
     // 获取 etag（best-effort，不影响上传结果）
-    let etag = match webdav::head_etag(&manifest_url, &auth).await {
+    let etag = match transport.etag(&manifest_segments).await {
         Ok(e) => e,
         Err(e) => {
-            log::debug!("[WebDAV] Failed to fetch ETag after upload: {e}");
+            log::debug!("[Sync] Failed to fetch ETag after upload: {e}");
             None
         }
     };
 
-    persist_sync_success_best_effort(&mut settings, &snapshot.manifest_hash, etag);
-
-    Ok(WebDavSyncSummary {
-        decision: SyncDecision::Upload,
-        message: "WebDAV upload completed".to_string(),
-    })
+    persist_sync_success_best_effort(target.backend, &snapshot.manifest_hash, etag);
+    save_base_best_effort(target, &snapshot.records);
+    Ok(())
 }
 
-async fn download() -> Result<WebDavSyncSummary, AppError> {
-    let mut settings = load_webdav_settings()?;
-    let auth = webdav::auth_from_credentials(&settings.username, &settings.password);
+async fn download(backend: SyncBackend) -> Result<WebDavSyncSummary, AppError> {
+    let target = SyncTarget::load(backend)?;
+    target.transport.open().await?;
 
-    if let Some(snapshot) = find_remote_snapshot(&settings, &auth).await? {
+    if let Some(snapshot) = find_remote_snapshot(&target).await? {
         validate_manifest_compat(&snapshot.manifest, snapshot.layout)?;
-
         let manifest_hash = sha256_hex(&snapshot.manifest_bytes);
-        let db_sql = download_and_verify(
-            &settings,
-            &auth,
-            snapshot.layout,
-            REMOTE_DB_SQL,
-            &snapshot.manifest.artifacts,
-        )
-        .await?;
-        let skills_zip = download_and_verify(
-            &settings,
-            &auth,
-            snapshot.layout,
-            REMOTE_SKILLS_ZIP,
-            &snapshot.manifest.artifacts,
-        )
-        .await?;
+        let (db_sql, skills_zip) = download_remote_artifacts(&target, &snapshot).await?;
 
         {
             let _guard = crate::services::state_coordination::acquire_restore_mutation_guard()
@@ -238,14 +325,20 @@ async fn download() -> Result<WebDavSyncSummary, AppError> {
             ensure_restore_allowed().await?;
             apply_snapshot(&db_sql, &skills_zip)?;
         }
-        persist_sync_success_best_effort(&mut settings, &manifest_hash, snapshot.manifest_etag);
-        cleanup_v1_remote(&settings, &auth).await;
+        persist_sync_success_best_effort(backend, &manifest_hash, snapshot.manifest_etag);
+        match skill_dir_hashes(&skills_zip)
+            .and_then(|hashes| collect_records(&Database::init()?, &hashes))
+        {
+            Ok(records) => save_base_best_effort(&target, &records),
+            Err(e) => log::warn!("[Sync] Failed to record sync base after download: {e}"),
+        }
+        cleanup_v1_remote(&target).await;
 
         Ok(WebDavSyncSummary {
             decision: SyncDecision::Download,
-            message: "WebDAV download completed".to_string(),
+            message: format!("{} download completed", backend_label(backend)),
         })
-    } else if detect_v1_manifest(&settings, &auth).await?.is_some() {
+    } else if detect_v1_manifest(&target).await?.is_some() {
         Ok(WebDavSyncSummary {
             decision: SyncDecision::V1MigrationNeeded,
             message: String::new(),
@@ -259,59 +352,280 @@ async fn download() -> Result<WebDavSyncSummary, AppError> {
     }
 }
 
-// ---------------------------------------------------------------------------
-// 设置加载 / 验证
-// ---------------------------------------------------------------------------
+/// 记录级三方合并：以上次同步的基线为参照，分别吸收本地与远端的改动后回写两侧
+async fn merge(
+    backend: SyncBackend,
+    resolutions: &ConflictResolutions,
+) -> Result<MergeOutcome, AppError> {
+    let target = SyncTarget::load(backend)?;
+    merge_with_target(&target, resolutions).await
+}
 
-fn load_webdav_settings() -> Result<WebDavSyncSettings, AppError> {
-    let settings = get_webdav_sync_settings().ok_or_else(|| {
-        localized(
-            "webdav.sync.not_configured",
-            "未配置 WebDAV 同步",
-            "WebDAV sync is not configured",
-        )
-    })?;
-    if !settings.enabled {
-        return Err(localized(
-            "webdav.sync.not_enabled",
-            "WebDAV 同步未启用",
-            "WebDAV sync is not enabled",
-        ));
+/// 远端在读取后、写回前被其他设备更新时放弃这次写回，基于新的远端重新合并，
+/// 避免后写入的设备覆盖先写入的改动
+async fn merge_with_target(
+    target: &SyncTarget,
+    resolutions: &ConflictResolutions,
+) -> Result<MergeOutcome, AppError> {
+    let manifest_segments = artifact_segments(target, RemoteLayout::Current, REMOTE_MANIFEST);
+    for _ in 0..MAX_MERGE_ATTEMPTS {
+        target.transport.open().await?;
+        let revision = target
+            .transport
+            .read_revision(&manifest_segments, MAX_MANIFEST_BYTES)
+            .await?;
+        if let Some(outcome) = merge_once(target, resolutions, revision.as_deref()).await? {
+            return Ok(outcome);
+        }
+        log::info!("[Sync] Remote changed during merge; merging again");
+    }
+    Err(localized(
+        "webdav.sync.remote_busy",
+        "合并期间远端同步数据被其他设备反复更新，请稍后重试",
+        "The remote sync data kept changing during the merge; try again later",
+    ))
+}
+
+/// 基于版本为 `revision` 的远端合并一次；写回前远端已变化时返回 None
+async fn merge_once(
+    target: &SyncTarget,
+    resolutions: &ConflictResolutions,
+    revision: Option<&str>,
+) -> Result<Option<MergeOutcome>, AppError> {
+    let backend = target.backend;
+    let label = backend_label(backend);
+
+    let Some(remote) = find_remote_snapshot(target).await? else {
+        if detect_v1_manifest(target).await?.is_some() {
+            return Ok(Some(MergeOutcome::Synced(WebDavSyncSummary {
+                decision: SyncDecision::V1MigrationNeeded,
+                message: String::new(),
+            })));
+        }
+        // 远端为空：本地即合并结果
+        let snapshot = build_local_snapshot(target)?;
+        let pushed = snapshot.records.len();
+        if !upload_if_unchanged(target, snapshot, revision).await? {
+            return Ok(None);
+        }
+        return Ok(Some(MergeOutcome::Synced(WebDavSyncSummary {
+            decision: SyncDecision::Merge,
+            message: format!("{label} sync completed: 0 pulled, {pushed} pushed"),
+        })));
+    };
+    validate_manifest_compat(&remote.manifest, remote.layout)?;
+
+    let local_snapshot = build_local_snapshot(target)?;
+    let local = local_snapshot.records.clone();
+    let base = load_base(target)?;
+
+    // 远端记录版本与基线一致时无需下载 artifacts
+    let remote_unchanged = !base.is_empty()
+        && !remote.manifest.records.is_empty()
+        && remote.manifest.records == record_versions(&base);
+    let (remote_records, remote_skills_zip) = if remote_unchanged {
+        (base.clone(), Vec::new())
+    } else {
+        let (db_sql, skills_zip) = download_remote_artifacts(target, &remote).await?;
+        let sql = std::str::from_utf8(&db_sql).map_err(|e| {
+            localized(
+                "webdav.sync.sql_not_utf8",
+                format!("SQL 非 UTF-8: {e}"),
+                format!("SQL is not valid UTF-8: {e}"),
+            )
+        })?;
+        validate_sql_user_version_for_import(sql)?;
+        let remote_db = Database::memory()?;
+        remote_db.import_sql_string_for_sync(sql)?;
+        let records = collect_records(&remote_db, &skill_dir_hashes(&skills_zip)?)?;
+        (records, skills_zip)
+    };
+
+    let result = three_way_merge(
+        &base,
+        &local,
+        &remote_records,
+        &resolutions.per_record,
+        resolutions.fallback,
+    );
+    if !result.conflicts.is_empty() {
+        return Ok(Some(MergeOutcome::Conflicts(result.conflicts)));
+    }
+    let pull_keys = result.changed_against(&local);
+    let mut merged = result.merged;
+
+    if !pull_keys.is_empty() {
+        let applied = {
+            let _guard = crate::services::state_coordination::acquire_restore_mutation_guard()
+                .await
+                .map_err(AppError::Message)?;
+            apply_records(
+                &Database::init()?,
+                &pull_keys,
+                &merged,
+                &local,
+                &remote_skills_zip,
+            )?
+        };
+        for key in &applied.kept {
+            if let Some(value) = local.get(key) {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        if applied.skills_changed {
+            if let Err(e) = crate::services::skill::SkillService::sync_all_enabled_best_effort() {
+                log::warn!("[Sync] Failed to sync skills to apps after merge: {e}");
+            }
+        }
+        if !applied.current_providers.is_empty() {
+            let current_providers = applied.current_providers;
+            let result = tokio::task::spawn_blocking(move || {
+                rewrite_current_providers_live(&current_providers)
+            })
+            .await;
+            if let Err(e) = result {
+                log::warn!("[Sync] Live config task failed after merge: {e}");
+            }
+        }
+    }
+
+    let pushed = merged
+        .iter()
+        .filter(|(key, value)| remote_records.get(*key) != Some(*value))
+        .count()
+        + remote_records
+            .keys()
+            .filter(|key| !merged.contains_key(*key))
+            .count();
+    if pushed > 0 {
+        // 本地已包含合并结果；有拉取时重新导出快照
+        let snapshot = if pull_keys.is_empty() {
+            local_snapshot
+        } else {
+            build_local_snapshot(target)?
+        };
+        if !upload_if_unchanged(target, snapshot, revision).await? {
+            return Ok(None);
+        }
+    } else {
+        persist_sync_success_best_effort(
+            backend,
+            &sha256_hex(&remote.manifest_bytes),
+            remote.manifest_etag,
+        );
+        save_base_best_effort(target, &merged);
+    }
+
+    Ok(Some(MergeOutcome::Synced(WebDavSyncSummary {
+        decision: SyncDecision::Merge,
+        message: format!(
+            "{label} sync completed: {} pulled, {pushed} pushed",
+            pull_keys.len()
+        ),
+    })))
+}
+
+/// 远端仍是合并时读取的版本才上传；远端已变化（含 git 推送被拒）时不上传并返回 false
+async fn upload_if_unchanged(
+    target: &SyncTarget,
+    snapshot: LocalSnapshot,
+    revision: Option<&str>,
+) -> Result<bool, AppError> {
+    let manifest_segments = artifact_segments(target, RemoteLayout::Current, REMOTE_MANIFEST);
+    let current = target
+        .transport
+        .remote_revision(&manifest_segments, MAX_MANIFEST_BYTES)
+        .await?;
+    if current.as_deref() != revision {
+        return Ok(false);
+    }
+    match upload_snapshot(target, snapshot).await {
+        Ok(()) => Ok(true),
+        Err(err) => match target
+            .transport
+            .remote_revision(&manifest_segments, MAX_MANIFEST_BYTES)
+            .await
+        {
+            Ok(current) if current.as_deref() != revision => Ok(false),
+            _ => Err(err),
+        },
+    }
+}
+
+/// 本机当前供应商的记录被合并改动时，按切换供应商的流程重新写入 live 配置
+/// （代理接管中的应用走热切换，不覆盖指向代理的 live 配置）
+fn rewrite_current_providers_live(providers: &[(AppType, String)]) {
+    let state = match crate::store::AppState::try_new() {
+        Ok(state) => state,
+        Err(e) => {
+            log::warn!("[Sync] Failed to load state to rewrite live configs after merge: {e}");
+            return;
+        }
+    };
+    for (app_type, provider_id) in providers {
+        if let Err(e) =
+            crate::services::ProviderService::switch(&state, app_type.clone(), provider_id)
+        {
+            log::warn!("[Sync] Failed to rewrite {app_type} live config after merge: {e}");
+        }
+    }
+}
+
+/// 下载、校验并（如需）解密远端 artifacts，返回 `(db.sql, skills.zip)` 明文
+async fn download_remote_artifacts(
+    target: &SyncTarget,
+    snapshot: &RemoteSnapshot,
+) -> Result<(Vec<u8>, Vec<u8>), AppError> {
+    let cipher = remote_cipher(target, &snapshot.manifest)?;
+    let db_sql = download_and_verify(
+        target,
+        snapshot.layout,
+        REMOTE_DB_SQL,
+        &snapshot.manifest.artifacts,
+    )
+    .await?;
+    let skills_zip = download_and_verify(
+        target,
+        snapshot.layout,
+        REMOTE_SKILLS_ZIP,
+        &snapshot.manifest.artifacts,
+    )
+    .await?;
+    match &cipher {
+        Some(cipher) => Ok((
+            cipher.open(REMOTE_DB_SQL, &db_sql)?,
+            cipher.open(REMOTE_SKILLS_ZIP, &skills_zip)?,
+        )),
+        None => Ok((db_sql, skills_zip)),
     }
-    settings.validate()?;
-    Ok(settings)
 }
 
 // ---------------------------------------------------------------------------
 // 远端路径
 // ---------------------------------------------------------------------------
 
-fn remote_dir_segments(settings: &WebDavSyncSettings, layout: RemoteLayout) -> Vec<String> {
+fn remote_dir_segments(target: &SyncTarget, layout: RemoteLayout) -> Vec<String> {
     let mut segments = Vec::new();
-    segments.extend(webdav::path_segments(&settings.remote_root).map(str::to_string));
+    segments.extend(webdav::path_segments(&target.remote_root).map(str::to_string));
     segments.push(format!("v{PROTOCOL_VERSION}"));
     if layout == RemoteLayout::Current {
         segments.push(format!("db-v{DB_COMPAT_VERSION}"));
     }
-    segments.extend(webdav::path_segments(&settings.profile).map(str::to_string));
+    segments.extend(webdav::path_segments(&target.profile).map(str::to_string));
     segments
 }
 
-fn build_artifact_url(
-    settings: &WebDavSyncSettings,
-    layout: RemoteLayout,
-    file_name: &str,
-) -> Result<String, AppError> {
-    let mut segments = remote_dir_segments(settings, layout);
+fn artifact_segments(target: &SyncTarget, layout: RemoteLayout, file_name: &str) -> Vec<String> {
+    let mut segments = remote_dir_segments(target, layout);
     segments.extend(webdav::path_segments(file_name).map(str::to_string));
-    webdav::build_remote_url(&settings.base_url, &segments)
+    segments
 }
 
 // ---------------------------------------------------------------------------
 // 本地快照构建
 // ---------------------------------------------------------------------------
 
-fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot, AppError> {
+fn build_local_snapshot(target: &SyncTarget) -> Result<LocalSnapshot, AppError> {
     let tmp = tempdir().map_err(|e| {
         io_context_localized(
             "webdav.sync.snapshot_tmpdir_failed",
@@ -322,7 +636,8 @@ fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot,
     })?;
 
     // 导出 DB
-    let db_sql = Database::init()?.export_sql_string_for_sync()?.into_bytes();
+    let db = Database::init()?;
+    let db_sql = db.export_sql_string_for_sync()?.into_bytes();
 
     // 打包 skills
     let skills_zip_path = tmp.path().join(REMOTE_SKILLS_ZIP);
@@ -330,6 +645,22 @@ fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot,
     let skills_zip =
         std::fs::read(&skills_zip_path).map_err(|e| AppError::io(&skills_zip_path, e))?;
 
+    // 记录级版本（供合并使用）
+    let records = collect_records(&db, &skill_dir_hashes(&skills_zip)?)?;
+
+    // 配置了同步口令时，先加密再计算 hash
+    let (db_sql, skills_zip, encryption) = match sync_passphrase(target) {
+        Some(passphrase) => {
+            let (cipher, encryption) = SyncCipher::create(passphrase)?;
+            (
+                cipher.seal(REMOTE_DB_SQL, &db_sql)?,
+                cipher.seal(REMOTE_SKILLS_ZIP, &skills_zip)?,
+                Some(encryption),
+            )
+        }
+        None => (db_sql, skills_zip, None),
+    };
+
     // 构建 artifacts map
     let mut artifacts = BTreeMap::new();
     artifacts.insert(
@@ -349,15 +680,22 @@ fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot,
 
     let snapshot_id = compute_snapshot_id(&artifacts);
     let device_name = detect_system_device_name().unwrap_or_else(|| "Unknown Device".to_string());
+    let record_versions = if encryption.is_some() {
+        BTreeMap::new()
+    } else {
+        record_versions(&records)
+    };
 
     let manifest = SyncManifest {
         format: PROTOCOL_FORMAT.to_string(),
         version: PROTOCOL_VERSION,
         db_compat_version: Some(DB_COMPAT_VERSION),
-        device_name,
+        device_name: device_name.clone(),
         created_at: Utc::now().to_rfc3339(),
         artifacts,
         snapshot_id,
+        encryption,
+        records: record_versions,
     };
 
     let manifest_bytes =
@@ -365,6 +703,8 @@ fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot,
     let manifest_hash = sha256_hex(&manifest_bytes);
 
     Ok(LocalSnapshot {
+        device_name,
+        records,
         db_sql,
         skills_zip,
         manifest_bytes,
@@ -372,6 +712,70 @@ fn build_local_snapshot(_settings: &WebDavSyncSettings) -> Result<LocalSnapshot,
     })
 }
 
+// ---------------------------------------------------------------------------
+// 端到端加密
+// ---------------------------------------------------------------------------
+
+fn sync_passphrase(target: &SyncTarget) -> Option<&str> {
+    let passphrase = target.encryption_passphrase.as_str();
+    (!passphrase.is_empty()).then_some(passphrase)
+}
+
+/// 远端快照已加密时校验本地口令并返回解密器；未加密且本机未配置口令时返回 None
+fn remote_cipher(
+    target: &SyncTarget,
+    manifest: &SyncManifest,
+) -> Result<Option<SyncCipher>, AppError> {
+    let Some(encryption) = manifest.encryption.as_ref() else {
+        // 配置了口令却读到明文快照，可能是远端被替换，不能静默接受
+        if sync_passphrase(target).is_some() {
+            return Err(localized(
+                "webdav.sync.remote_not_encrypted",
+                format!(
+                    "远端同步数据（设备“{}”上传）未加密，但本机已配置同步口令：请先上传以加密远端数据，或清空同步口令",
+                    manifest.device_name
+                ),
+                format!(
+                    "Remote sync data uploaded by \"{}\" is not encrypted but this device has a sync passphrase; upload to encrypt the remote data, or clear the passphrase",
+                    manifest.device_name
+                ),
+            ));
+        }
+        return Ok(None);
+    };
+    if !ITERATIONS_RANGE.contains(&encryption.iterations) {
+        return Err(localized(
+            "webdav.sync.encryption_iterations_invalid",
+            format!(
+                "远端 manifest 的密钥派生迭代次数 {} 超出允许范围（{}–{}）",
+                encryption.iterations,
+                ITERATIONS_RANGE.start(),
+                ITERATIONS_RANGE.end()
+            ),
+            format!(
+                "Remote manifest key derivation iterations {} are outside the allowed range ({}-{})",
+                encryption.iterations,
+                ITERATIONS_RANGE.start(),
+                ITERATIONS_RANGE.end()
+            ),
+        ));
+    }
+    let passphrase = sync_passphrase(target).ok_or_else(|| {
+        localized(
+            "webdav.sync.passphrase_required",
+            format!(
+                "远端同步数据已由设备“{}”加密，请先配置同步口令",
+                manifest.device_name
+            ),
+            format!(
+                "Remote sync data was encrypted by \"{}\"; configure the sync passphrase first",
+                manifest.device_name
+            ),
+        )
+    })?;
+    SyncCipher::unlock(passphrase, encryption, &manifest.device_name).map(Some)
+}
+
 // ---------------------------------------------------------------------------
 // Manifest 验证
 // ---------------------------------------------------------------------------
@@ -446,25 +850,23 @@ fn validate_manifest_compat(manifest: &SyncManifest, layout: RemoteLayout) -> Re
     Ok(())
 }
 
-async fn find_remote_snapshot(
-    settings: &WebDavSyncSettings,
-    auth: &webdav::WebDavAuth,
-) -> Result<Option<RemoteSnapshot>, AppError> {
-    if let Some(snapshot) = fetch_remote_snapshot(settings, auth, RemoteLayout::Current).await? {
+async fn find_remote_snapshot(target: &SyncTarget) -> Result<Option<RemoteSnapshot>, AppError> {
+    if let Some(snapshot) = fetch_remote_snapshot(target, RemoteLayout::Current).await? {
         return Ok(Some(snapshot));
     }
 
-    fetch_remote_snapshot(settings, auth, RemoteLayout::Legacy).await
+    fetch_remote_snapshot(target, RemoteLayout::Legacy).await
 }
 
 async fn fetch_remote_snapshot(
-    settings: &WebDavSyncSettings,
-    auth: &webdav::WebDavAuth,
+    target: &SyncTarget,
     layout: RemoteLayout,
 ) -> Result<Option<RemoteSnapshot>, AppError> {
-    let manifest_url = build_artifact_url(settings, layout, REMOTE_MANIFEST)?;
-    let Some((manifest_bytes, manifest_etag)) =
-        webdav::get_bytes(&manifest_url, auth, Some(MAX_MANIFEST_BYTES)).await?
+    let manifest_segments = artifact_segments(target, layout, REMOTE_MANIFEST);
+    let Some((manifest_bytes, manifest_etag)) = target
+        .transport
+        .get(&manifest_segments, Some(MAX_MANIFEST_BYTES))
+        .await?
     else {
         return Ok(None);
     };
@@ -488,8 +890,7 @@ async fn fetch_remote_snapshot(
 // ---------------------------------------------------------------------------
 
 async fn download_and_verify(
-    settings: &WebDavSyncSettings,
-    auth: &webdav::WebDavAuth,
+    target: &SyncTarget,
     layout: RemoteLayout,
     artifact_name: &str,
     artifacts: &BTreeMap<String, ArtifactMeta>,
@@ -504,8 +905,10 @@ async fn download_and_verify(
 
     validate_artifact_size_limit(artifact_name, meta.size)?;
 
-    let url = build_artifact_url(settings, layout, artifact_name)?;
-    let (bytes, _) = webdav::get_bytes(&url, auth, Some(MAX_SYNC_ARTIFACT_BYTES))
+    let segments = artifact_segments(target, layout, artifact_name);
+    let (bytes, _) = target
+        .transport
+        .get(&segments, Some(MAX_SYNC_ARTIFACT_BYTES))
         .await?
         .ok_or_else(|| {
             localized(
@@ -638,7 +1041,7 @@ fn extract_sql_user_version(sql: &str) -> Option<i32> {
 // ---------------------------------------------------------------------------
 
 fn persist_sync_success(
-    settings: &mut WebDavSyncSettings,
+    backend: SyncBackend,
     manifest_hash: &str,
     etag: Option<String>,
 ) -> Result<(), AppError> {
@@ -650,17 +1053,16 @@ fn persist_sync_success(
         last_local_manifest_hash: Some(manifest_hash.to_string()),
         last_remote_manifest_hash: Some(manifest_hash.to_string()),
     };
-    settings.status = status.clone();
-    update_webdav_sync_status(status)
+    update_sync_status(backend, status)
 }
 
 /// 尽力持久化同步状态，失败时仅记录日志
 fn persist_sync_success_best_effort(
-    settings: &mut WebDavSyncSettings,
+    backend: SyncBackend,
     manifest_hash: &str,
     etag: Option<String>,
 ) -> bool {
-    match persist_sync_success(settings, manifest_hash, etag) {
+    match persist_sync_success(backend, manifest_hash, etag) {
         Ok(()) => true,
         Err(e) => {
             log::warn!("持久化同步状态失败（非致命）: {e}");
@@ -797,30 +1199,27 @@ struct V1ArtifactMeta {
     size: u64,
 }
 
-fn v1_remote_dir_segments(settings: &WebDavSyncSettings) -> Vec<String> {
+fn v1_remote_dir_segments(target: &SyncTarget) -> Vec<String> {
     let mut segments = Vec::new();
-    segments.extend(webdav::path_segments(&settings.remote_root).map(str::to_string));
+    segments.extend(webdav::path_segments(&target.remote_root).map(str::to_string));
     segments.push(format!("v{V1_PROTOCOL_VERSION}"));
-    segments.extend(webdav::path_segments(&settings.profile).map(str::to_string));
+    segments.extend(webdav::path_segments(&target.profile).map(str::to_string));
     segments
 }
 
-fn build_v1_artifact_url(
-    settings: &WebDavSyncSettings,
-    file_name: &str,
-) -> Result<String, AppError> {
-    let mut segments = v1_remote_dir_segments(settings);
+fn v1_artifact_segments(target: &SyncTarget, file_name: &str) -> Vec<String> {
+    let mut segments = v1_remote_dir_segments(target);
     segments.extend(webdav::path_segments(file_name).map(str::to_string));
-    webdav::build_remote_url(&settings.base_url, &segments)
+    segments
 }
 
 /// 检测远端是否存在 V1 manifest，返回 Some(manifest) 或 None
-async fn detect_v1_manifest(
-    settings: &WebDavSyncSettings,
-    auth: &webdav::WebDavAuth,
-) -> Result<Option<V1Manifest>, AppError> {
-    let url = build_v1_artifact_url(settings, REMOTE_MANIFEST)?;
-    let result = webdav::get_bytes(&url, auth, Some(MAX_MANIFEST_BYTES)).await?;
+async fn detect_v1_manifest(target: &SyncTarget) -> Result<Option<V1Manifest>, AppError> {
+    let segments = v1_artifact_segments(target, REMOTE_MANIFEST);
+    let result = target
+        .transport
+        .get(&segments, Some(MAX_MANIFEST_BYTES))
+        .await?;
     match result {
         None => Ok(None),
         Some((bytes, _)) => {
@@ -841,8 +1240,7 @@ async fn detect_v1_manifest(
 
 /// 下载 V1 artifact 并校验
 async fn download_v1_artifact(
-    settings: &WebDavSyncSettings,
-    auth: &webdav::WebDavAuth,
+    target: &SyncTarget,
     file_name: &str,
     meta: &V1ArtifactMeta,
 ) -> Result<Vec<u8>, AppError> {
@@ -855,8 +1253,10 @@ async fn download_v1_artifact(
         ));
     }
 
-    let url = build_v1_artifact_url(settings, file_name)?;
-    let (bytes, _) = webdav::get_bytes(&url, auth, Some(MAX_SYNC_ARTIFACT_BYTES))
+    let segments = v1_artifact_segments(target, file_name);
+    let (bytes, _) = target
+        .transport
+        .get(&segments, Some(MAX_SYNC_ARTIFACT_BYTES))
         .await?
         .ok_or_else(|| {
             localized(
@@ -887,14 +1287,17 @@ async fn download_v1_artifact(
 }
 
 /// 删除 V1 远端目录（best-effort）
-async fn cleanup_v1_remote(settings: &WebDavSyncSettings, auth: &webdav::WebDavAuth) {
-    let segments = v1_remote_dir_segments(settings);
-    let url = match webdav::build_remote_url(&settings.base_url, &segments) {
-        Ok(u) => u,
-        Err(_) => return,
-    };
+async fn cleanup_v1_remote(target: &SyncTarget) {
+    let segments = v1_remote_dir_segments(target);
     // WebDAV DELETE on a collection removes the directory and all contents
-    match webdav::delete_collection(&url, auth).await {
+    match target
+        .transport
+        .delete_dir(
+            &segments,
+            &[REMOTE_MANIFEST, REMOTE_DB_SQL, REMOTE_SKILLS_ZIP],
+        )
+        .await
+    {
         Ok(true) => log::info!("[WebDAV] V1 remote data cleaned up"),
         Ok(false) => log::debug!("[WebDAV] V1 remote data already gone"),
         Err(e) => log::warn!("[WebDAV] Failed to clean up V1 remote data: {e}"),
@@ -902,12 +1305,12 @@ async fn cleanup_v1_remote(settings: &WebDavSyncSettings, auth: &webdav::WebDavA
 }
 
 /// 迁移 V1 → V2：下载 V1 数据 → 本地应用 → 上传 V2 → 删除 V1
-async fn migrate_v1_to_v2() -> Result<WebDavSyncSummary, AppError> {
-    let settings = load_webdav_settings()?;
-    let auth = webdav::auth_from_credentials(&settings.username, &settings.password);
+async fn migrate_v1_to_v2(backend: SyncBackend) -> Result<WebDavSyncSummary, AppError> {
+    let target = SyncTarget::load(backend)?;
+    target.transport.open().await?;
 
     // 1. 下载 V1 manifest
-    let v1_manifest = detect_v1_manifest(&settings, &auth).await?.ok_or_else(|| {
+    let v1_manifest = detect_v1_manifest(&target).await?.ok_or_else(|| {
         localized(
             "webdav.sync.v1_not_found",
             "远端未找到 V1 同步数据",
@@ -916,16 +1319,10 @@ async fn migrate_v1_to_v2() -> Result<WebDavSyncSummary, AppError> {
     })?;
 
     // 2. 下载 V1 artifacts（V1 的 settings_sync 不迁移，V2 不再同步该数据）
-    let db_sql = download_v1_artifact(
-        &settings,
-        &auth,
-        REMOTE_DB_SQL,
-        &v1_manifest.artifacts.db_sql,
-    )
-    .await?;
+    let db_sql =
+        download_v1_artifact(&target, REMOTE_DB_SQL, &v1_manifest.artifacts.db_sql).await?;
     let skills_zip = download_v1_artifact(
-        &settings,
-        &auth,
+        &target,
         REMOTE_SKILLS_ZIP,
         &v1_manifest.artifacts.skills_zip,
     )
@@ -940,7 +1337,7 @@ async fn migrate_v1_to_v2() -> Result<WebDavSyncSummary, AppError> {
     drop(_guard);
 
     // 4. 重新上传为 V2 格式（upload 内部会 best-effort 清理 V1 远端数据）
-    upload().await?;
+    upload(backend).await?;
 
     Ok(WebDavSyncSummary {
         decision: SyncDecision::Download,
@@ -990,6 +1387,8 @@ async fn ensure_restore_allowed() -> Result<(), AppError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::webdav;
+    use crate::settings::WebDavSyncSettings;
 
     fn sample_settings() -> WebDavSyncSettings {
         WebDavSyncSettings {
@@ -999,6 +1398,7 @@ mod tests {
             profile: "default profile".to_string(),
             username: "demo".to_string(),
             password: "secret".to_string(),
+            encryption_passphrase: String::new(),
             auto_sync: false,
             status: WebDavSyncStatus::default(),
         }
@@ -1008,7 +1408,8 @@ mod tests {
     fn remote_dir_segments_uses_current_layout() {
         let mut settings = sample_settings();
         settings.normalize();
-        let segments = remote_dir_segments(&settings, RemoteLayout::Current);
+        let target = SyncTarget::from_webdav(&settings);
+        let segments = remote_dir_segments(&target, RemoteLayout::Current);
         assert_eq!(
             segments,
             vec![
@@ -1025,8 +1426,12 @@ mod tests {
     fn build_artifact_url_encodes_path_segments() {
         let mut settings = sample_settings();
         settings.normalize();
-        let url = build_artifact_url(&settings, RemoteLayout::Current, REMOTE_MANIFEST)
-            .expect("build artifact url");
+        let target = SyncTarget::from_webdav(&settings);
+        let url = webdav::build_remote_url(
+            &settings.base_url,
+            &artifact_segments(&target, RemoteLayout::Current, REMOTE_MANIFEST),
+        )
+        .expect("build artifact url");
         assert_eq!(
             url,
             "https://dav.example.com/remote.php/dav/files/demo/cc%20switch-sync/team%20a/v2/db-v6/default%20profile/manifest.json"
@@ -1095,6 +1500,8 @@ mod tests {
             created_at: "2026-01-01T00:00:00Z".to_string(),
             artifacts: BTreeMap::new(),
             snapshot_id: "id".to_string(),
+            encryption: None,
+            records: BTreeMap::new(),
         }
     }
 
@@ -1126,7 +1533,8 @@ mod tests {
     fn remote_dir_segments_uses_legacy_layout() {
         let mut settings = sample_settings();
         settings.normalize();
-        let segments = remote_dir_segments(&settings, RemoteLayout::Legacy);
+        let target = SyncTarget::from_webdav(&settings);
+        let segments = remote_dir_segments(&target, RemoteLayout::Legacy);
         assert_eq!(
             segments,
             vec![
@@ -1479,6 +1887,8 @@ mod tests {
             created_at: "2026-01-01T00:00:00Z".to_string(),
             artifacts: BTreeMap::new(),
             snapshot_id: "snap-1".to_string(),
+            encryption: None,
+            records: BTreeMap::new(),
         };
         let value = serde_json::to_value(&manifest).expect("serialize manifest");
         assert!(
@@ -1493,5 +1903,170 @@ mod tests {
             value.get("dbCompatVersion").and_then(|v| v.as_u64()),
             Some(DB_COMPAT_VERSION as u64)
         );
+        assert!(
+            value.get("encryption").is_none(),
+            "plaintext manifest should omit encryption"
+        );
+    }
+
+    #[test]
+    fn remote_cipher_requires_passphrase_for_encrypted_manifest() {
+        let (_, encryption) = SyncCipher::create("team passphrase").expect("create cipher");
+        let mut manifest =
+            manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, Some(DB_COMPAT_VERSION));
+        manifest.encryption = Some(encryption);
+
+        let mut settings = sample_settings();
+        let err = remote_cipher(&SyncTarget::from_webdav(&settings), &manifest)
+            .err()
+            .expect("missing passphrase should fail");
+        assert!(
+            err.to_string().contains("同步口令") || err.to_string().contains("sync passphrase"),
+            "unexpected error: {err}"
+        );
+
+        settings.encryption_passphrase = "wrong".to_string();
+        assert!(remote_cipher(&SyncTarget::from_webdav(&settings), &manifest).is_err());
+
+        settings.encryption_passphrase = "team passphrase".to_string();
+        assert!(
+            remote_cipher(&SyncTarget::from_webdav(&settings), &manifest)
+                .expect("correct passphrase")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn remote_cipher_rejects_plaintext_manifest_when_passphrase_is_set() {
+        let manifest = manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, Some(DB_COMPAT_VERSION));
+        let mut settings = sample_settings();
+        assert!(
+            remote_cipher(&SyncTarget::from_webdav(&settings), &manifest)
+                .expect("plaintext manifest without passphrase")
+                .is_none()
+        );
+
+        settings.encryption_passphrase = "team passphrase".to_string();
+        let err = remote_cipher(&SyncTarget::from_webdav(&settings), &manifest)
+            .err()
+            .expect("plaintext manifest with passphrase should fail");
+        assert!(
+            err.to_string().contains("未加密") || err.to_string().contains("not encrypted"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn remote_cipher_rejects_out_of_range_iterations() {
+        let (_, mut encryption) = SyncCipher::create("team passphrase").expect("create cipher");
+        let mut settings = sample_settings();
+        settings.encryption_passphrase = "team passphrase".to_string();
+        for iterations in [1, u32::MAX] {
+            encryption.iterations = iterations;
+            let mut manifest =
+                manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, Some(DB_COMPAT_VERSION));
+            manifest.encryption = Some(encryption.clone());
+            let err = remote_cipher(&SyncTarget::from_webdav(&settings), &manifest)
+                .err()
+                .expect("out-of-range iterations should fail");
+            assert!(err.to_string().contains("iterations") || err.to_string().contains("迭代"));
+        }
+    }
+
+    #[test]
+    fn merge_retries_when_the_remote_changes_before_upload() -> Result<(), AppError> {
+        use super::transport::SyncTransport;
+        use crate::services::git_repo::GitRepo;
+        use crate::settings::GitSyncSettings;
+
+        if std::process::Command::new("git")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            eprintln!("git not installed; skipping");
+            return Ok(());
+        }
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let _env = crate::test_support::TestEnvGuard::isolated(temp.path());
+        let remote = temp.path().join("remote.git");
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()
+            .expect("run git init");
+        assert!(status.success());
+
+        let settings = GitSyncSettings {
+            enabled: true,
+            repo_url: remote.display().to_string(),
+            ..GitSyncSettings::default()
+        };
+        let target_for = |name: &str| SyncTarget {
+            backend: SyncBackend::Git,
+            transport: SyncTransport::Git(GitRepo::with_work_dir(
+                &settings,
+                temp.path().join(name),
+            )),
+            remote_root: settings.remote_root.clone(),
+            profile: settings.profile.clone(),
+            encryption_passphrase: String::new(),
+        };
+        let local = target_for("local");
+        let other = GitRepo::with_work_dir(&settings, temp.path().join("other"));
+        let save = |id: &str| {
+            Database::init()?.save_provider(
+                "claude",
+                &crate::provider::Provider::with_id(
+                    id.to_string(),
+                    id.to_uppercase(),
+                    serde_json::json!({}),
+                    None,
+                ),
+            )
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build runtime");
+        let resolutions = ConflictResolutions::default();
+        let manifest_segments = artifact_segments(&local, RemoteLayout::Current, REMOTE_MANIFEST);
+
+        save("p1")?;
+        runtime.block_on(merge_with_target(&local, &resolutions))?;
+
+        // 读取远端之后，另一台设备抢先推送
+        save("p2")?;
+        let seen = runtime.block_on(async {
+            local.transport.open().await?;
+            local
+                .transport
+                .read_revision(&manifest_segments, MAX_MANIFEST_BYTES)
+                .await
+        })?;
+        other.checkout()?;
+        other.write_file(&["other.txt".to_string()], b"other device")?;
+        other.commit_and_push("sync: other device")?;
+        let other_head = other.head_commit()?;
+        assert_ne!(seen, other_head);
+
+        let outcome = runtime.block_on(merge_once(&local, &resolutions, seen.as_deref()))?;
+        assert!(outcome.is_none(), "stale merge must not upload");
+        assert_eq!(other.remote_head()?, other_head);
+
+        let outcome = runtime.block_on(merge_with_target(&local, &resolutions))?;
+        assert!(matches!(outcome, MergeOutcome::Synced(_)));
+        let check = target_for("check");
+        let snapshot = runtime.block_on(async {
+            check.transport.open().await?;
+            find_remote_snapshot(&check).await
+        })?;
+        let records = snapshot.expect("remote snapshot").manifest.records;
+        assert!(records.contains_key("provider/claude/p2"), "{records:?}");
+        let SyncTransport::Git(repo) = &check.transport else {
+            unreachable!("git target");
+        };
+        assert!(repo.read_file(&["other.txt".to_string()], None)?.is_some());
+        Ok(())
     }
 }
@@ -0,0 +1,489 @@
+//! 同步记录的提取、应用与基线存储
+//!
+//! 记录从数据库（本地库或由远端 db.sql 导入的内存库）与 skills.zip 中提取：
+//! 供应商（含保险库密文及加密所用密钥的校验值）、MCP 服务器、提示词、共享提示词与
+//! Skills（含目录内容指纹）。设备相关的字段（当前供应商、故障转移队列、安装时间等）
+//! 不参与记录比较。
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::app_config::{AppType, InstalledSkill, McpServer};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::prompt::{Prompt, SharedPrompt};
+use crate::provider::Provider;
+use crate::secret_vault::ProviderSecret;
+
+use super::archive::restore_skill_dirs;
+use super::merge::RecordSet;
+use super::transport::SyncTarget;
+
+const PROVIDER_PREFIX: &str = "provider";
+const MCP_PREFIX: &str = "mcp";
+const PROMPT_PREFIX: &str = "prompt";
+const SHARED_PROMPT_PREFIX: &str = "shared_prompt";
+const SKILL_PREFIX: &str = "skill";
+
+/// Skill 记录中保存目录内容指纹的字段
+const SKILL_FILES_FIELD: &str = "files";
+/// 供应商记录中携带保险库密文的字段
+const PROVIDER_SECRETS_FIELD: &str = "secrets";
+/// 供应商记录中标识密文所属保险库的字段（保险库的 key_check）
+const PROVIDER_VAULT_FIELD: &str = "vaultKeyCheck";
+
+// ---------------------------------------------------------------------------
+// 提取
+// ---------------------------------------------------------------------------
+
+/// 从数据库与 skills 目录指纹中提取全部同步记录
+pub(super) fn collect_records(
+    db: &Database,
+    skill_hashes: &BTreeMap<String, String>,
+) -> Result<RecordSet, AppError> {
+    let mut records = RecordSet::new();
+
+    let vault_key_check = db
+        .get_secret_vault_config()?
+        .map(|config| Value::String(config.key_check));
+    let mut secrets: BTreeMap<(String, String), Vec<ProviderSecret>> = BTreeMap::new();
+    for secret in db.list_provider_secrets()? {
+        secrets
+            .entry((secret.app_type.clone(), secret.provider_id.clone()))
+            .or_default()
+            .push(secret);
+    }
+
+    for app_type in AppType::all() {
+        let app = app_type.as_str();
+        for (id, provider) in db.get_all_providers(app)? {
+            let mut value = to_value(&provider)?;
+            strip(&mut value, &["inFailoverQueue"]);
+            if let Some(secrets) = secrets.remove(&(app.to_string(), id.clone())) {
+                value[PROVIDER_SECRETS_FIELD] = to_value(&secrets)?;
+                if let Some(key_check) = &vault_key_check {
+                    value[PROVIDER_VAULT_FIELD] = key_check.clone();
+                }
+            }
+            records.insert(format!("{PROVIDER_PREFIX}/{app}/{id}"), value);
+        }
+        for (id, prompt) in db.get_prompts(app)? {
+            let mut value = to_value(&prompt)?;
+            strip(&mut value, &["updatedAt"]);
+            records.insert(format!("{PROMPT_PREFIX}/{app}/{id}"), value);
+        }
+    }
+
+    for (id, prompt) in db.get_shared_prompts()? {
+        let mut value = to_value(&prompt)?;
+        strip(&mut value, &["updatedAt"]);
+        records.insert(format!("{SHARED_PROMPT_PREFIX}/{id}"), value);
+    }
+
+    for (id, server) in db.get_all_mcp_servers()? {
+        records.insert(format!("{MCP_PREFIX}/{id}"), to_value(&server)?);
+    }
+
+    for (id, skill) in db.get_all_installed_skills()? {
+        let mut value = to_value(&skill)?;
+        strip(&mut value, &["installedAt"]);
+        value[SKILL_FILES_FIELD] = Value::String(
+            skill_hashes
+                .get(&skill.directory)
+                .cloned()
+                .unwrap_or_default(),
+        );
+        records.insert(format!("{SKILL_PREFIX}/{id}"), value);
+    }
+
+    Ok(records)
+}
+
+/// 记录版本：规范化（键排序）后 JSON 的 SHA256
+pub(super) fn record_versions(records: &RecordSet) -> BTreeMap<String, String> {
+    records
+        .iter()
+        .map(|(key, value)| {
+            let canonical = serde_json::to_vec(&canonicalize(value)).unwrap_or_default();
+            (key.clone(), format!("{:x}", Sha256::digest(&canonical)))
+        })
+        .collect()
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonicalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+fn strip(value: &mut Value, fields: &[&str]) {
+    if let Some(map) = value.as_object_mut() {
+        for field in fields {
+            map.shift_remove(*field);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 应用
+// ---------------------------------------------------------------------------
+
+/// 本地应用合并结果后的统计
+#[derive(Debug, Default)]
+pub(super) struct AppliedChanges {
+    pub updated: usize,
+    pub deleted: usize,
+    /// 因正在使用而保留的记录（如本机当前供应商），需回写到合并结果
+    pub kept: Vec<String>,
+    /// 记录被更新的本机当前供应商，需重新写入对应应用的 live 配置
+    pub current_providers: Vec<(AppType, String)>,
+    pub skills_changed: bool,
+}
+
+/// 把 `keys` 对应的合并结果写入本地；合并结果中不存在的键视为删除
+pub(super) fn apply_records(
+    db: &Database,
+    keys: &[String],
+    merged: &RecordSet,
+    local: &RecordSet,
+    remote_skills_zip: &[u8],
+) -> Result<AppliedChanges, AppError> {
+    ensure_same_vault(db, keys, merged)?;
+
+    let mut applied = AppliedChanges::default();
+    let mut replace_dirs = Vec::new();
+    let mut remove_dirs = Vec::new();
+
+    for key in keys {
+        let Some((kind, rest)) = key.split_once('/') else {
+            log::warn!("[Sync] 忽略无法识别的同步记录: {key}");
+            continue;
+        };
+        let value = merged.get(key);
+        match (kind, rest.split_once('/')) {
+            (PROVIDER_PREFIX, Some((app, id))) => match value {
+                Some(value) => {
+                    let mut value = value.clone();
+                    strip(&mut value, &[PROVIDER_VAULT_FIELD]);
+                    let secrets: Vec<ProviderSecret> = value
+                        .as_object_mut()
+                        .and_then(|map| map.shift_remove(PROVIDER_SECRETS_FIELD))
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| record_error(key, e))?
+                        .unwrap_or_default();
+                    let provider: Provider =
+                        serde_json::from_value(value).map_err(|e| record_error(key, e))?;
+                    db.save_provider(app, &provider)?;
+                    if !secrets.is_empty() {
+                        db.store_sealed_provider_settings(
+                            app,
+                            id,
+                            &provider.settings_config,
+                            &secrets,
+                        )?;
+                    }
+                    let app_type: AppType = app.parse()?;
+                    if crate::settings::get_effective_current_provider(db, &app_type)?.as_deref()
+                        == Some(id)
+                    {
+                        applied.current_providers.push((app_type, id.to_string()));
+                    }
+                    applied.updated += 1;
+                }
+                None if db.get_current_provider(app)?.as_deref() == Some(id) => {
+                    log::info!("[Sync] 保留本机当前供应商 {app}/{id}（远端已删除）");
+                    applied.kept.push(key.clone());
+                }
+                None => {
+                    db.delete_provider(app, id)?;
+                    applied.deleted += 1;
+                }
+            },
+            (PROMPT_PREFIX, Some((app, id))) => match value {
+                Some(value) => {
+                    let mut prompt: Prompt =
+                        serde_json::from_value(value.clone()).map_err(|e| record_error(key, e))?;
+                    prompt.updated_at = Some(chrono::Utc::now().timestamp());
+                    db.save_prompt(app, &prompt)?;
+                    applied.updated += 1;
+                }
+                None => {
+                    db.delete_prompt(app, id)?;
+                    applied.deleted += 1;
+                }
+            },
+            (SHARED_PROMPT_PREFIX, _) => match value {
+                Some(value) => {
+                    let mut prompt: SharedPrompt =
+                        serde_json::from_value(value.clone()).map_err(|e| record_error(key, e))?;
+                    prompt.updated_at = Some(chrono::Utc::now().timestamp());
+                    db.save_shared_prompt(&prompt)?;
+                    applied.updated += 1;
+                }
+                None => {
+                    db.delete_shared_prompt(rest)?;
+                    applied.deleted += 1;
+                }
+            },
+            (MCP_PREFIX, _) => match value {
+                Some(value) => {
+                    let server: McpServer =
+                        serde_json::from_value(value.clone()).map_err(|e| record_error(key, e))?;
+                    db.save_mcp_server(&server)?;
+                    applied.updated += 1;
+                }
+                None => {
+                    db.delete_mcp_server(rest)?;
+                    applied.deleted += 1;
+                }
+            },
+            (SKILL_PREFIX, _) => {
+                let id = rest;
+                let local_value = local.get(key);
+                let files = |value: Option<&Value>| {
+                    value
+                        .and_then(|value| value.get(SKILL_FILES_FIELD))
+                        .cloned()
+                };
+                match value {
+                    Some(value) => {
+                        let mut value = value.clone();
+                        strip(&mut value, &[SKILL_FILES_FIELD]);
+                        let installed_at = db
+                            .get_installed_skill(id)?
+                            .map(|skill| skill.installed_at)
+                            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+                        value["installedAt"] = Value::from(installed_at);
+                        let skill: InstalledSkill =
+                            serde_json::from_value(value).map_err(|e| record_error(key, e))?;
+                        if files(merged.get(key)) != files(local_value) {
+                            replace_dirs.push(skill.directory.clone());
+                        }
+                        db.save_skill(&skill)?;
+                        applied.updated += 1;
+                    }
+                    None => {
+                        if let Some(directory) = local_value
+                            .and_then(|value| value.get("directory"))
+                            .and_then(Value::as_str)
+                        {
+                            remove_dirs.push(directory.to_string());
+                        }
+                        db.delete_skill(id)?;
+                        applied.deleted += 1;
+                    }
+                }
+                applied.skills_changed = true;
+            }
+            _ => log::warn!("[Sync] 忽略无法识别的同步记录: {key}"),
+        }
+    }
+
+    if !replace_dirs.is_empty() || !remove_dirs.is_empty() {
+        restore_skill_dirs(remote_skills_zip, &replace_dirs, &remove_dirs)?;
+    }
+    Ok(applied)
+}
+
+/// 带密文的供应商记录必须由本机保险库的同一密钥加密，否则写入后在本机无法解密
+fn ensure_same_vault(db: &Database, keys: &[String], merged: &RecordSet) -> Result<(), AppError> {
+    let local_key_check = db.get_secret_vault_config()?.map(|config| config.key_check);
+    for key in keys {
+        if !key.starts_with(&format!("{PROVIDER_PREFIX}/")) {
+            continue;
+        }
+        let Some(value) = merged.get(key) else {
+            continue;
+        };
+        if value.get(PROVIDER_SECRETS_FIELD).is_none() {
+            continue;
+        }
+        let key_check = value.get(PROVIDER_VAULT_FIELD).and_then(Value::as_str);
+        if key_check.is_none() || key_check != local_key_check.as_deref() {
+            return Err(AppError::localized(
+                "sync.merge.vault_mismatch",
+                format!(
+                    "同步记录 {key} 的密钥由其他设备的保险库加密，本机无法解密：请先在本机使用相同的保险库密钥"
+                ),
+                format!(
+                    "Sync record {key} holds secrets sealed by another device's vault; set up this device with the same vault key first"
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn record_error(key: &str, e: serde_json::Error) -> AppError {
+    AppError::localized(
+        "sync.merge.record_invalid",
+        format!("同步记录 {key} 格式无效: {e}"),
+        format!("Sync record {key} is malformed: {e}"),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// 基线
+// ---------------------------------------------------------------------------
+
+/// 上次同步成功时双方一致的记录集合，按同步目标分别保存
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncBase {
+    #[serde(default)]
+    records: RecordSet,
+}
+
+fn base_path(target: &SyncTarget) -> PathBuf {
+    let mut hasher = Sha256::new();
+    for part in [
+        target.backend.as_str(),
+        target.transport.location().as_str(),
+        target.remote_root.as_str(),
+        target.profile.as_str(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\n");
+    }
+    let id = format!("{:x}", hasher.finalize());
+    crate::config::get_app_config_dir()
+        .join("sync")
+        .join("base")
+        .join(format!("{}.json", &id[..16]))
+}
+
+/// 读取基线；从未同步过时返回空集合
+pub(super) fn load_base(target: &SyncTarget) -> Result<RecordSet, AppError> {
+    let path = base_path(target);
+    let raw = match std::fs::read(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(RecordSet::new()),
+        Err(e) => return Err(AppError::io(&path, e)),
+    };
+    let base: SyncBase = serde_json::from_slice(&raw).map_err(|e| AppError::Json {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    Ok(base.records)
+}
+
+pub(super) fn save_base(target: &SyncTarget, records: &RecordSet) -> Result<(), AppError> {
+    let path = base_path(target);
+    if let Some(parent) = path.parent() {
+        crate::database::create_secure_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec(&SyncBase {
+        records: records.clone(),
+    })
+    .map_err(|e| AppError::JsonSerialize { source: e })?;
+    crate::config::atomic_write(&path, &bytes)
+}
+
+/// 尽力保存基线，失败时仅记录日志（下次合并会把差异当作双方新增处理）
+pub(super) fn save_base_best_effort(target: &SyncTarget, records: &RecordSet) {
+    if let Err(e) = save_base(target, records) {
+        log::warn!("保存同步基线失败（非致命）: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn record_versions_ignore_key_order() {
+        let a = RecordSet::from([("mcp/x".to_string(), json!({"a": 1, "b": {"c": 2, "d": 3}}))]);
+        let b = RecordSet::from([("mcp/x".to_string(), json!({"b": {"d": 3, "c": 2}, "a": 1}))]);
+        assert_eq!(record_versions(&a), record_versions(&b));
+    }
+
+    #[test]
+    fn collect_records_skips_device_local_fields() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let mut provider = Provider::with_id(
+            "p1".to_string(),
+            "Relay".to_string(),
+            json!({"env": {"ANTHROPIC_BASE_URL": "https://relay.example"}}),
+            None,
+        );
+        provider.in_failover_queue = true;
+        db.save_provider("claude", &provider)?;
+
+        let records = collect_records(&db, &BTreeMap::new())?;
+        let record = &records["provider/claude/p1"];
+        assert_eq!(record["name"], "Relay");
+        assert!(record.get("inFailoverQueue").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_records_refuses_secrets_sealed_by_another_vault() -> Result<(), AppError> {
+        use crate::secret_vault::{create_vault, vault_ref_id, VaultKeySource};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let vault = |name: &str| {
+            create_vault(VaultKeySource::KeyFile, None, Some(&dir.path().join(name)))
+                .map(|(config, _)| config)
+        };
+        let remote_db = Database::memory()?;
+        let remote_vault = vault("remote.key")?;
+        remote_db.set_secret_vault_config(&remote_vault)?;
+        remote_db.save_provider(
+            "claude",
+            &Provider::with_id(
+                "p1".to_string(),
+                "Relay".to_string(),
+                json!({"env": {"ANTHROPIC_AUTH_TOKEN": "sk-remote"}}),
+                None,
+            ),
+        )?;
+        let remote = collect_records(&remote_db, &BTreeMap::new())?;
+        let keys = vec!["provider/claude/p1".to_string()];
+
+        let other_db = Database::memory()?;
+        other_db.set_secret_vault_config(&vault("other.key")?)?;
+        let err = apply_records(&other_db, &keys, &remote, &RecordSet::new(), &[])
+            .expect_err("secrets from another vault");
+        assert!(err.to_string().contains("provider/claude/p1"), "{err}");
+        assert!(other_db.get_all_providers("claude")?.is_empty());
+
+        let plain_db = Database::memory()?;
+        assert!(apply_records(&plain_db, &keys, &remote, &RecordSet::new(), &[]).is_err());
+
+        let same_db = Database::memory()?;
+        same_db.set_secret_vault_config(&remote_vault)?;
+        apply_records(&same_db, &keys, &remote, &RecordSet::new(), &[])?;
+        let token = same_db.get_all_providers("claude")?["p1"].settings_config["env"]
+            ["ANTHROPIC_AUTH_TOKEN"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        assert!(vault_ref_id(&token).is_some(), "{token}");
+        assert_eq!(
+            crate::secret_vault::reveal_value(&same_db, &token)?,
+            "sk-remote"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+//! 同步协议的可插拔传输层
+//!
+//! manifest 协议只关心"按路径分段读写字节"，具体由 WebDAV、S3 兼容对象存储或 git 仓库实现。
+
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::services::git_repo::GitRepo;
+use crate::services::s3::{self, S3Bucket};
+use crate::services::webdav;
+use crate::settings::{
+    get_git_sync_settings, get_s3_sync_settings, get_webdav_sync_settings, SyncBackend,
+};
+
+pub(super) enum SyncTransport {
+    WebDav {
+        base_url: String,
+        auth: webdav::WebDavAuth,
+    },
+    S3(S3Bucket),
+    Git(GitRepo),
+}
+
+/// 已加载的同步目标：传输层 + 协议层共用参数
+pub(super) struct SyncTarget {
+    pub backend: SyncBackend,
+    pub transport: SyncTransport,
+    pub remote_root: String,
+    pub profile: String,
+    pub encryption_passphrase: String,
+}
+
+fn not_configured(backend: SyncBackend) -> AppError {
+    AppError::localized(
+        "sync.not_configured",
+        format!("未配置 {} 同步", backend.as_str()),
+        format!("{} sync is not configured", backend.as_str()),
+    )
+}
+
+fn not_enabled(backend: SyncBackend) -> AppError {
+    AppError::localized(
+        "sync.not_enabled",
+        format!("{} 同步未启用", backend.as_str()),
+        format!("{} sync is not enabled", backend.as_str()),
+    )
+}
+
+impl SyncTarget {
+    /// 从设置加载指定后端；未配置或未启用时报错
+    pub fn load(backend: SyncBackend) -> Result<Self, AppError> {
+        match backend {
+            SyncBackend::WebDav => {
+                let settings = get_webdav_sync_settings().ok_or_else(|| {
+                    AppError::localized(
+                        "webdav.sync.not_configured",
+                        "未配置 WebDAV 同步",
+                        "WebDAV sync is not configured",
+                    )
+                })?;
+                if !settings.enabled {
+                    return Err(AppError::localized(
+                        "webdav.sync.not_enabled",
+                        "WebDAV 同步未启用",
+                        "WebDAV sync is not enabled",
+                    ));
+                }
+                settings.validate()?;
+                Ok(Self::from_webdav(&settings))
+            }
+            SyncBackend::S3 => {
+                let settings = get_s3_sync_settings().ok_or_else(|| not_configured(backend))?;
+                if !settings.enabled {
+                    return Err(not_enabled(backend));
+                }
+                settings.validate()?;
+                Ok(Self {
+                    backend,
+                    transport: SyncTransport::S3(S3Bucket::new(&settings)?),
+                    remote_root: settings.remote_root,
+                    profile: settings.profile,
+                    encryption_passphrase: settings.encryption_passphrase,
+                })
+            }
+            SyncBackend::Git => {
+                let settings = get_git_sync_settings().ok_or_else(|| not_configured(backend))?;
+                if !settings.enabled {
+                    return Err(not_enabled(backend));
+                }
+                settings.validate()?;
+                Ok(Self {
+                    backend,
+                    transport: SyncTransport::Git(GitRepo::new(&settings)),
+                    remote_root: settings.remote_root,
+                    profile: settings.profile,
+                    encryption_passphrase: settings.encryption_passphrase,
+                })
+            }
+        }
+    }
+
+    pub fn from_webdav(settings: &crate::settings::WebDavSyncSettings) -> Self {
+        Self {
+            backend: SyncBackend::WebDav,
+            transport: SyncTransport::WebDav {
+                base_url: settings.base_url.clone(),
+                auth: webdav::auth_from_credentials(&settings.username, &settings.password),
+            },
+            remote_root: settings.remote_root.clone(),
+            profile: settings.profile.clone(),
+            encryption_passphrase: settings.encryption_passphrase.clone(),
+        }
+    }
+}
+
+impl SyncTransport {
+    /// 远端位置描述，用于区分本地保存的同步基线
+    pub fn location(&self) -> String {
+        match self {
+            Self::WebDav { base_url, .. } => base_url.clone(),
+            Self::S3(bucket) => bucket.location(),
+            Self::Git(repo) => repo.location(),
+        }
+    }
+
+    pub async fn test_connection(&self) -> Result<(), AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => webdav::test_connection(base_url, auth).await,
+            Self::S3(bucket) => bucket.test_connection().await,
+            Self::Git(repo) => repo.test_connection(),
+        }
+    }
+
+    /// 读写前的准备：git 与远端分支对齐，其余后端无需操作
+    pub async fn open(&self) -> Result<(), AppError> {
+        match self {
+            Self::Git(repo) => repo.checkout(),
+            Self::WebDav { .. } | Self::S3(_) => Ok(()),
+        }
+    }
+
+    /// 确保目录存在（仅 WebDAV 需要显式创建目录）
+    pub async fn ensure_dirs(&self, dir_segments: &[String]) -> Result<(), AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => {
+                webdav::ensure_remote_directories(base_url, dir_segments, auth).await
+            }
+            Self::S3(_) | Self::Git(_) => Ok(()),
+        }
+    }
+
+    pub async fn put(
+        &self,
+        segments: &[String],
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => {
+                let url = webdav::build_remote_url(base_url, segments)?;
+                webdav::put_bytes(&url, auth, bytes, content_type).await
+            }
+            Self::S3(bucket) => {
+                bucket
+                    .put_object(&s3::object_key(segments), bytes, content_type)
+                    .await
+            }
+            Self::Git(repo) => repo.write_file(segments, &bytes),
+        }
+    }
+
+    pub async fn get(
+        &self,
+        segments: &[String],
+        max_bytes: Option<u64>,
+    ) -> Result<Option<(Vec<u8>, Option<String>)>, AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => {
+                let url = webdav::build_remote_url(base_url, segments)?;
+                webdav::get_bytes(&url, auth, max_bytes).await
+            }
+            Self::S3(bucket) => {
+                bucket
+                    .get_object(&s3::object_key(segments), max_bytes)
+                    .await
+            }
+            Self::Git(repo) => repo.read_file(segments, max_bytes),
+        }
+    }
+
+    pub async fn etag(&self, segments: &[String]) -> Result<Option<String>, AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => {
+                let url = webdav::build_remote_url(base_url, segments)?;
+                webdav::head_etag(&url, auth).await
+            }
+            Self::S3(bucket) => bucket.head_object(&s3::object_key(segments)).await,
+            Self::Git(repo) => repo.file_etag(segments),
+        }
+    }
+
+    /// 本次读取所基于的远端版本：git 为工作副本对齐到的提交，其余后端同 `remote_revision`
+    pub async fn read_revision(
+        &self,
+        manifest_segments: &[String],
+        max_bytes: u64,
+    ) -> Result<Option<String>, AppError> {
+        match self {
+            Self::Git(repo) => repo.head_commit(),
+            Self::WebDav { .. } | Self::S3(_) => {
+                self.remote_revision(manifest_segments, max_bytes).await
+            }
+        }
+    }
+
+    /// 远端当前版本：git 为远端分支的最新提交，其余后端为 manifest 内容的 SHA256
+    pub async fn remote_revision(
+        &self,
+        manifest_segments: &[String],
+        max_bytes: u64,
+    ) -> Result<Option<String>, AppError> {
+        match self {
+            Self::Git(repo) => repo.remote_head(),
+            Self::WebDav { .. } | Self::S3(_) => Ok(self
+                .get(manifest_segments, Some(max_bytes))
+                .await?
+                .map(|(bytes, _)| format!("{:x}", Sha256::digest(&bytes)))),
+        }
+    }
+
+    /// 删除目录；S3 没有真正的目录，按需逐个删除对象
+    pub async fn delete_dir(
+        &self,
+        dir_segments: &[String],
+        file_names: &[&str],
+    ) -> Result<bool, AppError> {
+        match self {
+            Self::WebDav { base_url, auth } => {
+                let url = webdav::build_remote_url(base_url, dir_segments)?;
+                webdav::delete_collection(&url, auth).await
+            }
+            Self::S3(bucket) => {
+                let mut deleted = false;
+                for name in file_names {
+                    let mut segments = dir_segments.to_vec();
+                    segments.push((*name).to_string());
+                    deleted |= bucket.delete_object(&s3::object_key(&segments)).await?;
+                }
+                Ok(deleted)
+            }
+            Self::Git(repo) => repo.remove_dir(dir_segments),
+        }
+    }
+
+    /// 写入完成后的提交：git 提交并推送，其余后端写入即生效
+    pub async fn commit(&self, message: &str) -> Result<(), AppError> {
+        match self {
+            Self::Git(repo) => repo.commit_and_push(message),
+            Self::WebDav { .. } | Self::S3(_) => Ok(()),
+        }
+    }
+}
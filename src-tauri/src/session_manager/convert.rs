@@ -0,0 +1,575 @@
+//! Convert a saved session into another app's on-disk format so the same
+//! conversation can be resumed there.
+//!
+//! Tool names and call ids differ between apps, so tool calls and results are
+//! carried over as plain text and thinking blocks are dropped. Consecutive
+//! messages of the same role are merged to keep user/assistant turns
+//! alternating.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::export::SessionTranscript;
+use super::MessagePart;
+
+/// Tool output kept per result; longer output is cut to keep the context small.
+const TOOL_RESULT_MAX_CHARS: usize = 4000;
+
+/// Context that apps inject as user messages and that should not be replayed.
+const INJECTED_CONTEXT_PREFIXES: &[&str] = &[
+    "<environment_context>",
+    "<user_instructions>",
+    "# AGENTS.md",
+    "<local-command-caveat>",
+    "<command-name>",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConvertTarget {
+    Claude,
+    Codex,
+    Gemini,
+}
+
+impl ConvertTarget {
+    pub fn provider_id(self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+            Self::Gemini => "gemini",
+        }
+    }
+
+    fn resume_command(self, session_id: &str) -> String {
+        match self {
+            Self::Claude => format!("claude --resume {session_id}"),
+            Self::Codex => format!("codex resume {session_id}"),
+            Self::Gemini => format!("gemini --resume {session_id}"),
+        }
+    }
+
+    fn config_root(self) -> PathBuf {
+        match self {
+            Self::Claude => crate::config::get_claude_config_dir(),
+            Self::Codex => crate::codex_config::get_codex_config_dir(),
+            Self::Gemini => crate::gemini_config::get_gemini_dir(),
+        }
+    }
+}
+
+/// The session written by a conversion.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedSession {
+    pub provider_id: String,
+    pub session_id: String,
+    pub source_path: PathBuf,
+    pub project_dir: String,
+    pub turns: usize,
+    pub resume_command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TurnRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Turn {
+    role: TurnRole,
+    text: String,
+    ts: Option<i64>,
+}
+
+/// Write `transcript` as a new session of `target` under that app's config
+/// directory, rooted at `project_dir`.
+pub fn convert_session(
+    transcript: &SessionTranscript,
+    target: ConvertTarget,
+    project_dir: &str,
+) -> Result<ConvertedSession, String> {
+    convert_session_into(transcript, target, project_dir, &target.config_root())
+}
+
+fn convert_session_into(
+    transcript: &SessionTranscript,
+    target: ConvertTarget,
+    project_dir: &str,
+    root: &Path,
+) -> Result<ConvertedSession, String> {
+    if transcript.session.provider_id == target.provider_id() {
+        return Err(format!(
+            "Session already belongs to {}; nothing to convert",
+            target.provider_id()
+        ));
+    }
+
+    let turns = conversation_turns(transcript);
+    if turns.is_empty() {
+        return Err("Session has no user or assistant messages to convert".to_string());
+    }
+
+    let session_id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let timestamps = turn_timestamps(&turns, transcript.session.created_at, now);
+    let (path, contents) = match target {
+        ConvertTarget::Claude => {
+            claude_session(root, project_dir, &session_id, &turns, &timestamps)?
+        }
+        ConvertTarget::Codex => {
+            codex_session(root, project_dir, &session_id, &turns, &timestamps, now)?
+        }
+        ConvertTarget::Gemini => {
+            gemini_session(root, project_dir, &session_id, &turns, &timestamps, now)?
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write converted session {}: {e}", path.display()))?;
+
+    Ok(ConvertedSession {
+        provider_id: target.provider_id().to_string(),
+        resume_command: target.resume_command(&session_id),
+        session_id,
+        source_path: path,
+        project_dir: project_dir.to_string(),
+        turns: turns.len(),
+    })
+}
+
+fn conversation_turns(transcript: &SessionTranscript) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    for message in &transcript.messages {
+        let role = match message.role.as_str() {
+            "user" | "tool" => TurnRole::User,
+            "assistant" | "gemini" | "model" => TurnRole::Assistant,
+            _ => continue,
+        };
+
+        let text = message
+            .normalized_parts()
+            .iter()
+            .filter_map(part_text)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if message.role == "user"
+            && INJECTED_CONTEXT_PREFIXES
+                .iter()
+                .any(|prefix| text.starts_with(prefix))
+        {
+            continue;
+        }
+
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                last.text.push_str("\n\n");
+                last.text.push_str(text);
+            }
+            _ => turns.push(Turn {
+                role,
+                text: text.to_string(),
+                ts: message.ts,
+            }),
+        }
+    }
+    turns
+}
+
+fn part_text(part: &MessagePart) -> Option<String> {
+    match part {
+        MessagePart::Text { text } => Some(text.trim().to_string()).filter(|t| !t.is_empty()),
+        MessagePart::Thinking { .. } => None,
+        MessagePart::ToolCall { name, input, .. } => Some(match input {
+            Value::Null => format!("[Tool call: {name}]"),
+            input => format!("[Tool call: {name}] {input}"),
+        }),
+        MessagePart::ToolResult {
+            content, is_error, ..
+        } => {
+            let label = if *is_error {
+                "[Tool error]"
+            } else {
+                "[Tool result]"
+            };
+            Some(format!("{label}\n{}", truncate_chars(content.trim())))
+        }
+    }
+}
+
+fn truncate_chars(text: &str) -> String {
+    match text.char_indices().nth(TOOL_RESULT_MAX_CHARS) {
+        Some((cut, _)) => format!("{}\n[... truncated]", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// Timestamp per turn; turns without one reuse the previous turn's time.
+fn turn_timestamps(turns: &[Turn], created_at: Option<i64>, now: DateTime<Utc>) -> Vec<String> {
+    let mut last = created_at.unwrap_or_else(|| now.timestamp_millis());
+    turns
+        .iter()
+        .map(|turn| {
+            last = turn.ts.unwrap_or(last);
+            Utc.timestamp_millis_opt(last)
+                .single()
+                .unwrap_or(now)
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+        .collect()
+}
+
+fn to_jsonl(lines: &[Value]) -> Result<String, String> {
+    let mut out = String::new();
+    for line in lines {
+        let encoded = serde_json::to_string(line)
+            .map_err(|e| format!("Failed to serialize session line: {e}"))?;
+        out.push_str(&encoded);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Claude Code keeps sessions in `projects/<cwd with non-alphanumerics as '-'>/<id>.jsonl`.
+fn claude_session(
+    root: &Path,
+    project_dir: &str,
+    session_id: &str,
+    turns: &[Turn],
+    timestamps: &[String],
+) -> Result<(PathBuf, String), String> {
+    let encoded_dir = project_dir
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let path = root
+        .join("projects")
+        .join(encoded_dir)
+        .join(format!("{session_id}.jsonl"));
+
+    let mut parent: Option<String> = None;
+    let mut lines = Vec::with_capacity(turns.len());
+    for (turn, timestamp) in turns.iter().zip(timestamps) {
+        let uuid = Uuid::new_v4().to_string();
+        let (kind, message) = match turn.role {
+            TurnRole::User => ("user", json!({ "role": "user", "content": turn.text })),
+            TurnRole::Assistant => (
+                "assistant",
+                json!({
+                    "id": format!("msg_{}", Uuid::new_v4().simple()),
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "<synthetic>",
+                    "content": [{ "type": "text", "text": turn.text }],
+                    "stop_reason": "end_turn",
+                    "stop_sequence": null,
+                }),
+            ),
+        };
+        lines.push(json!({
+            "parentUuid": parent,
+            "isSidechain": false,
+            "userType": "external",
+            "cwd": project_dir,
+            "sessionId": session_id,
+            "type": kind,
+            "message": message,
+            "uuid": uuid,
+            "timestamp": timestamp,
+        }));
+        parent = Some(uuid);
+    }
+
+    Ok((path, to_jsonl(&lines)?))
+}
+
+/// Codex keeps rollouts in `sessions/YYYY/MM/DD/rollout-<local time>-<id>.jsonl`.
+/// Besides the response items replayed to the model, user and agent events are
+/// written so the session shows up in `codex resume` and its history view.
+fn codex_session(
+    root: &Path,
+    project_dir: &str,
+    session_id: &str,
+    turns: &[Turn],
+    timestamps: &[String],
+    now: DateTime<Utc>,
+) -> Result<(PathBuf, String), String> {
+    let local = now.with_timezone(&Local);
+    let path = root
+        .join("sessions")
+        .join(local.format("%Y").to_string())
+        .join(local.format("%m").to_string())
+        .join(local.format("%d").to_string())
+        .join(format!(
+            "rollout-{}-{session_id}.jsonl",
+            local.format("%Y-%m-%dT%H-%M-%S")
+        ));
+
+    let started = timestamps.first().cloned().unwrap_or_default();
+    let mut lines = vec![json!({
+        "timestamp": started,
+        "type": "session_meta",
+        "payload": {
+            "id": session_id,
+            "timestamp": started,
+            "cwd": project_dir,
+            "originator": "cc_switch",
+            "cli_version": env!("CARGO_PKG_VERSION"),
+            "instructions": null,
+            "source": "cli",
+        },
+    })];
+    for (turn, timestamp) in turns.iter().zip(timestamps) {
+        let (role, content_type, event_type) = match turn.role {
+            TurnRole::User => ("user", "input_text", "user_message"),
+            TurnRole::Assistant => ("assistant", "output_text", "agent_message"),
+        };
+        lines.push(json!({
+            "timestamp": timestamp,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": role,
+                "content": [{ "type": content_type, "text": turn.text }],
+            },
+        }));
+        let mut event = json!({ "type": event_type, "message": turn.text });
+        if turn.role == TurnRole::User {
+            event["images"] = json!([]);
+        }
+        lines.push(json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": event,
+        }));
+    }
+
+    Ok((path, to_jsonl(&lines)?))
+}
+
+/// Gemini CLI keeps chats in `tmp/<sha256 of project root>/chats/session-*.json`.
+fn gemini_session(
+    root: &Path,
+    project_dir: &str,
+    session_id: &str,
+    turns: &[Turn],
+    timestamps: &[String],
+    now: DateTime<Utc>,
+) -> Result<(PathBuf, String), String> {
+    let project_hash = format!("{:x}", Sha256::digest(project_dir.as_bytes()));
+    let project_root = root.join("tmp").join(&project_hash);
+    let path = project_root.join("chats").join(format!(
+        "session-{}-{}.json",
+        now.format("%Y-%m-%dT%H-%M"),
+        &session_id[..8]
+    ));
+
+    // The session scanner reads the project directory from this marker file.
+    let marker = project_root.join(".project_root");
+    if !marker.exists() {
+        std::fs::create_dir_all(&project_root)
+            .map_err(|e| format!("Failed to create {}: {e}", project_root.display()))?;
+        std::fs::write(&marker, project_dir)
+            .map_err(|e| format!("Failed to write {}: {e}", marker.display()))?;
+    }
+
+    let messages = turns
+        .iter()
+        .zip(timestamps)
+        .map(|(turn, timestamp)| {
+            json!({
+                "id": Uuid::new_v4().to_string(),
+                "timestamp": timestamp,
+                "type": match turn.role {
+                    TurnRole::User => "user",
+                    TurnRole::Assistant => "gemini",
+                },
+                "content": turn.text,
+            })
+        })
+        .collect::<Vec<_>>();
+    let document = json!({
+        "sessionId": session_id,
+        "projectHash": project_hash,
+        "startTime": timestamps.first(),
+        "lastUpdated": timestamps.last(),
+        "messages": messages,
+    });
+    let contents = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize Gemini session: {e}"))?;
+
+    Ok((path, contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_manager::providers::{claude, codex, gemini};
+    use crate::session_manager::{SessionMessage, SessionMeta};
+    use tempfile::tempdir;
+
+    fn message(role: &str, content: &str, parts: Vec<MessagePart>) -> SessionMessage {
+        SessionMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            ts: Some(1_772_791_200_000),
+            parts,
+        }
+    }
+
+    fn transcript(provider_id: &str) -> SessionTranscript {
+        SessionTranscript {
+            session: SessionMeta {
+                provider_id: provider_id.to_string(),
+                session_id: "source-1".to_string(),
+                title: None,
+                summary: None,
+                project_dir: Some("/work/app".to_string()),
+                created_at: Some(1_772_791_100_000),
+                last_active_at: None,
+                source_path: None,
+                resume_command: None,
+            },
+            messages: vec![
+                message(
+                    "user",
+                    "<environment_context>cwd</environment_context>",
+                    Vec::new(),
+                ),
+                message("user", "Why does login fail?", Vec::new()),
+                message(
+                    "assistant",
+                    "[Tool: Read]",
+                    vec![
+                        MessagePart::Thinking {
+                            text: "Check the handler".to_string(),
+                        },
+                        MessagePart::ToolCall {
+                            id: Some("toolu_1".to_string()),
+                            name: "Read".to_string(),
+                            input: json!({ "path": "src/login.rs" }),
+                        },
+                    ],
+                ),
+                message(
+                    "tool",
+                    "fn login() {}",
+                    vec![MessagePart::ToolResult {
+                        id: Some("toolu_1".to_string()),
+                        content: "fn login() {}".to_string(),
+                        is_error: false,
+                    }],
+                ),
+                message("assistant", "The handler is empty.", Vec::new()),
+                message("assistant", "Add the password check.", Vec::new()),
+            ],
+        }
+    }
+
+    #[test]
+    fn conversation_turns_flatten_tools_and_merge_same_role() {
+        let turns = conversation_turns(&transcript("claude"));
+        let roles = turns.iter().map(|turn| turn.role).collect::<Vec<_>>();
+        assert_eq!(
+            roles,
+            vec![
+                TurnRole::User,
+                TurnRole::Assistant,
+                TurnRole::User,
+                TurnRole::Assistant
+            ]
+        );
+        assert_eq!(turns[0].text, "Why does login fail?");
+        assert_eq!(
+            turns[1].text,
+            "[Tool call: Read] {\"path\":\"src/login.rs\"}"
+        );
+        assert_eq!(turns[2].text, "[Tool result]\nfn login() {}");
+        assert_eq!(
+            turns[3].text,
+            "The handler is empty.\n\nAdd the password check."
+        );
+    }
+
+    #[test]
+    fn converted_sessions_load_back_in_target_format() {
+        let temp = tempdir().expect("tempdir");
+        let source = transcript("claude");
+
+        let codex_out =
+            convert_session_into(&source, ConvertTarget::Codex, "/work/app", temp.path())
+                .expect("convert to codex");
+        assert!(codex_out
+            .source_path
+            .starts_with(temp.path().join("sessions")));
+        assert_eq!(
+            codex_out.resume_command,
+            format!("codex resume {}", codex_out.session_id)
+        );
+        let messages = codex::load_messages(&codex_out.source_path).expect("load codex");
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].role, "user");
+        assert_eq!(messages[0].content, "Why does login fail?");
+
+        let source = transcript("codex");
+        let claude_out =
+            convert_session_into(&source, ConvertTarget::Claude, "/work/app", temp.path())
+                .expect("convert to claude");
+        assert_eq!(
+            claude_out.source_path,
+            temp.path()
+                .join("projects/-work-app")
+                .join(format!("{}.jsonl", claude_out.session_id))
+        );
+        let messages = claude::load_messages(&claude_out.source_path).expect("load claude");
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[3].role, "assistant");
+        assert!(messages[3].content.contains("Add the password check."));
+
+        let gemini_out =
+            convert_session_into(&source, ConvertTarget::Gemini, "/work/app", temp.path())
+                .expect("convert to gemini");
+        let messages = gemini::load_messages(&gemini_out.source_path).expect("load gemini");
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1].role, "assistant");
+        let marker = gemini_out
+            .source_path
+            .parent()
+            .and_then(Path::parent)
+            .map(|dir| dir.join(".project_root"))
+            .expect("project root");
+        assert_eq!(std::fs::read_to_string(marker).unwrap(), "/work/app");
+    }
+
+    #[test]
+    fn convert_rejects_same_provider_and_empty_sessions() {
+        let temp = tempdir().expect("tempdir");
+        let err = convert_session_into(
+            &transcript("codex"),
+            ConvertTarget::Codex,
+            "/work/app",
+            temp.path(),
+        )
+        .unwrap_err();
+        assert!(err.contains("already belongs to codex"));
+
+        let mut empty = transcript("claude");
+        empty.messages.retain(|message| message.role == "system");
+        assert!(
+            convert_session_into(&empty, ConvertTarget::Gemini, "/work/app", temp.path()).is_err()
+        );
+    }
+}
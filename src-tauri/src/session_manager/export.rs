@@ -0,0 +1,466 @@
+//! Render saved sessions as Markdown, self-contained HTML or normalized JSONL.
+//!
+//! Exports work on full transcripts (see [`super::load_transcript`]) so tool
+//! calls, tool results and thinking blocks survive instead of being flattened
+//! into the preview text.
+
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{MessagePart, SessionMessage, SessionMeta};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[value(name = "md", alias = "markdown")]
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// A session together with every message of its transcript.
+#[derive(Debug, Clone)]
+pub struct SessionTranscript {
+    pub session: SessionMeta,
+    pub messages: Vec<SessionMessage>,
+}
+
+pub fn render(format: ExportFormat, transcripts: &[SessionTranscript]) -> Result<String, String> {
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(transcripts)),
+        ExportFormat::Html => Ok(render_html(transcripts)),
+        ExportFormat::Jsonl => render_jsonl(transcripts),
+    }
+}
+
+fn session_heading(session: &SessionMeta) -> String {
+    session
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} session {}", session.provider_id, session.session_id))
+}
+
+fn format_time(ts: Option<i64>) -> Option<String> {
+    ts.and_then(|ts| Local.timestamp_millis_opt(ts).single())
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn role_label(role: &str) -> String {
+    let mut chars = role.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Unknown".to_string(),
+    }
+}
+
+fn pretty_input(input: &Value) -> String {
+    match input {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+fn metadata_rows(session: &SessionMeta) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Provider", session.provider_id.clone()),
+        ("Session", session.session_id.clone()),
+    ];
+    let optional = [
+        ("Workdir", session.project_dir.clone()),
+        ("Started", format_time(session.created_at)),
+        ("Last active", format_time(session.last_active_at)),
+        ("Resume", session.resume_command.clone()),
+    ];
+    rows.extend(
+        optional.into_iter().filter_map(|(label, value)| {
+            value.filter(|v| !v.trim().is_empty()).map(|v| (label, v))
+        }),
+    );
+    rows
+}
+
+// ── Markdown ──
+
+fn render_markdown(transcripts: &[SessionTranscript]) -> String {
+    let mut out = String::new();
+    for (index, transcript) in transcripts.iter().enumerate() {
+        if index > 0 {
+            out.push_str("\n---\n\n");
+        }
+        let session = &transcript.session;
+        out.push_str(&format!("# {}\n\n", session_heading(session)));
+        for (label, value) in metadata_rows(session) {
+            out.push_str(&format!("- **{label}:** {}\n", inline_code(&value)));
+        }
+
+        for message in &transcript.messages {
+            out.push('\n');
+            let mut heading = format!("## {}", role_label(&message.role));
+            if let Some(time) = format_time(message.ts) {
+                heading.push_str(&format!(" · {time}"));
+            }
+            out.push_str(&heading);
+            out.push_str("\n\n");
+            for part in message.normalized_parts() {
+                out.push_str(&markdown_part(&part));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn markdown_part(part: &MessagePart) -> String {
+    match part {
+        MessagePart::Text { text } => format!("{}\n", text.trim_end()),
+        MessagePart::Thinking { text } => format!(
+            "<details>\n<summary>Thinking</summary>\n\n{}\n\n</details>\n",
+            text.trim_end()
+        ),
+        MessagePart::ToolCall { name, input, .. } => {
+            let input = pretty_input(input);
+            if input.is_empty() {
+                format!("**Tool call:** {}\n", inline_code(name))
+            } else {
+                format!(
+                    "**Tool call:** {}\n\n{}",
+                    inline_code(name),
+                    fenced(&input, "json")
+                )
+            }
+        }
+        MessagePart::ToolResult {
+            content, is_error, ..
+        } => {
+            let label = if *is_error {
+                "Tool error"
+            } else {
+                "Tool result"
+            };
+            format!(
+                "<details>\n<summary>{label}</summary>\n\n{}\n</details>\n",
+                fenced(content, "text")
+            )
+        }
+    }
+}
+
+/// Fence `body` with more backticks than any run it contains.
+fn fenced(body: &str, lang: &str) -> String {
+    let longest = longest_backtick_run(body);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}{lang}\n{}\n{fence}\n", body.trim_end())
+}
+
+fn inline_code(value: &str) -> String {
+    let ticks = "`".repeat(longest_backtick_run(value) + 1);
+    let pad = if value.starts_with('`') || value.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{ticks}{pad}{value}{pad}{ticks}")
+}
+
+fn longest_backtick_run(value: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in value.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+// ── HTML ──
+
+const HTML_STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; line-height: 1.5; }
+h1 { border-bottom: 1px solid #d0d7de; padding-bottom: .3rem; }
+dl.meta { display: grid; grid-template-columns: max-content 1fr; gap: .2rem 1rem; font-size: .9rem; }
+dl.meta dt { font-weight: 600; color: #57606a; }
+dl.meta dd { margin: 0; font-family: ui-monospace, monospace; word-break: break-all; }
+.message { border: 1px solid #d0d7de; border-radius: 6px; margin: 1rem 0; padding: .75rem 1rem; }
+.message.user { background: #f6f8fa; }
+.message.tool { background: #fbfbf0; }
+.message header { font-weight: 600; margin-bottom: .5rem; }
+.message header time { font-weight: normal; color: #57606a; margin-left: .5rem; }
+.text { white-space: pre-wrap; }
+pre { background: #f6f8fa; border-radius: 6px; padding: .5rem; overflow-x: auto; white-space: pre-wrap; }
+details { margin: .5rem 0; }
+summary { cursor: pointer; color: #57606a; }
+.error summary { color: #cf222e; }
+hr { margin: 2rem 0; }
+"#;
+
+fn render_html(transcripts: &[SessionTranscript]) -> String {
+    let title = match transcripts {
+        [single] => session_heading(&single.session),
+        _ => format!("{} sessions", transcripts.len()),
+    };
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_html(&title)));
+    out.push_str(&format!("<style>{HTML_STYLE}</style>\n</head>\n<body>\n"));
+
+    for (index, transcript) in transcripts.iter().enumerate() {
+        if index > 0 {
+            out.push_str("<hr>\n");
+        }
+        let session = &transcript.session;
+        out.push_str("<section class=\"session\">\n");
+        out.push_str(&format!(
+            "<h1>{}</h1>\n<dl class=\"meta\">\n",
+            escape_html(&session_heading(session))
+        ));
+        for (label, value) in metadata_rows(session) {
+            out.push_str(&format!(
+                "<dt>{label}</dt><dd>{}</dd>\n",
+                escape_html(&value)
+            ));
+        }
+        out.push_str("</dl>\n");
+
+        for message in &transcript.messages {
+            out.push_str(&format!(
+                "<article class=\"message {}\">\n<header>{}",
+                escape_html(&message.role),
+                escape_html(&role_label(&message.role))
+            ));
+            if let Some(time) = format_time(message.ts) {
+                out.push_str(&format!("<time>{}</time>", escape_html(&time)));
+            }
+            out.push_str("</header>\n");
+            for part in message.normalized_parts() {
+                out.push_str(&html_part(&part));
+            }
+            out.push_str("</article>\n");
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_part(part: &MessagePart) -> String {
+    match part {
+        MessagePart::Text { text } => {
+            format!("<div class=\"text\">{}</div>\n", escape_html(text.trim_end()))
+        }
+        MessagePart::Thinking { text } => format!(
+            "<details class=\"thinking\"><summary>Thinking</summary><div class=\"text\">{}</div></details>\n",
+            escape_html(text.trim_end())
+        ),
+        MessagePart::ToolCall { name, input, .. } => {
+            let input = pretty_input(input);
+            let body = if input.is_empty() {
+                String::new()
+            } else {
+                format!("<pre>{}</pre>", escape_html(&input))
+            };
+            format!(
+                "<details class=\"tool-call\" open><summary>Tool call: <code>{}</code></summary>{body}</details>\n",
+                escape_html(name)
+            )
+        }
+        MessagePart::ToolResult {
+            content, is_error, ..
+        } => {
+            let (class, label) = if *is_error {
+                ("tool-result error", "Tool error")
+            } else {
+                ("tool-result", "Tool result")
+            };
+            format!(
+                "<details class=\"{class}\"><summary>{label}</summary><pre>{}</pre></details>\n",
+                escape_html(content.trim_end())
+            )
+        }
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+// ── JSONL ──
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonlRecord<'a> {
+    #[serde(rename_all = "camelCase")]
+    Session {
+        #[serde(flatten)]
+        session: &'a SessionMeta,
+        message_count: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    Message {
+        provider_id: &'a str,
+        session_id: &'a str,
+        index: usize,
+        role: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ts: Option<i64>,
+        parts: Vec<MessagePart>,
+    },
+}
+
+/// One `session` record followed by one `message` record per message. Every
+/// message carries at least one part, so consumers never need `content`.
+fn render_jsonl(transcripts: &[SessionTranscript]) -> Result<String, String> {
+    let mut out = String::new();
+    for transcript in transcripts {
+        let session = &transcript.session;
+        let header = JsonlRecord::Session {
+            session,
+            message_count: transcript.messages.len(),
+        };
+        push_json_line(&mut out, &header)?;
+        for (index, message) in transcript.messages.iter().enumerate() {
+            let record = JsonlRecord::Message {
+                provider_id: &session.provider_id,
+                session_id: &session.session_id,
+                index,
+                role: &message.role,
+                ts: message.ts,
+                parts: message.normalized_parts(),
+            };
+            push_json_line(&mut out, &record)?;
+        }
+    }
+    Ok(out)
+}
+
+fn push_json_line(out: &mut String, record: &JsonlRecord<'_>) -> Result<(), String> {
+    let line =
+        serde_json::to_string(record).map_err(|e| format!("Failed to serialize session: {e}"))?;
+    out.push_str(&line);
+    out.push('\n');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transcript() -> SessionTranscript {
+        SessionTranscript {
+            session: SessionMeta {
+                provider_id: "claude".to_string(),
+                session_id: "abc123".to_string(),
+                title: Some("Fix <login> bug".to_string()),
+                summary: None,
+                project_dir: Some("/work/app".to_string()),
+                created_at: None,
+                last_active_at: None,
+                source_path: Some("/tmp/abc123.jsonl".to_string()),
+                resume_command: Some("claude --resume abc123".to_string()),
+            },
+            messages: vec![
+                SessionMessage {
+                    role: "user".to_string(),
+                    content: "Why does login fail?".to_string(),
+                    ts: None,
+                    parts: Vec::new(),
+                },
+                SessionMessage {
+                    role: "assistant".to_string(),
+                    content: "[Tool: Read]".to_string(),
+                    ts: None,
+                    parts: vec![
+                        MessagePart::Thinking {
+                            text: "Check the handler".to_string(),
+                        },
+                        MessagePart::ToolCall {
+                            id: Some("toolu_1".to_string()),
+                            name: "Read".to_string(),
+                            input: json!({"file_path": "login.rs"}),
+                        },
+                    ],
+                },
+                SessionMessage {
+                    role: "tool".to_string(),
+                    content: "fn login() { ```raw``` }".to_string(),
+                    ts: None,
+                    parts: vec![MessagePart::ToolResult {
+                        id: Some("toolu_1".to_string()),
+                        content: "fn login() { ```raw``` }".to_string(),
+                        is_error: false,
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn markdown_keeps_tool_calls_results_and_thinking() {
+        let output = render(ExportFormat::Markdown, &[transcript()]).expect("render");
+
+        assert!(output.starts_with("# Fix <login> bug\n"));
+        assert!(output.contains("<summary>Thinking</summary>"));
+        assert!(output.contains("**Tool call:** `Read`"));
+        assert!(output.contains("\"file_path\": \"login.rs\""));
+        assert!(output.contains("````text\nfn login() { ```raw``` }\n````"));
+    }
+
+    #[test]
+    fn html_is_escaped_and_self_contained() {
+        let output = render(ExportFormat::Html, &[transcript()]).expect("render");
+
+        assert!(output.contains("<title>Fix &lt;login&gt; bug</title>"));
+        assert!(output.contains("<style>"));
+        assert!(!output.contains("<script"));
+        assert!(output.contains("Tool call: <code>Read</code>"));
+    }
+
+    #[test]
+    fn jsonl_emits_session_header_and_normalized_messages() {
+        let output = render(ExportFormat::Jsonl, &[transcript()]).expect("render");
+        let lines = output
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("valid json line"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["type"], "session");
+        assert_eq!(lines[0]["sessionId"], "abc123");
+        assert_eq!(lines[0]["messageCount"], 3);
+        assert_eq!(lines[1]["parts"][0]["type"], "text");
+        assert_eq!(lines[2]["parts"][1]["type"], "tool_call");
+        assert_eq!(lines[2]["parts"][1]["input"]["file_path"], "login.rs");
+        assert_eq!(lines[3]["parts"][0]["type"], "tool_result");
+    }
+}
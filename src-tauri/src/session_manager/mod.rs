@@ -1,7 +1,10 @@
+pub mod convert;
+pub mod export;
 pub mod providers;
 pub mod terminal;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::{Path, PathBuf};
 
 use providers::{claude, codex, gemini, hermes, openclaw, opencode};
@@ -31,9 +34,51 @@ pub struct SessionMeta {
 #[serde(rename_all = "camelCase")]
 pub struct SessionMessage {
     pub role: String,
+    /// Flattened text used by list previews and search.
     pub content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ts: Option<i64>,
+    /// Structured blocks (text, thinking, tool calls and results) when the
+    /// provider's log format carries them. Empty for plain-text messages.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<MessagePart>,
+}
+
+impl SessionMessage {
+    /// Structured parts, falling back to a single text part built from `content`.
+    pub fn normalized_parts(&self) -> Vec<MessagePart> {
+        if self.parts.is_empty() {
+            return vec![MessagePart::Text {
+                text: self.content.clone(),
+            }];
+        }
+        self.parts.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessagePart {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolCall {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        name: String,
+        #[serde(skip_serializing_if = "Value::is_null")]
+        input: Value,
+    },
+    ToolResult {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        content: String,
+        #[serde(rename = "isError", skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -113,7 +158,23 @@ pub fn scan_sessions_for_provider(provider_id: &str) -> Vec<SessionMeta> {
     sessions
 }
 
+/// Messages with visible text, as shown in previews and indexed for search.
+/// Blocks without flattened text (e.g. standalone thinking) are left out; use
+/// [`load_transcript`] to keep them.
 pub fn load_messages(provider_id: &str, source_path: &str) -> Result<Vec<SessionMessage>, String> {
+    load_transcript(provider_id, source_path).map(|messages| {
+        messages
+            .into_iter()
+            .filter(|message| !message.content.trim().is_empty())
+            .collect()
+    })
+}
+
+/// Every parsed message, including ones that only carry structured parts.
+pub fn load_transcript(
+    provider_id: &str,
+    source_path: &str,
+) -> Result<Vec<SessionMessage>, String> {
     // SQLite sessions use a "sqlite:" prefixed source_path
     if provider_id == "opencode" && source_path.starts_with("sqlite:") {
         return opencode::load_messages_sqlite(source_path);
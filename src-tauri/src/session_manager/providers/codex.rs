@@ -7,11 +7,11 @@ use regex::Regex;
 use serde_json::Value;
 
 use crate::codex_config::get_codex_config_dir;
-use crate::session_manager::{SessionMessage, SessionMeta};
+use crate::session_manager::{MessagePart, SessionMessage, SessionMeta};
 
 use super::utils::{
-    extract_text, parse_timestamp_to_ms, path_basename, read_head_tail_lines, truncate_summary,
-    TITLE_MAX_CHARS,
+    extract_parts, extract_text, parse_timestamp_to_ms, parse_tool_arguments, path_basename,
+    read_head_tail_lines, truncate_summary, TITLE_MAX_CHARS,
 };
 
 const PROVIDER_ID: &str = "codex";
@@ -62,8 +62,13 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
 
         let payload_type = payload.get("type").and_then(Value::as_str).unwrap_or("");
 
+        let call_id = payload
+            .get("call_id")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
         // Codex uses separate payload types for tool interactions
-        let (role, content) = match payload_type {
+        let (role, content, parts) = match payload_type {
             "message" => {
                 let role = payload
                     .get("role")
@@ -71,14 +76,27 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
                     .unwrap_or("unknown")
                     .to_string();
                 let content = payload.get("content").map(extract_text).unwrap_or_default();
-                (role, content)
+                let parts = payload
+                    .get("content")
+                    .map(extract_parts)
+                    .unwrap_or_default();
+                (role, content, parts)
             }
             "function_call" => {
                 let name = payload
                     .get("name")
                     .and_then(Value::as_str)
                     .unwrap_or("unknown");
-                ("assistant".to_string(), format!("[Tool: {name}]"))
+                let part = MessagePart::ToolCall {
+                    id: call_id,
+                    name: name.to_string(),
+                    input: parse_tool_arguments(payload.get("arguments")),
+                };
+                (
+                    "assistant".to_string(),
+                    format!("[Tool: {name}]"),
+                    vec![part],
+                )
             }
             "function_call_output" => {
                 let output = payload
@@ -86,18 +104,43 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
                     .and_then(Value::as_str)
                     .unwrap_or("")
                     .to_string();
-                ("tool".to_string(), output)
+                let part = MessagePart::ToolResult {
+                    id: call_id,
+                    content: output.clone(),
+                    is_error: false,
+                };
+                ("tool".to_string(), output, vec![part])
+            }
+            // Reasoning summaries are transcript-only; they carry no preview text
+            "reasoning" => {
+                let parts = payload
+                    .get("summary")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|item| item.get("text").and_then(Value::as_str))
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| MessagePart::Thinking {
+                        text: text.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                ("assistant".to_string(), String::new(), parts)
             }
             _ => continue,
         };
 
-        if content.trim().is_empty() {
+        if content.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
         let ts = value.get("timestamp").and_then(parse_timestamp_to_ms);
 
-        messages.push(SessionMessage { role, content, ts });
+        messages.push(SessionMessage {
+            role,
+            content,
+            ts,
+            parts,
+        });
     }
 
     Ok(messages)
@@ -2,7 +2,7 @@ use std::path::Path;
 
 use serde_json::Value;
 
-use crate::session_manager::{SessionMessage, SessionMeta};
+use crate::session_manager::{MessagePart, SessionMessage, SessionMeta};
 
 use super::utils::{parse_timestamp_to_ms, truncate_summary};
 
@@ -84,6 +84,13 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
             _ => String::new(),
         };
 
+        let mut parts = gemini_thought_parts(msg);
+        if !content.trim().is_empty() {
+            parts.push(MessagePart::Text {
+                text: content.clone(),
+            });
+        }
+
         // Append tool call names from the optional toolCalls array
         if let Some(Value::Array(calls)) = msg.get("toolCalls") {
             for call in calls {
@@ -92,11 +99,12 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
                         content.push('\n');
                     }
                     content.push_str(&format!("[Tool: {name}]"));
+                    parts.extend(gemini_tool_call_parts(call, name));
                 }
             }
         }
 
-        if content.trim().is_empty() {
+        if content.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
@@ -106,12 +114,62 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
             role: role.to_string(),
             content,
             ts,
+            parts,
         });
     }
 
     Ok(result)
 }
 
+fn gemini_thought_parts(msg: &Value) -> Vec<MessagePart> {
+    msg.get("thoughts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|thought| {
+            let subject = thought.get("subject").and_then(Value::as_str).unwrap_or("");
+            let description = thought
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            let text = match (subject.trim(), description.trim()) {
+                ("", "") => return None,
+                (subject, "") => subject.to_string(),
+                ("", description) => description.to_string(),
+                (subject, description) => format!("{subject}: {description}"),
+            };
+            Some(MessagePart::Thinking { text })
+        })
+        .collect()
+}
+
+fn gemini_tool_call_parts(call: &Value, name: &str) -> Vec<MessagePart> {
+    let id = call.get("id").and_then(Value::as_str).map(str::to_string);
+    let mut parts = vec![MessagePart::ToolCall {
+        id: id.clone(),
+        name: name.to_string(),
+        input: call.get("args").cloned().unwrap_or(Value::Null),
+    }];
+
+    let output = call
+        .get("resultDisplay")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| {
+            call.get("result")
+                .filter(|result| !result.is_null())
+                .map(|result| serde_json::to_string(result).unwrap_or_default())
+        });
+    if let Some(content) = output {
+        parts.push(MessagePart::ToolResult {
+            id,
+            content,
+            is_error: call.get("status").and_then(Value::as_str) == Some("error"),
+        });
+    }
+    parts
+}
+
 pub fn delete_session(_root: &Path, path: &Path, session_id: &str) -> Result<bool, String> {
     let meta = parse_session(path).ok_or_else(|| {
         format!(
@@ -9,7 +9,8 @@ use crate::hermes_config::get_hermes_dir;
 use crate::session_manager::{SessionMessage, SessionMeta};
 
 use super::utils::{
-    extract_text, parse_timestamp_to_ms, read_head_tail_lines, truncate_summary, TITLE_MAX_CHARS,
+    extract_parts, extract_text, parse_timestamp_to_ms, read_head_tail_lines, truncate_summary,
+    TITLE_MAX_CHARS,
 };
 
 const PROVIDER_ID: &str = "hermes";
@@ -223,6 +224,7 @@ pub fn load_messages_sqlite(source: &str) -> Result<Vec<SessionMessage>, String>
             role,
             content,
             ts: ts_ms,
+            parts: Vec::new(),
         });
     }
 
@@ -473,12 +475,18 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
         };
 
         let content = content_val.map(extract_text).unwrap_or_default();
-        if content.trim().is_empty() {
+        let parts = content_val.map(extract_parts).unwrap_or_default();
+        if content.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
         let ts = ts_val.and_then(parse_timestamp_to_ms);
-        messages.push(SessionMessage { role, content, ts });
+        messages.push(SessionMessage {
+            role,
+            content,
+            ts,
+            parts,
+        });
     }
 
     Ok(messages)
@@ -8,12 +8,12 @@ use serde_json::Value;
 use crate::openclaw_config::get_openclaw_dir;
 use crate::{
     config::write_json_file,
-    session_manager::{SessionMessage, SessionMeta},
+    session_manager::{MessagePart, SessionMessage, SessionMeta},
 };
 
 use super::utils::{
-    extract_text, parse_timestamp_to_ms, path_basename, read_head_tail_lines, truncate_summary,
-    TITLE_MAX_CHARS,
+    extract_parts, extract_text, parse_timestamp_to_ms, path_basename, read_head_tail_lines,
+    truncate_summary, TITLE_MAX_CHARS,
 };
 
 const PROVIDER_ID: &str = "openclaw";
@@ -110,13 +110,33 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
         };
 
         let content = message.get("content").map(extract_text).unwrap_or_default();
-        if content.trim().is_empty() {
+        let parts = if raw_role == "toolResult" {
+            vec![MessagePart::ToolResult {
+                id: message
+                    .get("toolCallId")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                content: content.clone(),
+                is_error: message.get("isError").and_then(Value::as_bool) == Some(true),
+            }]
+        } else {
+            message
+                .get("content")
+                .map(extract_parts)
+                .unwrap_or_default()
+        };
+        if content.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
         let ts = value.get("timestamp").and_then(parse_timestamp_to_ms);
 
-        messages.push(SessionMessage { role, content, ts });
+        messages.push(SessionMessage {
+            role,
+            content,
+            ts,
+            parts,
+        });
     }
 
     Ok(messages)
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use rusqlite::Connection;
 use serde_json::Value;
 
-use crate::session_manager::{SessionMessage, SessionMeta};
+use crate::session_manager::{MessagePart, SessionMessage, SessionMeta};
 
 use super::utils::{parse_timestamp_to_ms, path_basename, truncate_summary};
 
@@ -170,7 +170,7 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
     collect_json_files(path, &mut msg_files);
 
     // Parse all messages and collect (created_ts, message_id, role, parts_text)
-    let mut entries: Vec<(i64, String, String, String)> = Vec::new();
+    let mut entries: Vec<(i64, String, String, String, Vec<MessagePart>)> = Vec::new();
 
     for msg_path in &msg_files {
         let data = match std::fs::read_to_string(msg_path) {
@@ -201,23 +201,24 @@ pub fn load_messages(path: &Path) -> Result<Vec<SessionMessage>, String> {
 
         // Collect text parts from storage/part/{messageID}/
         let part_dir = storage.join("part").join(&msg_id);
-        let text = collect_parts_text(&part_dir);
-        if text.trim().is_empty() {
+        let (text, parts) = collect_parts(&part_dir);
+        if text.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
-        entries.push((created_ts, msg_id, role, text));
+        entries.push((created_ts, msg_id, role, text, parts));
     }
 
     // Sort by created timestamp
-    entries.sort_by_key(|(ts, _, _, _)| *ts);
+    entries.sort_by_key(|(ts, _, _, _, _)| *ts);
 
     let messages = entries
         .into_iter()
-        .map(|(ts, _, role, content)| SessionMessage {
+        .map(|(ts, _, role, content, parts)| SessionMessage {
             role,
             content,
             ts: if ts > 0 { Some(ts) } else { None },
+            parts,
         })
         .collect();
 
@@ -286,8 +287,9 @@ pub fn load_messages_sqlite(source: &str) -> Result<Vec<SessionMessage>, String>
             .to_string();
 
         let mut texts = Vec::new();
-        if let Some(parts) = parts_map.get(&msg_id) {
-            for part_data in parts {
+        let mut parts = Vec::new();
+        if let Some(part_rows) = parts_map.get(&msg_id) {
+            for part_data in part_rows {
                 let part_value: Value = match serde_json::from_str(part_data) {
                     Ok(v) => v,
                     Err(_) => continue,
@@ -295,11 +297,12 @@ pub fn load_messages_sqlite(source: &str) -> Result<Vec<SessionMessage>, String>
                 if let Some(text) = extract_part_text(&part_value) {
                     texts.push(text);
                 }
+                parts.extend(extract_message_parts(&part_value));
             }
         }
 
         let content = texts.join("\n");
-        if content.trim().is_empty() {
+        if content.trim().is_empty() && parts.is_empty() {
             continue;
         }
 
@@ -307,6 +310,7 @@ pub fn load_messages_sqlite(source: &str) -> Result<Vec<SessionMessage>, String>
             role,
             content,
             ts: Some(ts),
+            parts,
         });
     }
 
@@ -522,7 +526,7 @@ fn get_first_user_summary(storage: &Path, session_id: &str) -> Option<String> {
     // Take first user message and get its parts
     let (_, first_id) = user_msgs.first()?;
     let part_dir = storage.join("part").join(first_id);
-    let text = collect_parts_text(&part_dir);
+    let (text, _) = collect_parts(&part_dir);
     if text.trim().is_empty() {
         return None;
     }
@@ -548,15 +552,59 @@ fn extract_part_text(part_value: &Value) -> Option<String> {
     }
 }
 
-fn collect_parts_text(part_dir: &Path) -> String {
+/// Structured view of an OpenCode part: text, reasoning, or a tool call with
+/// its output once the call has completed.
+fn extract_message_parts(part_value: &Value) -> Vec<MessagePart> {
+    let string_field = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|text| !text.trim().is_empty())
+            .map(str::to_string)
+    };
+
+    match part_value.get("type").and_then(Value::as_str) {
+        Some("text") => string_field(part_value, "text")
+            .map(|text| MessagePart::Text { text })
+            .into_iter()
+            .collect(),
+        Some("reasoning") => string_field(part_value, "text")
+            .map(|text| MessagePart::Thinking { text })
+            .into_iter()
+            .collect(),
+        Some("tool") => {
+            let id = string_field(part_value, "callID");
+            let state = part_value.get("state").unwrap_or(&Value::Null);
+            let mut parts = vec![MessagePart::ToolCall {
+                id: id.clone(),
+                name: string_field(part_value, "tool").unwrap_or_else(|| "unknown".to_string()),
+                input: state.get("input").cloned().unwrap_or(Value::Null),
+            }];
+            let status = state.get("status").and_then(Value::as_str);
+            let output = string_field(state, "output").or_else(|| string_field(state, "error"));
+            if let Some(content) = output {
+                parts.push(MessagePart::ToolResult {
+                    id,
+                    content,
+                    is_error: status == Some("error"),
+                });
+            }
+            parts
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn collect_parts(part_dir: &Path) -> (String, Vec<MessagePart>) {
     if !part_dir.is_dir() {
-        return String::new();
+        return (String::new(), Vec::new());
     }
 
     let mut parts = Vec::new();
     collect_json_files(part_dir, &mut parts);
 
     let mut texts = Vec::new();
+    let mut message_parts = Vec::new();
     for part_path in &parts {
         let data = match std::fs::read_to_string(part_path) {
             Ok(d) => d,
@@ -570,9 +618,10 @@ fn collect_parts_text(part_dir: &Path) -> String {
         if let Some(text) = extract_part_text(&value) {
             texts.push(text);
         }
+        message_parts.extend(extract_message_parts(&value));
     }
 
-    texts.join("\n")
+    (texts.join("\n"), message_parts)
 }
 
 fn collect_json_files(root: &Path, files: &mut Vec<PathBuf>) {
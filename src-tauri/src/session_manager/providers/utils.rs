@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use chrono::{DateTime, FixedOffset};
 use serde_json::Value;
 
-use crate::session_manager::SessionMeta;
+use crate::session_manager::{MessagePart, SessionMeta};
 
 /// Parse many session files in parallel, preserving input order. Each file is
 /// read independently, so this is a pure fan-out over `parse`. Falls back to a
@@ -172,6 +172,58 @@ fn extract_text_from_item(item: &Value) -> Option<String> {
     None
 }
 
+/// Structured blocks from an Anthropic-style content array. Also understands
+/// OpenClaw's `toolCall` items. Plain string content yields a single text part.
+pub fn extract_parts(content: &Value) -> Vec<MessagePart> {
+    match content {
+        Value::String(text) if !text.trim().is_empty() => vec![MessagePart::Text {
+            text: text.to_string(),
+        }],
+        Value::Array(items) => items.iter().filter_map(extract_part_from_item).collect(),
+        Value::Object(_) => extract_part_from_item(content).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn extract_part_from_item(item: &Value) -> Option<MessagePart> {
+    let string_field = |key: &str| item.get(key).and_then(Value::as_str).map(str::to_string);
+
+    match item.get("type").and_then(Value::as_str).unwrap_or("") {
+        "thinking" | "reasoning" => string_field("thinking")
+            .or_else(|| string_field("text"))
+            .filter(|text| !text.trim().is_empty())
+            .map(|text| MessagePart::Thinking { text }),
+        "tool_use" | "toolCall" | "tool_call" => Some(MessagePart::ToolCall {
+            id: string_field("id"),
+            name: string_field("name").unwrap_or_else(|| "unknown".to_string()),
+            input: item
+                .get("input")
+                .or_else(|| item.get("arguments"))
+                .cloned()
+                .unwrap_or(Value::Null),
+        }),
+        "tool_result" => Some(MessagePart::ToolResult {
+            id: string_field("tool_use_id"),
+            content: item.get("content").map(extract_text).unwrap_or_default(),
+            is_error: item.get("is_error").and_then(Value::as_bool) == Some(true),
+        }),
+        _ => extract_text_from_item(item)
+            .filter(|text| !text.trim().is_empty())
+            .map(|text| MessagePart::Text { text }),
+    }
+}
+
+/// Tool call arguments are often a JSON-encoded string; decode when possible.
+pub fn parse_tool_arguments(value: Option<&Value>) -> Value {
+    match value {
+        Some(Value::String(raw)) => {
+            serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+        }
+        Some(other) => other.clone(),
+        None => Value::Null,
+    }
+}
+
 pub fn truncate_summary(text: &str, max_chars: usize) -> String {
     let trimmed = text.trim();
     if trimmed.is_empty() {
@@ -204,6 +256,38 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn extract_parts_keeps_thinking_and_tool_blocks() {
+        let parts = extract_parts(&json!([
+            {"type": "thinking", "thinking": "Plan the edit"},
+            {"type": "text", "text": "Writing the file."},
+            {"type": "tool_use", "id": "toolu_1", "name": "Write", "input": {"file_path": "a.txt"}},
+            {"type": "tool_result", "tool_use_id": "toolu_1", "content": "ok", "is_error": true}
+        ]));
+
+        assert_eq!(
+            parts,
+            vec![
+                MessagePart::Thinking {
+                    text: "Plan the edit".to_string()
+                },
+                MessagePart::Text {
+                    text: "Writing the file.".to_string()
+                },
+                MessagePart::ToolCall {
+                    id: Some("toolu_1".to_string()),
+                    name: "Write".to_string(),
+                    input: json!({"file_path": "a.txt"}),
+                },
+                MessagePart::ToolResult {
+                    id: Some("toolu_1".to_string()),
+                    content: "ok".to_string(),
+                    is_error: true,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn parse_timestamp_to_ms_supports_integers_and_rfc3339() {
         assert_eq!(
@@ -248,12 +248,17 @@ pub struct WebDavSyncSettings {
     pub username: String,
     #[serde(default)]
     pub password: String,
+    /// 同步口令；非空时上传的 artifacts 在本地加密后再上传
+    #[serde(default)]
+    pub encryption_passphrase: String,
     #[serde(default)]
     pub auto_sync: bool,
     #[serde(default)]
     pub status: WebDavSyncStatus,
 }
 
+const DEFAULT_SYNC_INTERVAL_MINUTES: u32 = 30;
+
 fn default_webdav_remote_root() -> String {
     "cc-switch-sync".to_string()
 }
@@ -273,6 +278,7 @@ impl Default for WebDavSyncSettings {
             profile: default_webdav_profile(),
             username: String::new(),
             password: String::new(),
+            encryption_passphrase: String::new(),
             auto_sync: false,
             status: WebDavSyncStatus::default(),
         }
@@ -326,6 +332,215 @@ impl WebDavSyncSettings {
     }
 }
 
+/// 同步后端：WebDAV、S3 兼容对象存储或 git 仓库，共用同一套 manifest 协议
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncBackend {
+    #[default]
+    WebDav,
+    S3,
+    Git,
+}
+
+impl SyncBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WebDav => "webdav",
+            Self::S3 => "s3",
+            Self::Git => "git",
+        }
+    }
+}
+
+impl std::str::FromStr for SyncBackend {
+    type Err = AppError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "webdav" => Ok(Self::WebDav),
+            "s3" => Ok(Self::S3),
+            "git" => Ok(Self::Git),
+            other => Err(AppError::InvalidInput(format!(
+                "未知的同步后端: {other}（可选 webdav、s3、git）"
+            ))),
+        }
+    }
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_git_branch() -> String {
+    "main".to_string()
+}
+
+/// S3 兼容对象存储同步设置（AWS S3、MinIO、Cloudflare R2 等）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct S3SyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 服务端点，例如 `https://s3.us-east-1.amazonaws.com` 或 `http://127.0.0.1:9000`
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default)]
+    pub access_key_id: String,
+    #[serde(default)]
+    pub secret_access_key: String,
+    /// 使用 `{endpoint}/{bucket}/{key}` 路径风格（MinIO 等自建服务通常需要）
+    #[serde(default)]
+    pub path_style: bool,
+    #[serde(default = "default_webdav_remote_root")]
+    pub remote_root: String,
+    #[serde(default = "default_webdav_profile")]
+    pub profile: String,
+    #[serde(default)]
+    pub encryption_passphrase: String,
+    #[serde(default)]
+    pub auto_sync: bool,
+    #[serde(default)]
+    pub status: WebDavSyncStatus,
+}
+
+impl Default for S3SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            region: default_s3_region(),
+            bucket: String::new(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            path_style: false,
+            remote_root: default_webdav_remote_root(),
+            profile: default_webdav_profile(),
+            encryption_passphrase: String::new(),
+            auto_sync: false,
+            status: WebDavSyncStatus::default(),
+        }
+    }
+}
+
+impl S3SyncSettings {
+    pub fn normalize(&mut self) {
+        self.endpoint = self.endpoint.trim().trim_end_matches('/').to_string();
+        self.region = self.region.trim().to_string();
+        if self.region.is_empty() {
+            self.region = default_s3_region();
+        }
+        self.bucket = self.bucket.trim().to_string();
+        self.access_key_id = self.access_key_id.trim().to_string();
+        self.secret_access_key = self.secret_access_key.trim().to_string();
+        self.remote_root = sanitize_path_segment(&self.remote_root);
+        self.profile = sanitize_path_segment(&self.profile);
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !self.enabled && self.endpoint.is_empty() {
+            return Ok(());
+        }
+        let url = url::Url::parse(&self.endpoint)
+            .map_err(|e| AppError::InvalidInput(format!("S3 endpoint 不是合法 URL: {e}")))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(AppError::InvalidInput(
+                "S3 endpoint 仅支持 http/https".to_string(),
+            ));
+        }
+        if self.bucket.is_empty() {
+            return Err(AppError::InvalidInput("S3 bucket 不能为空".to_string()));
+        }
+        if self.access_key_id.is_empty() || self.secret_access_key.is_empty() {
+            return Err(AppError::InvalidInput(
+                "S3 access_key_id/secret_access_key 不能为空".to_string(),
+            ));
+        }
+        validate_sync_path(&self.remote_root, &self.profile, "S3")
+    }
+}
+
+/// git 仓库同步设置；认证沿用本机 git 的凭据配置（SSH key / credential helper）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub repo_url: String,
+    #[serde(default = "default_git_branch")]
+    pub branch: String,
+    #[serde(default = "default_webdav_remote_root")]
+    pub remote_root: String,
+    #[serde(default = "default_webdav_profile")]
+    pub profile: String,
+    #[serde(default)]
+    pub encryption_passphrase: String,
+    #[serde(default)]
+    pub auto_sync: bool,
+    #[serde(default)]
+    pub status: WebDavSyncStatus,
+}
+
+impl Default for GitSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo_url: String::new(),
+            branch: default_git_branch(),
+            remote_root: default_webdav_remote_root(),
+            profile: default_webdav_profile(),
+            encryption_passphrase: String::new(),
+            auto_sync: false,
+            status: WebDavSyncStatus::default(),
+        }
+    }
+}
+
+impl GitSyncSettings {
+    pub fn normalize(&mut self) {
+        self.repo_url = self.repo_url.trim().to_string();
+        self.branch = self.branch.trim().to_string();
+        if self.branch.is_empty() {
+            self.branch = default_git_branch();
+        }
+        self.remote_root = sanitize_path_segment(&self.remote_root);
+        self.profile = sanitize_path_segment(&self.profile);
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !self.enabled && self.repo_url.is_empty() {
+            return Ok(());
+        }
+        if self.repo_url.is_empty() {
+            return Err(AppError::InvalidInput("git repo_url 不能为空".to_string()));
+        }
+        if self.repo_url.starts_with('-') || self.branch.starts_with('-') {
+            return Err(AppError::InvalidInput(
+                "git repo_url/branch 不能以 '-' 开头".to_string(),
+            ));
+        }
+        validate_sync_path(&self.remote_root, &self.profile, "git")
+    }
+}
+
+fn validate_sync_path(remote_root: &str, profile: &str, backend: &str) -> Result<(), AppError> {
+    if remote_root.is_empty() || profile.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "{backend} remote_root/profile 不能为空"
+        )));
+    }
+    if remote_root.contains("..") || profile.contains("..") {
+        return Err(AppError::InvalidInput(format!(
+            "{backend} remote_root/profile 不能包含 '..'"
+        )));
+    }
+    Ok(())
+}
+
 fn sanitize_path_segment(raw: &str) -> String {
     raw.trim()
         .trim_matches('/')
@@ -456,6 +671,9 @@ pub struct AppSettings {
     /// cleared when the toggle turns off.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub unify_codex_migrate_existing: Option<bool>,
+    /// 提示词以托管区块写入 live 文件，保留区块外由用户编写的内容
+    #[serde(default)]
+    pub prompt_managed_block: bool,
     /// Skills 同步方式（auto|symlink|copy）
     #[serde(default)]
     pub skill_sync_method: crate::services::skill::SyncMethod,
@@ -463,6 +681,16 @@ pub struct AppSettings {
     pub security: Option<SecuritySettings>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub webdav_sync: Option<WebDavSyncSettings>,
+    /// `config sync` 使用的同步后端
+    #[serde(default)]
+    pub sync_backend: SyncBackend,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3_sync: Option<S3SyncSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sync: Option<GitSyncSettings>,
+    /// 守护进程后台自动同步的间隔（分钟）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_interval_minutes: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub backup_retain_count: Option<u32>,
     /// 首选终端应用，用于会话恢复。
@@ -514,9 +742,14 @@ impl Default for AppSettings {
             preserve_codex_official_auth_on_switch: false,
             unify_codex_session_history: false,
             unify_codex_migrate_existing: None,
+            prompt_managed_block: false,
             skill_sync_method: crate::services::skill::SyncMethod::default(),
             security: None,
             webdav_sync: None,
+            sync_backend: SyncBackend::default(),
+            s3_sync: None,
+            git_sync: None,
+            sync_interval_minutes: None,
             backup_retain_count: None,
             preferred_terminal: None,
             local_migrations: None,
@@ -586,6 +819,12 @@ impl AppSettings {
         if let Some(webdav) = self.webdav_sync.as_mut() {
             webdav.normalize();
         }
+        if let Some(s3) = self.s3_sync.as_mut() {
+            s3.normalize();
+        }
+        if let Some(git) = self.git_sync.as_mut() {
+            git.normalize();
+        }
 
         self.preferred_terminal = self
             .preferred_terminal
@@ -885,6 +1124,22 @@ pub fn unify_codex_session_history() -> bool {
         .unify_codex_session_history
 }
 
+pub fn prompt_managed_block() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|error| {
+            log::warn!("设置锁已毒化，使用恢复值: {error}");
+            error.into_inner()
+        })
+        .prompt_managed_block
+}
+
+pub fn set_prompt_managed_block(enabled: bool) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.prompt_managed_block = enabled;
+    update_settings(settings)
+}
+
 pub fn get_gemini_override_dir() -> Option<PathBuf> {
     let settings = settings_store().read().ok()?;
     settings
@@ -1047,6 +1302,145 @@ pub fn update_webdav_sync_status(status: WebDavSyncStatus) -> Result<(), AppErro
     update_settings(settings)
 }
 
+pub fn get_sync_backend() -> SyncBackend {
+    settings_store()
+        .read()
+        .map(|s| s.sync_backend)
+        .unwrap_or_default()
+}
+
+pub fn set_sync_backend(backend: SyncBackend) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.sync_backend = backend;
+    update_settings(settings)
+}
+
+pub fn get_s3_sync_settings() -> Option<S3SyncSettings> {
+    settings_store().read().ok().and_then(|s| s.s3_sync.clone())
+}
+
+pub fn set_s3_sync_settings(s3_sync: Option<S3SyncSettings>) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.s3_sync = match s3_sync {
+        Some(mut cfg) => {
+            cfg.normalize();
+            cfg.validate()?;
+            Some(cfg)
+        }
+        None => None,
+    };
+    update_settings(settings)
+}
+
+pub fn get_git_sync_settings() -> Option<GitSyncSettings> {
+    settings_store()
+        .read()
+        .ok()
+        .and_then(|s| s.git_sync.clone())
+}
+
+pub fn set_git_sync_settings(git_sync: Option<GitSyncSettings>) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.git_sync = match git_sync {
+        Some(mut cfg) => {
+            cfg.normalize();
+            cfg.validate()?;
+            Some(cfg)
+        }
+        None => None,
+    };
+    update_settings(settings)
+}
+
+/// 指定同步后端是否已启用且开启了自动同步
+pub fn is_auto_sync_enabled(backend: SyncBackend) -> bool {
+    let Ok(settings) = settings_store().read() else {
+        return false;
+    };
+    match backend {
+        SyncBackend::WebDav => settings
+            .webdav_sync
+            .as_ref()
+            .is_some_and(|cfg| cfg.enabled && cfg.auto_sync),
+        SyncBackend::S3 => settings
+            .s3_sync
+            .as_ref()
+            .is_some_and(|cfg| cfg.enabled && cfg.auto_sync),
+        SyncBackend::Git => settings
+            .git_sync
+            .as_ref()
+            .is_some_and(|cfg| cfg.enabled && cfg.auto_sync),
+    }
+}
+
+/// 自动同步间隔（分钟），未设置时为 30 分钟
+pub fn effective_sync_interval_minutes() -> u32 {
+    settings_store()
+        .read()
+        .ok()
+        .and_then(|settings| settings.sync_interval_minutes)
+        .unwrap_or(DEFAULT_SYNC_INTERVAL_MINUTES)
+        .max(1)
+}
+
+pub fn set_sync_interval_minutes(minutes: Option<u32>) -> Result<(), AppError> {
+    if minutes == Some(0) {
+        return Err(AppError::InvalidInput(
+            "同步间隔必须大于 0 分钟".to_string(),
+        ));
+    }
+    let mut settings = get_settings();
+    settings.sync_interval_minutes = minutes;
+    update_settings(settings)
+}
+
+/// 读取指定同步后端的同步状态；后端未配置时返回 None
+pub fn get_sync_status(backend: SyncBackend) -> Option<WebDavSyncStatus> {
+    let settings = settings_store().read().ok()?;
+    match backend {
+        SyncBackend::WebDav => settings.webdav_sync.as_ref().map(|cfg| cfg.status.clone()),
+        SyncBackend::S3 => settings.s3_sync.as_ref().map(|cfg| cfg.status.clone()),
+        SyncBackend::Git => settings.git_sync.as_ref().map(|cfg| cfg.status.clone()),
+    }
+}
+
+/// 记录（或清除）指定同步后端的最近一次错误，保留其余状态字段
+pub fn set_sync_last_error(
+    backend: SyncBackend,
+    last_error: Option<String>,
+    source: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(mut status) = get_sync_status(backend) else {
+        return Ok(());
+    };
+    status.last_error = last_error;
+    status.last_error_source = source.map(str::to_string);
+    update_sync_status(backend, status)
+}
+
+/// 更新指定同步后端的同步状态
+pub fn update_sync_status(backend: SyncBackend, status: WebDavSyncStatus) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    match backend {
+        SyncBackend::WebDav => {
+            if let Some(ref mut webdav) = settings.webdav_sync {
+                webdav.status = status;
+            }
+        }
+        SyncBackend::S3 => {
+            if let Some(ref mut s3) = settings.s3_sync {
+                s3.status = status;
+            }
+        }
+        SyncBackend::Git => {
+            if let Some(ref mut git) = settings.git_sync {
+                git.status = status;
+            }
+        }
+    }
+    update_settings(settings)
+}
+
 pub fn webdav_jianguoyun_preset(username: &str, password: &str) -> WebDavSyncSettings {
     WebDavSyncSettings::jianguoyun_preset(username, password)
 }
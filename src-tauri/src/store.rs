@@ -96,6 +96,12 @@ impl AppState {
         Self::from_parts(db, config)
     }
 
+    /// 基于已打开的数据库构建应用状态，供守护进程复用自身的连接。
+    pub fn from_database(db: Arc<Database>) -> Result<Self, AppError> {
+        let config = export_db_to_multi_app_config(&db)?;
+        Self::from_parts(db, config)
+    }
+
     /// 创建新的应用状态，并在真实进程启动路径上执行一次启动恢复。
     pub fn try_new_with_startup_recovery() -> Result<Self, AppError> {
         let state = Self::try_new()?;
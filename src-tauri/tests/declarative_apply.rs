@@ -0,0 +1,126 @@
+use serial_test::serial;
+
+use cc_switch_lib::{
+    AppType, DeclarativeConfig, DeclarativeService, McpService, MultiAppConfig, PromptService,
+    ProviderService,
+};
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, lock_test_mutex, reset_test_fs, state_from_config};
+
+const TEAM_YAML: &str = r#"
+providers:
+  claude:
+    - id: team-relay
+      name: Team Relay
+      current: true
+      settings:
+        env:
+          ANTHROPIC_BASE_URL: https://relay.example.com
+          ANTHROPIC_AUTH_TOKEN: ${env:CC_SWITCH_TEST_TEAM_KEY}
+    - id: backup
+      settings:
+        env:
+          ANTHROPIC_BASE_URL: https://backup.example.com
+          ANTHROPIC_AUTH_TOKEN: sk-backup
+mcp_servers:
+  - id: filesystem
+    server: { type: stdio, command: npx, args: ["-y", "server-filesystem"] }
+    apps: [claude, codex]
+prompts:
+  claude:
+    - id: team
+      content: Prefer small, reviewed changes.
+      enabled: true
+failover:
+  claude: [team-relay, backup]
+"#;
+
+fn load_team_config() -> DeclarativeConfig {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("team.yaml");
+    std::fs::write(&path, TEAM_YAML).expect("write team.yaml");
+    std::env::set_var("CC_SWITCH_TEST_TEAM_KEY", "sk-team");
+    DeclarativeConfig::load(&path).expect("load team.yaml")
+}
+
+#[test]
+#[serial]
+fn apply_creates_declared_setup_and_is_idempotent() {
+    let _guard = lock_test_mutex();
+    reset_test_fs();
+    ensure_test_home();
+
+    let state = state_from_config(MultiAppConfig::default());
+    let config = load_team_config();
+
+    let plan = DeclarativeService::plan(&state, &config, false).expect("plan");
+    assert!(!plan.is_empty());
+    DeclarativeService::apply(&state, &config, &plan).expect("apply");
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    let team = providers.get("team-relay").expect("team provider created");
+    assert_eq!(team.name, "Team Relay");
+    assert_eq!(
+        team.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        serde_json::json!("sk-team")
+    );
+    assert_eq!(
+        ProviderService::current(&state, AppType::Claude).expect("current provider"),
+        "team-relay"
+    );
+
+    let queue = state
+        .db
+        .get_failover_queue("claude")
+        .expect("failover queue")
+        .into_iter()
+        .map(|item| item.provider_id)
+        .collect::<Vec<_>>();
+    assert_eq!(queue, vec!["team-relay", "backup"]);
+
+    let servers = McpService::get_all_servers(&state).expect("mcp servers");
+    let filesystem = servers.get("filesystem").expect("mcp server created");
+    assert!(filesystem.apps.claude && filesystem.apps.codex && !filesystem.apps.gemini);
+
+    let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("prompts");
+    let prompt = prompts.get("team").expect("prompt created");
+    assert!(prompt.enabled);
+    assert_eq!(prompt.content, "Prefer small, reviewed changes.");
+
+    let replan = DeclarativeService::plan(&state, &config, false).expect("replan");
+    assert!(
+        replan.is_empty(),
+        "second plan should be empty: {:?}",
+        replan.changes
+    );
+}
+
+#[test]
+#[serial]
+fn prune_removes_undeclared_providers_in_managed_apps() {
+    let _guard = lock_test_mutex();
+    reset_test_fs();
+    ensure_test_home();
+
+    let state = state_from_config(MultiAppConfig::default());
+    let config = load_team_config();
+    let plan = DeclarativeService::plan(&state, &config, false).expect("plan");
+    DeclarativeService::apply(&state, &config, &plan).expect("apply");
+
+    let stray = cc_switch_lib::Provider::with_id(
+        "stray".to_string(),
+        "Stray".to_string(),
+        serde_json::json!({"env": {"ANTHROPIC_BASE_URL": "https://stray.example.com"}}),
+        None,
+    );
+    ProviderService::add(&state, AppType::Claude, stray).expect("add stray provider");
+
+    let plan = DeclarativeService::plan(&state, &config, true).expect("prune plan");
+    DeclarativeService::apply(&state, &config, &plan).expect("apply prune");
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    assert!(!providers.contains_key("stray"));
+    assert!(providers.contains_key("team-relay") && providers.contains_key("backup"));
+}
@@ -0,0 +1,214 @@
+use serde_json::json;
+use serial_test::serial;
+
+use cc_switch_lib::{
+    AppType, McpApps, McpServer, McpService, MultiAppConfig, ProjectProfile, ProjectService,
+    PromptService, Provider, ProviderService,
+};
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, lock_test_mutex, reset_test_fs, state_from_config};
+
+fn mcp_server(id: &str, command: &str) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        server: json!({"type": "stdio", "command": command}),
+        apps: McpApps::default(),
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+    }
+}
+
+fn seed_state() -> cc_switch_lib::AppState {
+    let mut config = MultiAppConfig::default();
+    let team_prompt = json!({
+        "team": {"id": "team", "name": "Team", "content": "Team rules.", "enabled": false}
+    });
+    config.prompts.claude.prompts =
+        serde_json::from_value(team_prompt.clone()).expect("deserialize prompts");
+    config.prompts.codex.prompts =
+        serde_json::from_value(team_prompt).expect("deserialize prompts");
+    let state = state_from_config(config);
+    for (id, url, key) in [
+        ("team", "https://team.example.com", "ANTHROPIC_AUTH_TOKEN"),
+        (
+            "personal",
+            "https://personal.example.com",
+            "ANTHROPIC_API_KEY",
+        ),
+    ] {
+        let provider = Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({"env": {"ANTHROPIC_BASE_URL": url, key: format!("sk-{id}")}}),
+            None,
+        );
+        ProviderService::add(&state, AppType::Claude, provider).expect("add provider");
+    }
+    for (id, command) in [("filesystem", "fs-server"), ("browser", "browser-server")] {
+        McpService::upsert_server(&state, mcp_server(id, command)).expect("add mcp server");
+    }
+    state
+}
+
+fn read_json(path: &std::path::Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).expect("read json")).expect("parse json")
+}
+
+#[test]
+#[serial]
+fn apply_writes_project_scoped_files_and_keeps_user_entries() {
+    let _guard = lock_test_mutex();
+    reset_test_fs();
+    ensure_test_home();
+    let state = seed_state();
+
+    let project = tempfile::tempdir().expect("tempdir");
+    let root = project.path();
+    std::fs::create_dir_all(root.join(".claude")).expect("create .claude");
+    std::fs::write(
+        root.join(".claude/settings.local.json"),
+        json!({
+            "permissions": {"allow": ["Bash(cargo test:*)"]},
+            "env": {"ANTHROPIC_API_KEY": "sk-personal", "RUST_LOG": "debug"}
+        })
+        .to_string(),
+    )
+    .expect("seed settings.local.json");
+    std::fs::write(
+        root.join(".mcp.json"),
+        json!({"mcpServers": {
+            "browser": {"type": "stdio", "command": "browser-server"},
+            "notes": {"type": "stdio", "command": "notes-server"}
+        }})
+        .to_string(),
+    )
+    .expect("seed .mcp.json");
+    std::fs::write(
+        root.join(".cc-switch.toml"),
+        r#"
+[claude]
+provider = "team"
+mcp_servers = ["filesystem"]
+prompt = "team"
+
+[codex]
+prompt = "team"
+"#,
+    )
+    .expect("write profile");
+
+    let profile = ProjectProfile::load(&root.join(".cc-switch.toml")).expect("load profile");
+    let changes = ProjectService::plan(&state, &profile, false).expect("plan");
+    assert_eq!(changes.len(), 4);
+    ProjectService::apply(&changes).expect("apply");
+
+    let settings = read_json(&root.join(".claude/settings.local.json"));
+    assert_eq!(
+        settings["permissions"]["allow"],
+        json!(["Bash(cargo test:*)"])
+    );
+    assert_eq!(
+        settings["env"],
+        json!({
+            "RUST_LOG": "debug",
+            "ANTHROPIC_BASE_URL": "https://team.example.com",
+            "ANTHROPIC_AUTH_TOKEN": "sk-team"
+        })
+    );
+
+    let mcp = read_json(&root.join(".mcp.json"));
+    let mut ids = mcp["mcpServers"]
+        .as_object()
+        .expect("mcpServers object")
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec!["filesystem", "notes"]);
+
+    assert_eq!(
+        std::fs::read_to_string(root.join("CLAUDE.md")).expect("read CLAUDE.md"),
+        "Team rules."
+    );
+    assert_eq!(
+        std::fs::read_to_string(root.join("AGENTS.md")).expect("read AGENTS.md"),
+        "Team rules."
+    );
+
+    // Global live MCP config is untouched.
+    let servers = McpService::get_all_servers(&state).expect("servers");
+    assert!(!servers["filesystem"].apps.claude);
+}
+
+#[test]
+#[serial]
+fn apply_refuses_to_overwrite_hand_written_prompt_files() {
+    let _guard = lock_test_mutex();
+    reset_test_fs();
+    ensure_test_home();
+    let state = seed_state();
+
+    let project = tempfile::tempdir().expect("tempdir");
+    let root = project.path();
+    std::fs::write(root.join("CLAUDE.md"), "My own notes.").expect("seed CLAUDE.md");
+    std::fs::write(
+        root.join(".cc-switch.toml"),
+        "[claude]\nprompt = \"team\"\n",
+    )
+    .expect("write profile");
+
+    let profile = ProjectProfile::load(&root.join(".cc-switch.toml")).expect("load profile");
+    let err = ProjectService::plan(&state, &profile, false).expect_err("conflict");
+    assert!(err.to_string().contains("--force"), "{err}");
+
+    let changes = ProjectService::plan(&state, &profile, true).expect("forced plan");
+    ProjectService::apply(&changes).expect("apply");
+    assert_eq!(
+        std::fs::read_to_string(root.join("CLAUDE.md")).expect("read CLAUDE.md"),
+        "Team rules."
+    );
+}
+
+#[test]
+#[serial]
+fn apply_writes_prompts_into_the_managed_block_when_enabled() {
+    let _guard = lock_test_mutex();
+    reset_test_fs();
+    ensure_test_home();
+    let state = seed_state();
+    PromptService::set_managed_block(&state, true).expect("enable block mode");
+
+    let project = tempfile::tempdir().expect("tempdir");
+    let root = project.path();
+    std::fs::write(root.join("CLAUDE.md"), "My own notes.\n").expect("seed CLAUDE.md");
+    std::fs::write(
+        root.join(".cc-switch.toml"),
+        "[claude]\nprompt = \"team\"\n",
+    )
+    .expect("write profile");
+
+    let profile = ProjectProfile::load(&root.join(".cc-switch.toml")).expect("load profile");
+    let changes = ProjectService::plan(&state, &profile, false).expect("plan");
+    ProjectService::apply(&changes).expect("apply");
+    let written = std::fs::read_to_string(root.join("CLAUDE.md")).expect("read CLAUDE.md");
+    assert_eq!(
+        written,
+        "My own notes.\n\n<!-- cc-switch:begin -->\nTeam rules.\n<!-- cc-switch:end -->\n"
+    );
+
+    // A hand edit inside the block is not overwritten without --force.
+    std::fs::write(
+        root.join("CLAUDE.md"),
+        written.replace("Team rules.", "Edited rules."),
+    )
+    .expect("edit block");
+    let err = ProjectService::plan(&state, &profile, false).expect_err("conflict");
+    assert!(err.to_string().contains("--force"), "{err}");
+
+    PromptService::set_managed_block(&state, false).expect("disable block mode");
+}
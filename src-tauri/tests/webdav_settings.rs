@@ -15,6 +15,7 @@ fn sample_settings() -> WebDavSyncSettings {
         profile: " default ".to_string(),
         username: "user@example.com".to_string(),
         password: "app-password".to_string(),
+        encryption_passphrase: String::new(),
         auto_sync: false,
         status: WebDavSyncStatus::default(),
     }
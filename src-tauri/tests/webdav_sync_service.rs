@@ -296,6 +296,7 @@ fn sample_settings(base_url: &str) -> WebDavSyncSettings {
         profile: "default-profile".to_string(),
         username: "demo".to_string(),
         password: "secret".to_string(),
+        encryption_passphrase: String::new(),
         auto_sync: false,
         status: WebDavSyncStatus::default(),
     }